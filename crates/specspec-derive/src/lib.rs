@@ -0,0 +1,354 @@
+//! `#[derive(SpecSpec)]` implements `specspec_prelude::Spec` for a Rust type, so the
+//! struct stays the source of truth for its spec. Use it through specspec-prelude's
+//! `derive` feature.
+//!
+//! Structs with named fields become objects, newtype structs their inner type, and enums of
+//! unit variants a string `OneOf`. `#[serde(rename, rename_all, default, skip)]` are followed.
+//! `#[spec(...)]` adds rules:
+//!
+//! - on the type: `name = "Order"`, `description = "..."` (default: doc comments), `max_depth = 8`
+//! - on a field: `min_length`, `max_length`, `pattern = "^[a-z]+$"` (checked at compile time),
+//!   `min`, `max`, `integer`, `min_items`, `max_items`, `unique`, `optional`, `skip`,
+//!   `description`, `deprecated` (or `deprecated = "use sku"`)
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::meta::ParseNestedMeta;
+use syn::{parse_macro_input, Attribute, Data, DeriveInput, Error, Expr, Fields, Lit, LitInt, LitStr, Result, Token, Type};
+
+#[proc_macro_derive(SpecSpec, attributes(spec))]
+pub fn derive_spec(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(&input).unwrap_or_else(Error::into_compile_error).into()
+}
+
+#[derive(Default)]
+struct TypeAttrs {
+    name: Option<String>,
+    description: Option<String>,
+    max_depth: Option<usize>,
+    rename_all: Option<String>,
+}
+
+#[derive(Default)]
+struct FieldAttrs {
+    rename: Option<String>,
+    optional: bool,
+    skip: bool,
+    description: Option<String>,
+    deprecated: Option<Option<String>>,
+    constraints: Vec<String>,
+}
+
+fn expand(input: &DeriveInput) -> Result<TokenStream2> {
+    if !input.generics.params.is_empty() {
+        return Err(Error::new_spanned(&input.generics, "SpecSpec cannot be derived for generic types"));
+    }
+    let ident = &input.ident;
+    let attrs = type_attrs(&input.attrs)?;
+    let name = attrs.name.clone().unwrap_or_else(|| ident.to_string());
+    let description = option_str(&attrs.description.clone().or_else(|| doc_comment(&input.attrs)));
+    let max_depth = match attrs.max_depth {
+        Some(n) => quote!(::core::option::Option::Some(#n)),
+        None => quote!(::core::option::Option::None),
+    };
+
+    let body = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => {
+                let (mut required, mut optional) = (vec![], vec![]);
+                for field in &fields.named {
+                    let field_attrs = field_attrs(&field.attrs)?;
+                    if field_attrs.skip {
+                        continue;
+                    }
+                    let field_ident = field.ident.as_ref().expect("named field").to_string();
+                    let field_ident = field_ident.trim_start_matches("r#");
+                    let key = match (&field_attrs.rename, &attrs.rename_all) {
+                        (Some(rename), _) => rename.clone(),
+                        (None, Some(rule)) => rename_field(field_ident, rule),
+                        (None, None) => field_ident.to_string(),
+                    };
+                    let is_optional = field_attrs.optional || is_option(&field.ty);
+                    let desc = field_desc(&key, &field.ty, &field_attrs, is_optional, &field.attrs);
+                    if is_optional { optional.push(desc) } else { required.push(desc) }
+                }
+                quote! {
+                    let required = ::std::vec![#(#required),*];
+                    let optional = ::std::vec![#(#optional),*];
+                    ::specspec_prelude::spec::object(required, optional)
+                }
+            }
+            Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+                let field = &fields.unnamed[0];
+                let ty = &field.ty;
+                let constraints = &field_attrs(&field.attrs)?.constraints;
+                quote! {
+                    ::specspec_prelude::spec::constrain(
+                        <#ty as ::specspec_prelude::Spec>::describe_in(seen),
+                        &[#(::std::string::String::from(#constraints)),*],
+                    )
+                }
+            }
+            _ => return Err(Error::new_spanned(ident, "SpecSpec needs a struct with named fields or a newtype struct")),
+        },
+        Data::Enum(data) => {
+            let mut values = vec![];
+            for variant in &data.variants {
+                if !matches!(variant.fields, Fields::Unit) {
+                    return Err(Error::new_spanned(variant, "SpecSpec only supports enums of unit variants"));
+                }
+                let variant_attrs = field_attrs(&variant.attrs)?;
+                if variant_attrs.skip {
+                    continue;
+                }
+                values.push(match (variant_attrs.rename, &attrs.rename_all) {
+                    (Some(rename), _) => rename,
+                    (None, Some(rule)) => rename_variant(&variant.ident.to_string(), rule),
+                    (None, None) => variant.ident.to_string(),
+                });
+            }
+            quote!(::specspec_prelude::spec::string_enum(&[#(#values),*]))
+        }
+        Data::Union(_) => return Err(Error::new_spanned(ident, "SpecSpec cannot be derived for unions")),
+    };
+
+    Ok(quote! {
+        impl ::specspec_prelude::Spec for #ident {
+            fn describe_in(seen: &mut ::std::vec::Vec<&'static str>) -> ::specspec_prelude::spec::Value {
+                // A use inside its own body is described by name only
+                if seen.contains(&#name) {
+                    return ::specspec_prelude::spec::named_ref(#name);
+                }
+                seen.push(#name);
+                let body = { #body };
+                seen.pop();
+                ::specspec_prelude::spec::named(#name, body, #description, #max_depth)
+            }
+
+            fn validate(value: &::specspec_prelude::spec::Value) -> ::specspec_prelude::ValidationResult {
+                static ENGINE: ::std::sync::OnceLock<::specspec_prelude::Engine> = ::std::sync::OnceLock::new();
+                ENGINE
+                    .get_or_init(|| ::specspec_prelude::spec::load(&<Self as ::specspec_prelude::Spec>::describe()))
+                    .validate(value)
+            }
+        }
+    })
+}
+
+fn field_desc(key: &str, ty: &Type, attrs: &FieldAttrs, optional: bool, raw_attrs: &[Attribute]) -> TokenStream2 {
+    let constraints = &attrs.constraints;
+    let description = option_str(&attrs.description.clone().or_else(|| doc_comment(raw_attrs)));
+    let deprecated = option_str(&attrs.deprecated.as_ref().map(|message| match message {
+        Some(message) => format!("Field \"{}\" is deprecated: {}", key, message),
+        None => format!("Field \"{}\" is deprecated", key),
+    }));
+    quote! {
+        ::specspec_prelude::spec::field(
+            #key,
+            ::specspec_prelude::spec::constrain(
+                <#ty as ::specspec_prelude::Spec>::describe_in(seen),
+                &[#(::std::string::String::from(#constraints)),*],
+            ),
+            #optional,
+            false,
+            #description,
+            #deprecated,
+        )
+    }
+}
+
+fn option_str(value: &Option<String>) -> TokenStream2 {
+    match value {
+        Some(s) => quote!(::core::option::Option::Some(#s)),
+        None => quote!(::core::option::Option::None),
+    }
+}
+
+fn is_option(ty: &Type) -> bool {
+    match ty {
+        Type::Path(path) => path.qself.is_none()
+            && path.path.segments.last().is_some_and(|segment| segment.ident == "Option"),
+        _ => false,
+    }
+}
+
+fn doc_comment(attrs: &[Attribute]) -> Option<String> {
+    let lines: Vec<String> = attrs.iter()
+        .filter(|attr| attr.path().is_ident("doc"))
+        .filter_map(|attr| match &attr.meta {
+            syn::Meta::NameValue(nv) => match &nv.value {
+                Expr::Lit(syn::ExprLit { lit: Lit::Str(s), .. }) => Some(s.value()),
+                _ => None,
+            },
+            _ => None,
+        })
+        .map(|line| line.strip_prefix(' ').unwrap_or(&line).to_string())
+        .collect();
+    let text = lines.join("\n").trim().to_string();
+    if text.is_empty() { None } else { Some(text) }
+}
+
+// === Attributes ===
+
+fn type_attrs(attrs: &[Attribute]) -> Result<TypeAttrs> {
+    let mut out = TypeAttrs::default();
+    for attr in attrs {
+        if attr.path().is_ident("spec") {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("name") {
+                    out.name = Some(meta.value()?.parse::<LitStr>()?.value());
+                } else if meta.path.is_ident("description") {
+                    out.description = Some(meta.value()?.parse::<LitStr>()?.value());
+                } else if meta.path.is_ident("max_depth") {
+                    out.max_depth = Some(meta.value()?.parse::<LitInt>()?.base10_parse()?);
+                } else {
+                    return Err(meta.error("unknown spec attribute"));
+                }
+                Ok(())
+            })?;
+        } else if attr.path().is_ident("serde") {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("rename_all") && meta.input.peek(Token![=]) {
+                    out.rename_all = Some(meta.value()?.parse::<LitStr>()?.value());
+                    Ok(())
+                } else {
+                    skip(&meta)
+                }
+            })?;
+        }
+    }
+    Ok(out)
+}
+
+fn field_attrs(attrs: &[Attribute]) -> Result<FieldAttrs> {
+    let mut out = FieldAttrs::default();
+    for attr in attrs {
+        if attr.path().is_ident("spec") {
+            attr.parse_nested_meta(|meta| {
+                let key = meta.path.get_ident().map(|i| i.to_string()).unwrap_or_default();
+                match key.as_str() {
+                    "min_length" => out.constraints.push(format!("minimum {} characters", number(&meta)?)),
+                    "max_length" => out.constraints.push(format!("maximum {} characters", number(&meta)?)),
+                    "pattern" => out.constraints.push(format!("matches `{}`", pattern(&meta)?)),
+                    "min" => out.constraints.push(format!("minimum {}", number(&meta)?)),
+                    "max" => out.constraints.push(format!("maximum {}", number(&meta)?)),
+                    "integer" => out.constraints.push("integer".to_string()),
+                    "min_items" => out.constraints.push(format!("minimum {} items", number(&meta)?)),
+                    "max_items" => out.constraints.push(format!("maximum {} items", number(&meta)?)),
+                    "unique" => out.constraints.push("unique items".to_string()),
+                    "optional" => out.optional = true,
+                    "skip" => out.skip = true,
+                    "description" => out.description = Some(meta.value()?.parse::<LitStr>()?.value()),
+                    "deprecated" => {
+                        out.deprecated = Some(if meta.input.peek(Token![=]) {
+                            Some(meta.value()?.parse::<LitStr>()?.value())
+                        } else {
+                            None
+                        });
+                    }
+                    _ => return Err(meta.error("unknown spec attribute")),
+                }
+                Ok(())
+            })?;
+        } else if attr.path().is_ident("serde") {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("rename") && meta.input.peek(Token![=]) {
+                    out.rename = Some(meta.value()?.parse::<LitStr>()?.value());
+                    Ok(())
+                } else if meta.path.is_ident("default") {
+                    out.optional = true;
+                    skip(&meta)
+                } else if meta.path.is_ident("skip") || meta.path.is_ident("skip_deserializing") {
+                    out.skip = true;
+                    Ok(())
+                } else if meta.path.is_ident("flatten") {
+                    Err(meta.error("SpecSpec does not support #[serde(flatten)]"))
+                } else {
+                    skip(&meta)
+                }
+            })?;
+        }
+    }
+    Ok(out)
+}
+
+/// A numeric attribute value as written, e.g. `-1.5`
+fn number(meta: &ParseNestedMeta) -> Result<String> {
+    let value = meta.value()?;
+    let sign = if value.parse::<Option<Token![-]>>()?.is_some() { "-" } else { "" };
+    match value.parse::<Lit>()? {
+        Lit::Int(n) => Ok(format!("{}{}", sign, n.base10_digits())),
+        Lit::Float(n) => Ok(format!("{}{}", sign, n.base10_digits())),
+        lit => Err(Error::new_spanned(lit, "expected a number")),
+    }
+}
+
+/// A regex attribute value, checked here so a bad pattern fails the build, not `validate()`
+fn pattern(meta: &ParseNestedMeta) -> Result<String> {
+    let lit = meta.value()?.parse::<LitStr>()?;
+    match regex::Regex::new(&lit.value()) {
+        Ok(_) => Ok(lit.value()),
+        Err(err) => Err(Error::new_spanned(lit, format!("invalid pattern: {}", err))),
+    }
+}
+
+/// Skip a serde attribute SpecSpec does not need
+fn skip(meta: &ParseNestedMeta) -> Result<()> {
+    if meta.input.peek(Token![=]) {
+        meta.value()?.parse::<Expr>()?;
+    } else if meta.input.peek(syn::token::Paren) {
+        meta.parse_nested_meta(|inner| skip(&inner))?;
+    }
+    Ok(())
+}
+
+// === serde rename rules ===
+
+fn rename_field(field: &str, rule: &str) -> String {
+    match rule {
+        "UPPERCASE" | "SCREAMING_SNAKE_CASE" => field.to_ascii_uppercase(),
+        "PascalCase" | "camelCase" => {
+            let mut out = String::new();
+            let mut capitalize = rule == "PascalCase";
+            for c in field.chars() {
+                if c == '_' {
+                    capitalize = true;
+                } else if capitalize {
+                    out.push(c.to_ascii_uppercase());
+                    capitalize = false;
+                } else {
+                    out.push(c);
+                }
+            }
+            out
+        }
+        "kebab-case" => field.replace('_', "-"),
+        "SCREAMING-KEBAB-CASE" => field.replace('_', "-").to_ascii_uppercase(),
+        _ => field.to_string(),
+    }
+}
+
+fn rename_variant(variant: &str, rule: &str) -> String {
+    let snake = || {
+        let mut out = String::new();
+        for (i, c) in variant.char_indices() {
+            if c.is_uppercase() && i > 0 {
+                out.push('_');
+            }
+            out.push(c.to_ascii_lowercase());
+        }
+        out
+    };
+    match rule {
+        "lowercase" => variant.to_ascii_lowercase(),
+        "UPPERCASE" => variant.to_ascii_uppercase(),
+        "camelCase" => variant[..1].to_ascii_lowercase() + &variant[1..],
+        "snake_case" => snake(),
+        "SCREAMING_SNAKE_CASE" => snake().to_ascii_uppercase(),
+        "kebab-case" => snake().replace('_', "-"),
+        "SCREAMING-KEBAB-CASE" => snake().replace('_', "-").to_ascii_uppercase(),
+        _ => variant.to_string(),
+    }
+}
@@ -0,0 +1,82 @@
+use serde::Deserialize;
+use serde_json::json;
+use specspec_prelude::{Spec, SpecSpec};
+
+/// A product in the catalog
+#[derive(SpecSpec, Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+struct Product {
+    #[spec(min_length = 3, pattern = "^[A-Z0-9-]+$")]
+    sku: String,
+    #[spec(min = 0, integer)]
+    unit_price: i64,
+    #[spec(max_items = 2, unique)]
+    tags: Vec<String>,
+    status: Status,
+    note: Option<String>,
+    #[spec(deprecated = "use sku")]
+    #[serde(default)]
+    code: Option<String>,
+}
+
+#[derive(SpecSpec, Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+enum Status {
+    InStock,
+    SoldOut,
+}
+
+#[derive(SpecSpec)]
+#[spec(max_depth = 2)]
+#[allow(dead_code)]
+struct Tree {
+    children: Vec<Tree>,
+}
+
+fn codes(value: serde_json::Value) -> Vec<String> {
+    Product::validate(&value).issues.into_iter().map(|issue| issue.code).collect()
+}
+
+#[test]
+fn describes_the_struct_as_a_named_object() {
+    let desc = Product::describe();
+    assert_eq!(desc["ref"], "Product");
+    assert_eq!(desc["description"], "A product in the catalog");
+    let required: Vec<&str> = desc["children"]["required"].as_array().unwrap().iter()
+        .map(|field| field["key"].as_str().unwrap())
+        .collect();
+    assert_eq!(required, ["sku", "unitPrice", "tags", "status"]);
+    assert_eq!(desc["children"]["optional"][0]["key"], "note");
+}
+
+#[test]
+fn validates_with_the_field_rules() {
+    let valid = json!({ "sku": "AB-1", "unitPrice": 5, "tags": ["a"], "status": "in_stock" });
+    assert!(codes(valid.clone()).is_empty());
+
+    assert_eq!(codes(json!({ "sku": "ab", "unitPrice": -1, "tags": ["a", "a", "b"], "status": "gone" })), [
+        "str.too_short", "str.pattern_mismatch", "num.too_small",
+        "list.too_long", "list.duplicate", "oneof.no_match",
+    ]);
+    assert_eq!(codes(json!({ "unitPrice": 1, "tags": [], "status": "sold_out" })), ["field.missing"]);
+
+    let product = Product::parse(&valid).unwrap();
+    assert_eq!(product.status, Status::InStock);
+}
+
+#[test]
+fn warns_about_deprecated_fields() {
+    let value = json!({ "sku": "AB-1", "unitPrice": 5, "tags": [], "status": "sold_out", "code": "x" });
+    let result = Product::validate(&value);
+    assert!(result.ok);
+    assert_eq!(result.issues[0].code, "field.deprecated");
+    assert_eq!(result.issues[0].message, "Field \"code\" is deprecated: use sku");
+}
+
+#[test]
+fn limits_recursive_types() {
+    assert_eq!(Tree::describe()["children"]["required"][0]["itemType"], json!({ "name": "Ref", "ref": "Tree" }));
+    assert!(Tree::validate(&json!({ "children": [{ "children": [] }] })).ok);
+    let deep = json!({ "children": [{ "children": [{ "children": [] }] }] });
+    assert_eq!(Tree::validate(&deep).issues[0].code, "recursion.too_deep");
+}
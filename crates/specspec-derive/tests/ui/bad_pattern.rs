@@ -0,0 +1,9 @@
+use specspec_prelude::SpecSpec;
+
+#[derive(SpecSpec)]
+struct User {
+    #[spec(pattern = "(")]
+    name: String,
+}
+
+fn main() {}
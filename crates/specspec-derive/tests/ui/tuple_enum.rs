@@ -0,0 +1,8 @@
+use specspec_prelude::SpecSpec;
+
+#[derive(SpecSpec)]
+enum Shape {
+    Circle(f64),
+}
+
+fn main() {}
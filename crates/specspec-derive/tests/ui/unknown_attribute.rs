@@ -0,0 +1,9 @@
+use specspec_prelude::SpecSpec;
+
+#[derive(SpecSpec)]
+struct User {
+    #[spec(min_lenght = 3)]
+    name: String,
+}
+
+fn main() {}
@@ -0,0 +1,1231 @@
+//! Interpreted validation: load a spec's description at runtime and validate with it,
+//! without generating code.
+//!
+//! `specspec <spec-file> --describe -o spec.json` writes the description. The engine
+//! checks values the way a validator generated with `--codegen rust` would, and returns
+//! the same `ValidationResult`.
+
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::fmt;
+
+use regex::Regex;
+use serde_json::{Map, Value};
+
+use crate::*;
+
+/// A spec description the engine cannot load
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpecError {
+    pub message: String,
+}
+
+impl SpecError {
+    fn new(message: impl Into<String>) -> Self {
+        SpecError { message: message.into() }
+    }
+}
+
+impl fmt::Display for SpecError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for SpecError {}
+
+type Check<'a> = Box<ValidatorFn<'a>>;
+
+/// A pattern compiled when the spec is loaded; patterns are equal when their sources are
+struct Pattern(Regex);
+
+impl PartialEq for Pattern {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.as_str() == other.0.as_str()
+    }
+}
+
+/// A data check, as the Rust code generator would emit it
+#[derive(PartialEq)]
+enum Node {
+    Any,
+    Str { min_length: Option<usize>, max_length: Option<usize>, pattern: Option<Pattern> },
+    Num { min: Option<f64>, max: Option<f64>, integer: bool },
+    Decimal { min: Option<String>, max: Option<String>, precision: Option<usize>, scale: Option<usize> },
+    Bool,
+    Literal(Value),
+    Pattern(Pattern),
+    Object(Vec<Node>),
+    Field { key: String, value: Option<Box<Node>>, optional: bool },
+    List {
+        item: Box<Node>,
+        min_items: Option<usize>,
+        max_items: Option<usize>,
+        unique: bool,
+        unique_by: Option<String>,
+        sorted: Option<String>,
+        sorted_by: Option<String>,
+    },
+    OneOf(Vec<Node>),
+    AllOf(Vec<Node>),
+    Not(Box<Node>),
+    TaggedUnion { discriminator: String, variants: Vec<(String, Node)> },
+    Nullable(Box<Node>),
+    Message { inner: Box<Node>, code: Option<String>, message: Option<String> },
+    Deprecated { inner: Box<Node>, code: &'static str, message: String },
+    /// Metadata, as JSON object text, attached to the issues of the inner node
+    Meta { inner: Box<Node>, meta: String },
+    /// A named type, by index into the engine's definitions
+    Ref(usize),
+}
+
+/// A named type (Def); the body is missing until the description defines it
+struct Definition {
+    name: String,
+    body: Option<Node>,
+    max_depth: Option<usize>,
+}
+
+#[cfg(feature = "fs")]
+enum FsNode {
+    JsonFile { path: String, content: Option<Node> },
+    File { path: String, ext: Option<String>, checksum: Option<(String, String)> },
+    Directory { path: String },
+    When { file: String, field: Option<String>, is: Option<Node>, then: Vec<FsNode>, otherwise: Vec<FsNode> },
+}
+
+#[cfg(feature = "fs")]
+struct Bundle {
+    accept_dir: bool,
+    accept_zip: bool,
+    zip_ext: Option<String>,
+    name_pattern: Option<String>,
+    content: Vec<FsNode>,
+}
+
+enum Root {
+    Data(Node),
+    #[cfg(feature = "fs")]
+    Bundle(Bundle),
+}
+
+/// A spec loaded at runtime.
+///
+/// ```
+/// use specspec_prelude::Engine;
+///
+/// let engine = Engine::from_json(r#"{
+///   "name": "Object",
+///   "children": { "required": [{ "name": "Field", "key": "name", "summary": "String" }] }
+/// }"#).unwrap();
+/// assert!(engine.validate(&serde_json::json!({ "name": "demo" })).ok);
+/// assert!(!engine.validate(&serde_json::json!({ "name": 1 })).ok);
+/// ```
+pub struct Engine {
+    root: Root,
+    definitions: Vec<Definition>,
+}
+
+impl Engine {
+    /// Load a description written by `specspec <spec-file> --describe`
+    pub fn from_json(json: &str) -> Result<Engine, SpecError> {
+        let desc: Value = serde_json::from_str(json)
+            .map_err(|e| SpecError::new(format!("Invalid description JSON: {}", e)))?;
+        Engine::from_description(&desc)
+    }
+
+    /// Load a description that is already parsed
+    pub fn from_description(desc: &Value) -> Result<Engine, SpecError> {
+        let mut loader = Loader { definitions: vec![], index: HashMap::new() };
+        let d = as_desc(desc)?;
+        let root = match text(d, "fsType") {
+            None => Root::Data(loader.node(d)?),
+            #[cfg(feature = "fs")]
+            Some("bundle") => Root::Bundle(loader.bundle(d)?),
+            #[cfg(not(feature = "fs"))]
+            Some(fs_type) => {
+                return Err(SpecError::new(format!(
+                    "Built without the fs feature, but the spec checks a {}", fs_type)));
+            }
+            #[cfg(feature = "fs")]
+            Some(fs_type) => {
+                return Err(SpecError::new(format!("The spec checks a {}; only bundle roots can be loaded", fs_type)));
+            }
+        };
+        if let Some(def) = loader.definitions.iter().find(|def| def.body.is_none()) {
+            return Err(SpecError::new(format!("Named type \"{}\" is referenced but never defined", def.name)));
+        }
+        Ok(Engine { root, definitions: loader.definitions })
+    }
+
+    /// Whether the spec checks a bundle path rather than a JSON value
+    pub fn is_bundle(&self) -> bool {
+        !matches!(self.root, Root::Data(_))
+    }
+
+    /// Validate a JSON value; a bundle spec reports an `engine.error` issue
+    pub fn validate(&self, value: &Value) -> ValidationResult {
+        match &self.root {
+            Root::Data(node) => {
+                let run = Run::new(&self.definitions);
+                validate(value, &|v, p, i| run.check(node, v, p, i))
+            }
+            #[cfg(feature = "fs")]
+            Root::Bundle(_) => {
+                let mut issues = Issues::new();
+                add_issue(&mut issues, &[], "engine.error", "The spec checks a bundle path, not a JSON value");
+                ValidationResult { ok: false, issues }
+            }
+        }
+    }
+
+    /// Validate a bundle directory or zip file; for a data spec, a JSON file
+    #[cfg(feature = "fs")]
+    pub fn validate_path(&self, path: &str) -> ValidationResult {
+        match &self.root {
+            Root::Data(_) => {
+                let parsed = std::fs::read_to_string(path)
+                    .map_err(|e| ("file.read_error", format!("Cannot read file: {}", e)))
+                    .and_then(|text| serde_json::from_str(&text)
+                        .map_err(|e| ("json.parse_error", format!("Invalid JSON: {}", e))));
+                match parsed {
+                    Ok(value) => self.validate(&value),
+                    Err((code, message)) => {
+                        let mut issues = Issues::new();
+                        add_issue(&mut issues, &[], code, &message);
+                        ValidationResult { ok: false, issues }
+                    }
+                }
+            }
+            Root::Bundle(bundle) => {
+                let run = Run::new(&self.definitions);
+                validate_path(path, &|bundle_path, p, i| run.bundle(bundle, bundle_path, p, i))
+            }
+        }
+    }
+
+    /// Validate a sub-path ("" for all of it) of a bundle loaded once, without the bundle
+    /// name check; engines and bundles can be shared between threads. A data spec reports
+    /// an `engine.error` issue.
+    ///
+    /// ```
+    /// use specspec_prelude::{Engine, SharedBundle};
+    ///
+    /// # let dir = std::env::temp_dir().join(format!("specspec-doc-{}", std::process::id()));
+    /// # std::fs::create_dir_all(dir.join("tenants/acme")).unwrap();
+    /// # std::fs::write(dir.join("tenants/acme/site.json"), "{}").unwrap();
+    /// # let bundle_path = dir.to_str().unwrap();
+    /// let engine = Engine::from_json(r#"{
+    ///   "name": "Sites",
+    ///   "fsType": "bundle",
+    ///   "accept": [{ "fsType": "directory" }],
+    ///   "children": { "required": [{ "fsType": "jsonFile", "filePath": "site.json" }] }
+    /// }"#).unwrap();
+    /// let bundle = SharedBundle::open(bundle_path).unwrap();
+    /// let (acme, globex) = std::thread::scope(|scope| {
+    ///     let acme = scope.spawn(|| engine.validate_shared(&bundle, "tenants/acme"));
+    ///     let globex = scope.spawn(|| engine.validate_shared(&bundle, "tenants/globex"));
+    ///     (acme.join().unwrap(), globex.join().unwrap())
+    /// });
+    /// assert!(acme.ok);
+    /// assert_eq!(globex.issues[0].code, "dir.not_found");
+    /// # std::fs::remove_dir_all(&dir).unwrap();
+    /// ```
+    #[cfg(feature = "fs")]
+    pub fn validate_shared(&self, bundle: &SharedBundle, sub_path: &str) -> ValidationResult {
+        match &self.root {
+            Root::Data(_) => {
+                let mut issues = Issues::new();
+                add_issue(&mut issues, &[], "engine.error", "The spec checks a JSON value, not a bundle");
+                ValidationResult { ok: false, issues }
+            }
+            Root::Bundle(bundle_spec) => {
+                let run = Run::new(&self.definitions);
+                bundle.validate(sub_path, &|ctx, p, i| {
+                    for child in &bundle_spec.content {
+                        run.fs_check(child, ctx, p, i);
+                    }
+                })
+            }
+        }
+    }
+}
+
+// Engines are shared between threads, e.g. by server request handlers
+const _: fn() = || {
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<Engine>();
+};
+
+// === Loading ===
+
+fn as_desc(desc: &Value) -> Result<&Map<String, Value>, SpecError> {
+    desc.as_object().ok_or_else(|| SpecError::new(format!("Expected a type description, got {}", desc)))
+}
+
+fn text<'a>(d: &'a Map<String, Value>, key: &str) -> Option<&'a str> {
+    d.get(key).and_then(Value::as_str)
+}
+
+fn flag(d: &Map<String, Value>, key: &str) -> bool {
+    d.get(key).and_then(Value::as_bool).unwrap_or(false)
+}
+
+fn constraints(d: &Map<String, Value>) -> Vec<&str> {
+    d.get("constraints")
+        .and_then(Value::as_array)
+        .map(|list| list.iter().filter_map(Value::as_str).collect())
+        .unwrap_or_default()
+}
+
+/// Leading number of a constraint value, e.g. `3` in "3 characters"
+fn leading<T: std::str::FromStr>(val: &str) -> Option<T> {
+    val.split(' ').next()?.parse().ok()
+}
+
+fn strip_backticks(val: &str) -> &str {
+    let val = val.strip_prefix('`').unwrap_or(val);
+    val.strip_suffix('`').unwrap_or(val)
+}
+
+/// Regex source for a pattern written as a JS literal (`/src/flags`), flags inlined
+fn regex_source(pattern: &str) -> String {
+    if let Some(rest) = pattern.strip_prefix('/') {
+        if let Some(end) = rest.rfind('/') {
+            let (body, flags) = (&rest[..end], &rest[end + 1..]);
+            if flags.chars().all(|c| c.is_ascii_lowercase()) {
+                let flags: String = flags.chars().filter(|c| "ims".contains(*c)).collect();
+                return if flags.is_empty() { body.to_string() } else { format!("(?{}){}", flags, body) };
+            }
+        }
+    }
+    pattern.to_string()
+}
+
+/// Compile a pattern, rejecting ones the regex crate cannot run (e.g. JS lookarounds)
+fn compile(pattern: &str) -> Result<Pattern, SpecError> {
+    let source = regex_source(pattern);
+    Regex::new(&source)
+        .map(Pattern)
+        .map_err(|e| SpecError::new(format!("Invalid pattern {}: {}", pattern, e)))
+}
+
+struct Loader {
+    definitions: Vec<Definition>,
+    index: HashMap<String, usize>,
+}
+
+impl Loader {
+    /// Index of a named type, adding it (undefined) on first use
+    fn slot(&mut self, name: &str) -> usize {
+        if let Some(&index) = self.index.get(name) {
+            return index;
+        }
+        self.definitions.push(Definition { name: name.to_string(), body: None, max_depth: None });
+        self.index.insert(name.to_string(), self.definitions.len() - 1);
+        self.definitions.len() - 1
+    }
+
+    /// Refer to a named type, defining it with this body unless it already is
+    fn define(&mut self, name: &str, body: Node, max_depth: Option<usize>) -> Result<Node, SpecError> {
+        let index = self.slot(name);
+        let def = &mut self.definitions[index];
+        match &def.body {
+            None => {
+                def.body = Some(body);
+                def.max_depth = max_depth;
+            }
+            Some(defined) if *defined != body => {
+                return Err(SpecError::new(format!("Named type \"{}\" is defined more than once with different shapes", name)));
+            }
+            Some(_) => {}
+        }
+        Ok(Node::Ref(index))
+    }
+
+    fn node(&mut self, d: &Map<String, Value>) -> Result<Node, SpecError> {
+        reject_phase(d)?;
+        let name = text(d, "name").unwrap_or("");
+        if name == "Field" {
+            return self.field(d);
+        }
+
+        // Named type: defined once, referenced by index
+        let mut node = match text(d, "ref") {
+            Some(r) if name == "Ref" => Node::Ref(self.slot(r)),
+            Some(r) => {
+                let body = self.body(name, d)?;
+                let max_depth = d.get("maxDepth").and_then(Value::as_u64).map(|n| n as usize);
+                self.define(r, body, max_depth)?
+            }
+            None => self.body(name, d)?,
+        };
+
+        // Deprecated type: warn, then validate as usual
+        if let Some(message) = text(d, "deprecated") {
+            node = Node::Deprecated { inner: Box::new(node), code: "type.deprecated", message: message.to_string() };
+        }
+        // Spec-defined code/message: report the inner failure as one issue
+        if let Some(custom) = d.get("customIssue").and_then(Value::as_object) {
+            node = message_node(node, custom);
+        }
+        if flag(d, "nullable") {
+            node = Node::Nullable(Box::new(node));
+        }
+        if let Some(meta) = meta(d) {
+            node = Node::Meta { inner: Box::new(node), meta };
+        }
+        Ok(node)
+    }
+
+    fn body(&mut self, name: &str, d: &Map<String, Value>) -> Result<Node, SpecError> {
+        let cs = constraints(d);
+        Ok(match name {
+            "Literal" => {
+                let raw = cs.first().and_then(|c| c.strip_prefix("equals "))
+                    .ok_or_else(|| SpecError::new("Literal without a value"))?;
+                let value = serde_json::from_str(raw)
+                    .map_err(|_| SpecError::new(format!("Invalid literal value {}", raw)))?;
+                Node::Literal(value)
+            }
+            "Pattern" => {
+                let pattern = cs.first().and_then(|c| c.strip_prefix("matches "))
+                    .ok_or_else(|| SpecError::new("Pattern without a pattern"))?;
+                Node::Pattern(compile(strip_backticks(pattern))?)
+            }
+            "String" => {
+                let (mut min_length, mut max_length, mut pattern) = (None, None, None);
+                for c in &cs {
+                    if let Some(val) = c.strip_prefix("minimum ").filter(|_| c.contains("character")) {
+                        min_length = leading(val);
+                    } else if let Some(val) = c.strip_prefix("maximum ").filter(|_| c.contains("character")) {
+                        max_length = leading(val);
+                    } else if let Some(val) = c.strip_prefix("matches ") {
+                        pattern = Some(compile(strip_backticks(val.trim()))?);
+                    }
+                }
+                Node::Str { min_length, max_length, pattern }
+            }
+            "Number" => {
+                let (mut min, mut max, mut integer) = (None, None, false);
+                for c in &cs {
+                    if *c == "integer" {
+                        integer = true;
+                    } else if let Some(val) = c.strip_prefix("minimum ") {
+                        min = leading(val);
+                    } else if let Some(val) = c.strip_prefix("maximum ") {
+                        max = leading(val);
+                    }
+                }
+                Node::Num { min, max, integer }
+            }
+            "Decimal" => {
+                let (mut min, mut max, mut precision, mut scale) = (None, None, None, None);
+                for c in &cs {
+                    if let Some(val) = c.strip_prefix("precision ") {
+                        precision = leading(val);
+                    } else if let Some(val) = c.strip_prefix("scale ") {
+                        scale = leading(val);
+                    } else if let Some(val) = c.strip_prefix("minimum ") {
+                        min = Some(val.trim().to_string());
+                    } else if let Some(val) = c.strip_prefix("maximum ") {
+                        max = Some(val.trim().to_string());
+                    }
+                }
+                Node::Decimal { min, max, precision, scale }
+            }
+            "Boolean" => Node::Bool,
+            "OneOf" => Node::OneOf(self.list_of_nodes(d, "oneOf")?),
+            "AllOf" => Node::AllOf(self.list_of_nodes(d, "allOf")?),
+            "Not" => Node::Not(Box::new(self.node(as_desc(required(d, "Not", "not")?)?)?)),
+            "TaggedUnion" => self.tagged_union(d)?,
+            "ListOf" => self.list(d)?,
+            "Object" => self.object(required(d, "Object", "children")?)?,
+            // Custom types (registered with the engine) cannot be checked here
+            _ => return Err(SpecError::new(format!("Unknown type \"{}\"", name))),
+        })
+    }
+
+    fn field(&mut self, d: &Map<String, Value>) -> Result<Node, SpecError> {
+        let key = match text(d, "key") {
+            Some(key) if !key.is_empty() => key.to_string(),
+            _ => return Err(SpecError::new("Field without a key")),
+        };
+
+        let mut value = if d.contains_key("oneOf") {
+            Some(Node::OneOf(self.list_of_nodes(d, "oneOf")?))
+        } else if d.contains_key("allOf") {
+            Some(Node::AllOf(self.list_of_nodes(d, "allOf")?))
+        } else if let Some(not) = d.get("not") {
+            Some(Node::Not(Box::new(self.node(as_desc(not)?)?)))
+        } else if d.contains_key("variants") {
+            Some(self.tagged_union(d)?)
+        } else if d.contains_key("itemType") {
+            Some(self.list(d)?)
+        } else if let Some(children) = d.get("children") {
+            Some(self.object(children)?)
+        } else if text(d, "summary") == Some("Ref") {
+            let r = text(d, "ref").ok_or_else(|| SpecError::new("Ref without a name"))?;
+            Some(Node::Ref(self.slot(r)))
+        } else if let Some(summary) = text(d, "summary") {
+            let mut typed = Map::new();
+            if let Some(cs) = d.get("constraints") {
+                typed.insert("constraints".to_string(), cs.clone());
+            }
+            Some(self.body(summary, &typed)?)
+        } else {
+            None
+        };
+
+        if let Some(r) = text(d, "ref").filter(|_| text(d, "summary") != Some("Ref")) {
+            if let Some(body) = value.take() {
+                let max_depth = d.get("maxDepth").and_then(Value::as_u64).map(|n| n as usize);
+                value = Some(self.define(r, body, max_depth)?);
+            }
+        }
+        if let Some(message) = text(d, "valueDeprecated") {
+            value = value.map(|inner| Node::Deprecated { inner: Box::new(inner), code: "type.deprecated", message: message.to_string() });
+        }
+        if let Some(custom) = d.get("customIssue").and_then(Value::as_object) {
+            value = value.map(|inner| message_node(inner, custom));
+        }
+        if flag(d, "nullable") {
+            value = value.map(|inner| Node::Nullable(Box::new(inner)));
+        }
+        if let Some(meta) = meta(d) {
+            value = value.map(|inner| Node::Meta { inner: Box::new(inner), meta });
+        }
+        if let Some(message) = text(d, "deprecated") {
+            let inner = value.take().unwrap_or(Node::Any);
+            value = Some(Node::Deprecated { inner: Box::new(inner), code: "field.deprecated", message: message.to_string() });
+        }
+
+        Ok(Node::Field { key, value: value.map(Box::new), optional: flag(d, "optional") })
+    }
+
+    fn list_of_nodes(&mut self, d: &Map<String, Value>, key: &str) -> Result<Vec<Node>, SpecError> {
+        let items = d.get(key).and_then(Value::as_array)
+            .ok_or_else(|| SpecError::new(format!("Expected a list of types in {}", key)))?;
+        items.iter().map(|item| self.node(as_desc(item)?)).collect()
+    }
+
+    fn tagged_union(&mut self, d: &Map<String, Value>) -> Result<Node, SpecError> {
+        let discriminator = text(d, "discriminator")
+            .ok_or_else(|| SpecError::new("TaggedUnion without a discriminator"))?
+            .to_string();
+        let variants = d.get("variants").and_then(Value::as_object)
+            .ok_or_else(|| SpecError::new("Expected variants by tag"))?;
+        let mut nodes = vec![];
+        for (tag, variant) in variants {
+            nodes.push((tag.clone(), self.node(as_desc(variant)?)?));
+        }
+        Ok(Node::TaggedUnion { discriminator, variants: nodes })
+    }
+
+    fn list(&mut self, d: &Map<String, Value>) -> Result<Node, SpecError> {
+        let item = self.node(as_desc(required(d, "ListOf", "itemType")?)?)?;
+        let (mut min_items, mut max_items, mut unique, mut unique_by, mut sorted, mut sorted_by) =
+            (None, None, false, None, None, None);
+        for c in constraints(d) {
+            if let Some(val) = c.strip_prefix("minimum ").filter(|_| c.contains("items")) {
+                min_items = leading(val);
+            } else if let Some(val) = c.strip_prefix("maximum ").filter(|_| c.contains("items")) {
+                max_items = leading(val);
+            } else if c == "unique items" {
+                unique = true;
+            } else if let Some(val) = c.strip_prefix("unique by ") {
+                unique_by = Some(strip_backticks(val.trim()).to_string());
+            } else if let Some(val) = c.strip_prefix("sorted ") {
+                let (order, by) = match val.split_once(" by ") {
+                    Some((order, by)) => (order, Some(by)),
+                    None => (val, None),
+                };
+                if order == "ascending" || order == "descending" {
+                    sorted = Some(if order == "descending" { "desc" } else { "asc" }.to_string());
+                    sorted_by = by.map(|by| strip_backticks(by).to_string());
+                }
+            }
+        }
+        Ok(Node::List { item: Box::new(item), min_items, max_items, unique, unique_by, sorted, sorted_by })
+    }
+
+    /// Object fields: required first, then optional ones (checked as optional)
+    fn object(&mut self, children: &Value) -> Result<Node, SpecError> {
+        if !children.is_object() {
+            return Err(SpecError::new(format!("Expected object fields, got {}", children)));
+        }
+        let mut fields = vec![];
+        for (group, optional) in [("required", false), ("optional", true)] {
+            for child in children.get(group).and_then(Value::as_array).into_iter().flatten() {
+                let mut node = self.node(as_desc(child)?)?;
+                if let Node::Field { optional: field_optional, .. } = &mut node {
+                    *field_optional |= optional;
+                }
+                fields.push(node);
+            }
+        }
+        Ok(Node::Object(fields))
+    }
+
+    #[cfg(feature = "fs")]
+    fn bundle(&mut self, d: &Map<String, Value>) -> Result<Bundle, SpecError> {
+        let mut bundle = Bundle {
+            accept_dir: false,
+            accept_zip: false,
+            zip_ext: None,
+            name_pattern: text(d, "namePattern").map(str::to_string),
+            content: vec![],
+        };
+        if let Some(pattern) = &bundle.name_pattern {
+            Regex::new(pattern).map_err(|e| SpecError::new(format!("Invalid name pattern {}: {}", pattern, e)))?;
+        }
+        for carrier in d.get("accept").and_then(Value::as_array).into_iter().flatten() {
+            let carrier = as_desc(carrier)?;
+            let (fs_type, name) = (text(carrier, "fsType"), text(carrier, "name"));
+            if fs_type == Some("directory") || name == Some("Directory") {
+                bundle.accept_dir = true;
+            }
+            if fs_type == Some("zipFile") || name == Some("ZipFile") {
+                bundle.accept_zip = true;
+                bundle.zip_ext = text(carrier, "fileExt").map(str::to_string);
+            }
+        }
+
+        bundle.content = self.fs_nodes(d.get("children"))?;
+        Ok(bundle)
+    }
+
+    /// The files, directories and When rules among the children of a bundle or When branch
+    #[cfg(feature = "fs")]
+    fn fs_nodes(&mut self, children: Option<&Value>) -> Result<Vec<FsNode>, SpecError> {
+        let mut nodes = vec![];
+        for group in ["required", "optional"] {
+            for child in children.and_then(|c| c.get(group)).and_then(Value::as_array).into_iter().flatten() {
+                nodes.push(self.fs_node(as_desc(child)?)?);
+            }
+        }
+        Ok(nodes)
+    }
+
+    #[cfg(feature = "fs")]
+    fn fs_node(&mut self, d: &Map<String, Value>) -> Result<FsNode, SpecError> {
+        reject_phase(d)?;
+        if let Some(condition) = d.get("condition").and_then(Value::as_object) {
+            let is = match condition.get("is") {
+                Some(is) => Some(self.node(as_desc(is)?)?),
+                None => None,
+            };
+            return Ok(FsNode::When {
+                file: text(condition, "file").unwrap_or("").to_string(),
+                field: text(condition, "field").map(str::to_string),
+                is,
+                then: self.when_branch(d.get("then"))?,
+                otherwise: self.when_branch(d.get("else"))?,
+            });
+        }
+
+        let fs_type = text(d, "fsType").unwrap_or("");
+        let path = match text(d, "filePath") {
+            Some(path) if !path.is_empty() => path.to_string(),
+            _ => return Err(SpecError::new(format!("Bundle entry without a path: {}", Value::Object(d.clone())))),
+        };
+        Ok(match fs_type {
+            "jsonFile" => {
+                let content = match d.get("children") {
+                    Some(children) => Some(self.object(children)?),
+                    None => None,
+                };
+                FsNode::JsonFile { path, content }
+            }
+            "file" => {
+                let checksum = d.get("checksum").and_then(Value::as_object).map(|c| (
+                    text(c, "algorithm").unwrap_or("").to_string(),
+                    text(c, "value").unwrap_or("").to_string(),
+                ));
+                let ext = text(d, "fileExt").map(str::to_string);
+                FsNode::File { path, ext, checksum }
+            }
+            "directory" => FsNode::Directory { path },
+            _ => return Err(SpecError::new(format!("Unknown bundle entry type \"{}\" for {}", fs_type, path))),
+        })
+    }
+
+    /// A When branch: the entries of an object spec, or a single entry
+    #[cfg(feature = "fs")]
+    fn when_branch(&mut self, branch: Option<&Value>) -> Result<Vec<FsNode>, SpecError> {
+        let Some(branch) = branch else {
+            return Ok(vec![]);
+        };
+        let d = as_desc(branch)?;
+        if text(d, "name") == Some("Object") {
+            return self.fs_nodes(d.get("children"));
+        }
+        Ok(vec![self.fs_node(d)?])
+    }
+}
+
+/// Phased checks would run unconditionally here, so specs using Phase() are not loaded
+fn reject_phase(d: &Map<String, Value>) -> Result<(), SpecError> {
+    match d.get("phase").and_then(Value::as_u64) {
+        Some(phase) if phase > 0 => Err(SpecError::new(format!(
+            "The engine checks in one pass and does not support Phase({}); validate this spec with specspec", phase))),
+        _ => Ok(()),
+    }
+}
+
+/// A key a description of this type must have
+fn required<'a>(d: &'a Map<String, Value>, name: &str, key: &str) -> Result<&'a Value, SpecError> {
+    d.get(key).ok_or_else(|| SpecError::new(format!("{} without {}", name, key)))
+}
+
+fn message_node(inner: Node, custom: &Map<String, Value>) -> Node {
+    Node::Message {
+        inner: Box::new(inner),
+        code: text(custom, "code").map(str::to_string),
+        message: text(custom, "message").map(str::to_string),
+    }
+}
+
+/// The `Meta()` metadata of a description, as JSON object text
+fn meta(d: &Map<String, Value>) -> Option<String> {
+    d.get("meta").filter(|meta| meta.as_object().is_some_and(|m| !m.is_empty())).map(Value::to_string)
+}
+
+// === Running ===
+
+/// State of one validation: how deeply each named type is nested
+struct Run<'a> {
+    definitions: &'a [Definition],
+    depth: Vec<Cell<usize>>,
+}
+
+impl<'a> Run<'a> {
+    fn new(definitions: &'a [Definition]) -> Self {
+        Run { definitions, depth: definitions.iter().map(|_| Cell::new(0)).collect() }
+    }
+
+    fn validator<'n>(&'n self, node: &'n Node) -> Check<'n> {
+        Box::new(move |v, p, i| self.check(node, v, p, i))
+    }
+
+    fn check(&self, node: &Node, v: &Value, p: &[String], i: &mut Issues) {
+        match node {
+            Node::Any => {}
+            Node::Str { min_length, max_length, pattern } => {
+                validate_str(v, p, i, *min_length, *max_length, None);
+                if let (Some(Pattern(re)), Some(s)) = (pattern, v.as_str()) {
+                    if !re.is_match(s) {
+                        add_issue(i, p, "str.pattern_mismatch", &format!("String does not match pattern {}", re.as_str()));
+                    }
+                }
+            }
+            Node::Num { min, max, integer } => validate_num(v, p, i, *min, *max, *integer),
+            Node::Decimal { min, max, precision, scale } => {
+                validate_decimal(v, p, i, min.as_deref(), max.as_deref(), *precision, *scale);
+            }
+            Node::Bool => validate_bool(v, p, i),
+            Node::Literal(Value::String(s)) => validate_literal_str(v, p, i, s),
+            Node::Literal(expected) => match expected.as_i64() {
+                Some(n) => validate_literal_i64(v, p, i, n),
+                None => validate_literal(v, p, i, expected.clone()),
+            },
+            Node::Pattern(Pattern(re)) => match v.as_str() {
+                Some(s) => {
+                    if !re.is_match(s) {
+                        add_issue(i, p, "pattern.mismatch", &format!("Value does not match pattern {}", re.as_str()));
+                    }
+                }
+                // Reports the type mismatch
+                None => validate_pattern(v, p, i, re.as_str()),
+            },
+            Node::Object(fields) => {
+                if validate_object(v, p, i) {
+                    for field in fields {
+                        self.check(field, v, p, i);
+                    }
+                }
+            }
+            Node::Field { key, value, optional } => {
+                let validator = value.as_deref().map(|inner| self.validator(inner));
+                validate_field(v, p, i, key, validator.as_deref(), *optional);
+            }
+            Node::List { item, min_items, max_items, unique, unique_by, sorted, sorted_by } => {
+                let validator = self.validator(item);
+                let options = ListOptions {
+                    min_items: *min_items,
+                    max_items: *max_items,
+                    unique: *unique,
+                    unique_by: unique_by.as_deref(),
+                    sorted: sorted.as_deref(),
+                    sorted_by: sorted_by.as_deref(),
+                };
+                validate_list(v, p, i, Some(&*validator), &options);
+            }
+            Node::OneOf(options) => {
+                let validators: Vec<Check> = options.iter().map(|option| self.validator(option)).collect();
+                let refs: Vec<&ValidatorFn> = validators.iter().map(|f| &**f).collect();
+                validate_oneof(v, p, i, &refs);
+            }
+            Node::AllOf(parts) => {
+                let validators: Vec<Check> = parts.iter().map(|part| self.validator(part)).collect();
+                let refs: Vec<&ValidatorFn> = validators.iter().map(|f| &**f).collect();
+                validate_allof(v, p, i, &refs);
+            }
+            Node::Not(inner) => validate_not(v, p, i, &*self.validator(inner)),
+            Node::TaggedUnion { discriminator, variants } => {
+                let validators: Vec<(&str, Check)> = variants.iter()
+                    .map(|(tag, variant)| (tag.as_str(), self.validator(variant)))
+                    .collect();
+                let refs: Vec<(&str, &ValidatorFn)> =
+                    validators.iter().map(|(tag, f)| (*tag, &**f)).collect();
+                validate_tagged_union(v, p, i, discriminator, &refs);
+            }
+            Node::Nullable(inner) => validate_nullable(v, p, i, &*self.validator(inner)),
+            Node::Message { inner, code, message } => {
+                validate_message(v, p, i, &*self.validator(inner), code.as_deref(), message.as_deref());
+            }
+            Node::Deprecated { inner, code, message } => {
+                validate_deprecated(v, p, i, &*self.validator(inner), code, message);
+            }
+            Node::Meta { inner, meta } => validate_meta(v, p, i, &*self.validator(inner), meta),
+            Node::Ref(index) => self.named(*index, v, p, i),
+        }
+    }
+
+    fn named(&self, index: usize, v: &Value, p: &[String], i: &mut Issues) {
+        let def = &self.definitions[index];
+        let body = match &def.body {
+            Some(body) => body,
+            None => return,
+        };
+        let max_depth = match def.max_depth {
+            Some(max_depth) => max_depth,
+            None => return self.check(body, v, p, i),
+        };
+        let depth = self.depth[index].get();
+        if depth >= max_depth {
+            add_issue(i, p, "recursion.too_deep",
+                &format!("Nesting of {} exceeds maximum depth {}", def.name, max_depth));
+            return;
+        }
+        self.depth[index].set(depth + 1);
+        self.check(body, v, p, i);
+        self.depth[index].set(depth);
+    }
+
+    #[cfg(feature = "fs")]
+    fn bundle(&self, bundle: &Bundle, bundle_path: &str, p: &[String], i: &mut Issues) -> Option<FSContext> {
+        let content = |ctx: &FSContext, p: &[String], i: &mut Issues| {
+            for child in &bundle.content {
+                self.fs_check(child, ctx, p, i);
+            }
+        };
+        let options = BundleOptions {
+            accept_dir: bundle.accept_dir,
+            accept_zip: bundle.accept_zip,
+            zip_ext: bundle.zip_ext.as_deref(),
+            name_pattern: bundle.name_pattern.as_deref(),
+        };
+        validate_bundle(bundle_path, p, i, &options, Some(&content))
+    }
+
+    #[cfg(feature = "fs")]
+    fn fs_check(&self, node: &FsNode, ctx: &FSContext, p: &[String], i: &mut Issues) {
+        match node {
+            FsNode::JsonFile { path, content } => {
+                let validator = content.as_ref().map(|content| self.validator(content));
+                validate_json_file(ctx, path, p, i, validator.as_deref());
+            }
+            FsNode::File { path, ext, checksum } => {
+                if validate_fs_file(ctx, path, p, i, ext.as_deref()) {
+                    if let Some((algorithm, expected)) = checksum {
+                        validate_file_checksum(ctx, path, p, i, algorithm, expected);
+                    }
+                }
+            }
+            FsNode::Directory { path } => {
+                validate_fs_directory(ctx, path, p, i);
+            }
+            FsNode::When { file, field, is, then, otherwise } => {
+                let is = is.as_ref().map(|is| self.validator(is));
+                let condition = Condition { file, field: field.as_deref(), is: is.as_deref() };
+                let then = |ctx: &FSContext, p: &[String], i: &mut Issues| {
+                    for child in then {
+                        self.fs_check(child, ctx, p, i);
+                    }
+                };
+                let otherwise = |ctx: &FSContext, p: &[String], i: &mut Issues| {
+                    for child in otherwise {
+                        self.fs_check(child, ctx, p, i);
+                    }
+                };
+                validate_when(ctx, p, i, &condition, Some(&then), Some(&otherwise));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn engine(desc: Value) -> Engine {
+        Engine::from_description(&desc).unwrap()
+    }
+
+    fn codes(engine: &Engine, value: Value) -> Vec<String> {
+        engine.validate(&value).issues.into_iter().map(|issue| issue.code).collect()
+    }
+
+    fn field(key: &str, summary: &str, constraints: &[&str]) -> Value {
+        json!({ "name": "Field", "key": key, "summary": summary, "constraints": constraints })
+    }
+
+    fn object(required: Vec<Value>) -> Value {
+        json!({ "name": "Object", "children": { "required": required } })
+    }
+
+    #[test]
+    fn rejects_descriptions_it_cannot_load() {
+        assert!(Engine::from_json("{").err().unwrap().message.starts_with("Invalid description JSON"));
+        assert!(Engine::from_json("[]").is_err());
+        let err = Engine::from_description(&json!({ "name": "Ref", "ref": "Node" })).err().unwrap();
+        assert_eq!(err.message, "Named type \"Node\" is referenced but never defined");
+    }
+
+    #[test]
+    fn rejects_descriptions_that_would_pass_everything() {
+        let error = |desc: Value| Engine::from_description(&desc).err().map(|e| e.message);
+        assert_eq!(error(json!({ "name": "Strnig" })).unwrap(), "Unknown type \"Strnig\"");
+        assert_eq!(error(json!({ "name": "Literal", "constraints": ["equals 'x'"] })).unwrap(), "Invalid literal value 'x'");
+        assert_eq!(error(json!({ "name": "Object" })).unwrap(), "Object without children");
+        assert!(error(json!({ "name": "OneOf" })).is_some());
+        assert!(error(json!({ "name": "ListOf", "constraints": ["minimum 1 items"] })).is_some());
+        assert!(error(object(vec![json!({ "name": "Field", "summary": "String" })])).is_some());
+        assert!(error(object(vec![json!({ "name": "Field", "key": "a", "summary": "String", "phase": 1 })])).unwrap().contains("Phase(1)"));
+    }
+
+    #[test]
+    fn compiles_patterns_when_loading() {
+        let err = Engine::from_description(&json!({ "name": "Pattern", "constraints": ["matches `(?=a)b`"] })).err().unwrap();
+        assert!(err.message.starts_with("Invalid pattern (?=a)b"), "{}", err.message);
+        assert!(Engine::from_description(&json!({ "name": "String", "constraints": ["matches `/[/`"] })).is_err());
+    }
+
+    #[test]
+    fn rejects_named_types_defined_twice_with_different_shapes() {
+        let desc = object(vec![
+            json!({ "name": "Field", "key": "a", "summary": "String", "ref": "T" }),
+            json!({ "name": "Field", "key": "b", "summary": "Number", "ref": "T", "constraints": ["minimum 10"] }),
+        ]);
+        let err = Engine::from_description(&desc).err().unwrap();
+        assert_eq!(err.message, "Named type \"T\" is defined more than once with different shapes");
+
+        // The same body used twice is one type
+        let e = engine(object(vec![
+            json!({ "name": "Field", "key": "a", "summary": "Number", "ref": "T", "constraints": ["minimum 10"] }),
+            json!({ "name": "Field", "key": "b", "summary": "Number", "ref": "T", "constraints": ["minimum 10"] }),
+        ]));
+        assert_eq!(codes(&e, json!({ "a": 10, "b": 5 })), ["num.too_small"]);
+    }
+
+    #[test]
+    fn checks_strings() {
+        let e = engine(json!({ "name": "String", "constraints": ["minimum 2 characters", "maximum 4 characters", "matches `/^[a-z]+$/i`"] }));
+        assert!(codes(&e, json!("Abc")).is_empty());
+        assert_eq!(codes(&e, json!("a")), ["str.too_short"]);
+        assert_eq!(codes(&e, json!("abcde")), ["str.too_long"]);
+        assert_eq!(codes(&e, json!("a1")), ["str.pattern_mismatch"]);
+        assert_eq!(codes(&e, json!(1)), ["type.mismatch"]);
+    }
+
+    #[test]
+    fn checks_numbers_and_decimals() {
+        let e = engine(json!({ "name": "Number", "constraints": ["integer", "minimum 1", "maximum 10"] }));
+        assert!(codes(&e, json!(5)).is_empty());
+        assert_eq!(codes(&e, json!(0)), ["num.too_small"]);
+        assert_eq!(codes(&e, json!(11)), ["num.too_large"]);
+        assert_eq!(codes(&e, json!(1.5)), ["num.not_integer"]);
+
+        let e = engine(json!({ "name": "Decimal", "constraints": ["precision 4 digits", "scale 2", "maximum 10.5"] }));
+        assert!(codes(&e, json!(10.5)).is_empty());
+        assert_eq!(codes(&e, json!(100.51)), ["decimal.too_many_digits", "num.too_large"]);
+        assert_eq!(codes(&e, json!(0.125)), ["decimal.scale_exceeded"]);
+    }
+
+    #[test]
+    fn checks_booleans_literals_and_patterns() {
+        let e = engine(json!({ "name": "Boolean" }));
+        assert!(codes(&e, json!(true)).is_empty());
+        assert_eq!(codes(&e, json!("true")), ["type.mismatch"]);
+
+        let e = engine(json!({ "name": "Literal", "constraints": ["equals \"v1\""] }));
+        assert!(codes(&e, json!("v1")).is_empty());
+        assert_eq!(codes(&e, json!("v2")), ["literal.mismatch"]);
+
+        let e = engine(json!({ "name": "Literal", "constraints": ["equals 3"] }));
+        assert!(codes(&e, json!(3)).is_empty());
+        assert_eq!(codes(&e, json!(4)), ["literal.mismatch"]);
+
+        let e = engine(json!({ "name": "Pattern", "constraints": ["matches `/^v\\d+$/`"] }));
+        assert!(codes(&e, json!("v12")).is_empty());
+        assert_eq!(codes(&e, json!("12")), ["pattern.mismatch"]);
+    }
+
+    #[test]
+    fn checks_object_fields() {
+        let e = engine(json!({
+            "name": "Object",
+            "children": {
+                "required": [field("name", "String", &[])],
+                "optional": [field("port", "Number", &["integer"])],
+            },
+        }));
+        assert!(codes(&e, json!({ "name": "api" })).is_empty());
+        assert_eq!(codes(&e, json!({})), ["field.missing"]);
+        assert_eq!(codes(&e, json!({ "name": "api", "port": 80.5 })), ["num.not_integer"]);
+        assert_eq!(codes(&e, json!([])), ["type.mismatch"]);
+
+        let result = e.validate(&json!({ "name": 1 }));
+        assert_eq!(result.issues[0].path, "name");
+    }
+
+    #[test]
+    fn checks_lists() {
+        let e = engine(json!({
+            "name": "ListOf",
+            "itemType": { "name": "Number" },
+            "constraints": ["minimum 1 items", "maximum 3 items", "unique items", "sorted ascending"],
+        }));
+        assert!(codes(&e, json!([1, 2, 3])).is_empty());
+        assert_eq!(codes(&e, json!([])), ["list.too_short"]);
+        assert_eq!(codes(&e, json!([1, 2, 3, 4])), ["list.too_long"]);
+        assert_eq!(codes(&e, json!([1, 1])), ["list.duplicate"]);
+        assert_eq!(codes(&e, json!([2, 1])), ["list.not_sorted"]);
+        assert_eq!(codes(&e, json!(["a"])), ["type.mismatch"]);
+
+        let e = engine(json!({
+            "name": "ListOf",
+            "itemType": { "name": "Object", "children": {} },
+            "constraints": ["unique by `meta.id`", "sorted descending by `rank`"],
+        }));
+        let items = json!([{ "meta": { "id": 1 }, "rank": 2 }, { "meta": { "id": 1 }, "rank": 3 }]);
+        let result = e.validate(&items);
+        assert_eq!(result.issues.iter().map(|i| i.code.as_str()).collect::<Vec<_>>(), ["list.duplicate", "list.not_sorted"]);
+        assert_eq!(result.issues[0].path, "[1]");
+    }
+
+    #[test]
+    fn checks_combinators() {
+        let e = engine(json!({ "name": "OneOf", "oneOf": [{ "name": "String" }, { "name": "Number" }] }));
+        assert!(codes(&e, json!(1)).is_empty());
+        assert_eq!(codes(&e, json!(true)), ["oneof.no_match"]);
+
+        let e = engine(json!({ "name": "AllOf", "allOf": [
+            object(vec![field("id", "String", &[])]),
+            object(vec![field("id", "String", &[]), field("rev", "Number", &[])]),
+        ] }));
+        assert!(codes(&e, json!({ "id": "a", "rev": 1 })).is_empty());
+        // The missing id is reported once, though both parts require it
+        assert_eq!(codes(&e, json!({ "rev": 1 })), ["field.missing"]);
+
+        let e = engine(json!({ "name": "Not", "not": { "name": "String", "constraints": ["maximum 0 characters"] } }));
+        assert!(codes(&e, json!("x")).is_empty());
+        assert_eq!(codes(&e, json!("")), ["not.matched"]);
+    }
+
+    #[test]
+    fn checks_tagged_unions() {
+        let e = engine(json!({
+            "name": "TaggedUnion",
+            "discriminator": "kind",
+            "variants": {
+                "circle": object(vec![field("radius", "Number", &[])]),
+                "square": object(vec![field("side", "Number", &[])]),
+            },
+        }));
+        assert!(codes(&e, json!({ "kind": "circle", "radius": 1 })).is_empty());
+        assert_eq!(codes(&e, json!({ "kind": "square", "radius": 1 })), ["field.missing"]);
+        assert_eq!(codes(&e, json!({ "kind": "hexagon" })), ["union.unknown_tag"]);
+        assert_eq!(codes(&e, json!({})), ["field.missing"]);
+    }
+
+    #[test]
+    fn checks_nullable_messages_and_deprecation() {
+        let e = engine(json!({ "name": "String", "nullable": true }));
+        assert!(codes(&e, json!(null)).is_empty());
+        assert_eq!(codes(&e, json!(1)), ["type.mismatch"]);
+
+        let e = engine(json!({
+            "name": "String",
+            "constraints": ["minimum 3 characters"],
+            "customIssue": { "code": "name.short", "message": "Names have at least 3 characters" },
+        }));
+        let result = e.validate(&json!("ab"));
+        assert_eq!((result.issues[0].code.as_str(), result.issues[0].message.as_str()),
+            ("name.short", "Names have at least 3 characters"));
+
+        let e = engine(object(vec![json!({ "name": "Field", "key": "sku", "summary": "String", "deprecated": "Use id" })]));
+        let result = e.validate(&json!({ "sku": "a" }));
+        assert!(result.ok);
+        assert_eq!((result.issues[0].level.as_str(), result.issues[0].code.as_str()), ("warning", "field.deprecated"));
+    }
+
+    #[test]
+    fn attaches_meta_to_issues() {
+        let e = engine(object(vec![json!({
+            "name": "Field",
+            "key": "owner",
+            "summary": "Object",
+            "meta": { "team": "legal", "policy": "LIC-1" },
+            "children": { "required": [{ "name": "Field", "key": "name", "summary": "String", "meta": { "team": "ops" } }] },
+        })]));
+        let result = e.validate(&json!({ "owner": { "name": 1 } }));
+        assert_eq!(result.issues.len(), 1);
+        assert_eq!(Value::Object(result.issues[0].meta.clone()), json!({ "team": "ops", "policy": "LIC-1" }));
+
+        // The field's own issues are not the value's
+        let result = e.validate(&json!({}));
+        assert!(result.issues[0].meta.is_empty());
+        assert!(!serde_json::to_string(&result.issues[0]).unwrap().contains("meta"));
+    }
+
+    #[test]
+    fn checks_recursive_named_types() {
+        let e = engine(json!({
+            "name": "Object",
+            "ref": "Node",
+            "maxDepth": 2,
+            "children": {
+                "optional": [{ "name": "Field", "key": "child", "summary": "Ref", "ref": "Node" }],
+            },
+        }));
+        assert!(codes(&e, json!({ "child": {} })).is_empty());
+        assert_eq!(codes(&e, json!({ "child": { "child": { "child": {} } } })), ["recursion.too_deep"]);
+    }
+
+    #[cfg(feature = "fs")]
+    #[test]
+    fn checks_bundles() {
+        let dir = std::env::temp_dir().join(format!("specspec-engine-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("assets")).unwrap();
+        std::fs::write(dir.join("manifest.json"), r#"{ "name": "demo" }"#).unwrap();
+        std::fs::write(dir.join("README.md"), "hello").unwrap();
+
+        let e = engine(json!({
+            "name": "Bundle",
+            "fsType": "bundle",
+            "accept": [{ "fsType": "directory" }],
+            "children": {
+                "required": [
+                    { "fsType": "jsonFile", "filePath": "manifest.json", "children": { "required": [field("name", "String", &[])] } },
+                    { "fsType": "file", "filePath": "README.md", "fileExt": "md", "checksum": {
+                        "algorithm": "sha256",
+                        "value": "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824",
+                    } },
+                    { "fsType": "directory", "filePath": "assets" },
+                ],
+            },
+        }));
+        assert!(e.is_bundle());
+        let result = e.validate_path(dir.to_str().unwrap());
+        assert!(result.ok, "{:?}", result.issues);
+
+        std::fs::write(dir.join("README.md"), "changed").unwrap();
+        std::fs::remove_dir(dir.join("assets")).unwrap();
+        let result = e.validate_path(dir.to_str().unwrap());
+        let found: Vec<&str> = result.issues.iter().map(|i| i.code.as_str()).collect();
+        assert_eq!(found, ["checksum.mismatch", "dir.not_found"]);
+
+        assert_eq!(codes(&e, json!({})), ["engine.error"]);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(feature = "fs")]
+    #[test]
+    fn rejects_unknown_bundle_entries() {
+        let bundle = |entry: Value| json!({
+            "name": "Bundle",
+            "fsType": "bundle",
+            "accept": [{ "fsType": "directory" }],
+            "children": { "required": [entry] },
+        });
+        let err = Engine::from_description(&bundle(json!({ "fsType": "zipFile", "filePath": "a.zip" }))).err().unwrap();
+        assert_eq!(err.message, "Unknown bundle entry type \"zipFile\" for a.zip");
+        assert!(Engine::from_description(&bundle(json!({ "fsType": "file" }))).is_err());
+    }
+
+    #[cfg(feature = "fs")]
+    #[test]
+    fn checks_when_rules() {
+        let dir = std::env::temp_dir().join(format!("specspec-engine-when-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("manifest.json"), r#"{ "icons": { "enabled": true } }"#).unwrap();
+
+        let e = engine(json!({
+            "name": "Bundle",
+            "fsType": "bundle",
+            "accept": [{ "fsType": "directory" }],
+            "children": {
+                "required": [{
+                    "name": "When",
+                    "condition": {
+                        "file": "manifest.json",
+                        "field": "icons.enabled",
+                        "is": { "name": "Literal", "constraints": ["equals true"] },
+                    },
+                    "then": { "fsType": "directory", "filePath": "icons" },
+                    "else": { "name": "Object", "children": { "required": [{ "fsType": "file", "filePath": "NO_ICONS" }] } },
+                }],
+            },
+        }));
+        let found = |e: &Engine| -> Vec<String> {
+            e.validate_path(dir.to_str().unwrap()).issues.into_iter().map(|i| i.code).collect()
+        };
+        assert_eq!(found(&e), ["dir.not_found"]);
+        std::fs::create_dir(dir.join("icons")).unwrap();
+        assert!(found(&e).is_empty());
+
+        std::fs::write(dir.join("manifest.json"), r#"{ "icons": { "enabled": false } }"#).unwrap();
+        assert_eq!(found(&e), ["file.not_found"]);
+        // A broken file decides nothing
+        std::fs::write(dir.join("manifest.json"), "{").unwrap();
+        assert!(found(&e).is_empty());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(feature = "fs")]
+    #[test]
+    fn shares_engines_and_bundles_between_threads() {
+        let dir = std::env::temp_dir().join(format!("specspec-engine-shared-{}", std::process::id()));
+        for (tenant, name) in [("acme", r#""Acme""#), ("globex", "42")] {
+            std::fs::create_dir_all(dir.join(tenant)).unwrap();
+            std::fs::write(dir.join(tenant).join("manifest.json"), format!(r#"{{ "name": {} }}"#, name)).unwrap();
+        }
+
+        let e = engine(json!({
+            "name": "Bundle",
+            "fsType": "bundle",
+            "accept": [{ "fsType": "directory" }],
+            "children": {
+                "required": [
+                    { "fsType": "jsonFile", "filePath": "manifest.json", "children": { "required": [field("name", "String", &[])] } },
+                ],
+            },
+        }));
+        let bundle = SharedBundle::open(dir.to_str().unwrap()).unwrap();
+        std::thread::scope(|scope| {
+            for _ in 0..4 {
+                scope.spawn(|| {
+                    assert!(e.validate_shared(&bundle, "acme").ok);
+                    let result = e.validate_shared(&bundle, "globex");
+                    assert_eq!(result.issues.len(), 1, "{:?}", result.issues);
+                    assert_eq!(result.issues[0].path, "globex.manifest.json.name");
+                });
+            }
+        });
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
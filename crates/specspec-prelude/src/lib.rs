@@ -0,0 +1,26 @@
+//! Validation primitives for Rust validators generated by SpecSpec with
+//! `--codegen rust --external-prelude`.
+//!
+//! The code is the same prelude that is otherwise embedded at the top of each
+//! generated validator; `npm run prelude:crate` copies it here before publishing.
+//! Without the default `fs` feature, the slim prelude (no filesystem or zip support) is used.
+//!
+//! [`Engine`] validates with a spec loaded at runtime instead of generated code, and
+//! [`Spec`] (`#[derive(SpecSpec)]` with the `derive` feature) describes specs with Rust types.
+
+#[cfg(feature = "fs")]
+include!("prelude.rs");
+#[cfg(not(feature = "fs"))]
+include!("prelude_slim.rs");
+
+// The prelude is embedded in generated validators, so its tests live here
+#[cfg(test)]
+mod prelude_tests;
+
+mod engine;
+pub use engine::{Engine, SpecError};
+
+pub mod spec;
+pub use spec::Spec;
+#[cfg(feature = "derive")]
+pub use specspec_derive::SpecSpec;
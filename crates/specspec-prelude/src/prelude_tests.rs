@@ -0,0 +1,340 @@
+use serde_json::{json, Value};
+
+use crate::*;
+
+fn codes(issues: &Issues) -> Vec<&str> {
+    issues.iter().map(|issue| issue.code.as_str()).collect()
+}
+
+// === Decimal ===
+
+fn check_decimal(value: &Value, min: Option<&str>, max: Option<&str>, precision: Option<usize>, scale: Option<usize>) -> Issues {
+    let mut issues = Issues::new();
+    validate_decimal(value, &[], &mut issues, min, max, precision, scale);
+    issues
+}
+
+#[test]
+fn decimal_parts_keep_the_exponent_as_a_number() {
+    let parts = parse_decimal("1e999999999999").unwrap();
+    assert_eq!((parts.digits.as_str(), parts.point), ("1", 1_000_000_000_000));
+    assert_eq!(parts.int_len(), 1_000_000_000_000);
+    assert_eq!(parts.frac_len(), 0);
+
+    let parts = parse_decimal("-12.5e-999999999999").unwrap();
+    assert!(parts.negative);
+    assert_eq!((parts.digits.as_str(), parts.point), ("125", -999_999_999_997));
+    assert_eq!(parts.frac_len(), 1_000_000_000_000);
+
+    let parts = parse_decimal("-0.000e5").unwrap();
+    assert!(!parts.negative);
+    assert_eq!((parts.int_len(), parts.frac_len()), (0, 0));
+}
+
+#[test]
+fn decimal_exponents_beyond_i64_are_not_decimals() {
+    assert!(parse_decimal("1e99999999999999999999").is_none());
+    assert!(parse_decimal(&format!("0.1e{}", i64::MAX)).is_some());
+    assert!(parse_decimal(&format!("1e{}", i64::MAX)).is_none());
+}
+
+#[test]
+fn decimal_compares_by_digits_and_exponent() {
+    let ord = |a: &str, b: &str| compare_decimal(&parse_decimal(a).unwrap(), &parse_decimal(b).unwrap());
+    assert!(ord("0.10000000000000000001", "0.1").is_gt());
+    assert!(ord("1e999999999999", "9e999999999998").is_gt());
+    assert!(ord("1e-999999999999", "0").is_gt());
+    assert!(ord("-1e-999999999999", "0").is_lt());
+    assert!(ord("-2e999999999999", "-1e999999999999").is_lt());
+    assert!(ord("120", "1.2e2").is_eq());
+    assert!(ord("-0", "0.0").is_eq());
+}
+
+#[test]
+fn decimal_bounds_with_huge_exponents() {
+    assert!(check_decimal(&json!(5), None, Some("1e999999999999"), None, None).is_empty());
+    assert!(check_decimal(&json!(5), Some("1e-999999999999"), None, None, None).is_empty());
+    assert_eq!(codes(&check_decimal(&json!(5), Some("1e999999999999"), None, None, None)), ["num.too_small"]);
+    assert_eq!(codes(&check_decimal(&json!(-5), None, Some("-1e999999999999"), None, None)), ["num.too_large"]);
+}
+
+#[test]
+fn decimal_counts_digits_and_places() {
+    assert!(check_decimal(&json!(123.45), None, None, Some(5), Some(2)).is_empty());
+    assert_eq!(codes(&check_decimal(&json!(123.456), None, None, Some(5), Some(2))),
+        ["decimal.too_many_digits", "decimal.scale_exceeded"]);
+    assert!(check_decimal(&json!(1200), None, None, Some(4), Some(0)).is_empty());
+    assert!(check_decimal(&json!(0.001), None, None, Some(3), Some(3)).is_empty());
+}
+
+#[cfg(feature = "arbitrary_precision")]
+#[test]
+fn decimal_values_with_huge_exponents() {
+    let huge: Value = serde_json::from_str("1e999999999999").unwrap();
+    let issues = check_decimal(&huge, None, Some("1e10"), Some(10), Some(2));
+    assert_eq!(codes(&issues), ["decimal.too_many_digits", "num.too_large"]);
+    assert!(issues[0].message.contains("1000000000000 digits"));
+
+    let tiny: Value = serde_json::from_str("-1e-999999999999").unwrap();
+    let issues = check_decimal(&tiny, Some("-1"), Some("0"), None, Some(2));
+    assert_eq!(codes(&issues), ["decimal.scale_exceeded"]);
+
+    let exact: Value = serde_json::from_str("0.10000000000000000001").unwrap();
+    assert_eq!(codes(&check_decimal(&exact, None, Some("0.1"), None, None)), ["num.too_large"]);
+}
+
+// === Validating Deserializer ===
+
+#[derive(Debug, PartialEq, serde::Deserialize)]
+struct Server {
+    host: String,
+    port: u16,
+    #[serde(default)]
+    tags: Vec<String>,
+}
+
+fn server_validator(v: &Value, p: &[String], i: &mut Issues) {
+    if validate_object(v, p, i) {
+        validate_field(v, p, i, "host", Some(&|v, p, i| validate_str(v, p, i, Some(1), None, None)), false);
+        validate_field(v, p, i, "port", Some(&|v, p, i| validate_num(v, p, i, Some(1024.0), None, true)), false);
+        validate_field(v, p, i, "legacy", Some(&|v, p, i| validate_deprecated(v, p, i, &|_, _, _| {}, "field.deprecated", "Use tags")), true);
+    }
+}
+
+#[test]
+fn deserializer_reports_validation_issues() {
+    let input = r#"{ "host": "", "port": 80 }"#;
+    let err = from_reader_validated::<_, Server>(input.as_bytes(), &server_validator).unwrap_err();
+    assert_eq!(codes(&err.issues), ["str.too_short", "num.too_small"]);
+    assert_eq!(err.issues[1].path, "port");
+    assert!(err.to_string().contains("port: Number 80 is less than minimum 1024"));
+
+    let mut de = serde_json::Deserializer::from_str(r#"{ "host": "db" }"#);
+    let err = <Server as serde::Deserialize>::deserialize(ValidatedDeserializer::new(&mut de, &server_validator)).unwrap_err();
+    assert_eq!(codes(&err.issues), ["field.missing"]);
+}
+
+#[test]
+fn deserializer_matches_serde_json_on_valid_input() {
+    let input = r#"{ "host": "db", "port": 5432, "tags": ["primary"], "legacy": 1 }"#;
+    let validated: Server = from_reader_validated(input.as_bytes(), &server_validator).unwrap();
+    let plain: Server = serde_json::from_str(input).unwrap();
+    assert_eq!(validated, plain);
+
+    let value: Value = serde_json::from_str(input).unwrap();
+    assert_eq!(from_value_validated::<Server>(value, &server_validator).unwrap(), plain);
+}
+
+#[test]
+fn deserializer_reports_json_and_type_errors() {
+    let err = from_reader_validated::<_, Server>(&b"{ \"host\": "[..], &server_validator).unwrap_err();
+    assert_eq!(codes(&err.issues), ["json.parse_error"]);
+
+    let err = from_reader_validated::<_, Server>(&b"{ \"host\": \"db\", \"port\": 5432 } []"[..], &server_validator).unwrap_err();
+    assert_eq!(codes(&err.issues), ["json.parse_error"]);
+
+    // Passes the validator, but does not fit the type
+    let err = from_reader_validated::<_, Server>(&br#"{ "host": "db", "port": 70000 }"#[..], &server_validator).unwrap_err();
+    assert_eq!(codes(&err.issues), ["type.deserialize"]);
+}
+
+// === Paths ===
+
+#[cfg(feature = "fs")]
+mod paths {
+    use super::*;
+    use std::io::Write;
+    use std::path::PathBuf;
+
+    /// A fresh directory under the system temp dir
+    pub(super) fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("specspec-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn extended_length_paths() {
+        assert_eq!(extended_length_path(r"C:\bundles\site"), r"\\?\C:\bundles\site");
+        assert_eq!(extended_length_path(r"\\server\share\site"), r"\\?\UNC\server\share\site");
+        assert_eq!(extended_length_path(r"\\?\C:\site"), r"\\?\C:\site");
+        assert_eq!(extended_length_path(r"\\.\pipe\site"), r"\\.\pipe\site");
+    }
+
+    #[test]
+    fn reserved_names() {
+        assert_eq!(reserved_name("CON"), Some("CON"));
+        assert_eq!(reserved_name("nul.txt"), Some("nul.txt"));
+        assert_eq!(reserved_name(r"docs\Aux.tar.gz"), Some("Aux.tar.gz"));
+        assert_eq!(reserved_name("logs/com1 .log"), Some("com1 .log"));
+        assert_eq!(reserved_name("console.txt"), None);
+        assert_eq!(reserved_name("com10/nulls.md"), None);
+    }
+
+    #[test]
+    fn reserved_names_are_reported_on_every_platform() {
+        let dir = temp_dir("reserved");
+        let ctx = FSContext::new(dir.to_str().unwrap()).unwrap();
+        let mut issues = Issues::new();
+        assert!(!validate_fs_file(&ctx, r"logs\nul.txt", &[], &mut issues, None));
+        assert!(!validate_fs_directory(&ctx, "CON", &[], &mut issues));
+        assert!(validate_json_file(&ctx, "aux.json", &[], &mut issues, None).is_none());
+        assert_eq!(codes(&issues), ["fs.reserved_name", "fs.reserved_name", "fs.reserved_name"]);
+        assert_eq!(issues[0].path, "logs/nul.txt");
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn backslashes_are_read_and_reported_as_forward_slashes() {
+        let dir = temp_dir("backslash");
+        std::fs::create_dir_all(dir.join("config")).unwrap();
+        std::fs::write(dir.join("config").join("app.json"), r#"{ "port": 80 }"#).unwrap();
+
+        let ctx = FSContext::new(dir.to_str().unwrap()).unwrap();
+        assert!(ctx.is_file(r"config\app.json"));
+        assert!(ctx.at(r"config\").is_file("app.json"));
+
+        let mut issues = Issues::new();
+        let port = |v: &Value, p: &[String], i: &mut Issues| {
+            validate_field(v, p, i, "port", Some(&|v, p, i| validate_num(v, p, i, Some(1024.0), None, true)), false);
+        };
+        validate_json_file(&ctx, r"config\app.json", &[], &mut issues, Some(&port));
+        validate_fs_file(&ctx, r"config\missing.json", &[], &mut issues, None);
+        let paths: Vec<&str> = issues.iter().map(|issue| issue.path.as_str()).collect();
+        assert_eq!(paths, ["config/app.json.port", "config/missing.json"]);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn zip_entries_with_backslashes() {
+        let dir = temp_dir("zip");
+        let zip_path = dir.join("site.zip");
+        let mut writer = zip::ZipWriter::new(std::fs::File::create(&zip_path).unwrap());
+        writer.start_file(r"docs\readme.md", zip::write::FileOptions::default()).unwrap();
+        writer.write_all(b"hello").unwrap();
+        writer.finish().unwrap();
+
+        let ctx = FSContext::new(zip_path.to_str().unwrap()).unwrap();
+        assert!(ctx.is_file("docs/readme.md"));
+        assert!(ctx.is_file(r"docs\readme.md"));
+        assert!(ctx.is_dir("docs"));
+        assert_eq!(ctx.at("docs").read("readme.md").unwrap(), "hello");
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn long_paths_are_read_on_windows() {
+        let dir = temp_dir("long");
+        let ctx = FSContext::new(dir.to_str().unwrap()).unwrap();
+        assert!(ctx.base_path.to_string_lossy().starts_with(r"\\?\"));
+
+        let nested = ["a".repeat(100), "b".repeat(100), "c".repeat(100)].join("/");
+        std::fs::create_dir_all(ctx.base_path.join(nested.replace('/', r"\"))).unwrap();
+        std::fs::write(ctx.base_path.join(nested.replace('/', r"\")).join("data.json"), "{}").unwrap();
+        let mut issues = Issues::new();
+        assert!(validate_json_file(&ctx, &format!("{}/data.json", nested), &[], &mut issues, None).is_some());
+        assert!(issues.is_empty());
+        std::fs::remove_dir_all(&ctx.base_path).unwrap();
+    }
+}
+
+// === Shared bundles ===
+
+#[cfg(feature = "fs")]
+mod shared {
+    use super::*;
+    use std::io::Write;
+
+    fn site(ctx: &FSContext, p: &[String], i: &mut Issues) {
+        let port = |v: &Value, p: &[String], i: &mut Issues| {
+            validate_field(v, p, i, "port", Some(&|v, p, i| validate_num(v, p, i, Some(1024.0), None, true)), false);
+        };
+        validate_json_file(ctx, "site.json", p, i, Some(&port));
+    }
+
+    #[test]
+    fn bundles_are_validated_from_many_threads() {
+        let dir = paths::temp_dir("shared");
+        let zip_path = dir.join("sites.zip");
+        let mut writer = zip::ZipWriter::new(std::fs::File::create(&zip_path).unwrap());
+        for (tenant, port) in [("acme", 8080), ("globex", 80)] {
+            writer.start_file(format!("tenants/{}/site.json", tenant), zip::write::FileOptions::default()).unwrap();
+            write!(writer, r#"{{ "port": {} }}"#, port).unwrap();
+        }
+        writer.finish().unwrap();
+
+        // Half of the threads borrow the bundle, the other half get clones
+        let bundle = SharedBundle::open(zip_path.to_str().unwrap()).unwrap();
+        let results: Vec<(&str, ValidationResult)> = std::thread::scope(|scope| {
+            let handles: Vec<_> = (0..8)
+                .map(|n| {
+                    let tenant = if n % 2 == 0 { "acme" } else { "globex" };
+                    let (bundle, clone) = (&bundle, bundle.clone());
+                    scope.spawn(move || {
+                        let bundle = if n < 4 { bundle } else { &clone };
+                        (tenant, bundle.validate(&format!("tenants/{}", tenant), &site))
+                    })
+                })
+                .collect();
+            handles.into_iter().map(|handle| handle.join().unwrap()).collect()
+        });
+
+        assert_eq!(results.len(), 8);
+        for (tenant, result) in &results {
+            if *tenant == "acme" {
+                assert!(result.ok, "{:?}", result.issues);
+            } else {
+                assert_eq!(codes(&result.issues), ["num.too_small"]);
+                assert_eq!(result.issues[0].path, "tenants/globex.site.json.port");
+            }
+        }
+        let missing = bundle.validate("tenants/initech", &site);
+        assert_eq!(codes(&missing.issues), ["dir.not_found"]);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
+
+// === When rules ===
+
+#[cfg(feature = "fs")]
+mod when {
+    use super::*;
+
+    // The shape `--codegen rust` emits for a When() rule
+    fn content(ctx: &FSContext, p: &[String], i: &mut Issues) {
+        validate_when(
+            ctx, p, i,
+            &Condition { file: "app.json", field: Some("targets.0.platform"), is: Some(&(|v, p, i| validate_literal_str(v, p, i, "web"))) },
+            Some(&(|ctx, p, i| { validate_fs_directory(ctx, "public", p, i); })),
+            None,
+        );
+    }
+
+    #[test]
+    fn checks_the_branch_the_condition_picks() {
+        let dir = paths::temp_dir("when");
+        let ctx = FSContext::new(dir.to_str().unwrap()).unwrap();
+        let run = || {
+            let mut issues = Issues::new();
+            content(&ctx, &[], &mut issues);
+            issues
+        };
+
+        // No file and a broken file decide nothing
+        assert!(run().is_empty());
+        std::fs::write(dir.join("app.json"), "[").unwrap();
+        assert!(run().is_empty());
+
+        std::fs::write(dir.join("app.json"), r#"{ "targets": [{ "platform": "web" }] }"#).unwrap();
+        assert_eq!(codes(&run()), ["dir.not_found"]);
+        std::fs::create_dir(dir.join("public")).unwrap();
+        assert!(run().is_empty());
+
+        std::fs::remove_dir(dir.join("public")).unwrap();
+        std::fs::write(dir.join("app.json"), r#"{ "targets": [{ "platform": "ios" }] }"#).unwrap();
+        assert!(run().is_empty());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
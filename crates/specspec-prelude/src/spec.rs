@@ -0,0 +1,207 @@
+//! Specs described by Rust types.
+//!
+//! `#[derive(SpecSpec)]` (the `derive` feature) implements [`Spec`] for a struct or a
+//! fieldless enum, so the Rust type stays the source of truth: `describe()` is the spec
+//! document, in the form `specspec <spec-file> --describe` writes, and `validate()` checks
+//! JSON values against it with the runtime [`Engine`]. The functions here build the
+//! descriptions; hand-written impls can use them too.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+pub use serde_json::Value;
+use serde_json::{json, Map};
+
+use crate::{add_issue, Engine, Issues, ValidationResult};
+
+/// A type that describes its own spec
+pub trait Spec {
+    /// The type's description. `seen` holds the named types being described, so a type
+    /// used inside itself is described by name only.
+    fn describe_in(seen: &mut Vec<&'static str>) -> Value;
+
+    /// The type's description
+    fn describe() -> Value {
+        Self::describe_in(&mut vec![])
+    }
+
+    /// Validate a JSON value against the type's spec; derived impls load the engine once
+    fn validate(value: &Value) -> ValidationResult {
+        load(&Self::describe()).validate(value)
+    }
+
+    /// Validate a JSON value, then deserialize it; the issues if either step fails
+    fn parse(value: &Value) -> Result<Self, Issues>
+    where
+        Self: serde::de::DeserializeOwned,
+    {
+        let result = Self::validate(value);
+        if !result.ok {
+            return Err(result.issues);
+        }
+        serde_json::from_value(value.clone()).map_err(|err| {
+            let mut issues = result.issues;
+            add_issue(&mut issues, &[], "type.deserialize", &format!("Cannot deserialize: {}", err));
+            issues
+        })
+    }
+}
+
+/// Load a derived description, which always loads
+pub fn load(desc: &Value) -> Engine {
+    Engine::from_description(desc).unwrap_or_else(|err| panic!("Derived spec does not load: {}", err))
+}
+
+/// A named type (Def) with this body
+pub fn named(name: &str, body: Value, description: Option<&str>, max_depth: Option<usize>) -> Value {
+    let mut desc = body;
+    if let Some(map) = desc.as_object_mut() {
+        map.insert("ref".to_string(), json!(name));
+        if let Some(description) = description {
+            map.insert("description".to_string(), json!(description));
+        }
+        if let Some(max_depth) = max_depth {
+            map.insert("maxDepth".to_string(), json!(max_depth));
+        }
+    }
+    desc
+}
+
+/// A use of a named type inside its own body
+pub fn named_ref(name: &str) -> Value {
+    json!({ "name": "Ref", "ref": name })
+}
+
+/// An object with these fields
+pub fn object(required: Vec<Value>, optional: Vec<Value>) -> Value {
+    json!({ "name": "Object", "children": { "required": required, "optional": optional } })
+}
+
+/// A string that is one of these values
+pub fn string_enum(values: &[&str]) -> Value {
+    let options: Vec<Value> = values.iter()
+        .map(|v| json!({ "name": "Literal", "constraints": [format!("equals {}", json!(v))] }))
+        .collect();
+    json!({ "name": "OneOf", "oneOf": options })
+}
+
+/// A field whose value has this description (merged into the field, as `Field()` describes it)
+pub fn field(key: &str, value: Value, optional: bool, nullable: bool, description: Option<&str>, deprecated: Option<&str>) -> Value {
+    let mut desc = Map::new();
+    desc.insert("name".to_string(), json!("Field"));
+    desc.insert("key".to_string(), json!(key));
+    if let Some(description) = description {
+        desc.insert("description".to_string(), json!(description));
+    }
+    if optional {
+        desc.insert("optional".to_string(), json!(true));
+    }
+    if nullable || value.get("nullable") == Some(&json!(true)) {
+        desc.insert("nullable".to_string(), json!(true));
+    }
+    if let Some(deprecated) = deprecated {
+        desc.insert("deprecated".to_string(), json!(deprecated));
+    }
+    if let Value::Object(value) = value {
+        for (k, v) in value {
+            match k.as_str() {
+                "name" => { desc.insert("summary".to_string(), v); }
+                "description" => { desc.insert("valueDescription".to_string(), v); }
+                "deprecated" => { desc.insert("valueDeprecated".to_string(), v); }
+                "nullable" | "optional" | "key" => {}
+                _ => { desc.insert(k, v); }
+            }
+        }
+    }
+    Value::Object(desc)
+}
+
+/// Add constraints (e.g. "minimum 3 characters") to a description
+pub fn constrain(desc: Value, constraints: &[String]) -> Value {
+    let mut desc = desc;
+    if constraints.is_empty() {
+        return desc;
+    }
+    if let Some(map) = desc.as_object_mut() {
+        let list = map.entry("constraints").or_insert_with(|| json!([]));
+        if let Some(list) = list.as_array_mut() {
+            list.extend(constraints.iter().map(|c| json!(c)));
+        }
+    }
+    desc
+}
+
+/// A list of items with this description
+pub fn list(item: Value) -> Value {
+    json!({ "name": "ListOf", "itemType": item })
+}
+
+// === Built-in types ===
+
+macro_rules! spec_for {
+    ($($ty:ty => $desc:expr),* $(,)?) => {
+        $(
+            impl Spec for $ty {
+                fn describe_in(_seen: &mut Vec<&'static str>) -> Value {
+                    $desc
+                }
+            }
+        )*
+    };
+}
+
+spec_for! {
+    String => json!({ "name": "String" }),
+    bool => json!({ "name": "Boolean" }),
+    f32 => json!({ "name": "Number" }),
+    f64 => json!({ "name": "Number" }),
+    i8 => json!({ "name": "Number", "constraints": ["integer", "minimum -128", "maximum 127"] }),
+    i16 => json!({ "name": "Number", "constraints": ["integer", "minimum -32768", "maximum 32767"] }),
+    i32 => json!({ "name": "Number", "constraints": ["integer", "minimum -2147483648", "maximum 2147483647"] }),
+    i64 => json!({ "name": "Number", "constraints": ["integer"] }),
+    isize => json!({ "name": "Number", "constraints": ["integer"] }),
+    u8 => json!({ "name": "Number", "constraints": ["integer", "minimum 0", "maximum 255"] }),
+    u16 => json!({ "name": "Number", "constraints": ["integer", "minimum 0", "maximum 65535"] }),
+    u32 => json!({ "name": "Number", "constraints": ["integer", "minimum 0", "maximum 4294967295"] }),
+    u64 => json!({ "name": "Number", "constraints": ["integer", "minimum 0"] }),
+    usize => json!({ "name": "Number", "constraints": ["integer", "minimum 0"] }),
+    Value => json!({ "name": "Unknown" }),
+}
+
+macro_rules! spec_for_generic {
+    ($($ty:ident<$t:ident> => |$seen:ident| $desc:expr),* $(,)?) => {
+        $(
+            impl<$t: Spec> Spec for $ty<$t> {
+                fn describe_in($seen: &mut Vec<&'static str>) -> Value {
+                    $desc
+                }
+            }
+        )*
+    };
+}
+
+spec_for_generic! {
+    Box<T> => |seen| T::describe_in(seen),
+    Option<T> => |seen| {
+        let mut desc = T::describe_in(seen);
+        if let Some(map) = desc.as_object_mut() {
+            map.insert("nullable".to_string(), json!(true));
+        }
+        desc
+    },
+    Vec<T> => |seen| list(T::describe_in(seen)),
+    BTreeSet<T> => |seen| constrain(list(T::describe_in(seen)), &["unique items".to_string()]),
+    HashSet<T> => |seen| constrain(list(T::describe_in(seen)), &["unique items".to_string()]),
+}
+
+// Maps only check for an object; the engine has no rule for their values
+impl<V> Spec for HashMap<String, V> {
+    fn describe_in(_seen: &mut Vec<&'static str>) -> Value {
+        object(vec![], vec![])
+    }
+}
+
+impl<V> Spec for BTreeMap<String, V> {
+    fn describe_in(_seen: &mut Vec<&'static str>) -> Value {
+        object(vec![], vec![])
+    }
+}
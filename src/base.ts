@@ -1,7 +1,7 @@
 // src/base.ts
 // Type 和 Modifier 基类
 
-import type { Context, Issue } from './context.js';
+import { validationProbe, type Context, type Issue, type ValidationOptions } from './context.js';
 
 /**
  * Type description for documentation and code generation
@@ -19,6 +19,8 @@ export interface TypeDescription {
   constraints?: string[] | undefined;
   /** Whether this item is optional */
   optional?: boolean | undefined;
+  /** Whether null is accepted in place of the value */
+  nullable?: boolean | undefined;
   /** Child items for structural types */
   children?: {
     required?: TypeDescription[] | undefined;
@@ -26,8 +28,34 @@ export interface TypeDescription {
   } | undefined;
   /** Options for OneOf */
   oneOf?: TypeDescription[] | undefined;
+  /** Parts for AllOf */
+  allOf?: TypeDescription[] | undefined;
+  /** Excluded type for Not */
+  not?: TypeDescription | undefined;
+  /** Discriminator field key for TaggedUnion */
+  discriminator?: string | undefined;
+  /** Variants by tag for TaggedUnion */
+  variants?: Record<string, TypeDescription> | undefined;
   /** Item type for ListOf */
   itemType?: TypeDescription | undefined;
+  /** Metadata attached to issues reported for this item */
+  meta?: Record<string, unknown> | undefined;
+  /** Name of the named type (Def) this description defines, or refers to when name is "Ref" */
+  ref?: string | undefined;
+  /** Maximum nesting of a recursive named type within itself */
+  maxDepth?: number | undefined;
+  /** Spec-defined code and/or message reported instead of the inner type's issues */
+  customIssue?: { code?: string; message?: string } | undefined;
+  /** Warning reported when this field or type is used */
+  deprecated?: string | undefined;
+  /** Validation phase the checks run in, when deferred with Phase() */
+  phase?: number | undefined;
+  /** For Field: description of the value's own type (e.g. a named type), besides the field's */
+  valueDescription?: string | undefined;
+  /** For Field: warning reported when the value matches a deprecated type */
+  valueDeprecated?: string | undefined;
+  /** Value filled in by normalization when this (optional) field is missing */
+  default?: unknown;
   /** Raw spec for custom rendering */
   spec?: unknown;
 
@@ -43,6 +71,16 @@ export interface TypeDescription {
   fileExt?: string | undefined;
   /** Name pattern (regex source) for Bundle */
   namePattern?: string | undefined;
+  /** Expected file digest for File */
+  checksum?: { algorithm: string; value: string } | undefined;
+  /** Directory allows only the entries it declares */
+  strict?: boolean | undefined;
+  /** For When: the JSON file value the content depends on, and what it must match */
+  condition?: { file: string; field?: string | undefined; is?: TypeDescription | undefined } | undefined;
+  /** For When: content checked when the condition holds */
+  then?: TypeDescription | undefined;
+  /** For When: content checked when it does not */
+  else?: TypeDescription | undefined;
 }
 
 /**
@@ -117,10 +155,12 @@ class TestContext implements Context {
   path: string[];
   readonly issues: Issue[] = [];
   value: unknown;
+  readonly options: ValidationOptions;
 
   constructor(parent: Context) {
     this.path = [...parent.path];
     this.value = parent.value;
+    this.options = parent.options;
   }
 
   addIssue(code: string, message: string): void {
@@ -131,6 +171,19 @@ class TestContext implements Context {
     this.issues.push({ level: 'warning', code, message, path: [...this.path] });
   }
 
+  addInfo(code: string, message: string): void {
+    this.issues.push({ level: 'info', code, message, path: [...this.path] });
+  }
+
+  withMeta(_meta: Record<string, unknown>): Context {
+    // Only errors matter for matches(), metadata is dropped
+    return this;
+  }
+
+  markPartial(): void {
+    // Only errors matter for matches()
+  }
+
   child(segment: string, value: unknown): Context {
     const childCtx = new TestContext(this);
     childCtx.path = [...this.path, segment];
@@ -180,6 +233,20 @@ export function isLiteralValue(v: unknown): v is LiteralValue {
  * 验证任意可验证对象
  */
 export function validateAny(schema: Validatable, value: unknown, ctx: Context): void {
+  const probe = validationProbe();
+  if (!probe || ctx instanceof TestContext) {
+    validateNode(schema, value, ctx);
+    return;
+  }
+  probe.enter(schema, value);
+  try {
+    validateNode(schema, value, ctx);
+  } finally {
+    probe.leave();
+  }
+}
+
+function validateNode(schema: Validatable, value: unknown, ctx: Context): void {
   if (isType(schema)) {
     schema.validate(value, ctx);
   } else if (isModifier(schema)) {
@@ -191,7 +258,10 @@ export function validateAny(schema: Validatable, value: unknown, ctx: Context):
         ctx.addIssue('literal.pattern_mismatch', `Expected to match ${schema}`);
       }
     } else if (value !== schema) {
-      ctx.addIssue('literal.mismatch', `Expected ${JSON.stringify(schema)}, got ${JSON.stringify(value)}`);
+      // 只有一个合法值，可直接替换
+      ctx.addIssue('literal.mismatch', `Expected ${JSON.stringify(schema)}, got ${JSON.stringify(value)}`, [
+        { op: 'replace', path: '', value: schema },
+      ]);
     }
   } else {
     // 当作对象结构处理（{ required: [], optional: [] }）
@@ -234,6 +304,52 @@ export function validateObjectSpec(spec: ObjectSpec, value: unknown, ctx: Contex
   }
 }
 
+/**
+ * 描述任意可验证对象（字面量、内联对象规格也有描述）
+ */
+export function describeValidatable(v: Validatable | ObjectSpec): TypeDescription {
+  if (isType(v)) {
+    return v.describe();
+  } else if (isModifier(v)) {
+    return v.describe();
+  } else if (isLiteralValue(v)) {
+    if (v instanceof RegExp) {
+      return { name: 'Pattern', constraints: [`matches \`${v}\``] };
+    }
+    return { name: 'Literal', constraints: [`equals ${JSON.stringify(v)}`] };
+  } else if (isObjectSpec(v)) {
+    return {
+      name: 'Object',
+      children: {
+        required: v.required?.map(describeValidatable),
+        optional: v.optional?.map(describeValidatable),
+      },
+    };
+  }
+  return { name: 'Unknown' };
+}
+
+/**
+ * 弃用说明（字段或类型）
+ */
+export interface DeprecationSpec {
+  /** 弃用原因或迁移说明 */
+  message?: string;
+  /** 替代的字段或类型 */
+  replacement?: string;
+}
+
+/**
+ * 生成弃用警告文本，例如 `Field "id" is deprecated: use UUIDs; use "uuid" instead`
+ */
+export function deprecationNotice(subject: string, deprecated: boolean | string | DeprecationSpec): string {
+  const spec: DeprecationSpec = typeof deprecated === 'string' ? { message: deprecated } : typeof deprecated === 'object' ? deprecated : {};
+  let notice = `${subject} is deprecated`;
+  if (spec.message) notice += `: ${spec.message}`;
+  if (spec.replacement) notice += `; use ${spec.replacement} instead`;
+  return notice;
+}
+
 /**
  * 尝试匹配（用于 OneOf）
  */
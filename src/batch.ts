@@ -0,0 +1,288 @@
+// src/batch.ts
+// Batch validation - many inputs against one spec, in parallel, with an aggregate summary
+
+import fs from 'node:fs';
+import os from 'node:os';
+import path from 'node:path';
+import { pathToFileURL } from 'node:url';
+import { Worker, isMainThread, parentPort, workerData } from 'node:worker_threads';
+import { SpecEngine, type ValidationResult } from './engine.js';
+import type { Schema } from './schema.js';
+import type { Issue } from './context.js';
+
+export interface BatchOptions {
+  /** Inputs validated at once, each job in a worker thread (default: available CPUs) */
+  jobs?: number;
+  /** Modules exporting custom types, loaded by every job (as `specspec -t`) */
+  typesFiles?: string[];
+  /** Validate every array item, ignoring spec sampling, here and in every job */
+  full?: boolean;
+}
+
+/** One input's result */
+export interface BatchResult extends ValidationResult {
+  input: string;
+}
+
+/** How often an issue code was reported across the batch */
+export interface IssueCodeCount {
+  code: string;
+  /** Inputs reporting it at least once */
+  inputs: number;
+  /** Issues with this code, over all inputs */
+  issues: number;
+}
+
+export interface BatchReport {
+  /** True when every input passed */
+  ok: boolean;
+  total: number;
+  passed: number;
+  failed: number;
+  /** Error and warning codes, reported by the most inputs first */
+  codes: IssueCodeCount[];
+  /** One entry per input, in the order they were given */
+  results: BatchResult[];
+}
+
+// ═══════════════════════════════════════════════════════════════
+// Inputs
+// ═══════════════════════════════════════════════════════════════
+
+function globRegex(pattern: string): RegExp {
+  let source = '';
+  for (let i = 0; i < pattern.length; i++) {
+    const c = pattern[i]!;
+    if (c === '*' && pattern[i + 1] === '*') {
+      // `**/` matches any number of directories, including none
+      source += pattern[i + 2] === '/' ? '(?:.*/)?' : '.*';
+      i += pattern[i + 2] === '/' ? 2 : 1;
+    } else if (c === '*') {
+      source += '[^/]*';
+    } else if (c === '?') {
+      source += '[^/]';
+    } else if (c === '{') {
+      const close = pattern.indexOf('}', i);
+      if (close === -1) {
+        source += '\\{';
+        continue;
+      }
+      source += `(?:${pattern.slice(i + 1, close).split(',').map(p => p.replace(/[.+^$()|[\]\\]/g, '\\$&')).join('|')})`;
+      i = close;
+    } else {
+      source += c.replace(/[.+^$()|[\]\\]/g, '\\$&');
+    }
+  }
+  return new RegExp(`^${source}$`);
+}
+
+// Entries under dir, relative to it with `/` separators; dot entries are skipped
+function walk(dir: string, prefix = ''): Array<{ rel: string; dir: boolean }> {
+  let entries: fs.Dirent[];
+  try {
+    entries = fs.readdirSync(dir, { withFileTypes: true });
+  } catch {
+    return [];
+  }
+  return entries.filter(e => !e.name.startsWith('.')).flatMap(e => {
+    const rel = prefix + e.name;
+    return e.isDirectory() ? [{ rel, dir: true }, ...walk(path.join(dir, e.name), `${rel}/`)] : [{ rel, dir: false }];
+  });
+}
+
+/**
+ * Resolve input arguments to paths. Patterns may use `*`, `?`, `**` (any number of
+ * directories) and `{a,b}`, so the shell does not have to expand hundreds of paths;
+ * a trailing `/` only matches directories (bundles). Other arguments are kept as
+ * given, even when missing, so they are reported. Sorted, without duplicates.
+ */
+export function expandInputs(patterns: string[], cwd = process.cwd()): string[] {
+  const inputs = new Set<string>();
+  for (const pattern of patterns) {
+    const normalized = pattern.split(path.sep).join('/');
+    if (!/[*?{]/.test(normalized)) {
+      inputs.add(path.resolve(cwd, pattern));
+      continue;
+    }
+    const segments = normalized.replace(/\/$/, '').split('/');
+    const fixed = segments.findIndex(s => /[*?{]/.test(s));
+    const base = path.resolve(cwd, segments.slice(0, fixed).join('/') || '.');
+    const matcher = globRegex(segments.slice(fixed).join('/'));
+    const dirsOnly = normalized.endsWith('/');
+    for (const entry of walk(base)) {
+      if (matcher.test(entry.rel) && (entry.dir || !dirsOnly)) {
+        inputs.add(path.join(base, entry.rel));
+      }
+    }
+  }
+  return [...inputs].sort();
+}
+
+/**
+ * Validate one input: a path for file system specs, a JSON file for data specs
+ */
+export function validateInput(schema: Schema, input: string): ValidationResult {
+  if (schema.describe().fsType !== undefined) {
+    return schema.validate(input);
+  }
+  let text: string;
+  try {
+    text = fs.readFileSync(input, 'utf-8');
+  } catch (err) {
+    return inputError('file.read_error', `Failed to read file: ${(err as Error).message}`);
+  }
+  return validateJsonText(schema, text);
+}
+
+/**
+ * Validate a JSON document against a data spec; text that is not JSON is reported as json.parse_error
+ */
+export function validateJsonText(schema: Schema, text: string): ValidationResult {
+  let value: unknown;
+  try {
+    value = JSON.parse(text);
+  } catch (err) {
+    return inputError('json.parse_error', `Failed to parse JSON: ${(err as Error).message}`);
+  }
+  return schema.validateValue(value);
+}
+
+function inputError(code: string, message: string): ValidationResult {
+  return { ok: false, issues: [{ level: 'error', code, message, path: [] }] };
+}
+
+/**
+ * Summarize per-input results: pass/fail counts and issue codes by frequency
+ */
+export function summarizeBatch(results: BatchResult[]): BatchReport {
+  const counts = new Map<string, IssueCodeCount>();
+  for (const result of results) {
+    const seen = new Set<string>();
+    for (const issue of result.issues) {
+      if (issue.level === 'info') continue;
+      const count = counts.get(issue.code) ?? { code: issue.code, inputs: 0, issues: 0 };
+      count.issues++;
+      if (!seen.has(issue.code)) {
+        seen.add(issue.code);
+        count.inputs++;
+      }
+      counts.set(issue.code, count);
+    }
+  }
+  const passed = results.filter(r => r.ok).length;
+  return {
+    ok: passed === results.length,
+    total: results.length,
+    passed,
+    failed: results.length - passed,
+    codes: [...counts.values()].sort((a, b) => b.inputs - a.inputs || b.issues - a.issues || a.code.localeCompare(b.code)),
+    results,
+  };
+}
+
+// ═══════════════════════════════════════════════════════════════
+// Running
+// ═══════════════════════════════════════════════════════════════
+
+async function loadEngine(typesFiles: string[], full: boolean): Promise<SpecEngine> {
+  const engine = new SpecEngine({ sampling: !full });
+  for (const typesFile of typesFiles) {
+    const module = await import(pathToFileURL(path.resolve(typesFile)).href) as Record<string, unknown>;
+    for (const [key, value] of Object.entries(module)) {
+      if (key !== 'default' && (typeof value === 'function' || (typeof value === 'object' && value !== null))) {
+        engine.register({ [key]: value });
+      }
+    }
+  }
+  return engine;
+}
+
+interface WorkerSetup {
+  specspecBatch: true;
+  specPath: string;
+  typesFiles: string[];
+  full: boolean;
+}
+
+/** A worker's message: ready after compiling the spec, then one per input */
+interface WorkerReply {
+  index?: number;
+  result?: ValidationResult;
+  /** An unexpected error validating the input */
+  error?: string;
+}
+
+function engineError(err: unknown): ValidationResult {
+  const issue: Issue = { level: 'error', code: 'engine.error', message: `Validation error: ${(err as Error).message}`, path: [] };
+  return { ok: false, issues: [issue] };
+}
+
+/**
+ * Validate many inputs against one spec and summarize the results. Inputs are
+ * spread over `jobs` worker threads (with one job, everything runs in this
+ * thread); results are reported in input order.
+ * Throws SchemaError when the spec does not compile.
+ *
+ * @example
+ * const report = await validateBatch('bundle.spec.js', expandInputs(['releases/v2-*']), { jobs: 8 });
+ * console.log(`${report.failed} of ${report.total} bundles failed`);
+ */
+export async function validateBatch(specPath: string, inputs: string[], options: BatchOptions = {}): Promise<BatchReport> {
+  const typesFiles = (options.typesFiles ?? []).map(f => path.resolve(f));
+  const engine = await loadEngine(typesFiles, options.full ?? false);
+  const schema = engine.compile(specPath); // Spec errors surface once, here
+  const jobs = Math.max(1, Math.min(options.jobs ?? os.availableParallelism(), inputs.length));
+
+  if (jobs === 1) {
+    return summarizeBatch(inputs.map(input => {
+      try {
+        return { input, ...validateInput(schema, input) };
+      } catch (err) {
+        return { input, ...engineError(err) };
+      }
+    }));
+  }
+
+  const results: BatchResult[] = new Array(inputs.length);
+  let next = 0;
+  const setup: WorkerSetup = { specspecBatch: true, specPath: path.resolve(specPath), typesFiles, full: options.full ?? false };
+  const runWorker = () => new Promise<void>((resolve, reject) => {
+    const worker = new Worker(new URL(import.meta.url), { workerData: setup });
+    // Each worker takes the next input when it is done with one, so slow inputs do not hold up a share
+    worker.on('message', (reply: WorkerReply) => {
+      if (reply.index !== undefined) {
+        const input = inputs[reply.index]!;
+        results[reply.index] = reply.result ? { input, ...reply.result } : { input, ...engineError(new Error(reply.error)) };
+      }
+      if (next < inputs.length) {
+        worker.postMessage({ index: next, input: inputs[next++] });
+      } else {
+        void worker.terminate().then(() => resolve());
+      }
+    });
+    worker.on('error', reject);
+  });
+  await Promise.all(Array.from({ length: jobs }, runWorker));
+  return summarizeBatch(results);
+}
+
+async function serveWorker(setup: WorkerSetup): Promise<void> {
+  const engine = await loadEngine(setup.typesFiles, setup.full);
+  const schema = engine.compile(setup.specPath);
+  const port = parentPort!;
+  port.on('message', ({ index, input }: { index: number; input: string }) => {
+    let reply: WorkerReply;
+    try {
+      reply = { index, result: validateInput(schema, input) };
+    } catch (err) {
+      reply = { index, error: (err as Error).message };
+    }
+    port.postMessage(reply);
+  });
+  port.postMessage({});
+}
+
+// Entry point of the worker threads started by validateBatch()
+if (!isMainThread && (workerData as Partial<WorkerSetup> | null)?.specspecBatch === true) {
+  void serveWorker(workerData as WorkerSetup);
+}
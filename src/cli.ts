@@ -4,11 +4,31 @@
 
 import path from 'node:path';
 import fs from 'node:fs';
+import os from 'node:os';
 import { pathToFileURL } from 'node:url';
-import { SpecEngine } from './engine.js';
+import { SpecEngine, type ValidationResult, type MultiValidationResult, type CompareResult } from './engine.js';
+import { SchemaError, type Schema } from './schema.js';
+import { watchTree, diffIssues } from './watcher.js';
+import AdmZip from 'adm-zip';
+import { validateBatch, expandInputs, validateJsonText, summarizeBatch, type BatchReport } from './batch.js';
+import { runFixtures, type FixtureReport } from './fixtures.js';
+import { lintSpecs } from './lint.js';
+import { formatSpec } from './fmt.js';
+import { scoreCorpus, type ScoreReport } from './score.js';
+import type { CoverageReport } from './coverage.js';
+import type { CompatReport } from './compat.js';
+import type { Issue } from './context.js';
 import { generateDoc } from './doc.js';
+import { importOpenApi, type OpenApiImport } from './openapi.js';
+import { exportProto } from './proto.js';
+import { portableDescription } from './portable.js';
+import { SpecLanguageService, serveLanguageServer } from './lsp.js';
 import { getGenerators, getSupportedLanguages } from './codegen/index.js';
+import type { CodegenOptions } from './codegen/base.js';
 import { Type, Modifier } from './base.js';
+import { repairReport, formatRepairReport, partitionStructural, type RepairReport } from './repair.js';
+import { differential, engineRunner, moduleRunner, commandRunner, listCorpus, type Runner } from './differential.js';
+import { CONFIG_FILE, findConfig, loadConfig, applyIssuePolicy, type ProjectConfig, type FailOn, type IssuePolicy } from './config.js';
 
 const args = process.argv.slice(2);
 
@@ -17,33 +37,159 @@ const help = `
 SpecSpec - Validate targets against spec files
 
 Usage:
-  specspec <spec-file> <target-path> [options]
+  specspec [validate] <spec-file> <target-path> [options]
+  specspec [validate] [<target-path>] [options]     (spec and target from .specspec.toml)
+  specspec [validate] <spec-file> - [options]
+  specspec [validate] <spec-file> --zip-stdin [options]
+  specspec <spec-file> <target-path> --spec <spec-file>... [options]
+  specspec <spec-file> <target-path> --compare <other-path> [options]
+  specspec <spec-file> <corpus-dir> --differential <validator> [options]
+  specspec <spec-file> <target-path> --watch [options]
+  specspec <spec-file> --codegen <lang> -o <file> --watch [options]
   specspec <spec-file> --doc [options]
   specspec <spec-file> --codegen <lang> [options]
+  specspec <spec-file> --proto [--package <name>] [-o <file>]
+  specspec <spec-file> --describe [-o <file>]
+  specspec batch <spec-file> <input>... [--jobs <n>] [--details] [options]
+  specspec test <spec-file> [--fixtures <dir>] [options]
+  specspec score <spec-file> <input>... [--examples <n>] [options]
+  specspec codegen [<spec-file>] [options]
+  specspec lint <spec-file>... [options]
+  specspec fmt <spec-file>... [--check]
+  specspec compat <old-spec> <new-spec> [options]
+  specspec generate <spec-file> [--seed <n>] [-o <path>]
+  specspec openapi <document.json> [--operations] [--root <name>] [-o <spec-file>]
+  specspec lsp [-t <types>]
   specspec --init [name]
 
 Options:
   -t, --types <file>   Load custom types (can be used multiple times)
   --json               Output results as JSON
+  --config <file>      Project configuration (default: the nearest .specspec.toml)
+  --fail-on <level>    Exit 1 on errors (default), or on warnings too: error, warning
+  --warnings-as-errors Same as --fail-on warning
+  --suppress <issue>   Leave out issues with this code, or code@path (can be used
+                       multiple times; * in a code matches anything)
+  -s, --spec <file>    Also validate against this spec, after the previous ones
+                       (can be used multiple times; results are reported per spec)
+  --stop-on-failure    With --spec, skip the remaining specs once one fails
+  --zip-stdin          Read the target from stdin as a zip archive: extracted for
+                       directory specs, validated as the archive for ZipFile specs
+  -j, --jobs <n>       With batch, inputs validated in parallel (default: CPU count)
+  --details            With batch, list every input and its issues
+  --fixtures <dir>     With test, the fixtures directory (default: fixtures next to the spec)
+  --coverage           With test, report the spec rules no fixture reached or broke
+  --examples <n>       With score, offending documents listed per rule (default: 3)
+  --check              With fmt, list spec files that are not formatted instead of
+                       rewriting them; exits 1 if there are any
+  --compare <path>     Compare spec-relevant content of target with another target
+  --full               Validate every array item, ignoring spec sampling
+  --watch              Re-run validation (or --codegen) when the spec, the specs it
+                       imports or the target change, printing new and resolved issues
+  --differential <v>   Diff results on each corpus entry against another validator:
+                       a SpecSpec entry module (.js/.mjs) or a generated validator command
   --doc                Generate Markdown documentation from spec file
   --codegen <lang>     Generate validator code (use --help for supported languages)
+  --proto              Export the spec's object types as proto3 messages; rules
+                       protobuf cannot express are listed on stderr
+  --describe           Write the spec's type description as JSON, for runtimes that
+                       load specs without codegen (Rust: specspec_prelude::Engine)
+  --fail-fast          Generated validators stop at the first failing check
+  --typed              Also generate typed data structures and parse() (Rust)
+  --external-prelude   Depend on the published prelude instead of embedding it
+                       (Rust: the specspec-prelude crate)
+  --slim               Leave out filesystem and zip support, for data specs (Rust)
+  --cli <name>         Generate a command line tool: <name> validate <path> [--format json] (Rust)
+  --property-tests     Also generate proptest tests at the spec's constraint boundaries (Rust)
+  --fuzz <dir>         Also write a fuzzing harness for the -o output into <dir> (Rust: cargo-fuzz)
+  --package <name>     Package of the generated code or .proto file; without it Go uses
+                       package main and Go/Kotlin get a command line entry point
+  --no-schedule        Keep spec order instead of running cheap checks first
+  --seed <n>           With generate, seed for reproducible samples (default: 0)
+  --operations         With openapi, also define request and response bodies
+  --root <name>        With openapi, the definition to use as the spec's root
   -o, --output <file>  Write output to file instead of stdout
   --help, -h           Show this help message
   --version, -v        Show version
 
 Commands:
   --init [name]        Create a sample spec file (default: spec.js)
+  batch <spec> <input>...
+                       Validate many inputs (bundles, or JSON files for data specs) and
+                       print a summary; inputs may be quoted globs ('releases/*/',
+                       'data/**/*.json'); exits 1 if any input fails
+  test <spec>          Check the spec against fixtures: everything under valid/ must
+                       pass, everything under invalid/ must fail (with the issues in
+                       <fixture>.expected.json, when present); exits 1 on a mismatch
+  score <spec> <input>...
+                       Run the spec over a corpus of real documents (inputs as for
+                       batch; for data specs a directory stands for its JSON files) and
+                       report how many each rule rejects, with examples
+  codegen [spec]       Generate every [[codegen]] target of .specspec.toml
+  lint <spec>...       Report unused Defs, OneOf options shadowed by earlier ones,
+                       contradictory constraints, patterns that cannot match and
+                       duplicate fields; exits 1 on errors (see --fail-on)
+  fmt <spec>...        Rewrite spec files in the canonical layout: indentation, line
+                       wrapping and the documented order of type options
+  compat <old> <new>   Report breaking and compatible changes between two spec
+                       versions (exits 1 if any change is breaking)
+  generate <spec>      Print a sample JSON value that satisfies the spec, or write
+                       a sample file tree to -o for file system specs
+  openapi <document>   Write a spec with a Def per schema of an OpenAPI 3 document
+                       (JSON); the root is the last definition unless --root is given
+  lsp                  Run a language server for spec files on stdin/stdout: diagnostics,
+                       go to definition, hover and completion of type options
 
 Examples:
   specspec package.spec.js ./my-project
+  curl -s https://example.com/config.json | specspec config.spec.js -
+  git archive --format=zip HEAD | specspec package.spec.js --zip-stdin
   specspec Spec.js ./bundle -t ./core.mjs -t ./oauth.mjs
+  specspec format.spec.js ./bundle -s policy.spec.js -s tenant.spec.js
+  specspec Spec.js ./bundle-v1 --compare ./bundle-v2
+  specspec Spec.js ./bundle --watch
+  specspec Spec.js ./corpus --differential ./old/node_modules/@specspec/core/dist/index.js
+  specspec Spec.js ./corpus --differential "python3 validator.py"
   specspec Spec.js --doc -o README.md
   specspec Spec.js --codegen python -o validator.py
+  specspec Spec.js --codegen go --package specs -o specs/validator.go
+  specspec Spec.js --proto --package acme.orders.v1 -o orders.proto
+  specspec Spec.js --describe -o spec.json
+  specspec batch Spec.js 'releases/*/' --jobs 8
+  specspec batch config.spec.js 'configs/**/*.json' --details
+  specspec test config.spec.js --fixtures test/fixtures
+  specspec test config.spec.js --coverage
+  specspec score config.spec.js ./corpus --examples 5
+  specspec Spec.js ./bundle --fail-on warning --suppress 'list.*'
+  specspec codegen
+  specspec lint types.spec.js order.spec.js
+  specspec fmt 'specs/**/*.spec.js' --check
+  specspec compat Spec-v1.js Spec-v2.js
+  specspec generate config.spec.js --seed 7 -o fixtures/config.json
+  specspec generate Spec.js -o fixtures/bundle
+  specspec openapi api.json --root Pet -o pet.spec.js
   specspec --init
 
 Custom Types:
   Export types as named exports, then load with -t:
     specspec spec.js target -t ./my-types.mjs
+
+Project Configuration:
+  Defaults for every command are read from .specspec.toml, in the current
+  directory or a parent; paths are relative to the file, command line options
+  take precedence:
+    spec = "specs/bundle.spec.js"
+    types = ["specs/types.mjs"]
+    target = "dist/bundle"
+
+    [report]
+    format = "json"                           # or "text"
+    fail-on = "warning"                       # or "error"
+    suppress = ["field.deprecated@legacy"]
+
+    [[codegen]]
+    lang = "python"
+    output = "gen/validator.py"
 `;
 
 // Version
@@ -102,14 +248,47 @@ interface Options {
   specFile?: string;
   targetPath?: string;
   typesFiles: string[];
+  /** Further specs to validate against, in order (--spec) */
+  specFiles: string[];
+  stopOnFailure?: boolean;
+  /** Positional arguments after the spec file (batch inputs) */
+  inputs: string[];
+  jobs?: number;
+  details?: boolean;
+  fixtures?: string;
+  coverage?: boolean;
+  examples?: number;
+  check?: boolean;
+  zipStdin?: boolean;
   json?: boolean;
+  compare?: string;
+  differential?: string;
+  full?: boolean;
+  watch?: boolean;
   doc?: boolean;
+  proto?: boolean;
+  describe?: boolean;
   codegen?: string;
+  failFast?: boolean;
+  typed?: boolean;
+  externalPrelude?: boolean;
+  slim?: boolean;
+  cli?: string;
+  propertyTests?: boolean;
+  fuzz?: string;
+  package?: string;
+  schedule?: boolean;
+  seed?: number;
+  operations?: boolean;
+  root?: string;
   output?: string;
+  failOn?: FailOn;
+  /** Issues to leave out: `code` or `code@path` (--suppress) */
+  suppress: string[];
 }
 
 function parseArgs(args: string[]): Options {
-  const opts: Options = { typesFiles: [] };
+  const opts: Options = { typesFiles: [], specFiles: [], inputs: [], suppress: [] };
   const positional: string[] = [];
 
   for (let i = 0; i < args.length; i++) {
@@ -117,26 +296,167 @@ function parseArgs(args: string[]): Options {
     if (arg === '-t' || arg === '--types') {
       const nextArg = args[++i];
       if (nextArg) opts.typesFiles.push(nextArg);
+    } else if (arg === '-s' || arg === '--spec') {
+      const nextArg = args[++i];
+      if (nextArg) opts.specFiles.push(nextArg);
+    } else if (arg === '--config') {
+      i++; // Read before parsing, by readProjectConfig()
+    } else if (arg === '--fail-on') {
+      const nextArg = args[++i];
+      if (nextArg !== 'error' && nextArg !== 'warning') {
+        console.error('Error: --fail-on must be error or warning');
+        process.exit(1);
+      }
+      opts.failOn = nextArg;
+    } else if (arg === '--warnings-as-errors') {
+      opts.failOn = 'warning';
+    } else if (arg === '--suppress') {
+      const nextArg = args[++i];
+      if (nextArg) opts.suppress.push(nextArg);
+    } else if (arg === '--stop-on-failure') {
+      opts.stopOnFailure = true;
+    } else if (arg === '-j' || arg === '--jobs') {
+      const nextArg = args[++i];
+      if (nextArg) opts.jobs = Number(nextArg);
+    } else if (arg === '--details') {
+      opts.details = true;
+    } else if (arg === '--zip-stdin') {
+      opts.zipStdin = true;
+    } else if (arg === '--coverage') {
+      opts.coverage = true;
+    } else if (arg === '--examples') {
+      const nextArg = args[++i];
+      if (nextArg) opts.examples = Number(nextArg);
+    } else if (arg === '--check') {
+      opts.check = true;
+    } else if (arg === '--fixtures') {
+      const nextArg = args[++i];
+      if (nextArg) opts.fixtures = nextArg;
     } else if (arg === '--json') {
       opts.json = true;
+    } else if (arg === '--compare') {
+      const nextArg = args[++i];
+      if (nextArg) opts.compare = nextArg;
+    } else if (arg === '--differential') {
+      const nextArg = args[++i];
+      if (nextArg) opts.differential = nextArg;
+    } else if (arg === '--full') {
+      opts.full = true;
+    } else if (arg === '--watch') {
+      opts.watch = true;
     } else if (arg === '--doc') {
       opts.doc = true;
+    } else if (arg === '--proto') {
+      opts.proto = true;
+    } else if (arg === '--describe') {
+      opts.describe = true;
     } else if (arg === '--codegen') {
       const nextArg = args[++i];
       if (nextArg) opts.codegen = nextArg;
+    } else if (arg === '--fail-fast') {
+      opts.failFast = true;
+    } else if (arg === '--typed') {
+      opts.typed = true;
+    } else if (arg === '--external-prelude') {
+      opts.externalPrelude = true;
+    } else if (arg === '--slim') {
+      opts.slim = true;
+    } else if (arg === '--cli') {
+      const nextArg = args[++i];
+      if (nextArg) opts.cli = nextArg;
+    } else if (arg === '--property-tests') {
+      opts.propertyTests = true;
+    } else if (arg === '--fuzz') {
+      const nextArg = args[++i];
+      if (nextArg) opts.fuzz = nextArg;
+    } else if (arg === '--package') {
+      const nextArg = args[++i];
+      if (nextArg) opts.package = nextArg;
+    } else if (arg === '--no-schedule') {
+      opts.schedule = false;
+    } else if (arg === '--seed') {
+      const nextArg = args[++i];
+      if (nextArg) opts.seed = Number(nextArg);
+    } else if (arg === '--operations') {
+      opts.operations = true;
+    } else if (arg === '--root') {
+      const nextArg = args[++i];
+      if (nextArg) opts.root = nextArg;
     } else if (arg === '-o' || arg === '--output') {
       const nextArg = args[++i];
       if (nextArg) opts.output = nextArg;
-    } else if (!arg.startsWith('-')) {
+    } else if (arg === '-' || !arg.startsWith('-')) {
+      // `-` is the target read from stdin
       positional.push(arg);
     }
   }
 
   if (positional[0]) opts.specFile = positional[0];
   if (positional[1]) opts.targetPath = positional[1];
+  opts.inputs = positional.slice(1);
   return opts;
 }
 
+// The project configuration: the file given with --config, else the nearest .specspec.toml
+function readProjectConfig(args: string[]): ProjectConfig | undefined {
+  const flag = args.indexOf('--config');
+  const file = flag === -1 ? findConfig() : args[flag + 1] && path.resolve(process.cwd(), args[flag + 1]!);
+  if (!file) {
+    if (flag === -1) return undefined;
+    console.error('Error: --config needs a file');
+    process.exit(1);
+  }
+  if (!fs.existsSync(file)) {
+    console.error(`Error: Config file not found: ${file}`);
+    process.exit(1);
+  }
+  try {
+    return loadConfig(file);
+  } catch (err) {
+    console.error(`Error: ${(err as Error).message}`);
+    process.exit(1);
+  }
+}
+
+// Add the configured types and report settings; the command line takes precedence
+function withConfig(opts: Options, config: ProjectConfig | undefined): Options {
+  if (!config) return opts;
+  return {
+    ...opts,
+    typesFiles: [...(config.types ?? []), ...opts.typesFiles],
+    suppress: [...(config.suppress ?? []), ...opts.suppress],
+    ...(opts.failOn === undefined && config.failOn !== undefined ? { failOn: config.failOn } : {}),
+    ...(opts.json === undefined && config.format === 'json' ? { json: true } : {}),
+  };
+}
+
+/**
+ * Use the configured spec when the command line names none. When validating, a
+ * lone path is the target (`specspec ./bundle`), and without one the configured
+ * target is used.
+ */
+function withConfigSpec(opts: Options, config: ProjectConfig | undefined, validating: boolean): Options {
+  if (config?.spec === undefined) return opts;
+  const target = opts.specFile ?? (opts.zipStdin ? undefined : config.target);
+  if (opts.specFile !== undefined && (!validating || opts.targetPath !== undefined || opts.zipStdin)) return opts;
+  return {
+    ...opts,
+    specFile: config.spec,
+    specFiles: [...(config.specs ?? []), ...opts.specFiles],
+    ...(validating && target !== undefined ? { targetPath: target } : {}),
+  };
+}
+
+function issuePolicy(opts: Options): IssuePolicy {
+  return { ...(opts.failOn !== undefined ? { failOn: opts.failOn } : {}), suppress: opts.suppress };
+}
+
+// Apply --suppress and --fail-on to each spec's result
+function applyMultiPolicy(result: MultiValidationResult, opts: Options): MultiValidationResult {
+  const results = result.results.map(r => r.skipped ? r : applyIssuePolicy(r, issuePolicy(opts)));
+  return { ok: results.every(r => r.ok), results };
+}
+
 async function validate(opts: Options) {
   const specPath = path.resolve(process.cwd(), opts.specFile!);
   const target = path.resolve(process.cwd(), opts.targetPath!);
@@ -202,7 +522,7 @@ async function validate(opts: Options) {
 
   if (!opts.json) {
     console.log(`Spec:   ${specPath}`);
-    console.log(`Target: ${target}`);
+    console.log(`Target: ${opts.zipStdin ? '(stdin, zip archive)' : target}`);
     if (loadedTypesFiles.length > 0) {
       console.log(`Types:  ${loadedTypesFiles.join(', ')}`);
       console.log(`        (${Object.keys(customTypes).join(', ')})`);
@@ -210,31 +530,911 @@ async function validate(opts: Options) {
     console.log('');
   }
 
-  const engine = new SpecEngine();
+  const engine = new SpecEngine({ sampling: !opts.full });
   if (Object.keys(customTypes).length > 0) {
     engine.register(customTypes);
   }
   const result = engine.run(specPath, target);
 
+  // Structural failures are explained by a repair report rather than one issue per entry
+  const { structural } = partitionStructural(result.issues);
+  let repair: RepairReport | null = null;
+  if (structural.length > 0) {
+    const root = engine.parseSpec(specPath);
+    const desc = root?.describe();
+    if (desc?.fsType === 'directory') {
+      repair = repairReport(desc, target);
+    }
+  }
+
+  reportValidation(result, opts, repair);
+}
+
+// Print a validation result (or --json it) and exit with 1 when it failed
+function reportValidation(result: ValidationResult, opts: Options, repair: RepairReport | null = null): never {
+  result = applyIssuePolicy(result, issuePolicy(opts));
   if (opts.json) {
-    console.log(JSON.stringify(result, null, 2));
+    console.log(JSON.stringify(repair ? { ...result, repair } : result, null, 2));
     process.exit(result.ok ? 0 : 1);
   }
 
   if (result.ok) {
     console.log('\x1b[32m✓ Validation passed\x1b[0m');
+    if (result.partial) {
+      console.log('\x1b[33m  (partial: some arrays were sampled; use --full to validate every item)\x1b[0m');
+    }
     process.exit(0);
   } else {
-    console.log('\x1b[31m✗ Validation failed\x1b[0m\n');
+    const onWarnings = !result.issues.some(i => i.level === 'error');
+    console.log(`\x1b[31m✗ Validation failed${onWarnings ? ' on warnings (--fail-on warning)' : ''}\x1b[0m\n`);
+    if (repair) {
+      console.log('Repair report (expected layout vs target):\n');
+      console.log(formatRepairReport(repair));
+      console.log('');
+    }
+    for (const issue of repair ? partitionStructural(result.issues).other : result.issues) {
+      printIssue(issue);
+    }
+    process.exit(1);
+  }
+}
+
+/**
+ * Validate a JSON document read from stdin (target `-`) against a data spec
+ */
+async function validateStdin(opts: Options) {
+  const specPath = path.resolve(process.cwd(), opts.specFile!);
+  const engine = await createEngineWithTypes(opts);
+
+  let result: ValidationResult;
+  try {
+    const schema = engine.compile(specPath);
+    if (schema.describe().fsType !== undefined) {
+      console.error('Error: `-` reads a JSON document for data specs; pipe a file system target with --zip-stdin');
+      process.exit(1);
+    }
+    result = validateJsonText(schema, fs.readFileSync(0, 'utf-8'));
+  } catch (err) {
+    if (!(err instanceof SchemaError)) throw err;
+    result = { ok: false, issues: err.issues };
+  }
+
+  if (!opts.json) {
+    console.log(`Spec:   ${specPath}`);
+    console.log('Target: (stdin)\n');
+  }
+  reportValidation(result, opts);
+}
+
+/**
+ * Read a zip archive from stdin into a temporary directory (removed on exit) and
+ * return the target to validate: the archive itself for ZipFile specs, otherwise
+ * the directory it was extracted to, whose top level is the archive's.
+ */
+async function zipStdinTarget(opts: Options): Promise<string> {
+  const engine = await createEngineWithTypes(opts);
+  const archive = fs.readFileSync(0);
+  const dir = fs.mkdtempSync(path.join(os.tmpdir(), 'specspec-stdin-'));
+  process.on('exit', () => fs.rmSync(dir, { recursive: true, force: true }));
+
+  const root = engine.parseSpec(path.resolve(process.cwd(), opts.specFile!));
+  if (root?.describe().fsType === 'zipFile') {
+    const file = path.join(dir, 'stdin.zip');
+    fs.writeFileSync(file, archive);
+    return file;
+  }
+  // Issues are located under `stdin`, as they would be under a bundle directory's name
+  const bundle = path.join(dir, 'stdin');
+  try {
+    new AdmZip(archive).extractAllTo(bundle, true);
+  } catch (err) {
+    console.error(`Error: Cannot read the zip archive from stdin: ${(err as Error).message}`);
+    process.exit(1);
+  }
+  return bundle;
+}
+
+function printIssue(issue: Issue, indent = '') {
+  const levelColor = issue.level === 'error' ? '\x1b[31m' : issue.level === 'warning' ? '\x1b[33m' : '\x1b[36m';
+  const pathStr = issue.path.length > 0 ? issue.path.join('.') : '(root)';
+  console.log(`${indent}${levelColor}[${issue.level}]\x1b[0m ${issue.code}`);
+  console.log(`${indent}  ${issue.message}`);
+  console.log(`${indent}  at: ${pathStr}\n`);
+}
+
+async function validateMulti(opts: Options) {
+  const specPaths = [opts.specFile!, ...opts.specFiles].map(p => path.resolve(process.cwd(), p));
+  const target = path.resolve(process.cwd(), opts.targetPath!);
+
+  if (!fs.existsSync(target)) {
+    if (opts.json) {
+      console.log(JSON.stringify({ ok: false, results: [], issues: [{ level: 'error', code: 'target.not_found', message: `Target not found: ${target}`, path: [] }] }));
+    } else {
+      console.error(`Error: Target not found: ${target}`);
+    }
+    process.exit(1);
+  }
+
+  const engine = await createEngineWithTypes(opts);
+  const result = applyMultiPolicy(engine.runAll(specPaths, target, opts.stopOnFailure ? { stopOnFailure: true } : {}), opts);
+
+  if (opts.json) {
+    console.log(JSON.stringify(result, null, 2));
+    process.exit(result.ok ? 0 : 1);
+  }
+
+  console.log(`Target: ${target}\n`);
+  result.results.forEach((specResult, index) => {
+    const label = `[${index + 1}/${result.results.length}] ${path.relative(process.cwd(), specResult.spec)}`;
+    if (specResult.skipped) {
+      console.log(`\x1b[33m- ${label}: skipped\x1b[0m\n`);
+    } else if (specResult.ok) {
+      const note = specResult.partial ? ' (partial: some arrays were sampled)' : '';
+      console.log(`\x1b[32m✓ ${label}: passed\x1b[0m${note}\n`);
+    } else {
+      console.log(`\x1b[31m✗ ${label}: failed\x1b[0m\n`);
+    }
+    for (const issue of specResult.issues) {
+      printIssue(issue, '    ');
+    }
+  });
+
+  const failed = result.results.filter(r => !r.ok && !r.skipped).length;
+  if (result.ok) {
+    console.log(`\x1b[32m✓ All ${result.results.length} specs passed\x1b[0m`);
+    process.exit(0);
+  }
+  console.log(`\x1b[31m✗ ${failed} of ${result.results.length} specs failed\x1b[0m`);
+  process.exit(1);
+}
+
+// Spec files a compiled (or failed) spec depends on: itself and the files it imports, transitively.
+// A missing spec file is still watched, so the spec is picked up when it appears.
+function specFiles(specPath: string, schema: Schema | SchemaError): string[] {
+  return [specPath, ...schema.imports];
+}
+
+function compileForWatch(engine: SpecEngine, specPath: string): Schema | SchemaError {
+  try {
+    return engine.compile(specPath);
+  } catch (err) {
+    if (err instanceof SchemaError) return err;
+    throw err;
+  }
+}
+
+function timestamp(): string {
+  return new Date().toTimeString().slice(0, 8);
+}
+
+// One line per issue: `+` new, `-` resolved, `•` initial run; prefix names the spec when there are several
+function issueLine(sign: '+' | '-' | '•', issue: Issue, prefix = ''): string {
+  const color = sign === '+' ? '\x1b[31m' : sign === '-' ? '\x1b[32m' : '';
+  const pathStr = issue.path.length > 0 ? issue.path.join('.') : '(root)';
+  return `  ${color}${sign}${color ? '\x1b[0m' : ''} [${issue.level}] ${prefix}${issue.code} at ${pathStr}: ${issue.message}`;
+}
+
+function issueCounts(issues: Issue[]): string {
+  const errors = issues.filter(i => i.level === 'error').length;
+  const warnings = issues.filter(i => i.level === 'warning').length;
+  return `${errors} error${errors === 1 ? '' : 's'}, ${warnings} warning${warnings === 1 ? '' : 's'}`;
+}
+
+// Watch mode: re-run validation or codegen on changes, printing what changed
+async function watch(opts: Options) {
+  if (opts.doc || opts.proto || opts.describe || opts.compare || opts.differential) {
+    console.error('Error: --watch works with validation and --codegen only');
+    process.exit(1);
+  }
+  if (opts.codegen ? !opts.output : !opts.targetPath) {
+    console.error(opts.codegen ? 'Error: --watch with --codegen needs -o <file>' : 'Error: Missing target path (or use --codegen)');
+    process.exit(1);
+  }
+  const engine = await createEngineWithTypes(opts);
+  if (opts.codegen) {
+    watchCodegen(engine, path.resolve(process.cwd(), opts.specFile!), opts);
+  } else {
+    watchValidation(engine, opts);
+  }
+  console.log('\x1b[2mWatching for changes (Ctrl-C to stop)\x1b[0m');
+}
+
+function watchValidation(engine: SpecEngine, opts: Options) {
+  const specPaths = [opts.specFile!, ...opts.specFiles].map(p => path.resolve(process.cwd(), p));
+  const target = path.resolve(process.cwd(), opts.targetPath!);
+  const label = (spec: string) => specPaths.length > 1 ? `${path.relative(process.cwd(), spec)}: ` : '';
+  let schemas = specPaths.map(spec => ({ spec, schema: compileForWatch(engine, spec) }));
+  let previous: Issue[][] | undefined;
+
+  const run = (reason: string) => {
+    const result = applyMultiPolicy(engine.validateAll(schemas, target, opts.stopOnFailure ? { stopOnFailure: true } : {}), opts);
+    const current = result.results.map(r => r.issues);
+    const status = result.ok ? '\x1b[32m✓ passed\x1b[0m' : '\x1b[31m✗ failed\x1b[0m';
+
+    if (!previous) {
+      console.log(`Spec:   ${specPaths.join(', ')}`);
+      console.log(`Target: ${target}\n`);
+      result.results.forEach(r => r.issues.forEach(issue => console.log(issueLine('•', issue, label(r.spec)))));
+      console.log(`[${timestamp()}] ${status} (${issueCounts(current.flat())})\n`);
+    } else {
+      let added = 0;
+      let resolved = 0;
+      console.log(`[${timestamp()}] ${reason} changed`);
+      result.results.forEach((r, n) => {
+        const diff = diffIssues(previous![n] ?? [], r.issues);
+        diff.resolved.forEach(issue => console.log(issueLine('-', issue, label(r.spec))));
+        diff.added.forEach(issue => console.log(issueLine('+', issue, label(r.spec))));
+        added += diff.added.length;
+        resolved += diff.resolved.length;
+      });
+      const change = added + resolved > 0 ? `${added} new, ${resolved} resolved` : 'no change in issues';
+      console.log(`[${timestamp()}] ${status} (${issueCounts(current.flat())}; ${change})\n`);
+    }
+    previous = current;
+  };
+
+  // Only a spec change recompiles; a target change revalidates with the compiled specs
+  let stopSpecs = () => {};
+  const watchSpecs = () => {
+    stopSpecs();
+    stopSpecs = watchTree(schemas.flatMap(({ spec, schema }) => specFiles(spec, schema)), () => {
+      schemas = specPaths.map(spec => ({ spec, schema: compileForWatch(engine, spec) }));
+      watchSpecs(); // Imports may have changed
+      run('spec');
+    });
+  };
+  watchSpecs();
+  watchTree([target], () => run('target'));
+  run('spec');
+}
+
+function watchCodegen(engine: SpecEngine, specPath: string, opts: Options) {
+  const generatorFactory = getGenerators().get(opts.codegen!);
+  if (!generatorFactory) {
+    console.error(`Error: Unsupported language: ${opts.codegen}`);
+    console.error(`Supported languages: ${getSupportedLanguages().join(', ')}`);
+    process.exit(1);
+  }
+  const outPath = path.resolve(process.cwd(), opts.output!);
+  let previous: Issue[] = [];
+
+  let files = [specPath];
+
+  const generate = () => {
+    const schema = compileForWatch(engine, specPath);
+    files = specFiles(specPath, schema);
+    if (schema instanceof SchemaError) {
+      const diff = diffIssues(previous, schema.issues);
+      console.log(`[${timestamp()}] \x1b[31m✗ spec errors\x1b[0m; ${path.relative(process.cwd(), outPath)} left as it was`);
+      diff.resolved.forEach(issue => console.log(issueLine('-', issue)));
+      diff.added.forEach(issue => console.log(issueLine('+', issue)));
+      previous = schema.issues;
+      return;
+    }
+    const code = generatorFactory(generatorOptions(opts)).generate(schema.describe());
+    const existing = fs.existsSync(outPath) ? fs.readFileSync(outPath, 'utf-8') : undefined;
+    if (code === existing) {
+      console.log(`[${timestamp()}] ${opts.codegen} validator unchanged: ${outPath}`);
+    } else {
+      fs.writeFileSync(outPath, code);
+      console.log(`[${timestamp()}] \x1b[32mGenerated ${opts.codegen} validator:\x1b[0m ${outPath}`);
+    }
+    previous.forEach(issue => console.log(issueLine('-', issue)));
+    previous = [];
+  };
+
+  let stop = () => {};
+  const watchSpec = () => {
+    stop();
+    stop = watchTree(files, () => {
+      generate();
+      watchSpec(); // Imports may have changed
+    });
+  };
+  generate();
+  watchSpec();
+}
+
+// Create an engine with custom types loaded, exiting on load errors; --full turns off ListOf sampling
+async function createEngineWithTypes(opts: Options): Promise<SpecEngine> {
+  const customTypes: Record<string, unknown> = {};
+  for (const typesFile of opts.typesFiles) {
+    const typesPath = path.resolve(process.cwd(), typesFile);
+    try {
+      const module = await import(pathToFileURL(typesPath).href);
+      for (const [key, value] of Object.entries(module)) {
+        if (key !== 'default' && (typeof value === 'function' || (typeof value === 'object' && value !== null))) {
+          customTypes[key] = value;
+        }
+      }
+    } catch (err) {
+      console.error(`Error loading types file ${typesPath}: ${(err as Error).message}`);
+      process.exit(1);
+    }
+  }
+
+  const engine = new SpecEngine({ sampling: !opts.full });
+  if (Object.keys(customTypes).length > 0) {
+    engine.register(customTypes);
+  }
+  return engine;
+}
+
+async function runBatch(opts: Options) {
+  if (!opts.specFile || opts.inputs.length === 0) {
+    console.error('Error: batch needs a spec file and at least one input');
+    process.exit(1);
+  }
+  if (opts.jobs !== undefined && !(Number.isInteger(opts.jobs) && opts.jobs > 0)) {
+    console.error('Error: --jobs must be a positive integer');
+    process.exit(1);
+  }
+  const specPath = path.resolve(process.cwd(), opts.specFile);
+  const inputs = expandInputs(opts.inputs);
+  if (inputs.length === 0) {
+    console.error(`Error: No inputs match ${opts.inputs.join(' ')}`);
+    process.exit(1);
+  }
+
+  let report: BatchReport;
+  try {
+    report = await validateBatch(specPath, inputs, {
+      typesFiles: opts.typesFiles,
+      full: opts.full ?? false,
+      ...(opts.jobs !== undefined ? { jobs: opts.jobs } : {}),
+    });
+  } catch (err) {
+    if (!(err instanceof SchemaError)) throw err;
+    for (const issue of err.issues) {
+      printIssue(issue);
+    }
+    process.exit(1);
+  }
+
+  report = summarizeBatch(report.results.map(r => applyIssuePolicy(r, issuePolicy(opts))));
+
+  if (opts.json) {
+    console.log(JSON.stringify(report, null, 2));
+    process.exit(report.ok ? 0 : 1);
+  }
+
+  console.log(`Spec:   ${specPath}`);
+  console.log(`Inputs: ${report.total}\n`);
+  for (const result of report.results) {
+    if (result.ok && !opts.details) continue;
+    const label = path.relative(process.cwd(), result.input) || '.';
+    const counted = result.issues.length > 0 ? `  ${issueCounts(result.issues)}` : '';
+    console.log(result.ok ? `\x1b[32m✓\x1b[0m ${label}${counted}` : `\x1b[31m✗\x1b[0m ${label}${counted}`);
+    if (opts.details) {
+      for (const issue of result.issues) {
+        printIssue(issue, '    ');
+      }
+    }
+  }
+  if (report.failed > 0 && !opts.details) console.log('');
+
+  const width = String(report.total).length + 2;
+  console.log(`Passed ${String(report.passed).padStart(width)}`);
+  console.log(`Failed ${String(report.failed).padStart(width)}`);
+  if (report.codes.length > 0) {
+    const top = report.codes.slice(0, 10);
+    const codeWidth = Math.max(16, ...top.map(c => c.code.length)) + 2;
+    console.log(`\n${'Top issue codes'.padEnd(codeWidth)}Inputs  Issues`);
+    for (const { code, inputs: count, issues } of top) {
+      console.log(`${code.padEnd(codeWidth)}${String(count).padStart(6)}  ${String(issues).padStart(6)}`);
+    }
+  }
+  console.log('');
+  if (report.ok) {
+    console.log(`\x1b[32m✓ All ${report.total} inputs passed\x1b[0m`);
+    process.exit(0);
+  }
+  console.log(`\x1b[31m✗ ${report.failed} of ${report.total} inputs failed\x1b[0m`);
+  process.exit(1);
+}
+
+async function testSpec(opts: Options) {
+  if (!opts.specFile) {
+    console.error('Error: Missing spec file');
+    process.exit(1);
+  }
+  const specPath = path.resolve(process.cwd(), opts.specFile);
+  const fixturesDir = opts.fixtures
+    ? path.resolve(process.cwd(), opts.fixtures)
+    : path.join(path.dirname(specPath), 'fixtures');
+  if (!fs.existsSync(path.join(fixturesDir, 'valid')) && !fs.existsSync(path.join(fixturesDir, 'invalid'))) {
+    console.error(`Error: No valid/ or invalid/ fixtures in ${fixturesDir}`);
+    process.exit(1);
+  }
+
+  const engine = await createEngineWithTypes(opts);
+  let report: FixtureReport;
+  try {
+    report = runFixtures(engine.compile(specPath), fixturesDir, { coverage: opts.coverage ?? false });
+  } catch (err) {
+    if (!(err instanceof SchemaError)) {
+      console.error(`Error: ${(err as Error).message}`);
+      process.exit(1);
+    }
+    for (const issue of err.issues) {
+      printIssue(issue);
+    }
+    process.exit(1);
+  }
+
+  if (opts.json) {
+    console.log(JSON.stringify(report, null, 2));
+    process.exit(report.ok ? 0 : 1);
+  }
+
+  console.log(`Spec:     ${specPath}`);
+  console.log(`Fixtures: ${fixturesDir}\n`);
+  for (const r of report.results) {
+    const label = path.relative(fixturesDir, r.fixture);
+    if (r.ok) {
+      console.log(`\x1b[32m✓\x1b[0m ${label}`);
+      continue;
+    }
+    const verdict = r.expected === 'valid' ? r.result.ok : !r.result.ok;
+    const reason = verdict ? '' : r.expected === 'valid' ? ': expected to pass' : ': expected to fail, but passed';
+    console.log(`\x1b[31m✗\x1b[0m ${label}${reason}`);
+    for (const e of r.missing) {
+      const where = e.path !== undefined ? ` at ${e.path}` : '';
+      console.log(`    missing    ${e.level ? `[${e.level}] ` : ''}${e.code}${where}`);
+    }
+    for (const issue of r.unexpected) {
+      const pathStr = issue.path.length > 0 ? issue.path.join('.') : '(root)';
+      console.log(`    unexpected [${issue.level}] ${issue.code} at ${pathStr}: ${issue.message}`);
+    }
+  }
+  if (report.coverage) {
+    printCoverage(report.coverage);
+  }
+
+  console.log('');
+  if (report.ok) {
+    console.log(`\x1b[32m✓ All ${report.total} fixtures passed\x1b[0m`);
+    process.exit(0);
+  }
+  console.log(`\x1b[31m✗ ${report.failed} of ${report.total} fixtures failed\x1b[0m`);
+  process.exit(1);
+}
+
+async function scoreSpec(opts: Options) {
+  if (!opts.specFile || opts.inputs.length === 0) {
+    console.error('Error: score needs a spec file and at least one input');
+    process.exit(1);
+  }
+  if (opts.examples !== undefined && !(Number.isInteger(opts.examples) && opts.examples >= 0)) {
+    console.error('Error: --examples must be a non-negative integer');
+    process.exit(1);
+  }
+  const specPath = path.resolve(process.cwd(), opts.specFile);
+  const inputs = expandInputs(opts.inputs);
+  if (inputs.length === 0) {
+    console.error(`Error: No inputs match ${opts.inputs.join(' ')}`);
+    process.exit(1);
+  }
+
+  // Scores count every item
+  const engine = await createEngineWithTypes({ ...opts, full: true });
+  let report: ScoreReport;
+  try {
+    report = scoreCorpus(engine.compile(specPath), inputs, opts.examples !== undefined ? { examples: opts.examples } : {});
+  } catch (err) {
+    if (!(err instanceof SchemaError)) {
+      console.error(`Error: ${(err as Error).message}`);
+      process.exit(1);
+    }
+    for (const issue of err.issues) {
+      printIssue(issue);
+    }
+    process.exit(1);
+  }
+
+  if (opts.json) {
+    console.log(JSON.stringify(report, null, 2));
+    process.exit(0);
+  }
+
+  const percent = (n: number): string => `${(report.total > 0 ? (n / report.total) * 100 : 0).toFixed(1)}%`;
+  console.log(`Spec:   ${specPath}`);
+  console.log(`Corpus: ${report.total} document${report.total === 1 ? '' : 's'}, ${report.passed} pass (${percent(report.passed)})`);
+  if (report.unreadable.length > 0) {
+    console.log(`\x1b[33m${report.unreadable.length} could not be read or parsed\x1b[0m`);
+  }
+
+  const violated = report.rules.filter(r => r.violations > 0);
+  if (violated.length > 0) {
+    const pathWidth = Math.max(...violated.map(r => r.path.length)) + 2;
+    const ruleWidth = Math.max(...violated.map(r => r.rule.length)) + 2;
+    console.log('\nViolations by rule:');
+    for (const rule of violated) {
+      const count = `${rule.violations} (${percent(rule.violations)})`;
+      console.log(`  ${rule.path.padEnd(pathWidth)}${rule.rule.padEnd(ruleWidth)}\x1b[31m${count}\x1b[0m`);
+      for (const example of rule.examples) {
+        console.log(`      ${path.relative(process.cwd(), example) || '.'}`);
+      }
+    }
+  }
+  const clean = report.rules.length - violated.length;
+  console.log(`\n\x1b[32m✓ ${clean} of ${report.rules.length} rules hold for every document\x1b[0m`);
+  process.exit(0);
+}
+
+async function lintFiles(opts: Options) {
+  const specPaths = [opts.specFile, ...opts.inputs, ...opts.specFiles]
+    .filter((p): p is string => p !== undefined)
+    .map(p => path.resolve(process.cwd(), p));
+  if (specPaths.length === 0) {
+    console.error('Error: lint needs at least one spec file');
+    process.exit(1);
+  }
+  const missing = specPaths.find(p => !fs.existsSync(p));
+  if (missing) {
+    console.error(`Error: Spec file not found: ${missing}`);
+    process.exit(1);
+  }
+
+  const engine = await createEngineWithTypes(opts);
+  const linted = lintSpecs(engine, specPaths);
+  const results = linted.results.map(r => applyIssuePolicy(r, issuePolicy(opts)));
+  const ok = results.every(r => r.ok);
+
+  if (opts.json) {
+    console.log(JSON.stringify({ ok, results }, null, 2));
+    process.exit(ok ? 0 : 1);
+  }
+
+  for (const result of results) {
+    const label = path.relative(process.cwd(), result.spec);
+    if (result.issues.length === 0) {
+      console.log(`\x1b[32m✓\x1b[0m ${label}`);
+      continue;
+    }
+    console.log(`${result.ok ? '\x1b[33m•' : '\x1b[31m✗'}\x1b[0m ${label}  ${issueCounts(result.issues)}\n`);
     for (const issue of result.issues) {
-      const levelColor = issue.level === 'error' ? '\x1b[31m' : '\x1b[33m';
+      printIssue(issue, '    ');
+    }
+  }
+
+  const problems = results.reduce((n, r) => n + r.issues.length, 0);
+  console.log('');
+  if (problems === 0) {
+    console.log(`\x1b[32m✓ No problems in ${results.length} spec${results.length === 1 ? '' : 's'}\x1b[0m`);
+  } else {
+    const color = ok ? '\x1b[33m' : '\x1b[31m';
+    console.log(`${color}${ok ? '•' : '✗'} ${problems} problem${problems === 1 ? '' : 's'} (${issueCounts(results.flatMap(r => r.issues))})\x1b[0m`);
+  }
+  process.exit(ok ? 0 : 1);
+}
+
+async function formatFiles(opts: Options) {
+  const specPaths = expandInputs([opts.specFile, ...opts.inputs, ...opts.specFiles].filter((p): p is string => p !== undefined));
+  if (specPaths.length === 0) {
+    console.error('Error: fmt needs at least one spec file');
+    process.exit(1);
+  }
+
+  let changed = 0;
+  let failed = 0;
+  for (const specPath of specPaths) {
+    const label = path.relative(process.cwd(), specPath);
+    let source: string;
+    let formatted: string;
+    try {
+      source = fs.readFileSync(specPath, 'utf-8');
+      formatted = formatSpec(source);
+    } catch (err) {
+      console.error(`\x1b[31m✗\x1b[0m ${label}: ${(err as Error).message}`);
+      failed++;
+      continue;
+    }
+    if (formatted === source) continue;
+    changed++;
+    if (opts.check) {
+      console.log(`\x1b[31m✗\x1b[0m ${label} is not formatted`);
+    } else {
+      fs.writeFileSync(specPath, formatted);
+      console.log(`Formatted ${label}`);
+    }
+  }
+
+  const files = `${specPaths.length} spec file${specPaths.length === 1 ? '' : 's'}`;
+  if (opts.check && changed > 0) {
+    console.log(`\n\x1b[31m✗ ${changed} of ${files} not formatted (run specspec fmt)\x1b[0m`);
+  } else if (failed === 0) {
+    console.log(opts.check || changed === 0 ? `\x1b[32m✓ ${files} formatted\x1b[0m` : `\n\x1b[32m✓ Formatted ${changed} of ${files}\x1b[0m`);
+  }
+  process.exit(failed > 0 || (opts.check && changed > 0) ? 1 : 0);
+}
+
+function printCoverage(coverage: CoverageReport) {
+  const percent = coverage.total > 0 ? Math.floor((coverage.covered / coverage.total) * 100) : 100;
+  console.log(`\nCoverage: ${coverage.covered} of ${coverage.total} rules covered (${percent}%)`);
+  const pathWidth = Math.max(0, ...coverage.rules.map(r => r.path.length)) + 2;
+  const sections = [
+    { status: 'missed', title: 'Never reached by a fixture' },
+    { status: 'unfailed', title: 'Never broken by an invalid fixture' },
+  ] as const;
+  for (const { status, title } of sections) {
+    const rules = coverage.rules.filter(r => r.status === status);
+    if (rules.length === 0) continue;
+    console.log(`\n${title} (${rules.length}):`);
+    for (const rule of rules) {
+      console.log(`  ${rule.path.padEnd(pathWidth)}${rule.rule}`);
+    }
+  }
+}
+
+async function compareTargets(opts: Options) {
+  const specPath = path.resolve(process.cwd(), opts.specFile!);
+  const left = path.resolve(process.cwd(), opts.targetPath!);
+  const right = path.resolve(process.cwd(), opts.compare!);
+
+  for (const p of [specPath, left, right]) {
+    if (!fs.existsSync(p)) {
+      console.error(`Error: Not found: ${p}`);
+      process.exit(1);
+    }
+  }
+
+  const engine = await createEngineWithTypes(opts);
+  let result: CompareResult;
+  try {
+    result = engine.compare(specPath, left, right);
+  } catch (err) {
+    if (!(err instanceof SchemaError)) throw err;
+    for (const issue of err.issues) {
+      printIssue(issue);
+    }
+    process.exit(1);
+  }
+
+  if (opts.json) {
+    console.log(JSON.stringify(result, null, 2));
+    process.exit(result.identical ? 0 : 1);
+  }
+
+  console.log(`Spec:  ${specPath}`);
+  console.log(`Left:  ${left} (${result.left.ok ? 'valid' : `${result.left.issues.length} issues`})`);
+  console.log(`Right: ${right} (${result.right.ok ? 'valid' : `${result.right.issues.length} issues`})`);
+  console.log('');
+
+  if (result.identical) {
+    console.log('\x1b[32m✓ No spec-relevant differences\x1b[0m');
+    process.exit(0);
+  }
+
+  console.log(`\x1b[31m✗ ${result.differences.length} spec-relevant difference(s)\x1b[0m\n`);
+  for (const diff of result.differences) {
+    const pathStr = diff.path.length > 0 ? diff.path.join('.') : '(root)';
+    if (diff.kind === 'added') {
+      console.log(`\x1b[32m+ ${pathStr}\x1b[0m: ${JSON.stringify(diff.right)}`);
+    } else if (diff.kind === 'removed') {
+      console.log(`\x1b[31m- ${pathStr}\x1b[0m: ${JSON.stringify(diff.left)}`);
+    } else {
+      console.log(`\x1b[33m~ ${pathStr}\x1b[0m: ${JSON.stringify(diff.left)} → ${JSON.stringify(diff.right)}`);
+    }
+  }
+  process.exit(1);
+}
+
+async function runDifferential(opts: Options) {
+  const specPath = path.resolve(process.cwd(), opts.specFile!);
+  const corpusDir = path.resolve(process.cwd(), opts.targetPath!);
+
+  for (const p of [specPath, corpusDir]) {
+    if (!fs.existsSync(p)) {
+      console.error(`Error: Not found: ${p}`);
+      process.exit(1);
+    }
+  }
+
+  const engine = await createEngineWithTypes(opts);
+  const left = engineRunner(engine, specPath);
+  let right: Runner;
+  if (/\.m?js$/.test(opts.differential!)) {
+    right = await moduleRunner(opts.differential!, specPath);
+  } else {
+    const [command, ...args] = opts.differential!.split(/\s+/).filter(Boolean);
+    right = commandRunner(command!, args);
+  }
+
+  const report = differential(listCorpus(corpusDir), left, right);
+
+  if (opts.json) {
+    console.log(JSON.stringify(report, null, 2));
+    process.exit(report.verdictChanges === 0 ? 0 : 1);
+  }
+
+  console.log(`Spec:   ${specPath}`);
+  console.log(`Corpus: ${corpusDir} (${report.total} entries)`);
+  console.log(`Other:  ${opts.differential}`);
+  console.log('');
+
+  for (const change of report.changes) {
+    const name = path.relative(corpusDir, change.target);
+    if (change.verdictChanged) {
+      const verdict = (ok: boolean) => ok ? 'valid' : 'invalid';
+      console.log(`\x1b[31m! ${name}\x1b[0m: ${verdict(change.left.ok)} → ${verdict(change.right.ok)}`);
+    } else {
+      console.log(`\x1b[33m~ ${name}\x1b[0m: issues differ`);
+    }
+    for (const issue of change.added) {
+      console.log(`    \x1b[32m+ ${issue.code}\x1b[0m at ${issue.path}`);
+    }
+    for (const issue of change.removed) {
+      console.log(`    \x1b[31m- ${issue.code}\x1b[0m at ${issue.path}`);
+    }
+  }
+
+  if (report.verdictChanges === 0) {
+    const note = report.changes.length > 0 ? ` (${report.changes.length} with different issues)` : '';
+    console.log(`\x1b[32m✓ No verdict changes across ${report.total} entries\x1b[0m${note}`);
+    process.exit(0);
+  }
+  console.log(`\n\x1b[31m✗ ${report.verdictChanges} of ${report.total} entries changed verdict\x1b[0m`);
+  process.exit(1);
+}
+
+async function checkCompat(opts: Options) {
+  if (!opts.specFile || !opts.targetPath) {
+    console.error('Error: compat needs an old and a new spec file');
+    process.exit(1);
+  }
+  const oldPath = path.resolve(process.cwd(), opts.specFile);
+  const newPath = path.resolve(process.cwd(), opts.targetPath);
+
+  for (const p of [oldPath, newPath]) {
+    if (!fs.existsSync(p)) {
+      console.error(`Error: Spec file not found: ${p}`);
+      process.exit(1);
+    }
+  }
+
+  const engine = await createEngineWithTypes(opts);
+  let report: CompatReport;
+  try {
+    report = engine.compat(oldPath, newPath);
+  } catch (err) {
+    if (!(err instanceof SchemaError)) throw err;
+    for (const issue of err.issues) {
+      printIssue(issue);
+    }
+    process.exit(1);
+  }
+
+  if (opts.json) {
+    console.log(JSON.stringify(report, null, 2));
+    process.exit(report.compatible ? 0 : 1);
+  }
+
+  console.log(`Old: ${oldPath}`);
+  console.log(`New: ${newPath}`);
+  console.log('');
+
+  for (const change of report.changes) {
+    const pathStr = change.path.length > 0 ? change.path.join('.') : '(root)';
+    if (change.kind === 'breaking') {
+      console.log(`\x1b[31m! ${pathStr}\x1b[0m: ${change.message} \x1b[2m(${change.code})\x1b[0m`);
+    } else {
+      console.log(`\x1b[32m~ ${pathStr}\x1b[0m: ${change.message} \x1b[2m(${change.code})\x1b[0m`);
+    }
+  }
+  if (report.changes.length > 0) {
+    console.log('');
+  }
+
+  const compatibleCount = report.changes.length - report.breaking;
+  if (report.compatible) {
+    console.log(`\x1b[32m✓ No breaking changes\x1b[0m (${compatibleCount} compatible)`);
+    process.exit(0);
+  }
+  console.log(`\x1b[31m✗ ${report.breaking} breaking change(s)\x1b[0m (${compatibleCount} compatible)`);
+  process.exit(1);
+}
+
+async function generateSample(opts: Options) {
+  if (!opts.specFile) {
+    console.error('Error: generate needs a spec file');
+    process.exit(1);
+  }
+  const specPath = path.resolve(process.cwd(), opts.specFile);
+  if (opts.seed !== undefined && !Number.isInteger(opts.seed)) {
+    console.error('Error: --seed must be an integer');
+    process.exit(1);
+  }
+
+  const engine = await createEngineWithTypes(opts);
+  let schema: Schema;
+  try {
+    schema = engine.compile(specPath);
+  } catch (err) {
+    if (!(err instanceof SchemaError)) throw err;
+    for (const issue of err.issues) {
+      printIssue(issue);
+    }
+    process.exit(1);
+  }
+
+  const options = opts.seed !== undefined ? { seed: opts.seed } : {};
+  const outPath = opts.output ? path.resolve(process.cwd(), opts.output) : undefined;
+  let result: ValidationResult;
+  if (schema.describe().fsType) {
+    if (!outPath) {
+      console.error('Error: generate needs -o <path> for file system specs');
+      process.exit(1);
+    }
+    result = schema.writeSample(outPath, options);
+    console.log(`Generated sample: ${outPath}`);
+  } else {
+    const sample = schema.sample(options);
+    const text = JSON.stringify(sample.value, null, 2);
+    if (outPath) {
+      fs.writeFileSync(outPath, `${text}\n`);
+      console.log(`Generated sample: ${outPath}`);
+    } else {
+      console.log(text);
+    }
+    result = sample;
+  }
+
+  // Samples are best effort; say what the spec still rejects, on stderr so stdout stays JSON
+  if (!result.ok) {
+    console.error('\x1b[33mWarning: the sample does not satisfy every rule:\x1b[0m');
+    for (const issue of result.issues.filter(i => i.level === 'error')) {
       const pathStr = issue.path.length > 0 ? issue.path.join('.') : '(root)';
-      console.log(`${levelColor}[${issue.level}]\x1b[0m ${issue.code}`);
-      console.log(`  ${issue.message}`);
-      console.log(`  at: ${pathStr}\n`);
+      console.error(`  ${issue.code}: ${issue.message} (at ${pathStr})`);
     }
     process.exit(1);
   }
+  process.exit(0);
+}
+
+function importDocument(opts: Options) {
+  if (!opts.specFile) {
+    console.error('Error: openapi needs an OpenAPI document');
+    process.exit(1);
+  }
+  const docPath = path.resolve(process.cwd(), opts.specFile);
+  if (!fs.existsSync(docPath)) {
+    console.error(`Error: OpenAPI document not found: ${docPath}`);
+    process.exit(1);
+  }
+
+  let document: unknown;
+  try {
+    document = JSON.parse(fs.readFileSync(docPath, 'utf-8'));
+  } catch (err) {
+    const hint = /\.ya?ml$/i.test(docPath) ? ' (YAML documents must be converted to JSON first)' : '';
+    console.error(`Error: Cannot parse ${docPath}: ${(err as Error).message}${hint}`);
+    process.exit(1);
+  }
+
+  let result: OpenApiImport;
+  try {
+    result = importOpenApi(document, {
+      operations: opts.operations ?? false,
+      ...(opts.root !== undefined ? { root: opts.root } : {}),
+    });
+  } catch (err) {
+    console.error(`Error: ${(err as Error).message}`);
+    process.exit(1);
+  }
+
+  // Warnings go to stderr so stdout stays a spec file
+  for (const warning of result.warnings) {
+    console.error(`\x1b[33mWarning:\x1b[0m ${warning}`);
+  }
+  if (opts.output) {
+    const outPath = path.resolve(process.cwd(), opts.output);
+    fs.writeFileSync(outPath, result.source);
+    console.log(`Generated spec with ${result.definitions.length} definition(s): ${outPath}`);
+  } else {
+    process.stdout.write(result.source);
+  }
+  process.exit(0);
 }
 
 async function generateDocumentation(opts: Options) {
@@ -297,6 +1497,79 @@ async function generateDocumentation(opts: Options) {
   }
 }
 
+async function exportProtoFile(opts: Options) {
+  const specPath = path.resolve(process.cwd(), opts.specFile!);
+  const engine = await createEngineWithTypes(opts);
+  let schema: Schema;
+  try {
+    schema = engine.compile(specPath);
+  } catch (err) {
+    if (!(err instanceof SchemaError)) throw err;
+    for (const issue of err.issues) {
+      printIssue(issue);
+    }
+    process.exit(1);
+  }
+
+  const { source, notes } = exportProto(schema.describe(), opts.package !== undefined ? { package: opts.package } : {});
+
+  // Listed on stderr so stdout stays a .proto file
+  for (const note of notes) {
+    console.error(`\x1b[33mNot representable:\x1b[0m ${note.path.length > 0 ? note.path.join('.') : '(root)'}: ${note.message}`);
+  }
+  if (opts.output) {
+    const outPath = path.resolve(process.cwd(), opts.output);
+    fs.writeFileSync(outPath, source);
+    console.log(`Generated proto: ${outPath}`);
+  } else {
+    process.stdout.write(source);
+  }
+}
+
+async function writeDescription(opts: Options) {
+  const specPath = path.resolve(process.cwd(), opts.specFile!);
+  const engine = await createEngineWithTypes(opts);
+  let schema: Schema;
+  try {
+    schema = engine.compile(specPath);
+  } catch (err) {
+    if (!(err instanceof SchemaError)) throw err;
+    for (const issue of err.issues) {
+      printIssue(issue);
+    }
+    process.exit(1);
+  }
+
+  const json = JSON.stringify(portableDescription(schema.describe()), null, 2) + '\n';
+  if (opts.output) {
+    const outPath = path.resolve(process.cwd(), opts.output);
+    fs.writeFileSync(outPath, json);
+    console.log(`Generated description: ${outPath}`);
+  } else {
+    process.stdout.write(json);
+  }
+}
+
+async function startLanguageServer(opts: Options) {
+  const engine = await createEngineWithTypes(opts);
+  // stdout carries the protocol; send the console.log() of spec files to stderr
+  console.log = console.error;
+  serveLanguageServer(new SpecLanguageService(engine));
+}
+
+function generatorOptions(opts: Options): CodegenOptions {
+  return {
+    failFast: opts.failFast ?? false,
+    types: opts.typed ?? false,
+    externalPrelude: opts.externalPrelude ?? false,
+    slim: opts.slim ?? false,
+    ...(opts.cli !== undefined ? { cli: opts.cli } : {}),
+    propertyTests: opts.propertyTests ?? false,
+    ...(opts.package !== undefined ? { package: opts.package } : {}),
+    schedule: opts.schedule ?? true,
+  };
+}
+
 async function generateCode(opts: Options) {
   const specPath = path.resolve(process.cwd(), opts.specFile!);
   const lang = opts.codegen!;
@@ -316,6 +1589,12 @@ async function generateCode(opts: Options) {
     process.exit(1);
   }
 
+  // The harness includes the generated file, so it needs a path to it
+  if (opts.fuzz !== undefined && !opts.output) {
+    console.error('Error: --fuzz needs -o <file>');
+    process.exit(1);
+  }
+
   // Load custom types if specified
   const customTypes: Record<string, unknown> = {};
   for (const typesFile of opts.typesFiles) {
@@ -358,7 +1637,7 @@ async function generateCode(opts: Options) {
 
   // Generate code using registered generator
   const generatorFactory = generators.get(lang)!;
-  const generator = generatorFactory();
+  const generator = generatorFactory(generatorOptions(opts));
   const code = generator.generate(desc);
 
   // Output
@@ -366,14 +1645,42 @@ async function generateCode(opts: Options) {
     const outPath = path.resolve(process.cwd(), opts.output);
     fs.writeFileSync(outPath, code);
     console.log(`Generated ${lang} validator: ${outPath}`);
+
+    if (opts.fuzz !== undefined) {
+      const fuzzDir = path.resolve(process.cwd(), opts.fuzz);
+      for (const [file, content] of generator.generateFuzzTarget(desc, path.relative(fuzzDir, outPath))) {
+        const filePath = path.join(fuzzDir, file);
+        fs.mkdirSync(path.dirname(filePath), { recursive: true });
+        fs.writeFileSync(filePath, content);
+      }
+      console.log(`Generated fuzz target: ${fuzzDir}`);
+    }
   } else {
     console.log(code);
   }
 }
 
+// `specspec codegen`: generate every [[codegen]] target of the project configuration
+async function generateConfigured(opts: Options, config: ProjectConfig | undefined) {
+  if (!config?.codegen) {
+    console.error(`Error: No [[codegen]] targets ${config ? `in ${config.file}` : `(no ${CONFIG_FILE} found)`}`);
+    process.exit(1);
+  }
+  if (!opts.specFile) {
+    console.error(`Error: Missing spec file (give one, or set spec in ${config.file})`);
+    process.exit(1);
+  }
+  for (const { lang, output, ...flags } of config.codegen) {
+    fs.mkdirSync(path.dirname(output), { recursive: true });
+    await generateCode({ ...opts, ...flags, codegen: lang, output });
+  }
+}
+
 // Main
 async function main() {
-  if (args.length === 0 || args.includes('--help') || args.includes('-h')) {
+  const wantsHelp = args.includes('--help') || args.includes('-h');
+  const config = wantsHelp ? undefined : readProjectConfig(args);
+  if (wantsHelp || (args.length === 0 && config?.spec === undefined)) {
     console.log(help);
     console.log(`Supported codegen languages: ${getSupportedLanguages().join(', ')}\n`);
     process.exit(0);
@@ -389,7 +1696,60 @@ async function main() {
     process.exit(0);
   }
 
-  const opts = parseArgs(args);
+  if (args[0] === 'batch') {
+    await runBatch(withConfig(parseArgs(args.slice(1)), config));
+    return;
+  }
+
+  if (args[0] === 'test') {
+    await testSpec(withConfigSpec(withConfig(parseArgs(args.slice(1)), config), config, false));
+    return;
+  }
+
+  if (args[0] === 'score') {
+    await scoreSpec(withConfig(parseArgs(args.slice(1)), config));
+    return;
+  }
+
+  if (args[0] === 'lint') {
+    await lintFiles(withConfigSpec(withConfig(parseArgs(args.slice(1)), config), config, false));
+    return;
+  }
+
+  if (args[0] === 'fmt') {
+    await formatFiles(withConfigSpec(withConfig(parseArgs(args.slice(1)), config), config, false));
+    return;
+  }
+
+  if (args[0] === 'compat') {
+    await checkCompat(withConfig(parseArgs(args.slice(1)), config));
+    return;
+  }
+
+  if (args[0] === 'generate') {
+    await generateSample(withConfigSpec(withConfig(parseArgs(args.slice(1)), config), config, false));
+    return;
+  }
+
+  if (args[0] === 'codegen') {
+    await generateConfigured(withConfigSpec(withConfig(parseArgs(args.slice(1)), config), config, false), config);
+    process.exit(0);
+  }
+
+  if (args[0] === 'openapi') {
+    importDocument(parseArgs(args.slice(1)));
+    return;
+  }
+
+  if (args[0] === 'lsp') {
+    await startLanguageServer(withConfig(parseArgs(args.slice(1)), config));
+    return;
+  }
+
+  // `validate` names the default mode explicitly
+  const parsed = withConfig(parseArgs(args[0] === 'validate' ? args.slice(1) : args), config);
+  const validating = !parsed.doc && !parsed.proto && !parsed.describe && !parsed.codegen;
+  const opts = withConfigSpec(parsed, config, validating);
 
   if (!opts.specFile) {
     console.error('Error: Missing spec file');
@@ -397,12 +1757,51 @@ async function main() {
     process.exit(1);
   }
 
+  // Stdin target: a JSON document (`-`) or a zip archive (--zip-stdin)
+  if (opts.zipStdin || opts.targetPath === '-') {
+    if (opts.watch || opts.compare || opts.differential) {
+      console.error('Error: --watch, --compare and --differential need a target path, not stdin');
+      process.exit(1);
+    }
+    if (opts.zipStdin) {
+      if (opts.targetPath !== undefined && opts.targetPath !== '-') {
+        console.error('Error: --zip-stdin reads the target from stdin; leave out the target path');
+        process.exit(1);
+      }
+      opts.targetPath = await zipStdinTarget(opts);
+    } else if (opts.specFiles.length > 0) {
+      console.error('Error: `-` validates against one data spec; --spec needs a target path or --zip-stdin');
+      process.exit(1);
+    } else {
+      await validateStdin(opts);
+      return;
+    }
+  }
+
+  // Watch mode
+  if (opts.watch) {
+    await watch(opts);
+    return;
+  }
+
   // Documentation mode
   if (opts.doc) {
     await generateDocumentation(opts);
     process.exit(0);
   }
 
+  // Protobuf export mode
+  if (opts.proto) {
+    await exportProtoFile(opts);
+    process.exit(0);
+  }
+
+  // Portable description mode
+  if (opts.describe) {
+    await writeDescription(opts);
+    process.exit(0);
+  }
+
   // Code generation mode
   if (opts.codegen) {
     await generateCode(opts);
@@ -416,6 +1815,24 @@ async function main() {
     process.exit(1);
   }
 
+  // Differential mode
+  if (opts.differential) {
+    await runDifferential(opts);
+    return;
+  }
+
+  // Comparison mode
+  if (opts.compare) {
+    await compareTargets(opts);
+    return;
+  }
+
+  // Multi-spec mode
+  if (opts.specFiles.length > 0) {
+    await validateMulti(opts);
+    return;
+  }
+
   await validate(opts);
 }
 
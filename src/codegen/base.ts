@@ -12,6 +12,18 @@ export interface LanguageConfig {
   preludeFile: string;
 }
 
+/**
+ * Convert a JS regex literal as written in descriptions (`/src/flags`) to a bare pattern
+ * for non-JS regex engines; i/m/s flags become an inline group such as `(?i)`.
+ * Bare patterns are returned unchanged.
+ */
+export function regexSource(pattern: string): string {
+  const literal = pattern.match(/^\/(.*)\/([a-z]*)$/s);
+  if (!literal) return pattern;
+  const flags = literal[2]!.replace(/[^ims]/g, '');
+  return flags ? `(?${flags})${literal[1]}` : literal[1]!;
+}
+
 /**
  * Parse constraint string to extract values
  */
@@ -70,14 +82,49 @@ export function extractNumberConstraints(constraints: string[] | undefined): {
   return result;
 }
 
+/**
+ * Extract decimal constraints from TypeDescription
+ * Bounds are kept as strings so they stay exact in generated code
+ */
+export function extractDecimalConstraints(constraints: string[] | undefined): {
+  min?: string;
+  max?: string;
+  precision?: number;
+  scale?: number;
+} {
+  const result: { min?: string; max?: string; precision?: number; scale?: number } = {};
+
+  for (const c of constraints ?? []) {
+    let val: string | null;
+    if ((val = parseConstraint(c, 'precision '))) {
+      result.precision = parseInt(val.split(' ')[0]!, 10);
+    } else if ((val = parseConstraint(c, 'scale '))) {
+      result.scale = parseInt(val, 10);
+    } else if ((val = parseConstraint(c, 'minimum '))) {
+      result.min = val;
+    } else if ((val = parseConstraint(c, 'maximum '))) {
+      result.max = val;
+    }
+  }
+
+  return result;
+}
+
 /**
  * Extract list constraints from TypeDescription
  */
 export function extractListConstraints(constraints: string[] | undefined): {
   minItems?: number;
   maxItems?: number;
+  unique?: boolean;
+  uniqueBy?: string;
+  sorted?: 'asc' | 'desc';
+  sortedBy?: string;
 } {
-  const result: { minItems?: number; maxItems?: number } = {};
+  const result: {
+    minItems?: number; maxItems?: number; unique?: boolean; uniqueBy?: string;
+    sorted?: 'asc' | 'desc'; sortedBy?: string;
+  } = {};
 
   for (const c of constraints ?? []) {
     let val: string | null;
@@ -85,6 +132,16 @@ export function extractListConstraints(constraints: string[] | undefined): {
       result.minItems = parseInt(val.split(' ')[0]!, 10);
     } else if ((val = parseConstraint(c, 'maximum ')) && c.includes('items')) {
       result.maxItems = parseInt(val.split(' ')[0]!, 10);
+    } else if (c === 'unique items') {
+      result.unique = true;
+    } else if ((val = parseConstraint(c, 'unique by '))) {
+      result.uniqueBy = val.replace(/^`|`$/g, '');
+    } else if ((val = parseConstraint(c, 'sorted '))) {
+      const match = val.match(/^(ascending|descending)(?: by `(.+)`)?$/);
+      if (match) {
+        result.sorted = match[1] === 'descending' ? 'desc' : 'asc';
+        if (match[2] !== undefined) result.sortedBy = match[2];
+      }
     }
   }
 
@@ -116,12 +173,226 @@ export function extractBundleAccept(accept: TypeDescription[] | undefined): {
   return { acceptDir, acceptZip, zipExt };
 }
 
+/**
+ * Options shared by all code generators
+ */
+export interface CodegenOptions {
+  /** Order sibling checks cheapest-first (default: true) */
+  schedule?: boolean;
+  /** Stop checking siblings once one of them has reported an issue */
+  failFast?: boolean;
+  /** Also emit typed data structures and a parse entry point, for data specs (Rust) */
+  types?: boolean;
+  /**
+   * Depend on the published prelude package instead of embedding the prelude text,
+   * so prelude fixes ship without regenerating (Rust: the `specspec-prelude` crate)
+   */
+  externalPrelude?: boolean;
+  /** Leave filesystem and archive support out of the prelude, for data specs (Rust) */
+  slim?: boolean;
+  /** Emit a command line tool with this name (`<name> validate <path> [--format json]`) instead of the plain entry point (Rust) */
+  cli?: string;
+  /** Also emit a proptest module checking the validator at its constraint boundaries, for data specs (Rust) */
+  propertyTests?: boolean;
+  /**
+   * Package of the generated file. Go: default `main`, which also gets a command line entry point;
+   * Kotlin: default none, and only then a `main` function
+   */
+  package?: string;
+}
+
+/**
+ * Rough relative costs used by estimateCost
+ */
+const COST = {
+  scalar: 1,
+  number: 2,
+  decimal: 4,
+  field: 1,
+  list: 4,
+  listItems: 8, // items are unknown at codegen time, assume a handful
+  fsStat: 10,
+  fsRead: 100, // reads and parses the whole file, grows with file size
+};
+
+/**
+ * Estimate the cost of matching a regular expression.
+ * Quantifiers add work, nested quantifiers risk heavy backtracking.
+ */
+export function estimateRegexCost(pattern: string): number {
+  const quantifiers = pattern.match(/[*+?]|\{\d+(,\d*)?\}/g)?.length ?? 0;
+  const nested = /\([^)]*[*+][^)]*\)[*+{]/.test(pattern);
+  return 2 + Math.ceil(pattern.length / 8) + quantifiers * 4 + (nested ? 50 : 0);
+}
+
+/**
+ * Estimate the relative runtime cost of the checks generated for a TypeDescription
+ */
+export function estimateCost(desc: TypeDescription): number {
+  switch (desc.fsType) {
+    case 'jsonFile':
+      return COST.fsRead + (desc.children ? estimateChildrenCost(desc.children) : 0);
+    case 'file':
+      return desc.checksum ? COST.fsStat + COST.fsRead : COST.fsStat;
+    case 'directory':
+      return COST.fsStat;
+    case 'bundle':
+      return COST.fsStat + (desc.children ? estimateChildrenCost(desc.children) : 0);
+  }
+
+  switch (desc.name) {
+    case 'Literal':
+    case 'Boolean':
+      return COST.scalar;
+    case 'Number':
+      return COST.number;
+    case 'Decimal':
+      return COST.decimal;
+    case 'String': {
+      const { pattern } = extractStringConstraints(desc.constraints);
+      return COST.number + (pattern ? estimateRegexCost(pattern) : 0);
+    }
+    case 'Pattern': {
+      const pattern = desc.constraints?.[0]?.replace('matches ', '').replace(/^`|`$/g, '') ?? '';
+      return estimateRegexCost(pattern);
+    }
+    case 'OneOf':
+      return (desc.oneOf ?? []).reduce((sum, opt) => sum + estimateCost(opt), 0);
+    case 'AllOf':
+      return (desc.allOf ?? []).reduce((sum, part) => sum + estimateCost(part), 0);
+    case 'Not':
+      return desc.not ? estimateCost(desc.not) : COST.scalar;
+    case 'TaggedUnion':
+      // Only the selected variant runs
+      return COST.field + Math.max(0, ...Object.values(desc.variants ?? {}).map(estimateCost));
+    case 'ListOf':
+      return COST.list + COST.listItems * (desc.itemType ? estimateCost(desc.itemType) : COST.scalar);
+    case 'Field': {
+      let value = 0;
+      if (desc.oneOf) {
+        value = desc.oneOf.reduce((sum, opt) => sum + estimateCost(opt), 0);
+      } else if (desc.allOf) {
+        value = desc.allOf.reduce((sum, part) => sum + estimateCost(part), 0);
+      } else if (desc.not) {
+        value = estimateCost(desc.not);
+      } else if (desc.variants) {
+        value = estimateCost({ name: 'TaggedUnion', variants: desc.variants });
+      } else if (desc.itemType) {
+        value = estimateCost({ name: 'ListOf', itemType: desc.itemType });
+      } else if (desc.children) {
+        value = estimateChildrenCost(desc.children);
+      } else if (desc.summary) {
+        value = estimateCost({ name: desc.summary, constraints: desc.constraints });
+      }
+      return COST.field + value;
+    }
+    case 'Object':
+      return COST.scalar + (desc.children ? estimateChildrenCost(desc.children) : 0);
+    case 'When':
+      // Reads the file, then runs one branch
+      return COST.fsRead + Math.max(desc.then ? estimateCost(desc.then) : 0, desc.else ? estimateCost(desc.else) : 0);
+  }
+
+  return COST.scalar;
+}
+
+function estimateChildrenCost(
+  children: { required?: TypeDescription[] | undefined; optional?: TypeDescription[] | undefined }
+): number {
+  return [...(children.required ?? []), ...(children.optional ?? [])]
+    .reduce((sum, child) => sum + estimateCost(child), 0);
+}
+
+/**
+ * Whether the description or any description nested in it matches
+ */
+export function someDescription(desc: TypeDescription, test: (d: TypeDescription) => boolean): boolean {
+  if (test(desc)) return true;
+  const nested = [
+    ...(desc.children?.required ?? []), ...(desc.children?.optional ?? []), ...(desc.oneOf ?? []), ...(desc.allOf ?? []),
+    ...Object.values(desc.variants ?? {}), ...(desc.accept ?? []), desc.not, desc.itemType,
+    desc.condition?.is, desc.then, desc.else,
+  ];
+  return nested.some(d => d !== undefined && someDescription(d, test));
+}
+
+/**
+ * Children of a When branch: the entries of an object spec, or a single entry
+ */
+export function whenBranch(
+  desc: TypeDescription
+): { required?: TypeDescription[] | undefined; optional?: TypeDescription[] | undefined } {
+  return desc.name === 'Object' && desc.children ? desc.children : { required: [desc] };
+}
+
+/**
+ * Whether the description is a use of a named type by name only (recursive uses)
+ */
+export function isRef(d: TypeDescription): boolean {
+  return d.name === 'Ref' || d.summary === 'Ref';
+}
+
+/**
+ * The value part of a Field description (undefined when the field accepts any value)
+ */
+export function fieldValue(d: TypeDescription): TypeDescription | undefined {
+  if (d.summary === undefined) {
+    return undefined;
+  }
+  return {
+    name: d.summary,
+    description: d.valueDescription,
+    constraints: d.constraints,
+    nullable: d.nullable,
+    children: d.children,
+    oneOf: d.oneOf,
+    allOf: d.allOf,
+    not: d.not,
+    discriminator: d.discriminator,
+    variants: d.variants,
+    itemType: d.itemType,
+    ref: d.ref,
+    maxDepth: d.maxDepth,
+    deprecated: d.valueDeprecated,
+    spec: d.spec,
+  };
+}
+
+/**
+ * The value of a Literal description (undefined when it is not JSON)
+ */
+export function parseLiteral(d: TypeDescription): unknown {
+  try {
+    return JSON.parse(d.constraints?.[0]?.replace(/^equals /, '') ?? 'null');
+  } catch {
+    return undefined;
+  }
+}
+
+/**
+ * Split a named type's name into lowercase words ("HTTPHeader-v2" -> ["http", "header", "v2"])
+ */
+export function nameWords(name: string): string[] {
+  const words = name
+    .replace(/([a-z0-9])([A-Z])/g, '$1 $2')
+    .replace(/([A-Z]+)([A-Z][a-z])/g, '$1 $2')
+    .split(/[^A-Za-z0-9]+/)
+    .filter(w => w.length > 0)
+    .map(w => w.toLowerCase());
+  return words.length > 0 ? words : ['type'];
+}
+
 /**
  * Abstract code generator base class
  */
 export abstract class CodeGenerator {
   abstract readonly config: LanguageConfig;
 
+  /** Named types (Def) emitted for the current generate() call, by name */
+  private definitions = new Map<string, { ident: string; expr?: string; maxDepth?: number; description?: string }>();
+
+  constructor(protected readonly options: CodegenOptions = {}) {}
+
   /**
    * Escape string for target language
    */
@@ -157,9 +428,26 @@ export abstract class CodeGenerator {
   abstract generateFSChildExpr(desc: TypeDescription): string;
 
   /**
-   * Load prelude content
+   * Load prelude content for the spec being generated
+   */
+  abstract loadPrelude(desc: TypeDescription): string;
+
+  /**
+   * Imports of the published prelude package, used instead of the prelude text
+   * with `externalPrelude`; languages without a published prelude reject it
    */
-  abstract loadPrelude(): string;
+  protected preludeImport(): string {
+    throw new Error(`No published prelude for ${this.config.name}; generate with the embedded prelude`);
+  }
+
+  /**
+   * Files of a fuzzing harness for the generated validator, by path relative to the
+   * harness directory; `validatorPath` is the generated file relative to that directory.
+   * Languages without a fuzzing harness reject it.
+   */
+  generateFuzzTarget(_desc: TypeDescription, _validatorPath: string): Map<string, string> {
+    throw new Error(`No fuzz target for ${this.config.name}`);
+  }
 
   /**
    * Generate main entry point code
@@ -170,13 +458,29 @@ export abstract class CodeGenerator {
    * Generate complete validator code
    */
   generate(desc: TypeDescription): string {
-    const prelude = this.loadPrelude();
+    if (this.options.slim && desc.fsType !== undefined) {
+      throw new Error(`A slim validator has no filesystem support, but the spec checks a ${desc.fsType}`);
+    }
+    if (someDescription(desc, d => d.meta !== undefined && d.fsType !== undefined)) {
+      throw new Error('Generated validators only support Meta() on data, not on files; validate this spec with specspec');
+    }
+    if (someDescription(desc, d => d.phase !== undefined && d.phase > 0)) {
+      throw new Error('Generated validators run every check in one pass and do not support Phase(); validate this spec with specspec');
+    }
+    const prelude = this.options.externalPrelude ? this.preludeImport() : this.loadPrelude(desc);
     const isBundle = desc.fsType === 'bundle';
+    this.definitions = new Map();
 
     const rootExpr = isBundle
       ? this.generateBundleExpr(desc)
       : this.generateDataValidatorExpr(desc);
 
+    for (const [name, def] of this.definitions) {
+      if (def.expr === undefined) {
+        throw new Error(`Named type "${name}" is referenced but never defined`);
+      }
+    }
+
     const mainCode = this.generateMainCode(isBundle);
     const validatorDecl = this.generateValidatorDecl(rootExpr, isBundle);
 
@@ -197,17 +501,122 @@ export abstract class CodeGenerator {
 
     lines.push(this.commentLine('='.repeat(60)));
     lines.push('');
+    for (const [name, { ident, expr, maxDepth, description }] of this.definitions) {
+      lines.push(this.commentLine(name));
+      if (description) {
+        lines.push(...this.docComment(description));
+      }
+      lines.push(this.generateDefinition(name, ident, expr!, maxDepth));
+      lines.push('');
+    }
+    if (this.options.types && desc.fsType === undefined) {
+      for (const decl of this.generateTypeDecls(desc)) {
+        lines.push(decl);
+        lines.push('');
+      }
+    }
     lines.push(validatorDecl);
     lines.push(mainCode);
+    if (this.options.propertyTests && desc.fsType === undefined) {
+      for (const decl of this.generateTestDecls(desc)) {
+        lines.push('');
+        lines.push(decl);
+      }
+    }
 
     return lines.join('\n');
   }
 
+  /**
+   * Emit a named type once as its own validator function and return a reference to it.
+   * Every use of the same name must produce the same validator. A null expr only
+   * refers to the type (a recursive use inside its own body); the function may be
+   * defined after it is referenced. The type's description documents the function.
+   */
+  protected namedValidator(name: string, expr: string | null, maxDepth?: number, description?: string): string {
+    let def = this.definitions.get(name);
+    if (!def) {
+      const base = this.definitionIdent(nameWords(name));
+      const taken = new Set(Array.from(this.definitions.values(), d => d.ident));
+      let ident = base;
+      for (let n = 2; taken.has(ident); n++) {
+        ident = `${base}${n}`;
+      }
+      def = { ident };
+      this.definitions.set(name, def);
+    }
+
+    if (expr !== null) {
+      if (def.expr === undefined) {
+        def.expr = expr;
+        if (maxDepth !== undefined) def.maxDepth = maxDepth;
+        if (description !== undefined) def.description = description;
+      } else if (def.expr !== expr) {
+        throw new Error(`Named type "${name}" is defined more than once with different shapes`);
+      }
+    }
+    return def.ident;
+  }
+
+  /**
+   * Flatten required and optional children into the order their checks
+   * should run: cheapest first unless scheduling is disabled.
+   */
+  protected scheduleChildren(
+    children: { required?: TypeDescription[] | undefined; optional?: TypeDescription[] | undefined }
+  ): { desc: TypeDescription; optional: boolean }[] {
+    const entries = [
+      ...(children.required ?? []).map(desc => ({ desc, optional: false })),
+      ...(children.optional ?? []).map(desc => ({ desc, optional: true })),
+    ];
+    if (this.options.schedule === false) {
+      return entries;
+    }
+    // Array.prototype.sort is stable, so equal-cost checks keep spec order
+    return entries
+      .map(entry => ({ entry, cost: estimateCost(entry.desc) }))
+      .sort((a, b) => a.cost - b.cost)
+      .map(({ entry }) => entry);
+  }
+
+  /**
+   * Typed data structures for a data spec and the entry point that validates into them.
+   * Only called with the `types` option; languages without typed output emit nothing.
+   */
+  protected generateTypeDecls(_desc: TypeDescription): string[] {
+    return [];
+  }
+
+  /**
+   * Generated tests for a data spec's validator. Only called with the `propertyTests`
+   * option; languages without generated tests emit nothing.
+   */
+  protected generateTestDecls(_desc: TypeDescription): string[] {
+    return [];
+  }
+
   /**
    * Generate validator declaration
    */
   protected abstract generateValidatorDecl(rootExpr: string, isBundle: boolean): string;
 
+  /**
+   * Function name for a named type, from its name's lowercase words
+   */
+  protected abstract definitionIdent(words: string[]): string;
+
+  /**
+   * Doc comment lines for a generated declaration (plain comments unless the language has doc comments)
+   */
+  protected docComment(text: string): string[] {
+    return text.split('\n').map(line => this.commentLine(line));
+  }
+
+  /**
+   * Declare a named type's validator function, guarded against nesting deeper than maxDepth
+   */
+  protected abstract generateDefinition(name: string, ident: string, expr: string, maxDepth?: number): string;
+
   /**
    * Generate a comment line
    */
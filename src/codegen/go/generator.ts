@@ -0,0 +1,440 @@
+// src/codegen/go/generator.ts
+// Go code generator implementation
+
+import fs from 'node:fs';
+import path from 'node:path';
+import { fileURLToPath } from 'node:url';
+import type { TypeDescription } from '../../base.js';
+import {
+  CodeGenerator,
+  type LanguageConfig,
+  type CodegenOptions,
+  extractStringConstraints,
+  regexSource,
+  extractNumberConstraints,
+  extractDecimalConstraints,
+  extractListConstraints,
+  extractBundleAccept,
+  whenBranch,
+} from '../base.js';
+
+const __dirname = path.dirname(fileURLToPath(import.meta.url));
+
+/** Signature of a data validator closure */
+const FN = 'func(v any, p []string, i *Issues)';
+
+/**
+ * Go code generator
+ */
+export class GoGenerator extends CodeGenerator {
+  readonly config: LanguageConfig = {
+    name: 'go',
+    fileExt: '.go',
+    preludeFile: 'prelude.go',
+  };
+
+  /** Package clause of the generated file; only `main` gets a command line entry point */
+  private get packageName(): string {
+    const name = this.options.package ?? 'main';
+    if (!/^[A-Za-z_][A-Za-z0-9_]*$/.test(name)) {
+      throw new Error(`Invalid Go package name: ${name}`);
+    }
+    return name;
+  }
+
+  escapeString(s: string): string {
+    // JSON string escapes are all valid in Go interpreted string literals
+    return JSON.stringify(s);
+  }
+
+  loadPrelude(): string {
+    const preludePath = path.join(__dirname, this.config.preludeFile);
+    return fs.readFileSync(preludePath, 'utf-8').replace(/^package main$/m, `package ${this.packageName}`);
+  }
+
+  generateDataValidatorExpr(desc: TypeDescription): string {
+    const name = desc.name;
+
+    // Metadata attached to every issue of the type (fields wrap their value validator below)
+    if (desc.meta && name !== 'Field') {
+      const innerExpr = this.generateDataValidatorExpr({ ...desc, meta: undefined });
+      return `${FN} { validateMeta(v, p, i, ${innerExpr}, ${this.escapeString(JSON.stringify(desc.meta))}) }`;
+    }
+
+    // Nullable (fields wrap their value validator below)
+    if (desc.nullable && name !== 'Field') {
+      const innerExpr = this.generateDataValidatorExpr({ ...desc, nullable: false });
+      return `${FN} { validateNullable(v, p, i, ${innerExpr}) }`;
+    }
+
+    // Spec-defined code/message: report the inner failure as one issue
+    if (desc.customIssue && name !== 'Field') {
+      const innerExpr = this.generateDataValidatorExpr({ ...desc, customIssue: undefined });
+      return `${FN} { validateMessage(v, p, i, ${innerExpr}, ${this.messageArgs(desc.customIssue)}) }`;
+    }
+
+    // Deprecated type: warn, then validate as usual
+    if (desc.deprecated && name !== 'Field') {
+      const innerExpr = this.generateDataValidatorExpr({ ...desc, deprecated: undefined });
+      return `${FN} { validateDeprecated(v, p, i, ${innerExpr}, "type.deprecated", ${this.escapeString(desc.deprecated)}) }`;
+    }
+
+    // Named type: emitted once as a function, referenced by name
+    if (name === 'Ref' && desc.ref !== undefined) {
+      return this.namedValidator(desc.ref, null);
+    }
+    if (desc.ref !== undefined && name !== 'Field') {
+      const bodyExpr = this.generateDataValidatorExpr({ ...desc, ref: undefined, maxDepth: undefined });
+      return this.namedValidator(desc.ref, bodyExpr, desc.maxDepth, desc.description);
+    }
+
+    // Literal value, compared as JSON
+    if (name === 'Literal') {
+      const val = desc.constraints?.[0]?.replace('equals ', '') ?? 'null';
+      return `${FN} { validateLiteral(v, p, i, ${this.escapeString(val)}) }`;
+    }
+
+    // Pattern
+    if (name === 'Pattern') {
+      const pattern = regexSource(desc.constraints?.[0]?.replace('matches ', '').replace(/^`|`$/g, '') ?? '');
+      return `${FN} { validatePattern(v, p, i, ${this.escapeString(pattern)}) }`;
+    }
+
+    // String
+    if (name === 'String') {
+      const opts = extractStringConstraints(desc.constraints);
+      const fields: string[] = [];
+      if (opts.minLength !== undefined) fields.push(`minLength: some(${opts.minLength})`);
+      if (opts.maxLength !== undefined) fields.push(`maxLength: some(${opts.maxLength})`);
+      if (opts.pattern) fields.push(`pattern: some(${this.escapeString(regexSource(opts.pattern))})`);
+      return `${FN} { validateStr(v, p, i, strOpts{${fields.join(', ')}}) }`;
+    }
+
+    // Number
+    if (name === 'Number') {
+      const opts = extractNumberConstraints(desc.constraints);
+      const fields: string[] = [];
+      if (opts.min !== undefined) fields.push(`min: some[float64](${opts.min})`);
+      if (opts.max !== undefined) fields.push(`max: some[float64](${opts.max})`);
+      if (opts.integer) fields.push('integer: true');
+      return `${FN} { validateNum(v, p, i, numOpts{${fields.join(', ')}}) }`;
+    }
+
+    // Decimal
+    if (name === 'Decimal') {
+      const opts = extractDecimalConstraints(desc.constraints);
+      const fields: string[] = [];
+      if (opts.min !== undefined) fields.push(`min: some(${this.escapeString(opts.min)})`);
+      if (opts.max !== undefined) fields.push(`max: some(${this.escapeString(opts.max)})`);
+      if (opts.precision !== undefined) fields.push(`precision: some(${opts.precision})`);
+      if (opts.scale !== undefined) fields.push(`scale: some(${opts.scale})`);
+      return `${FN} { validateDecimal(v, p, i, decimalOpts{${fields.join(', ')}}) }`;
+    }
+
+    // Boolean
+    if (name === 'Boolean') {
+      return `${FN} { validateBool(v, p, i) }`;
+    }
+
+    // OneOf
+    if (name === 'OneOf' && desc.oneOf) {
+      const options = desc.oneOf.map(opt => this.generateDataValidatorExpr(opt));
+      return `${FN} { validateOneOf(v, p, i, []Validator{${options.join(', ')}}) }`;
+    }
+
+    // AllOf
+    if (name === 'AllOf' && desc.allOf) {
+      const parts = desc.allOf.map(part => this.generateDataValidatorExpr(part));
+      return `${FN} { validateAllOf(v, p, i, []Validator{${parts.join(', ')}}) }`;
+    }
+
+    // Not
+    if (name === 'Not' && desc.not) {
+      const inner = this.generateDataValidatorExpr(desc.not);
+      return `${FN} { validateNot(v, p, i, ${inner}) }`;
+    }
+
+    // TaggedUnion
+    if (name === 'TaggedUnion' && desc.discriminator !== undefined && desc.variants) {
+      const variants = Object.entries(desc.variants).map(
+        ([tag, variant]) => `{${this.escapeString(tag)}, ${this.generateDataValidatorExpr(variant)}}`
+      );
+      return `${FN} { validateTaggedUnion(v, p, i, ${this.escapeString(desc.discriminator)}, []variant{${variants.join(', ')}}) }`;
+    }
+
+    // ListOf
+    if (name === 'ListOf' && desc.itemType) {
+      const itemExpr = this.generateDataValidatorExpr(desc.itemType);
+      const opts = extractListConstraints(desc.constraints);
+      const fields: string[] = [`item: ${itemExpr}`];
+      if (opts.minItems !== undefined) fields.push(`minItems: some(${opts.minItems})`);
+      if (opts.maxItems !== undefined) fields.push(`maxItems: some(${opts.maxItems})`);
+      if (opts.unique) fields.push('unique: true');
+      if (opts.uniqueBy !== undefined) fields.push(`uniqueBy: some(${this.escapeString(opts.uniqueBy)})`);
+      if (opts.sorted !== undefined) fields.push(`sorted: some(${this.escapeString(opts.sorted)})`);
+      if (opts.sortedBy !== undefined) fields.push(`sortedBy: some(${this.escapeString(opts.sortedBy)})`);
+      return `${FN} { validateList(v, p, i, listOpts{${fields.join(', ')}}) }`;
+    }
+
+    // Field
+    if (name === 'Field' && desc.key) {
+      const key = desc.key;
+      const optional = desc.optional ?? false;
+
+      let valueExpr: string | null = null;
+      if (desc.oneOf) {
+        const options = desc.oneOf.map(opt => this.generateDataValidatorExpr(opt));
+        valueExpr = `${FN} { validateOneOf(v, p, i, []Validator{${options.join(', ')}}) }`;
+      } else if (desc.allOf) {
+        valueExpr = this.generateDataValidatorExpr({ name: 'AllOf', allOf: desc.allOf });
+      } else if (desc.not) {
+        valueExpr = this.generateDataValidatorExpr({ name: 'Not', not: desc.not });
+      } else if (desc.variants) {
+        valueExpr = this.generateDataValidatorExpr({
+          name: 'TaggedUnion',
+          discriminator: desc.discriminator,
+          variants: desc.variants,
+        });
+      } else if (desc.itemType) {
+        valueExpr = this.generateDataValidatorExpr({
+          name: 'ListOf',
+          itemType: desc.itemType,
+          constraints: desc.constraints,
+        });
+      } else if (desc.children) {
+        valueExpr = this.generateObjectExpr(desc.children);
+      } else if (desc.summary) {
+        valueExpr = this.generateDataValidatorExpr({
+          name: desc.summary,
+          constraints: desc.constraints,
+        });
+      }
+
+      if (valueExpr && desc.ref !== undefined) {
+        valueExpr = this.namedValidator(desc.ref, desc.summary === 'Ref' ? null : valueExpr, desc.maxDepth, desc.valueDescription);
+      }
+      if (valueExpr && desc.valueDeprecated) {
+        valueExpr = `${FN} { validateDeprecated(v, p, i, ${valueExpr}, "type.deprecated", ${this.escapeString(desc.valueDeprecated)}) }`;
+      }
+      if (valueExpr && desc.customIssue) {
+        valueExpr = `${FN} { validateMessage(v, p, i, ${valueExpr}, ${this.messageArgs(desc.customIssue)}) }`;
+      }
+
+      if (valueExpr && desc.nullable) {
+        valueExpr = `${FN} { validateNullable(v, p, i, ${valueExpr}) }`;
+      }
+      if (valueExpr && desc.meta) {
+        valueExpr = `${FN} { validateMeta(v, p, i, ${valueExpr}, ${this.escapeString(JSON.stringify(desc.meta))}) }`;
+      }
+      if (desc.deprecated) {
+        valueExpr = `${FN} { validateDeprecated(v, p, i, ${valueExpr ?? `${FN} {}`}, "field.deprecated", ${this.escapeString(desc.deprecated)}) }`;
+      }
+
+      return `${FN} { validateField(v, p, i, ${this.escapeString(key)}, ${valueExpr ?? 'nil'}, ${optional}) }`;
+    }
+
+    // Object with children
+    if (name === 'Object' && desc.children) {
+      return this.generateObjectExpr(desc.children);
+    }
+
+    // Unknown type
+    return `${FN} {}`;
+  }
+
+  generateObjectExpr(
+    children: { required?: TypeDescription[] | undefined; optional?: TypeDescription[] | undefined }
+  ): string {
+    const fieldExprs: string[] = [];
+
+    for (const { desc, optional } of this.scheduleChildren(children)) {
+      fieldExprs.push(this.generateDataValidatorExpr(optional ? { ...desc, optional: true } : desc));
+    }
+
+    if (fieldExprs.length === 0) {
+      return `${FN} { validateObject(v, p, i) }`;
+    }
+
+    const fieldCalls = this.joinChecks(fieldExprs.map(expr => `(${expr})(v, p, i)`));
+    return `${FN} { if validateObject(v, p, i) { ${fieldCalls} } }`;
+  }
+
+  generateBundleContentExpr(
+    children: { required?: TypeDescription[] | undefined; optional?: TypeDescription[] | undefined }
+  ): string {
+    const parts: string[] = [];
+
+    for (const { desc } of this.scheduleChildren(children)) {
+      parts.push(this.generateFSChildExpr(desc));
+    }
+
+    if (parts.length === 0) {
+      return 'nil';
+    }
+
+    return `func(ctx *FSContext, p []string, i *Issues) { ${this.joinChecks(parts)} }`;
+  }
+
+  /**
+   * Join check statements, returning early after a check reports errors in fail-fast mode
+   */
+  private joinChecks(calls: string[]): string {
+    if (!this.options.failFast) {
+      return calls.join('; ');
+    }
+    return `n := errorCount(i); ${calls.map(call => `${call}; if errorCount(i) > n { return }`).join('; ')}`;
+  }
+
+  generateFSChildExpr(desc: TypeDescription): string {
+    const fsType = desc.fsType;
+    const comment = desc.description ? `/* ${desc.description.replace(/\*\//g, '* /')} */ ` : '';
+
+    if (desc.name === 'When' && desc.condition) {
+      const { file, field, is } = desc.condition;
+      const fields = [`file: ${this.escapeString(file)}`];
+      if (field !== undefined) fields.push(`field: some(${this.escapeString(field)})`);
+      if (is) fields.push(`is: ${this.generateDataValidatorExpr(is)}`);
+      if (desc.then) fields.push(`then: ${this.generateBundleContentExpr(whenBranch(desc.then))}`);
+      if (desc.else) fields.push(`otherwise: ${this.generateBundleContentExpr(whenBranch(desc.else))}`);
+      return `${comment}validateWhen(ctx, p, i, whenOpts{${fields.join(', ')}})`;
+    }
+
+    if (fsType === 'jsonFile' && desc.filePath) {
+      const contentExpr = desc.children ? this.generateObjectExpr(desc.children) : 'nil';
+      return `${comment}validateJsonFile(ctx, ${this.escapeString(desc.filePath)}, p, i, ${contentExpr})`;
+    }
+
+    if (fsType === 'file' && desc.filePath) {
+      const fileCheck = `validateFsFile(ctx, ${this.escapeString(desc.filePath)}, p, i, ${this.escapeString(desc.fileExt ?? '')})`;
+      if (desc.checksum) {
+        const { algorithm, value } = desc.checksum;
+        return `${comment}if ${fileCheck} { validateFileChecksum(ctx, ${this.escapeString(desc.filePath)}, p, i, ${this.escapeString(algorithm)}, ${this.escapeString(value)}) }`;
+      }
+      return `${comment}${fileCheck}`;
+    }
+
+    if (fsType === 'directory' && desc.filePath) {
+      return `${comment}validateFsDirectory(ctx, ${this.escapeString(desc.filePath)}, p, i)`;
+    }
+
+    return '/* unknown fs type */';
+  }
+
+  generateBundleExpr(desc: TypeDescription): string {
+    const accept = extractBundleAccept(desc.accept);
+    const fields: string[] = [];
+
+    fields.push(`acceptDir: ${accept.acceptDir}`);
+    fields.push(`acceptZip: ${accept.acceptZip}`);
+    if (accept.zipExt) {
+      fields.push(`zipExt: some(${this.escapeString(accept.zipExt)})`);
+    }
+
+    if (desc.namePattern) {
+      fields.push(`namePattern: some(${this.escapeString(desc.namePattern)})`);
+    }
+
+    if (desc.children) {
+      const contentExpr = this.generateBundleContentExpr(desc.children);
+      fields.push(`content: ${contentExpr}`);
+    }
+
+    return `func(path string, p []string, i *Issues) *FSContext { return validateBundle(path, p, i, bundleOpts{${fields.join(', ')}}) }`;
+  }
+
+  generateMainCode(isBundle: boolean): string {
+    const entry = isBundle
+      ? `
+// ValidateRoot validates a bundle directory or zip file
+func ValidateRoot(bundlePath string) ValidationResult {
+	return validatePath(bundlePath, rootValidator)
+}`
+      : `
+// ValidateRoot validates a decoded JSON value; decode with ParseJSON to keep decimals exact
+func ValidateRoot(value any) ValidationResult {
+	return validate(value, rootValidator)
+}`;
+    if (this.packageName !== 'main') {
+      return entry;
+    }
+
+    const load = isBundle
+      ? '	outcome := ValidateRoot(os.Args[1])'
+      : `	data, err := os.ReadFile(os.Args[1])
+	if err != nil {
+		fmt.Println("Error: Cannot read JSON file")
+		os.Exit(1)
+	}
+	value, err := ParseJSON(data)
+	if err != nil {
+		fmt.Println("Error: Cannot read JSON file")
+		os.Exit(1)
+	}
+	outcome := ValidateRoot(value)`;
+    return `${entry}
+
+// CLI entry point
+func main() {
+	if len(os.Args) < 2 {
+		fmt.Fprintf(os.Stderr, "Usage: %s <path>\\n", filepath.Base(os.Args[0]))
+		os.Exit(2)
+	}
+${load}
+	output, _ := json.MarshalIndent(outcome, "", "  ")
+	fmt.Println(string(output))
+	if !outcome.OK {
+		os.Exit(1)
+	}
+}`;
+  }
+
+  protected generateValidatorDecl(rootExpr: string, isBundle: boolean): string {
+    return isBundle
+      ? `var rootValidator = ${rootExpr}`
+      : `var rootValidator Validator = ${rootExpr}`;
+  }
+
+  protected definitionIdent(words: string[]): string {
+    return `validateDef${words.map(w => w[0]!.toUpperCase() + w.slice(1)).join('')}`;
+  }
+
+  protected generateDefinition(name: string, ident: string, expr: string, maxDepth?: number): string {
+    if (maxDepth === undefined) {
+      return [
+        `func ${ident}(v any, p []string, i *Issues) {`,
+        `\t(${expr})(v, p, i)`,
+        '}',
+      ].join('\n');
+    }
+    const message = this.escapeString(`Nesting of ${name} exceeds maximum depth ${maxDepth}`);
+    return [
+      `func ${ident}(v any, p []string, i *Issues) {`,
+      `\tif i.depth[${this.escapeString(ident)}] >= ${maxDepth} {`,
+      `\t\taddIssue(i, p, "recursion.too_deep", ${message})`,
+      '\t\treturn',
+      '\t}',
+      `\ti.enter(${this.escapeString(ident)})`,
+      `\tdefer i.leave(${this.escapeString(ident)})`,
+      `\t(${expr})(v, p, i)`,
+      '}',
+    ].join('\n');
+  }
+
+  /** Code and message arguments for validateMessage */
+  private messageArgs(customIssue: NonNullable<TypeDescription['customIssue']>): string {
+    const opt = (s: string | undefined): string => s === undefined ? 'nil' : `some(${this.escapeString(s)})`;
+    return `${opt(customIssue.code)}, ${opt(customIssue.message)}`;
+  }
+
+  protected commentLine(text: string): string {
+    return `// ${text}`.trimEnd();
+  }
+}
+
+/**
+ * Generate Go validator code from TypeDescription
+ */
+export function generateGo(desc: TypeDescription, options: CodegenOptions = {}): string {
+  const generator = new GoGenerator(options);
+  return generator.generate(desc);
+}
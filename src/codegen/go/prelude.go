@@ -0,0 +1,982 @@
+// Code generated by specspec. DO NOT EDIT.
+
+// SpecSpec Go Prelude
+// Validation primitives - embedded at top of generated validators
+
+package main
+
+import (
+	"archive/zip"
+	"bytes"
+	"crypto/sha256"
+	"crypto/sha512"
+	"encoding/hex"
+	"encoding/json"
+	"fmt"
+	"io"
+	"math"
+	"math/big"
+	"os"
+	"path/filepath"
+	"regexp"
+	"strconv"
+	"strings"
+	"sync"
+	"unicode/utf8"
+)
+
+// === Types ===
+
+// Issue is one problem found in the validated value
+type Issue struct {
+	// "error" or "warning"; warnings do not fail validation
+	Level   string `json:"level"`
+	Path    string `json:"path"`
+	Code    string `json:"code"`
+	Message string `json:"message"`
+	// Metadata of the rule that reported the issue (Meta() in the spec)
+	Meta map[string]any `json:"meta,omitempty"`
+}
+
+// Issues collects the issues of one validation run. It also tracks how deeply
+// named types are nested, so concurrent runs never share state.
+type Issues struct {
+	list  []Issue
+	depth map[string]int
+}
+
+// Validator checks a decoded JSON value at a path
+type Validator = func(v any, p []string, i *Issues)
+
+// FSValidator checks the content of a bundle
+type FSValidator = func(ctx *FSContext, p []string, i *Issues)
+
+// ValidationResult is the outcome of validating a value or bundle
+type ValidationResult struct {
+	OK     bool    `json:"ok"`
+	Issues []Issue `json:"issues"`
+}
+
+func pushIssue(issues *Issues, level string, path []string, code string, message string) {
+	where := "(root)"
+	if len(path) > 0 {
+		where = strings.Join(path, ".")
+	}
+	issues.list = append(issues.list, Issue{Level: level, Path: where, Code: code, Message: message})
+}
+
+func addIssue(issues *Issues, path []string, code string, message string) {
+	pushIssue(issues, "error", path, code, message)
+}
+
+func addWarning(issues *Issues, path []string, code string, message string) {
+	pushIssue(issues, "warning", path, code, message)
+}
+
+func errorCount(issues *Issues) int {
+	n := 0
+	for _, issue := range issues.list {
+		if issue.Level == "error" {
+			n++
+		}
+	}
+	return n
+}
+
+// scratch returns an empty collector for trial validations, sharing the nesting depth
+func (i *Issues) scratch() *Issues {
+	return &Issues{depth: i.depth}
+}
+
+func (i *Issues) enter(name string) {
+	if i.depth == nil {
+		i.depth = map[string]int{}
+	}
+	i.depth[name]++
+}
+
+func (i *Issues) leave(name string) {
+	i.depth[name]--
+}
+
+// child extends a path without sharing the parent's backing array
+func child(path []string, segment string) []string {
+	return append(path[:len(path):len(path)], segment)
+}
+
+func index(path []string, n int) []string {
+	return child(path, fmt.Sprintf("[%d]", n))
+}
+
+func some[T any](v T) *T {
+	return &v
+}
+
+// jsonType names the JSON type of a decoded value, for messages
+func jsonType(value any) string {
+	switch value.(type) {
+	case nil:
+		return "null"
+	case bool:
+		return "boolean"
+	case string:
+		return "string"
+	case []any:
+		return "array"
+	case map[string]any:
+		return "object"
+	}
+	if _, ok := toNumber(value); ok {
+		return "number"
+	}
+	return fmt.Sprintf("%T", value)
+}
+
+func toNumber(value any) (float64, bool) {
+	switch n := value.(type) {
+	case float64:
+		return n, true
+	case float32:
+		return float64(n), true
+	case int:
+		return float64(n), true
+	case int32:
+		return float64(n), true
+	case int64:
+		return float64(n), true
+	case uint:
+		return float64(n), true
+	case uint32:
+		return float64(n), true
+	case uint64:
+		return float64(n), true
+	case json.Number:
+		f, err := n.Float64()
+		return f, err == nil
+	}
+	return 0, false
+}
+
+func formatNum(n float64) string {
+	return strconv.FormatFloat(n, 'f', -1, 64)
+}
+
+var regexCache sync.Map
+
+// matches reports whether s matches pattern; patterns are compiled once and
+// must use RE2 syntax (no lookaround or backreferences), otherwise nothing matches
+func matches(pattern string, s string) bool {
+	cached, ok := regexCache.Load(pattern)
+	if !ok {
+		re, _ := regexp.Compile(pattern)
+		cached, _ = regexCache.LoadOrStore(pattern, re)
+	}
+	re, _ := cached.(*regexp.Regexp)
+	return re != nil && re.MatchString(s)
+}
+
+// ParseJSON decodes a JSON document, keeping numbers as json.Number so
+// decimal checks see the digits exactly as written
+func ParseJSON(data []byte) (any, error) {
+	decoder := json.NewDecoder(bytes.NewReader(data))
+	decoder.UseNumber()
+	var value any
+	if err := decoder.Decode(&value); err != nil {
+		return nil, err
+	}
+	if _, err := decoder.Token(); err != io.EOF {
+		return nil, fmt.Errorf("unexpected data after JSON value")
+	}
+	return value, nil
+}
+
+// === Primitive Validators ===
+
+type strOpts struct {
+	minLength *int
+	maxLength *int
+	pattern   *string
+}
+
+func validateStr(value any, path []string, issues *Issues, opts strOpts) {
+	str, ok := value.(string)
+	if !ok {
+		addIssue(issues, path, "type.mismatch", "Expected string, got "+jsonType(value))
+		return
+	}
+	length := utf8.RuneCountInString(str)
+	if opts.minLength != nil && length < *opts.minLength {
+		addIssue(issues, path, "str.too_short", fmt.Sprintf("String length %d is less than minimum %d", length, *opts.minLength))
+	}
+	if opts.maxLength != nil && length > *opts.maxLength {
+		addIssue(issues, path, "str.too_long", fmt.Sprintf("String length %d exceeds maximum %d", length, *opts.maxLength))
+	}
+	if opts.pattern != nil && !matches(*opts.pattern, str) {
+		addIssue(issues, path, "str.pattern_mismatch", "String does not match pattern "+*opts.pattern)
+	}
+}
+
+type numOpts struct {
+	min     *float64
+	max     *float64
+	integer bool
+}
+
+func validateNum(value any, path []string, issues *Issues, opts numOpts) {
+	num, ok := toNumber(value)
+	if !ok {
+		addIssue(issues, path, "type.mismatch", "Expected number, got "+jsonType(value))
+		return
+	}
+	if opts.integer && num != math.Trunc(num) {
+		addIssue(issues, path, "num.not_integer", "Expected integer, got "+formatNum(num))
+	}
+	if opts.min != nil && num < *opts.min {
+		addIssue(issues, path, "num.too_small", fmt.Sprintf("Number %s is less than minimum %s", formatNum(num), formatNum(*opts.min)))
+	}
+	if opts.max != nil && num > *opts.max {
+		addIssue(issues, path, "num.too_large", fmt.Sprintf("Number %s exceeds maximum %s", formatNum(num), formatNum(*opts.max)))
+	}
+}
+
+type decimalOpts struct {
+	min       *string
+	max       *string
+	precision *int
+	scale     *int
+}
+
+// decimalText is a number's source text; parse with ParseJSON to keep every digit
+func decimalText(value any) (string, bool) {
+	switch n := value.(type) {
+	case json.Number:
+		return n.String(), true
+	case float64:
+		if math.IsInf(n, 0) || math.IsNaN(n) {
+			return "", false
+		}
+		return strconv.FormatFloat(n, 'f', -1, 64), true
+	case int:
+		return strconv.Itoa(n), true
+	case int64:
+		return strconv.FormatInt(n, 10), true
+	}
+	return "", false
+}
+
+// decimalDigits counts significant digits and decimal places of a number's text
+func decimalDigits(text string) (digits int, places int) {
+	unsigned := strings.TrimPrefix(text, "-")
+	mantissa, exponent := unsigned, 0
+	if idx := strings.IndexAny(unsigned, "eE"); idx >= 0 {
+		mantissa = unsigned[:idx]
+		exponent, _ = strconv.Atoi(unsigned[idx+1:])
+	}
+	intPart, fracPart, _ := strings.Cut(mantissa, ".")
+	all := intPart + fracPart
+	point := len(intPart) + exponent
+	trimmed := strings.TrimLeft(all, "0")
+	point -= len(all) - len(trimmed)
+	trimmed = strings.TrimRight(trimmed, "0")
+	if trimmed == "" {
+		return 0, 0
+	}
+	places = len(trimmed) - point
+	if places < 0 {
+		places = 0
+	}
+	whole := point
+	if whole < 0 {
+		whole = 0
+	}
+	return whole + places, places
+}
+
+func validateDecimal(value any, path []string, issues *Issues, opts decimalOpts) {
+	text, ok := decimalText(value)
+	num, valid := new(big.Rat).SetString(text)
+	if !ok || !valid {
+		addIssue(issues, path, "type.mismatch", "Expected number, got "+jsonType(value))
+		return
+	}
+
+	digits, places := decimalDigits(text)
+	if opts.precision != nil && digits > *opts.precision {
+		addIssue(issues, path, "decimal.too_many_digits", fmt.Sprintf("Number %s has %d digits, maximum is %d", text, digits, *opts.precision))
+	}
+	if opts.scale != nil && places > *opts.scale {
+		addIssue(issues, path, "decimal.scale_exceeded", fmt.Sprintf("Number %s has %d decimal places, maximum is %d", text, places, *opts.scale))
+	}
+	if opts.min != nil {
+		if bound, ok := new(big.Rat).SetString(*opts.min); ok && num.Cmp(bound) < 0 {
+			addIssue(issues, path, "num.too_small", fmt.Sprintf("Number %s is less than minimum %s", text, *opts.min))
+		}
+	}
+	if opts.max != nil {
+		if bound, ok := new(big.Rat).SetString(*opts.max); ok && num.Cmp(bound) > 0 {
+			addIssue(issues, path, "num.too_large", fmt.Sprintf("Number %s exceeds maximum %s", text, *opts.max))
+		}
+	}
+}
+
+func validateBool(value any, path []string, issues *Issues) {
+	if _, ok := value.(bool); !ok {
+		addIssue(issues, path, "type.mismatch", "Expected boolean, got "+jsonType(value))
+	}
+}
+
+// validateLiteral compares against the expected value given as JSON text
+func validateLiteral(value any, path []string, issues *Issues, expected string) {
+	want, err := ParseJSON([]byte(expected))
+	if err != nil {
+		want = expected
+	}
+	got := canonicalJSON(value)
+	if got != canonicalJSON(want) {
+		addIssue(issues, path, "literal.mismatch", fmt.Sprintf("Expected %s, got %s", expected, got))
+	}
+}
+
+func validatePattern(value any, path []string, issues *Issues, pattern string) {
+	str, ok := value.(string)
+	if !ok {
+		addIssue(issues, path, "type.mismatch", "Expected string for pattern match, got "+jsonType(value))
+		return
+	}
+	if !matches(pattern, str) {
+		addIssue(issues, path, "pattern.mismatch", "Value does not match pattern "+pattern)
+	}
+}
+
+// === Structural Validators ===
+
+func validateObject(value any, path []string, issues *Issues) bool {
+	if _, ok := value.(map[string]any); ok {
+		return true
+	}
+	addIssue(issues, path, "type.mismatch", "Expected object, got "+jsonType(value))
+	return false
+}
+
+func validateField(obj any, path []string, issues *Issues, key string, validator Validator, optional bool) {
+	dict, ok := obj.(map[string]any)
+	if !ok {
+		return
+	}
+
+	value, present := dict[key]
+	if !present {
+		if !optional {
+			addIssue(issues, path, "field.missing", "Missing required field: "+key)
+		}
+		return
+	}
+
+	if validator != nil {
+		validator(value, child(path, key), issues)
+	}
+}
+
+func getKeyPath(value any, keyPath string) (any, bool) {
+	current := value
+	for _, key := range strings.Split(keyPath, ".") {
+		dict, ok := current.(map[string]any)
+		if !ok {
+			return nil, false
+		}
+		next, ok := dict[key]
+		if !ok {
+			return nil, false
+		}
+		current = next
+	}
+	return current, true
+}
+
+// normalizeNumbers turns every number into float64, so equal numbers encode alike
+func normalizeNumbers(value any) any {
+	switch v := value.(type) {
+	case map[string]any:
+		out := make(map[string]any, len(v))
+		for key, item := range v {
+			out[key] = normalizeNumbers(item)
+		}
+		return out
+	case []any:
+		out := make([]any, len(v))
+		for n, item := range v {
+			out[n] = normalizeNumbers(item)
+		}
+		return out
+	}
+	if num, ok := toNumber(value); ok {
+		return num
+	}
+	return value
+}
+
+// canonicalJSON encodes a value with sorted keys, for equality checks and messages
+func canonicalJSON(value any) string {
+	var buf bytes.Buffer
+	encoder := json.NewEncoder(&buf)
+	encoder.SetEscapeHTML(false)
+	if err := encoder.Encode(normalizeNumbers(value)); err != nil {
+		return fmt.Sprint(value)
+	}
+	return strings.TrimSuffix(buf.String(), "\n")
+}
+
+type listOpts struct {
+	item     Validator
+	minItems *int
+	maxItems *int
+	unique   bool
+	uniqueBy *string
+	sorted   *string
+	sortedBy *string
+}
+
+func validateList(value any, path []string, issues *Issues, opts listOpts) {
+	arr, ok := value.([]any)
+	if !ok {
+		addIssue(issues, path, "type.mismatch", "Expected array, got "+jsonType(value))
+		return
+	}
+
+	if opts.minItems != nil && len(arr) < *opts.minItems {
+		addIssue(issues, path, "list.too_short", fmt.Sprintf("Array length %d is less than minimum %d", len(arr), *opts.minItems))
+	}
+	if opts.maxItems != nil && len(arr) > *opts.maxItems {
+		addIssue(issues, path, "list.too_long", fmt.Sprintf("Array length %d exceeds maximum %d", len(arr), *opts.maxItems))
+	}
+
+	if opts.unique || opts.uniqueBy != nil {
+		seen := map[string]int{}
+		for n, item := range arr {
+			keyValue := item
+			if opts.uniqueBy != nil {
+				v, ok := getKeyPath(item, *opts.uniqueBy)
+				if !ok {
+					continue
+				}
+				keyValue = v
+			}
+			key := canonicalJSON(keyValue)
+			if first, dup := seen[key]; dup {
+				message := fmt.Sprintf("Items [%d] and [%d] are duplicates", first, n)
+				if opts.uniqueBy != nil {
+					message = fmt.Sprintf("Items [%d] and [%d] share %s %s", first, n, *opts.uniqueBy, key)
+				}
+				addIssue(issues, index(path, n), "list.duplicate", message)
+			} else {
+				seen[key] = n
+			}
+		}
+	}
+
+	if opts.sorted != nil || opts.sortedBy != nil {
+		descending := opts.sorted != nil && *opts.sorted == "desc"
+		// Only the first out-of-order pair is reported
+		prevIndex := -1
+		var prevKey any
+		for n, item := range arr {
+			key := item
+			if opts.sortedBy != nil {
+				v, ok := getKeyPath(item, *opts.sortedBy)
+				if !ok {
+					continue
+				}
+				key = v
+			}
+			str, isStr := key.(string)
+			num, isNum := toNumber(key)
+			if !isStr && !isNum {
+				continue
+			}
+			if prevIndex >= 0 {
+				outOfOrder := false
+				if prevStr, ok := prevKey.(string); ok && isStr {
+					outOfOrder = (descending && str > prevStr) || (!descending && str < prevStr)
+				} else if prevNum, ok := toNumber(prevKey); ok && isNum {
+					outOfOrder = (descending && num > prevNum) || (!descending && num < prevNum)
+				}
+				if outOfOrder {
+					order := "ascending"
+					if descending {
+						order = "descending"
+					}
+					by := ""
+					if opts.sortedBy != nil {
+						by = " by " + *opts.sortedBy
+					}
+					addIssue(issues, index(path, n), "list.not_sorted", fmt.Sprintf("Items [%d] and [%d] are not in %s order%s", prevIndex, n, order, by))
+					break
+				}
+			}
+			prevIndex, prevKey = n, key
+		}
+	}
+
+	if opts.item != nil {
+		for n, item := range arr {
+			opts.item(item, index(path, n), issues)
+		}
+	}
+}
+
+func validateOneOf(value any, path []string, issues *Issues, validators []Validator) {
+	for _, validator := range validators {
+		test := issues.scratch()
+		validator(value, path, test)
+		if errorCount(test) == 0 {
+			issues.list = append(issues.list, test.list...) // Matched; keep its warnings
+			return
+		}
+	}
+	addIssue(issues, path, "oneof.no_match", "Value does not match any of the options")
+}
+
+func validateAllOf(value any, path []string, issues *Issues, validators []Validator) {
+	// Every part runs; issues shared by several parts are reported once
+	var merged []Issue
+	for _, validator := range validators {
+		part := issues.scratch()
+		validator(value, path, part)
+		for _, issue := range part.list {
+			duplicate := false
+			for _, seen := range merged {
+				if seen.Path == issue.Path && seen.Code == issue.Code && seen.Message == issue.Message {
+					duplicate = true
+					break
+				}
+			}
+			if !duplicate {
+				merged = append(merged, issue)
+			}
+		}
+	}
+	issues.list = append(issues.list, merged...)
+}
+
+type variant struct {
+	tag      string
+	validate Validator
+}
+
+func validateTaggedUnion(value any, path []string, issues *Issues, discriminator string, variants []variant) {
+	dict, ok := value.(map[string]any)
+	if !ok {
+		addIssue(issues, path, "type.mismatch", "Expected object, got "+jsonType(value))
+		return
+	}
+	tag, present := dict[discriminator]
+	if !present {
+		addIssue(issues, path, "field.missing", "Missing required field: "+discriminator)
+		return
+	}
+	// Only the variant selected by the tag runs
+	if tagStr, ok := tag.(string); ok {
+		for _, v := range variants {
+			if v.tag == tagStr {
+				v.validate(value, path, issues)
+				return
+			}
+		}
+	}
+	tags := make([]string, len(variants))
+	for n, v := range variants {
+		tags[n] = v.tag
+	}
+	addIssue(issues, child(path, discriminator), "union.unknown_tag",
+		fmt.Sprintf("Unknown %s %s; expected one of: %s", discriminator, canonicalJSON(tag), strings.Join(tags, ", ")))
+}
+
+func validateNot(value any, path []string, issues *Issues, inner Validator) {
+	test := issues.scratch()
+	inner(value, path, test)
+	if errorCount(test) == 0 {
+		addIssue(issues, path, "not.matched", "Value matches a disallowed schema")
+	}
+}
+
+func validateNullable(value any, path []string, issues *Issues, inner Validator) {
+	if value == nil {
+		return
+	}
+	inner(value, path, issues)
+}
+
+func validateMessage(value any, path []string, issues *Issues, inner Validator, code *string, message *string) {
+	innerIssues := issues.scratch()
+	inner(value, path, innerIssues)
+	var first *Issue
+	for n, issue := range innerIssues.list {
+		if issue.Level == "warning" {
+			issues.list = append(issues.list, issue)
+		} else if first == nil {
+			first = &innerIssues.list[n]
+		}
+	}
+	if first != nil {
+		reportCode, reportMessage := first.Code, first.Message
+		if code != nil {
+			reportCode = *code
+		}
+		if message != nil {
+			reportMessage = *message
+		}
+		addIssue(issues, path, reportCode, reportMessage)
+	}
+}
+
+func validateDeprecated(value any, path []string, issues *Issues, inner Validator, code string, message string) {
+	addWarning(issues, path, code, message)
+	inner(value, path, issues)
+}
+
+// validateMeta attaches metadata (a JSON object) to the issues of the inner validator;
+// keys already on an issue, from a nested Meta(), win
+func validateMeta(value any, path []string, issues *Issues, inner Validator, meta string) {
+	innerIssues := issues.scratch()
+	inner(value, path, innerIssues)
+	for _, issue := range innerIssues.list {
+		var merged map[string]any // Parsed per issue, so issues never share a map
+		_ = json.Unmarshal([]byte(meta), &merged)
+		for key, item := range issue.Meta {
+			merged[key] = item
+		}
+		issue.Meta = merged
+		issues.list = append(issues.list, issue)
+	}
+}
+
+// === File System Context ===
+
+// FSContext reads the files of a bundle directory or zip archive
+type FSContext struct {
+	BasePath   string
+	IsZip      bool
+	zipEntries map[string][]byte
+}
+
+func openFSContext(path string, isZip bool) (*FSContext, error) {
+	ctx := &FSContext{BasePath: path, IsZip: isZip}
+	if !isZip {
+		return ctx, nil
+	}
+
+	archive, err := zip.OpenReader(path)
+	if err != nil {
+		return nil, fmt.Errorf("Invalid zip: %v", err)
+	}
+	defer archive.Close()
+
+	ctx.zipEntries = map[string][]byte{}
+	for _, entry := range archive.File {
+		if entry.FileInfo().IsDir() {
+			continue
+		}
+		reader, err := entry.Open()
+		if err != nil {
+			return nil, fmt.Errorf("Cannot read zip entry: %v", err)
+		}
+		data, err := io.ReadAll(reader)
+		reader.Close()
+		if err != nil {
+			return nil, fmt.Errorf("Cannot read zip content: %v", err)
+		}
+		ctx.zipEntries[entry.Name] = data
+	}
+	return ctx, nil
+}
+
+func (ctx *FSContext) hasZipDir(relPath string) bool {
+	for name := range ctx.zipEntries {
+		if strings.HasPrefix(name, relPath+"/") {
+			return true
+		}
+	}
+	return false
+}
+
+func (ctx *FSContext) Exists(relPath string) bool {
+	if ctx.IsZip {
+		_, ok := ctx.zipEntries[relPath]
+		return ok || ctx.hasZipDir(relPath)
+	}
+	_, err := os.Stat(filepath.Join(ctx.BasePath, relPath))
+	return err == nil
+}
+
+func (ctx *FSContext) IsFile(relPath string) bool {
+	if ctx.IsZip {
+		_, ok := ctx.zipEntries[relPath]
+		return ok
+	}
+	info, err := os.Stat(filepath.Join(ctx.BasePath, relPath))
+	return err == nil && info.Mode().IsRegular()
+}
+
+func (ctx *FSContext) IsDir(relPath string) bool {
+	if ctx.IsZip {
+		return ctx.hasZipDir(relPath)
+	}
+	info, err := os.Stat(filepath.Join(ctx.BasePath, relPath))
+	return err == nil && info.IsDir()
+}
+
+func (ctx *FSContext) ReadBytes(relPath string) ([]byte, error) {
+	if ctx.IsZip {
+		data, ok := ctx.zipEntries[relPath]
+		if !ok {
+			return nil, fmt.Errorf("File not found: %s", relPath)
+		}
+		return data, nil
+	}
+	data, err := os.ReadFile(filepath.Join(ctx.BasePath, relPath))
+	if err != nil {
+		return nil, fmt.Errorf("Cannot read file: %v", err)
+	}
+	return data, nil
+}
+
+func (ctx *FSContext) ReadJSON(relPath string) (any, error) {
+	data, err := ctx.ReadBytes(relPath)
+	if err != nil {
+		return nil, err
+	}
+	value, err := ParseJSON(data)
+	if err != nil {
+		return nil, fmt.Errorf("Invalid JSON: %v", err)
+	}
+	return value, nil
+}
+
+// Basename is the bundle's file name without its extension
+func (ctx *FSContext) Basename() string {
+	name := filepath.Base(ctx.BasePath)
+	return strings.TrimSuffix(name, filepath.Ext(name))
+}
+
+// === File System Validators ===
+
+type bundleOpts struct {
+	acceptDir   bool
+	acceptZip   bool
+	zipExt      *string
+	namePattern *string
+	content     FSValidator
+}
+
+func validateBundle(bundlePath string, pathList []string, issues *Issues, opts bundleOpts) *FSContext {
+	info, err := os.Stat(bundlePath)
+	if err != nil {
+		addIssue(issues, pathList, "bundle.not_found", "Path not found: "+bundlePath)
+		return nil
+	}
+
+	isDir := info.IsDir()
+	isZip := !isDir && (strings.HasSuffix(bundlePath, ".zip") ||
+		(opts.zipExt != nil && strings.HasSuffix(bundlePath, "."+*opts.zipExt)))
+
+	if isDir && !opts.acceptDir {
+		addIssue(issues, pathList, "bundle.type_mismatch", "Directory not accepted")
+		return nil
+	}
+	if isZip && !opts.acceptZip {
+		addIssue(issues, pathList, "bundle.type_mismatch", "Zip file not accepted")
+		return nil
+	}
+	if !isDir && !isZip {
+		addIssue(issues, pathList, "bundle.invalid", "Not a valid bundle: "+bundlePath)
+		return nil
+	}
+
+	ctx, err := openFSContext(bundlePath, isZip)
+	if err != nil {
+		addIssue(issues, pathList, "bundle.open_error", err.Error())
+		return nil
+	}
+
+	if opts.namePattern != nil {
+		name := ctx.Basename()
+		if !matches(*opts.namePattern, name) {
+			addIssue(issues, pathList, "bundle.name_mismatch", fmt.Sprintf("Name '%s' does not match pattern", name))
+		}
+	}
+
+	if opts.content != nil {
+		opts.content(ctx, pathList, issues)
+	}
+
+	return ctx
+}
+
+func validateJsonFile(ctx *FSContext, relPath string, path []string, issues *Issues, contentValidator Validator) any {
+	filePath := child(path, relPath)
+
+	if !ctx.Exists(relPath) {
+		addIssue(issues, filePath, "file.not_found", "File not found: "+relPath)
+		return nil
+	}
+
+	if !ctx.IsFile(relPath) {
+		addIssue(issues, filePath, "file.not_file", "Not a file: "+relPath)
+		return nil
+	}
+
+	content, err := ctx.ReadJSON(relPath)
+	if err != nil {
+		addIssue(issues, filePath, "json.parse_error", err.Error())
+		return nil
+	}
+	if contentValidator != nil {
+		contentValidator(content, filePath, issues)
+	}
+	return content
+}
+
+// validateFsFile checks a file exists; ext, unless empty, is its required extension
+func validateFsFile(ctx *FSContext, relPath string, path []string, issues *Issues, ext string) bool {
+	filePath := child(path, relPath)
+
+	if !ctx.Exists(relPath) {
+		addIssue(issues, filePath, "file.not_found", "File not found: "+relPath)
+		return false
+	}
+
+	if !ctx.IsFile(relPath) {
+		addIssue(issues, filePath, "file.not_file", "Not a file: "+relPath)
+		return false
+	}
+
+	if ext != "" {
+		actualExt := strings.TrimPrefix(filepath.Ext(relPath), ".")
+		if actualExt != ext {
+			addIssue(issues, filePath, "file.wrong_ext", fmt.Sprintf("Expected .%s, got .%s", ext, actualExt))
+			return false
+		}
+	}
+
+	return true
+}
+
+// Digests available to checksum rules, as lowercase hex; register more (e.g. blake3) by name
+var Digests = map[string]func(data []byte) string{
+	"sha256": func(data []byte) string { sum := sha256.Sum256(data); return hex.EncodeToString(sum[:]) },
+	"sha384": func(data []byte) string { sum := sha512.Sum384(data); return hex.EncodeToString(sum[:]) },
+	"sha512": func(data []byte) string { sum := sha512.Sum512(data); return hex.EncodeToString(sum[:]) },
+}
+
+func validateFileChecksum(ctx *FSContext, relPath string, path []string, issues *Issues, algorithm string, expected string) bool {
+	filePath := child(path, relPath)
+
+	digest, ok := Digests[strings.ToLower(algorithm)]
+	if !ok {
+		addIssue(issues, filePath, "checksum.unsupported_algorithm", "Digest algorithm not available: "+algorithm)
+		return false
+	}
+
+	data, err := ctx.ReadBytes(relPath)
+	if err != nil {
+		addIssue(issues, filePath, "file.read_error", err.Error())
+		return false
+	}
+
+	actual := digest(data)
+	if actual != strings.ToLower(expected) {
+		addIssue(issues, filePath, "checksum.mismatch", fmt.Sprintf("%s digest mismatch: expected %s, got %s", algorithm, expected, actual))
+		return false
+	}
+
+	return true
+}
+
+func validateFsDirectory(ctx *FSContext, relPath string, path []string, issues *Issues) bool {
+	dirPath := child(path, relPath)
+
+	if !ctx.Exists(relPath) {
+		addIssue(issues, dirPath, "dir.not_found", "Directory not found: "+relPath)
+		return false
+	}
+
+	if !ctx.IsDir(relPath) {
+		addIssue(issues, dirPath, "dir.not_dir", "Not a directory: "+relPath)
+		return false
+	}
+
+	return true
+}
+
+type whenOpts struct {
+	file      string
+	field     *string
+	is        Validator
+	then      FSValidator
+	otherwise FSValidator
+}
+
+// validateWhen checks then when the value at field of the JSON file is present and
+// matches is, and otherwise when not; a file that cannot be read or parsed decides nothing
+func validateWhen(ctx *FSContext, path []string, issues *Issues, opts whenOpts) {
+	value, err := ctx.ReadJSON(opts.file)
+	if err != nil {
+		return
+	}
+	found := true
+	if opts.field != nil {
+		for _, segment := range strings.Split(*opts.field, ".") {
+			switch container := value.(type) {
+			case map[string]any:
+				value, found = container[segment]
+			case []any:
+				index, err := strconv.Atoi(segment)
+				found = err == nil && index >= 0 && index < len(container)
+				if found {
+					value = container[index]
+				}
+			default:
+				found = false
+			}
+			if !found {
+				break
+			}
+		}
+	}
+
+	holds := found
+	if holds && opts.is != nil {
+		test := issues.scratch()
+		opts.is(value, path, test)
+		holds = errorCount(test) == 0
+	}
+	content := opts.otherwise
+	if holds {
+		content = opts.then
+	}
+	if content != nil {
+		content(ctx, path, issues)
+	}
+}
+
+// === Entry Points ===
+
+func newValidationResult(issues *Issues) ValidationResult {
+	list := issues.list
+	if list == nil {
+		list = []Issue{}
+	}
+	return ValidationResult{OK: errorCount(issues) == 0, Issues: list}
+}
+
+func validate(value any, validator Validator) ValidationResult {
+	issues := &Issues{}
+	validator(value, []string{}, issues)
+	return newValidationResult(issues)
+}
+
+func validatePath(bundlePath string, validator func(string, []string, *Issues) *FSContext) ValidationResult {
+	issues := &Issues{}
+	validator(bundlePath, []string{}, issues)
+	return newValidationResult(issues)
+}
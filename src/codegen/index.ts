@@ -2,30 +2,36 @@
 // Code generation exports
 
 // Base classes and utilities
-export { CodeGenerator, type LanguageConfig } from './base.js';
+export { CodeGenerator, type LanguageConfig, type CodegenOptions, estimateCost, estimateRegexCost } from './base.js';
 
 // Language-specific generators
 export { generatePython, PythonGenerator } from './python/generator.js';
 export { generateTypeScript, TypeScriptGenerator } from './typescript/generator.js';
 export { generateSwift, SwiftGenerator } from './swift/generator.js';
 export { generateRust, RustGenerator } from './rust/generator.js';
+export { generateGo, GoGenerator } from './go/generator.js';
+export { generateKotlin, KotlinGenerator } from './kotlin/generator.js';
 
 // Registry of available generators
 import { PythonGenerator } from './python/generator.js';
 import { TypeScriptGenerator } from './typescript/generator.js';
 import { SwiftGenerator } from './swift/generator.js';
 import { RustGenerator } from './rust/generator.js';
-import type { CodeGenerator } from './base.js';
+import { GoGenerator } from './go/generator.js';
+import { KotlinGenerator } from './kotlin/generator.js';
+import type { CodeGenerator, CodegenOptions } from './base.js';
 
 /**
  * Get all registered code generators
  */
-export function getGenerators(): Map<string, () => CodeGenerator> {
-  const generators = new Map<string, () => CodeGenerator>();
-  generators.set('python', () => new PythonGenerator());
-  generators.set('typescript', () => new TypeScriptGenerator());
-  generators.set('swift', () => new SwiftGenerator());
-  generators.set('rust', () => new RustGenerator());
+export function getGenerators(): Map<string, (options?: CodegenOptions) => CodeGenerator> {
+  const generators = new Map<string, (options?: CodegenOptions) => CodeGenerator>();
+  generators.set('python', (options) => new PythonGenerator(options));
+  generators.set('typescript', (options) => new TypeScriptGenerator(options));
+  generators.set('swift', (options) => new SwiftGenerator(options));
+  generators.set('rust', (options) => new RustGenerator(options));
+  generators.set('go', (options) => new GoGenerator(options));
+  generators.set('kotlin', (options) => new KotlinGenerator(options));
   return generators;
 }
 
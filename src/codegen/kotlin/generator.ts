@@ -0,0 +1,466 @@
+// src/codegen/kotlin/generator.ts
+// Kotlin (JVM) code generator implementation
+
+import fs from 'node:fs';
+import path from 'node:path';
+import { fileURLToPath } from 'node:url';
+import type { TypeDescription } from '../../base.js';
+import {
+  CodeGenerator,
+  type LanguageConfig,
+  type CodegenOptions,
+  extractStringConstraints,
+  regexSource,
+  extractNumberConstraints,
+  extractDecimalConstraints,
+  extractListConstraints,
+  extractBundleAccept,
+  whenBranch,
+} from '../base.js';
+
+const __dirname = path.dirname(fileURLToPath(import.meta.url));
+
+/**
+ * Kotlin code generator
+ */
+export class KotlinGenerator extends CodeGenerator {
+  readonly config: LanguageConfig = {
+    name: 'kotlin',
+    fileExt: '.kt',
+    preludeFile: 'prelude.kt',
+  };
+
+  escapeString(s: string): string {
+    // Kotlin string escaping; `$` would start a template
+    const escaped = s.replace(/[\\"$\n\r\t\u0000-\u001f]/g, c => {
+      switch (c) {
+        case '\\': return '\\\\';
+        case '"': return '\\"';
+        case '$': return '\\$';
+        case '\n': return '\\n';
+        case '\r': return '\\r';
+        case '\t': return '\\t';
+        default: return `\\u${c.charCodeAt(0).toString(16).padStart(4, '0')}`;
+      }
+    });
+    return `"${escaped}"`;
+  }
+
+  loadPrelude(): string {
+    const preludePath = path.join(__dirname, this.config.preludeFile);
+    const prelude = fs.readFileSync(preludePath, 'utf-8');
+    const pkg = this.options.package;
+    if (pkg === undefined) {
+      return prelude;
+    }
+    if (!/^[A-Za-z_][A-Za-z0-9_]*(\.[A-Za-z_][A-Za-z0-9_]*)*$/.test(pkg)) {
+      throw new Error(`Invalid Kotlin package name: ${pkg}`);
+    }
+    // The package clause goes after file annotations and before the imports
+    return prelude.replace(/^import /m, `package ${pkg}\n\nimport `);
+  }
+
+  generateDataValidatorExpr(desc: TypeDescription): string {
+    const name = desc.name;
+
+    // Metadata attached to every issue of the type (fields wrap their value validator below)
+    if (desc.meta && name !== 'Field') {
+      const innerExpr = this.generateDataValidatorExpr({ ...desc, meta: undefined });
+      return `{ v, p, i -> validateMeta(v, p, i, ${innerExpr}, ${this.escapeString(JSON.stringify(desc.meta))}) }`;
+    }
+
+    // Nullable (fields wrap their value validator below)
+    if (desc.nullable && name !== 'Field') {
+      const innerExpr = this.generateDataValidatorExpr({ ...desc, nullable: false });
+      return `{ v, p, i -> validateNullable(v, p, i, ${innerExpr}) }`;
+    }
+
+    // Spec-defined code/message: report the inner failure as one issue
+    if (desc.customIssue && name !== 'Field') {
+      const innerExpr = this.generateDataValidatorExpr({ ...desc, customIssue: undefined });
+      return `{ v, p, i -> validateMessage(v, p, i, ${innerExpr}, ${this.messageArgs(desc.customIssue)}) }`;
+    }
+
+    // Deprecated type: warn, then validate as usual
+    if (desc.deprecated && name !== 'Field') {
+      const innerExpr = this.generateDataValidatorExpr({ ...desc, deprecated: undefined });
+      return `{ v, p, i -> validateDeprecated(v, p, i, ${innerExpr}, "type.deprecated", ${this.escapeString(desc.deprecated)}) }`;
+    }
+
+    // Named type: emitted once as a function, referenced by name
+    if (name === 'Ref' && desc.ref !== undefined) {
+      return `::${this.namedValidator(desc.ref, null)}`;
+    }
+    if (desc.ref !== undefined && name !== 'Field') {
+      const bodyExpr = this.generateDataValidatorExpr({ ...desc, ref: undefined, maxDepth: undefined });
+      return `::${this.namedValidator(desc.ref, bodyExpr, desc.maxDepth, desc.description)}`;
+    }
+
+    // Literal value, compared as JSON
+    if (name === 'Literal') {
+      const val = desc.constraints?.[0]?.replace('equals ', '') ?? 'null';
+      return `{ v, p, i -> validateLiteral(v, p, i, ${this.escapeString(val)}) }`;
+    }
+
+    // Pattern
+    if (name === 'Pattern') {
+      const pattern = regexSource(desc.constraints?.[0]?.replace('matches ', '').replace(/^`|`$/g, '') ?? '');
+      return `{ v, p, i -> validatePattern(v, p, i, ${this.escapeString(pattern)}) }`;
+    }
+
+    // String
+    if (name === 'String') {
+      const opts = extractStringConstraints(desc.constraints);
+      const args: string[] = [];
+      if (opts.minLength !== undefined) args.push(`minLength = ${opts.minLength}`);
+      if (opts.maxLength !== undefined) args.push(`maxLength = ${opts.maxLength}`);
+      if (opts.pattern) args.push(`pattern = ${this.escapeString(regexSource(opts.pattern))}`);
+      return `{ v, p, i -> validateStr(${['v, p, i', ...args].join(', ')}) }`;
+    }
+
+    // Number
+    if (name === 'Number') {
+      const opts = extractNumberConstraints(desc.constraints);
+      const args: string[] = [];
+      if (opts.integer) args.push('integer = true');
+      if (opts.min !== undefined) args.push(`min = ${this.doubleLiteral(opts.min)}`);
+      if (opts.max !== undefined) args.push(`max = ${this.doubleLiteral(opts.max)}`);
+      return `{ v, p, i -> validateNum(${['v, p, i', ...args].join(', ')}) }`;
+    }
+
+    // Decimal
+    if (name === 'Decimal') {
+      const opts = extractDecimalConstraints(desc.constraints);
+      const args: string[] = [];
+      if (opts.min !== undefined) args.push(`min = ${this.escapeString(opts.min)}`);
+      if (opts.max !== undefined) args.push(`max = ${this.escapeString(opts.max)}`);
+      if (opts.precision !== undefined) args.push(`precision = ${opts.precision}`);
+      if (opts.scale !== undefined) args.push(`scale = ${opts.scale}`);
+      return `{ v, p, i -> validateDecimal(${['v, p, i', ...args].join(', ')}) }`;
+    }
+
+    // Boolean
+    if (name === 'Boolean') {
+      return '{ v, p, i -> validateBool(v, p, i) }';
+    }
+
+    // OneOf
+    if (name === 'OneOf' && desc.oneOf) {
+      const options = desc.oneOf.map(opt => this.generateDataValidatorExpr(opt));
+      return `{ v, p, i -> validateOneOf(v, p, i, listOf<Validator>(${options.join(', ')})) }`;
+    }
+
+    // AllOf
+    if (name === 'AllOf' && desc.allOf) {
+      const parts = desc.allOf.map(part => this.generateDataValidatorExpr(part));
+      return `{ v, p, i -> validateAllOf(v, p, i, listOf<Validator>(${parts.join(', ')})) }`;
+    }
+
+    // Not
+    if (name === 'Not' && desc.not) {
+      const inner = this.generateDataValidatorExpr(desc.not);
+      return `{ v, p, i -> validateNot(v, p, i, ${inner}) }`;
+    }
+
+    // TaggedUnion
+    if (name === 'TaggedUnion' && desc.discriminator !== undefined && desc.variants) {
+      const variants = Object.entries(desc.variants).map(
+        ([tag, variant]) => `Pair<String, Validator>(${this.escapeString(tag)}, ${this.generateDataValidatorExpr(variant)})`
+      );
+      return `{ v, p, i -> validateTaggedUnion(v, p, i, ${this.escapeString(desc.discriminator)}, listOf(${variants.join(', ')})) }`;
+    }
+
+    // ListOf
+    if (name === 'ListOf' && desc.itemType) {
+      const itemExpr = this.generateDataValidatorExpr(desc.itemType);
+      const opts = extractListConstraints(desc.constraints);
+      const args: string[] = [`itemValidator = ${itemExpr}`];
+      if (opts.minItems !== undefined) args.push(`minItems = ${opts.minItems}`);
+      if (opts.maxItems !== undefined) args.push(`maxItems = ${opts.maxItems}`);
+      if (opts.unique) args.push('unique = true');
+      if (opts.uniqueBy !== undefined) args.push(`uniqueBy = ${this.escapeString(opts.uniqueBy)}`);
+      if (opts.sorted !== undefined) args.push(`sorted = ${this.escapeString(opts.sorted)}`);
+      if (opts.sortedBy !== undefined) args.push(`sortedBy = ${this.escapeString(opts.sortedBy)}`);
+      return `{ v, p, i -> validateList(v, p, i, ${args.join(', ')}) }`;
+    }
+
+    // Field
+    if (name === 'Field' && desc.key) {
+      const key = desc.key;
+      const optional = desc.optional ?? false;
+      const args: string[] = [this.escapeString(key)];
+
+      let valueExpr: string | null = null;
+      if (desc.oneOf) {
+        const options = desc.oneOf.map(opt => this.generateDataValidatorExpr(opt));
+        valueExpr = `{ v, p, i -> validateOneOf(v, p, i, listOf<Validator>(${options.join(', ')})) }`;
+      } else if (desc.allOf) {
+        valueExpr = this.generateDataValidatorExpr({ name: 'AllOf', allOf: desc.allOf });
+      } else if (desc.not) {
+        valueExpr = this.generateDataValidatorExpr({ name: 'Not', not: desc.not });
+      } else if (desc.variants) {
+        valueExpr = this.generateDataValidatorExpr({
+          name: 'TaggedUnion',
+          discriminator: desc.discriminator,
+          variants: desc.variants,
+        });
+      } else if (desc.itemType) {
+        valueExpr = this.generateDataValidatorExpr({
+          name: 'ListOf',
+          itemType: desc.itemType,
+          constraints: desc.constraints,
+        });
+      } else if (desc.children) {
+        valueExpr = this.generateObjectExpr(desc.children);
+      } else if (desc.summary) {
+        valueExpr = this.generateDataValidatorExpr({
+          name: desc.summary,
+          constraints: desc.constraints,
+        });
+      }
+
+      if (valueExpr && desc.ref !== undefined) {
+        valueExpr = `::${this.namedValidator(desc.ref, desc.summary === 'Ref' ? null : valueExpr, desc.maxDepth, desc.valueDescription)}`;
+      }
+      if (valueExpr && desc.valueDeprecated) {
+        valueExpr = `{ v, p, i -> validateDeprecated(v, p, i, ${valueExpr}, "type.deprecated", ${this.escapeString(desc.valueDeprecated)}) }`;
+      }
+      if (valueExpr && desc.customIssue) {
+        valueExpr = `{ v, p, i -> validateMessage(v, p, i, ${valueExpr}, ${this.messageArgs(desc.customIssue)}) }`;
+      }
+
+      if (valueExpr && desc.nullable) {
+        valueExpr = `{ v, p, i -> validateNullable(v, p, i, ${valueExpr}) }`;
+      }
+      if (valueExpr && desc.meta) {
+        valueExpr = `{ v, p, i -> validateMeta(v, p, i, ${valueExpr}, ${this.escapeString(JSON.stringify(desc.meta))}) }`;
+      }
+      if (desc.deprecated) {
+        valueExpr = `{ v, p, i -> validateDeprecated(v, p, i, ${valueExpr ?? '{ _, _, _ -> }'}, "field.deprecated", ${this.escapeString(desc.deprecated)}) }`;
+      }
+
+      if (valueExpr) {
+        args.push(`validator = ${valueExpr}`);
+      }
+      if (optional) {
+        args.push('optional = true');
+      }
+
+      return `{ v, p, i -> validateField(v, p, i, ${args.join(', ')}) }`;
+    }
+
+    // Object with children
+    if (name === 'Object' && desc.children) {
+      return this.generateObjectExpr(desc.children);
+    }
+
+    // Unknown type
+    return '{ _, _, _ -> }';
+  }
+
+  generateObjectExpr(
+    children: { required?: TypeDescription[] | undefined; optional?: TypeDescription[] | undefined }
+  ): string {
+    const fieldExprs: string[] = [];
+
+    for (const { desc, optional } of this.scheduleChildren(children)) {
+      fieldExprs.push(this.generateDataValidatorExpr(optional ? { ...desc, optional: true } : desc));
+    }
+
+    if (fieldExprs.length === 0) {
+      return '{ v, p, i -> validateObject(v, p, i) }';
+    }
+
+    const fieldCalls = this.joinChecks(fieldExprs.map(expr => `validateWith(v, p, i, ${expr})`));
+    return `{ v, p, i -> if (validateObject(v, p, i)) { ${fieldCalls} } }`;
+  }
+
+  generateBundleContentExpr(
+    children: { required?: TypeDescription[] | undefined; optional?: TypeDescription[] | undefined }
+  ): string {
+    const parts: string[] = [];
+
+    for (const { desc } of this.scheduleChildren(children)) {
+      parts.push(this.generateFSChildExpr(desc));
+    }
+
+    if (parts.length === 0) {
+      return '{ _, _, _ -> }';
+    }
+
+    return `{ ctx, p, i -> ${this.joinChecks(parts)} }`;
+  }
+
+  /**
+   * Join check statements; in fail-fast mode each check only runs while no errors were added
+   * (lambdas cannot return early without a label)
+   */
+  private joinChecks(calls: string[]): string {
+    if (!this.options.failFast || calls.length < 2) {
+      return calls.join('; ');
+    }
+    const nested = calls.reduceRight((rest, call) => rest ? `${call}; if (errorCount(i) == n) { ${rest} }` : call, '');
+    return `val n = errorCount(i); ${nested}`;
+  }
+
+  generateFSChildExpr(desc: TypeDescription): string {
+    const fsType = desc.fsType;
+    const comment = desc.description ? `/* ${desc.description.replace(/\/\*|\*\//g, '* ')} */ ` : '';
+
+    if (desc.name === 'When' && desc.condition) {
+      const { file, field, is } = desc.condition;
+      const args = [this.escapeString(file)];
+      if (field !== undefined) args.push(`field = ${this.escapeString(field)}`);
+      if (is) args.push(`matching = ${this.generateDataValidatorExpr(is)}`);
+      if (desc.then) args.push(`then = ${this.generateBundleContentExpr(whenBranch(desc.then))}`);
+      if (desc.else) args.push(`otherwise = ${this.generateBundleContentExpr(whenBranch(desc.else))}`);
+      return `${comment}validateWhen(ctx, p, i, ${args.join(', ')})`;
+    }
+
+    if (fsType === 'jsonFile' && desc.filePath) {
+      const content = desc.children ? `, contentValidator = ${this.generateObjectExpr(desc.children)}` : '';
+      return `${comment}validateJsonFile(ctx, ${this.escapeString(desc.filePath)}, p, i${content})`;
+    }
+
+    if (fsType === 'file' && desc.filePath) {
+      const ext = desc.fileExt ? `, ext = ${this.escapeString(desc.fileExt)}` : '';
+      const fileCheck = `validateFsFile(ctx, ${this.escapeString(desc.filePath)}, p, i${ext})`;
+      if (desc.checksum) {
+        const { algorithm, value } = desc.checksum;
+        return `${comment}if (${fileCheck}) { validateFileChecksum(ctx, ${this.escapeString(desc.filePath)}, p, i, algorithm = ${this.escapeString(algorithm)}, expected = ${this.escapeString(value)}) }`;
+      }
+      return `${comment}${fileCheck}`;
+    }
+
+    if (fsType === 'directory' && desc.filePath) {
+      return `${comment}validateFsDirectory(ctx, ${this.escapeString(desc.filePath)}, p, i)`;
+    }
+
+    return '/* unknown fs type */';
+  }
+
+  generateBundleExpr(desc: TypeDescription): string {
+    const accept = extractBundleAccept(desc.accept);
+    const args: string[] = [];
+
+    args.push(`acceptDir = ${accept.acceptDir}`);
+    args.push(`acceptZip = ${accept.acceptZip}`);
+    if (accept.zipExt) {
+      args.push(`zipExt = ${this.escapeString(accept.zipExt)}`);
+    }
+
+    if (desc.namePattern) {
+      args.push(`namePattern = ${this.escapeString(desc.namePattern)}`);
+    }
+
+    if (desc.children) {
+      const contentExpr = this.generateBundleContentExpr(desc.children);
+      args.push(`contentValidator = ${contentExpr}`);
+    }
+
+    return `{ path, p, i -> validateBundle(path, p, i, ${args.join(', ')}) }`;
+  }
+
+  generateMainCode(isBundle: boolean): string {
+    const entry = isBundle
+      ? `
+/** Validates a bundle directory or zip file */
+fun validateRoot(bundlePath: String): ValidationResult = validatePath(bundlePath, rootValidator)`
+      : `
+/** Validates a value decoded with parseJson (or any maps, lists and scalars) */
+fun validateRoot(value: Any?): ValidationResult = validate(value, rootValidator)`;
+    // A packaged validator is a library; only the default package gets a command line entry point
+    if (this.options.package !== undefined) {
+      return entry;
+    }
+
+    const load = isBundle
+      ? '    val result = validateRoot(args[0])'
+      : `    val json = try {
+        parseJson(File(args[0]).readText())
+    } catch (e: Exception) {
+        println("Error: Cannot read JSON file")
+        exitProcess(1)
+    }
+    val result = validateRoot(json)`;
+    return `${entry}
+
+// CLI entry point
+fun main(args: Array<String>) {
+    if (args.isEmpty()) {
+        System.err.println("Usage: validator <path>")
+        exitProcess(2)
+    }
+${load}
+    println(result.toJson())
+    exitProcess(if (result.ok) 0 else 1)
+}`;
+  }
+
+  protected generateValidatorDecl(rootExpr: string, isBundle: boolean): string {
+    return isBundle
+      ? `private val rootValidator: (String, List<String>, Issues) -> FSContext? = ${rootExpr}`
+      : `private val rootValidator: Validator = ${rootExpr}`;
+  }
+
+  protected definitionIdent(words: string[]): string {
+    return `validateDef${words.map(w => w[0]!.toUpperCase() + w.slice(1)).join('')}`;
+  }
+
+  protected generateDefinition(name: string, ident: string, expr: string, maxDepth?: number): string {
+    if (maxDepth === undefined) {
+      return [
+        `private fun ${ident}(v: Any?, p: List<String>, i: Issues) {`,
+        `    val validator: Validator = ${expr}`,
+        '    validator(v, p, i)',
+        '}',
+      ].join('\n');
+    }
+    const key = this.escapeString(ident);
+    const message = this.escapeString(`Nesting of ${name} exceeds maximum depth ${maxDepth}`);
+    return [
+      `private fun ${ident}(v: Any?, p: List<String>, i: Issues) {`,
+      `    val depth = i.depth[${key}] ?: 0`,
+      `    if (depth >= ${maxDepth}) {`,
+      `        addIssue(i, p, "recursion.too_deep", ${message})`,
+      '        return',
+      '    }',
+      `    i.depth[${key}] = depth + 1`,
+      '    try {',
+      `        val validator: Validator = ${expr}`,
+      '        validator(v, p, i)',
+      '    } finally {',
+      `        i.depth[${key}] = depth`,
+      '    }',
+      '}',
+    ].join('\n');
+  }
+
+  /** A Kotlin Double literal for a bound (`min = 1` would be an Int) */
+  private doubleLiteral(n: number): string {
+    const text = String(n);
+    return /[.eE]/.test(text) ? text : `${text}.0`;
+  }
+
+  /** Code and message arguments for validateMessage */
+  private messageArgs(customIssue: NonNullable<TypeDescription['customIssue']>): string {
+    const opt = (s: string | undefined): string => s === undefined ? 'null' : this.escapeString(s);
+    return `${opt(customIssue.code)}, ${opt(customIssue.message)}`;
+  }
+
+  protected commentLine(text: string): string {
+    return `// ${text}`.trimEnd();
+  }
+
+  protected docComment(text: string): string[] {
+    return ['/**', ...text.split('\n').map(line => ` * ${line.replace(/\*\//g, '* /')}`.trimEnd()), ' */'];
+  }
+}
+
+/**
+ * Generate Kotlin validator code from TypeDescription
+ */
+export function generateKotlin(desc: TypeDescription, options: CodegenOptions = {}): string {
+  const generator = new KotlinGenerator(options);
+  return generator.generate(desc);
+}
@@ -0,0 +1,809 @@
+@file:Suppress("NAME_SHADOWING", "UNUSED_ANONYMOUS_PARAMETER", "UNUSED_PARAMETER")
+
+// SpecSpec Kotlin Prelude
+// Validation primitives - embedded at top of generated validators
+
+import java.io.File
+import java.io.IOException
+import java.math.BigDecimal
+import java.math.BigInteger
+import java.security.MessageDigest
+import java.util.concurrent.ConcurrentHashMap
+import java.util.zip.ZipFile
+import kotlin.system.exitProcess
+
+// === Types ===
+
+/**
+ * One problem found in the validated value; [level] is "error" or "warning", and warnings do not fail validation.
+ * [meta] is the metadata of the rule that reported it (`Meta()` in the spec).
+ */
+data class Issue(
+    val level: String, val path: String, val code: String, val message: String,
+    val meta: Map<String, Any?> = emptyMap(),
+)
+
+/** Issues of one validation run, plus how deeply named types are nested in it */
+class Issues(val depth: MutableMap<String, Int> = HashMap()) : ArrayList<Issue>()
+
+typealias Validator = (Any?, List<String>, Issues) -> Unit
+typealias FSValidator = (FSContext, List<String>, Issues) -> Unit
+
+data class ValidationResult(val ok: Boolean, val issues: List<Issue>) {
+    /** The result as JSON, shaped like every other language's result */
+    fun toJson(): String = jsonText(
+        linkedMapOf(
+            "ok" to ok,
+            "issues" to issues.map { issue ->
+                linkedMapOf<String, Any?>("level" to issue.level, "path" to issue.path, "code" to issue.code, "message" to issue.message)
+                    .apply { if (issue.meta.isNotEmpty()) put("meta", issue.meta) }
+            },
+        ),
+        pretty = true,
+    )
+}
+
+private fun addIssue(issues: Issues, path: List<String>, code: String, message: String) {
+    issues.add(Issue("error", if (path.isEmpty()) "(root)" else path.joinToString("."), code, message))
+}
+
+private fun addWarning(issues: Issues, path: List<String>, code: String, message: String) {
+    issues.add(Issue("warning", if (path.isEmpty()) "(root)" else path.joinToString("."), code, message))
+}
+
+private fun errorCount(issues: List<Issue>): Int = issues.count { it.level == "error" }
+
+/** Runs a validator given as a lambda, which cannot be called in place */
+private fun validateWith(v: Any?, p: List<String>, i: Issues, validator: Validator) = validator(v, p, i)
+
+private fun typeName(value: Any?): String = when (value) {
+    null -> "null"
+    is Boolean -> "boolean"
+    is String -> "string"
+    is Number -> "number"
+    is List<*> -> "array"
+    is Map<*, *> -> "object"
+    else -> value?.javaClass?.simpleName ?: "unknown"
+}
+
+@Suppress("UNCHECKED_CAST")
+private fun asObject(value: Any?): Map<String, Any?>? = if (value is Map<*, *>) value as Map<String, Any?> else null
+
+/** Any JSON number as an exact decimal; null for non-numbers and non-finite doubles */
+private fun toDecimal(value: Any?): BigDecimal? = when (value) {
+    is BigDecimal -> value
+    is BigInteger -> BigDecimal(value)
+    is Double -> if (value.isFinite()) BigDecimal(value.toString()) else null
+    is Float -> if (value.isFinite()) BigDecimal(value.toString()) else null
+    is Long -> BigDecimal.valueOf(value)
+    is Int -> BigDecimal.valueOf(value.toLong())
+    is Short -> BigDecimal.valueOf(value.toLong())
+    is Byte -> BigDecimal.valueOf(value.toLong())
+    else -> null
+}
+
+private fun plain(num: BigDecimal): String = num.stripTrailingZeros().toPlainString()
+
+private fun plain(num: Double): String = plain(BigDecimal(num.toString()))
+
+private val regexCache = ConcurrentHashMap<String, Result<Regex>>()
+
+/** Whether [s] contains a match of [pattern]; invalid patterns never match */
+private fun matches(pattern: String, s: String): Boolean =
+    regexCache.getOrPut(pattern) { runCatching { Regex(pattern) } }.getOrNull()?.containsMatchIn(s) == true
+
+// === JSON ===
+
+/** Parses a JSON document into maps, lists, strings, BigDecimal numbers, booleans and null */
+fun parseJson(text: String): Any? = JsonParser(text).parseDocument()
+
+private class JsonParser(private val text: String) {
+    private var pos = 0
+
+    fun parseDocument(): Any? {
+        val value = parseValue()
+        skipWhitespace()
+        if (pos < text.length) fail("Unexpected data after JSON value")
+        return value
+    }
+
+    private fun fail(message: String): Nothing = throw IllegalArgumentException("$message at offset $pos")
+
+    private fun skipWhitespace() {
+        while (pos < text.length && text[pos] in " \t\r\n") pos++
+    }
+
+    private fun consume(c: Char) {
+        skipWhitespace()
+        if (pos >= text.length || text[pos] != c) fail("Expected '$c'")
+        pos++
+    }
+
+    private fun parseValue(): Any? {
+        skipWhitespace()
+        if (pos >= text.length) fail("Unexpected end of JSON")
+        val c = text[pos]
+        return when {
+            c == '{' -> parseObject()
+            c == '[' -> parseArray()
+            c == '"' -> parseString()
+            c == '-' || c in '0'..'9' -> parseNumber()
+            text.startsWith("true", pos) -> { pos += 4; true }
+            text.startsWith("false", pos) -> { pos += 5; false }
+            text.startsWith("null", pos) -> { pos += 4; null }
+            else -> fail("Unexpected character '$c'")
+        }
+    }
+
+    private fun parseObject(): Map<String, Any?> {
+        val result = LinkedHashMap<String, Any?>()
+        consume('{')
+        skipWhitespace()
+        if (pos < text.length && text[pos] == '}') {
+            pos++
+            return result
+        }
+        while (pos < text.length) {
+            skipWhitespace()
+            if (pos >= text.length || text[pos] != '"') fail("Expected string key")
+            val key = parseString()
+            consume(':')
+            result[key] = parseValue()
+            skipWhitespace()
+            if (pos < text.length && text[pos] == '}') {
+                pos++
+                return result
+            }
+            consume(',')
+        }
+        fail("Unexpected end of JSON")
+    }
+
+    private fun parseArray(): List<Any?> {
+        val result = ArrayList<Any?>()
+        consume('[')
+        skipWhitespace()
+        if (pos < text.length && text[pos] == ']') {
+            pos++
+            return result
+        }
+        while (pos < text.length) {
+            result.add(parseValue())
+            skipWhitespace()
+            if (pos < text.length && text[pos] == ']') {
+                pos++
+                return result
+            }
+            consume(',')
+        }
+        fail("Unexpected end of JSON")
+    }
+
+    private fun parseString(): String {
+        val out = StringBuilder()
+        pos++ // opening quote
+        while (pos < text.length) {
+            val c = text[pos++]
+            when {
+                c == '"' -> return out.toString()
+                c == '\\' -> {
+                    if (pos >= text.length) fail("Unterminated string")
+                    when (val e = text[pos++]) {
+                        '"', '\\', '/' -> out.append(e)
+                        'b' -> out.append('\b')
+                        'f' -> out.append('\u000C')
+                        'n' -> out.append('\n')
+                        'r' -> out.append('\r')
+                        't' -> out.append('\t')
+                        'u' -> {
+                            val code = text.substring(pos, minOf(pos + 4, text.length)).takeIf { it.length == 4 }?.toIntOrNull(16)
+                                ?: fail("Invalid unicode escape")
+                            out.append(code.toChar())
+                            pos += 4
+                        }
+                        else -> fail("Invalid escape '\\$e'")
+                    }
+                }
+                c < ' ' -> fail("Control character in string")
+                else -> out.append(c)
+            }
+        }
+        fail("Unterminated string")
+    }
+
+    private fun parseNumber(): BigDecimal {
+        val start = pos
+        while (pos < text.length && (text[pos] in '0'..'9' || text[pos] in "-+.eE")) pos++
+        return text.substring(start, pos).toBigDecimalOrNull() ?: fail("Invalid number")
+    }
+}
+
+private fun jsonString(s: String): String {
+    val out = StringBuilder("\"")
+    for (c in s) {
+        when {
+            c == '"' -> out.append("\\\"")
+            c == '\\' -> out.append("\\\\")
+            c == '\n' -> out.append("\\n")
+            c == '\r' -> out.append("\\r")
+            c == '\t' -> out.append("\\t")
+            c < ' ' -> out.append("\\u%04x".format(c.code))
+            else -> out.append(c)
+        }
+    }
+    return out.append('"').toString()
+}
+
+/** Encodes a value as JSON; compact output sorts object keys so equal values encode alike */
+private fun jsonText(value: Any?, pretty: Boolean = false, level: Int = 0): String {
+    val indent = if (pretty) "\n" + "  ".repeat(level + 1) else ""
+    val close = if (pretty) "\n" + "  ".repeat(level) else ""
+    return when (value) {
+        null -> "null"
+        is String -> jsonString(value)
+        is Boolean -> value.toString()
+        is Number -> toDecimal(value)?.let { plain(it) } ?: "null"
+        is Map<*, *> -> {
+            val entries = value.entries.map { it.key.toString() to it.value }
+            val ordered = if (pretty) entries else entries.sortedBy { it.first }
+            if (ordered.isEmpty()) "{}"
+            else ordered.joinToString(",", "{", "$close}") { (key, item) ->
+                indent + jsonString(key) + (if (pretty) ": " else ":") + jsonText(item, pretty, level + 1)
+            }
+        }
+        is List<*> -> {
+            if (value.isEmpty()) "[]"
+            else value.joinToString(",", "[", "$close]") { indent + jsonText(it, pretty, level + 1) }
+        }
+        else -> jsonString(value.toString())
+    }
+}
+
+private fun canonicalJson(value: Any?): String = jsonText(value)
+
+// === Primitive Validators ===
+
+private fun validateStr(
+    value: Any?, path: List<String>, issues: Issues,
+    minLength: Int? = null, maxLength: Int? = null, pattern: String? = null,
+) {
+    if (value !is String) {
+        addIssue(issues, path, "type.mismatch", "Expected string, got ${typeName(value)}")
+        return
+    }
+    val length = value.codePointCount(0, value.length)
+    if (minLength != null && length < minLength) {
+        addIssue(issues, path, "str.too_short", "String length $length is less than minimum $minLength")
+    }
+    if (maxLength != null && length > maxLength) {
+        addIssue(issues, path, "str.too_long", "String length $length exceeds maximum $maxLength")
+    }
+    if (pattern != null && !matches(pattern, value)) {
+        addIssue(issues, path, "str.pattern_mismatch", "String does not match pattern $pattern")
+    }
+}
+
+private fun validateNum(
+    value: Any?, path: List<String>, issues: Issues,
+    min: Double? = null, max: Double? = null, integer: Boolean = false,
+) {
+    val num = toDecimal(value)?.toDouble()
+    if (num == null) {
+        addIssue(issues, path, "type.mismatch", "Expected number, got ${typeName(value)}")
+        return
+    }
+    if (integer && num % 1.0 != 0.0) {
+        addIssue(issues, path, "num.not_integer", "Expected integer, got ${plain(num)}")
+    }
+    if (min != null && num < min) {
+        addIssue(issues, path, "num.too_small", "Number ${plain(num)} is less than minimum ${plain(min)}")
+    }
+    if (max != null && num > max) {
+        addIssue(issues, path, "num.too_large", "Number ${plain(num)} exceeds maximum ${plain(max)}")
+    }
+}
+
+private fun validateDecimal(
+    value: Any?, path: List<String>, issues: Issues,
+    min: String? = null, max: String? = null, precision: Int? = null, scale: Int? = null,
+) {
+    val num = toDecimal(value)
+    if (num == null) {
+        addIssue(issues, path, "type.mismatch", "Expected number, got ${typeName(value)}")
+        return
+    }
+
+    // Digits before the point without leading zeros, after it without trailing zeros
+    val text = num.toPlainString()
+    val stripped = num.stripTrailingZeros()
+    val places = if (num.signum() == 0) 0 else maxOf(stripped.scale(), 0)
+    val digits = if (num.signum() == 0) 0 else maxOf(stripped.precision() - stripped.scale(), 0) + places
+
+    if (precision != null && digits > precision) {
+        addIssue(issues, path, "decimal.too_many_digits", "Number $text has $digits digits, maximum is $precision")
+    }
+    if (scale != null && places > scale) {
+        addIssue(issues, path, "decimal.scale_exceeded", "Number $text has $places decimal places, maximum is $scale")
+    }
+    if (min != null && num < BigDecimal(min)) {
+        addIssue(issues, path, "num.too_small", "Number $text is less than minimum $min")
+    }
+    if (max != null && num > BigDecimal(max)) {
+        addIssue(issues, path, "num.too_large", "Number $text exceeds maximum $max")
+    }
+}
+
+private fun validateBool(value: Any?, path: List<String>, issues: Issues) {
+    if (value !is Boolean) {
+        addIssue(issues, path, "type.mismatch", "Expected boolean, got ${typeName(value)}")
+    }
+}
+
+/** Compares against the expected value given as JSON text */
+private fun validateLiteral(value: Any?, path: List<String>, issues: Issues, expected: String) {
+    val actual = canonicalJson(value)
+    if (actual != canonicalJson(parseJson(expected))) {
+        addIssue(issues, path, "literal.mismatch", "Expected $expected, got $actual")
+    }
+}
+
+private fun validatePattern(value: Any?, path: List<String>, issues: Issues, pattern: String) {
+    if (value !is String) {
+        addIssue(issues, path, "type.mismatch", "Expected string for pattern match, got ${typeName(value)}")
+        return
+    }
+    if (!matches(pattern, value)) {
+        addIssue(issues, path, "pattern.mismatch", "Value does not match pattern $pattern")
+    }
+}
+
+// === Structural Validators ===
+
+private fun validateObject(value: Any?, path: List<String>, issues: Issues): Boolean {
+    if (value is Map<*, *>) {
+        return true
+    }
+    addIssue(issues, path, "type.mismatch", "Expected object, got ${typeName(value)}")
+    return false
+}
+
+private fun validateField(
+    obj: Any?, path: List<String>, issues: Issues,
+    key: String, validator: Validator? = null, optional: Boolean = false,
+) {
+    val dict = asObject(obj) ?: return
+
+    if (!dict.containsKey(key)) {
+        if (!optional) {
+            addIssue(issues, path, "field.missing", "Missing required field: $key")
+        }
+        return
+    }
+
+    validator?.invoke(dict[key], path + key, issues)
+}
+
+/** The value at a dotted key path, and whether the path exists */
+private fun getKeyPath(value: Any?, keyPath: String): Pair<Any?, Boolean> {
+    var current = value
+    for (key in keyPath.split(".")) {
+        val dict = asObject(current)
+        if (dict == null || !dict.containsKey(key)) {
+            return null to false
+        }
+        current = dict[key]
+    }
+    return current to true
+}
+
+private fun validateList(
+    value: Any?, path: List<String>, issues: Issues,
+    itemValidator: Validator? = null, minItems: Int? = null, maxItems: Int? = null,
+    unique: Boolean = false, uniqueBy: String? = null,
+    sorted: String? = null, sortedBy: String? = null,
+) {
+    if (value !is List<*>) {
+        addIssue(issues, path, "type.mismatch", "Expected array, got ${typeName(value)}")
+        return
+    }
+
+    if (minItems != null && value.size < minItems) {
+        addIssue(issues, path, "list.too_short", "Array length ${value.size} is less than minimum $minItems")
+    }
+    if (maxItems != null && value.size > maxItems) {
+        addIssue(issues, path, "list.too_long", "Array length ${value.size} exceeds maximum $maxItems")
+    }
+
+    if (unique || uniqueBy != null) {
+        val seen = HashMap<String, Int>()
+        for ((n, item) in value.withIndex()) {
+            val (keyValue, found) = if (uniqueBy != null) getKeyPath(item, uniqueBy) else item to true
+            if (!found) continue
+            val key = canonicalJson(keyValue)
+            val first = seen[key]
+            if (first != null) {
+                val message = if (uniqueBy != null) "Items [$first] and [$n] share $uniqueBy $key"
+                    else "Items [$first] and [$n] are duplicates"
+                addIssue(issues, path + "[$n]", "list.duplicate", message)
+            } else {
+                seen[key] = n
+            }
+        }
+    }
+
+    if (sorted != null || sortedBy != null) {
+        val descending = sorted == "desc"
+        // Only the first out-of-order pair is reported
+        var prevIndex = -1
+        var prevKey: Any? = null
+        for ((n, item) in value.withIndex()) {
+            val (key, found) = if (sortedBy != null) getKeyPath(item, sortedBy) else item to true
+            if (!found || !(key is String || toDecimal(key) != null)) continue
+            if (prevIndex >= 0) {
+                val prev = prevKey
+                val order = when {
+                    prev is String && key is String -> key.compareTo(prev)
+                    prev !is String && key !is String -> toDecimal(key)!!.compareTo(toDecimal(prev)!!)
+                    else -> 0
+                }
+                if ((descending && order > 0) || (!descending && order < 0)) {
+                    val direction = if (descending) "descending" else "ascending"
+                    val by = if (sortedBy != null) " by $sortedBy" else ""
+                    addIssue(issues, path + "[$n]", "list.not_sorted",
+                        "Items [$prevIndex] and [$n] are not in $direction order$by")
+                    break
+                }
+            }
+            prevIndex = n
+            prevKey = key
+        }
+    }
+
+    if (itemValidator != null) {
+        for ((n, item) in value.withIndex()) {
+            itemValidator(item, path + "[$n]", issues)
+        }
+    }
+}
+
+private fun validateOneOf(value: Any?, path: List<String>, issues: Issues, validators: List<Validator>) {
+    for (validator in validators) {
+        val testIssues = Issues(issues.depth)
+        validator(value, path, testIssues)
+        if (errorCount(testIssues) == 0) {
+            issues.addAll(testIssues) // Matched; keep its warnings
+            return
+        }
+    }
+    addIssue(issues, path, "oneof.no_match", "Value does not match any of the options")
+}
+
+private fun validateAllOf(value: Any?, path: List<String>, issues: Issues, validators: List<Validator>) {
+    // Every part runs; issues shared by several parts are reported once
+    val merged = ArrayList<Issue>()
+    for (validator in validators) {
+        val partIssues = Issues(issues.depth)
+        validator(value, path, partIssues)
+        for (issue in partIssues) {
+            if (merged.none { it.path == issue.path && it.code == issue.code && it.message == issue.message }) {
+                merged.add(issue)
+            }
+        }
+    }
+    issues.addAll(merged)
+}
+
+private fun validateTaggedUnion(
+    value: Any?, path: List<String>, issues: Issues,
+    discriminator: String, variants: List<Pair<String, Validator>>,
+) {
+    val dict = asObject(value)
+    if (dict == null) {
+        addIssue(issues, path, "type.mismatch", "Expected object, got ${typeName(value)}")
+        return
+    }
+    if (!dict.containsKey(discriminator)) {
+        addIssue(issues, path, "field.missing", "Missing required field: $discriminator")
+        return
+    }
+    // Only the variant selected by the tag runs
+    val tag = dict[discriminator]
+    val variant = variants.firstOrNull { it.first == tag }
+    if (tag is String && variant != null) {
+        variant.second(value, path, issues)
+        return
+    }
+    val expected = variants.joinToString(", ") { it.first }
+    addIssue(issues, path + discriminator, "union.unknown_tag",
+        "Unknown $discriminator ${canonicalJson(tag)}; expected one of: $expected")
+}
+
+private fun validateNot(value: Any?, path: List<String>, issues: Issues, inner: Validator) {
+    val testIssues = Issues(issues.depth)
+    inner(value, path, testIssues)
+    if (errorCount(testIssues) == 0) {
+        addIssue(issues, path, "not.matched", "Value matches a disallowed schema")
+    }
+}
+
+private fun validateNullable(value: Any?, path: List<String>, issues: Issues, inner: Validator) {
+    if (value == null) {
+        return
+    }
+    inner(value, path, issues)
+}
+
+private fun validateMessage(
+    value: Any?, path: List<String>, issues: Issues,
+    inner: Validator, code: String?, message: String?,
+) {
+    val innerIssues = Issues(issues.depth)
+    inner(value, path, innerIssues)
+    issues.addAll(innerIssues.filter { it.level == "warning" })
+    val first = innerIssues.firstOrNull { it.level == "error" }
+    if (first != null) {
+        addIssue(issues, path, code ?: first.code, message ?: first.message)
+    }
+}
+
+private fun validateDeprecated(
+    value: Any?, path: List<String>, issues: Issues,
+    inner: Validator, code: String, message: String,
+) {
+    addWarning(issues, path, code, message)
+    inner(value, path, issues)
+}
+
+/** Attaches metadata (a JSON object) to the issues of [inner]; keys already on an issue, from a nested Meta(), win */
+private fun validateMeta(value: Any?, path: List<String>, issues: Issues, inner: Validator, meta: String) {
+    val innerIssues = Issues(issues.depth)
+    inner(value, path, innerIssues)
+    if (innerIssues.isEmpty()) return
+    val parsed = asObject(parseJson(meta)) ?: emptyMap()
+    innerIssues.mapTo(issues) { it.copy(meta = parsed + it.meta) }
+}
+
+// === File System Context ===
+
+/** Reads the files of a bundle directory or zip archive */
+class FSContext(val basePath: String, val isZip: Boolean) {
+    private val zipEntries = HashMap<String, ByteArray>()
+
+    init {
+        if (isZip) {
+            ZipFile(basePath).use { zip ->
+                for (entry in zip.entries()) {
+                    if (!entry.isDirectory) {
+                        zipEntries[entry.name] = zip.getInputStream(entry).use { it.readBytes() }
+                    }
+                }
+            }
+        }
+    }
+
+    private fun isZipDir(relPath: String): Boolean = zipEntries.keys.any { it.startsWith("$relPath/") }
+
+    fun exists(relPath: String): Boolean =
+        if (isZip) zipEntries.containsKey(relPath) || isZipDir(relPath) else File(basePath, relPath).exists()
+
+    fun isFile(relPath: String): Boolean =
+        if (isZip) zipEntries.containsKey(relPath) else File(basePath, relPath).isFile
+
+    fun isDir(relPath: String): Boolean =
+        if (isZip) isZipDir(relPath) else File(basePath, relPath).isDirectory
+
+    fun readBytes(relPath: String): ByteArray =
+        if (isZip) zipEntries[relPath] ?: throw IOException("File not found: $relPath")
+        else File(basePath, relPath).readBytes()
+
+    fun read(relPath: String): String = String(readBytes(relPath), Charsets.UTF_8)
+
+    fun readJson(relPath: String): Any? = parseJson(read(relPath))
+
+    /** The bundle's file name without its extension */
+    fun basename(): String = File(basePath).name.substringBeforeLast('.')
+}
+
+// === File System Validators ===
+
+private fun validateBundle(
+    bundlePath: String, pathList: List<String>, issues: Issues,
+    acceptDir: Boolean = true, acceptZip: Boolean = false,
+    zipExt: String? = null, namePattern: String? = null,
+    contentValidator: FSValidator? = null,
+): FSContext? {
+    val file = File(bundlePath)
+    if (!file.exists()) {
+        addIssue(issues, pathList, "bundle.not_found", "Path not found: $bundlePath")
+        return null
+    }
+
+    val isDirectory = file.isDirectory
+    val isZipFile = !isDirectory && (bundlePath.endsWith(".zip") || (zipExt != null && bundlePath.endsWith(".$zipExt")))
+
+    if (isDirectory && !acceptDir) {
+        addIssue(issues, pathList, "bundle.type_mismatch", "Directory not accepted")
+        return null
+    }
+    if (isZipFile && !acceptZip) {
+        addIssue(issues, pathList, "bundle.type_mismatch", "Zip file not accepted")
+        return null
+    }
+    if (!isDirectory && !isZipFile) {
+        addIssue(issues, pathList, "bundle.invalid", "Not a valid bundle: $bundlePath")
+        return null
+    }
+
+    val ctx = try {
+        FSContext(bundlePath, isZipFile)
+    } catch (e: Exception) {
+        addIssue(issues, pathList, "bundle.open_error", e.message ?: e.toString())
+        return null
+    }
+
+    if (namePattern != null) {
+        val name = ctx.basename()
+        if (!matches(namePattern, name)) {
+            addIssue(issues, pathList, "bundle.name_mismatch", "Name '$name' does not match pattern")
+        }
+    }
+
+    contentValidator?.invoke(ctx, pathList, issues)
+    return ctx
+}
+
+private fun validateJsonFile(
+    ctx: FSContext, relPath: String, path: List<String>, issues: Issues,
+    contentValidator: Validator? = null,
+): Any? {
+    val filePath = path + relPath
+
+    if (!ctx.exists(relPath)) {
+        addIssue(issues, filePath, "file.not_found", "File not found: $relPath")
+        return null
+    }
+
+    if (!ctx.isFile(relPath)) {
+        addIssue(issues, filePath, "file.not_file", "Not a file: $relPath")
+        return null
+    }
+
+    val content = try {
+        ctx.readJson(relPath)
+    } catch (e: Exception) {
+        addIssue(issues, filePath, "json.parse_error", "Invalid JSON: ${e.message}")
+        return null
+    }
+    contentValidator?.invoke(content, filePath, issues)
+    return content
+}
+
+private fun validateFsFile(
+    ctx: FSContext, relPath: String, path: List<String>, issues: Issues,
+    ext: String? = null,
+): Boolean {
+    val filePath = path + relPath
+
+    if (!ctx.exists(relPath)) {
+        addIssue(issues, filePath, "file.not_found", "File not found: $relPath")
+        return false
+    }
+
+    if (!ctx.isFile(relPath)) {
+        addIssue(issues, filePath, "file.not_file", "Not a file: $relPath")
+        return false
+    }
+
+    if (ext != null) {
+        val actualExt = File(relPath).extension
+        if (actualExt != ext) {
+            addIssue(issues, filePath, "file.wrong_ext", "Expected .$ext, got .$actualExt")
+            return false
+        }
+    }
+
+    return true
+}
+
+private fun hexDigest(algorithm: String, data: ByteArray): String =
+    MessageDigest.getInstance(algorithm).digest(data).joinToString("") { "%02x".format(it) }
+
+/** Digests available to checksum rules; register more (e.g. blake3) by name */
+val digests: MutableMap<String, (ByteArray) -> String> = mutableMapOf(
+    "sha256" to { data: ByteArray -> hexDigest("SHA-256", data) },
+    "sha384" to { data: ByteArray -> hexDigest("SHA-384", data) },
+    "sha512" to { data: ByteArray -> hexDigest("SHA-512", data) },
+)
+
+private fun validateFileChecksum(
+    ctx: FSContext, relPath: String, path: List<String>, issues: Issues,
+    algorithm: String, expected: String,
+): Boolean {
+    val filePath = path + relPath
+
+    val digest = digests[algorithm.lowercase()]
+    if (digest == null) {
+        addIssue(issues, filePath, "checksum.unsupported_algorithm", "Digest algorithm not available: $algorithm")
+        return false
+    }
+
+    val data = try {
+        ctx.readBytes(relPath)
+    } catch (e: Exception) {
+        addIssue(issues, filePath, "file.read_error", e.message ?: e.toString())
+        return false
+    }
+
+    val actual = digest(data)
+    if (actual != expected.lowercase()) {
+        addIssue(issues, filePath, "checksum.mismatch", "$algorithm digest mismatch: expected $expected, got $actual")
+        return false
+    }
+
+    return true
+}
+
+private fun validateFsDirectory(ctx: FSContext, relPath: String, path: List<String>, issues: Issues): Boolean {
+    val dirPath = path + relPath
+
+    if (!ctx.exists(relPath)) {
+        addIssue(issues, dirPath, "dir.not_found", "Directory not found: $relPath")
+        return false
+    }
+
+    if (!ctx.isDir(relPath)) {
+        addIssue(issues, dirPath, "dir.not_dir", "Not a directory: $relPath")
+        return false
+    }
+
+    return true
+}
+
+private object Missing
+
+/**
+ * Checks [then] when the value at [field] of the JSON [file] is present and [matching] accepts it,
+ * and [otherwise] when not; a file that cannot be read or parsed decides nothing
+ */
+private fun validateWhen(
+    ctx: FSContext, path: List<String>, issues: Issues, file: String,
+    field: String? = null, matching: Validator? = null,
+    then: FSValidator? = null, otherwise: FSValidator? = null,
+) {
+    var value: Any? = try {
+        ctx.readJson(file)
+    } catch (e: Exception) {
+        return
+    }
+    for (segment in field?.split('.') ?: emptyList()) {
+        val current = value
+        val index = segment.toIntOrNull()
+        value = when {
+            current is Map<*, *> && current.containsKey(segment) -> current[segment]
+            current is List<*> && index != null && index in current.indices -> current[index]
+            else -> Missing
+        }
+    }
+
+    var holds = value !== Missing
+    if (holds && matching != null) {
+        val testIssues = Issues(issues.depth)
+        matching(value, path, testIssues)
+        holds = errorCount(testIssues) == 0
+    }
+    (if (holds) then else otherwise)?.invoke(ctx, path, issues)
+}
+
+// === Entry Points ===
+
+private fun validate(value: Any?, validator: Validator): ValidationResult {
+    val issues = Issues()
+    validator(value, emptyList(), issues)
+    return ValidationResult(errorCount(issues) == 0, issues.toList())
+}
+
+private fun validatePath(bundlePath: String, validator: (String, List<String>, Issues) -> FSContext?): ValidationResult {
+    val issues = Issues()
+    validator(bundlePath, emptyList(), issues)
+    return ValidationResult(errorCount(issues) == 0, issues.toList())
+}
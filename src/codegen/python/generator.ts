@@ -8,10 +8,14 @@ import type { TypeDescription } from '../../base.js';
 import {
   CodeGenerator,
   type LanguageConfig,
+  type CodegenOptions,
   extractStringConstraints,
+  regexSource,
   extractNumberConstraints,
+  extractDecimalConstraints,
   extractListConstraints,
   extractBundleAccept,
+  whenBranch,
 } from '../base.js';
 
 const __dirname = path.dirname(fileURLToPath(import.meta.url));
@@ -38,6 +42,39 @@ export class PythonGenerator extends CodeGenerator {
   generateDataValidatorExpr(desc: TypeDescription): string {
     const name = desc.name;
 
+    // Metadata attached to every issue of the type (fields wrap their value validator below)
+    if (desc.meta && name !== 'Field') {
+      const innerExpr = this.generateDataValidatorExpr({ ...desc, meta: undefined });
+      return `lambda v, p, i: validate_meta(v, p, i, ${innerExpr}, ${this.escapeString(JSON.stringify(desc.meta))})`;
+    }
+
+    // Nullable (fields wrap their value validator below)
+    if (desc.nullable && name !== 'Field') {
+      const innerExpr = this.generateDataValidatorExpr({ ...desc, nullable: false });
+      return `lambda v, p, i: validate_nullable(v, p, i, ${innerExpr})`;
+    }
+
+    // Spec-defined code/message: report the inner failure as one issue
+    if (desc.customIssue && name !== 'Field') {
+      const innerExpr = this.generateDataValidatorExpr({ ...desc, customIssue: undefined });
+      return `lambda v, p, i: validate_message(v, p, i, ${innerExpr}${this.messageArgs(desc.customIssue)})`;
+    }
+
+    // Deprecated type: warn, then validate as usual
+    if (desc.deprecated && name !== 'Field') {
+      const innerExpr = this.generateDataValidatorExpr({ ...desc, deprecated: undefined });
+      return `lambda v, p, i: validate_deprecated(v, p, i, ${innerExpr}, "type.deprecated", ${this.escapeString(desc.deprecated)})`;
+    }
+
+    // Named type: emitted once as a function, referenced by name
+    if (name === 'Ref' && desc.ref !== undefined) {
+      return this.namedValidator(desc.ref, null);
+    }
+    if (desc.ref !== undefined && name !== 'Field') {
+      const bodyExpr = this.generateDataValidatorExpr({ ...desc, ref: undefined, maxDepth: undefined });
+      return this.namedValidator(desc.ref, bodyExpr, desc.maxDepth, desc.description);
+    }
+
     // Literal value
     if (name === 'Literal') {
       const val = desc.constraints?.[0]?.replace('equals ', '') ?? 'None';
@@ -46,7 +83,7 @@ export class PythonGenerator extends CodeGenerator {
 
     // Pattern
     if (name === 'Pattern') {
-      const pattern = desc.constraints?.[0]?.replace('matches ', '').replace(/^`|`$/g, '') ?? '';
+      const pattern = regexSource(desc.constraints?.[0]?.replace('matches ', '').replace(/^`|`$/g, '') ?? '');
       return `lambda v, p, i: validate_pattern(v, p, i, ${this.escapeString(pattern)})`;
     }
 
@@ -56,7 +93,7 @@ export class PythonGenerator extends CodeGenerator {
       const args: string[] = [];
       if (opts.minLength !== undefined) args.push(`min_length=${opts.minLength}`);
       if (opts.maxLength !== undefined) args.push(`max_length=${opts.maxLength}`);
-      if (opts.pattern) args.push(`pattern=${this.escapeString(opts.pattern)}`);
+      if (opts.pattern) args.push(`pattern=${this.escapeString(regexSource(opts.pattern))}`);
 
       if (args.length === 0) {
         return 'validate_str';
@@ -78,6 +115,21 @@ export class PythonGenerator extends CodeGenerator {
       return `lambda v, p, i: validate_num(v, p, i, ${args.join(', ')})`;
     }
 
+    // Decimal
+    if (name === 'Decimal') {
+      const opts = extractDecimalConstraints(desc.constraints);
+      const args: string[] = [];
+      if (opts.min !== undefined) args.push(`min_val=${this.escapeString(opts.min)}`);
+      if (opts.max !== undefined) args.push(`max_val=${this.escapeString(opts.max)}`);
+      if (opts.precision !== undefined) args.push(`precision=${opts.precision}`);
+      if (opts.scale !== undefined) args.push(`scale=${opts.scale}`);
+
+      if (args.length === 0) {
+        return 'validate_decimal';
+      }
+      return `lambda v, p, i: validate_decimal(v, p, i, ${args.join(', ')})`;
+    }
+
     // Boolean
     if (name === 'Boolean') {
       return 'validate_bool';
@@ -89,6 +141,26 @@ export class PythonGenerator extends CodeGenerator {
       return `lambda v, p, i: validate_oneof(v, p, i, [${options.join(', ')}])`;
     }
 
+    // AllOf
+    if (name === 'AllOf' && desc.allOf) {
+      const parts = desc.allOf.map(part => this.generateDataValidatorExpr(part));
+      return `lambda v, p, i: validate_allof(v, p, i, [${parts.join(', ')}])`;
+    }
+
+    // Not
+    if (name === 'Not' && desc.not) {
+      const inner = this.generateDataValidatorExpr(desc.not);
+      return `lambda v, p, i: validate_not(v, p, i, ${inner})`;
+    }
+
+    // TaggedUnion
+    if (name === 'TaggedUnion' && desc.discriminator !== undefined && desc.variants) {
+      const variants = Object.entries(desc.variants).map(
+        ([tag, variant]) => `${this.escapeString(tag)}: ${this.generateDataValidatorExpr(variant)}`
+      );
+      return `lambda v, p, i: validate_tagged_union(v, p, i, ${this.escapeString(desc.discriminator)}, {${variants.join(', ')}})`;
+    }
+
     // ListOf
     if (name === 'ListOf' && desc.itemType) {
       const itemExpr = this.generateDataValidatorExpr(desc.itemType);
@@ -96,6 +168,10 @@ export class PythonGenerator extends CodeGenerator {
       const args: string[] = [`item_validator=${itemExpr}`];
       if (opts.minItems !== undefined) args.push(`min_items=${opts.minItems}`);
       if (opts.maxItems !== undefined) args.push(`max_items=${opts.maxItems}`);
+      if (opts.unique) args.push('unique=True');
+      if (opts.uniqueBy !== undefined) args.push(`unique_by=${this.escapeString(opts.uniqueBy)}`);
+      if (opts.sorted !== undefined) args.push(`sort_order=${this.escapeString(opts.sorted)}`);
+      if (opts.sortedBy !== undefined) args.push(`sort_by=${this.escapeString(opts.sortedBy)}`);
       return `lambda v, p, i: validate_list(v, p, i, ${args.join(', ')})`;
     }
 
@@ -109,6 +185,16 @@ export class PythonGenerator extends CodeGenerator {
       if (desc.oneOf) {
         const options = desc.oneOf.map(opt => this.generateDataValidatorExpr(opt));
         valueExpr = `lambda v, p, i: validate_oneof(v, p, i, [${options.join(', ')}])`;
+      } else if (desc.allOf) {
+        valueExpr = this.generateDataValidatorExpr({ name: 'AllOf', allOf: desc.allOf });
+      } else if (desc.not) {
+        valueExpr = this.generateDataValidatorExpr({ name: 'Not', not: desc.not });
+      } else if (desc.variants) {
+        valueExpr = this.generateDataValidatorExpr({
+          name: 'TaggedUnion',
+          discriminator: desc.discriminator,
+          variants: desc.variants,
+        });
       } else if (desc.itemType) {
         valueExpr = this.generateDataValidatorExpr({
           name: 'ListOf',
@@ -124,6 +210,26 @@ export class PythonGenerator extends CodeGenerator {
         });
       }
 
+      if (valueExpr && desc.ref !== undefined) {
+        valueExpr = this.namedValidator(desc.ref, desc.summary === 'Ref' ? null : valueExpr, desc.maxDepth, desc.valueDescription);
+      }
+      if (valueExpr && desc.valueDeprecated) {
+        valueExpr = `lambda v, p, i: validate_deprecated(v, p, i, ${valueExpr}, "type.deprecated", ${this.escapeString(desc.valueDeprecated)})`;
+      }
+      if (valueExpr && desc.customIssue) {
+        valueExpr = `lambda v, p, i: validate_message(v, p, i, ${valueExpr}${this.messageArgs(desc.customIssue)})`;
+      }
+
+      if (valueExpr && desc.nullable) {
+        valueExpr = `lambda v, p, i: validate_nullable(v, p, i, ${valueExpr})`;
+      }
+      if (valueExpr && desc.meta) {
+        valueExpr = `lambda v, p, i: validate_meta(v, p, i, ${valueExpr}, ${this.escapeString(JSON.stringify(desc.meta))})`;
+      }
+      if (desc.deprecated) {
+        valueExpr = `lambda v, p, i: validate_deprecated(v, p, i, ${valueExpr ?? 'lambda v, p, i: None'}, "field.deprecated", ${this.escapeString(desc.deprecated)})`;
+      }
+
       if (valueExpr) {
         args.push(`validator=${valueExpr}`);
       }
@@ -147,17 +253,19 @@ export class PythonGenerator extends CodeGenerator {
   ): string {
     const fieldExprs: string[] = [];
 
-    for (const child of children.required ?? []) {
-      fieldExprs.push(this.generateDataValidatorExpr(child));
-    }
-    for (const child of children.optional ?? []) {
-      fieldExprs.push(this.generateDataValidatorExpr({ ...child, optional: true }));
+    for (const { desc, optional } of this.scheduleChildren(children)) {
+      fieldExprs.push(this.generateDataValidatorExpr(optional ? { ...desc, optional: true } : desc));
     }
 
     if (fieldExprs.length === 0) {
       return 'validate_object';
     }
 
+    if (this.options.failFast) {
+      const checks = fieldExprs.map(expr => `lambda: (${expr})(v, p, i)`).join(', ');
+      return `lambda v, p, i: validate_object(v, p, i) and run_checks(i, [${checks}])`;
+    }
+
     const fieldCalls = fieldExprs.map(expr => `(${expr})(v, p, i)`).join(', ');
     return `lambda v, p, i: validate_object(v, p, i) and [${fieldCalls}]`;
   }
@@ -167,17 +275,19 @@ export class PythonGenerator extends CodeGenerator {
   ): string {
     const parts: string[] = [];
 
-    for (const child of children.required ?? []) {
-      parts.push(this.generateFSChildExpr(child));
-    }
-    for (const child of children.optional ?? []) {
-      parts.push(this.generateFSChildExpr(child));
+    for (const { desc } of this.scheduleChildren(children)) {
+      parts.push(this.generateFSChildExpr(desc));
     }
 
     if (parts.length === 0) {
       return 'lambda ctx, p, i: None';
     }
 
+    if (this.options.failFast) {
+      const checks = parts.map(part => `lambda: ${part}`).join(', ');
+      return `lambda ctx, p, i: run_checks(i, [${checks}])`;
+    }
+
     const calls = parts.join(', ');
     return `lambda ctx, p, i: [${calls}]`;
   }
@@ -186,6 +296,16 @@ export class PythonGenerator extends CodeGenerator {
     const fsType = desc.fsType;
     const comment = desc.description ? `  # ${desc.description}\n        ` : '';
 
+    if (desc.name === 'When' && desc.condition) {
+      const { file, field, is } = desc.condition;
+      const args = [this.escapeString(file)];
+      if (field !== undefined) args.push(`field=${this.escapeString(field)}`);
+      if (is) args.push(`is_=${this.generateDataValidatorExpr(is)}`);
+      if (desc.then) args.push(`then=${this.generateBundleContentExpr(whenBranch(desc.then))}`);
+      if (desc.else) args.push(`otherwise=${this.generateBundleContentExpr(whenBranch(desc.else))}`);
+      return `${comment}validate_when(ctx, p, i, ${args.join(', ')})`;
+    }
+
     if (fsType === 'jsonFile' && desc.filePath) {
       const contentExpr = desc.children ? this.generateObjectExpr(desc.children) : 'None';
       return `${comment}validate_json_file(ctx, ${this.escapeString(desc.filePath)}, p, i, content_validator=${contentExpr})`;
@@ -193,7 +313,12 @@ export class PythonGenerator extends CodeGenerator {
 
     if (fsType === 'file' && desc.filePath) {
       const ext = desc.fileExt ? `, ext=${this.escapeString(desc.fileExt)}` : '';
-      return `${comment}validate_fs_file(ctx, ${this.escapeString(desc.filePath)}, p, i${ext})`;
+      const fileCheck = `validate_fs_file(ctx, ${this.escapeString(desc.filePath)}, p, i${ext})`;
+      if (desc.checksum) {
+        const { algorithm, value } = desc.checksum;
+        return `${comment}${fileCheck} and validate_file_checksum(ctx, ${this.escapeString(desc.filePath)}, p, i, ${this.escapeString(algorithm)}, ${this.escapeString(value)})`;
+      }
+      return `${comment}${fileCheck}`;
     }
 
     if (fsType === 'directory' && desc.filePath) {
@@ -270,6 +395,42 @@ if __name__ == "__main__":
     return `_root_validator = ${rootExpr}`;
   }
 
+  protected definitionIdent(words: string[]): string {
+    return `validate_def_${words.join('_')}`;
+  }
+
+  protected generateDefinition(name: string, ident: string, expr: string, maxDepth?: number): string {
+    if (maxDepth === undefined) {
+      return [
+        `def ${ident}(v, p, i):`,
+        `    (${expr})(v, p, i)`,
+      ].join('\n');
+    }
+    const message = this.escapeString(`Nesting of ${name} exceeds maximum depth ${maxDepth}`);
+    return [
+      `_${ident}_depth = [0]`,
+      '',
+      '',
+      `def ${ident}(v, p, i):`,
+      `    if _${ident}_depth[0] >= ${maxDepth}:`,
+      `        add_issue(i, p, "recursion.too_deep", ${message})`,
+      '        return',
+      `    _${ident}_depth[0] += 1`,
+      '    try:',
+      `        (${expr})(v, p, i)`,
+      '    finally:',
+      `        _${ident}_depth[0] -= 1`,
+    ].join('\n');
+  }
+
+  /** Keyword arguments for validate_message */
+  private messageArgs(customIssue: NonNullable<TypeDescription['customIssue']>): string {
+    let args = '';
+    if (customIssue.code !== undefined) args += `, code=${this.escapeString(customIssue.code)}`;
+    if (customIssue.message !== undefined) args += `, message=${this.escapeString(customIssue.message)}`;
+    return args;
+  }
+
   protected commentLine(text: string): string {
     return `# ${text}`;
   }
@@ -278,7 +439,7 @@ if __name__ == "__main__":
 /**
  * Generate Python validator code from TypeDescription
  */
-export function generatePython(desc: TypeDescription): string {
-  const generator = new PythonGenerator();
+export function generatePython(desc: TypeDescription, options: CodegenOptions = {}): string {
+  const generator = new PythonGenerator(options);
   return generator.generate(desc);
 }
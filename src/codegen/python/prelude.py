@@ -6,6 +6,8 @@ import os
 import json
 import zipfile
 import re
+import decimal
+import hashlib
 
 # Type aliases
 Issues: TypeAlias = list[dict[str, Any]]
@@ -14,7 +16,17 @@ Validator: TypeAlias = Callable[[Any, list[str], Issues], None]
 
 def add_issue(issues: Issues, path: list[str], code: str, message: str) -> None:
     """Add a validation issue."""
-    issues.append({"path": ".".join(path) if path else "(root)", "code": code, "message": message})
+    issues.append({"level": "error", "path": ".".join(path) if path else "(root)", "code": code, "message": message})
+
+
+def add_warning(issues: Issues, path: list[str], code: str, message: str) -> None:
+    """Add a warning; warnings do not fail validation."""
+    issues.append({"level": "warning", "path": ".".join(path) if path else "(root)", "code": code, "message": message})
+
+
+def error_count(issues: Issues) -> int:
+    """Number of error-level issues."""
+    return sum(1 for issue in issues if issue["level"] == "error")
 
 
 # === Primitive validators ===
@@ -52,6 +64,46 @@ def validate_num(value: Any, path: list[str], issues: Issues,
         add_issue(issues, path, "num.too_large", f"Number {value} exceeds maximum {max_val}")
 
 
+def validate_decimal(value: Any, path: list[str], issues: Issues,
+                     min_val: str | None = None,
+                     max_val: str | None = None,
+                     precision: int | None = None,
+                     scale: int | None = None) -> None:
+    """Validate number value with exact decimal arithmetic."""
+    if isinstance(value, bool) or not isinstance(value, (int, float, decimal.Decimal)):
+        add_issue(issues, path, "type.mismatch", f"Expected number, got {type(value).__name__}")
+        return
+    try:
+        num = decimal.Decimal(str(value))
+    except decimal.InvalidOperation:
+        add_issue(issues, path, "type.mismatch", f"Expected decimal number, got {value}")
+        return
+    if not num.is_finite():
+        add_issue(issues, path, "type.mismatch", f"Expected decimal number, got {value}")
+        return
+
+    if num == 0:
+        int_digits, places = 0, 0
+    else:
+        # Not normalize(): it rounds to the context precision (28 digits)
+        _, digit_tuple, exponent = num.as_tuple()
+        significant = len(digit_tuple)
+        while digit_tuple[significant - 1] == 0:
+            significant -= 1
+            exponent += 1
+        places = max(0, -exponent)
+        int_digits = max(0, significant + exponent)
+    digits = int_digits + places
+    if precision is not None and digits > precision:
+        add_issue(issues, path, "decimal.too_many_digits", f"Number {value} has {digits} digits, maximum is {precision}")
+    if scale is not None and places > scale:
+        add_issue(issues, path, "decimal.scale_exceeded", f"Number {value} has {places} decimal places, maximum is {scale}")
+    if min_val is not None and num < decimal.Decimal(min_val):
+        add_issue(issues, path, "num.too_small", f"Number {value} is less than minimum {min_val}")
+    if max_val is not None and num > decimal.Decimal(max_val):
+        add_issue(issues, path, "num.too_large", f"Number {value} exceeds maximum {max_val}")
+
+
 def validate_bool(value: Any, path: list[str], issues: Issues) -> None:
     """Validate boolean value."""
     if not isinstance(value, bool):
@@ -99,10 +151,24 @@ def validate_field(obj: Any, path: list[str], issues: Issues,
         validator(obj[key], path + [key], issues)
 
 
+def get_key_path(value: Any, key_path: str) -> Any:
+    """Resolve a dotted key path (e.g. "meta.id") inside a dict, or None if absent."""
+    current = value
+    for key in key_path.split('.'):
+        if not isinstance(current, dict) or key not in current:
+            return None
+        current = current[key]
+    return current
+
+
 def validate_list(value: Any, path: list[str], issues: Issues,
                   item_validator: Validator | None = None,
                   min_items: int | None = None,
-                  max_items: int | None = None) -> None:
+                  max_items: int | None = None,
+                  unique: bool = False,
+                  unique_by: str | None = None,
+                  sort_order: str | None = None,
+                  sort_by: str | None = None) -> None:
     """Validate list/array value."""
     if not isinstance(value, list):
         add_issue(issues, path, "type.mismatch", f"Expected array, got {type(value).__name__}")
@@ -113,6 +179,39 @@ def validate_list(value: Any, path: list[str], issues: Issues,
     if max_items is not None and len(value) > max_items:
         add_issue(issues, path, "list.too_long", f"Array length {len(value)} exceeds maximum {max_items}")
 
+    if unique or unique_by is not None:
+        seen: dict[str, int] = {}
+        for i, item in enumerate(value):
+            key_value = get_key_path(item, unique_by) if unique_by is not None else item
+            if unique_by is not None and key_value is None:
+                continue
+            key = json.dumps(key_value, sort_keys=True)
+            if key in seen:
+                if unique_by is not None:
+                    message = f"Items [{seen[key]}] and [{i}] share {unique_by} {key}"
+                else:
+                    message = f"Items [{seen[key]}] and [{i}] are duplicates"
+                add_issue(issues, path + [f"[{i}]"], "list.duplicate", message)
+            else:
+                seen[key] = i
+
+    if sort_order is not None or sort_by is not None:
+        descending = sort_order == "desc"
+        # Only the first out-of-order pair is reported
+        prev: tuple[int, Any] | None = None
+        for i, item in enumerate(value):
+            key = get_key_path(item, sort_by) if sort_by is not None else item
+            if isinstance(key, bool) or not isinstance(key, (int, float, str)):
+                continue
+            if prev is not None and isinstance(prev[1], str) == isinstance(key, str):
+                if (key > prev[1]) if descending else (key < prev[1]):
+                    order = "descending" if descending else "ascending"
+                    by = f" by {sort_by}" if sort_by is not None else ""
+                    add_issue(issues, path + [f"[{i}]"], "list.not_sorted",
+                              f"Items [{prev[0]}] and [{i}] are not in {order} order{by}")
+                    break
+            prev = (i, key)
+
     if item_validator is not None:
         for i, item in enumerate(value):
             item_validator(item, path + [f"[{i}]"], issues)
@@ -125,8 +224,9 @@ def validate_oneof(value: Any, path: list[str], issues: Issues,
     for validator in validators:
         test_issues: Issues = []
         validator(value, path, test_issues)
-        if not test_issues:
-            return  # Matched
+        if error_count(test_issues) == 0:
+            issues.extend(test_issues)  # Matched; keep its warnings
+            return
 
     desc = ", ".join(descriptions) if descriptions else "any of the options"
     add_issue(issues, path, "oneof.no_match", f"Value does not match {desc}")
@@ -136,7 +236,95 @@ def matches_validator(value: Any, validator: Validator) -> bool:
     """Test if value matches a validator without adding issues."""
     test_issues: Issues = []
     validator(value, [], test_issues)
-    return len(test_issues) == 0
+    return error_count(test_issues) == 0
+
+
+def validate_allof(value: Any, path: list[str], issues: Issues,
+                   validators: list[Validator]) -> None:
+    """Validate value against every validator; issues shared by several are reported once."""
+    merged: Issues = []
+    for validator in validators:
+        part_issues: Issues = []
+        validator(value, path, part_issues)
+        for issue in part_issues:
+            if issue not in merged:
+                merged.append(issue)
+    issues.extend(merged)
+
+
+def validate_tagged_union(value: Any, path: list[str], issues: Issues,
+                          discriminator: str,
+                          variants: dict[str, Validator]) -> None:
+    """Validate value against the variant selected by its discriminator field."""
+    if not isinstance(value, dict):
+        add_issue(issues, path, "type.mismatch", f"Expected object, got {type(value).__name__}")
+        return
+    if discriminator not in value:
+        add_issue(issues, path, "field.missing", f"Missing required field: {discriminator}")
+        return
+    tag = value[discriminator]
+    # Only the variant selected by the tag runs
+    if isinstance(tag, str) and tag in variants:
+        variants[tag](value, path, issues)
+        return
+    add_issue(issues, path + [discriminator], "union.unknown_tag",
+              f"Unknown {discriminator} {json.dumps(tag)}; expected one of: {', '.join(variants)}")
+
+
+def validate_not(value: Any, path: list[str], issues: Issues,
+                 inner: Validator) -> None:
+    """Validate value does NOT match the inner validator."""
+    test_issues: Issues = []
+    inner(value, path, test_issues)
+    if error_count(test_issues) == 0:
+        add_issue(issues, path, "not.matched", "Value matches a disallowed schema")
+
+
+def validate_nullable(value: Any, path: list[str], issues: Issues,
+                      inner: Validator) -> None:
+    """Validate value is null or matches the inner validator."""
+    if value is None:
+        return
+    inner(value, path, issues)
+
+
+def validate_message(value: Any, path: list[str], issues: Issues, inner: Validator,
+                     code: str | None = None, message: str | None = None) -> None:
+    """Run the inner validator; report any failure as one issue with a spec-defined code/message."""
+    inner_issues: Issues = []
+    inner(value, path, inner_issues)
+    issues.extend(issue for issue in inner_issues if issue["level"] == "warning")
+    errors = [issue for issue in inner_issues if issue["level"] == "error"]
+    if errors:
+        add_issue(issues, path, code or errors[0]["code"], message or errors[0]["message"])
+
+
+def validate_deprecated(value: Any, path: list[str], issues: Issues, inner: Validator,
+                        code: str, message: str) -> None:
+    """Warn that a deprecated field or type is used, then validate it as usual."""
+    add_warning(issues, path, code, message)
+    inner(value, path, issues)
+
+
+def validate_meta(value: Any, path: list[str], issues: Issues, inner: Validator, meta: str) -> None:
+    """Attach metadata (a JSON object) to the issues of the inner validator; keys already on an issue win."""
+    inner_issues: Issues = []
+    inner(value, path, inner_issues)
+    if not inner_issues:
+        return
+    parsed = json.loads(meta)
+    for issue in inner_issues:
+        issue["meta"] = {**parsed, **issue.get("meta", {})}
+    issues.extend(inner_issues)
+
+
+def run_checks(issues: Issues, checks: list[Callable[[], Any]]) -> None:
+    """Run checks in order, stopping after the first one that reports errors."""
+    start = error_count(issues)
+    for check in checks:
+        check()
+        if error_count(issues) > start:
+            return
 
 
 # === File system context ===
@@ -205,6 +393,14 @@ class FSContext:
             with open(os.path.join(self.base_path, rel_path), 'r', encoding='utf-8') as f:
                 return f.read()
 
+    def read_bytes(self, rel_path: str) -> bytes:
+        """Read raw file content."""
+        if self.is_zip:
+            return self.zip_file.read(rel_path)
+        else:
+            with open(os.path.join(self.base_path, rel_path), 'rb') as f:
+                return f.read()
+
     def read_json(self, rel_path: str) -> Any:
         """Read and parse JSON file."""
         return json.loads(self.read(rel_path))
@@ -327,6 +523,40 @@ def validate_fs_file(ctx: FSContext, rel_path: str, path: list[str], issues: Iss
     return True
 
 
+# Digests available to checksum rules; register more (e.g. blake3) by name
+DIGESTS: dict[str, Callable[[bytes], str]] = {
+    "sha256": lambda data: hashlib.sha256(data).hexdigest(),
+    "sha384": lambda data: hashlib.sha384(data).hexdigest(),
+    "sha512": lambda data: hashlib.sha512(data).hexdigest(),
+}
+
+
+def validate_file_checksum(ctx: FSContext, rel_path: str, path: list[str], issues: Issues,
+                           algorithm: str, expected: str) -> bool:
+    """Validate a file's digest within a bundle context."""
+    file_path = path + [rel_path]
+
+    digest = DIGESTS.get(algorithm.lower())
+    if digest is None:
+        add_issue(issues, file_path, "checksum.unsupported_algorithm",
+                  f"Digest algorithm not available: {algorithm}")
+        return False
+
+    try:
+        data = ctx.read_bytes(rel_path)
+    except (OSError, KeyError) as e:
+        add_issue(issues, file_path, "file.read_error", str(e))
+        return False
+
+    actual = digest(data)
+    if actual != expected.lower():
+        add_issue(issues, file_path, "checksum.mismatch",
+                  f"{algorithm} digest mismatch: expected {expected}, got {actual}")
+        return False
+
+    return True
+
+
 def validate_fs_directory(ctx: FSContext, rel_path: str, path: list[str], issues: Issues) -> bool:
     """Validate a directory exists within a bundle context."""
     dir_path = path + [rel_path]
@@ -342,6 +572,36 @@ def validate_fs_directory(ctx: FSContext, rel_path: str, path: list[str], issues
     return True
 
 
+_MISSING = object()
+
+
+def validate_when(ctx: FSContext, path: list[str], issues: Issues, file: str,
+                  field: str | None = None, is_: Validator | None = None,
+                  then: FSValidator | None = None, otherwise: FSValidator | None = None) -> None:
+    """Check `then` when the value at `field` of the JSON `file` is present and matches `is_`,
+    and `otherwise` when not; a file that cannot be read or parsed decides nothing."""
+    try:
+        value: Any = ctx.read_json(file)
+    except Exception:
+        return
+    for segment in [] if field is None else field.split('.'):
+        if isinstance(value, dict):
+            value = value.get(segment, _MISSING)
+        elif isinstance(value, list) and segment.isdigit() and int(segment) < len(value):
+            value = value[int(segment)]
+        else:
+            value = _MISSING
+
+    holds = value is not _MISSING
+    if holds and is_ is not None:
+        test: Issues = []
+        is_(value, path, test)
+        holds = error_count(test) == 0
+    content = then if holds else otherwise
+    if content is not None:
+        content(ctx, path, issues)
+
+
 # === Entry points ===
 
 def validate(value: Any, validator: Validator) -> dict[str, Any]:
@@ -349,7 +609,7 @@ def validate(value: Any, validator: Validator) -> dict[str, Any]:
     issues: Issues = []
     validator(value, [], issues)
     return {
-        "ok": len(issues) == 0,
+        "ok": error_count(issues) == 0,
         "issues": issues
     }
 
@@ -361,6 +621,6 @@ def validate_path(path: str, validator: Callable[[str, list[str], Issues], FSCon
     if ctx:
         ctx.close()
     return {
-        "ok": len(issues) == 0,
+        "ok": error_count(issues) == 0,
         "issues": issues
     }
@@ -8,14 +8,23 @@ import type { TypeDescription } from '../../base.js';
 import {
   CodeGenerator,
   type LanguageConfig,
+  type CodegenOptions,
   extractStringConstraints,
+  regexSource,
   extractNumberConstraints,
+  extractDecimalConstraints,
   extractListConstraints,
   extractBundleAccept,
+  whenBranch,
 } from '../base.js';
+import { RustTypeEmitter } from './types.js';
+import { RustPropertyTestEmitter } from './proptests.js';
 
 const __dirname = path.dirname(fileURLToPath(import.meta.url));
 
+/** specspec-prelude crate version generated code is pinned to (crates/specspec-prelude) */
+export const PRELUDE_CRATE_VERSION = '0.3.0';
+
 /**
  * Rust code generator
  */
@@ -26,6 +35,9 @@ export class RustGenerator extends CodeGenerator {
     preludeFile: 'prelude.rs',
   };
 
+  /** Content check of the bundle being generated, shared by validate_root and validate_shared */
+  private bundleContentExpr = '|_, _, _| {}';
+
   escapeString(s: string): string {
     // Rust string escaping
     return '"' + s.replace(/\\/g, '\\\\').replace(/"/g, '\\"').replace(/\n/g, '\\n') + '"';
@@ -33,12 +45,65 @@ export class RustGenerator extends CodeGenerator {
 
   loadPrelude(): string {
     const preludePath = path.join(__dirname, this.config.preludeFile);
-    return fs.readFileSync(preludePath, 'utf-8');
+    const prelude = fs.readFileSync(preludePath, 'utf-8');
+    if (!this.options.slim) {
+      return prelude;
+    }
+    return prelude
+      .replace(/^\/\/ --- fs ---\n[\s\S]*?^\/\/ --- end fs ---\n/gm, '')
+      .replace('\n\n', '\n// Slim: no filesystem or zip support; needs only serde, serde_json and regex\n\n');
+  }
+
+  protected preludeImport(): string {
+    return [
+      '// SpecSpec Rust Prelude',
+      '// Validation primitives come from the specspec-prelude crate; in Cargo.toml:',
+      this.options.slim
+        ? `//   specspec-prelude = { version = "=${PRELUDE_CRATE_VERSION}", default-features = false }`
+        : `//   specspec-prelude = "=${PRELUDE_CRATE_VERSION}"`,
+      '',
+      ...(this.options.types ? ['use serde::{Deserialize, Serialize};'] : []),
+      'use serde_json::Value;',
+      'use specspec_prelude::*;',
+    ].join('\n');
   }
 
   generateDataValidatorExpr(desc: TypeDescription): string {
     const name = desc.name;
 
+    // Metadata attached to every issue of the type (fields wrap their value validator below)
+    if (desc.meta && name !== 'Field') {
+      const innerExpr = this.generateDataValidatorExpr({ ...desc, meta: undefined });
+      return `|v, p, i| validate_meta(v, p, i, &(${innerExpr}), ${this.escapeString(JSON.stringify(desc.meta))})`;
+    }
+
+    // Nullable (fields wrap their value validator below)
+    if (desc.nullable && name !== 'Field') {
+      const innerExpr = this.generateDataValidatorExpr({ ...desc, nullable: false });
+      return `|v, p, i| validate_nullable(v, p, i, &(${innerExpr}))`;
+    }
+
+    // Spec-defined code/message: report the inner failure as one issue
+    if (desc.customIssue && name !== 'Field') {
+      const innerExpr = this.generateDataValidatorExpr({ ...desc, customIssue: undefined });
+      return `|v, p, i| validate_message(v, p, i, &(${innerExpr}), ${this.messageArgs(desc.customIssue)})`;
+    }
+
+    // Deprecated type: warn, then validate as usual
+    if (desc.deprecated && name !== 'Field') {
+      const innerExpr = this.generateDataValidatorExpr({ ...desc, deprecated: undefined });
+      return `|v, p, i| validate_deprecated(v, p, i, &(${innerExpr}), "type.deprecated", ${this.escapeString(desc.deprecated)})`;
+    }
+
+    // Named type: emitted once as a function, referenced by name
+    if (name === 'Ref' && desc.ref !== undefined) {
+      return this.namedValidator(desc.ref, null);
+    }
+    if (desc.ref !== undefined && name !== 'Field') {
+      const bodyExpr = this.generateDataValidatorExpr({ ...desc, ref: undefined, maxDepth: undefined });
+      return this.namedValidator(desc.ref, bodyExpr, desc.maxDepth, desc.description);
+    }
+
     // Literal value
     if (name === 'Literal') {
       const rawVal = desc.constraints?.[0]?.replace('equals ', '') ?? 'null';
@@ -54,7 +119,7 @@ export class RustGenerator extends CodeGenerator {
 
     // Pattern
     if (name === 'Pattern') {
-      const pattern = desc.constraints?.[0]?.replace('matches ', '').replace(/^`|`$/g, '') ?? '';
+      const pattern = regexSource(desc.constraints?.[0]?.replace('matches ', '').replace(/^`|`$/g, '') ?? '');
       return `|v, p, i| validate_pattern(v, p, i, ${this.escapeString(pattern)})`;
     }
 
@@ -64,7 +129,7 @@ export class RustGenerator extends CodeGenerator {
       const args: string[] = [];
       args.push(opts.minLength !== undefined ? `Some(${opts.minLength})` : 'None');
       args.push(opts.maxLength !== undefined ? `Some(${opts.maxLength})` : 'None');
-      args.push(opts.pattern ? `Some(${this.escapeString(opts.pattern)})` : 'None');
+      args.push(opts.pattern ? `Some(${this.escapeString(regexSource(opts.pattern))})` : 'None');
 
       return `|v, p, i| validate_str(v, p, i, ${args.join(', ')})`;
     }
@@ -80,6 +145,18 @@ export class RustGenerator extends CodeGenerator {
       return `|v, p, i| validate_num(v, p, i, ${args.join(', ')})`;
     }
 
+    // Decimal (exact, compared on the number's source text)
+    if (name === 'Decimal') {
+      const opts = extractDecimalConstraints(desc.constraints);
+      const args: string[] = [];
+      args.push(opts.min !== undefined ? `Some(${this.escapeString(opts.min)})` : 'None');
+      args.push(opts.max !== undefined ? `Some(${this.escapeString(opts.max)})` : 'None');
+      args.push(opts.precision !== undefined ? `Some(${opts.precision})` : 'None');
+      args.push(opts.scale !== undefined ? `Some(${opts.scale})` : 'None');
+
+      return `|v, p, i| validate_decimal(v, p, i, ${args.join(', ')})`;
+    }
+
     // Boolean
     if (name === 'Boolean') {
       return '|v, p, i| validate_bool(v, p, i)';
@@ -91,15 +168,41 @@ export class RustGenerator extends CodeGenerator {
       return `|v, p, i| validate_oneof(v, p, i, &[${options.join(', ')}])`;
     }
 
+    // AllOf
+    if (name === 'AllOf' && desc.allOf) {
+      const parts = desc.allOf.map(part => `&(${this.generateDataValidatorExpr(part)})`);
+      return `|v, p, i| validate_allof(v, p, i, &[${parts.join(', ')}])`;
+    }
+
+    // Not
+    if (name === 'Not' && desc.not) {
+      const inner = this.generateDataValidatorExpr(desc.not);
+      return `|v, p, i| validate_not(v, p, i, &(${inner}))`;
+    }
+
+    // TaggedUnion
+    if (name === 'TaggedUnion' && desc.discriminator !== undefined && desc.variants) {
+      const variants = Object.entries(desc.variants).map(
+        ([tag, variant]) => `(${this.escapeString(tag)}, &(${this.generateDataValidatorExpr(variant)}))`
+      );
+      return `|v, p, i| validate_tagged_union(v, p, i, ${this.escapeString(desc.discriminator)}, &[${variants.join(', ')}])`;
+    }
+
     // ListOf
     if (name === 'ListOf' && desc.itemType) {
       const itemExpr = this.generateDataValidatorExpr(desc.itemType);
       const opts = extractListConstraints(desc.constraints);
-      const args: string[] = [];
-      args.push(`Some(&(${itemExpr}))`);
-      args.push(opts.minItems !== undefined ? `Some(${opts.minItems})` : 'None');
-      args.push(opts.maxItems !== undefined ? `Some(${opts.maxItems})` : 'None');
-      return `|v, p, i| validate_list(v, p, i, ${args.join(', ')})`;
+      const fields: string[] = [];
+      if (opts.minItems !== undefined) fields.push(`min_items: Some(${opts.minItems})`);
+      if (opts.maxItems !== undefined) fields.push(`max_items: Some(${opts.maxItems})`);
+      if (opts.unique) fields.push('unique: true');
+      if (opts.uniqueBy !== undefined) fields.push(`unique_by: Some(${this.escapeString(opts.uniqueBy)})`);
+      if (opts.sorted !== undefined) fields.push(`sorted: Some(${this.escapeString(opts.sorted)})`);
+      if (opts.sortedBy !== undefined) fields.push(`sorted_by: Some(${this.escapeString(opts.sortedBy)})`);
+      const options = fields.length > 0
+        ? `&ListOptions { ${fields.join(', ')}, ..ListOptions::default() }`
+        : '&ListOptions::default()';
+      return `|v, p, i| validate_list(v, p, i, Some(&(${itemExpr})), ${options})`;
     }
 
     // Field
@@ -111,6 +214,16 @@ export class RustGenerator extends CodeGenerator {
       if (desc.oneOf) {
         const options = desc.oneOf.map(opt => `&(${this.generateDataValidatorExpr(opt)})`);
         valueExpr = `|v, p, i| validate_oneof(v, p, i, &[${options.join(', ')}])`;
+      } else if (desc.allOf) {
+        valueExpr = this.generateDataValidatorExpr({ name: 'AllOf', allOf: desc.allOf });
+      } else if (desc.not) {
+        valueExpr = this.generateDataValidatorExpr({ name: 'Not', not: desc.not });
+      } else if (desc.variants) {
+        valueExpr = this.generateDataValidatorExpr({
+          name: 'TaggedUnion',
+          discriminator: desc.discriminator,
+          variants: desc.variants,
+        });
       } else if (desc.itemType) {
         valueExpr = this.generateDataValidatorExpr({
           name: 'ListOf',
@@ -126,6 +239,26 @@ export class RustGenerator extends CodeGenerator {
         });
       }
 
+      if (valueExpr && desc.ref !== undefined) {
+        valueExpr = this.namedValidator(desc.ref, desc.summary === 'Ref' ? null : valueExpr, desc.maxDepth, desc.valueDescription);
+      }
+      if (valueExpr && desc.valueDeprecated) {
+        valueExpr = `|v, p, i| validate_deprecated(v, p, i, &(${valueExpr}), "type.deprecated", ${this.escapeString(desc.valueDeprecated)})`;
+      }
+      if (valueExpr && desc.customIssue) {
+        valueExpr = `|v, p, i| validate_message(v, p, i, &(${valueExpr}), ${this.messageArgs(desc.customIssue)})`;
+      }
+
+      if (valueExpr && desc.nullable) {
+        valueExpr = `|v, p, i| validate_nullable(v, p, i, &(${valueExpr}))`;
+      }
+      if (valueExpr && desc.meta) {
+        valueExpr = `|v, p, i| validate_meta(v, p, i, &(${valueExpr}), ${this.escapeString(JSON.stringify(desc.meta))})`;
+      }
+      if (desc.deprecated) {
+        valueExpr = `|v, p, i| validate_deprecated(v, p, i, &(${valueExpr ?? '|_, _, _| {}'}), "field.deprecated", ${this.escapeString(desc.deprecated)})`;
+      }
+
       const validatorArg = valueExpr ? `Some(&(${valueExpr}))` : 'None';
       return `|v, p, i| validate_field(v, p, i, ${this.escapeString(key)}, ${validatorArg}, ${optional})`;
     }
@@ -144,19 +277,16 @@ export class RustGenerator extends CodeGenerator {
   ): string {
     const fieldExprs: string[] = [];
 
-    for (const child of children.required ?? []) {
-      fieldExprs.push(this.generateDataValidatorExpr(child));
-    }
-    for (const child of children.optional ?? []) {
-      fieldExprs.push(this.generateDataValidatorExpr({ ...child, optional: true }));
+    for (const { desc, optional } of this.scheduleChildren(children)) {
+      fieldExprs.push(this.generateDataValidatorExpr(optional ? { ...desc, optional: true } : desc));
     }
 
     if (fieldExprs.length === 0) {
       return '|v, p, i| { validate_object(v, p, i); }';
     }
 
-    const fieldCalls = fieldExprs.map(expr => `(${expr})(v, p, i)`).join('; ');
-    return `|v, p, i| { if validate_object(v, p, i) { ${fieldCalls}; } }`;
+    const fieldCalls = this.joinChecks(fieldExprs.map(expr => `(${expr})(v, p, i)`));
+    return `|v, p, i| { if validate_object(v, p, i) { ${fieldCalls} } }`;
   }
 
   generateBundleContentExpr(
@@ -164,24 +294,40 @@ export class RustGenerator extends CodeGenerator {
   ): string {
     const parts: string[] = [];
 
-    for (const child of children.required ?? []) {
-      parts.push(this.generateFSChildExpr(child));
-    }
-    for (const child of children.optional ?? []) {
-      parts.push(this.generateFSChildExpr(child));
+    for (const { desc } of this.scheduleChildren(children)) {
+      parts.push(this.generateFSChildExpr(desc));
     }
 
     if (parts.length === 0) {
       return '|_, _, _| {}';
     }
 
-    return `|ctx, p, i| { ${parts.join('; ')}; }`;
+    return `|ctx, p, i| { ${this.joinChecks(parts)} }`;
+  }
+
+  /**
+   * Join check statements, returning early after a check reports errors in fail-fast mode
+   */
+  private joinChecks(calls: string[]): string {
+    if (!this.options.failFast) {
+      return `${calls.join('; ')};`;
+    }
+    return `let n = error_count(i); ${calls.map(call => `${call}; if error_count(i) > n { return; }`).join(' ')}`;
   }
 
   generateFSChildExpr(desc: TypeDescription): string {
     const fsType = desc.fsType;
     const comment = desc.description ? `/* ${desc.description} */ ` : '';
 
+    if (desc.name === 'When' && desc.condition) {
+      const { file, field, is } = desc.condition;
+      const fieldArg = field !== undefined ? `Some(${this.escapeString(field)})` : 'None';
+      const isArg = is ? `Some(&(${this.generateDataValidatorExpr(is)}))` : 'None';
+      const condition = `&Condition { file: ${this.escapeString(file)}, field: ${fieldArg}, is: ${isArg} }`;
+      const branch = (d: TypeDescription | undefined) => d ? `Some(&(${this.generateBundleContentExpr(whenBranch(d))}))` : 'None';
+      return `${comment}validate_when(ctx, p, i, ${condition}, ${branch(desc.then)}, ${branch(desc.else)})`;
+    }
+
     if (fsType === 'jsonFile' && desc.filePath) {
       const validatorArg = desc.children ? `Some(&(${this.generateObjectExpr(desc.children)}))` : 'None';
       return `${comment}validate_json_file(ctx, ${this.escapeString(desc.filePath)}, p, i, ${validatorArg})`;
@@ -189,7 +335,12 @@ export class RustGenerator extends CodeGenerator {
 
     if (fsType === 'file' && desc.filePath) {
       const ext = desc.fileExt ? `Some(${this.escapeString(desc.fileExt)})` : 'None';
-      return `${comment}validate_fs_file(ctx, ${this.escapeString(desc.filePath)}, p, i, ${ext})`;
+      const fileCheck = `validate_fs_file(ctx, ${this.escapeString(desc.filePath)}, p, i, ${ext})`;
+      if (desc.checksum) {
+        const { algorithm, value } = desc.checksum;
+        return `${comment}if ${fileCheck} { validate_file_checksum(ctx, ${this.escapeString(desc.filePath)}, p, i, ${this.escapeString(algorithm)}, ${this.escapeString(value)}); }`;
+      }
+      return `${comment}${fileCheck}`;
     }
 
     if (fsType === 'directory' && desc.filePath) {
@@ -201,24 +352,28 @@ export class RustGenerator extends CodeGenerator {
 
   generateBundleExpr(desc: TypeDescription): string {
     const accept = extractBundleAccept(desc.accept);
-    const args: string[] = [];
+    const fields: string[] = [];
 
-    args.push(accept.acceptDir.toString());
-    args.push(accept.acceptZip.toString());
-    args.push(accept.zipExt ? `Some(${this.escapeString(accept.zipExt)})` : 'None');
-    args.push(desc.namePattern ? `Some(${this.escapeString(desc.namePattern)})` : 'None');
+    fields.push(`accept_dir: ${accept.acceptDir}`);
+    fields.push(`accept_zip: ${accept.acceptZip}`);
+    fields.push(`zip_ext: ${accept.zipExt ? `Some(${this.escapeString(accept.zipExt)})` : 'None'}`);
+    fields.push(`name_pattern: ${desc.namePattern ? `Some(${this.escapeString(desc.namePattern)})` : 'None'}`);
 
+    let contentArg = 'None';
     if (desc.children) {
-      const contentExpr = this.generateBundleContentExpr(desc.children);
-      args.push(`Some(&(${contentExpr}))`);
+      this.bundleContentExpr = this.generateBundleContentExpr(desc.children);
+      contentArg = 'Some(&CONTENT_VALIDATOR)';
     } else {
-      args.push('None');
+      this.bundleContentExpr = '|_, _, _| {}';
     }
 
-    return `|path, p, i| validate_bundle(path, p, i, ${args.join(', ')})`;
+    return `|path, p, i| validate_bundle(path, p, i, &BundleOptions { ${fields.join(', ')} }, ${contentArg})`;
   }
 
   generateMainCode(isBundle: boolean): string {
+    if (this.options.cli !== undefined) {
+      return this.generateCliMain(this.options.cli, isBundle);
+    }
     if (isBundle) {
       return `
 pub fn validate_root(bundle_path: &str) -> ValidationResult {
@@ -259,20 +414,253 @@ fn main() {
     }
   }
 
-  protected generateValidatorDecl(rootExpr: string, _isBundle: boolean): string {
+  /** A clap command line tool: `<name> validate <path> [--format text|json]` */
+  private generateCliMain(name: string, isBundle: boolean): string {
+    const entry = isBundle
+      ? `pub fn validate_root(bundle_path: &str) -> ValidationResult {
+    validate_path(bundle_path, &ROOT_VALIDATOR)
+}`
+      : `pub fn validate_root(value: &Value) -> ValidationResult {
+    validate(value, &ROOT_VALIDATOR)
+}`;
+    const run = isBundle
+      ? '            let result = validate_root(&path);'
+      : `            let content = std::fs::read_to_string(&path).unwrap_or_else(|err| {
+                eprintln!("Cannot read {}: {}", path, err);
+                std::process::exit(2);
+            });
+            let value: Value = serde_json::from_str(&content).unwrap_or_else(|err| {
+                eprintln!("Invalid JSON in {}: {}", path, err);
+                std::process::exit(2);
+            });
+            let result = validate_root(&value);`;
+    return `
+${entry}
+
+// === Command Line ===
+// Needs clap = { version = "4", features = ["derive"] }
+// Exit codes: 0 valid, 1 invalid, 2 usage error or unreadable input
+
+use clap::{Parser, Subcommand, ValueEnum};
+
+#[derive(Parser)]
+#[command(name = ${this.escapeString(name)}, version, about = "Validate ${isBundle ? 'bundles' : 'JSON files'} against the spec")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Validate ${isBundle ? 'a bundle (directory or zip archive)' : 'a JSON file'}
+    Validate {
+        path: String,
+        /// Report format
+        #[arg(long, value_enum, default_value_t = Format::Text)]
+        format: Format,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Format {
+    Text,
+    Json,
+}
+
+fn report(result: &ValidationResult, format: Format) {
+    match format {
+        Format::Json => println!("{}", serde_json::to_string_pretty(result).unwrap()),
+        Format::Text => {
+            for issue in &result.issues {
+                println!("{} {}: {} ({})", issue.level, issue.path, issue.message, issue.code);
+            }
+            let errors = error_count(&result.issues);
+            if errors == 0 {
+                println!("OK");
+            } else {
+                println!("FAILED: {} error(s)", errors);
+            }
+        }
+    }
+}
+
+fn main() {
+    match Cli::parse().command {
+        Command::Validate { path, format } => {
+${run}
+            report(&result, format);
+            std::process::exit(if result.ok { 0 } else { 1 });
+        }
+    }
+}`;
+  }
+
+  /**
+   * A cargo-fuzz crate: fuzz_targets/validate.rs includes the generated validator and
+   * feeds it arbitrary input (JSON for data specs, a zip archive for bundles)
+   */
+  generateFuzzTarget(desc: TypeDescription, validatorPath: string): Map<string, string> {
+    const isBundle = desc.fsType === 'bundle';
+    const { zipExt } = extractBundleAccept(desc.accept);
+    const body = isBundle
+      ? `    // Bundles are read from disk, so the input is written out as an archive
+    let path = std::env::temp_dir().join(format!("specspec-fuzz-{}.${zipExt ?? 'zip'}", std::process::id()));
+    if std::fs::write(&path, data).is_ok() {
+        let _ = validator::validate_root(&path.to_string_lossy());
+    }`
+      : `    if let Ok(value) = serde_json::from_slice::<serde_json::Value>(data) {
+        let _ = validator::validate_root(&value);
+    }`;
+    const target = `#![no_main]
+// cargo-fuzz target for the SpecSpec-generated validator: cargo fuzz run validate
+// Any panic or crash on the generated input is a bug in the validator or its prelude
+
+use libfuzzer_sys::fuzz_target;
+
+#[allow(dead_code)]
+mod validator {
+    include!(${this.escapeString(path.posix.join('..', validatorPath.split(path.sep).join('/')))});
+}
+
+fuzz_target!(|data: &[u8]| {
+${body}
+});
+`;
+
+    const dependencies = ['libfuzzer-sys = "0.4"', 'serde = { version = "1", features = ["derive"] }', 'serde_json = "1"'];
+    if (this.options.externalPrelude) {
+      dependencies.push(this.options.slim
+        ? `specspec-prelude = { version = "=${PRELUDE_CRATE_VERSION}", default-features = false }`
+        : `specspec-prelude = "=${PRELUDE_CRATE_VERSION}"`);
+    } else {
+      dependencies.push('regex = "1"');
+      if (!this.options.slim) {
+        dependencies.push('zip = { version = "0.6", default-features = false, features = ["deflate"] }', 'sha2 = "0.10"', 'blake3 = { version = "1", optional = true }');
+      }
+    }
+    if (this.options.cli !== undefined) {
+      dependencies.push('clap = { version = "4", features = ["derive"] }');
+    }
+    const features = this.options.externalPrelude || this.options.slim ? [] : ['', '[features]', 'blake3 = ["dep:blake3"]', 'fips = []'];
+    const manifest = [
+      '[package]',
+      'name = "validator-fuzz"',
+      'version = "0.0.0"',
+      'publish = false',
+      'edition = "2021"',
+      '',
+      '[package.metadata]',
+      'cargo-fuzz = true',
+      '',
+      '[dependencies]',
+      ...dependencies,
+      ...features,
+      '',
+      '[[bin]]',
+      'name = "validate"',
+      'path = "fuzz_targets/validate.rs"',
+      'test = false',
+      'doc = false',
+      'bench = false',
+      '',
+    ].join('\n');
+
+    return new Map([['Cargo.toml', manifest], ['.gitignore', 'target\ncorpus\nartifacts\ncoverage\n'], ['fuzz_targets/validate.rs', target]]);
+  }
+
+  protected generateTypeDecls(desc: TypeDescription): string[] {
+    const { rootType, code } = new RustTypeEmitter(s => this.escapeString(s)).emit(desc);
+    return [
+      code,
+      [
+        '/// Validate a value, then deserialize it into T (e.g. the root type)',
+        'pub fn parse<T: serde::de::DeserializeOwned>(value: &Value) -> Result<T, Issues> {',
+        '    parse_with(value, &ROOT_VALIDATOR)',
+        '}',
+        '',
+        `/// Validate a value and deserialize it into ${rootType}`,
+        `pub fn parse_root(value: &Value) -> Result<${rootType}, Issues> {`,
+        '    parse(value)',
+        '}',
+        '',
+        '/// Read JSON and deserialize it into T, rejecting data that fails validation; the input is read once',
+        'pub fn parse_reader<R: std::io::Read, T: serde::de::DeserializeOwned>(reader: R) -> Result<T, ValidationError> {',
+        '    from_reader_validated(reader, &ROOT_VALIDATOR)',
+        '}',
+      ].join('\n'),
+    ];
+  }
+
+  protected generateTestDecls(desc: TypeDescription): string[] {
+    return [new RustPropertyTestEmitter(s => this.escapeString(s)).emit(desc)];
+  }
+
+  protected generateValidatorDecl(rootExpr: string, isBundle: boolean): string {
     // Rust requires static/const declarations differently
-    return `static ROOT_VALIDATOR: fn(&str, &[String], &mut Issues) -> Option<FSContext> = ${rootExpr};`;
+    if (isBundle) {
+      return [
+        `static CONTENT_VALIDATOR: fn(&FSContext, &[String], &mut Issues) = ${this.bundleContentExpr};`,
+        `static ROOT_VALIDATOR: fn(&str, &[String], &mut Issues) -> Option<FSContext> = ${rootExpr};`,
+        '',
+        '/// Validate a sub-path ("" for all of it) of a bundle loaded once with SharedBundle::open,',
+        '/// without the bundle name check. Safe to call from many threads at once.',
+        'pub fn validate_shared(bundle: &SharedBundle, sub_path: &str) -> ValidationResult {',
+        '    bundle.validate(sub_path, &CONTENT_VALIDATOR)',
+        '}',
+      ].join('\n');
+    }
+    return `static ROOT_VALIDATOR: fn(&Value, &[String], &mut Issues) = ${rootExpr};`;
+  }
+
+  protected definitionIdent(words: string[]): string {
+    return `validate_def_${words.join('_')}`;
+  }
+
+  protected generateDefinition(name: string, ident: string, expr: string, maxDepth?: number): string {
+    if (maxDepth === undefined) {
+      return [
+        `fn ${ident}(v: &Value, p: &[String], i: &mut Issues) {`,
+        `    let validator: &dyn Fn(&Value, &[String], &mut Issues) = &(${expr});`,
+        '    validator(v, p, i)',
+        '}',
+      ].join('\n');
+    }
+    const message = this.escapeString(`Nesting of ${name} exceeds maximum depth ${maxDepth}`);
+    return [
+      `fn ${ident}(v: &Value, p: &[String], i: &mut Issues) {`,
+      '    thread_local! { static DEPTH: std::cell::Cell<usize> = const { std::cell::Cell::new(0) }; }',
+      '    let depth = DEPTH.with(|d| d.get());',
+      `    if depth >= ${maxDepth} {`,
+      `        add_issue(i, p, "recursion.too_deep", ${message});`,
+      '        return;',
+      '    }',
+      '    DEPTH.with(|d| d.set(depth + 1));',
+      `    let validator: &dyn Fn(&Value, &[String], &mut Issues) = &(${expr});`,
+      '    validator(v, p, i);',
+      '    DEPTH.with(|d| d.set(depth));',
+      '}',
+    ].join('\n');
+  }
+
+  /** Code and message arguments for validate_message */
+  private messageArgs(customIssue: NonNullable<TypeDescription['customIssue']>): string {
+    const opt = (s: string | undefined): string => s === undefined ? 'None' : `Some(${this.escapeString(s)})`;
+    return `${opt(customIssue.code)}, ${opt(customIssue.message)}`;
   }
 
   protected commentLine(text: string): string {
     return `// ${text}`;
   }
+
+  protected docComment(text: string): string[] {
+    return text.split('\n').map(line => `/// ${line}`.trimEnd());
+  }
 }
 
 /**
  * Generate Rust validator code from TypeDescription
  */
-export function generateRust(desc: TypeDescription): string {
-  const generator = new RustGenerator();
+export function generateRust(desc: TypeDescription, options: CodegenOptions = {}): string {
+  const generator = new RustGenerator(options);
   return generator.generate(desc);
 }
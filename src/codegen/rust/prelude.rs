@@ -1,27 +1,44 @@
 // SpecSpec Rust Prelude
-// Validation primitives - embedded at top of generated validators
+// Validation primitives - embedded at top of generated validators,
+// or published as the specspec-prelude crate (crates/specspec-prelude).
+// Sections between "--- fs ---" markers are left out of the slim prelude.
 
 use serde::{Deserialize, Serialize};
-use serde_json::Value;
+use serde_json::{Map, Value};
 use std::collections::HashMap;
+use regex::Regex;
+// --- fs ---
 use std::fs;
 use std::io::Read;
 use std::path::{Path, PathBuf};
-use regex::Regex;
+use std::sync::Arc;
 use zip::ZipArchive;
+// --- end fs ---
 
 // === Types ===
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Issue {
+    /// "error" or "warning"; warnings do not fail validation
+    pub level: String,
     pub path: String,
     pub code: String,
     pub message: String,
+    /// Metadata of the rule that reported the issue (`Meta()` in the spec)
+    #[serde(default, skip_serializing_if = "Map::is_empty")]
+    pub meta: Map<String, Value>,
 }
 
 pub type Issues = Vec<Issue>;
-pub type Validator = Box<dyn Fn(&Value, &[String], &mut Issues)>;
-pub type FSValidator = Box<dyn Fn(&FSContext, &[String], &mut Issues)>;
+pub type Validator = Box<dyn Fn(&Value, &[String], &mut Issues) + Send + Sync>;
+/// A validator taken by reference: a generated validator, a closure or a `Validator`
+pub type ValidatorFn<'a> = dyn Fn(&Value, &[String], &mut Issues) + 'a;
+// --- fs ---
+pub type FSValidator = Box<dyn Fn(&FSContext, &[String], &mut Issues) + Send + Sync>;
+pub type FSValidatorFn<'a> = dyn Fn(&FSContext, &[String], &mut Issues) + 'a;
+/// A root validator of a bundle path; returns the opened bundle
+pub type PathValidatorFn<'a> = dyn Fn(&str, &[String], &mut Issues) -> Option<FSContext> + 'a;
+// --- end fs ---
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ValidationResult {
@@ -29,14 +46,30 @@ pub struct ValidationResult {
     pub issues: Issues,
 }
 
-fn add_issue(issues: &mut Issues, path: &[String], code: &str, message: &str) {
+fn push_issue(issues: &mut Issues, level: &str, path: &[String], code: &str, message: &str) {
     issues.push(Issue {
+        level: level.to_string(),
         path: if path.is_empty() { "(root)".to_string() } else { path.join(".") },
         code: code.to_string(),
         message: message.to_string(),
+        meta: Map::new(),
     });
 }
 
+pub fn add_issue(issues: &mut Issues, path: &[String], code: &str, message: &str) {
+    push_issue(issues, "error", path, code, message);
+}
+
+/// Add a warning; warnings do not fail validation
+pub fn add_warning(issues: &mut Issues, path: &[String], code: &str, message: &str) {
+    push_issue(issues, "warning", path, code, message);
+}
+
+/// Number of error-level issues
+pub fn error_count(issues: &Issues) -> usize {
+    issues.iter().filter(|issue| issue.level == "error").count()
+}
+
 // === Primitive Validators ===
 
 pub fn validate_str(
@@ -113,6 +146,133 @@ pub fn validate_num(
     }
 }
 
+// Decimal validation works on the number's source text instead of f64.
+// Enable serde_json's `arbitrary_precision` feature so `Number::to_string`
+// returns the digits exactly as they appeared in the input.
+
+struct DecimalParts {
+    negative: bool,
+    digits: String, // significant digits, without leading or trailing zeros
+    point: i64,     // position of the decimal point, counted from the first digit
+}
+
+impl DecimalParts {
+    /// Digits before the decimal point, including zeros up to it
+    fn int_len(&self) -> i128 {
+        if self.digits.is_empty() { 0 } else { i128::from(self.point).max(0) }
+    }
+
+    /// Digits after the decimal point, including zeros after it
+    fn frac_len(&self) -> i128 {
+        (self.digits.len() as i128 - i128::from(self.point)).max(0)
+    }
+}
+
+// The exponent stays a number: zeros it implies are never written out, so
+// `1e999999999999` is as cheap as `1e9`
+fn parse_decimal(text: &str) -> Option<DecimalParts> {
+    let text = text.trim();
+    let (negative, unsigned) = match text.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, text),
+    };
+    let (mantissa, exponent) = match unsigned.find(['e', 'E']) {
+        Some(idx) => (&unsigned[..idx], unsigned[idx + 1..].parse::<i64>().ok()?),
+        None => (unsigned, 0),
+    };
+    let (int_part, frac_part) = match mantissa.find('.') {
+        Some(idx) => (&mantissa[..idx], &mantissa[idx + 1..]),
+        None => (mantissa, ""),
+    };
+    if int_part.is_empty() || !int_part.chars().all(|c| c.is_ascii_digit())
+        || !frac_part.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+
+    let all = format!("{}{}", int_part, frac_part);
+    let significant = all.trim_start_matches('0');
+    let leading_zeros = (all.len() - significant.len()) as i64;
+    let digits = significant.trim_end_matches('0').to_string();
+    if digits.is_empty() {
+        return Some(DecimalParts { negative: false, digits, point: 0 });
+    }
+    let point = (int_part.len() as i64).checked_sub(leading_zeros)?.checked_add(exponent)?;
+    Some(DecimalParts { negative, digits, point })
+}
+
+fn compare_decimal(a: &DecimalParts, b: &DecimalParts) -> std::cmp::Ordering {
+    use std::cmp::Ordering;
+    if a.negative != b.negative {
+        return if a.negative { Ordering::Less } else { Ordering::Greater };
+    }
+    let magnitude = match (a.digits.is_empty(), b.digits.is_empty()) {
+        (true, true) => Ordering::Equal,
+        (true, false) => Ordering::Less,
+        (false, true) => Ordering::Greater,
+        // Neither has trailing zeros, so digit strings compare like their values
+        (false, false) => a.point.cmp(&b.point).then_with(|| a.digits.cmp(&b.digits)),
+    };
+    if a.negative { magnitude.reverse() } else { magnitude }
+}
+
+pub fn validate_decimal(
+    value: &Value,
+    path: &[String],
+    issues: &mut Issues,
+    min: Option<&str>,
+    max: Option<&str>,
+    precision: Option<usize>,
+    scale: Option<usize>,
+) {
+    let text = match value {
+        Value::Number(n) => n.to_string(),
+        _ => {
+            add_issue(issues, path, "type.mismatch",
+                &format!("Expected number, got {:?}", value));
+            return;
+        }
+    };
+    let parts = match parse_decimal(&text) {
+        Some(parts) => parts,
+        None => {
+            add_issue(issues, path, "type.mismatch",
+                &format!("Expected decimal number, got {}", text));
+            return;
+        }
+    };
+
+    let places = parts.frac_len();
+    let digits = parts.int_len() + places;
+    if let Some(p) = precision {
+        if digits > p as i128 {
+            add_issue(issues, path, "decimal.too_many_digits",
+                &format!("Number {} has {} digits, maximum is {}", text, digits, p));
+        }
+    }
+    if let Some(s) = scale {
+        if places > s as i128 {
+            add_issue(issues, path, "decimal.scale_exceeded",
+                &format!("Number {} has {} decimal places, maximum is {}", text, places, s));
+        }
+    }
+    if let Some(m) = min {
+        if let Some(bound) = parse_decimal(m) {
+            if compare_decimal(&parts, &bound) == std::cmp::Ordering::Less {
+                add_issue(issues, path, "num.too_small",
+                    &format!("Number {} is less than minimum {}", text, m));
+            }
+        }
+    }
+    if let Some(m) = max {
+        if let Some(bound) = parse_decimal(m) {
+            if compare_decimal(&parts, &bound) == std::cmp::Ordering::Greater {
+                add_issue(issues, path, "num.too_large",
+                    &format!("Number {} exceeds maximum {}", text, m));
+            }
+        }
+    }
+}
+
 pub fn validate_bool(value: &Value, path: &[String], issues: &mut Issues) {
     if !value.is_boolean() {
         add_issue(issues, path, "type.mismatch",
@@ -186,7 +346,7 @@ pub fn validate_field(
     path: &[String],
     issues: &mut Issues,
     key: &str,
-    validator: Option<&dyn Fn(&Value, &[String], &mut Issues)>,
+    validator: Option<&ValidatorFn>,
     optional: bool,
 ) {
     if let Some(map) = obj.as_object() {
@@ -207,14 +367,45 @@ pub fn validate_field(
     }
 }
 
+/// Resolve a dotted key path (e.g. "meta.id") inside an object value
+pub fn get_key_path<'a>(value: &'a Value, key_path: &str) -> Option<&'a Value> {
+    let mut current = value;
+    for key in key_path.split('.') {
+        current = current.as_object()?.get(key)?;
+    }
+    Some(current)
+}
+
+/// Order two sort keys; None unless both are numbers or both are strings
+pub fn compare_keys(a: &Value, b: &Value) -> Option<std::cmp::Ordering> {
+    match (a, b) {
+        (Value::Number(x), Value::Number(y)) => x.as_f64()?.partial_cmp(&y.as_f64()?),
+        (Value::String(x), Value::String(y)) => Some(x.cmp(y)),
+        _ => None,
+    }
+}
+
+/// Constraints of a list besides its item type; `..ListOptions::default()` leaves the rest unchecked
+#[derive(Debug, Clone, Default)]
+pub struct ListOptions<'a> {
+    pub min_items: Option<usize>,
+    pub max_items: Option<usize>,
+    pub unique: bool,
+    /// Key path (e.g. "meta.id") that must be unique among items
+    pub unique_by: Option<&'a str>,
+    /// "asc" or "desc"
+    pub sorted: Option<&'a str>,
+    pub sorted_by: Option<&'a str>,
+}
+
 pub fn validate_list(
     value: &Value,
     path: &[String],
     issues: &mut Issues,
-    item_validator: Option<&dyn Fn(&Value, &[String], &mut Issues)>,
-    min_items: Option<usize>,
-    max_items: Option<usize>,
+    item_validator: Option<&ValidatorFn>,
+    options: &ListOptions,
 ) {
+    let ListOptions { min_items, max_items, unique, unique_by, sorted, sorted_by } = *options;
     match value.as_array() {
         Some(arr) => {
             if let Some(min) = min_items {
@@ -229,6 +420,63 @@ pub fn validate_list(
                         &format!("Array length {} exceeds maximum {}", arr.len(), max));
                 }
             }
+            if unique || unique_by.is_some() {
+                // serde_json's default map is sorted, so to_string() is canonical
+                let mut seen: HashMap<String, usize> = HashMap::new();
+                for (i, item) in arr.iter().enumerate() {
+                    let key_value = match unique_by {
+                        Some(key_path) => match get_key_path(item, key_path) {
+                            Some(v) => v,
+                            None => continue,
+                        },
+                        None => item,
+                    };
+                    let key = key_value.to_string();
+                    if let Some(&first) = seen.get(&key) {
+                        let mut item_path = path.to_vec();
+                        item_path.push(format!("[{}]", i));
+                        let message = match unique_by {
+                            Some(key_path) => format!("Items [{}] and [{}] share {} {}", first, i, key_path, key),
+                            None => format!("Items [{}] and [{}] are duplicates", first, i),
+                        };
+                        add_issue(issues, &item_path, "list.duplicate", &message);
+                    } else {
+                        seen.insert(key, i);
+                    }
+                }
+            }
+            if sorted.is_some() || sorted_by.is_some() {
+                let descending = sorted == Some("desc");
+                // Only the first out-of-order pair is reported
+                let mut prev: Option<(usize, &Value)> = None;
+                for (i, item) in arr.iter().enumerate() {
+                    let key = match sorted_by {
+                        Some(key_path) => match get_key_path(item, key_path) {
+                            Some(v) => v,
+                            None => continue,
+                        },
+                        None => item,
+                    };
+                    if !key.is_number() && !key.is_string() {
+                        continue;
+                    }
+                    if let Some((prev_index, prev_key)) = prev {
+                        if let Some(ord) = compare_keys(prev_key, key) {
+                            let out_of_order = if descending { ord.is_lt() } else { ord.is_gt() };
+                            if out_of_order {
+                                let mut item_path = path.to_vec();
+                                item_path.push(format!("[{}]", i));
+                                let order = if descending { "descending" } else { "ascending" };
+                                let by = sorted_by.map(|k| format!(" by {}", k)).unwrap_or_default();
+                                add_issue(issues, &item_path, "list.not_sorted",
+                                    &format!("Items [{}] and [{}] are not in {} order{}", prev_index, i, order, by));
+                                break;
+                            }
+                        }
+                    }
+                    prev = Some((i, key));
+                }
+            }
             if let Some(iv) = item_validator {
                 for (i, item) in arr.iter().enumerate() {
                     let mut new_path = path.to_vec();
@@ -248,36 +496,242 @@ pub fn validate_oneof(
     value: &Value,
     path: &[String],
     issues: &mut Issues,
-    validators: &[&dyn Fn(&Value, &[String], &mut Issues)],
+    validators: &[&ValidatorFn],
 ) {
     for validator in validators {
         let mut test_issues: Issues = vec![];
         validator(value, path, &mut test_issues);
-        if test_issues.is_empty() {
-            return; // Matched
+        if error_count(&test_issues) == 0 {
+            issues.extend(test_issues); // Matched; keep its warnings
+            return;
         }
     }
     add_issue(issues, path, "oneof.no_match",
         "Value does not match any of the options");
 }
 
+pub fn validate_allof(
+    value: &Value,
+    path: &[String],
+    issues: &mut Issues,
+    validators: &[&ValidatorFn],
+) {
+    // Every part runs; issues shared by several parts are reported once
+    let mut merged: Issues = vec![];
+    for validator in validators {
+        let mut part_issues: Issues = vec![];
+        validator(value, path, &mut part_issues);
+        for issue in part_issues {
+            let duplicate = merged.iter().any(|m| {
+                m.path == issue.path && m.code == issue.code && m.message == issue.message
+            });
+            if !duplicate {
+                merged.push(issue);
+            }
+        }
+    }
+    issues.extend(merged);
+}
+
+pub fn validate_tagged_union(
+    value: &Value,
+    path: &[String],
+    issues: &mut Issues,
+    discriminator: &str,
+    variants: &[(&str, &ValidatorFn)],
+) {
+    let obj = match value.as_object() {
+        Some(o) => o,
+        None => {
+            add_issue(issues, path, "type.mismatch", &format!("Expected object, got {:?}", value));
+            return;
+        }
+    };
+    let tag = match obj.get(discriminator) {
+        Some(t) => t,
+        None => {
+            add_issue(issues, path, "field.missing", &format!("Missing required field: {}", discriminator));
+            return;
+        }
+    };
+    // Only the variant selected by the tag runs
+    if let Some(tag_str) = tag.as_str() {
+        if let Some((_, validator)) = variants.iter().find(|(t, _)| *t == tag_str) {
+            validator(value, path, issues);
+            return;
+        }
+    }
+    let mut tag_path = path.to_vec();
+    tag_path.push(discriminator.to_string());
+    let expected: Vec<&str> = variants.iter().map(|(t, _)| *t).collect();
+    add_issue(issues, &tag_path, "union.unknown_tag",
+        &format!("Unknown {} {}; expected one of: {}", discriminator, tag, expected.join(", ")));
+}
+
+pub fn validate_not(
+    value: &Value,
+    path: &[String],
+    issues: &mut Issues,
+    inner: &ValidatorFn,
+) {
+    let mut test_issues: Issues = vec![];
+    inner(value, path, &mut test_issues);
+    if error_count(&test_issues) == 0 {
+        add_issue(issues, path, "not.matched", "Value matches a disallowed schema");
+    }
+}
+
+pub fn validate_nullable(
+    value: &Value,
+    path: &[String],
+    issues: &mut Issues,
+    inner: &ValidatorFn,
+) {
+    if value.is_null() {
+        return;
+    }
+    inner(value, path, issues);
+}
+
+/// Run the inner validator; report any failure as one issue with a spec-defined code/message
+pub fn validate_message(
+    value: &Value,
+    path: &[String],
+    issues: &mut Issues,
+    inner: &ValidatorFn,
+    code: Option<&str>,
+    message: Option<&str>,
+) {
+    let mut inner_issues = Issues::new();
+    inner(value, path, &mut inner_issues);
+    let (warnings, errors): (Issues, Issues) = inner_issues.into_iter().partition(|issue| issue.level == "warning");
+    issues.extend(warnings);
+    if let Some(first) = errors.first() {
+        add_issue(issues, path, code.unwrap_or(&first.code), message.unwrap_or(&first.message));
+    }
+}
+
+/// Warn that a deprecated field or type is used, then validate it as usual
+pub fn validate_deprecated(
+    value: &Value,
+    path: &[String],
+    issues: &mut Issues,
+    inner: &ValidatorFn,
+    code: &str,
+    message: &str,
+) {
+    add_warning(issues, path, code, message);
+    inner(value, path, issues);
+}
+
+/// Attach metadata (a JSON object) to the issues of the inner validator; keys already
+/// on an issue, from a nested `Meta()`, win. The metadata is only parsed for issues.
+pub fn validate_meta(
+    value: &Value,
+    path: &[String],
+    issues: &mut Issues,
+    inner: &ValidatorFn,
+    meta: &str,
+) {
+    let mut inner_issues = Issues::new();
+    inner(value, path, &mut inner_issues);
+    if inner_issues.is_empty() {
+        return;
+    }
+    let meta: Map<String, Value> = serde_json::from_str(meta).unwrap_or_default();
+    for mut issue in inner_issues {
+        for (key, item) in &meta {
+            issue.meta.entry(key.clone()).or_insert_with(|| item.clone());
+        }
+        issues.push(issue);
+    }
+}
+
+// --- fs ---
+// === Paths ===
+// Spec and issue paths use forward slashes on every platform. On Windows, bundle paths
+// are made absolute (drive-relative ones like `C:bundle` included) and given the `\\?\`
+// prefix, so entries beyond MAX_PATH can be read.
+
+/// Windows device names, reserved with any extension (`nul.txt`) in every directory
+const RESERVED_NAMES: &[&str] = &[
+    "CON", "PRN", "AUX", "NUL",
+    "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8", "COM9",
+    "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+/// A spec or zip entry path with forward slashes
+pub fn normalize_rel_path(rel_path: &str) -> String {
+    rel_path.replace('\\', "/")
+}
+
+/// The first segment of a relative path that is a reserved device name on Windows
+pub fn reserved_name(rel_path: &str) -> Option<&str> {
+    rel_path.split(['/', '\\']).find(|segment| {
+        let stem = segment.split('.').next().unwrap_or("").trim_end();
+        RESERVED_NAMES.iter().any(|name| name.eq_ignore_ascii_case(stem))
+    })
+}
+
+/// The `\\?\` form of an absolute Windows path; UNC shares become `\\?\UNC\`
+#[cfg_attr(not(windows), allow(dead_code))]
+fn extended_length_path(absolute: &str) -> String {
+    if absolute.starts_with(r"\\?\") || absolute.starts_with(r"\\.\") {
+        absolute.to_string()
+    } else if let Some(share) = absolute.strip_prefix(r"\\") {
+        format!(r"\\?\UNC\{}", share)
+    } else {
+        format!(r"\\?\{}", absolute)
+    }
+}
+
+#[cfg(windows)]
+fn long_path(path: &Path) -> PathBuf {
+    let absolute = std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf());
+    PathBuf::from(extended_length_path(&absolute.to_string_lossy()))
+}
+
+#[cfg(not(windows))]
+fn long_path(path: &Path) -> PathBuf {
+    path.to_path_buf()
+}
+
+/// Report `fs.reserved_name` for a path Windows cannot open. Such entries are not looked
+/// up on any platform, so a bundle gets the same result everywhere.
+fn check_reserved_name(rel_path: &str, path: &[String], issues: &mut Issues) -> bool {
+    match reserved_name(rel_path) {
+        Some(name) => {
+            add_issue(issues, path, "fs.reserved_name",
+                &format!("'{}' is a reserved name on Windows", name));
+            false
+        }
+        None => true,
+    }
+}
+
 // === File System Context ===
 
+/// A directory or zip bundle being validated. Zip contents are read once and shared:
+/// clones and sub-path views (`at`) do not copy them, and contexts are `Send + Sync`.
+#[derive(Clone)]
 pub struct FSContext {
     pub base_path: PathBuf,
     pub is_zip: bool,
-    zip_entries: HashMap<String, Vec<u8>>,
+    zip_entries: Arc<HashMap<String, Vec<u8>>>,
+    /// Sub-path of a view into a zip bundle, with a trailing `/` (empty for the whole bundle)
+    zip_prefix: String,
 }
 
 impl FSContext {
     pub fn new(path: &str) -> Result<Self, String> {
-        let path_buf = PathBuf::from(path);
+        let path_buf = long_path(Path::new(path));
 
         if path_buf.is_dir() {
             Ok(FSContext {
                 base_path: path_buf,
                 is_zip: false,
-                zip_entries: HashMap::new(),
+                zip_entries: Arc::new(HashMap::new()),
+                zip_prefix: String::new(),
             })
         } else if path_buf.is_file() && (path.ends_with(".zip") || path.ends_with(".asks")) {
             let file = fs::File::open(&path_buf)
@@ -289,7 +743,7 @@ impl FSContext {
             for i in 0..archive.len() {
                 let mut entry = archive.by_index(i)
                     .map_err(|e| format!("Cannot read zip entry: {}", e))?;
-                let name = entry.name().to_string();
+                let name = normalize_rel_path(entry.name());
                 if !entry.is_dir() {
                     let mut data = Vec::new();
                     entry.read_to_end(&mut data)
@@ -301,46 +755,90 @@ impl FSContext {
             Ok(FSContext {
                 base_path: path_buf,
                 is_zip: true,
-                zip_entries: entries,
+                zip_entries: Arc::new(entries),
+                zip_prefix: String::new(),
             })
         } else {
             Err(format!("Not a valid bundle: {}", path))
         }
     }
 
+    /// A view of a sub-directory, sharing what was already loaded
+    pub fn at(&self, sub_path: &str) -> FSContext {
+        let sub_path = normalize_rel_path(sub_path);
+        let sub_path = sub_path.trim_matches('/');
+        if sub_path.is_empty() {
+            return self.clone();
+        }
+        if self.is_zip {
+            FSContext { zip_prefix: format!("{}{}/", self.zip_prefix, sub_path), ..self.clone() }
+        } else {
+            FSContext { base_path: self.full_path(sub_path), ..self.clone() }
+        }
+    }
+
+    /// Path of an entry on disk; segments are pushed one by one, as `\\?\` paths take no `/`
+    fn full_path(&self, rel_path: &str) -> PathBuf {
+        let mut full = self.base_path.clone();
+        for segment in rel_path.split(['/', '\\']).filter(|s| !s.is_empty() && *s != ".") {
+            full.push(segment);
+        }
+        full
+    }
+
+    /// Name of an entry in the zip archive
+    fn zip_key(&self, rel_path: &str) -> String {
+        format!("{}{}", self.zip_prefix, normalize_rel_path(rel_path))
+    }
+
     pub fn exists(&self, rel_path: &str) -> bool {
         if self.is_zip {
-            self.zip_entries.contains_key(rel_path)
-                || self.zip_entries.keys().any(|k| k.starts_with(&format!("{}/", rel_path)))
+            let key = self.zip_key(rel_path);
+            self.zip_entries.contains_key(&key)
+                || self.zip_entries.keys().any(|k| k.starts_with(&format!("{}/", key)))
         } else {
-            self.base_path.join(rel_path).exists()
+            self.full_path(rel_path).exists()
         }
     }
 
     pub fn is_file(&self, rel_path: &str) -> bool {
         if self.is_zip {
-            self.zip_entries.contains_key(rel_path)
+            self.zip_entries.contains_key(&self.zip_key(rel_path))
         } else {
-            self.base_path.join(rel_path).is_file()
+            self.full_path(rel_path).is_file()
         }
     }
 
     pub fn is_dir(&self, rel_path: &str) -> bool {
         if self.is_zip {
-            self.zip_entries.keys().any(|k| k.starts_with(&format!("{}/", rel_path)))
+            let key = self.zip_key(rel_path);
+            self.zip_entries.keys().any(|k| k.starts_with(&format!("{}/", key)))
         } else {
-            self.base_path.join(rel_path).is_dir()
+            self.full_path(rel_path).is_dir()
         }
     }
 
     pub fn read(&self, rel_path: &str) -> Result<String, String> {
         if self.is_zip {
-            self.zip_entries.get(rel_path)
-                .ok_or_else(|| format!("File not found: {}", rel_path))
+            let key = self.zip_key(rel_path);
+            self.zip_entries.get(&key)
+                .ok_or_else(|| format!("File not found: {}", key))
                 .and_then(|data| String::from_utf8(data.clone())
                     .map_err(|e| format!("Invalid UTF-8: {}", e)))
         } else {
-            fs::read_to_string(self.base_path.join(rel_path))
+            fs::read_to_string(self.full_path(rel_path))
+                .map_err(|e| format!("Cannot read file: {}", e))
+        }
+    }
+
+    pub fn read_bytes(&self, rel_path: &str) -> Result<Vec<u8>, String> {
+        if self.is_zip {
+            let key = self.zip_key(rel_path);
+            self.zip_entries.get(&key)
+                .cloned()
+                .ok_or_else(|| format!("File not found: {}", key))
+        } else {
+            fs::read(self.full_path(rel_path))
                 .map_err(|e| format!("Cannot read file: {}", e))
         }
     }
@@ -360,19 +858,141 @@ impl FSContext {
     }
 }
 
+/// A bundle loaded once for validation from many threads, e.g. by server request handlers.
+/// Clones are cheap and share the loaded contents. The example is not a doctest, as the
+/// prelude is also embedded in generated validators; the crate tests run it from threads.
+///
+/// ```ignore
+/// let bundle = SharedBundle::open("uploads/site.zip")?;
+/// let result = bundle.validate("tenants/acme", &CONTENT_VALIDATOR);
+/// ```
+#[derive(Clone)]
+pub struct SharedBundle {
+    ctx: FSContext,
+}
+
+impl SharedBundle {
+    pub fn open(path: &str) -> Result<Self, String> {
+        Ok(SharedBundle { ctx: FSContext::new(path)? })
+    }
+
+    pub fn context(&self) -> &FSContext {
+        &self.ctx
+    }
+
+    /// Check a sub-path of the bundle ("" for all of it) with a bundle content validator.
+    /// Issues of a sub-path are reported below it.
+    pub fn validate(
+        &self,
+        sub_path: &str,
+        validator: &FSValidatorFn,
+    ) -> ValidationResult {
+        let mut issues: Issues = vec![];
+        let sub_path = normalize_rel_path(sub_path).trim_matches('/').to_string();
+        if sub_path.is_empty() {
+            validator(&self.ctx, &[], &mut issues);
+        } else if validate_fs_directory(&self.ctx, &sub_path, &[], &mut issues) {
+            validator(&self.ctx.at(&sub_path), &[sub_path], &mut issues);
+        }
+        ValidationResult {
+            ok: error_count(&issues) == 0,
+            issues,
+        }
+    }
+}
+
+// Bundles and validators are shared between threads
+const _: fn() = || {
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<FSContext>();
+    assert_send_sync::<SharedBundle>();
+    assert_send_sync::<FSValidator>();
+    assert_send_sync::<Validator>();
+};
+
+// === Digests ===
+// Checksum rules hash file content through the `Digest` trait.
+// SHA-2 comes from the `sha2` crate; BLAKE3 needs the `blake3` cargo feature (and crate).
+// Building with the `fips` feature restricts checksums to FIPS 180-4 algorithms.
+
+pub trait Digest {
+    fn name(&self) -> &'static str;
+    fn hex_digest(&self, data: &[u8]) -> String;
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+pub struct Sha256Digest;
+pub struct Sha384Digest;
+pub struct Sha512Digest;
+
+impl Digest for Sha256Digest {
+    fn name(&self) -> &'static str { "sha256" }
+    fn hex_digest(&self, data: &[u8]) -> String {
+        to_hex(&<sha2::Sha256 as sha2::Digest>::digest(data))
+    }
+}
+
+impl Digest for Sha384Digest {
+    fn name(&self) -> &'static str { "sha384" }
+    fn hex_digest(&self, data: &[u8]) -> String {
+        to_hex(&<sha2::Sha384 as sha2::Digest>::digest(data))
+    }
+}
+
+impl Digest for Sha512Digest {
+    fn name(&self) -> &'static str { "sha512" }
+    fn hex_digest(&self, data: &[u8]) -> String {
+        to_hex(&<sha2::Sha512 as sha2::Digest>::digest(data))
+    }
+}
+
+#[cfg(all(feature = "blake3", not(feature = "fips")))]
+pub struct Blake3Digest;
+
+#[cfg(all(feature = "blake3", not(feature = "fips")))]
+impl Digest for Blake3Digest {
+    fn name(&self) -> &'static str { "blake3" }
+    fn hex_digest(&self, data: &[u8]) -> String {
+        blake3::hash(data).to_hex().to_string()
+    }
+}
+
+/// Look up the digest for an algorithm name, if it is available in this build
+pub fn digest_for(algorithm: &str) -> Option<Box<dyn Digest>> {
+    match algorithm.to_ascii_lowercase().as_str() {
+        "sha256" => Some(Box::new(Sha256Digest)),
+        "sha384" => Some(Box::new(Sha384Digest)),
+        "sha512" => Some(Box::new(Sha512Digest)),
+        #[cfg(all(feature = "blake3", not(feature = "fips")))]
+        "blake3" => Some(Box::new(Blake3Digest)),
+        _ => None,
+    }
+}
+
 // === File System Validators ===
 
+/// What a bundle may be and how it must be named
+#[derive(Debug, Clone, Default)]
+pub struct BundleOptions<'a> {
+    pub accept_dir: bool,
+    pub accept_zip: bool,
+    /// Extension accepted for zip bundles besides `.zip`
+    pub zip_ext: Option<&'a str>,
+    pub name_pattern: Option<&'a str>,
+}
+
 pub fn validate_bundle(
     bundle_path: &str,
     path_list: &[String],
     issues: &mut Issues,
-    accept_dir: bool,
-    accept_zip: bool,
-    zip_ext: Option<&str>,
-    name_pattern: Option<&str>,
-    content_validator: Option<&dyn Fn(&FSContext, &[String], &mut Issues)>,
+    options: &BundleOptions,
+    content_validator: Option<&FSValidatorFn>,
 ) -> Option<FSContext> {
-    let path = Path::new(bundle_path);
+    let BundleOptions { accept_dir, accept_zip, zip_ext, name_pattern } = *options;
+    let path = long_path(Path::new(bundle_path));
 
     if !path.exists() {
         add_issue(issues, path_list, "bundle.not_found",
@@ -428,11 +1048,16 @@ pub fn validate_json_file(
     rel_path: &str,
     path: &[String],
     issues: &mut Issues,
-    content_validator: Option<&dyn Fn(&Value, &[String], &mut Issues)>,
+    content_validator: Option<&ValidatorFn>,
 ) -> Option<Value> {
+    let rel_path = &normalize_rel_path(rel_path);
     let mut file_path = path.to_vec();
     file_path.push(rel_path.to_string());
 
+    if !check_reserved_name(rel_path, &file_path, issues) {
+        return None;
+    }
+
     if !ctx.exists(rel_path) {
         add_issue(issues, &file_path, "file.not_found",
             &format!("File not found: {}", rel_path));
@@ -466,9 +1091,14 @@ pub fn validate_fs_file(
     issues: &mut Issues,
     ext: Option<&str>,
 ) -> bool {
+    let rel_path = &normalize_rel_path(rel_path);
     let mut file_path = path.to_vec();
     file_path.push(rel_path.to_string());
 
+    if !check_reserved_name(rel_path, &file_path, issues) {
+        return false;
+    }
+
     if !ctx.exists(rel_path) {
         add_issue(issues, &file_path, "file.not_found",
             &format!("File not found: {}", rel_path));
@@ -496,15 +1126,59 @@ pub fn validate_fs_file(
     true
 }
 
+pub fn validate_file_checksum(
+    ctx: &FSContext,
+    rel_path: &str,
+    path: &[String],
+    issues: &mut Issues,
+    algorithm: &str,
+    expected: &str,
+) -> bool {
+    let rel_path = &normalize_rel_path(rel_path);
+    let mut file_path = path.to_vec();
+    file_path.push(rel_path.to_string());
+
+    let digest = match digest_for(algorithm) {
+        Some(d) => d,
+        None => {
+            add_issue(issues, &file_path, "checksum.unsupported_algorithm",
+                &format!("Digest algorithm not available: {}", algorithm));
+            return false;
+        }
+    };
+
+    let data = match ctx.read_bytes(rel_path) {
+        Ok(d) => d,
+        Err(e) => {
+            add_issue(issues, &file_path, "file.read_error", &e);
+            return false;
+        }
+    };
+
+    let actual = digest.hex_digest(&data);
+    if !actual.eq_ignore_ascii_case(expected) {
+        add_issue(issues, &file_path, "checksum.mismatch",
+            &format!("{} digest mismatch: expected {}, got {}", digest.name(), expected, actual));
+        return false;
+    }
+
+    true
+}
+
 pub fn validate_fs_directory(
     ctx: &FSContext,
     rel_path: &str,
     path: &[String],
     issues: &mut Issues,
 ) -> bool {
+    let rel_path = &normalize_rel_path(rel_path);
     let mut dir_path = path.to_vec();
     dir_path.push(rel_path.to_string());
 
+    if !check_reserved_name(rel_path, &dir_path, issues) {
+        return false;
+    }
+
     if !ctx.exists(rel_path) {
         add_issue(issues, &dir_path, "dir.not_found",
             &format!("Directory not found: {}", rel_path));
@@ -520,25 +1194,205 @@ pub fn validate_fs_directory(
     true
 }
 
+/// The JSON file value a When() rule depends on
+pub struct Condition<'a> {
+    /// JSON file holding the value, relative to the directory
+    pub file: &'a str,
+    /// Dotted path of the value in the file (`icons.enabled`, `targets.0.name`); the whole document when `None`
+    pub field: Option<&'a str>,
+    /// What the value must match; being present is enough when `None`
+    pub is: Option<&'a ValidatorFn<'a>>,
+}
+
+/// Check `then` when the condition holds and `otherwise` when it does not or the value is
+/// missing; a file that cannot be read or parsed decides nothing (its own rules report it)
+pub fn validate_when(
+    ctx: &FSContext,
+    path: &[String],
+    issues: &mut Issues,
+    condition: &Condition,
+    then: Option<&FSValidatorFn>,
+    otherwise: Option<&FSValidatorFn>,
+) {
+    let Ok(document) = ctx.read_json(condition.file) else {
+        return;
+    };
+    let value = match condition.field {
+        Some(field) => field.split('.').try_fold(&document, |value, segment| match value {
+            Value::Object(map) => map.get(segment),
+            Value::Array(items) => segment.parse::<usize>().ok().and_then(|index| items.get(index)),
+            _ => None,
+        }),
+        None => Some(&document),
+    };
+
+    let holds = match (value, condition.is) {
+        (None, _) => false,
+        (Some(_), None) => true,
+        (Some(value), Some(is)) => {
+            let mut test = Vec::new();
+            is(value, path, &mut test);
+            error_count(&test) == 0
+        }
+    };
+    if let Some(content) = if holds { then } else { otherwise } {
+        content(ctx, path, issues);
+    }
+}
+// --- end fs ---
+
 // === Entry Points ===
 
-pub fn validate(value: &Value, validator: &dyn Fn(&Value, &[String], &mut Issues)) -> ValidationResult {
+pub fn validate(value: &Value, validator: &ValidatorFn) -> ValidationResult {
     let mut issues: Issues = vec![];
     validator(value, &[], &mut issues);
     ValidationResult {
-        ok: issues.is_empty(),
+        ok: error_count(&issues) == 0,
         issues,
     }
 }
 
+/// Validate a value, then deserialize it; the issues if either step fails
+pub fn parse_with<T: serde::de::DeserializeOwned>(
+    value: &Value,
+    validator: &ValidatorFn,
+) -> Result<T, Issues> {
+    let result = validate(value, validator);
+    if !result.ok {
+        return Err(result.issues);
+    }
+    serde_json::from_value(value.clone()).map_err(|err| {
+        let mut issues = result.issues;
+        add_issue(&mut issues, &[], "type.deserialize", &format!("Cannot deserialize: {}", err));
+        issues
+    })
+}
+
+// === Validating Deserializer ===
+
+/// Deserialization rejected by a validator (or failed); every error issue found
+#[derive(Debug)]
+pub struct ValidationError {
+    pub issues: Issues,
+}
+
+impl ValidationError {
+    fn new(code: &str, message: &str) -> Self {
+        let mut issues = Issues::new();
+        add_issue(&mut issues, &[], code, message);
+        ValidationError { issues }
+    }
+}
+
+impl std::fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let messages: Vec<String> = self.issues.iter().map(|i| format!("{}: {}", i.path, i.message)).collect();
+        f.write_str(&messages.join("; "))
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+impl serde::de::Error for ValidationError {
+    fn custom<T: std::fmt::Display>(msg: T) -> Self {
+        ValidationError::new("type.deserialize", &format!("Cannot deserialize: {}", msg))
+    }
+}
+
+/// Wraps a serde `Deserializer` so the validator runs on the input before it reaches the
+/// target type: the input is read once into a `Value`, checked, and only handed on if it
+/// has no errors. Warnings are dropped; call `validate` to see them.
+///
+/// `T::deserialize(ValidatedDeserializer::new(&mut serde_json::Deserializer::from_reader(r), &validator))`
+pub struct ValidatedDeserializer<'v, D> {
+    inner: D,
+    validator: &'v ValidatorFn<'v>,
+}
+
+impl<'v, D> ValidatedDeserializer<'v, D> {
+    pub fn new(inner: D, validator: &'v ValidatorFn<'v>) -> Self {
+        ValidatedDeserializer { inner, validator }
+    }
+
+    fn checked<'de>(self) -> Result<Value, ValidationError>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let value = Value::deserialize(self.inner)
+            .map_err(|err| ValidationError::new("json.parse_error", &format!("Invalid JSON: {}", err)))?;
+        reject_invalid(&value, self.validator)?;
+        Ok(value)
+    }
+}
+
+fn reject_invalid(value: &Value, validator: &ValidatorFn) -> Result<(), ValidationError> {
+    let mut issues = Issues::new();
+    validator(value, &[], &mut issues);
+    if error_count(&issues) > 0 {
+        issues.retain(|issue| issue.level == "error");
+        return Err(ValidationError { issues });
+    }
+    Ok(())
+}
+
+macro_rules! forward_checked {
+    ($($method:ident($($arg:ident: $ty:ty),*)),* $(,)?) => {
+        $(
+            fn $method<V: serde::de::Visitor<'de>>(self, $($arg: $ty,)* visitor: V) -> Result<V::Value, Self::Error> {
+                self.checked()?.$method($($arg,)* visitor).map_err(serde::de::Error::custom)
+            }
+        )*
+    };
+}
+
+impl<'de, 'v, D: serde::Deserializer<'de>> serde::Deserializer<'de> for ValidatedDeserializer<'v, D> {
+    type Error = ValidationError;
+
+    forward_checked! {
+        deserialize_any(), deserialize_bool(), deserialize_i8(), deserialize_i16(), deserialize_i32(),
+        deserialize_i64(), deserialize_u8(), deserialize_u16(), deserialize_u32(), deserialize_u64(),
+        deserialize_f32(), deserialize_f64(), deserialize_char(), deserialize_str(), deserialize_string(),
+        deserialize_bytes(), deserialize_byte_buf(), deserialize_option(), deserialize_unit(),
+        deserialize_seq(), deserialize_map(), deserialize_identifier(), deserialize_ignored_any(),
+        deserialize_unit_struct(name: &'static str),
+        deserialize_newtype_struct(name: &'static str),
+        deserialize_tuple(len: usize),
+        deserialize_tuple_struct(name: &'static str, len: usize),
+        deserialize_struct(name: &'static str, fields: &'static [&'static str]),
+        deserialize_enum(name: &'static str, variants: &'static [&'static str]),
+    }
+}
+
+/// Read JSON and deserialize it into T, rejecting data that fails the validator
+pub fn from_reader_validated<R: std::io::Read, T: serde::de::DeserializeOwned>(
+    reader: R,
+    validator: &ValidatorFn,
+) -> Result<T, ValidationError> {
+    let mut de = serde_json::Deserializer::from_reader(reader);
+    let value = T::deserialize(ValidatedDeserializer::new(&mut de, validator))?;
+    de.end().map_err(|err| ValidationError::new("json.parse_error", &format!("Invalid JSON: {}", err)))?;
+    Ok(value)
+}
+
+/// Deserialize a value into T, rejecting it if it fails the validator; the value is not copied
+pub fn from_value_validated<T: serde::de::DeserializeOwned>(
+    value: Value,
+    validator: &ValidatorFn,
+) -> Result<T, ValidationError> {
+    reject_invalid(&value, validator)?;
+    T::deserialize(value).map_err(serde::de::Error::custom)
+}
+
+// --- fs ---
 pub fn validate_path(
     bundle_path: &str,
-    validator: &dyn Fn(&str, &[String], &mut Issues) -> Option<FSContext>,
+    validator: &PathValidatorFn,
 ) -> ValidationResult {
     let mut issues: Issues = vec![];
     let _ = validator(bundle_path, &[], &mut issues);
     ValidationResult {
-        ok: issues.is_empty(),
+        ok: error_count(&issues) == 0,
         issues,
     }
 }
+// --- end fs ---
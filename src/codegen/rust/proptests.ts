@@ -0,0 +1,467 @@
+// src/codegen/rust/proptests.ts
+// proptest module checking a generated validator at its constraint boundaries
+
+import type { TypeDescription } from '../../base.js';
+import {
+  nameWords,
+  isRef,
+  fieldValue,
+  parseLiteral,
+  regexSource,
+  extractStringConstraints,
+  extractNumberConstraints,
+  extractDecimalConstraints,
+  extractListConstraints,
+} from '../base.js';
+
+type Children = { required?: TypeDescription[] | undefined; optional?: TypeDescription[] | undefined };
+
+/** Bounds used where a spec leaves a value unbounded */
+const SPAN = { string: 16, number: 1_000_000, list: 4 };
+
+const OBJECT = '.prop_map(|fields| Value::Object(fields.into_iter().filter_map(|(k, v)| v.map(|v| (k, v))).collect())).boxed()';
+
+function union(strategies: string[]): string {
+  return strategies.length === 1 ? strategies[0]! : `proptest::strategy::Union::new(vec![${strategies.join(', ')}]).boxed()`;
+}
+
+function float(n: number): string {
+  return Number.isInteger(n) ? `${n}.0_f64` : `${n}_f64`;
+}
+
+// A regex strategy for a pattern the validator searches for; proptest cannot generate anchors
+function unanchored(pattern: string): string | null {
+  const flags = pattern.match(/^\(\?[ims]+\)/)?.[0] ?? '';
+  const body = pattern.slice(flags.length).replace(/^\^/, '').replace(/(^|[^\\])\$$/, '$1');
+  return /(^|[^\\[])\^|(^|[^\\])\$/.test(body) ? null : flags + body;
+}
+
+/**
+ * Emits a `#[cfg(test)]` proptest module for a data spec: a strategy for values the
+ * spec accepts, and tests that values just inside each constraint (string lengths,
+ * numeric bounds, list sizes, enum options) are accepted and values just outside rejected.
+ * Constraints are checked along required fields from the root; parts of the spec no
+ * strategy can be derived for (e.g. Not, unique lists) are left unchecked.
+ */
+export class RustPropertyTestEmitter {
+  /** Bodies of named types, for uses described by name only */
+  private readonly named = new Map<string, TypeDescription>();
+  /** Named types whose strategy is being built; recursive uses get none */
+  private readonly visiting = new Set<string>();
+  private readonly tests: string[] = [];
+  private readonly testNames = new Set<string>();
+  /** Whether strategies use the `decimal` helper */
+  private usesDecimal = false;
+
+  constructor(private readonly escapeString: (s: string) => string) {}
+
+  emit(desc: TypeDescription): string {
+    this.collectNamed(desc);
+    const root = this.strategy(desc);
+    const lines = [
+      '// === Property Tests ===',
+      '// Needs proptest = "1" under [dev-dependencies]; run with `cargo test`',
+      '',
+      '#[cfg(test)]',
+      'mod property_tests {',
+    ];
+    if (root === null) {
+      lines.push('    // No strategy can be derived for values of this spec', '}');
+      return lines.join('\n');
+    }
+
+    this.test('accepts_valid_values', 'value in valid_root()', 'let result = validate_root(&value);', true);
+    this.locations(desc, []);
+
+    lines.push(
+      '    use super::*;',
+      '    use proptest::prelude::*;',
+      '',
+    );
+    if (this.usesDecimal) {
+      lines.push(
+        '    /// The decimal number units / 10^scale, written out exactly',
+        '    fn decimal(units: i64, scale: usize) -> Value {',
+        '        let digits = format!("{:0>width$}", units.unsigned_abs(), width = scale + 1);',
+        '        let (int, frac) = digits.split_at(digits.len() - scale);',
+        '        let sign = if units < 0 { "-" } else { "" };',
+        '        let text = if scale == 0 { format!("{}{}", sign, int) } else { format!("{}{}.{}", sign, int, frac) };',
+        '        serde_json::from_str(&text).unwrap()',
+        '    }',
+        '',
+      );
+    }
+    lines.push(
+      '    /// Replace (or with None, remove) the value at a path of object keys',
+      '    fn set_at(value: &mut Value, path: &[&str], new: Option<Value>) {',
+      '        let Some((last, parents)) = path.split_last() else {',
+      '            if let Some(new) = new {',
+      '                *value = new;',
+      '            }',
+      '            return;',
+      '        };',
+      '        let mut target = value;',
+      '        for key in parents {',
+      '            target = &mut target[*key];',
+      '        }',
+      '        if let Some(map) = target.as_object_mut() {',
+      '            match new {',
+      '                Some(new) => { map.insert(last.to_string(), new); }',
+      '                None => { map.remove(*last); }',
+      '            }',
+      '        }',
+      '    }',
+      '',
+      '    fn valid_root() -> BoxedStrategy<Value> {',
+      `        ${root}`,
+      '    }',
+      '',
+      '    proptest! {',
+      this.tests.join('\n\n'),
+      '    }',
+      '}',
+    );
+    return lines.join('\n');
+  }
+
+  private collectNamed(d: TypeDescription): void {
+    if (d.ref !== undefined && !isRef(d) && !this.named.has(d.ref)) {
+      this.named.set(d.ref, d.name === 'Field' ? fieldValue(d) ?? d : d);
+    }
+    for (const child of [...(d.children?.required ?? []), ...(d.children?.optional ?? [])]) this.collectNamed(child);
+    for (const option of d.oneOf ?? []) this.collectNamed(option);
+    for (const part of d.allOf ?? []) this.collectNamed(part);
+    for (const variant of Object.values(d.variants ?? {})) this.collectNamed(variant);
+    if (d.itemType) this.collectNamed(d.itemType);
+  }
+
+  // === Valid values ===
+
+  /** A `BoxedStrategy<Value>` of values the description accepts, or null if none can be derived */
+  private strategy(d: TypeDescription): string | null {
+    const base = this.baseStrategy(d);
+    return base !== null && d.nullable ? union([base, 'Just(Value::Null).boxed()']) : base;
+  }
+
+  private baseStrategy(d: TypeDescription): string | null {
+    if (d.ref !== undefined) {
+      const body = isRef(d) ? this.named.get(d.ref) : d;
+      if (!body || this.visiting.has(d.ref)) {
+        return null;
+      }
+      this.visiting.add(d.ref);
+      try {
+        return this.strategy({ ...body, ref: undefined, nullable: false });
+      } finally {
+        this.visiting.delete(d.ref);
+      }
+    }
+
+    switch (d.name) {
+      case 'String':
+      case 'Pattern':
+        return this.stringStrategy(d);
+      case 'Number':
+        return this.numberStrategy(d);
+      case 'Decimal': {
+        const bounds = this.decimalBounds(d);
+        if (bounds === null) return null;
+        this.usesDecimal = true;
+        return `(${bounds.lo}i64..=${bounds.hi}i64).prop_map(|n| decimal(n, ${bounds.scale})).boxed()`;
+      }
+      case 'Boolean':
+        return 'any::<bool>().prop_map(Value::from).boxed()';
+      case 'Literal':
+        return `Just(${this.literal(parseLiteral(d))}).boxed()`;
+      case 'ListOf':
+        return this.listStrategy(d);
+      case 'OneOf': {
+        const options = (d.oneOf ?? []).map(o => this.strategy(o)).filter((s): s is string => s !== null);
+        return options.length > 0 ? union(options) : null;
+      }
+      case 'TaggedUnion':
+        return this.taggedUnionStrategy(d);
+    }
+    const children = this.objectChildren(d);
+    return children ? this.objectStrategy(children) : null;
+  }
+
+  /** Fields of an object, or of an AllOf whose parts are all objects (e.g. Extend) */
+  private objectChildren(d: TypeDescription): Children | undefined {
+    if (d.children) {
+      return d.children;
+    }
+    const parts = (d.allOf ?? []).map(part => {
+      const body = isRef(part) && part.ref !== undefined ? this.named.get(part.ref) : part;
+      return body?.children;
+    });
+    if (parts.length === 0 || parts.some(part => part === undefined)) {
+      return undefined;
+    }
+    return {
+      required: parts.flatMap(part => part?.required ?? []),
+      optional: parts.flatMap(part => part?.optional ?? []),
+    };
+  }
+
+  private stringStrategy(d: TypeDescription): string | null {
+    const { minLength = 0, maxLength, pattern } = extractStringConstraints(d.constraints);
+    if (pattern === undefined) {
+      return this.letters(minLength, maxLength ?? minLength + SPAN.string);
+    }
+    const regex = unanchored(regexSource(pattern));
+    if (regex === null) {
+      return null;
+    }
+    const strings = `proptest::string::string_regex(${this.escapeString(regex)}).unwrap()`;
+    if (minLength === 0 && maxLength === undefined) {
+      return `${strings}.prop_map(Value::from).boxed()`;
+    }
+    const range = maxLength !== undefined ? `${minLength}..=${maxLength}` : `${minLength}..`;
+    return `${strings}.prop_filter("length", |s| (${range}).contains(&s.len())).prop_map(Value::from).boxed()`;
+  }
+
+  private letters(min: number, max: number): string {
+    return `proptest::string::string_regex(${this.escapeString(`[a-z]{${min},${max}}`)}).unwrap().prop_map(Value::from).boxed()`;
+  }
+
+  private numberStrategy(d: TypeDescription): string | null {
+    const bounds = this.numberBounds(d);
+    if (bounds === null) {
+      return null;
+    }
+    const { lo, hi, integer } = bounds;
+    return integer
+      ? `(${lo}i64..=${hi}i64).prop_map(Value::from).boxed()`
+      : `(${float(lo)}..=${float(hi)}).prop_map(Value::from).boxed()`;
+  }
+
+  private numberBounds(d: TypeDescription): { lo: number; hi: number; integer: boolean } | null {
+    const { min, max, integer = false } = extractNumberConstraints(d.constraints);
+    let lo = min ?? (max !== undefined ? max - SPAN.number : -SPAN.number);
+    let hi = max ?? lo + 2 * SPAN.number;
+    if (integer) {
+      lo = Math.ceil(lo);
+      hi = Math.floor(hi);
+    }
+    return lo <= hi ? { lo, hi, integer } : null;
+  }
+
+  /** Decimal values as integer units of the smallest decimal place */
+  private decimalBounds(d: TypeDescription): { lo: number; hi: number; scale: number } | null {
+    const { min, max, precision, scale = 0 } = extractDecimalConstraints(d.constraints);
+    if (precision !== undefined && scale > precision) {
+      return null;
+    }
+    const unit = 10 ** scale;
+    const limit = Math.min(precision !== undefined ? 10 ** precision - 1 : Number.MAX_SAFE_INTEGER, 1e15);
+    const lo = Math.max(min !== undefined ? Math.ceil(parseFloat(min) * unit) : -SPAN.number, -limit);
+    const hi = Math.min(max !== undefined ? Math.floor(parseFloat(max) * unit) : lo + 2 * SPAN.number, limit);
+    return lo <= hi ? { lo, hi, scale } : null;
+  }
+
+  private listStrategy(d: TypeDescription): string | null {
+    const { minItems = 0, maxItems, unique, uniqueBy, sorted, sortedBy } = extractListConstraints(d.constraints);
+    if (unique || uniqueBy !== undefined || sorted !== undefined || sortedBy !== undefined) {
+      return null;
+    }
+    const item = d.itemType ? this.strategy(d.itemType) : 'Just(Value::Null).boxed()';
+    if (item === null) {
+      return minItems === 0 ? 'Just(Value::Array(vec![])).boxed()' : null;
+    }
+    return this.list(item, `${minItems}..=${maxItems ?? minItems + SPAN.list}`);
+  }
+
+  private list(item: string, size: string): string {
+    return `proptest::collection::vec(${item}, ${size}).prop_map(Value::Array).boxed()`;
+  }
+
+  private objectStrategy(children: Children): string | null {
+    const entries = [
+      ...(children.required ?? []).map(d => ({ d, optional: d.optional ?? false })),
+      ...(children.optional ?? []).map(d => ({ d, optional: true })),
+    ];
+    const fields: string[] = [];
+    for (const { d, optional } of entries) {
+      if (d.name !== 'Field' || d.key === undefined) continue;
+      const value = fieldValue(d);
+      const strategy = value ? this.strategy(value) : 'Just(Value::Null).boxed()';
+      const key = this.escapeString(d.key);
+      if (strategy === null) {
+        if (!optional) return null;
+      } else if (optional) {
+        fields.push(`proptest::option::of(${strategy}).prop_map(|v| (${key}.to_string(), v)).boxed()`);
+      } else {
+        fields.push(`${strategy}.prop_map(|v| (${key}.to_string(), Some(v))).boxed()`);
+      }
+    }
+    return fields.length > 0 ? `vec![${fields.join(', ')}]${OBJECT}` : 'Just(Value::Object(Default::default())).boxed()';
+  }
+
+  private taggedUnionStrategy(d: TypeDescription): string | null {
+    const discriminator = this.escapeString(d.discriminator ?? 'type');
+    const variants: string[] = [];
+    for (const [tag, variant] of Object.entries(d.variants ?? {})) {
+      const body = isRef(variant) && variant.ref !== undefined ? this.named.get(variant.ref) ?? variant : variant;
+      const strategy = body.children ? this.objectStrategy(body.children) : null;
+      if (strategy !== null) {
+        variants.push(`${strategy}.prop_map(|mut v| { v[${discriminator}] = Value::from(${this.escapeString(tag)}); v }).boxed()`);
+      }
+    }
+    return variants.length > 0 ? union(variants) : null;
+  }
+
+  private literal(value: unknown): string {
+    return `serde_json::from_str::<Value>(${this.escapeString(JSON.stringify(value) ?? 'null')}).unwrap()`;
+  }
+
+  // === Boundary tests ===
+
+  /** Add boundary tests for a value always present at `path`, and for its required fields */
+  private locations(desc: TypeDescription, path: string[]): void {
+    const d = desc;
+    if (d.ref !== undefined) {
+      const body = isRef(d) ? this.named.get(d.ref) : d;
+      if (!body || this.visiting.has(d.ref)) return;
+      this.visiting.add(d.ref);
+      try {
+        this.locations({ ...body, ref: undefined, nullable: d.nullable }, path);
+      } finally {
+        this.visiting.delete(d.ref);
+      }
+      return;
+    }
+    // Object specs wrapped in modifiers (e.g. Nullable) are only described by their raw spec;
+    // generated validators do not check them field by field, so there is nothing to test
+    if (d.name === 'Object' && !d.children) {
+      return;
+    }
+
+    const at = `&[${path.map(key => this.escapeString(key)).join(', ')}]`;
+    const name = path.length > 0 ? path.flatMap(nameWords).join('_') : 'root';
+    const reject = (what: string, strategy: string) => {
+      this.test(`rejects_${name}_${what}`, `mut value in valid_root(), bad in ${strategy}`,
+        `set_at(&mut value, ${at}, Some(bad));\n            let result = validate_root(&value);`, false);
+    };
+    const accept = (what: string, strategy: string) => {
+      this.test(`accepts_${name}_${what}`, `mut value in valid_root(), edge in ${strategy}`,
+        `set_at(&mut value, ${at}, Some(edge));\n            let result = validate_root(&value);`, true);
+    };
+
+    switch (d.name) {
+      case 'String':
+      case 'Pattern': {
+        const { minLength, maxLength, pattern } = extractStringConstraints(d.constraints);
+        reject('wrong_type', 'any::<bool>().prop_map(Value::from)');
+        if (minLength !== undefined && minLength > 0) {
+          reject('too_short', this.letters(0, minLength - 1));
+          if (pattern === undefined) accept('min_length', this.letters(minLength, minLength));
+        }
+        if (maxLength !== undefined) {
+          reject('too_long', this.letters(maxLength + 1, maxLength + SPAN.string));
+          if (pattern === undefined) accept('max_length', this.letters(maxLength, maxLength));
+        }
+        return;
+      }
+      case 'Number': {
+        const { min, max, integer } = extractNumberConstraints(d.constraints);
+        const bounds = this.numberBounds(d);
+        reject('wrong_type', 'any::<bool>().prop_map(Value::from)');
+        if (bounds === null) return;
+        const value = (n: number) => integer ? `Value::from(${n}i64)` : `Value::from(${float(n)})`;
+        if (min !== undefined) {
+          reject('below_min', integer
+            ? `(${bounds.lo - 1000}i64..${bounds.lo}i64).prop_map(Value::from)`
+            : `(1u32..=1000).prop_map(|d| Value::from(${float(min)} - d as f64 / 8.0))`);
+          accept('at_min', `Just(${value(bounds.lo)})`);
+        }
+        if (max !== undefined) {
+          reject('above_max', integer
+            ? `(${bounds.hi + 1}i64..=${bounds.hi + 1000}i64).prop_map(Value::from)`
+            : `(1u32..=1000).prop_map(|d| Value::from(${float(max)} + d as f64 / 8.0))`);
+          accept('at_max', `Just(${value(bounds.hi)})`);
+        }
+        if (integer && bounds.lo < bounds.hi) {
+          reject('fraction', `(${bounds.lo}i64..${bounds.hi}i64).prop_map(|n| Value::from(n as f64 + 0.5))`);
+        }
+        return;
+      }
+      case 'Decimal': {
+        const { min, max, scale } = extractDecimalConstraints(d.constraints);
+        const bounds = this.decimalBounds(d);
+        reject('wrong_type', 'any::<bool>().prop_map(Value::from)');
+        if (bounds === null) return;
+        this.usesDecimal = true;
+        if (min !== undefined) {
+          reject('below_min', `(${bounds.lo - 1000}i64..${bounds.lo}i64).prop_map(|n| decimal(n, ${bounds.scale}))`);
+        }
+        if (max !== undefined) {
+          reject('above_max', `(${bounds.hi + 1}i64..=${bounds.hi + 1000}i64).prop_map(|n| decimal(n, ${bounds.scale}))`);
+        }
+        if (scale !== undefined) {
+          // One more decimal place than allowed, ending in a non-zero digit
+          reject('too_many_decimals', `(${bounds.lo}i64..=${bounds.hi}i64).prop_map(|n| decimal(n * 10 + if n < 0 { -5 } else { 5 }, ${scale + 1}))`);
+        }
+        return;
+      }
+      case 'Boolean':
+        reject('wrong_type', 'any::<i64>().prop_map(Value::from)');
+        return;
+      case 'ListOf': {
+        const { minItems, maxItems } = extractListConstraints(d.constraints);
+        const item = d.itemType ? this.strategy(d.itemType) : 'Just(Value::Null).boxed()';
+        reject('wrong_type', 'any::<bool>().prop_map(Value::from)');
+        if (item === null) return;
+        if (minItems !== undefined && minItems > 0) {
+          reject('too_few_items', this.list(item, `0..${minItems}`));
+        }
+        if (maxItems !== undefined) {
+          reject('too_many_items', this.list(item, `${maxItems + 1}..=${maxItems + SPAN.list}`));
+        }
+        return;
+      }
+      case 'OneOf': {
+        // String enums: every option is accepted, anything else rejected
+        const values = (d.oneOf ?? []).map(o => o.name === 'Literal' ? parseLiteral(o) : undefined);
+        if (values.length === 0 || !values.every(v => typeof v === 'string')) return;
+        const options = values.map(v => this.escapeString(v as string)).join(', ');
+        accept('options', `proptest::sample::select(vec![${options}]).prop_map(Value::from)`);
+        reject('other_value', `proptest::string::string_regex("[a-z0-9_-]{0,12}").unwrap()`
+          + `.prop_filter("an option", |s| ![${options}].contains(&s.as_str())).prop_map(Value::from)`);
+        return;
+      }
+    }
+
+    const children = this.objectChildren(d);
+    if (children) {
+      reject('wrong_type', 'any::<bool>().prop_map(Value::from)');
+      // A nullable object may be null, so its fields are not always present
+      if (d.nullable) return;
+      for (const child of children.required ?? []) {
+        if (child.name !== 'Field' || child.key === undefined || child.optional) continue;
+        const childPath = [...path, child.key];
+        this.test(`rejects_missing_${childPath.flatMap(nameWords).join('_')}`, 'mut value in valid_root()',
+          `set_at(&mut value, &[${childPath.map(key => this.escapeString(key)).join(', ')}], None);\n            let result = validate_root(&value);`, false);
+        const value = fieldValue(child);
+        if (value) {
+          this.locations(value, childPath);
+        }
+      }
+    }
+  }
+
+  private test(name: string, args: string, body: string, ok: boolean): void {
+    let unique = name;
+    for (let n = 2; this.testNames.has(unique); n++) {
+      unique = `${name}_${n}`;
+    }
+    this.testNames.add(unique);
+    this.tests.push([
+      '        #[test]',
+      `        fn ${unique}(${args}) {`,
+      `            ${body}`,
+      ok ? '            prop_assert!(result.ok, "{:?}", result.issues);' : '            prop_assert!(!result.ok);',
+      '        }',
+    ].join('\n'));
+  }
+}
@@ -0,0 +1,267 @@
+// src/codegen/rust/types.ts
+// Typed Rust data structures (serde) matching a data spec
+
+import type { TypeDescription } from '../../base.js';
+import { nameWords, isRef, fieldValue, parseLiteral } from '../base.js';
+
+type Children = { required?: TypeDescription[] | undefined; optional?: TypeDescription[] | undefined };
+
+const KEYWORDS = new Set([
+  'as', 'async', 'await', 'break', 'const', 'continue', 'crate', 'dyn', 'else', 'enum', 'extern', 'false',
+  'fn', 'for', 'if', 'impl', 'in', 'let', 'loop', 'match', 'mod', 'move', 'mut', 'pub', 'ref', 'return',
+  'self', 'static', 'struct', 'super', 'trait', 'true', 'type', 'unsafe', 'use', 'where', 'while', 'yield',
+]);
+
+const DERIVE = '#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]';
+
+function pascal(name: string): string {
+  return nameWords(name).map(w => w[0]!.toUpperCase() + w.slice(1)).join('');
+}
+
+function snake(name: string): string {
+  const ident = nameWords(name).join('_');
+  if (/^\d/.test(ident)) return `f_${ident}`;
+  return KEYWORDS.has(ident) ? `${ident}_` : ident;
+}
+
+// `///` lines for a description
+function doc(text: string | undefined, indent = ''): string[] {
+  return text ? text.split('\n').map(line => `${indent}/// ${line}`.trimEnd()) : [];
+}
+
+/**
+ * Emits `#[derive(Deserialize)]` structs and enums for a data spec.
+ * Objects become structs, string enums and tagged unions become enums, other
+ * OneOfs become untagged enums; anything without a fixed shape is a `Value`.
+ */
+export class RustTypeEmitter {
+  /** Declarations by Rust type name, in the order they were first used */
+  private readonly decls = new Map<string, string>();
+  /** Named types being declared; uses inside their own body are boxed */
+  private readonly pending = new Set<string>();
+  /** Bodies of named types, for recursive uses described by name only */
+  private readonly named = new Map<string, TypeDescription>();
+  /** serde default functions for fields with defaults */
+  private readonly defaults: string[] = [];
+
+  constructor(private readonly escapeString: (s: string) => string) {}
+
+  /**
+   * Declare the types for a root data description; returns the root's Rust type and the declarations
+   */
+  emit(desc: TypeDescription): { rootType: string; code: string } {
+    this.collectNamed(desc);
+    const rootType = this.typeOf(desc, desc.ref !== undefined ? pascal(desc.ref) : 'Root', false);
+    return { rootType, code: [...this.decls.values(), ...this.defaults].join('\n\n') };
+  }
+
+  private collectNamed(d: TypeDescription): void {
+    if (d.ref !== undefined && !isRef(d) && !this.named.has(d.ref)) {
+      this.named.set(d.ref, d.name === 'Field' ? fieldValue(d) ?? d : d);
+    }
+    for (const child of [...(d.children?.required ?? []), ...(d.children?.optional ?? [])]) this.collectNamed(child);
+    for (const option of d.oneOf ?? []) this.collectNamed(option);
+    for (const part of d.allOf ?? []) this.collectNamed(part);
+    for (const variant of Object.values(d.variants ?? {})) this.collectNamed(variant);
+    if (d.itemType) this.collectNamed(d.itemType);
+  }
+
+  /**
+   * Rust type for a value; `hint` names anonymous structs and enums it needs.
+   * `indirect` is set where the type is already behind a pointer (Vec items), so recursion needs no Box.
+   */
+  private typeOf(d: TypeDescription, hint: string, indirect: boolean): string {
+    const base = this.baseType(d, hint, indirect);
+    return d.nullable ? `Option<${base}>` : base;
+  }
+
+  private baseType(d: TypeDescription, hint: string, indirect: boolean): string {
+    if (d.ref !== undefined) {
+      const name = pascal(d.ref);
+      if (this.pending.has(name)) {
+        return indirect ? name : `Box<${name}>`;
+      }
+      const body = isRef(d) ? this.named.get(d.ref) : d;
+      if (!this.decls.has(name) && body) {
+        this.pending.add(name);
+        const declared = this.baseType({ ...body, ref: undefined, nullable: false }, name, indirect);
+        this.pending.delete(name);
+        // Named scalars and lists become aliases
+        if (declared !== name) {
+          this.decls.set(name, [...doc(body.description), `pub type ${name} = ${declared};`].join('\n'));
+        }
+      }
+      return name;
+    }
+
+    switch (d.name) {
+      case 'String':
+      case 'Pattern':
+        return 'String';
+      case 'Number':
+        return d.constraints?.includes('integer') ? 'i64' : 'f64';
+      case 'Decimal':
+        return 'serde_json::Number';
+      case 'Boolean':
+        return 'bool';
+      case 'Literal':
+        return literalType(d);
+      case 'ListOf':
+        return d.itemType ? `Vec<${this.typeOf(d.itemType, `${hint}Item`, true)}>` : 'Vec<Value>';
+      case 'OneOf':
+        return this.oneOfType(d.oneOf ?? [], hint, d.description);
+      case 'AllOf':
+        return this.allOfType(d.allOf ?? [], hint, d.description);
+      case 'TaggedUnion':
+        return this.taggedUnionType(d, hint);
+    }
+
+    if (d.children) {
+      return this.struct(this.unique(hint), d.children, undefined, d.description);
+    }
+    return 'Value';
+  }
+
+  private unique(name: string): string {
+    let candidate = name;
+    for (let n = 2; this.decls.has(candidate); n++) {
+      candidate = `${name}${n}`;
+    }
+    return candidate;
+  }
+
+  private struct(name: string, children: Children, omit?: string, description?: string): string {
+    this.decls.set(name, '');
+    const entries = [
+      ...(children.required ?? []).map(d => ({ d, optional: d.optional ?? false })),
+      ...(children.optional ?? []).map(d => ({ d, optional: true })),
+    ];
+
+    const lines = [...doc(description), DERIVE, `pub struct ${name} {`];
+    for (const { d, optional } of entries) {
+      if (d.name !== 'Field' || d.key === undefined || d.key === omit) continue;
+      const ident = snake(d.key);
+      const value = fieldValue(d);
+      let type = value ? this.typeOf(value, `${name}${pascal(d.key)}`, false) : 'Value';
+
+      lines.push(...doc(d.description, '    '));
+      if (ident !== d.key) {
+        lines.push(`    #[serde(rename = ${this.escapeString(d.key)})]`);
+      }
+      if (optional && d.default !== undefined && !d.nullable) {
+        const fn = `default_${snake(name)}_${ident}`.replace(/_+$/, '');
+        this.defaults.push(`fn ${fn}() -> ${type} {\n    serde_json::from_str(${this.escapeString(JSON.stringify(d.default))}).unwrap()\n}`);
+        lines.push(`    #[serde(default = ${this.escapeString(fn)})]`);
+      } else if (optional && !type.startsWith('Option<')) {
+        type = `Option<${type}>`;
+      }
+      lines.push(`    pub ${ident}: ${type},`);
+    }
+    lines.push('}');
+    this.decls.set(name, lines.join('\n'));
+    return name;
+  }
+
+  private oneOfType(options: TypeDescription[], hint: string, description?: string): string {
+    const literals = options.map(o => o.name === 'Literal' ? parseLiteral(o) : undefined);
+    if (literals.every(v => typeof v === 'string')) {
+      return this.stringEnum(this.unique(hint), literals as string[], description);
+    }
+
+    // One variant per distinct Rust type, tried in spec order
+    const variants = new Map<string, string>();
+    options.forEach((option, n) => {
+      const type = this.typeOf(option, `${hint}${n + 1}`, false);
+      if (![...variants.values()].includes(type)) {
+        variants.set(this.variantName(type, variants), type);
+      }
+    });
+    if (variants.size === 1) {
+      return [...variants.values()][0]!;
+    }
+
+    const name = this.unique(hint);
+    const lines = [...doc(description), DERIVE, '#[serde(untagged)]', `pub enum ${name} {`];
+    for (const [variant, type] of variants) {
+      lines.push(`    ${variant}(${type}),`);
+    }
+    lines.push('}');
+    this.decls.set(name, lines.join('\n'));
+    return name;
+  }
+
+  private variantName(type: string, taken: Map<string, string>): string {
+    const base = ({ String: 'Str', i64: 'Int', f64: 'Num', bool: 'Bool', Value: 'Any', 'serde_json::Number': 'Decimal' } as Record<string, string>)[type]
+      ?? (type.startsWith('Vec<') ? 'List' : type.replace(/^(Box|Option)<(.*)>$/, '$2'));
+    let name = base;
+    for (let n = 2; taken.has(name); n++) {
+      name = `${base}${n}`;
+    }
+    return name;
+  }
+
+  private stringEnum(name: string, values: string[], description?: string): string {
+    const lines = [...doc(description), DERIVE, `pub enum ${name} {`];
+    const used = new Set<string>();
+    values.forEach((value, n) => {
+      let base = /[A-Za-z0-9]/.test(value) ? pascal(value) : `Value${n + 1}`;
+      if (/^\d/.test(base)) base = `V${base}`;
+      let variant = base;
+      for (let k = 2; used.has(variant); k++) variant = `${base}${k}`;
+      used.add(variant);
+      lines.push(`    #[serde(rename = ${this.escapeString(value)})]`);
+      lines.push(`    ${variant},`);
+    });
+    lines.push('}');
+    this.decls.set(name, lines.join('\n'));
+    return name;
+  }
+
+  private allOfType(parts: TypeDescription[], hint: string, description?: string): string {
+    // Object parts (e.g. an extended base and its added fields) merge into one struct
+    const objects = parts
+      .map(part => isRef(part) && part.ref !== undefined ? this.named.get(part.ref) : part)
+      .filter((p): p is TypeDescription => p?.children !== undefined);
+    if (objects.length > 0) {
+      return this.struct(this.unique(hint), {
+        required: objects.flatMap(o => o.children?.required ?? []),
+        optional: objects.flatMap(o => o.children?.optional ?? []),
+      }, undefined, description);
+    }
+    return parts[0] ? this.typeOf(parts[0], hint, false) : 'Value';
+  }
+
+  private taggedUnionType(d: TypeDescription, hint: string): string {
+    const discriminator = d.discriminator ?? 'type';
+    const name = this.unique(hint);
+    this.decls.set(name, '');
+    const lines = [...doc(d.description), DERIVE, `#[serde(tag = ${this.escapeString(discriminator)})]`, `pub enum ${name} {`];
+    for (const [tag, variant] of Object.entries(d.variants ?? {})) {
+      // The tag is consumed by serde, so variant structs leave it out
+      const body = isRef(variant) && variant.ref !== undefined ? this.named.get(variant.ref) ?? variant : variant;
+      const variantName = pascal(tag);
+      lines.push(`    #[serde(rename = ${this.escapeString(tag)})]`);
+      if (body.children) {
+        lines.push(`    ${variantName}(${this.struct(this.unique(`${name}${variantName}`), body.children, discriminator)}),`);
+      } else {
+        lines.push(`    ${variantName},`);
+      }
+    }
+    lines.push('}');
+    this.decls.set(name, lines.join('\n'));
+    return name;
+  }
+}
+
+function literalType(d: TypeDescription): string {
+  const value = parseLiteral(d);
+  switch (typeof value) {
+    case 'string':
+      return 'String';
+    case 'boolean':
+      return 'bool';
+    case 'number':
+      return Number.isInteger(value) ? 'i64' : 'f64';
+  }
+  return 'Value';
+}
@@ -7,11 +7,16 @@ import { fileURLToPath } from 'node:url';
 import type { TypeDescription } from '../../base.js';
 import {
   CodeGenerator,
+  someDescription,
   type LanguageConfig,
+  type CodegenOptions,
   extractStringConstraints,
+  regexSource,
   extractNumberConstraints,
+  extractDecimalConstraints,
   extractListConstraints,
   extractBundleAccept,
+  whenBranch,
 } from '../base.js';
 
 const __dirname = path.dirname(fileURLToPath(import.meta.url));
@@ -31,14 +36,52 @@ export class SwiftGenerator extends CodeGenerator {
     return '"' + s.replace(/\\/g, '\\\\').replace(/"/g, '\\"').replace(/\n/g, '\\n') + '"';
   }
 
-  loadPrelude(): string {
+  loadPrelude(desc: TypeDescription): string {
     const preludePath = path.join(__dirname, this.config.preludeFile);
-    return fs.readFileSync(preludePath, 'utf-8');
+    const prelude = fs.readFileSync(preludePath, 'utf-8');
+    // CryptoKit is missing off Apple platforms, so only specs with checksums need swift-crypto there
+    if (someDescription(desc, d => d.checksum !== undefined)) {
+      return prelude;
+    }
+    return prelude.replace(/^\/\/ --- checksum ---\n[\s\S]*?^\/\/ --- end checksum ---\n/gm, '');
   }
 
   generateDataValidatorExpr(desc: TypeDescription): string {
     const name = desc.name;
 
+    // Metadata attached to every issue of the type (fields wrap their value validator below)
+    if (desc.meta && name !== 'Field') {
+      const innerExpr = this.generateDataValidatorExpr({ ...desc, meta: undefined });
+      return `{ v, p, i in validateMeta(v, p, &i, ${innerExpr}, ${this.escapeString(JSON.stringify(desc.meta))}) }`;
+    }
+
+    // Nullable (fields wrap their value validator below)
+    if (desc.nullable && name !== 'Field') {
+      const innerExpr = this.generateDataValidatorExpr({ ...desc, nullable: false });
+      return `{ v, p, i in validateNullable(v, p, &i, ${innerExpr}) }`;
+    }
+
+    // Spec-defined code/message: report the inner failure as one issue
+    if (desc.customIssue && name !== 'Field') {
+      const innerExpr = this.generateDataValidatorExpr({ ...desc, customIssue: undefined });
+      return `{ v, p, i in validateMessage(v, p, &i, ${innerExpr}, ${this.messageArgs(desc.customIssue)}) }`;
+    }
+
+    // Deprecated type: warn, then validate as usual
+    if (desc.deprecated && name !== 'Field') {
+      const innerExpr = this.generateDataValidatorExpr({ ...desc, deprecated: undefined });
+      return `{ v, p, i in validateDeprecated(v, p, &i, ${innerExpr}, "type.deprecated", ${this.escapeString(desc.deprecated)}) }`;
+    }
+
+    // Named type: emitted once as a function, referenced by name
+    if (name === 'Ref' && desc.ref !== undefined) {
+      return this.namedValidator(desc.ref, null);
+    }
+    if (desc.ref !== undefined && name !== 'Field') {
+      const bodyExpr = this.generateDataValidatorExpr({ ...desc, ref: undefined, maxDepth: undefined });
+      return this.namedValidator(desc.ref, bodyExpr, desc.maxDepth, desc.description);
+    }
+
     // Literal value
     if (name === 'Literal') {
       const val = desc.constraints?.[0]?.replace('equals ', '') ?? 'nil';
@@ -48,7 +91,7 @@ export class SwiftGenerator extends CodeGenerator {
 
     // Pattern
     if (name === 'Pattern') {
-      const pattern = desc.constraints?.[0]?.replace('matches ', '').replace(/^`|`$/g, '') ?? '';
+      const pattern = regexSource(desc.constraints?.[0]?.replace('matches ', '').replace(/^`|`$/g, '') ?? '');
       return `{ v, p, i in validatePattern(v, p, &i, ${this.escapeString(pattern)}) }`;
     }
 
@@ -58,7 +101,7 @@ export class SwiftGenerator extends CodeGenerator {
       const args: string[] = [];
       if (opts.minLength !== undefined) args.push(`minLength: ${opts.minLength}`);
       if (opts.maxLength !== undefined) args.push(`maxLength: ${opts.maxLength}`);
-      if (opts.pattern) args.push(`pattern: ${this.escapeString(opts.pattern)}`);
+      if (opts.pattern) args.push(`pattern: ${this.escapeString(regexSource(opts.pattern))}`);
 
       if (args.length === 0) {
         return '{ v, p, i in validateStr(v, p, &i) }';
@@ -80,6 +123,21 @@ export class SwiftGenerator extends CodeGenerator {
       return `{ v, p, i in validateNum(v, p, &i, ${args.join(', ')}) }`;
     }
 
+    // Decimal
+    if (name === 'Decimal') {
+      const opts = extractDecimalConstraints(desc.constraints);
+      const args: string[] = [];
+      if (opts.min !== undefined) args.push(`min: ${this.escapeString(opts.min)}`);
+      if (opts.max !== undefined) args.push(`max: ${this.escapeString(opts.max)}`);
+      if (opts.precision !== undefined) args.push(`precision: ${opts.precision}`);
+      if (opts.scale !== undefined) args.push(`scale: ${opts.scale}`);
+
+      if (args.length === 0) {
+        return '{ v, p, i in validateDecimal(v, p, &i) }';
+      }
+      return `{ v, p, i in validateDecimal(v, p, &i, ${args.join(', ')}) }`;
+    }
+
     // Boolean
     if (name === 'Boolean') {
       return '{ v, p, i in validateBool(v, p, &i) }';
@@ -91,6 +149,27 @@ export class SwiftGenerator extends CodeGenerator {
       return `{ v, p, i in validateOneOf(v, p, &i, [${options.join(', ')}]) }`;
     }
 
+    // AllOf
+    if (name === 'AllOf' && desc.allOf) {
+      const parts = desc.allOf.map(part => this.generateDataValidatorExpr(part));
+      return `{ v, p, i in validateAllOf(v, p, &i, [${parts.join(', ')}]) }`;
+    }
+
+    // Not
+    if (name === 'Not' && desc.not) {
+      const inner = this.generateDataValidatorExpr(desc.not);
+      return `{ v, p, i in validateNot(v, p, &i, ${inner}) }`;
+    }
+
+    // TaggedUnion
+    if (name === 'TaggedUnion' && desc.discriminator !== undefined && desc.variants) {
+      const variants = Object.entries(desc.variants).map(
+        ([tag, variant]) => `${this.escapeString(tag)}: ${this.generateDataValidatorExpr(variant)}`
+      );
+      const table = variants.length > 0 ? variants.join(', ') : ':';
+      return `{ v, p, i in validateTaggedUnion(v, p, &i, ${this.escapeString(desc.discriminator)}, [${table}]) }`;
+    }
+
     // ListOf
     if (name === 'ListOf' && desc.itemType) {
       const itemExpr = this.generateDataValidatorExpr(desc.itemType);
@@ -98,6 +177,10 @@ export class SwiftGenerator extends CodeGenerator {
       const args: string[] = [`itemValidator: ${itemExpr}`];
       if (opts.minItems !== undefined) args.push(`minItems: ${opts.minItems}`);
       if (opts.maxItems !== undefined) args.push(`maxItems: ${opts.maxItems}`);
+      if (opts.unique) args.push('unique: true');
+      if (opts.uniqueBy !== undefined) args.push(`uniqueBy: ${this.escapeString(opts.uniqueBy)}`);
+      if (opts.sorted !== undefined) args.push(`sorted: ${this.escapeString(opts.sorted)}`);
+      if (opts.sortedBy !== undefined) args.push(`sortedBy: ${this.escapeString(opts.sortedBy)}`);
       return `{ v, p, i in validateList(v, p, &i, ${args.join(', ')}) }`;
     }
 
@@ -111,6 +194,16 @@ export class SwiftGenerator extends CodeGenerator {
       if (desc.oneOf) {
         const options = desc.oneOf.map(opt => this.generateDataValidatorExpr(opt));
         valueExpr = `{ v, p, i in validateOneOf(v, p, &i, [${options.join(', ')}]) }`;
+      } else if (desc.allOf) {
+        valueExpr = this.generateDataValidatorExpr({ name: 'AllOf', allOf: desc.allOf });
+      } else if (desc.not) {
+        valueExpr = this.generateDataValidatorExpr({ name: 'Not', not: desc.not });
+      } else if (desc.variants) {
+        valueExpr = this.generateDataValidatorExpr({
+          name: 'TaggedUnion',
+          discriminator: desc.discriminator,
+          variants: desc.variants,
+        });
       } else if (desc.itemType) {
         valueExpr = this.generateDataValidatorExpr({
           name: 'ListOf',
@@ -126,6 +219,26 @@ export class SwiftGenerator extends CodeGenerator {
         });
       }
 
+      if (valueExpr && desc.ref !== undefined) {
+        valueExpr = this.namedValidator(desc.ref, desc.summary === 'Ref' ? null : valueExpr, desc.maxDepth, desc.valueDescription);
+      }
+      if (valueExpr && desc.valueDeprecated) {
+        valueExpr = `{ v, p, i in validateDeprecated(v, p, &i, ${valueExpr}, "type.deprecated", ${this.escapeString(desc.valueDeprecated)}) }`;
+      }
+      if (valueExpr && desc.customIssue) {
+        valueExpr = `{ v, p, i in validateMessage(v, p, &i, ${valueExpr}, ${this.messageArgs(desc.customIssue)}) }`;
+      }
+
+      if (valueExpr && desc.nullable) {
+        valueExpr = `{ v, p, i in validateNullable(v, p, &i, ${valueExpr}) }`;
+      }
+      if (valueExpr && desc.meta) {
+        valueExpr = `{ v, p, i in validateMeta(v, p, &i, ${valueExpr}, ${this.escapeString(JSON.stringify(desc.meta))}) }`;
+      }
+      if (desc.deprecated) {
+        valueExpr = `{ v, p, i in validateDeprecated(v, p, &i, ${valueExpr ?? '{ _, _, _ in }'}, "field.deprecated", ${this.escapeString(desc.deprecated)}) }`;
+      }
+
       if (valueExpr) {
         args.push(`validator: ${valueExpr}`);
       }
@@ -150,18 +263,15 @@ export class SwiftGenerator extends CodeGenerator {
   ): string {
     const fieldExprs: string[] = [];
 
-    for (const child of children.required ?? []) {
-      fieldExprs.push(this.generateDataValidatorExpr(child));
-    }
-    for (const child of children.optional ?? []) {
-      fieldExprs.push(this.generateDataValidatorExpr({ ...child, optional: true }));
+    for (const { desc, optional } of this.scheduleChildren(children)) {
+      fieldExprs.push(this.generateDataValidatorExpr(optional ? { ...desc, optional: true } : desc));
     }
 
     if (fieldExprs.length === 0) {
       return '{ v, p, i in _ = validateObject(v, p, &i) }';
     }
 
-    const fieldCalls = fieldExprs.map(expr => `(${expr})(v, p, &i)`).join('; ');
+    const fieldCalls = this.joinChecks(fieldExprs.map(expr => `(${expr})(v, p, &i)`));
     return `{ v, p, i in if validateObject(v, p, &i) { ${fieldCalls} } }`;
   }
 
@@ -170,24 +280,41 @@ export class SwiftGenerator extends CodeGenerator {
   ): string {
     const parts: string[] = [];
 
-    for (const child of children.required ?? []) {
-      parts.push(this.generateFSChildExpr(child));
-    }
-    for (const child of children.optional ?? []) {
-      parts.push(this.generateFSChildExpr(child));
+    for (const { desc } of this.scheduleChildren(children)) {
+      parts.push(this.generateFSChildExpr(desc));
     }
 
     if (parts.length === 0) {
       return '{ _, _, _ in }';
     }
 
-    return `{ ctx, p, i in ${parts.join('; ')} }`;
+    return `{ ctx, p, i in ${this.joinChecks(parts)} }`;
+  }
+
+  /**
+   * Join check statements, returning early after a check reports errors in fail-fast mode
+   */
+  private joinChecks(calls: string[]): string {
+    if (!this.options.failFast) {
+      return calls.join('; ');
+    }
+    return `let n = errorCount(i); ${calls.map(call => `${call}; if errorCount(i) > n { return }`).join('; ')}`;
   }
 
   generateFSChildExpr(desc: TypeDescription): string {
     const fsType = desc.fsType;
     const comment = desc.description ? `/* ${desc.description} */ ` : '';
 
+    if (desc.name === 'When' && desc.condition) {
+      const { file, field, is } = desc.condition;
+      const args = [`file: ${this.escapeString(file)}`];
+      if (field !== undefined) args.push(`field: ${this.escapeString(field)}`);
+      if (is) args.push(`is: ${this.generateDataValidatorExpr(is)}`);
+      if (desc.then) args.push(`then: ${this.generateBundleContentExpr(whenBranch(desc.then))}`);
+      if (desc.else) args.push(`otherwise: ${this.generateBundleContentExpr(whenBranch(desc.else))}`);
+      return `${comment}validateWhen(ctx, p, &i, ${args.join(', ')})`;
+    }
+
     if (fsType === 'jsonFile' && desc.filePath) {
       const contentExpr = desc.children ? this.generateObjectExpr(desc.children) : 'nil';
       return `${comment}_ = validateJsonFile(ctx, ${this.escapeString(desc.filePath)}, p, &i, contentValidator: ${contentExpr})`;
@@ -195,7 +322,12 @@ export class SwiftGenerator extends CodeGenerator {
 
     if (fsType === 'file' && desc.filePath) {
       const ext = desc.fileExt ? `, ext: ${this.escapeString(desc.fileExt)}` : '';
-      return `${comment}_ = validateFsFile(ctx, ${this.escapeString(desc.filePath)}, p, &i${ext})`;
+      const fileCheck = `validateFsFile(ctx, ${this.escapeString(desc.filePath)}, p, &i${ext})`;
+      if (desc.checksum) {
+        const { algorithm, value } = desc.checksum;
+        return `${comment}if ${fileCheck} { _ = validateFileChecksum(ctx, ${this.escapeString(desc.filePath)}, p, &i, algorithm: ${this.escapeString(algorithm)}, expected: ${this.escapeString(value)}) }`;
+      }
+      return `${comment}_ = ${fileCheck}`;
     }
 
     if (fsType === 'directory' && desc.filePath) {
@@ -277,15 +409,54 @@ if CommandLine.arguments.count >= 2 {
     return `let rootValidator: (String, [String], inout Issues) -> FSContext? = ${rootExpr}`;
   }
 
+  protected definitionIdent(words: string[]): string {
+    return `validateDef${words.map(w => w[0]!.toUpperCase() + w.slice(1)).join('')}`;
+  }
+
+  protected generateDefinition(name: string, ident: string, expr: string, maxDepth?: number): string {
+    if (maxDepth === undefined) {
+      return [
+        `func ${ident}(_ v: Any, _ p: [String], _ i: inout Issues) {`,
+        `    let validator: Validator = ${expr}`,
+        '    validator(v, p, &i)',
+        '}',
+      ].join('\n');
+    }
+    const message = this.escapeString(`Nesting of ${name} exceeds maximum depth ${maxDepth}`);
+    return [
+      `var ${ident}Depth = 0`,
+      `func ${ident}(_ v: Any, _ p: [String], _ i: inout Issues) {`,
+      `    if ${ident}Depth >= ${maxDepth} {`,
+      `        addIssue(&i, p, "recursion.too_deep", ${message})`,
+      '        return',
+      '    }',
+      `    ${ident}Depth += 1`,
+      `    defer { ${ident}Depth -= 1 }`,
+      `    let validator: Validator = ${expr}`,
+      '    validator(v, p, &i)',
+      '}',
+    ].join('\n');
+  }
+
+  /** Code and message arguments for validateMessage */
+  private messageArgs(customIssue: NonNullable<TypeDescription['customIssue']>): string {
+    const opt = (s: string | undefined): string => s === undefined ? 'nil' : this.escapeString(s);
+    return `${opt(customIssue.code)}, ${opt(customIssue.message)}`;
+  }
+
   protected commentLine(text: string): string {
     return `// ${text}`;
   }
+
+  protected docComment(text: string): string[] {
+    return text.split('\n').map(line => `/// ${line}`.trimEnd());
+  }
 }
 
 /**
  * Generate Swift validator code from TypeDescription
  */
-export function generateSwift(desc: TypeDescription): string {
-  const generator = new SwiftGenerator();
+export function generateSwift(desc: TypeDescription, options: CodegenOptions = {}): string {
+  const generator = new SwiftGenerator(options);
   return generator.generate(desc);
 }
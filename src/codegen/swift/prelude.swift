@@ -1,14 +1,65 @@
 // SpecSpec Swift Prelude
 // Validation primitives - embedded at top of generated validators
+// Sections between "--- checksum ---" markers are left out unless the spec has checksum rules.
 
 import Foundation
+// --- checksum ---
+#if canImport(CryptoKit)
+import CryptoKit
+#else
+import Crypto
+#endif
+// --- end checksum ---
 
 // MARK: - Types
 
 public struct Issue: Codable {
+    /// "error" or "warning"; warnings do not fail validation
+    public let level: String
     public let path: String
     public let code: String
     public let message: String
+    /// Metadata of the rule that reported the issue (`Meta()` in the spec)
+    public var meta: [String: MetaValue]? = nil
+}
+
+/// A JSON value in issue metadata
+public indirect enum MetaValue: Codable {
+    case null
+    case bool(Bool)
+    case number(Double)
+    case string(String)
+    case array([MetaValue])
+    case object([String: MetaValue])
+
+    public init(from decoder: Decoder) throws {
+        let container = try decoder.singleValueContainer()
+        if container.decodeNil() {
+            self = .null
+        } else if let b = try? container.decode(Bool.self) {
+            self = .bool(b)
+        } else if let n = try? container.decode(Double.self) {
+            self = .number(n)
+        } else if let s = try? container.decode(String.self) {
+            self = .string(s)
+        } else if let items = try? container.decode([MetaValue].self) {
+            self = .array(items)
+        } else {
+            self = .object(try container.decode([String: MetaValue].self))
+        }
+    }
+
+    public func encode(to encoder: Encoder) throws {
+        var container = encoder.singleValueContainer()
+        switch self {
+        case .null: try container.encodeNil()
+        case .bool(let b): try container.encode(b)
+        case .number(let n): try container.encode(n)
+        case .string(let s): try container.encode(s)
+        case .array(let items): try container.encode(items)
+        case .object(let fields): try container.encode(fields)
+        }
+    }
 }
 
 public typealias Issues = [Issue]
@@ -20,7 +71,15 @@ public struct ValidationResult: Codable {
 }
 
 private func addIssue(_ issues: inout Issues, _ path: [String], _ code: String, _ message: String) {
-    issues.append(Issue(path: path.isEmpty ? "(root)" : path.joined(separator: "."), code: code, message: message))
+    issues.append(Issue(level: "error", path: path.isEmpty ? "(root)" : path.joined(separator: "."), code: code, message: message))
+}
+
+private func addWarning(_ issues: inout Issues, _ path: [String], _ code: String, _ message: String) {
+    issues.append(Issue(level: "warning", path: path.isEmpty ? "(root)" : path.joined(separator: "."), code: code, message: message))
+}
+
+public func errorCount(_ issues: Issues) -> Int {
+    return issues.filter { $0.level == "error" }.count
 }
 
 // MARK: - Primitive Validators
@@ -71,6 +130,35 @@ public func validateNum(_ value: Any, _ path: [String], _ issues: inout Issues,
     }
 }
 
+public func validateDecimal(_ value: Any, _ path: [String], _ issues: inout Issues,
+                            min: String? = nil, max: String? = nil, precision: Int? = nil, scale: Int? = nil) {
+    guard !(value is Bool), let num = (value as? NSNumber)?.decimalValue, num.isFinite else {
+        addIssue(&issues, path, "type.mismatch", "Expected number, got \(type(of: value))")
+        return
+    }
+
+    // Decimal.description never uses exponent notation, so digits can be counted directly
+    let text = num.description
+    let unsigned = text.hasPrefix("-") ? String(text.dropFirst()) : text
+    let parts = unsigned.split(separator: ".", maxSplits: 1).map(String.init)
+    let intPart = String((parts.first ?? "").drop(while: { $0 == "0" }))
+    let fracPart = parts.count > 1 ? String(parts[1].reversed().drop(while: { $0 == "0" }).reversed()) : ""
+    let digits = intPart.count + fracPart.count
+
+    if let p = precision, digits > p {
+        addIssue(&issues, path, "decimal.too_many_digits", "Number \(text) has \(digits) digits, maximum is \(p)")
+    }
+    if let s = scale, fracPart.count > s {
+        addIssue(&issues, path, "decimal.scale_exceeded", "Number \(text) has \(fracPart.count) decimal places, maximum is \(s)")
+    }
+    if let m = min, let bound = Decimal(string: m, locale: Locale(identifier: "en_US_POSIX")), num < bound {
+        addIssue(&issues, path, "num.too_small", "Number \(text) is less than minimum \(m)")
+    }
+    if let m = max, let bound = Decimal(string: m, locale: Locale(identifier: "en_US_POSIX")), num > bound {
+        addIssue(&issues, path, "num.too_large", "Number \(text) exceeds maximum \(m)")
+    }
+}
+
 public func validateBool(_ value: Any, _ path: [String], _ issues: inout Issues) {
     if !(value is Bool) {
         addIssue(&issues, path, "type.mismatch", "Expected boolean, got \(type(of: value))")
@@ -125,8 +213,28 @@ public func validateField(_ obj: Any, _ path: [String], _ issues: inout Issues,
     }
 }
 
+public func getKeyPath(_ value: Any, _ keyPath: String) -> Any? {
+    var current: Any = value
+    for key in keyPath.split(separator: ".") {
+        guard let dict = current as? [String: Any], let next = dict[String(key)] else {
+            return nil
+        }
+        current = next
+    }
+    return current
+}
+
+private func canonicalJson(_ value: Any) -> String {
+    guard let data = try? JSONSerialization.data(withJSONObject: value, options: [.sortedKeys, .fragmentsAllowed]) else {
+        return String(describing: value)
+    }
+    return String(data: data, encoding: .utf8) ?? ""
+}
+
 public func validateList(_ value: Any, _ path: [String], _ issues: inout Issues,
-                         itemValidator: Validator? = nil, minItems: Int? = nil, maxItems: Int? = nil) {
+                         itemValidator: Validator? = nil, minItems: Int? = nil, maxItems: Int? = nil,
+                         unique: Bool = false, uniqueBy: String? = nil,
+                         sorted: String? = nil, sortedBy: String? = nil) {
     guard let arr = value as? [Any] else {
         addIssue(&issues, path, "type.mismatch", "Expected array, got \(type(of: value))")
         return
@@ -139,6 +247,59 @@ public func validateList(_ value: Any, _ path: [String], _ issues: inout Issues,
         addIssue(&issues, path, "list.too_long", "Array length \(arr.count) exceeds maximum \(max)")
     }
 
+    if unique || uniqueBy != nil {
+        var seen: [String: Int] = [:]
+        for (i, item) in arr.enumerated() {
+            let keyValue: Any
+            if let keyPath = uniqueBy {
+                guard let v = getKeyPath(item, keyPath) else { continue }
+                keyValue = v
+            } else {
+                keyValue = item
+            }
+            let key = canonicalJson(keyValue)
+            if let first = seen[key] {
+                let message = uniqueBy.map { "Items [\(first)] and [\(i)] share \($0) \(key)" }
+                    ?? "Items [\(first)] and [\(i)] are duplicates"
+                addIssue(&issues, path + ["[\(i)]"], "list.duplicate", message)
+            } else {
+                seen[key] = i
+            }
+        }
+    }
+
+    if sorted != nil || sortedBy != nil {
+        let descending = sorted == "desc"
+        // Only the first out-of-order pair is reported
+        var prev: (index: Int, key: Any)?
+        for (i, item) in arr.enumerated() {
+            let key: Any
+            if let keyPath = sortedBy {
+                guard let v = getKeyPath(item, keyPath) else { continue }
+                key = v
+            } else {
+                key = item
+            }
+            guard key is String || (key is NSNumber && !(key is Bool)) else { continue }
+            if let p = prev {
+                var outOfOrder = false
+                if let a = p.key as? String, let b = key as? String {
+                    outOfOrder = descending ? b > a : b < a
+                } else if !(p.key is String), !(key is String), let a = p.key as? NSNumber, let b = key as? NSNumber {
+                    outOfOrder = descending ? b.doubleValue > a.doubleValue : b.doubleValue < a.doubleValue
+                }
+                if outOfOrder {
+                    let order = descending ? "descending" : "ascending"
+                    let by = sortedBy.map { " by \($0)" } ?? ""
+                    addIssue(&issues, path + ["[\(i)]"], "list.not_sorted",
+                             "Items [\(p.index)] and [\(i)] are not in \(order) order\(by)")
+                    break
+                }
+            }
+            prev = (i, key)
+        }
+    }
+
     if let iv = itemValidator {
         for (i, item) in arr.enumerated() {
             iv(item, path + ["[\(i)]"], &issues)
@@ -151,13 +312,96 @@ public func validateOneOf(_ value: Any, _ path: [String], _ issues: inout Issues
     for validator in validators {
         var testIssues: Issues = []
         validator(value, path, &testIssues)
-        if testIssues.isEmpty {
-            return // Matched
+        if errorCount(testIssues) == 0 {
+            issues.append(contentsOf: testIssues) // Matched; keep its warnings
+            return
         }
     }
     addIssue(&issues, path, "oneof.no_match", "Value does not match any of the options")
 }
 
+public func validateAllOf(_ value: Any, _ path: [String], _ issues: inout Issues,
+                          _ validators: [Validator]) {
+    // Every part runs; issues shared by several parts are reported once
+    var merged: Issues = []
+    for validator in validators {
+        var partIssues: Issues = []
+        validator(value, path, &partIssues)
+        for issue in partIssues where !merged.contains(where: {
+            $0.path == issue.path && $0.code == issue.code && $0.message == issue.message
+        }) {
+            merged.append(issue)
+        }
+    }
+    issues.append(contentsOf: merged)
+}
+
+public func validateTaggedUnion(_ value: Any, _ path: [String], _ issues: inout Issues,
+                                _ discriminator: String, _ variants: KeyValuePairs<String, Validator>) {
+    guard let dict = value as? [String: Any] else {
+        addIssue(&issues, path, "type.mismatch", "Expected object, got \(type(of: value))")
+        return
+    }
+    guard let tag = dict[discriminator] else {
+        addIssue(&issues, path, "field.missing", "Missing required field: \(discriminator)")
+        return
+    }
+    // Only the variant selected by the tag runs
+    if let tagStr = tag as? String, let variant = variants.first(where: { $0.key == tagStr }) {
+        variant.value(value, path, &issues)
+        return
+    }
+    let expected = variants.map { $0.key }.joined(separator: ", ")
+    addIssue(&issues, path + [discriminator], "union.unknown_tag",
+             "Unknown \(discriminator) \(canonicalJson(tag)); expected one of: \(expected)")
+}
+
+public func validateNot(_ value: Any, _ path: [String], _ issues: inout Issues,
+                        _ inner: Validator) {
+    var testIssues: Issues = []
+    inner(value, path, &testIssues)
+    if errorCount(testIssues) == 0 {
+        addIssue(&issues, path, "not.matched", "Value matches a disallowed schema")
+    }
+}
+
+public func validateNullable(_ value: Any, _ path: [String], _ issues: inout Issues,
+                             _ inner: Validator) {
+    if value is NSNull {
+        return
+    }
+    inner(value, path, &issues)
+}
+
+public func validateMessage(_ value: Any, _ path: [String], _ issues: inout Issues,
+                            _ inner: Validator, _ code: String?, _ message: String?) {
+    var innerIssues: Issues = []
+    inner(value, path, &innerIssues)
+    issues.append(contentsOf: innerIssues.filter { $0.level == "warning" })
+    if let first = innerIssues.first(where: { $0.level == "error" }) {
+        addIssue(&issues, path, code ?? first.code, message ?? first.message)
+    }
+}
+
+public func validateDeprecated(_ value: Any, _ path: [String], _ issues: inout Issues,
+                               _ inner: Validator, _ code: String, _ message: String) {
+    addWarning(&issues, path, code, message)
+    inner(value, path, &issues)
+}
+
+/// Attach metadata (a JSON object) to the issues of the inner validator; keys already on an issue, from a nested Meta(), win
+public func validateMeta(_ value: Any, _ path: [String], _ issues: inout Issues,
+                         _ inner: Validator, _ meta: String) {
+    var innerIssues: Issues = []
+    inner(value, path, &innerIssues)
+    if innerIssues.isEmpty { return }
+    let parsed = (try? JSONDecoder().decode([String: MetaValue].self, from: Data(meta.utf8))) ?? [:]
+    for var issue in innerIssues {
+        issue.meta = parsed.merging(issue.meta ?? [:]) { _, own in own }
+        issues.append(issue)
+    }
+}
+
 // MARK: - File System Context
 
 public class FSContext {
@@ -227,6 +471,17 @@ public class FSContext {
         return try String(contentsOfFile: fullPath, encoding: .utf8)
     }
 
+    public func readBytes(_ relPath: String) throws -> Data {
+        if isZip {
+            guard let data = zipEntries[relPath] else {
+                throw NSError(domain: "FSContext", code: 3, userInfo: [NSLocalizedDescriptionKey: "File not found in zip: \(relPath)"])
+            }
+            return data
+        }
+        let fullPath = (basePath as NSString).appendingPathComponent(relPath)
+        return try Data(contentsOf: URL(fileURLWithPath: fullPath))
+    }
+
     public func readJson(_ relPath: String) throws -> Any {
         let content = try read(relPath)
         guard let data = content.data(using: .utf8) else {
@@ -350,6 +605,41 @@ public func validateFsFile(_ ctx: FSContext, _ relPath: String, _ path: [String]
     return true
 }
 
+// --- checksum ---
+// Digests available to checksum rules; register more (e.g. blake3) by name
+public var digests: [String: (Data) -> String] = [
+    "sha256": { data in SHA256.hash(data: data).map { String(format: "%02x", $0) }.joined() },
+    "sha384": { data in SHA384.hash(data: data).map { String(format: "%02x", $0) }.joined() },
+    "sha512": { data in SHA512.hash(data: data).map { String(format: "%02x", $0) }.joined() },
+]
+
+public func validateFileChecksum(_ ctx: FSContext, _ relPath: String, _ path: [String], _ issues: inout Issues,
+                                 algorithm: String, expected: String) -> Bool {
+    let filePath = path + [relPath]
+
+    guard let digest = digests[algorithm.lowercased()] else {
+        addIssue(&issues, filePath, "checksum.unsupported_algorithm", "Digest algorithm not available: \(algorithm)")
+        return false
+    }
+
+    let data: Data
+    do {
+        data = try ctx.readBytes(relPath)
+    } catch {
+        addIssue(&issues, filePath, "file.read_error", error.localizedDescription)
+        return false
+    }
+
+    let actual = digest(data)
+    if actual != expected.lowercased() {
+        addIssue(&issues, filePath, "checksum.mismatch", "\(algorithm) digest mismatch: expected \(expected), got \(actual)")
+        return false
+    }
+
+    return true
+}
+// --- end checksum ---
+
 public func validateFsDirectory(_ ctx: FSContext, _ relPath: String, _ path: [String], _ issues: inout Issues) -> Bool {
     let dirPath = path + [relPath]
 
@@ -366,17 +656,47 @@ public func validateFsDirectory(_ ctx: FSContext, _ relPath: String, _ path: [St
     return true
 }
 
+/// Check `then` when the value at `field` of the JSON `file` is present and matches `is`,
+/// and `otherwise` when not; a file that cannot be read or parsed decides nothing
+public func validateWhen(_ ctx: FSContext, _ path: [String], _ issues: inout Issues,
+                         file: String, field: String? = nil, is test: Validator? = nil,
+                         then: FSValidator? = nil, otherwise: FSValidator? = nil) {
+    guard let document = try? ctx.readJson(file) else {
+        return
+    }
+    var value: Any? = document
+    for segment in field?.split(separator: ".", omittingEmptySubsequences: false) ?? [] {
+        if let object = value as? [String: Any] {
+            value = object[String(segment)]
+        } else if let items = value as? [Any], let index = Int(segment), items.indices.contains(index) {
+            value = items[index]
+        } else {
+            value = nil
+        }
+    }
+
+    var holds = value != nil
+    if let value = value, let test = test {
+        var testIssues: Issues = []
+        test(value, path, &testIssues)
+        holds = errorCount(testIssues) == 0
+    }
+    if let content = holds ? then : otherwise {
+        content(ctx, path, &issues)
+    }
+}
+
 // MARK: - Entry Points
 
 public func validate(_ value: Any, _ validator: Validator) -> ValidationResult {
     var issues: Issues = []
     validator(value, [], &issues)
-    return ValidationResult(ok: issues.isEmpty, issues: issues)
+    return ValidationResult(ok: errorCount(issues) == 0, issues: issues)
 }
 
 public func validatePath(_ bundlePath: String,
                          _ validator: @escaping (String, [String], inout Issues) -> FSContext?) -> ValidationResult {
     var issues: Issues = []
     _ = validator(bundlePath, [], &issues)
-    return ValidationResult(ok: issues.isEmpty, issues: issues)
+    return ValidationResult(ok: errorCount(issues) == 0, issues: issues)
 }
@@ -8,10 +8,13 @@ import type { TypeDescription } from '../../base.js';
 import {
   CodeGenerator,
   type LanguageConfig,
+  type CodegenOptions,
   extractStringConstraints,
   extractNumberConstraints,
+  extractDecimalConstraints,
   extractListConstraints,
   extractBundleAccept,
+  whenBranch,
 } from '../base.js';
 
 const __dirname = path.dirname(fileURLToPath(import.meta.url));
@@ -38,6 +41,39 @@ export class TypeScriptGenerator extends CodeGenerator {
   generateDataValidatorExpr(desc: TypeDescription): string {
     const name = desc.name;
 
+    // Metadata attached to every issue of the type (fields wrap their value validator below)
+    if (desc.meta && name !== 'Field') {
+      const innerExpr = this.generateDataValidatorExpr({ ...desc, meta: undefined });
+      return `(v, p, i) => validateMeta(v, p, i, ${innerExpr}, ${JSON.stringify(desc.meta)})`;
+    }
+
+    // Nullable (fields wrap their value validator below)
+    if (desc.nullable && name !== 'Field') {
+      const innerExpr = this.generateDataValidatorExpr({ ...desc, nullable: false });
+      return `(v, p, i) => validateNullable(v, p, i, ${innerExpr})`;
+    }
+
+    // Spec-defined code/message: report the inner failure as one issue
+    if (desc.customIssue && name !== 'Field') {
+      const innerExpr = this.generateDataValidatorExpr({ ...desc, customIssue: undefined });
+      return `(v, p, i) => validateMessage(v, p, i, ${innerExpr}, ${this.messageArgs(desc.customIssue)})`;
+    }
+
+    // Deprecated type: warn, then validate as usual
+    if (desc.deprecated && name !== 'Field') {
+      const innerExpr = this.generateDataValidatorExpr({ ...desc, deprecated: undefined });
+      return `(v, p, i) => validateDeprecated(v, p, i, ${innerExpr}, "type.deprecated", ${this.escapeString(desc.deprecated)})`;
+    }
+
+    // Named type: emitted once as a function, referenced by name
+    if (name === 'Ref' && desc.ref !== undefined) {
+      return this.namedValidator(desc.ref, null);
+    }
+    if (desc.ref !== undefined && name !== 'Field') {
+      const bodyExpr = this.generateDataValidatorExpr({ ...desc, ref: undefined, maxDepth: undefined });
+      return this.namedValidator(desc.ref, bodyExpr, desc.maxDepth, desc.description);
+    }
+
     // Literal value
     if (name === 'Literal') {
       const val = desc.constraints?.[0]?.replace('equals ', '') ?? 'null';
@@ -78,6 +114,21 @@ export class TypeScriptGenerator extends CodeGenerator {
       return `(v, p, i) => validateNum(v, p, i, { ${args.join(', ')} })`;
     }
 
+    // Decimal
+    if (name === 'Decimal') {
+      const opts = extractDecimalConstraints(desc.constraints);
+      const args: string[] = [];
+      if (opts.min !== undefined) args.push(`min: ${this.escapeString(opts.min)}`);
+      if (opts.max !== undefined) args.push(`max: ${this.escapeString(opts.max)}`);
+      if (opts.precision !== undefined) args.push(`precision: ${opts.precision}`);
+      if (opts.scale !== undefined) args.push(`scale: ${opts.scale}`);
+
+      if (args.length === 0) {
+        return 'validateDecimal';
+      }
+      return `(v, p, i) => validateDecimal(v, p, i, { ${args.join(', ')} })`;
+    }
+
     // Boolean
     if (name === 'Boolean') {
       return 'validateBool';
@@ -89,6 +140,26 @@ export class TypeScriptGenerator extends CodeGenerator {
       return `(v, p, i) => validateOneOf(v, p, i, [${options.join(', ')}])`;
     }
 
+    // AllOf
+    if (name === 'AllOf' && desc.allOf) {
+      const parts = desc.allOf.map(part => this.generateDataValidatorExpr(part));
+      return `(v, p, i) => validateAllOf(v, p, i, [${parts.join(', ')}])`;
+    }
+
+    // Not
+    if (name === 'Not' && desc.not) {
+      const inner = this.generateDataValidatorExpr(desc.not);
+      return `(v, p, i) => validateNot(v, p, i, ${inner})`;
+    }
+
+    // TaggedUnion
+    if (name === 'TaggedUnion' && desc.discriminator !== undefined && desc.variants) {
+      const variants = Object.entries(desc.variants).map(
+        ([tag, variant]) => `${this.escapeString(tag)}: ${this.generateDataValidatorExpr(variant)}`
+      );
+      return `(v, p, i) => validateTaggedUnion(v, p, i, ${this.escapeString(desc.discriminator)}, { ${variants.join(', ')} })`;
+    }
+
     // ListOf
     if (name === 'ListOf' && desc.itemType) {
       const itemExpr = this.generateDataValidatorExpr(desc.itemType);
@@ -96,6 +167,10 @@ export class TypeScriptGenerator extends CodeGenerator {
       const args: string[] = [`itemValidator: ${itemExpr}`];
       if (opts.minItems !== undefined) args.push(`minItems: ${opts.minItems}`);
       if (opts.maxItems !== undefined) args.push(`maxItems: ${opts.maxItems}`);
+      if (opts.unique) args.push('unique: true');
+      if (opts.uniqueBy !== undefined) args.push(`uniqueBy: ${this.escapeString(opts.uniqueBy)}`);
+      if (opts.sorted !== undefined) args.push(`sorted: ${this.escapeString(opts.sorted)}`);
+      if (opts.sortedBy !== undefined) args.push(`sortedBy: ${this.escapeString(opts.sortedBy)}`);
       return `(v, p, i) => validateList(v, p, i, { ${args.join(', ')} })`;
     }
 
@@ -109,6 +184,16 @@ export class TypeScriptGenerator extends CodeGenerator {
       if (desc.oneOf) {
         const options = desc.oneOf.map(opt => this.generateDataValidatorExpr(opt));
         valueExpr = `(v, p, i) => validateOneOf(v, p, i, [${options.join(', ')}])`;
+      } else if (desc.allOf) {
+        valueExpr = this.generateDataValidatorExpr({ name: 'AllOf', allOf: desc.allOf });
+      } else if (desc.not) {
+        valueExpr = this.generateDataValidatorExpr({ name: 'Not', not: desc.not });
+      } else if (desc.variants) {
+        valueExpr = this.generateDataValidatorExpr({
+          name: 'TaggedUnion',
+          discriminator: desc.discriminator,
+          variants: desc.variants,
+        });
       } else if (desc.itemType) {
         valueExpr = this.generateDataValidatorExpr({
           name: 'ListOf',
@@ -124,6 +209,26 @@ export class TypeScriptGenerator extends CodeGenerator {
         });
       }
 
+      if (valueExpr && desc.ref !== undefined) {
+        valueExpr = this.namedValidator(desc.ref, desc.summary === 'Ref' ? null : valueExpr, desc.maxDepth, desc.valueDescription);
+      }
+      if (valueExpr && desc.valueDeprecated) {
+        valueExpr = `(v, p, i) => validateDeprecated(v, p, i, ${valueExpr}, "type.deprecated", ${this.escapeString(desc.valueDeprecated)})`;
+      }
+      if (valueExpr && desc.customIssue) {
+        valueExpr = `(v, p, i) => validateMessage(v, p, i, ${valueExpr}, ${this.messageArgs(desc.customIssue)})`;
+      }
+
+      if (valueExpr && desc.nullable) {
+        valueExpr = `(v, p, i) => validateNullable(v, p, i, ${valueExpr})`;
+      }
+      if (valueExpr && desc.meta) {
+        valueExpr = `(v, p, i) => validateMeta(v, p, i, ${valueExpr}, ${JSON.stringify(desc.meta)})`;
+      }
+      if (desc.deprecated) {
+        valueExpr = `(v, p, i) => validateDeprecated(v, p, i, ${valueExpr ?? '() => {}'}, "field.deprecated", ${this.escapeString(desc.deprecated)})`;
+      }
+
       if (valueExpr) {
         args.push(`validator: ${valueExpr}`);
       }
@@ -149,19 +254,16 @@ export class TypeScriptGenerator extends CodeGenerator {
   ): string {
     const fieldExprs: string[] = [];
 
-    for (const child of children.required ?? []) {
-      fieldExprs.push(this.generateDataValidatorExpr(child));
-    }
-    for (const child of children.optional ?? []) {
-      fieldExprs.push(this.generateDataValidatorExpr({ ...child, optional: true }));
+    for (const { desc, optional } of this.scheduleChildren(children)) {
+      fieldExprs.push(this.generateDataValidatorExpr(optional ? { ...desc, optional: true } : desc));
     }
 
     if (fieldExprs.length === 0) {
       return 'validateObject';
     }
 
-    const fieldCalls = fieldExprs.map(expr => `(${expr})(v, p, i)`).join(', ');
-    return `(v, p, i) => { if (validateObject(v, p, i)) { ${fieldCalls}; } }`;
+    const fieldCalls = this.joinChecks(fieldExprs.map(expr => `(${expr})(v, p, i)`));
+    return `(v, p, i) => { if (validateObject(v, p, i)) { ${fieldCalls} } }`;
   }
 
   generateBundleContentExpr(
@@ -169,24 +271,40 @@ export class TypeScriptGenerator extends CodeGenerator {
   ): string {
     const parts: string[] = [];
 
-    for (const child of children.required ?? []) {
-      parts.push(this.generateFSChildExpr(child));
-    }
-    for (const child of children.optional ?? []) {
-      parts.push(this.generateFSChildExpr(child));
+    for (const { desc } of this.scheduleChildren(children)) {
+      parts.push(this.generateFSChildExpr(desc));
     }
 
     if (parts.length === 0) {
       return '(ctx: FSContext, p: string[], i: Issues) => {}';
     }
 
-    return `(ctx: FSContext, p: string[], i: Issues) => { ${parts.join('; ')}; }`;
+    return `(ctx: FSContext, p: string[], i: Issues) => { ${this.joinChecks(parts)} }`;
+  }
+
+  /**
+   * Join check statements, returning early after a check reports errors in fail-fast mode
+   */
+  private joinChecks(calls: string[]): string {
+    if (!this.options.failFast) {
+      return `${calls.join('; ')};`;
+    }
+    return `const n = errorCount(i); ${calls.map(call => `${call}; if (errorCount(i) > n) return;`).join(' ')}`;
   }
 
   generateFSChildExpr(desc: TypeDescription): string {
     const fsType = desc.fsType;
     const comment = desc.description ? `/* ${desc.description} */ ` : '';
 
+    if (desc.name === 'When' && desc.condition) {
+      const { file, field, is } = desc.condition;
+      const condition = [`file: ${this.escapeString(file)}`];
+      if (field !== undefined) condition.push(`field: ${this.escapeString(field)}`);
+      if (is) condition.push(`is: ${this.generateDataValidatorExpr(is)}`);
+      const branch = (d: TypeDescription | undefined) => d ? this.generateBundleContentExpr(whenBranch(d)) : 'undefined';
+      return `${comment}validateWhen(ctx, p, i, { ${condition.join(', ')} }, ${branch(desc.then)}, ${branch(desc.else)})`;
+    }
+
     if (fsType === 'jsonFile' && desc.filePath) {
       const contentExpr = desc.children ? this.generateObjectExpr(desc.children) : 'undefined';
       return `${comment}validateJsonFile(ctx, ${this.escapeString(desc.filePath)}, p, i, ${contentExpr})`;
@@ -194,7 +312,12 @@ export class TypeScriptGenerator extends CodeGenerator {
 
     if (fsType === 'file' && desc.filePath) {
       const ext = desc.fileExt ? `, ${this.escapeString(desc.fileExt)}` : '';
-      return `${comment}validateFsFile(ctx, ${this.escapeString(desc.filePath)}, p, i${ext})`;
+      const fileCheck = `validateFsFile(ctx, ${this.escapeString(desc.filePath)}, p, i${ext})`;
+      if (desc.checksum) {
+        const { algorithm, value } = desc.checksum;
+        return `${comment}${fileCheck} && validateFileChecksum(ctx, ${this.escapeString(desc.filePath)}, p, i, ${this.escapeString(algorithm)}, ${this.escapeString(value)})`;
+      }
+      return `${comment}${fileCheck}`;
     }
 
     if (fsType === 'directory' && desc.filePath) {
@@ -265,15 +388,57 @@ if (args.length >= 1) {
     }
   }
 
+  protected definitionIdent(words: string[]): string {
+    return `validateDef${words.map(w => w[0]!.toUpperCase() + w.slice(1)).join('')}`;
+  }
+
+  protected generateDefinition(name: string, ident: string, expr: string, maxDepth?: number): string {
+    if (maxDepth === undefined) {
+      return [
+        `function ${ident}(v: unknown, p: string[], i: Issues): void {`,
+        `  const validator: Validator = ${expr};`,
+        '  validator(v, p, i);',
+        '}',
+      ].join('\n');
+    }
+    const message = this.escapeString(`Nesting of ${name} exceeds maximum depth ${maxDepth}`);
+    return [
+      `let ${ident}Depth = 0;`,
+      `function ${ident}(v: unknown, p: string[], i: Issues): void {`,
+      `  if (${ident}Depth >= ${maxDepth}) {`,
+      `    addIssue(i, p, 'recursion.too_deep', ${message});`,
+      '    return;',
+      '  }',
+      `  ${ident}Depth++;`,
+      '  try {',
+      `    const validator: Validator = ${expr};`,
+      '    validator(v, p, i);',
+      '  } finally {',
+      `    ${ident}Depth--;`,
+      '  }',
+      '}',
+    ].join('\n');
+  }
+
+  /** Code and message arguments for validateMessage */
+  private messageArgs(customIssue: NonNullable<TypeDescription['customIssue']>): string {
+    const opt = (s: string | undefined): string => s === undefined ? 'undefined' : this.escapeString(s);
+    return `${opt(customIssue.code)}, ${opt(customIssue.message)}`;
+  }
+
   protected commentLine(text: string): string {
     return `// ${text}`;
   }
+
+  protected docComment(text: string): string[] {
+    return ['/**', ...text.split('\n').map(line => ` * ${line}`.trimEnd()), ' */'];
+  }
 }
 
 /**
  * Generate TypeScript validator code from TypeDescription
  */
-export function generateTypeScript(desc: TypeDescription): string {
-  const generator = new TypeScriptGenerator();
+export function generateTypeScript(desc: TypeDescription, options: CodegenOptions = {}): string {
+  const generator = new TypeScriptGenerator(options);
   return generator.generate(desc);
 }
@@ -1,6 +1,7 @@
 // SpecSpec TypeScript Prelude
 // Validation primitives - embedded at top of generated validators
 
+import * as crypto from 'crypto';
 import * as fs from 'fs';
 import * as path from 'path';
 import AdmZip from 'adm-zip';
@@ -8,9 +9,13 @@ import AdmZip from 'adm-zip';
 // === Types ===
 
 export interface Issue {
+  /** Warnings do not fail validation */
+  level: 'error' | 'warning';
   path: string;
   code: string;
   message: string;
+  /** Metadata of the rule that reported the issue (`Meta()` in the spec) */
+  meta?: Record<string, unknown>;
 }
 
 export type Issues = Issue[];
@@ -23,7 +28,15 @@ export interface ValidationResult {
 }
 
 function addIssue(issues: Issues, path: string[], code: string, message: string): void {
-  issues.push({ path: path.length > 0 ? path.join('.') : '(root)', code, message });
+  issues.push({ level: 'error', path: path.length > 0 ? path.join('.') : '(root)', code, message });
+}
+
+function addWarning(issues: Issues, path: string[], code: string, message: string): void {
+  issues.push({ level: 'warning', path: path.length > 0 ? path.join('.') : '(root)', code, message });
+}
+
+export function errorCount(issues: Issues): number {
+  return issues.filter(issue => issue.level === 'error').length;
 }
 
 // === Primitive validators ===
@@ -66,6 +79,72 @@ export function validateNum(
   }
 }
 
+interface DecimalParts {
+  negative: boolean;
+  digits: string;
+  point: number;
+}
+
+function parseDecimal(text: string): DecimalParts | null {
+  const m = /^(-)?(\d+)(?:\.(\d+))?(?:[eE]([+-]?\d+))?$/.exec(text.trim());
+  if (!m) return null;
+  const all = m[2]! + (m[3] ?? '');
+  const significant = all.replace(/^0+/, '');
+  const digits = significant.replace(/0+$/, '');
+  if (digits === '') return { negative: false, digits, point: 0 };
+  const point = m[2]!.length - (all.length - significant.length) + Number(m[4] ?? '0');
+  if (!Number.isSafeInteger(point)) return null;
+  return { negative: m[1] === '-', digits, point };
+}
+
+function intLength(parts: DecimalParts): number {
+  return parts.digits === '' ? 0 : Math.max(0, parts.point);
+}
+
+function fracLength(parts: DecimalParts): number {
+  return Math.max(0, parts.digits.length - parts.point);
+}
+
+function compareDecimal(a: DecimalParts, b: DecimalParts): number {
+  if (a.negative !== b.negative) return a.negative ? -1 : 1;
+  const sign = a.negative ? -1 : 1;
+  if (a.digits === '' || b.digits === '') return (a.digits === '' ? 0 : 1) - (b.digits === '' ? 0 : 1);
+  if (a.point !== b.point) return (a.point < b.point ? -1 : 1) * sign;
+  return a.digits === b.digits ? 0 : (a.digits < b.digits ? -1 : 1) * sign;
+}
+
+export function validateDecimal(
+  value: unknown, path: string[], issues: Issues,
+  opts?: { min?: string; max?: string; precision?: number; scale?: number }
+): void {
+  if (typeof value !== 'number' || !Number.isFinite(value)) {
+    addIssue(issues, path, 'type.mismatch', `Expected number, got ${typeof value}`);
+    return;
+  }
+  const text = String(value);
+  const parts = parseDecimal(text);
+  if (!parts) {
+    addIssue(issues, path, 'type.mismatch', `Expected decimal number, got ${text}`);
+    return;
+  }
+  const places = fracLength(parts);
+  const digits = intLength(parts) + places;
+  if (opts?.precision !== undefined && digits > opts.precision) {
+    addIssue(issues, path, 'decimal.too_many_digits', `Number ${text} has ${digits} digits, maximum is ${opts.precision}`);
+  }
+  if (opts?.scale !== undefined && places > opts.scale) {
+    addIssue(issues, path, 'decimal.scale_exceeded', `Number ${text} has ${places} decimal places, maximum is ${opts.scale}`);
+  }
+  const min = opts?.min !== undefined ? parseDecimal(opts.min) : null;
+  if (min && compareDecimal(parts, min) < 0) {
+    addIssue(issues, path, 'num.too_small', `Number ${text} is less than minimum ${opts!.min}`);
+  }
+  const max = opts?.max !== undefined ? parseDecimal(opts.max) : null;
+  if (max && compareDecimal(parts, max) > 0) {
+    addIssue(issues, path, 'num.too_large', `Number ${text} exceeds maximum ${opts!.max}`);
+  }
+}
+
 export function validateBool(value: unknown, path: string[], issues: Issues): void {
   if (typeof value !== 'boolean') {
     addIssue(issues, path, 'type.mismatch', `Expected boolean, got ${typeof value}`);
@@ -117,9 +196,32 @@ export function validateField(
   }
 }
 
+function canonicalJson(v: unknown): string {
+  if (Array.isArray(v)) {
+    return `[${v.map(canonicalJson).join(',')}]`;
+  }
+  if (v !== null && typeof v === 'object') {
+    const entries = Object.keys(v).sort().map(k => `${JSON.stringify(k)}:${canonicalJson((v as Record<string, unknown>)[k])}`);
+    return `{${entries.join(',')}}`;
+  }
+  return JSON.stringify(v);
+}
+
+export function getKeyPath(value: unknown, keyPath: string): unknown {
+  let current = value;
+  for (const key of keyPath.split('.')) {
+    if (current === null || typeof current !== 'object' || Array.isArray(current)) {
+      return undefined;
+    }
+    current = (current as Record<string, unknown>)[key];
+  }
+  return current;
+}
+
 export function validateList(
   value: unknown, path: string[], issues: Issues,
-  opts?: { itemValidator?: Validator; minItems?: number; maxItems?: number }
+  opts?: { itemValidator?: Validator; minItems?: number; maxItems?: number; unique?: boolean; uniqueBy?: string;
+    sorted?: 'asc' | 'desc'; sortedBy?: string }
 ): void {
   if (!Array.isArray(value)) {
     addIssue(issues, path, 'type.mismatch', `Expected array, got ${typeof value}`);
@@ -133,6 +235,42 @@ export function validateList(
     addIssue(issues, path, 'list.too_long', `Array length ${value.length} exceeds maximum ${opts.maxItems}`);
   }
 
+  if (opts?.unique || opts?.uniqueBy !== undefined) {
+    const uniqueBy = opts.uniqueBy;
+    const seen = new Map<string, number>();
+    value.forEach((item, i) => {
+      const keyValue = uniqueBy !== undefined ? getKeyPath(item, uniqueBy) : item;
+      if (keyValue === undefined) return;
+      const key = canonicalJson(keyValue);
+      const first = seen.get(key);
+      if (first === undefined) {
+        seen.set(key, i);
+      } else {
+        const message = uniqueBy !== undefined
+          ? `Items [${first}] and [${i}] share ${uniqueBy} ${key}`
+          : `Items [${first}] and [${i}] are duplicates`;
+        addIssue(issues, [...path, `[${i}]`], 'list.duplicate', message);
+      }
+    });
+  }
+
+  if (opts?.sorted !== undefined || opts?.sortedBy !== undefined) {
+    const descending = opts.sorted === 'desc';
+    // Only the first out-of-order pair is reported
+    let prev: { index: number; key: number | string } | undefined;
+    for (let i = 0; i < value.length; i++) {
+      const key = opts.sortedBy !== undefined ? getKeyPath(value[i], opts.sortedBy) : value[i];
+      if (typeof key !== 'number' && typeof key !== 'string') continue;
+      if (prev && typeof prev.key === typeof key && (descending ? key > prev.key : key < prev.key)) {
+        const by = opts.sortedBy !== undefined ? ` by ${opts.sortedBy}` : '';
+        addIssue(issues, [...path, `[${i}]`], 'list.not_sorted',
+          `Items [${prev.index}] and [${i}] are not in ${descending ? 'descending' : 'ascending'} order${by}`);
+        break;
+      }
+      prev = { index: i, key };
+    }
+  }
+
   if (opts?.itemValidator) {
     value.forEach((item, i) => {
       opts.itemValidator!(item, [...path, `[${i}]`], issues);
@@ -147,13 +285,109 @@ export function validateOneOf(
   for (const validator of validators) {
     const testIssues: Issues = [];
     validator(value, path, testIssues);
-    if (testIssues.length === 0) {
-      return; // Matched
+    if (errorCount(testIssues) === 0) {
+      issues.push(...testIssues); // Matched; keep its warnings
+      return;
     }
   }
   addIssue(issues, path, 'oneof.no_match', 'Value does not match any of the options');
 }
 
+export function validateAllOf(
+  value: unknown, path: string[], issues: Issues,
+  validators: Validator[]
+): void {
+  // Every part runs; issues shared by several parts are reported once
+  const merged: Issues = [];
+  for (const validator of validators) {
+    const partIssues: Issues = [];
+    validator(value, path, partIssues);
+    for (const issue of partIssues) {
+      if (!merged.some(m => m.path === issue.path && m.code === issue.code && m.message === issue.message)) {
+        merged.push(issue);
+      }
+    }
+  }
+  issues.push(...merged);
+}
+
+export function validateTaggedUnion(
+  value: unknown, path: string[], issues: Issues,
+  discriminator: string, variants: Record<string, Validator>
+): void {
+  if (value === null || typeof value !== 'object' || Array.isArray(value)) {
+    addIssue(issues, path, 'type.mismatch', `Expected object, got ${Array.isArray(value) ? 'array' : typeof value}`);
+    return;
+  }
+  const tag = (value as Record<string, unknown>)[discriminator];
+  if (tag === undefined) {
+    addIssue(issues, path, 'field.missing', `Missing required field: ${discriminator}`);
+    return;
+  }
+  // Only the variant selected by the tag runs
+  const variant = typeof tag === 'string' && Object.prototype.hasOwnProperty.call(variants, tag) ? variants[tag] : undefined;
+  if (variant) {
+    variant(value, path, issues);
+    return;
+  }
+  addIssue(issues, [...path, discriminator], 'union.unknown_tag',
+    `Unknown ${discriminator} ${JSON.stringify(tag)}; expected one of: ${Object.keys(variants).join(', ')}`);
+}
+
+export function validateNot(
+  value: unknown, path: string[], issues: Issues,
+  inner: Validator
+): void {
+  const testIssues: Issues = [];
+  inner(value, path, testIssues);
+  if (errorCount(testIssues) === 0) {
+    addIssue(issues, path, 'not.matched', 'Value matches a disallowed schema');
+  }
+}
+
+export function validateNullable(
+  value: unknown, path: string[], issues: Issues,
+  inner: Validator
+): void {
+  if (value === null) {
+    return;
+  }
+  inner(value, path, issues);
+}
+
+export function validateMessage(
+  value: unknown, path: string[], issues: Issues,
+  inner: Validator, code?: string, message?: string
+): void {
+  const innerIssues: Issues = [];
+  inner(value, path, innerIssues);
+  issues.push(...innerIssues.filter(issue => issue.level === 'warning'));
+  const first = innerIssues.find(issue => issue.level === 'error');
+  if (first) {
+    addIssue(issues, path, code ?? first.code, message ?? first.message);
+  }
+}
+
+export function validateDeprecated(
+  value: unknown, path: string[], issues: Issues,
+  inner: Validator, code: string, message: string
+): void {
+  addWarning(issues, path, code, message);
+  inner(value, path, issues);
+}
+
+/** Attach metadata to the issues of the inner validator; keys already on an issue, from a nested Meta(), win */
+export function validateMeta(
+  value: unknown, path: string[], issues: Issues,
+  inner: Validator, meta: Record<string, unknown>
+): void {
+  const innerIssues: Issues = [];
+  inner(value, path, innerIssues);
+  for (const issue of innerIssues) {
+    issues.push({ ...issue, meta: { ...meta, ...issue.meta } });
+  }
+}
+
 // === File system context ===
 
 export class FSContext {
@@ -210,6 +444,15 @@ export class FSContext {
     return fs.readFileSync(path.join(this.basePath, relPath), 'utf-8');
   }
 
+  readBytes(relPath: string): Buffer {
+    if (this.isZip) {
+      const entry = this.zipFile!.getEntry(relPath);
+      if (!entry) throw new Error(`File not found: ${relPath}`);
+      return entry.getData();
+    }
+    return fs.readFileSync(path.join(this.basePath, relPath));
+  }
+
   readJson(relPath: string): unknown {
     return JSON.parse(this.read(relPath));
   }
@@ -342,6 +585,42 @@ export function validateFsFile(
   return true;
 }
 
+// Digests available to checksum rules; register more (e.g. blake3) by name
+export const digests: Record<string, (data: Buffer) => string> = {
+  sha256: data => crypto.createHash('sha256').update(data).digest('hex'),
+  sha384: data => crypto.createHash('sha384').update(data).digest('hex'),
+  sha512: data => crypto.createHash('sha512').update(data).digest('hex'),
+};
+
+export function validateFileChecksum(
+  ctx: FSContext, relPath: string, pathList: string[], issues: Issues,
+  algorithm: string, expected: string
+): boolean {
+  const filePath = [...pathList, relPath];
+
+  const digest = digests[algorithm.toLowerCase()];
+  if (!digest) {
+    addIssue(issues, filePath, 'checksum.unsupported_algorithm', `Digest algorithm not available: ${algorithm}`);
+    return false;
+  }
+
+  let data: Buffer;
+  try {
+    data = ctx.readBytes(relPath);
+  } catch (e) {
+    addIssue(issues, filePath, 'file.read_error', (e as Error).message);
+    return false;
+  }
+
+  const actual = digest(data);
+  if (actual !== expected.toLowerCase()) {
+    addIssue(issues, filePath, 'checksum.mismatch', `${algorithm} digest mismatch: expected ${expected}, got ${actual}`);
+    return false;
+  }
+
+  return true;
+}
+
 export function validateFsDirectory(
   ctx: FSContext, relPath: string, pathList: string[], issues: Issues
 ): boolean {
@@ -360,12 +639,50 @@ export function validateFsDirectory(
   return true;
 }
 
+/** The JSON file value a When() rule depends on */
+export interface Condition {
+  /** JSON file holding the value, relative to the directory */
+  file: string;
+  /** Dotted path of the value in the file (`icons.enabled`, `targets.0.name`); the whole document by default */
+  field?: string;
+  /** What the value must match; being present is enough by default */
+  is?: Validator;
+}
+
+/**
+ * Check `then` when the condition holds and `otherwise` when it does not or the value is
+ * missing; a file that cannot be read or parsed decides nothing (its own rules report it)
+ */
+export function validateWhen(
+  ctx: FSContext, pathList: string[], issues: Issues,
+  condition: Condition, then?: FSValidator, otherwise?: FSValidator
+): void {
+  let value: unknown;
+  try {
+    value = ctx.readJson(condition.file);
+  } catch {
+    return;
+  }
+  for (const segment of condition.field === undefined ? [] : condition.field.split('.')) {
+    value = value !== null && typeof value === 'object' ? (value as Record<string, unknown>)[segment] : undefined;
+  }
+
+  let holds = value !== undefined;
+  if (holds && condition.is) {
+    const test: Issues = [];
+    condition.is(value, pathList, test);
+    holds = errorCount(test) === 0;
+  }
+  const content = holds ? then : otherwise;
+  content?.(ctx, pathList, issues);
+}
+
 // === Entry points ===
 
 export function validate(value: unknown, validator: Validator): ValidationResult {
   const issues: Issues = [];
   validator(value, [], issues);
-  return { ok: issues.length === 0, issues };
+  return { ok: errorCount(issues) === 0, issues };
 }
 
 export function validatePath(
@@ -374,5 +691,5 @@ export function validatePath(
 ): ValidationResult {
   const issues: Issues = [];
   validator(bundlePath, [], issues);
-  return { ok: issues.length === 0, issues };
+  return { ok: errorCount(issues) === 0, issues };
 }
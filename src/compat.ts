@@ -0,0 +1,477 @@
+// src/compat.ts
+// Compat - classify the changes between two versions of a spec as breaking or compatible
+
+import type { TypeDescription } from './base.js';
+import { whenBranch, fieldValue as valuePart } from './codegen/base.js';
+
+/**
+ * A single change between two spec versions.
+ * Breaking changes may reject targets the old spec accepted; compatible ones cannot.
+ */
+export interface CompatChange {
+  /** Path to the changed item (field keys, file paths, `[]` for list items, `[tag=value]` for variants, `[then]` / `[else]` for When branches) */
+  path: string[];
+  kind: 'breaking' | 'compatible';
+  /** Change code (e.g. "item.became_required", "enum.value_removed") */
+  code: string;
+  message: string;
+}
+
+export interface CompatReport {
+  /** True when no change is breaking */
+  compatible: boolean;
+  /** Number of breaking changes */
+  breaking: number;
+  changes: CompatChange[];
+}
+
+type Changes = CompatChange[];
+
+/**
+ * Compare two spec versions, given as the descriptions of their root types
+ */
+export function compatReport(oldDesc: TypeDescription, newDesc: TypeDescription): CompatReport {
+  const changes: Changes = [];
+  compareItem([], oldDesc, newDesc, changes, new Set());
+  const breaking = changes.filter(c => c.kind === 'breaking').length;
+  return { compatible: breaking === 0, breaking, changes };
+}
+
+function breaking(changes: Changes, path: string[], code: string, message: string): void {
+  changes.push({ path, kind: 'breaking', code, message });
+}
+
+function compatible(changes: Changes, path: string[], code: string, message: string): void {
+  changes.push({ path, kind: 'compatible', code, message });
+}
+
+// ─── Items: fields and file system entries ───
+
+function isFsItem(d: TypeDescription): boolean {
+  return d.fsType !== undefined;
+}
+
+function itemLabel(d: TypeDescription): string {
+  if (d.name === 'Field') {
+    return `Field "${d.key}"`;
+  }
+  return `${d.fsType === 'directory' ? 'Directory' : 'File'} "${d.filePath ?? d.key}"`;
+}
+
+/**
+ * Compare a field or file system entry, then its value / content
+ */
+function compareItem(path: string[], oldDesc: TypeDescription, newDesc: TypeDescription, changes: Changes, seen: Set<string>): void {
+  if (newDesc.deprecated && !oldDesc.deprecated) {
+    compatible(changes, path, 'item.deprecated', newDesc.deprecated);
+  }
+
+  if (oldDesc.name === 'Field' && newDesc.name === 'Field') {
+    compareValue(path, fieldValue(oldDesc), fieldValue(newDesc), changes, seen);
+    return;
+  }
+
+  if (oldDesc.name === 'When' && newDesc.name === 'When') {
+    compareWhen(path, oldDesc, newDesc, changes, seen);
+    return;
+  }
+
+  if (isFsItem(oldDesc) && isFsItem(newDesc)) {
+    if (oldDesc.fsType !== newDesc.fsType) {
+      breaking(changes, path, 'type.changed', `${itemLabel(oldDesc)} is now a ${newDesc.name}`);
+      return;
+    }
+    if (newDesc.fileExt !== undefined && newDesc.fileExt !== oldDesc.fileExt) {
+      breaking(changes, path, 'file.ext_changed', `${itemLabel(newDesc)} must now have extension ${newDesc.fileExt}`);
+    }
+    if (newDesc.checksum && (newDesc.checksum.algorithm !== oldDesc.checksum?.algorithm || newDesc.checksum.value !== oldDesc.checksum.value)) {
+      breaking(changes, path, 'file.checksum_changed', `${itemLabel(newDesc)} must now have ${newDesc.checksum.algorithm} digest ${newDesc.checksum.value}`);
+    } else if (oldDesc.checksum && !newDesc.checksum) {
+      compatible(changes, path, 'file.checksum_removed', `${itemLabel(newDesc)} no longer has a checksum`);
+    }
+    if (newDesc.strict && !oldDesc.strict) {
+      breaking(changes, path, 'directory.strict', `${itemLabel(newDesc)} no longer allows undeclared entries`);
+    } else if (oldDesc.strict && !newDesc.strict) {
+      compatible(changes, path, 'directory.not_strict', `${itemLabel(newDesc)} now allows undeclared entries`);
+    }
+    compareChildren(path, oldDesc, newDesc, changes, seen);
+    return;
+  }
+
+  compareValue(path, oldDesc, newDesc, changes, seen);
+}
+
+function conditionLabel(d: TypeDescription): string {
+  const { file, field, is } = d.condition ?? { file: '' };
+  return `"${file}"${field !== undefined ? ` field "${field}"` : ''}${is ? ` is ${typeLabel(is)}` : ' is present'}`;
+}
+
+/**
+ * Compare When rules: a changed condition may move targets to the other branch,
+ * and each branch is compared as directory content
+ */
+function compareWhen(path: string[], oldDesc: TypeDescription, newDesc: TypeDescription, changes: Changes, seen: Set<string>): void {
+  const before = oldDesc.condition;
+  const after = newDesc.condition;
+  if (before?.file !== after?.file || before?.field !== after?.field || !sameShape(before?.is, after?.is)) {
+    breaking(changes, path, 'when.condition_changed', `Condition changed from ${conditionLabel(oldDesc)} to ${conditionLabel(newDesc)}`);
+  }
+  const content = (branch: TypeDescription | undefined): TypeDescription => ({
+    name: 'Directory',
+    fsType: 'directory',
+    children: branch ? whenBranch(branch) : {},
+  });
+  compareChildren([...path, '[then]'], content(oldDesc.then), content(newDesc.then), changes, seen);
+  compareChildren([...path, '[else]'], content(oldDesc.else), content(newDesc.else), changes, seen);
+}
+
+/**
+ * The value part of a Field description; a nullable field without a type accepts any value or null
+ */
+function fieldValue(d: TypeDescription): TypeDescription | undefined {
+  return valuePart(d) ?? (d.nullable ? { name: 'Any', nullable: true } : undefined);
+}
+
+function itemKey(d: TypeDescription): string | undefined {
+  return d.name === 'Field' ? d.key : d.filePath ?? d.key;
+}
+
+function isRequired(d: TypeDescription, inRequiredList: boolean): boolean {
+  // Fields are optional by their own flag; file system entries by the list they are in
+  return d.name === 'Field' ? !d.optional : inRequiredList;
+}
+
+/**
+ * Compare object fields or directory entries, matched by key
+ */
+function compareChildren(path: string[], oldDesc: TypeDescription, newDesc: TypeDescription, changes: Changes, seen: Set<string>): void {
+  const collect = (d: TypeDescription) => {
+    const items = new Map<string, { desc: TypeDescription; required: boolean }>();
+    const unkeyed: TypeDescription[] = [];
+    const add = (list: TypeDescription[] | undefined, required: boolean) => {
+      for (const child of list ?? []) {
+        const key = itemKey(child);
+        if (key === undefined) {
+          unkeyed.push(child);
+        } else {
+          items.set(key, { desc: child, required: isRequired(child, required) });
+        }
+      }
+    };
+    add(d.children?.required, true);
+    add(d.children?.optional, false);
+    return { items, unkeyed };
+  };
+
+  const before = collect(oldDesc);
+  const after = collect(newDesc);
+
+  for (const [key, { desc, required }] of after.items) {
+    const childPath = [...path, key];
+    const previous = before.items.get(key);
+    if (!previous) {
+      if (required) {
+        breaking(changes, childPath, 'item.added_required', `${itemLabel(desc)} is new and required`);
+      } else {
+        compatible(changes, childPath, 'item.added_optional', `${itemLabel(desc)} is new and optional`);
+      }
+      continue;
+    }
+    if (required && !previous.required) {
+      breaking(changes, childPath, 'item.became_required', `${itemLabel(desc)} became required`);
+    } else if (!required && previous.required) {
+      compatible(changes, childPath, 'item.became_optional', `${itemLabel(desc)} became optional`);
+    }
+    compareItem(childPath, previous.desc, desc, changes, seen);
+  }
+
+  for (const [key, { desc }] of before.items) {
+    if (after.items.has(key)) continue;
+    // An entry the new spec no longer declares is rejected by a strict directory
+    if (newDesc.strict && isFsItem(desc)) {
+      breaking(changes, [...path, key], 'item.removed', `${itemLabel(desc)} is no longer declared and the directory is strict`);
+    } else {
+      compatible(changes, [...path, key], 'item.removed', `${itemLabel(desc)} is no longer checked`);
+    }
+  }
+
+  // Content without a key (e.g. a single type for a file) is matched by position
+  const count = Math.max(before.unkeyed.length, after.unkeyed.length);
+  for (let i = 0; i < count; i++) {
+    const previous = before.unkeyed[i];
+    const current = after.unkeyed[i];
+    if (previous && current) {
+      compareItem(path, previous, current, changes, seen);
+    } else if (current) {
+      breaking(changes, path, 'constraint.added', `Content must now match ${typeLabel(current)}`);
+    } else if (previous) {
+      compatible(changes, path, 'constraint.removed', `Content no longer has to match ${typeLabel(previous)}`);
+    }
+  }
+}
+
+// ─── Values ───
+
+function typeLabel(d: TypeDescription): string {
+  if (d.name === 'Literal') {
+    return (d.constraints?.[0] ?? '').replace(/^equals /, '');
+  }
+  return d.ref ?? d.name;
+}
+
+function normalize(d: TypeDescription): TypeDescription {
+  if (d.name === 'Pattern') {
+    // A RegExp literal is a string with a pattern
+    return { ...d, name: 'String' };
+  }
+  return d;
+}
+
+/**
+ * Compare two value types. An undefined type accepts any value.
+ */
+function compareValue(path: string[], oldValue: TypeDescription | undefined, newValue: TypeDescription | undefined, changes: Changes, seen: Set<string>): void {
+  if (!newValue) {
+    if (oldValue) {
+      compatible(changes, path, 'type.removed', `Value no longer has to be ${typeLabel(oldValue)}`);
+    }
+    return;
+  }
+  if (!oldValue) {
+    breaking(changes, path, 'type.added', `Value must now be ${typeLabel(newValue)}`);
+    return;
+  }
+
+  const oldDesc = normalize(oldValue);
+  const newDesc = normalize(newValue);
+
+  if (newDesc.deprecated && !oldDesc.deprecated) {
+    compatible(changes, path, 'type.deprecated', newDesc.deprecated);
+  }
+  if (oldDesc.nullable && !newDesc.nullable) {
+    breaking(changes, path, 'null.removed', 'Value is no longer nullable');
+  } else if (newDesc.nullable && !oldDesc.nullable) {
+    compatible(changes, path, 'null.added', 'Value is now nullable');
+  }
+
+  // Named types: recursive uses are compared by name, each pair of bodies once
+  if (oldDesc.ref !== undefined && newDesc.ref !== undefined) {
+    const pair = `${oldDesc.ref}\u0000${newDesc.ref}`;
+    if (oldDesc.name === 'Ref' || newDesc.name === 'Ref' || seen.has(pair)) {
+      if (oldDesc.ref !== newDesc.ref) {
+        breaking(changes, path, 'type.changed', `Type changed from ${oldDesc.ref} to ${newDesc.ref}`);
+      }
+      return;
+    }
+    seen.add(pair);
+  }
+
+  if (oldDesc.oneOf || newDesc.oneOf) {
+    compareOptions(path, oldDesc.oneOf ?? [asOption(oldDesc)], newDesc.oneOf ?? [asOption(newDesc)], changes, seen);
+    return;
+  }
+
+  if (oldDesc.name !== newDesc.name) {
+    if (oldDesc.name === 'Literal' && isWidening(oldDesc, newDesc)) {
+      compatible(changes, path, 'type.widened', `Type widened from ${typeLabel(oldDesc)} to ${typeLabel(newDesc)}`);
+    } else {
+      breaking(changes, path, 'type.changed', `Type changed from ${typeLabel(oldDesc)} to ${typeLabel(newDesc)}`);
+    }
+    return;
+  }
+
+  if (oldDesc.name === 'Literal') {
+    if (typeLabel(oldDesc) !== typeLabel(newDesc)) {
+      breaking(changes, path, 'type.changed', `Value changed from ${typeLabel(oldDesc)} to ${typeLabel(newDesc)}`);
+    }
+    return;
+  }
+
+  compareConstraints(path, oldDesc.constraints ?? [], newDesc.constraints ?? [], changes);
+
+  if (newDesc.maxDepth !== undefined && (oldDesc.maxDepth === undefined || newDesc.maxDepth < oldDesc.maxDepth)) {
+    breaking(changes, path, 'constraint.tightened', `Maximum nesting is now ${newDesc.maxDepth}`);
+  } else if (oldDesc.maxDepth !== undefined && (newDesc.maxDepth === undefined || newDesc.maxDepth > oldDesc.maxDepth)) {
+    compatible(changes, path, 'constraint.loosened', newDesc.maxDepth === undefined ? 'Nesting is no longer limited' : `Maximum nesting is now ${newDesc.maxDepth}`);
+  }
+
+  if (oldDesc.itemType || newDesc.itemType) {
+    compareValue([...path, '[]'], oldDesc.itemType, newDesc.itemType, changes, seen);
+  }
+  if (oldDesc.children || newDesc.children) {
+    compareChildren(path, oldDesc, newDesc, changes, seen);
+  }
+  if (oldDesc.variants || newDesc.variants) {
+    compareVariants(path, oldDesc, newDesc, changes, seen);
+  }
+  if (oldDesc.allOf || newDesc.allOf) {
+    const before = oldDesc.allOf ?? [];
+    const after = newDesc.allOf ?? [];
+    for (let i = 0; i < Math.max(before.length, after.length); i++) {
+      compareValue(path, before[i], after[i], changes, seen);
+    }
+  }
+  if ((oldDesc.not || newDesc.not) && !sameShape(oldDesc.not, newDesc.not)) {
+    if (newDesc.not) {
+      breaking(changes, path, 'constraint.changed', `Value must now not match ${typeLabel(newDesc.not)}`);
+    } else {
+      compatible(changes, path, 'constraint.removed', 'Value is no longer excluded from any type');
+    }
+  }
+}
+
+// A non-OneOf type compared with a OneOf is its only option; the checks above already covered its flags
+function asOption(d: TypeDescription): TypeDescription {
+  return { ...d, nullable: undefined, deprecated: undefined, ref: undefined };
+}
+
+// A literal is still accepted by an unconstrained type of the same kind
+function isWidening(literal: TypeDescription, to: TypeDescription): boolean {
+  if (to.constraints?.length || to.children || to.itemType || to.variants || to.allOf || to.not) {
+    return false;
+  }
+  const kind = typeof JSON.parse(typeLabel(literal) || 'null');
+  return (kind === 'string' && to.name === 'String')
+    || (kind === 'number' && (to.name === 'Number' || to.name === 'Decimal'))
+    || (kind === 'boolean' && to.name === 'Boolean');
+}
+
+/**
+ * Compare OneOf options: every old option must still be accepted by some new option
+ */
+function compareOptions(path: string[], before: TypeDescription[], after: TypeDescription[], changes: Changes, seen: Set<string>): void {
+  const used = new Set<TypeDescription>();
+
+  for (const option of before) {
+    // Prefer an identical option, then any option that accepts everything the old one did
+    const trial = after.map(candidate => {
+      const found: Changes = [];
+      compareValue(path, option, candidate, found, new Set(seen));
+      return { candidate, found };
+    });
+    const match = trial.find(t => t.found.length === 0) ?? trial.find(t => t.found.every(c => c.kind === 'compatible'));
+    if (match) {
+      used.add(match.candidate);
+      changes.push(...match.found);
+      continue;
+    }
+    if (normalize(option).name === 'Literal') {
+      breaking(changes, path, 'enum.value_removed', `Value ${typeLabel(option)} is no longer accepted`);
+    } else if (after.length === 1 && before.length === 1) {
+      compareValue(path, option, after[0], changes, seen);
+    } else {
+      breaking(changes, path, 'option.removed', `Option ${typeLabel(option)} is no longer accepted`);
+    }
+  }
+
+  for (const option of after) {
+    if (used.has(option) || (before.length === 1 && after.length === 1)) continue;
+    if (normalize(option).name === 'Literal') {
+      compatible(changes, path, 'enum.value_added', `Value ${typeLabel(option)} is now accepted`);
+    } else {
+      compatible(changes, path, 'option.added', `Option ${typeLabel(option)} is now accepted`);
+    }
+  }
+}
+
+/**
+ * Compare TaggedUnion variants by tag
+ */
+function compareVariants(path: string[], oldDesc: TypeDescription, newDesc: TypeDescription, changes: Changes, seen: Set<string>): void {
+  if (oldDesc.discriminator !== newDesc.discriminator) {
+    breaking(changes, path, 'variant.discriminator_changed', `Discriminator changed from "${oldDesc.discriminator}" to "${newDesc.discriminator}"`);
+    return;
+  }
+  const before = oldDesc.variants ?? {};
+  const after = newDesc.variants ?? {};
+  for (const [tag, variant] of Object.entries(after)) {
+    const previous = before[tag];
+    const variantPath = [...path, `[${newDesc.discriminator}=${tag}]`];
+    if (previous) {
+      compareValue(variantPath, previous, variant, changes, seen);
+    } else {
+      compatible(changes, variantPath, 'variant.added', `Variant "${tag}" is now accepted`);
+    }
+  }
+  for (const tag of Object.keys(before)) {
+    if (!Object.hasOwn(after, tag)) {
+      breaking(changes, [...path, `[${oldDesc.discriminator}=${tag}]`], 'variant.removed', `Variant "${tag}" is no longer accepted`);
+    }
+  }
+}
+
+function sameShape(a: TypeDescription | undefined, b: TypeDescription | undefined): boolean {
+  if (!a || !b) {
+    return a === b;
+  }
+  const found: Changes = [];
+  compareValue([], a, b, found, new Set());
+  return found.length === 0;
+}
+
+// ─── Constraints ───
+
+// Bounds: `minimum 1`, `maximum 64 characters`, `minimum 2 items`, `precision 10 digits`, `scale 2`
+const BOUND = /^(minimum|maximum|precision|scale) (-?\d+(?:\.\d+)?)(.*)$/;
+
+// Constraints that describe the value without restricting it
+const INFORMATIONAL = /^(default: |sampled: )/;
+
+/**
+ * Compare constraint lists: raised minimums, lowered maximums, new patterns and
+ * new flags (e.g. `integer`, `unique items`) are breaking
+ */
+function compareConstraints(path: string[], before: string[], after: string[], changes: Changes): void {
+  const bounds = (list: string[]) => {
+    const map = new Map<string, { limit: number; text: string }>();
+    for (const text of list) {
+      const m = BOUND.exec(text);
+      if (m) map.set(`${m[1]}${m[3]}`, { limit: Number(m[2]), text });
+    }
+    return map;
+  };
+  const oldBounds = bounds(before);
+  const newBounds = bounds(after);
+
+  for (const [key, { limit, text }] of newBounds) {
+    const previous = oldBounds.get(key)?.limit;
+    const lower = key.startsWith('minimum');
+    if (previous === undefined || (lower ? limit > previous : limit < previous)) {
+      breaking(changes, path, 'constraint.tightened', previous === undefined ? `Value must now have ${text}` : `Constraint tightened to ${text}`);
+    } else if (limit !== previous) {
+      compatible(changes, path, 'constraint.loosened', `Constraint loosened to ${text}`);
+    }
+  }
+  for (const [key, { text }] of oldBounds) {
+    if (!newBounds.has(key)) {
+      compatible(changes, path, 'constraint.removed', `Constraint removed: ${text}`);
+    }
+  }
+
+  const flags = (list: string[]) => list.filter(c => !BOUND.test(c) && !INFORMATIONAL.test(c));
+  const oldFlags = flags(before);
+  const newFlags = flags(after);
+  const oldPattern = oldFlags.find(c => c.startsWith('matches '));
+  const newPattern = newFlags.find(c => c.startsWith('matches '));
+
+  if (newPattern && newPattern !== oldPattern) {
+    // Patterns cannot be compared, so any new or changed pattern may reject old values
+    const message = oldPattern
+      ? `Pattern changed from ${oldPattern.slice('matches '.length)} to ${newPattern.slice('matches '.length)}`
+      : `Value must now match ${newPattern.slice('matches '.length)}`;
+    breaking(changes, path, 'pattern.tightened', message);
+  } else if (oldPattern && !newPattern) {
+    compatible(changes, path, 'pattern.removed', `Value no longer has to match ${oldPattern.slice('matches '.length)}`);
+  }
+
+  for (const flag of newFlags) {
+    if (!flag.startsWith('matches ') && !oldFlags.includes(flag)) {
+      breaking(changes, path, 'constraint.added', `Value must now be ${flag}`);
+    }
+  }
+  for (const flag of oldFlags) {
+    if (!flag.startsWith('matches ') && !newFlags.includes(flag)) {
+      compatible(changes, path, 'constraint.removed', `Constraint removed: ${flag}`);
+    }
+  }
+}
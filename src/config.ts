@@ -0,0 +1,372 @@
+// src/config.ts
+// Project configuration - .specspec.toml with the specs, codegen targets and report settings of a project
+
+import fs from 'node:fs';
+import path from 'node:path';
+import type { Issue } from './context.js';
+import type { ValidationResult } from './engine.js';
+
+export const CONFIG_FILE = '.specspec.toml';
+
+/** Which issue levels fail a validation */
+export type FailOn = 'error' | 'warning';
+
+export interface IssuePolicy {
+  /** Default: error, so warnings are reported without failing */
+  failOn?: FailOn;
+  /** Issues left out of results: `code` or `code@path`; `*` in a code matches anything, a path also matches below it */
+  suppress?: string[];
+}
+
+/** A [[codegen]] entry: one generated validator */
+export interface CodegenTarget {
+  lang: string;
+  output: string;
+  package?: string;
+  cli?: string;
+  typed?: boolean;
+  slim?: boolean;
+  failFast?: boolean;
+  externalPrelude?: boolean;
+  propertyTests?: boolean;
+  schedule?: boolean;
+}
+
+export interface ProjectConfig extends IssuePolicy {
+  /** The file the configuration was read from */
+  file: string;
+  /** Paths are resolved against the file's directory */
+  spec?: string;
+  /** Further specs validated after `spec`, as with --spec */
+  specs?: string[];
+  types?: string[];
+  target?: string;
+  format?: 'text' | 'json';
+  codegen?: CodegenTarget[];
+}
+
+// ═══════════════════════════════════════════════════════════════
+// TOML (the subset configuration files need)
+// ═══════════════════════════════════════════════════════════════
+
+type TomlValue = string | number | boolean | TomlValue[] | TomlTable;
+interface TomlTable { [key: string]: TomlValue }
+
+class TomlReader {
+  private pos = 0;
+
+  constructor(private readonly text: string, private readonly file: string) {}
+
+  error(message: string, at = this.pos): Error {
+    const line = this.text.slice(0, at).split('\n').length;
+    return new Error(`${this.file}:${line}: ${message}`);
+  }
+
+  parse(): TomlTable {
+    const root: TomlTable = {};
+    let table = root;
+    for (;;) {
+      this.skipBlank(true);
+      if (this.pos >= this.text.length) return root;
+      const start = this.pos;
+      if (this.text.startsWith('[[', this.pos)) {
+        this.pos += 2;
+        const name = this.key();
+        this.expect(']]');
+        const list: TomlValue = root[name] ?? [];
+        if (!Array.isArray(list)) throw this.error(`"${name}" is already defined`, start);
+        table = {};
+        list.push(table);
+        root[name] = list;
+      } else if (this.text[this.pos] === '[') {
+        this.pos++;
+        const name = this.key();
+        this.expect(']');
+        if (root[name] !== undefined) throw this.error(`Table [${name}] is already defined`, start);
+        table = {};
+        root[name] = table;
+      } else {
+        const key = this.key();
+        this.skipBlank(false);
+        this.expect('=');
+        this.skipBlank(false);
+        if (Object.hasOwn(table, key)) throw this.error(`"${key}" is already defined`, start);
+        table[key] = this.value();
+      }
+      this.skipBlank(false);
+      if (this.pos < this.text.length && this.text[this.pos] !== '\n') {
+        throw this.error(`Expected the end of the line, got "${this.text[this.pos]}"`);
+      }
+    }
+  }
+
+  // Spaces and comments; newlines too when `lines`
+  private skipBlank(lines: boolean): void {
+    while (this.pos < this.text.length) {
+      const c = this.text[this.pos]!;
+      if (c === '#') {
+        while (this.pos < this.text.length && this.text[this.pos] !== '\n') this.pos++;
+      } else if (c === ' ' || c === '\t' || c === '\r' || (lines && c === '\n')) {
+        this.pos++;
+      } else {
+        return;
+      }
+    }
+  }
+
+  private expect(token: string): void {
+    if (!this.text.startsWith(token, this.pos)) throw this.error(`Expected "${token}"`);
+    this.pos += token.length;
+  }
+
+  private key(): string {
+    this.skipBlank(false);
+    const c = this.text[this.pos];
+    if (c === '"' || c === '\'') return this.string();
+    const match = /^[A-Za-z0-9_-]+/.exec(this.text.slice(this.pos));
+    if (!match) throw this.error('Expected a key');
+    this.pos += match[0].length;
+    if (this.text[this.pos] === '.') throw this.error('Dotted keys are not supported; use a [table]');
+    this.skipBlank(false);
+    return match[0];
+  }
+
+  private value(): TomlValue {
+    const c = this.text[this.pos];
+    if (c === '"' || c === '\'') return this.string();
+    if (c === '[') return this.array();
+    if (c === '{') throw this.error('Inline tables are not supported; use a [table]');
+    const match = /^[^\s,\]#]+/.exec(this.text.slice(this.pos));
+    if (!match) throw this.error('Expected a value');
+    const word = match[0];
+    let value: TomlValue;
+    if (word === 'true' || word === 'false') {
+      value = word === 'true';
+    } else if (/^[+-]?(\d[\d_]*)(\.\d[\d_]*)?([eE][+-]?\d+)?$/.test(word)) {
+      value = Number(word.replace(/_/g, ''));
+    } else {
+      throw this.error(`Invalid value: ${word} (strings need quotes)`);
+    }
+    this.pos += word.length;
+    return value;
+  }
+
+  private string(): string {
+    const quote = this.text[this.pos]!;
+    const start = this.pos++;
+    let out = '';
+    while (this.pos < this.text.length) {
+      const c = this.text[this.pos++]!;
+      if (c === quote) return out;
+      if (c === '\n') break;
+      if (c === '\\' && quote === '"') {
+        const e = this.text[this.pos++];
+        const escapes: Record<string, string> = { n: '\n', t: '\t', r: '\r', '"': '"', '\\': '\\', b: '\b', f: '\f' };
+        if (e === 'u' || e === 'U') {
+          const length = e === 'u' ? 4 : 8;
+          const hex = this.text.slice(this.pos, this.pos + length);
+          if (!/^[0-9a-fA-F]+$/.test(hex) || hex.length !== length) throw this.error('Invalid unicode escape');
+          out += String.fromCodePoint(parseInt(hex, 16));
+          this.pos += length;
+        } else if (e !== undefined && e in escapes) {
+          out += escapes[e]!;
+        } else {
+          throw this.error(`Invalid escape: \\${e ?? ''}`);
+        }
+      } else {
+        out += c;
+      }
+    }
+    throw this.error('Unterminated string', start);
+  }
+
+  private array(): TomlValue[] {
+    this.pos++;
+    const items: TomlValue[] = [];
+    for (;;) {
+      this.skipBlank(true);
+      if (this.text[this.pos] === ']') {
+        this.pos++;
+        return items;
+      }
+      items.push(this.value());
+      this.skipBlank(true);
+      if (this.text[this.pos] === ',') {
+        this.pos++;
+      } else if (this.text[this.pos] !== ']') {
+        throw this.error('Expected "," or "]" in array');
+      }
+    }
+  }
+}
+
+/**
+ * Parse TOML: tables, arrays of tables, and key/value pairs holding strings,
+ * numbers, booleans and arrays. Dotted keys, inline tables and dates are not
+ * supported. Errors name the file and line.
+ */
+export function parseToml(text: string, file = 'config.toml'): Record<string, unknown> {
+  return new TomlReader(text, file).parse();
+}
+
+// ═══════════════════════════════════════════════════════════════
+// Configuration
+// ═══════════════════════════════════════════════════════════════
+
+/**
+ * The nearest .specspec.toml: in `dir` or one of its parents
+ */
+export function findConfig(dir = process.cwd()): string | undefined {
+  for (let current = path.resolve(dir); ; current = path.dirname(current)) {
+    const file = path.join(current, CONFIG_FILE);
+    if (fs.existsSync(file)) return file;
+    if (path.dirname(current) === current) return undefined;
+  }
+}
+
+type Check<T> = (value: unknown, key: string) => T;
+
+const isString: Check<string> = (value, key) => {
+  if (typeof value !== 'string') throw new Error(`"${key}" must be a string`);
+  return value;
+};
+
+const isBoolean: Check<boolean> = (value, key) => {
+  if (typeof value !== 'boolean') throw new Error(`"${key}" must be true or false`);
+  return value;
+};
+
+const isStrings: Check<string[]> = (value, key) => {
+  if (!Array.isArray(value) || !value.every(v => typeof v === 'string')) throw new Error(`"${key}" must be an array of strings`);
+  return value as string[];
+};
+
+function oneOf<T extends string>(...allowed: T[]): Check<T> {
+  return (value, key) => {
+    if (!allowed.includes(value as T)) throw new Error(`"${key}" must be one of: ${allowed.map(a => `"${a}"`).join(', ')}`);
+    return value as T;
+  };
+}
+
+// Check a table's keys and values; unknown keys are errors, so typos do not go unnoticed
+function readTable<T>(table: unknown, name: string, fields: { [K in keyof T]-?: [string, Check<T[K]>] }): Partial<T> {
+  if (table === null || typeof table !== 'object' || Array.isArray(table)) {
+    throw new Error(`"${name}" must be a table`);
+  }
+  const out: Partial<T> = {};
+  const known = new Map(Object.entries(fields).map(([field, [key]]) => [key as string, field]));
+  for (const [key, value] of Object.entries(table)) {
+    const field = known.get(key);
+    if (field === undefined) {
+      throw new Error(`Unknown key "${key}"${name ? ` in [${name}]` : ''}`);
+    }
+    const check = (fields as Record<string, [string, Check<unknown>]>)[field]![1];
+    (out as Record<string, unknown>)[field] = check(value, name ? `${name}.${key}` : key);
+  }
+  return out;
+}
+
+/**
+ * Read a .specspec.toml. Throws an Error naming the file (and line, for syntax
+ * errors) when it is not valid.
+ *
+ * @example
+ * # .specspec.toml
+ * spec = "specs/bundle.spec.js"
+ * specs = ["specs/store-policy.spec.js"]
+ * types = ["specs/types.mjs"]
+ * target = "dist/bundle"
+ *
+ * [report]
+ * format = "json"
+ * fail-on = "warning"
+ * suppress = ["field.deprecated@legacy", "list.sampled"]
+ *
+ * [[codegen]]
+ * lang = "python"
+ * output = "gen/validator.py"
+ */
+export function loadConfig(file: string): ProjectConfig {
+  const toml = parseToml(fs.readFileSync(file, 'utf-8'), file);
+  const dir = path.dirname(file);
+  const resolve = (p: string) => path.resolve(dir, p);
+  try {
+    const { report, codegen, ...top } = toml;
+    const main = readTable<{ spec: string; specs: string[]; types: string[]; target: string }>(top, '', {
+      spec: ['spec', isString],
+      specs: ['specs', isStrings],
+      types: ['types', isStrings],
+      target: ['target', isString],
+    });
+    type Report = { format: 'text' | 'json'; failOn: FailOn; warningsAsErrors: boolean; suppress: string[] };
+    const { warningsAsErrors, ...reporting }: Partial<Report> = report === undefined ? {} : readTable<Report>(report, 'report', {
+      format: ['format', oneOf('text', 'json')],
+      failOn: ['fail-on', oneOf('error', 'warning')],
+      warningsAsErrors: ['warnings-as-errors', isBoolean],
+      suppress: ['suppress', isStrings],
+    });
+    if (warningsAsErrors !== undefined && reporting.failOn !== undefined) {
+      throw new Error('Set either "report.fail-on" or "report.warnings-as-errors"');
+    }
+    if (codegen !== undefined && !Array.isArray(codegen)) {
+      throw new Error('Declare codegen targets as [[codegen]] tables');
+    }
+    const targets = ((codegen ?? []) as unknown[]).map((entry, i): CodegenTarget => {
+      const target = readTable<CodegenTarget>(entry, 'codegen', {
+        lang: ['lang', isString],
+        output: ['output', isString],
+        package: ['package', isString],
+        cli: ['cli', isString],
+        typed: ['typed', isBoolean],
+        slim: ['slim', isBoolean],
+        failFast: ['fail-fast', isBoolean],
+        externalPrelude: ['external-prelude', isBoolean],
+        propertyTests: ['property-tests', isBoolean],
+        schedule: ['schedule', isBoolean],
+      });
+      if (target.lang === undefined || target.output === undefined) {
+        throw new Error(`[[codegen]] ${i + 1} needs "lang" and "output"`);
+      }
+      return { ...target, lang: target.lang, output: resolve(target.output) };
+    });
+
+    return {
+      file,
+      ...(main.spec !== undefined ? { spec: resolve(main.spec) } : {}),
+      ...(main.specs !== undefined ? { specs: main.specs.map(resolve) } : {}),
+      ...(main.types !== undefined ? { types: main.types.map(resolve) } : {}),
+      ...(main.target !== undefined ? { target: resolve(main.target) } : {}),
+      ...reporting,
+      ...(warningsAsErrors ? { failOn: 'warning' as const } : {}),
+      ...(targets.length > 0 ? { codegen: targets } : {}),
+    };
+  } catch (err) {
+    throw new Error(`${file}: ${(err as Error).message}`);
+  }
+}
+
+// ═══════════════════════════════════════════════════════════════
+// Issue policy
+// ═══════════════════════════════════════════════════════════════
+
+function suppresses(entry: string, issue: Issue): boolean {
+  const at = entry.indexOf('@');
+  const code = at === -1 ? entry : entry.slice(0, at);
+  const codePattern = new RegExp(`^${code.split('*').map(p => p.replace(/[.+?^$()|[\]{}\\]/g, '\\$&')).join('.*')}$`);
+  if (!codePattern.test(issue.code)) return false;
+  if (at === -1) return true;
+  const prefix = entry.slice(at + 1);
+  const issuePath = issue.path.join('.');
+  return issuePath === prefix || issuePath.startsWith(`${prefix}.`);
+}
+
+/**
+ * Apply a policy to a result: drop suppressed issues, then fail on errors, or
+ * on warnings too with `failOn: 'warning'`
+ */
+export function applyIssuePolicy<T extends ValidationResult>(result: T, policy: IssuePolicy): T {
+  const suppress = policy.suppress ?? [];
+  const issues = result.issues.filter(issue => !suppress.some(entry => suppresses(entry, issue)));
+  const failing = policy.failOn === 'warning' ? ['error', 'warning'] : ['error'];
+  return { ...result, issues, ok: !issues.some(i => failing.includes(i.level)) };
+}
@@ -1,11 +1,31 @@
 // src/context.ts
 // 验证上下文 - 收集问题，跟踪路径
 
+/**
+ * JSON Patch (RFC 6902) operation
+ */
+export type JsonPatchOperation =
+  | { op: 'add' | 'replace' | 'test'; path: string; value: unknown }
+  | { op: 'remove'; path: string }
+  | { op: 'move' | 'copy'; from: string; path: string };
+
 export interface Issue {
-  level: 'error' | 'warning';
+  level: 'error' | 'warning' | 'info';
   code: string;
   message: string;
   path: string[];
+  /** 机器可应用的修复；JSON Pointer 相对于 path 处的值 */
+  fix?: JsonPatchOperation[];
+  /** 附加元数据（负责团队、文档链接、策略 ID 等），供下游路由和展示 */
+  meta?: Record<string, unknown>;
+}
+
+/**
+ * 验证选项：由引擎传给根上下文，子上下文沿用
+ */
+export interface ValidationOptions {
+  /** 对带 sample 的 ListOf 只验证抽中的数组项（默认 true）；false 时验证每一项，同 --full */
+  sampling?: boolean;
 }
 
 export interface Context {
@@ -18,32 +38,81 @@ export interface Context {
   /** 当前验证的值 */
   readonly value: unknown;
 
-  /** 报告错误 */
-  addIssue(code: string, message: string): void;
+  /** 验证选项 */
+  readonly options: ValidationOptions;
+
+  /** 报告错误（可附带修复建议） */
+  addIssue(code: string, message: string, fix?: JsonPatchOperation[]): void;
 
   /** 报告警告 */
   addWarning(code: string, message: string): void;
 
+  /** 报告提示信息（不影响验证结果） */
+  addInfo(code: string, message: string): void;
+
   /** 创建子上下文 */
   child(segment: string, value: unknown): Context;
+
+  /** 创建附带元数据的上下文，经由它（及其子上下文）报告的问题都带上这些元数据 */
+  withMeta(meta: Record<string, unknown>): Context;
+
+  /** 标记结果不完整：有内容没有验证（如只验证了抽样的数组项） */
+  markPartial(): void;
+}
+
+/**
+ * 验证观察者（用于覆盖率统计，见 coverage.ts）：enter()/leave() 包住 validateAny()
+ * 对每个节点的验证（matches() 试探除外），issue() 收到其间报告的每个错误码
+ */
+export interface ValidationProbe {
+  enter(node: unknown, value: unknown): void;
+  leave(): void;
+  issue(code: string): void;
+}
+
+let activeProbe: ValidationProbe | undefined;
+
+/** 安装（传 undefined 则移除）验证观察者 */
+export function setValidationProbe(probe: ValidationProbe | undefined): void {
+  activeProbe = probe;
+}
+
+/** 当前的验证观察者 */
+export function validationProbe(): ValidationProbe | undefined {
+  return activeProbe;
 }
 
 export class ValidationContext implements Context {
   readonly issues: Issue[] = [];
+  private partialResult = false;
 
   constructor(
     readonly path: string[],
     readonly value: unknown,
-    private readonly root?: ValidationContext
+    private readonly root?: ValidationContext,
+    private readonly meta?: Record<string, unknown>,
+    readonly options: ValidationOptions = {}
   ) {}
 
-  addIssue(code: string, message: string): void {
+  /** 结果是否不完整（见 markPartial()） */
+  get partial(): boolean {
+    return (this.root ?? this).partialResult;
+  }
+
+  markPartial(): void {
+    (this.root ?? this).partialResult = true;
+  }
+
+  addIssue(code: string, message: string, fix?: JsonPatchOperation[]): void {
+    activeProbe?.issue(code);
     const target = this.root ?? this;
     target.issues.push({
       level: 'error',
       code,
       message,
       path: [...this.path],
+      ...(fix && fix.length > 0 ? { fix } : {}),
+      ...(this.meta ? { meta: { ...this.meta } } : {}),
     });
   }
 
@@ -54,6 +123,18 @@ export class ValidationContext implements Context {
       code,
       message,
       path: [...this.path],
+      ...(this.meta ? { meta: { ...this.meta } } : {}),
+    });
+  }
+
+  addInfo(code: string, message: string): void {
+    const target = this.root ?? this;
+    target.issues.push({
+      level: 'info',
+      code,
+      message,
+      path: [...this.path],
+      ...(this.meta ? { meta: { ...this.meta } } : {}),
     });
   }
 
@@ -61,7 +142,24 @@ export class ValidationContext implements Context {
     return new ValidationContext(
       [...this.path, segment],
       value,
-      this.root ?? this
+      this.root ?? this,
+      this.meta,
+      this.options
+    );
+  }
+
+  withMeta(meta: Record<string, unknown>): Context {
+    return new ValidationContext(
+      this.path,
+      this.value,
+      this.root ?? this,
+      { ...this.meta, ...meta },
+      this.options
     );
   }
+
+  /** 经由本上下文报告的问题是否汇总到 root（root 自身或由它派生的上下文） */
+  reportsTo(root: ValidationContext): boolean {
+    return (this.root ?? this) === root;
+  }
 }
@@ -0,0 +1,365 @@
+// src/coverage.ts
+// Rule coverage - which constraints and branches of a spec a set of validation runs exercised
+
+import { isType, isModifier, isObjectSpec, type Validatable, type ObjectSpec } from './base.js';
+import { setValidationProbe, type ValidationProbe } from './context.js';
+import { StrType, NumType, DecimalType } from './types/primitives.js';
+import { FieldType, FileType, DirectoryType, JsonFileType } from './types/structural.js';
+import {
+  ListOfModifier, OneOfModifier, TaggedUnionModifier, NullableModifier, DefModifier, NotModifier, ExtendModifier,
+} from './modifiers/index.js';
+
+export interface CoverageRule {
+  /** Where the rule applies: dotted field keys, `[]` for list items, `(kind=circle)` inside a variant, `(option 2)` inside a OneOf option */
+  path: string;
+  /** A constraint or required entry holds until a fixture breaks it; a branch is taken or not */
+  kind: 'constraint' | 'required' | 'branch';
+  /** What it checks, e.g. "minimum 3 characters", "required", "present", "null", "kind=circle" */
+  rule: string;
+  /** Runs that reached the rule, or took the branch */
+  hits: number;
+  /** Runs the rule rejected (constraints and required entries) */
+  failures: number;
+  /**
+   * covered: a branch some run took, or a rule some run broke;
+   * unfailed: a rule runs reached but none broke, so it is not tested;
+   * missed: no run reached it
+   */
+  status: 'covered' | 'unfailed' | 'missed';
+  /** Labels of runs the rule rejected, up to the recorder's `examples` limit */
+  examples?: string[];
+}
+
+export interface CoverageOptions {
+  /** Labels kept per rule of the runs that broke it (see record()); default 0 */
+  examples?: number;
+}
+
+export interface CoverageReport {
+  total: number;
+  covered: number;
+  /** In spec order */
+  rules: CoverageRule[];
+}
+
+interface RuleEntry {
+  path: string;
+  kind: CoverageRule['kind'];
+  rule: string;
+  /** Codes reporting a failure of the rule; undefined: any error but type.mismatch */
+  codes?: string[] | undefined;
+  /** Branch key: present, null, option:<n>, tag:<tag> */
+  branch?: string;
+  hits: number;
+  failures: number;
+  examples: string[];
+}
+
+// Constraint descriptions of the built-in types and the codes reported when they fail
+const CONSTRAINT_CODES: Array<[RegExp, string[]]> = [
+  [/^minimum .* characters$/, ['str.too_short']],
+  [/^maximum .* characters$/, ['str.too_long']],
+  [/^minimum .* items$/, ['list.too_short']],
+  [/^maximum .* items$/, ['list.too_long']],
+  [/^minimum \d+ (\.\S+ )?files?( matching .*)?$/, ['dir.too_few_files']],
+  [/^maximum \d+ (\.\S+ )?files?( matching .*)?$/, ['dir.too_many_files']],
+  [/^minimum /, ['num.too_small']],
+  [/^maximum /, ['num.too_large']],
+  [/^matches /, ['str.pattern_mismatch']],
+  [/^integer$/, ['num.not_integer']],
+  [/^precision /, ['decimal.too_many_digits']],
+  [/^scale /, ['decimal.scale_exceeded']],
+  [/^unique /, ['list.duplicate']],
+  [/^sorted /, ['list.not_sorted']],
+  [/^extension: /, ['file.wrong_ext']],
+  [/ checksum /, ['checksum.mismatch']],
+  [/^no undeclared entries$/, ['dir.unexpected']],
+];
+
+// Constraint descriptions that document rather than check
+const NOT_CHECKED = /^(default|sampled): /;
+
+function isBuiltIn(node: object): boolean {
+  return node instanceof StrType || node instanceof NumType || node instanceof DecimalType
+    || node instanceof ListOfModifier || node instanceof FileType || node instanceof DirectoryType;
+}
+
+export function isLiteral(v: unknown): boolean {
+  return v === null || typeof v !== 'object' || Object.prototype.toString.call(v) === '[object RegExp]';
+}
+
+// A short name for a OneOf option or negated type: Sku, String, "auto", /^v\d+$/
+export function typeLabel(v: Validatable): string {
+  if (isType(v) || isModifier(v)) {
+    const desc = v.describe();
+    return desc.ref ?? desc.name;
+  }
+  if (isLiteral(v)) {
+    return Object.prototype.toString.call(v) === '[object RegExp]' ? String(v) : JSON.stringify(v) ?? String(v);
+  }
+  return 'Object';
+}
+
+function joinPath(path: string, key: string): string {
+  return path ? `${path}.${key}` : key;
+}
+
+// Types, modifiers and object specs anywhere inside a spec value (for types and modifiers without a known layout)
+function nestedNodes(value: unknown, optional = false, seen = new Set<unknown>()): Array<{ node: object; optional: boolean }> {
+  if (value === null || typeof value !== 'object' || seen.has(value)) return [];
+  seen.add(value);
+  if (isType(value) || isModifier(value) || isObjectSpec(value)) {
+    return [{ node: value, optional }];
+  }
+  if (Array.isArray(value)) {
+    return value.flatMap(v => nestedNodes(v, optional, seen));
+  }
+  return Object.entries(value).flatMap(([key, v]) => nestedNodes(v, key === 'optional' || optional, seen));
+}
+
+/**
+ * Records which rules of a spec validation runs exercise. Rules come from the
+ * compiled spec: the constraints of each type (as describe() lists them),
+ * required fields, files and directories, and the branches - optional entries
+ * present, null taken, OneOf options, TaggedUnion variants. A constraint is
+ * covered once a run breaks it, a branch once a run takes it.
+ *
+ * @example
+ * const coverage = new CoverageRecorder(schema.root);
+ * for (const file of fixtures) coverage.record(() => schema.validateValue(JSON.parse(fs.readFileSync(file, 'utf-8'))));
+ * coverage.report().rules.filter(r => r.status === 'missed');
+ */
+export class CoverageRecorder implements ValidationProbe {
+  private readonly entries: RuleEntry[] = [];
+  private readonly byNode = new Map<object, RuleEntry[]>();
+  private readonly visited = new Set<object>();
+  // Rule owners of the nodes being validated, innermost last
+  private readonly stack: Array<object | undefined> = [];
+  private hit = new Set<RuleEntry>();
+  private failed = new Set<RuleEntry>();
+  private readonly exampleLimit: number;
+
+  constructor(root: Validatable | ObjectSpec, options: CoverageOptions = {}) {
+    this.exampleLimit = options.examples ?? 0;
+    this.collect(root, '', false, undefined);
+  }
+
+  /** Run a validation (one fixture, named `label` among the examples) and record the rules it exercised */
+  record<T>(run: () => T, label?: string): T {
+    this.hit = new Set();
+    this.failed = new Set();
+    this.stack.length = 0;
+    setValidationProbe(this);
+    try {
+      return run();
+    } finally {
+      setValidationProbe(undefined);
+      for (const entry of this.hit) entry.hits++;
+      for (const entry of this.failed) {
+        entry.failures++;
+        if (label !== undefined && entry.examples.length < this.exampleLimit) entry.examples.push(label);
+      }
+    }
+  }
+
+  report(): CoverageReport {
+    const rules = this.entries.map(({ path, kind, rule, hits, failures, examples }): CoverageRule => {
+      const status = hits === 0 ? 'missed' : kind === 'branch' || failures > 0 ? 'covered' : 'unfailed';
+      return { path: path || '(root)', kind, rule, hits, failures, status, ...(this.exampleLimit > 0 ? { examples } : {}) };
+    });
+    return { total: rules.length, covered: rules.filter(r => r.status === 'covered').length, rules };
+  }
+
+  // ─── ValidationProbe ───
+
+  enter(node: unknown, value: unknown): void {
+    const parent = this.stack[this.stack.length - 1];
+    if (parent instanceof OneOfModifier) {
+      this.take(parent, `option:${parent.options.indexOf(node as Validatable)}`);
+    } else if (parent instanceof TaggedUnionModifier) {
+      this.take(parent, `tag:${String((value as Record<string, unknown>)[parent.discriminator])}`);
+    }
+    if (isLiteral(node)) {
+      // Literal checks are rules of the node holding the literal
+      this.stack.push(parent);
+      return;
+    }
+
+    const owner = node as object;
+    this.stack.push(owner);
+    for (const entry of this.byNode.get(owner) ?? []) {
+      if (entry.branch === undefined || (entry.branch === 'present' && !(owner instanceof FieldType))) {
+        this.hit.add(entry);
+      }
+    }
+    if (owner instanceof FieldType && value !== null && typeof value === 'object') {
+      const fieldValue = (value as Record<string, unknown>)[owner.spec.key];
+      if (fieldValue !== undefined) this.take(owner, 'present');
+      if (fieldValue === null) this.take(owner, 'null');
+    } else if (owner instanceof NullableModifier && value === null) {
+      this.take(owner, 'null');
+    }
+  }
+
+  leave(): void {
+    this.stack.pop();
+  }
+
+  issue(code: string): void {
+    const owner = this.stack[this.stack.length - 1];
+    if (owner === undefined) return;
+    for (const entry of this.byNode.get(owner) ?? []) {
+      if (entry.branch === undefined && (entry.codes ? entry.codes.includes(code) : code !== 'type.mismatch')) {
+        this.failed.add(entry);
+      }
+    }
+  }
+
+  private take(owner: object, branch: string): void {
+    const entry = this.byNode.get(owner)?.find(e => e.branch === branch);
+    if (entry) this.hit.add(entry);
+  }
+
+  // ─── Rule inventory ───
+
+  private add(owner: object, entry: Omit<RuleEntry, 'hits' | 'failures' | 'examples'>): void {
+    const full: RuleEntry = { ...entry, hits: 0, failures: 0, examples: [] };
+    this.entries.push(full);
+    this.byNode.set(owner, [...(this.byNode.get(owner) ?? []), full]);
+  }
+
+  private collect(node: unknown, path: string, optional: boolean, parent: object | undefined): void {
+    if (isLiteral(node)) {
+      if (parent !== undefined && !(parent instanceof OneOfModifier)) {
+        const pattern = Object.prototype.toString.call(node) === '[object RegExp]';
+        this.add(parent, {
+          path,
+          kind: 'constraint',
+          rule: pattern ? `matches ${String(node)}` : `equals ${JSON.stringify(node)}`,
+          codes: [pattern ? 'literal.pattern_mismatch' : 'literal.mismatch'],
+        });
+      }
+      return;
+    }
+    const owner = node as object;
+    if (this.visited.has(owner)) return;
+    this.visited.add(owner);
+
+    if (owner instanceof FieldType) {
+      const { key, value, optional: optionalField, nullable } = owner.spec;
+      const fieldPath = joinPath(path, key);
+      if (optionalField) {
+        this.add(owner, { path: fieldPath, kind: 'branch', rule: 'present', branch: 'present' });
+      } else {
+        this.add(owner, { path: fieldPath, kind: 'required', rule: 'required', codes: ['field.missing'] });
+      }
+      if (nullable) {
+        this.add(owner, { path: fieldPath, kind: 'branch', rule: 'null', branch: 'null' });
+      }
+      if (value !== undefined && isObjectSpec(value)) {
+        this.collectObject(value, fieldPath, owner);
+      } else if (value !== undefined) {
+        this.collect(value, fieldPath, false, owner);
+      }
+      return;
+    }
+
+    if (owner instanceof FileType || owner instanceof DirectoryType || owner instanceof JsonFileType) {
+      const entryPath = owner.spec?.path ? joinPath(path, owner.spec.path) : path;
+      if (optional) {
+        this.add(owner, { path: entryPath, kind: 'branch', rule: 'present', branch: 'present' });
+      } else {
+        const codes = owner instanceof DirectoryType ? ['dir.not_found', 'dir.not_dir'] : ['file.not_found', 'file.not_file'];
+        this.add(owner, { path: entryPath, kind: 'required', rule: 'exists', codes });
+      }
+      if (owner instanceof JsonFileType) {
+        this.add(owner, { path: entryPath, kind: 'constraint', rule: 'valid JSON', codes: ['json.parse_error'] });
+      }
+      this.addConstraints(owner, entryPath);
+      for (const child of nestedNodes(owner.spec)) {
+        this.collect(child.node, entryPath, child.optional, owner);
+      }
+      return;
+    }
+
+    if (owner instanceof OneOfModifier) {
+      this.add(owner, { path, kind: 'constraint', rule: 'matches an option', codes: ['oneof.no_match'] });
+      owner.options.forEach((option, i) => {
+        this.add(owner, { path, kind: 'branch', rule: `option ${i + 1}: ${typeLabel(option)}`, branch: `option:${i}` });
+      });
+      owner.options.forEach((option, i) => this.collect(option, `${path}(option ${i + 1})`, false, owner));
+      return;
+    }
+
+    if (owner instanceof TaggedUnionModifier) {
+      const { discriminator } = owner;
+      this.add(owner, { path: joinPath(path, discriminator), kind: 'required', rule: 'required', codes: ['field.missing'] });
+      this.add(owner, { path: joinPath(path, discriminator), kind: 'constraint', rule: 'known tag', codes: ['union.unknown_tag'] });
+      for (const tag of Object.keys(owner.variants)) {
+        this.add(owner, { path, kind: 'branch', rule: `${discriminator}=${tag}`, branch: `tag:${tag}` });
+      }
+      for (const [tag, variant] of Object.entries(owner.variants)) {
+        this.collect(variant, `${path}(${discriminator}=${tag})`, false, owner);
+      }
+      return;
+    }
+
+    if (owner instanceof ListOfModifier) {
+      this.addConstraints(owner, path);
+      this.collect(owner.itemType, `${path}[]`, false, owner);
+      return;
+    }
+
+    if (owner instanceof NullableModifier) {
+      this.add(owner, { path, kind: 'branch', rule: 'null', branch: 'null' });
+      this.collect(owner.inner, path, false, owner);
+      return;
+    }
+
+    if (owner instanceof DefModifier) {
+      if (owner.options.maxDepth !== undefined) {
+        this.add(owner, { path, kind: 'constraint', rule: `maximum depth ${owner.options.maxDepth}`, codes: ['recursion.too_deep'] });
+      }
+      this.collect(owner.body, path, false, owner);
+      return;
+    }
+
+    if (owner instanceof NotModifier) {
+      // The negated type's own rules are not rules of this spec
+      this.add(owner, { path, kind: 'constraint', rule: `does not match ${typeLabel(owner.inner)}`, codes: ['not.matched'] });
+      return;
+    }
+
+    if (owner instanceof ExtendModifier) {
+      this.collect(owner.spec, path, false, owner);
+      return;
+    }
+
+    if (isObjectSpec(owner)) {
+      this.collectObject(owner, path, owner);
+      return;
+    }
+
+    // Other types (primitives, custom types) and modifiers (AllOf, Meta, Message, ...)
+    if (isType(owner)) {
+      this.addConstraints(owner, path);
+    }
+    for (const child of nestedNodes(isType(owner) ? owner.spec : Object.values(owner))) {
+      this.collect(child.node, path, child.optional, owner);
+    }
+  }
+
+  private collectObject(spec: ObjectSpec, path: string, owner: object): void {
+    for (const item of spec.required ?? []) this.collect(item, path, false, owner);
+    for (const item of spec.optional ?? []) this.collect(item, path, true, owner);
+  }
+
+  private addConstraints(owner: object, path: string): void {
+    if (!isType(owner) && !isModifier(owner)) return;
+    for (const constraint of owner.describe().constraints ?? []) {
+      if (NOT_CHECKED.test(constraint)) continue;
+      const codes = isBuiltIn(owner) ? CONSTRAINT_CODES.find(([pattern]) => pattern.test(constraint))?.[1] : undefined;
+      this.add(owner, { path, kind: 'constraint', rule: constraint, codes });
+    }
+  }
+}
@@ -0,0 +1,129 @@
+// src/differential.ts
+// Differential execution - run a corpus through two validator versions and diff the results
+
+import fs from 'node:fs';
+import path from 'node:path';
+import { spawnSync } from 'node:child_process';
+import { pathToFileURL } from 'node:url';
+import type { SpecEngine, ValidationResult } from './engine.js';
+
+/**
+ * Result of one validator run, normalized so core engines and generated validators compare equal.
+ * Issue paths are dotted strings ("(root)" for the root), as printed by generated validators.
+ * Issues are compared by code and path only; message wording may change between versions.
+ */
+export interface RunOutcome {
+  ok: boolean;
+  issues: Array<{ code: string; path: string; message: string }>;
+}
+
+/** A validator version: validates one corpus entry */
+export type Runner = (target: string) => RunOutcome;
+
+export interface DifferentialChange {
+  target: string;
+  /** True when the entry flipped between valid and invalid */
+  verdictChanged: boolean;
+  left: RunOutcome;
+  right: RunOutcome;
+  /** Issues only the right version reports */
+  added: RunOutcome['issues'];
+  /** Issues only the left version reports */
+  removed: RunOutcome['issues'];
+}
+
+export interface DifferentialReport {
+  total: number;
+  /** Entries whose verdict flipped */
+  verdictChanges: number;
+  changes: DifferentialChange[];
+}
+
+function issueKey(issue: { code: string; path: string }): string {
+  return `${issue.code}@${issue.path}`;
+}
+
+/**
+ * Normalize a core ValidationResult (warnings are ignored, as generated validators do not report them)
+ */
+export function normalizeResult(result: ValidationResult): RunOutcome {
+  return {
+    ok: result.ok,
+    issues: result.issues
+      .filter(i => i.level === 'error')
+      .map(i => ({ code: i.code, path: i.path.length > 0 ? i.path.join('.') : '(root)', message: i.message })),
+  };
+}
+
+/**
+ * Runner backed by a SpecEngine instance
+ */
+export function engineRunner(engine: SpecEngine, specPath: string): Runner {
+  return (target) => normalizeResult(engine.run(specPath, target));
+}
+
+/**
+ * Runner backed by another installed SpecSpec version, loaded from its entry module
+ * (e.g. node_modules/@specspec/core-previous/dist/index.js)
+ */
+export async function moduleRunner(modulePath: string, specPath: string): Promise<Runner> {
+  const mod = await import(pathToFileURL(path.resolve(modulePath)).href) as {
+    SpecEngine: new () => SpecEngine;
+  };
+  return engineRunner(new mod.SpecEngine(), specPath);
+}
+
+/**
+ * Runner backed by a generated validator program that prints `{ ok, issues }` JSON,
+ * e.g. commandRunner('python3', ['validator.py']) or commandRunner('./target/release/validator')
+ */
+export function commandRunner(command: string, args: string[] = []): Runner {
+  return (target) => {
+    const proc = spawnSync(command, [...args, target], { encoding: 'utf-8' });
+    try {
+      const parsed = JSON.parse(proc.stdout) as { ok: boolean; issues: Array<RunOutcome['issues'][number] & { level?: string }> };
+      // Like normalizeResult, only errors are compared; older validators print no level
+      const errors = parsed.issues.filter(i => i.level !== 'warning');
+      return { ok: parsed.ok, issues: errors.map(i => ({ code: i.code, path: i.path, message: i.message })) };
+    } catch {
+      const detail = proc.error?.message ?? (proc.stderr.trim() || `exit status ${proc.status}`);
+      return { ok: false, issues: [{ code: 'runner.error', path: '(root)', message: `Validator failed: ${detail}` }] };
+    }
+  };
+}
+
+/**
+ * Entries of a corpus directory, sorted by name
+ */
+export function listCorpus(corpusDir: string): string[] {
+  return fs.readdirSync(corpusDir)
+    .filter(name => !name.startsWith('.'))
+    .sort()
+    .map(name => path.join(corpusDir, name));
+}
+
+/**
+ * Run every corpus entry through both versions and report entries whose results differ
+ */
+export function differential(corpus: string[], left: Runner, right: Runner): DifferentialReport {
+  const changes: DifferentialChange[] = [];
+
+  for (const target of corpus) {
+    const l = left(target);
+    const r = right(target);
+    const leftKeys = new Set(l.issues.map(issueKey));
+    const rightKeys = new Set(r.issues.map(issueKey));
+    const added = r.issues.filter(i => !leftKeys.has(issueKey(i)));
+    const removed = l.issues.filter(i => !rightKeys.has(issueKey(i)));
+
+    if (l.ok !== r.ok || added.length > 0 || removed.length > 0) {
+      changes.push({ target, verdictChanged: l.ok !== r.ok, left: l, right: r, added, removed });
+    }
+  }
+
+  return {
+    total: corpus.length,
+    verdictChanges: changes.filter(c => c.verdictChanged).length,
+    changes,
+  };
+}
@@ -0,0 +1,66 @@
+// src/digest.ts
+// Digest algorithms used by checksum rules
+
+import crypto from 'node:crypto';
+
+/**
+ * Digest implementation - hashes bytes into a lowercase hex string
+ */
+export interface Digest {
+  /** Algorithm name as written in specs (e.g. "sha256") */
+  readonly name: string;
+  hash(data: Uint8Array): string;
+}
+
+/** Algorithms approved by FIPS 180-4 */
+export const FIPS_DIGESTS: readonly string[] = ['sha256', 'sha384', 'sha512'];
+
+/** Algorithm used when a checksum rule does not name one */
+export const DEFAULT_DIGEST = 'sha256';
+
+const registry = new Map<string, Digest>();
+let fipsOnly = false;
+
+function nodeDigest(name: string): Digest {
+  return {
+    name,
+    hash: (data) => crypto.createHash(name).update(data).digest('hex'),
+  };
+}
+
+for (const name of FIPS_DIGESTS) {
+  registry.set(name, nodeDigest(name));
+}
+
+/**
+ * Register a digest implementation, e.g. BLAKE3 from a native package:
+ *   registerDigest({ name: 'blake3', hash: (data) => blake3.hash(data).toString('hex') })
+ */
+export function registerDigest(digest: Digest): void {
+  registry.set(digest.name.toLowerCase(), digest);
+}
+
+/**
+ * Restrict checksum rules to FIPS-approved algorithms
+ */
+export function setFipsOnly(enabled: boolean): void {
+  fipsOnly = enabled;
+}
+
+/**
+ * Look up a digest by name, honoring FIPS-only mode
+ */
+export function getDigest(name: string): Digest | undefined {
+  const key = name.toLowerCase();
+  if (fipsOnly && !FIPS_DIGESTS.includes(key)) {
+    return undefined;
+  }
+  return registry.get(key);
+}
+
+/**
+ * Names of the digests currently available
+ */
+export function availableDigests(): string[] {
+  return Array.from(registry.keys()).filter(name => !fipsOnly || FIPS_DIGESTS.includes(name));
+}
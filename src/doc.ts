@@ -15,10 +15,26 @@ function isSimpleType(desc: TypeDescription): boolean {
   if (desc.oneOf && desc.oneOf.some(opt => !isSimpleType(opt))) {
     return false;
   }
+  // TaggedUnion with structured variants = not simple
+  if (desc.variants && Object.values(desc.variants).some(v => !isSimpleType(v))) {
+    return false;
+  }
+  // Not with complex inner type = not simple
+  if (desc.not && !isSimpleType(desc.not)) {
+    return false;
+  }
+  // AllOf with complex parts = not simple
+  if (desc.allOf && desc.allOf.some(part => !isSimpleType(part))) {
+    return false;
+  }
   // ListOf with complex item = not simple
   if (desc.itemType && !isSimpleType(desc.itemType)) {
     return false;
   }
+  // When always lists its branches
+  if (desc.condition) {
+    return false;
+  }
   return true;
 }
 
@@ -26,12 +42,21 @@ function isSimpleType(desc: TypeDescription): boolean {
  * Format a simple type as inline text
  */
 function formatInline(desc: TypeDescription): string {
+  if (desc.nullable) {
+    return `${formatInline({ ...desc, nullable: false })} | null`;
+  }
+
   // Literal values
   if (desc.name === 'Literal') {
     const val = desc.constraints?.[0]?.replace('equals ', '') ?? 'literal';
     return `\`${val}\``;
   }
 
+  // Recursive use of a named type
+  if (desc.name === 'Ref' && desc.ref !== undefined) {
+    return desc.ref;
+  }
+
   // Pattern (regex)
   if (desc.name === 'Pattern') {
     return desc.constraints?.[0] ?? 'pattern';
@@ -43,6 +68,22 @@ function formatInline(desc: TypeDescription): string {
     return options.join(' | ');
   }
 
+  // TaggedUnion with simple variants
+  if (desc.variants) {
+    const tags = Object.keys(desc.variants).map(tag => `\`${JSON.stringify(tag)}\``);
+    return `${desc.discriminator}: ${tags.join(' | ')}`;
+  }
+
+  // Not with simple inner type
+  if (desc.not) {
+    return `not ${formatInline(desc.not)}`;
+  }
+
+  // AllOf with simple parts
+  if (desc.allOf) {
+    return desc.allOf.map(formatInline).join(' & ');
+  }
+
   // ListOf
   if (desc.itemType) {
     const itemStr = formatInline(desc.itemType);
@@ -74,13 +115,17 @@ function renderType(desc: TypeDescription, indent: number = 0, depth: number = 1
 
   // Determine the display name
   const displayName = desc.key ?? desc.name;
-  const optional = desc.optional ? ' *(optional)*' : '';
+  const hasDefault = desc.name === 'Field' && desc.default !== undefined && !desc.constraints?.some(c => c.startsWith('default: '));
+  const markers = (desc.optional ? ' *(optional)*' : '')
+    + (hasDefault ? ` *(default: \`${JSON.stringify(desc.default)}\`)*` : '')
+    + (desc.deprecated ? ' *(deprecated)*' : '')
+    + (desc.phase !== undefined ? ` *(phase ${desc.phase})*` : '');
 
   // Simple type: render inline
   if (isSimpleType(desc)) {
     if (desc.key) {
       const descText = desc.description ? ` — ${desc.description}` : '';
-      lines.push(`${prefix}- **\`${displayName}\`**${optional}: ${formatInline(desc)}${descText}`);
+      lines.push(`${prefix}- **\`${displayName}\`**${markers}: ${formatInline(desc)}${descText}`);
     } else if (useHeadings && depth === 1) {
       lines.push(`${'#'.repeat(depth)} ${displayName}`);
       lines.push('');
@@ -100,6 +145,10 @@ function renderType(desc: TypeDescription, indent: number = 0, depth: number = 1
 
   // Complex type: render with structure
 
+  if (desc.condition) {
+    return renderWhen(desc, effectiveIndent, depth);
+  }
+
   // Header for complex types
   if (desc.key) {
     let typeLabel = '';
@@ -115,10 +164,16 @@ function renderType(desc: TypeDescription, indent: number = 0, depth: number = 1
       typeLabel = '数组';
     } else if (desc.name === 'OneOf') {
       typeLabel = '多选一';
+    } else if (desc.name === 'AllOf') {
+      typeLabel = '全部满足';
+    } else if (desc.name === 'Not') {
+      typeLabel = '不得满足';
+    } else if (desc.name === 'TaggedUnion') {
+      typeLabel = `按 \`${desc.discriminator}\` 区分`;
     }
 
     if (useHeadings) {
-      lines.push(`${'#'.repeat(Math.min(depth, 5))} \`${displayName}\`${optional}`);
+      lines.push(`${'#'.repeat(Math.min(depth, 5))} \`${displayName}\`${markers}`);
       lines.push('');
       if (desc.description) {
         lines.push(desc.description);
@@ -130,7 +185,7 @@ function renderType(desc: TypeDescription, indent: number = 0, depth: number = 1
       }
     } else {
       const descText = desc.description ? ` — ${desc.description}` : '';
-      lines.push(`${prefix}- **\`${displayName}\`**${optional}${typeLabel ? ': ' + typeLabel : ''}${descText}`);
+      lines.push(`${prefix}- **\`${displayName}\`**${markers}${typeLabel ? ': ' + typeLabel : ''}${descText}`);
     }
   } else if (useHeadings && depth === 1) {
     // Root type without key
@@ -163,6 +218,31 @@ function renderType(desc: TypeDescription, indent: number = 0, depth: number = 1
     lines.push(...renderOneOf(desc.oneOf, effectiveIndent, depth));
   }
 
+  // AllOf parts
+  if (desc.allOf && desc.allOf.length > 0) {
+    lines.push(...renderAllOf(desc.allOf, effectiveIndent, depth));
+  }
+
+  // TaggedUnion variants, labelled by tag
+  if (desc.variants) {
+    const variants = Object.entries(desc.variants).map(
+      ([tag, variant]) => ({ ...variant, summary: `${desc.discriminator}=${JSON.stringify(tag)}` })
+    );
+    lines.push(...renderOneOf(variants, effectiveIndent, depth));
+  }
+
+  // Not: excluded structure
+  if (desc.not) {
+    if (useHeadings) {
+      lines.push('**不得满足:**');
+      lines.push('');
+      lines.push(...renderType(desc.not, 0, depth + 1));
+    } else {
+      lines.push(`${prefix}  - **不得满足:**`);
+      lines.push(...renderType(desc.not, effectiveIndent + 2, depth + 1));
+    }
+  }
+
   // ListOf item type
   if (desc.itemType) {
     lines.push(...renderListOf(desc.itemType, effectiveIndent, depth));
@@ -176,6 +256,23 @@ function renderType(desc: TypeDescription, indent: number = 0, depth: number = 1
   return lines;
 }
 
+/**
+ * Render When: the condition, then the content of each branch
+ */
+function renderWhen(desc: TypeDescription, indent: number, depth: number): string[] {
+  const prefix = '  '.repeat(indent);
+  const { file, field, is } = desc.condition!;
+  const subject = field !== undefined ? `\`${file}\` 中的 \`${field}\`` : `\`${file}\``;
+  const test = is ? `为 ${formatInline(is)}` : '存在';
+  const lines = [`${prefix}- **当 ${subject} ${test} 时**${desc.description ? ` — ${desc.description}` : ''}`];
+  for (const [label, branch] of [['则', desc.then], ['否则', desc.else]] as const) {
+    if (!branch) continue;
+    lines.push(`${prefix}  - **${label}:**`);
+    lines.push(...(branch.children ? renderChildren(branch.children, indent + 1, depth + 1) : renderType(branch, indent + 2, depth + 1)));
+  }
+  return lines;
+}
+
 /**
  * Render OneOf options
  */
@@ -225,6 +322,35 @@ function renderOneOf(options: TypeDescription[], indent: number, depth: number):
   return lines;
 }
 
+/**
+ * Render AllOf parts - object parts are merged into one field list
+ */
+function renderAllOf(parts: TypeDescription[], indent: number, depth: number): string[] {
+  const lines: string[] = [];
+  const prefix = '  '.repeat(indent);
+
+  const simple = parts.filter(part => !part.children);
+  if (simple.length > 0) {
+    const partsStr = simple.map(formatInline).join(' & ');
+    if (indent > 0) {
+      lines.push(`${prefix}  - 同时满足: ${partsStr}`);
+    } else {
+      lines.push(`**同时满足:** ${partsStr}`);
+      lines.push('');
+    }
+  }
+
+  const merged = {
+    required: parts.flatMap(part => part.children?.required ?? []),
+    optional: parts.flatMap(part => part.children?.optional ?? []),
+  };
+  if (merged.required.length > 0 || merged.optional.length > 0) {
+    lines.push(...renderChildren(merged, indent, depth + 1));
+  }
+
+  return lines;
+}
+
 /**
  * Render ListOf item
  */
@@ -2,9 +2,18 @@
 // SpecEngine - VM sandbox for running spec files
 
 import fs from 'node:fs';
+import path from 'node:path';
 import vm from 'node:vm';
-import { ValidationContext, type Issue } from './context.js';
-import { Type, Modifier, isType, isModifier } from './base.js';
+import { ValidationContext, type Issue, type ValidationOptions } from './context.js';
+import { Type, Modifier, isType, isModifier, validateAny, type TypeDescription } from './base.js';
+import type { RedlineDifference } from './redline.js';
+import { compatReport, type CompatReport } from './compat.js';
+import { normalizeValue, type NormalizeOptions } from './normalize.js';
+import { sampleValue, writeSample, type SampleOptions } from './sample.js';
+import type { UploadSession, UploadOptions } from './upload.js';
+import { Schema, SchemaError } from './schema.js';
+import { getDigest } from './digest.js';
+import { FsCache, withFsCache } from './fscache.js';
 
 // Import all built-in types and modifiers
 import * as primitives from './types/primitives.js';
@@ -14,11 +23,78 @@ import * as modifiers from './modifiers/index.js';
 export interface ValidationResult {
   ok: boolean;
   issues: Issue[];
+  /** Set when some items were skipped by sampling, so a pass is not conclusive */
+  partial?: boolean;
+}
+
+/** One spec's share of a multi-spec validation */
+export interface SpecResult extends ValidationResult {
+  /** The spec, as given to runAll() / validateAll() */
+  spec: string;
+  /** Set when the spec was not run because an earlier one failed (stopOnFailure) */
+  skipped?: boolean;
+}
+
+export interface MultiValidationResult {
+  /** True when every spec ran and passed */
+  ok: boolean;
+  /** One entry per spec, in the order they were given */
+  results: SpecResult[];
+}
+
+export interface MultiValidationOptions {
+  /** Skip the remaining specs once one fails (e.g. no policy checks on a malformed bundle) */
+  stopOnFailure?: boolean;
+}
+
+export interface NormalizeResult extends ValidationResult {
+  /** Copy of the input with defaults filled in */
+  value: unknown;
+}
+
+export interface SampleResult extends ValidationResult {
+  /** The generated value; issues report whatever in it the spec still rejects */
+  value: unknown;
+}
+
+/** Seeds tried by sample() before returning a value the spec rejects */
+const SAMPLE_ATTEMPTS = 10;
+
+export interface CompareResult {
+  /** True when no spec-relevant differences were found */
+  identical: boolean;
+  left: ValidationResult;
+  right: ValidationResult;
+  differences: RedlineDifference[];
 }
 
 export interface EngineOptions {
   /** Custom types to register */
   types?: Record<string, unknown>;
+  /**
+   * Validate only the sampled items of `ListOf(T, { sample })` arrays (default: true).
+   * Offline runs pass false so every item is validated, as `--full` does.
+   */
+  sampling?: boolean;
+}
+
+/**
+ * Error while resolving Import() in a spec file, with the issue code to report
+ */
+class SpecImportError extends Error {
+  constructor(readonly code: string, message: string) {
+    super(message);
+  }
+}
+
+/** Import state shared by one spec evaluation and everything it imports */
+interface ImportState {
+  /** Absolute paths of the files currently being evaluated, outermost first */
+  stack: string[];
+  /** Exports of files already imported, by absolute path */
+  cache: Map<string, Record<string, unknown>>;
+  /** Absolute paths of every file Import() asked for, including ones that failed to load */
+  files: Set<string>;
 }
 
 /**
@@ -31,13 +107,18 @@ export interface EngineOptions {
  */
 export class SpecEngine {
   private readonly globals: Record<string, unknown> = {};
+  /** Options of every validation run by this engine */
+  readonly validationOptions: ValidationOptions;
 
   constructor(options?: EngineOptions) {
+    this.validationOptions = { sampling: options?.sampling ?? true };
+
     // Register built-in primitives
     this.register({
       Str: primitives.Str,
       Bool: primitives.Bool,
       Num: primitives.Num,
+      Decimal: primitives.Decimal,
     });
 
     // Register built-in structural types
@@ -46,12 +127,24 @@ export class SpecEngine {
       File: structural.File,
       Directory: structural.Directory,
       JsonFile: structural.JsonFile,
+      When: structural.When,
     });
 
     // Register built-in modifiers
     this.register({
       OneOf: modifiers.OneOf,
+      AllOf: modifiers.AllOf,
+      Not: modifiers.Not,
+      TaggedUnion: modifiers.TaggedUnion,
       ListOf: modifiers.ListOf,
+      Nullable: modifiers.Nullable,
+      Def: modifiers.Def,
+      Meta: modifiers.Meta,
+      Extend: modifiers.Extend,
+      Generic: modifiers.Generic,
+      Message: modifiers.Message,
+      Deprecated: modifiers.Deprecated,
+      Phase: modifiers.Phase,
     });
 
     // Register custom types
@@ -68,87 +161,309 @@ export class SpecEngine {
   }
 
   /**
-   * Run a spec file against a target path
+   * Names of the types and modifiers available in spec files (Import() aside)
+   */
+  typeNames(): string[] {
+    return Object.keys(this.globals);
+  }
+
+  /**
+   * Run a spec file against a target path.
+   * Spec errors are reported as spec.* issues rather than thrown.
    */
   run(specPath: string, targetPath: string): ValidationResult {
-    const specCode = fs.readFileSync(specPath, 'utf-8');
-    let rootType: Type | Modifier | null = null;
+    let schema: Schema;
+    try {
+      schema = this.compile(specPath);
+    } catch (err) {
+      if (err instanceof SchemaError) {
+        return { ok: false, issues: err.issues };
+      }
+      throw err;
+    }
+    return schema.validate(targetPath);
+  }
 
-    // Create sandbox context with all globals
-    // The last top-level expression that produces a Type/Modifier becomes root
-    const sandbox = this.createSandbox((result) => {
-      rootType = result;
+  /**
+   * Run several spec files against one target, in order.
+   * Each spec's result is reported separately; a spec that fails to compile
+   * fails on its own without stopping the others.
+   *
+   * @example
+   * engine.runAll(['format.spec.js', 'store-policy.spec.js', 'tenant.spec.js'], './bundle');
+   */
+  runAll(specPaths: string[], targetPath: string, options?: MultiValidationOptions): MultiValidationResult {
+    const specs = specPaths.map((spec): { spec: string; schema: Schema | SchemaError } => {
+      try {
+        return { spec, schema: this.compile(spec) };
+      } catch (err) {
+        if (err instanceof SchemaError) {
+          return { spec, schema: err };
+        }
+        throw err;
+      }
+    });
+    return this.validateSpecs(specs, targetPath, options);
+  }
+
+  /**
+   * Validate one target against several compiled schemas, in order.
+   * The target is read from disk once: stats, file contents and directory
+   * listings are shared between the schemas. A SchemaError in place of a
+   * schema fails that spec with its issues, as in runAll().
+   */
+  validateAll(
+    schemas: Array<{ spec: string; schema: Schema | SchemaError }>,
+    targetPath: string,
+    options?: MultiValidationOptions
+  ): MultiValidationResult {
+    return this.validateSpecs(schemas, targetPath, options);
+  }
+
+  private validateSpecs(
+    specs: Array<{ spec: string; schema: Schema | SchemaError }>,
+    targetPath: string,
+    options?: MultiValidationOptions
+  ): MultiValidationResult {
+    const results: SpecResult[] = [];
+    withFsCache(new FsCache(), () => {
+      for (const { spec, schema } of specs) {
+        if (options?.stopOnFailure && results.some(r => !r.ok)) {
+          results.push({ spec, ok: false, issues: [], skipped: true });
+        } else if (schema instanceof SchemaError) {
+          results.push({ spec, ok: false, issues: schema.issues });
+        } else {
+          results.push({ spec, ...schema.validate(targetPath) });
+        }
+      }
     });
+    return { ok: results.every(r => r.ok), results };
+  }
 
-    // Run spec file in sandbox
+  /**
+   * Compile a spec file. Throws SchemaError when the spec cannot be used.
+   */
+  compile(specPath: string): Schema {
+    let specCode: string;
     try {
-      vm.runInContext(specCode, sandbox, { filename: specPath });
+      specCode = fs.readFileSync(specPath, 'utf-8');
     } catch (err) {
-      return {
-        ok: false,
-        issues: [{
-          level: 'error',
-          code: 'spec.syntax_error',
-          message: `Spec file error: ${(err as Error).message}`,
-          path: [],
-        }],
-      };
-    }
-
-    // Check root type was defined
-    if (!rootType) {
-      return {
-        ok: false,
-        issues: [{
-          level: 'error',
-          code: 'spec.no_root',
-          message: 'Spec file must define a root type (e.g., Directory({ ... }))',
-          path: [],
-        }],
-      };
-    }
-
-    // Validate target
-    const ctx = new ValidationContext([], targetPath);
-    // TypeScript narrowing doesn't work well across closures, use assertion
-    const root = rootType as Type | Modifier;
+      throw new SchemaError([specIssue('spec.not_found', `Cannot read spec file: ${(err as Error).message}`)]);
+    }
+    return this.compileSource(specCode, specPath);
+  }
 
+  /**
+   * Compile spec source code. Import() paths resolve relative to `filename`.
+   * Throws SchemaError when the spec cannot be used.
+   */
+  compileSource(specCode: string, filename = 'spec.js'): Schema {
+    const imports: ImportState = { stack: [], cache: new Map(), files: new Set() };
+    let root: Type | Modifier;
     try {
-      root.validate(targetPath, ctx);
+      root = this.evaluate(specCode, filename, imports);
+    } catch (err) {
+      if (err instanceof SchemaError) {
+        throw new SchemaError(err.issues, { cause: err.cause, imports: [...imports.files] });
+      }
+      throw err;
+    }
+    const issues = checkSpec(root);
+    if (issues.length > 0) {
+      throw new SchemaError(issues, { imports: [...imports.files] });
+    }
+    return new Schema(root, this, [...imports.files]);
+  }
+
+  /**
+   * Validate a target against an already loaded root type
+   */
+  validate(root: Type | Modifier, targetPath: string): ValidationResult {
+    return this.check(root, targetPath);
+  }
+
+  /**
+   * Validate a JSON value (for data specs) against an already loaded root type
+   */
+  validateValue(root: Type | Modifier, value: unknown): ValidationResult {
+    return this.check(root, value);
+  }
+
+  /**
+   * Validate a JSON value after filling in field defaults (and, with stripUnknown,
+   * dropping undeclared fields; with coerce, converting string inputs, reported as
+   * info issues). The normalized copy is returned even when invalid; the input is
+   * left untouched.
+   */
+  normalize(root: Type | Modifier, value: unknown, options?: NormalizeOptions): NormalizeResult {
+    const coercions: Issue[] = [];
+    const normalized = normalizeValue(root.describe(), value, options, coercions);
+    const result = this.check(root, normalized);
+    return { ...result, issues: [...coercions, ...result.issues], value: normalized };
+  }
+
+  /**
+   * Generate a JSON value that satisfies a spec, reproducibly from options.seed.
+   * Generation is best effort, so the value is validated; when it is rejected the next
+   * seeds are tried, and the last value is returned with its issues if none passes.
+   */
+  sample(root: Type | Modifier, options: SampleOptions = {}): SampleResult {
+    const desc = root.describe();
+    const seed = options.seed ?? 0;
+    let result: SampleResult = { ok: false, issues: [], value: undefined };
+    for (let attempt = 0; attempt < SAMPLE_ATTEMPTS && !result.ok; attempt++) {
+      const value = sampleValue(desc, { ...options, seed: seed + attempt });
+      result = { ...this.check(root, value), value };
+    }
+    return result;
+  }
+
+  /**
+   * Write a sample file tree (directories, JSON files, archives) for a file system
+   * spec under targetPath, reproducibly from options.seed, and validate it.
+   */
+  writeSample(root: Type | Modifier, targetPath: string, options?: SampleOptions): ValidationResult {
+    writeSample(root.describe(), targetPath, options);
+    return this.check(root, targetPath);
+  }
+
+  private check(root: Type | Modifier, target: unknown): ValidationResult {
+    const ctx = new ValidationContext([], target, undefined, undefined, this.validationOptions);
+
+    try {
+      modifiers.validateInPhases(ctx, () => validateAny(root, target, ctx));
     } catch (err) {
       ctx.addIssue('engine.error', `Validation error: ${(err as Error).message}`);
     }
 
-    return {
+    const result: ValidationResult = {
       ok: ctx.issues.filter(i => i.level === 'error').length === 0,
       issues: ctx.issues,
     };
+    if (ctx.partial) {
+      result.partial = true;
+    }
+    return result;
   }
 
   /**
-   * Parse a spec file and return the root type (for documentation generation)
+   * Validate two targets against the same spec and compare their spec-relevant content.
+   * Throws SchemaError when the spec cannot be used.
+   */
+  compare(specPath: string, leftPath: string, rightPath: string): CompareResult {
+    return this.compile(specPath).compare(leftPath, rightPath);
+  }
+
+  /**
+   * Classify the changes from one spec version to the next as breaking or compatible.
+   * Throws SchemaError when either spec cannot be used.
+   */
+  compat(oldSpecPath: string, newSpecPath: string): CompatReport {
+    return compatReport(this.compile(oldSpecPath).describe(), this.compile(newSpecPath).describe());
+  }
+
+  /**
+   * Start an incremental upload validated against a spec file.
+   * Throws SchemaError when the spec cannot be used.
+   */
+  upload(specPath: string, options?: UploadOptions): UploadSession {
+    return this.compile(specPath).upload(options);
+  }
+
+  /**
+   * Parse a spec file and return the root type (for documentation generation).
+   * Unlike compile(), only evaluation errors are checked.
    */
   parseSpec(specPath: string): Type | Modifier | null {
-    const specCode = fs.readFileSync(specPath, 'utf-8');
+    try {
+      return this.evaluate(fs.readFileSync(specPath, 'utf-8'), specPath);
+    } catch {
+      return null;
+    }
+  }
+
+  /**
+   * Run spec code in a sandbox and return its root type.
+   * The last top-level expression that produces a Type/Modifier becomes root.
+   */
+  private evaluate(specCode: string, filename: string, imports?: ImportState): Type | Modifier {
     let rootType: Type | Modifier | null = null;
 
     const sandbox = this.createSandbox((result) => {
       rootType = result;
-    });
+    }, filename, imports);
 
     try {
-      vm.runInContext(specCode, sandbox, { filename: specPath });
-    } catch {
-      return null;
+      vm.runInContext(specCode, sandbox, { filename });
+    } catch (err) {
+      const error = err as Error;
+      if (err instanceof SpecImportError) {
+        throw new SchemaError([specIssue(err.code, `Spec file error: ${error.message}`)], { cause: err });
+      }
+      // Errors thrown inside the sandbox come from its own realm, so compare names
+      if (error.name === 'ReferenceError') {
+        throw new SchemaError([specIssue(
+          'spec.unknown_type',
+          `Spec file error: ${error.message} (custom types must be registered with engine.register())`
+        )], { cause: err });
+      }
+      throw new SchemaError([specIssue('spec.syntax_error', `Spec file error: ${error.message}`)], { cause: err });
     }
 
-    return rootType;
+    if (!rootType) {
+      throw new SchemaError([specIssue('spec.no_root', 'Spec file must define a root type (e.g., Directory({ ... }))')]);
+    }
+    // TypeScript narrowing doesn't work well across closures, use assertion
+    return rootType as Type | Modifier;
   }
 
   /**
-   * Create a VM sandbox with all registered globals
+   * Evaluate an imported spec file and return its exports: every Def() it
+   * creates, by name, plus anything it assigns to `exports`.
+   * Each file is evaluated once per top-level evaluation.
    */
-  private createSandbox(onRoot: (root: Type | Modifier) => void): vm.Context {
+  private importSpec(specPath: string, imports: ImportState): Record<string, unknown> {
+    imports.files.add(specPath);
+    if (imports.stack.includes(specPath)) {
+      const chain = [...imports.stack.slice(imports.stack.indexOf(specPath)), specPath];
+      throw new SpecImportError('spec.import_cycle', `Import cycle: ${chain.map(p => path.basename(p)).join(' -> ')}`);
+    }
+    const cached = imports.cache.get(specPath);
+    if (cached) {
+      return cached;
+    }
+    if (!fs.existsSync(specPath)) {
+      throw new SpecImportError('spec.import_not_found', `Cannot import ${specPath}: file not found`);
+    }
+
+    const exports: Record<string, unknown> = {};
+    const sandbox = this.createSandbox((result) => {
+      if (result instanceof modifiers.DefModifier) {
+        exports[result.defName] = result;
+      }
+    }, specPath, imports);
+
+    try {
+      vm.runInContext(fs.readFileSync(specPath, 'utf-8'), sandbox, { filename: specPath });
+    } catch (err) {
+      if (err instanceof SpecImportError) throw err;
+      throw new SpecImportError('spec.syntax_error', `${path.basename(specPath)}: ${(err as Error).message}`);
+    }
+
+    Object.assign(exports, sandbox['exports']);
+    imports.cache.set(specPath, exports);
+    return exports;
+  }
+
+  /**
+   * Create a VM sandbox with all registered globals.
+   * Import() paths resolve relative to `filename`.
+   */
+  private createSandbox(
+    onRoot: (root: Type | Modifier) => void,
+    filename: string,
+    imports: ImportState = { stack: [], cache: new Map(), files: new Set() }
+  ): vm.Context {
     const wrappedGlobals: Record<string, unknown> = {};
 
     // Wrap each global to capture root
@@ -168,13 +483,65 @@ export class SpecEngine {
       }
     }
 
+    const importer = path.resolve(filename);
     return vm.createContext({
       ...wrappedGlobals,
+      Import: (request: string) => this.importSpec(
+        path.resolve(path.dirname(importer), request),
+        { ...imports, stack: [...imports.stack, importer] }
+      ),
+      exports: {},
       console, // Allow console for debugging
     });
   }
 }
 
+function specIssue(code: string, message: string): Issue {
+  return { level: 'error', code, message, path: [] };
+}
+
+/**
+ * Checks that need the whole spec, run once at compile time
+ */
+function checkSpec(root: Type | Modifier): Issue[] {
+  let desc: TypeDescription;
+  try {
+    desc = root.describe();
+  } catch (err) {
+    return [specIssue('spec.invalid', `Spec is invalid: ${(err as Error).message}`)];
+  }
+
+  const issues: Issue[] = [];
+  const visit = (d: TypeDescription): void => {
+    if (d.name === 'Field' && d.default !== undefined && !d.optional) {
+      issues.push(specIssue(
+        'spec.invalid_default',
+        `Field "${d.key}" has a default but is required; defaults only apply to optional fields`
+      ));
+    }
+    if (d.checksum) {
+      const { algorithm } = d.checksum;
+      if (!getDigest(algorithm)) {
+        issues.push(specIssue(
+          'spec.unsupported_algorithm',
+          `Digest algorithm not available: ${algorithm}${d.filePath ? ` (${d.filePath})` : ''}`
+        ));
+      }
+    }
+    for (const child of [...(d.children?.required ?? []), ...(d.children?.optional ?? [])]) visit(child);
+    for (const option of d.oneOf ?? []) visit(option);
+    for (const part of d.allOf ?? []) visit(part);
+    for (const variant of Object.values(d.variants ?? {})) visit(variant);
+    if (d.not) visit(d.not);
+    if (d.itemType) visit(d.itemType);
+    if (d.condition?.is) visit(d.condition.is);
+    if (d.then) visit(d.then);
+    if (d.else) visit(d.else);
+  };
+  visit(desc);
+  return issues;
+}
+
 /**
  * Create a new SpecEngine with default configuration
  */
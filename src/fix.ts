@@ -0,0 +1,188 @@
+// src/fix.ts
+// Fix application - apply the JSON Patch suggestions attached to issues
+
+import type { Issue, JsonPatchOperation } from './context.js';
+
+export interface FixResult {
+  /** The patched document (the input is left untouched) */
+  value: unknown;
+  /** Issues whose fix was applied */
+  applied: Issue[];
+  /** Issues whose fix could not be applied (e.g. an earlier fix changed the target) */
+  skipped: Issue[];
+}
+
+/**
+ * Convert issue path segments into a JSON Pointer ("[0]" list segments become "0")
+ */
+export function toPointer(segments: string[]): string {
+  return segments
+    .map(segment => {
+      const index = /^\[(\d+)\]$/.exec(segment);
+      const key = index ? index[1]! : segment;
+      return '/' + key.replace(/~/g, '~0').replace(/\//g, '~1');
+    })
+    .join('');
+}
+
+function parsePointer(pointer: string): string[] {
+  if (pointer === '') return [];
+  if (!pointer.startsWith('/')) {
+    throw new Error(`Invalid JSON Pointer: ${pointer}`);
+  }
+  return pointer.slice(1).split('/').map(s => s.replace(/~1/g, '/').replace(/~0/g, '~'));
+}
+
+function isContainer(value: unknown): value is Record<string, unknown> | unknown[] {
+  return value !== null && typeof value === 'object';
+}
+
+function listIndex(list: unknown[], token: string, allowEnd: boolean): number {
+  if (allowEnd && token === '-') return list.length;
+  const index = /^(0|[1-9]\d*)$/.test(token) ? Number(token) : NaN;
+  if (Number.isNaN(index) || index > list.length || (!allowEnd && index === list.length)) {
+    throw new Error(`Invalid list index: ${token}`);
+  }
+  return index;
+}
+
+/** Resolve the parent container and last token of a pointer */
+function locate(doc: unknown, pointer: string): [Record<string, unknown> | unknown[], string] {
+  const tokens = parsePointer(pointer);
+  const last = tokens.pop();
+  if (last === undefined) {
+    throw new Error('Operation cannot target the document root');
+  }
+  let current = doc;
+  for (const token of tokens) {
+    if (Array.isArray(current)) {
+      current = current[listIndex(current, token, false)];
+    } else if (isContainer(current) && Object.hasOwn(current, token)) {
+      current = current[token];
+    } else {
+      throw new Error(`Path not found: ${pointer}`);
+    }
+  }
+  if (!isContainer(current)) {
+    throw new Error(`Path not found: ${pointer}`);
+  }
+  return [current, last];
+}
+
+function get(doc: unknown, pointer: string): unknown {
+  if (pointer === '') return doc;
+  const [parent, token] = locate(doc, pointer);
+  if (Array.isArray(parent)) {
+    return parent[listIndex(parent, token, false)];
+  }
+  if (!Object.hasOwn(parent, token)) {
+    throw new Error(`Path not found: ${pointer}`);
+  }
+  return parent[token];
+}
+
+function remove(doc: unknown, pointer: string): unknown {
+  const [parent, token] = locate(doc, pointer);
+  if (Array.isArray(parent)) {
+    return parent.splice(listIndex(parent, token, false), 1)[0];
+  }
+  if (!Object.hasOwn(parent, token)) {
+    throw new Error(`Path not found: ${pointer}`);
+  }
+  const removed = parent[token];
+  delete parent[token];
+  return removed;
+}
+
+/** Add a value, returning the new document (differs from doc only when the root is replaced) */
+function add(doc: unknown, pointer: string, value: unknown): unknown {
+  if (pointer === '') return value;
+  const [parent, token] = locate(doc, pointer);
+  if (Array.isArray(parent)) {
+    parent.splice(listIndex(parent, token, true), 0, value);
+  } else {
+    parent[token] = value;
+  }
+  return doc;
+}
+
+/**
+ * Apply JSON Patch operations to a document in place, returning the new document
+ */
+export function applyPatch(doc: unknown, ops: JsonPatchOperation[]): unknown {
+  let result = doc;
+  for (const op of ops) {
+    switch (op.op) {
+      case 'add':
+        result = add(result, op.path, structuredClone(op.value));
+        break;
+      case 'remove':
+        remove(result, op.path);
+        break;
+      case 'replace':
+        get(result, op.path);
+        if (op.path !== '') remove(result, op.path);
+        result = add(result, op.path, structuredClone(op.value));
+        break;
+      case 'move': {
+        if (op.path.startsWith(op.from + '/')) {
+          throw new Error(`Cannot move ${op.from} into itself`);
+        }
+        const moved = op.from === '' ? result : remove(result, op.from);
+        result = add(result, op.path, moved);
+        break;
+      }
+      case 'copy':
+        result = add(result, op.path, structuredClone(get(result, op.from)));
+        break;
+      case 'test':
+        if (JSON.stringify(get(result, op.path)) !== JSON.stringify(op.value)) {
+          throw new Error(`Test failed at ${op.path}`);
+        }
+        break;
+    }
+  }
+  return result;
+}
+
+/**
+ * Apply the fixes attached to issues to the document they were reported on.
+ *
+ * Fix pointers are relative to the issue's path; `documentPath` is the part of
+ * that path which locates the document itself (e.g. ['pkg', 'package.json'] for
+ * a JsonFile), and only issues under it are considered. Each issue's fix is
+ * applied atomically: if any operation fails, the whole fix is skipped.
+ *
+ * @example
+ * const result = engine.run('pkg.spec.js', 'pkg');
+ * const doc = JSON.parse(fs.readFileSync('pkg/package.json', 'utf-8'));
+ * const { value } = applyFixes(doc, result.issues, ['pkg', 'package.json']);
+ */
+export function applyFixes(document: unknown, issues: Issue[], documentPath: string[] = []): FixResult {
+  let value = structuredClone(document);
+  const applied: Issue[] = [];
+  const skipped: Issue[] = [];
+
+  for (const issue of issues) {
+    if (!issue.fix) continue;
+    const inDocument = documentPath.length <= issue.path.length
+      && documentPath.every((segment, i) => issue.path[i] === segment);
+    if (!inDocument) continue;
+
+    const base = toPointer(issue.path.slice(documentPath.length));
+    const ops = issue.fix.map((op): JsonPatchOperation =>
+      op.op === 'move' || op.op === 'copy'
+        ? { ...op, from: base + op.from, path: base + op.path }
+        : { ...op, path: base + op.path }
+    );
+
+    try {
+      value = applyPatch(structuredClone(value), ops);
+      applied.push(issue);
+    } catch {
+      skipped.push(issue);
+    }
+  }
+
+  return { value, applied, skipped };
+}
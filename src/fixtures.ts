@@ -0,0 +1,165 @@
+// src/fixtures.ts
+// Spec tests - run a spec against fixtures/valid and fixtures/invalid and report mismatches
+
+import fs from 'node:fs';
+import path from 'node:path';
+import type { ValidationResult } from './engine.js';
+import type { Schema } from './schema.js';
+import type { Issue } from './context.js';
+import { validateInput } from './batch.js';
+import { CoverageRecorder, type CoverageReport } from './coverage.js';
+
+/** An issue an expected-issue file asks for */
+export interface ExpectedIssue {
+  code: string;
+  /** Dotted path, "(root)" for the root (default: any path) */
+  path?: string;
+  /** Default: any level */
+  level?: Issue['level'];
+}
+
+export interface FixtureResult {
+  fixture: string;
+  /** Whether the fixture is under valid/ or invalid/ */
+  expected: 'valid' | 'invalid';
+  /** The expected-issue file, when the fixture has one */
+  expectedFile?: string;
+  /** True when the result matches the expectations */
+  ok: boolean;
+  result: ValidationResult;
+  /** Expected issues that were not reported */
+  missing: ExpectedIssue[];
+  /** Errors the fixture should not have: any error of a valid fixture, or one no expected issue covers */
+  unexpected: Issue[];
+}
+
+export interface FixtureReport {
+  /** True when every fixture matched */
+  ok: boolean;
+  total: number;
+  passed: number;
+  failed: number;
+  results: FixtureResult[];
+  /** Which spec rules the fixtures exercised, with `coverage: true` */
+  coverage?: CoverageReport;
+}
+
+export interface FixtureOptions {
+  /** Record rule coverage (see CoverageRecorder) */
+  coverage?: boolean;
+}
+
+const EXPECTED_SUFFIX = '.expected.json';
+
+/**
+ * The expected-issue file of a fixture: the fixture's name with its extension
+ * replaced (`bad-name.json` → `bad-name.expected.json`, `bundle/` → `bundle.expected.json`)
+ */
+export function expectedIssuesFile(fixture: string): string {
+  const isDir = fs.existsSync(fixture) && fs.statSync(fixture).isDirectory();
+  const base = isDir ? fixture : fixture.slice(0, fixture.length - path.extname(fixture).length);
+  return base + EXPECTED_SUFFIX;
+}
+
+function listFixtures(dir: string, recursive: boolean): string[] {
+  if (!fs.existsSync(dir)) return [];
+  return fs.readdirSync(dir, { withFileTypes: true })
+    .filter(e => !e.name.startsWith('.') && !e.name.endsWith(EXPECTED_SUFFIX))
+    .sort((a, b) => a.name.localeCompare(b.name))
+    .flatMap(e => {
+      const full = path.join(dir, e.name);
+      if (!recursive) return [full];
+      if (e.isDirectory()) return listFixtures(full, true);
+      return e.name.endsWith('.json') ? [full] : [];
+    });
+}
+
+/**
+ * Fixtures under `dir`/valid and `dir`/invalid. For data specs every JSON file,
+ * at any depth, is a fixture; for file system specs every entry directly inside
+ * (a bundle directory or archive).
+ */
+export function findFixtures(dir: string, fileSystem: boolean): Array<{ fixture: string; expected: 'valid' | 'invalid' }> {
+  return (['valid', 'invalid'] as const).flatMap(expected =>
+    listFixtures(path.join(dir, expected), !fileSystem).map(fixture => ({ fixture, expected }))
+  );
+}
+
+/**
+ * Read an expected-issue file: a JSON array of `{ code, path?, level? }`, or of
+ * codes as strings
+ */
+export function readExpectedIssues(file: string): ExpectedIssue[] {
+  const parsed = JSON.parse(fs.readFileSync(file, 'utf-8')) as unknown;
+  if (!Array.isArray(parsed)) {
+    throw new Error(`${file}: expected a JSON array of issues`);
+  }
+  return parsed.map((entry: unknown) => {
+    if (typeof entry === 'string') return { code: entry };
+    if (typeof entry === 'object' && entry !== null && typeof (entry as ExpectedIssue).code === 'string') {
+      return entry as ExpectedIssue;
+    }
+    throw new Error(`${file}: each issue needs a "code"`);
+  });
+}
+
+function dottedPath(issue: Issue): string {
+  return issue.path.length > 0 ? issue.path.join('.') : '(root)';
+}
+
+function covers(expected: ExpectedIssue, issue: Issue): boolean {
+  return expected.code === issue.code
+    && (expected.path === undefined || expected.path === dottedPath(issue))
+    && (expected.level === undefined || expected.level === issue.level);
+}
+
+/**
+ * Check one fixture's result against its expectations. Valid fixtures must pass,
+ * invalid ones must fail. With expected issues, each must be reported and every
+ * error must be one of them.
+ */
+export function checkFixture(
+  fixture: string,
+  expected: 'valid' | 'invalid',
+  result: ValidationResult,
+  expectedIssues?: ExpectedIssue[]
+): Omit<FixtureResult, 'expectedFile'> {
+  const missing = (expectedIssues ?? []).filter(e => !result.issues.some(issue => covers(e, issue)));
+  const errors = result.issues.filter(i => i.level === 'error');
+  const unexpected = expected === 'valid'
+    ? errors
+    : expectedIssues ? errors.filter(issue => !expectedIssues.some(e => covers(e, issue))) : [];
+  const verdict = expected === 'valid' ? result.ok : !result.ok;
+  return { fixture, expected, ok: verdict && missing.length === 0 && unexpected.length === 0, result, missing, unexpected };
+}
+
+/**
+ * Run a compiled spec against the fixtures under `dir` (see findFixtures()).
+ * With `coverage`, the report also tells which rules of the spec the fixtures
+ * reached and broke.
+ *
+ * @example
+ * const report = runFixtures(engine.compile('config.spec.js'), 'fixtures', { coverage: true });
+ * for (const r of report.results.filter(r => !r.ok)) console.log(r.fixture, r.missing, r.unexpected);
+ * report.coverage!.rules.filter(r => r.status === 'missed');
+ */
+export function runFixtures(schema: Schema, dir: string, options: FixtureOptions = {}): FixtureReport {
+  const fileSystem = schema.describe().fsType !== undefined;
+  const recorder = options.coverage ? new CoverageRecorder(schema.root) : undefined;
+  const results = findFixtures(dir, fileSystem).map(({ fixture, expected }): FixtureResult => {
+    const expectedFile = expectedIssuesFile(fixture);
+    const expectedIssues = fs.existsSync(expectedFile) ? readExpectedIssues(expectedFile) : undefined;
+    const result = recorder ? recorder.record(() => validateInput(schema, fixture)) : validateInput(schema, fixture);
+    const checked = checkFixture(fixture, expected, result, expectedIssues);
+    return expectedIssues ? { ...checked, expectedFile } : checked;
+  });
+  const passed = results.filter(r => r.ok).length;
+  return {
+    ok: passed === results.length,
+    total: results.length,
+    passed,
+    failed: results.length - passed,
+    results,
+    ...(recorder ? { coverage: recorder.report() } : {}),
+  };
+}
@@ -0,0 +1,489 @@
+// src/fmt.ts
+// Spec formatter - one canonical layout for spec files: indentation, option order and line wrapping
+
+import { keysFor } from './lsp.js';
+
+export interface FormatOptions {
+  /** Column lines are wrapped at (default: 100) */
+  width?: number;
+  /** Spaces per indentation level (default: 2) */
+  indent?: number;
+}
+
+// ═══════════════════════════════════════════════════════════════
+// Tokens
+// ═══════════════════════════════════════════════════════════════
+
+interface Token {
+  kind: 'word' | 'number' | 'string' | 'template' | 'regex' | 'punct' | 'comment';
+  text: string;
+  /** Line breaks between the previous token and this one */
+  newlines: number;
+  line: number;
+}
+
+const PUNCTUATORS = [
+  '>>>=', '...', '===', '!==', '**=', '<<=', '>>=', '>>>', '&&=', '||=', '??=',
+  '=>', '==', '!=', '<=', '>=', '&&', '||', '??', '?.', '++', '--', '+=', '-=', '*=', '/=', '%=', '&=', '|=', '^=', '**', '<<', '>>',
+];
+
+// Words a statement or expression continues after; a `/` following them starts a regex
+const KEYWORDS = new Set([
+  'async', 'await', 'case', 'catch', 'const', 'delete', 'do', 'else', 'export', 'extends', 'for', 'function', 'if', 'in',
+  'instanceof', 'let', 'new', 'of', 'return', 'switch', 'throw', 'typeof', 'var', 'void', 'while', 'yield',
+]);
+
+const NUMBER = /(?:0[xXoObB][\da-fA-F_]+|(?:\d[\d_]*(?:\.[\d_]*)?|\.\d[\d_]*)(?:[eE][+-]?\d+)?)n?/y;
+const WORD = /[A-Za-z_$\u00c0-\uffff][\w$\u00c0-\uffff]*/y;
+
+function tokenize(source: string): Token[] {
+  const tokens: Token[] = [];
+  let newlines = 0;
+  let line = 1;
+  let i = 0;
+  const fail = (message: string) => new Error(`${message} on line ${line}`);
+  const push = (kind: Token['kind'], end: number) => {
+    const text = source.slice(i, end);
+    tokens.push({ kind, text, newlines, line });
+    newlines = 0;
+    line += text.split('\n').length - 1;
+    i = end;
+  };
+  const match = (pattern: RegExp) => {
+    pattern.lastIndex = i;
+    return pattern.exec(source)?.[0];
+  };
+
+  while (i < source.length) {
+    const c = source[i]!;
+    if (c === '\n') {
+      newlines++;
+      line++;
+      i++;
+    } else if (/\s/.test(c)) {
+      i++;
+    } else if (source.startsWith('//', i) || (i === 0 && source.startsWith('#!'))) {
+      const end = source.indexOf('\n', i);
+      push('comment', end === -1 ? source.length : end);
+      tokens[tokens.length - 1]!.text = tokens[tokens.length - 1]!.text.trimEnd();
+    } else if (source.startsWith('/*', i)) {
+      const end = source.indexOf('*/', i + 2);
+      if (end === -1) throw fail('Unterminated comment');
+      push('comment', end + 2);
+    } else if (c === '\'' || c === '"') {
+      push('string', quotedEnd(source, i, fail));
+    } else if (c === '`') {
+      push('template', templateEnd(source, i, fail));
+    } else if (c === '/' && regexAllowed(tokens[tokens.length - 1])) {
+      push('regex', regexEnd(source, i, fail));
+    } else if (/[\d.]/.test(c) && match(NUMBER)) {
+      push('number', i + match(NUMBER)!.length);
+    } else if (match(WORD)) {
+      push('word', i + match(WORD)!.length);
+    } else {
+      const punct = PUNCTUATORS.find(p => source.startsWith(p, i)) ?? c;
+      push('punct', i + punct.length);
+    }
+  }
+  return tokens;
+}
+
+function quotedEnd(source: string, start: number, fail: (message: string) => Error): number {
+  const quote = source[start];
+  for (let i = start + 1; i < source.length; i++) {
+    if (source[i] === '\\') i++;
+    else if (source[i] === quote) return i + 1;
+    else if (source[i] === '\n') break;
+  }
+  throw fail('Unterminated string');
+}
+
+function templateEnd(source: string, start: number, fail: (message: string) => Error): number {
+  for (let i = start + 1; i < source.length; i++) {
+    if (source[i] === '\\') {
+      i++;
+    } else if (source[i] === '`') {
+      return i + 1;
+    } else if (source.startsWith('${', i)) {
+      // Skip the substitution, minding nested strings and templates
+      let depth = 0;
+      for (i += 2; i < source.length && (source[i] !== '}' || depth > 0); i++) {
+        const c = source[i]!;
+        if (c === '{') depth++;
+        else if (c === '}') depth--;
+        else if (c === '\'' || c === '"') i = quotedEnd(source, i, fail) - 1;
+        else if (c === '`') i = templateEnd(source, i, fail) - 1;
+      }
+    }
+  }
+  throw fail('Unterminated template literal');
+}
+
+function regexEnd(source: string, start: number, fail: (message: string) => Error): number {
+  let inClass = false;
+  for (let i = start + 1; i < source.length; i++) {
+    const c = source[i];
+    if (c === '\\') i++;
+    else if (c === '[') inClass = true;
+    else if (c === ']') inClass = false;
+    else if (c === '\n') break;
+    else if (c === '/' && !inClass) return i + 1 + (/^[a-z]*/.exec(source.slice(i + 1))![0].length);
+  }
+  throw fail('Unterminated regular expression');
+}
+
+// A `/` starts a regex where an operand is expected, not after one
+function regexAllowed(prev: Token | undefined): boolean {
+  if (!prev) return true;
+  if (prev.kind === 'punct') return ![')', ']', '}', '++', '--'].includes(prev.text);
+  return prev.kind === 'word' && KEYWORDS.has(prev.text);
+}
+
+// Single quotes unless the string holds one
+function tokenText(token: Token): string {
+  if (token.kind !== 'string' || token.text[0] === '\'') return token.text;
+  const inner = token.text.slice(1, -1);
+  return inner.includes('\'') ? token.text : `'${inner.replace(/\\"/g, '"')}'`;
+}
+
+// ═══════════════════════════════════════════════════════════════
+// Bracket tree
+// ═══════════════════════════════════════════════════════════════
+
+interface Group {
+  kind: 'group';
+  open: '(' | '[' | '{';
+  close: string;
+  /** A `{` holding statements (a function body or block) rather than an object literal */
+  block: boolean;
+  children: Node[];
+  newlines: number;
+}
+
+type Node = Token | Group;
+
+/** An element of a bracket group: what lies between two commas, with its comments */
+interface Item {
+  nodes: Node[];
+  leading: Token[];
+  trailing?: Token;
+  newlines: number;
+}
+
+const CLOSE = { '(': ')', '[': ']', '{': '}' } as const;
+
+function parse(tokens: Token[]): Node[] {
+  const root: Node[] = [];
+  const stack: Array<{ group?: Group; children: Node[]; line: number }> = [{ children: root, line: 1 }];
+  for (const token of tokens) {
+    const top = stack[stack.length - 1]!;
+    if (token.kind === 'punct' && (token.text === '(' || token.text === '[' || token.text === '{')) {
+      const group: Group = {
+        kind: 'group',
+        open: token.text,
+        close: CLOSE[token.text],
+        block: token.text === '{' && opensBlock(top.group, top.children),
+        children: [],
+        newlines: token.newlines,
+      };
+      top.children.push(group);
+      stack.push({ group, children: group.children, line: token.line });
+    } else if (token.kind === 'punct' && (token.text === ')' || token.text === ']' || token.text === '}')) {
+      if (top.group?.close !== token.text) throw new Error(`Unexpected "${token.text}" on line ${token.line}`);
+      stack.pop();
+    } else {
+      top.children.push(token);
+    }
+  }
+  const open = stack[stack.length - 1]!;
+  if (open.group) throw new Error(`Unclosed "${open.group.open}" from line ${open.line}`);
+  return root;
+}
+
+function opensBlock(container: Group | undefined, siblings: Node[]): boolean {
+  const prev = [...siblings].reverse().find(n => n.kind !== 'comment');
+  if (prev?.kind === 'group') return prev.open === '(' || prev.block;
+  if (prev?.kind === 'punct' && prev.text === '=>') return true;
+  if (prev?.kind === 'word' && ['else', 'try', 'finally', 'do'].includes(prev.text)) return true;
+  const statements = container === undefined || container.block;
+  return statements && (prev === undefined || (prev.kind === 'punct' && prev.text === ';'));
+}
+
+function isPunct(node: Node | undefined, ...texts: string[]): boolean {
+  return node?.kind === 'punct' && texts.includes(node.text);
+}
+
+function splitItems(group: Group): { items: Item[]; dangling: Token[] } {
+  const items: Item[] = [];
+  let current: Item = { nodes: [], leading: [], newlines: 0 };
+  const start = (node: Node) => {
+    if (current.nodes.length === 0 && current.leading.length === 0) current.newlines = node.newlines;
+  };
+  for (const node of group.children) {
+    if (isPunct(node, ',')) {
+      items.push(current);
+      current = { nodes: [], leading: [], newlines: 0 };
+    } else if (node.kind === 'comment') {
+      const prev = items[items.length - 1];
+      if (current.nodes.length === 0 && current.leading.length === 0 && prev && !prev.trailing && node.newlines === 0) {
+        prev.trailing = node;
+      } else {
+        start(node);
+        (current.nodes.length === 0 ? current.leading : current.nodes).push(node);
+      }
+    } else {
+      start(node);
+      current.nodes.push(node);
+    }
+  }
+  if (current.nodes.length > 0) items.push(current);
+  // A comment closing the last item (no comma follows) trails it
+  for (const item of items) {
+    const last = item.nodes[item.nodes.length - 1];
+    if (last?.kind === 'comment' && !item.trailing && item.nodes.length > 1) {
+      item.trailing = last;
+      item.nodes.pop();
+    }
+  }
+  return { items, dangling: current.nodes.length === 0 ? current.leading : [] };
+}
+
+function hasComments(nodes: Node[]): boolean {
+  return nodes.some(n => n.kind === 'comment' || (n.kind === 'group' && hasComments(n.children)));
+}
+
+function hasObjects(nodes: Node[]): boolean {
+  return nodes.some(n => n.kind === 'group' && ((n.open === '{' && !n.block) || hasObjects(n.children)));
+}
+
+function itemKey(item: Item): string | undefined {
+  const [key, colon] = item.nodes;
+  if (item.nodes.length < 3 || !isPunct(colon, ':')) return undefined;
+  if (key?.kind === 'word') return key.text;
+  if (key?.kind === 'string') return key.text.slice(1, -1);
+  return undefined;
+}
+
+// Options of a known owner (see lsp.ts) in the order the LSP lists them, description last
+function reorder(items: Item[], owner: string | undefined): Item[] {
+  const keys = keysFor(owner);
+  if (!keys) return items;
+  const order = [...Object.keys(keys).filter(k => k !== 'description'), 'description'];
+  const ranked = items.map(item => ({ item, rank: order.indexOf(itemKey(item) ?? '') }));
+  if (ranked.some(r => r.rank === -1)) return items;
+  return ranked.sort((a, b) => a.rank - b.rank).map(r => r.item);
+}
+
+// ═══════════════════════════════════════════════════════════════
+// Printing
+// ═══════════════════════════════════════════════════════════════
+
+// Whether the word before a `(` or `[` is called or indexed, rather than a keyword
+function isOperand(node: Node | undefined): boolean {
+  if (!node) return false;
+  if (node.kind === 'group') return !node.block;
+  if (node.kind === 'word') return !KEYWORDS.has(node.text);
+  return node.kind === 'number' || node.kind === 'string' || node.kind === 'template' || node.kind === 'regex';
+}
+
+function isUnaryPosition(prev: Node | undefined): boolean {
+  return prev === undefined || prev.kind === 'punct' || (prev.kind === 'word' && KEYWORDS.has(prev.text));
+}
+
+function needsSpace(prev: Node | undefined, a: Node, b: Node, propertyColon: boolean): boolean {
+  if (b.kind === 'comment') return true;
+  if (isPunct(a, '.', '?.', '...', '!', '~')) return false;
+  if (isPunct(b, '.', '?.', ',', ';')) return false;
+  if (propertyColon && isPunct(b, ':')) return false;
+  if (isPunct(b, '++', '--') && isOperand(a)) return false;
+  if (isPunct(a, '++', '--', '-', '+') && isUnaryPosition(prev)) return false;
+  if (isOperand(a) && ((b.kind === 'group' && b.open !== '{') || b.kind === 'template')) return false;
+  return true;
+}
+
+function callee(nodes: Node[], j: number): string | undefined {
+  const node = nodes[j];
+  const prev = nodes[j - 1];
+  return node?.kind === 'group' && node.open === '(' && prev?.kind === 'word' && !KEYWORDS.has(prev.text) ? prev.text : undefined;
+}
+
+// Line breaks kept between statements: where the source had one and JavaScript ends a statement there
+function breaksBetween(a: Node, b: Node): boolean {
+  if (a.kind === 'comment' && a.text.startsWith('//')) return true;
+  if (b.newlines === 0) return false;
+  if (b.kind === 'comment' || a.kind === 'comment' || isPunct(a, ';')) return true;
+  if (a.kind === 'group' && a.block) return !(b.kind === 'word' && ['else', 'catch', 'finally', 'while'].includes(b.text));
+  const ends = isOperand(a) || isPunct(a, '++', '--') || (a.kind === 'word' && ['return', 'break', 'continue'].includes(a.text));
+  const starts = b.kind === 'word' || b.kind === 'number' || b.kind === 'string' || (b.kind === 'group' && b.open === '{') || isPunct(b, '++', '--', '!', '~');
+  return ends && starts;
+}
+
+function lastColumn(text: string, column: number): number {
+  const newline = text.lastIndexOf('\n');
+  return newline === -1 ? column + text.length : text.length - newline - 1;
+}
+
+class Printer {
+  constructor(private readonly width: number, private readonly unit: number) {}
+
+  private pad(indent: number): string {
+    return ' '.repeat(indent);
+  }
+
+  /** Statements, one per line as in the source, with at most one blank line between */
+  body(nodes: Node[], indent: number): string {
+    let out = this.pad(indent);
+    let column = indent;
+    nodes.forEach((node, j) => {
+      const prev = nodes[j - 1];
+      if (prev && breaksBetween(prev, node)) {
+        out += `${node.newlines > 1 ? '\n' : ''}\n${this.pad(indent)}`;
+        column = indent;
+      } else if (prev && needsSpace(nodes[j - 2], prev, node, false)) {
+        out += ' ';
+        column++;
+      }
+      const text = this.node(nodes, j, indent, column, 0, false, undefined);
+      out += text;
+      column = lastColumn(text, column);
+    });
+    return out;
+  }
+
+  /** An item's nodes, on one line unless a group inside breaks */
+  nodes(nodes: Node[], indent: number, column: number, rest: number, objectItem: boolean, owner: string | undefined): string {
+    let out = '';
+    nodes.forEach((node, j) => {
+      const prev = nodes[j - 1];
+      if (prev?.kind === 'comment' && prev.text.startsWith('//')) {
+        out += `\n${this.pad(indent)}`;
+        column = indent;
+      } else if (prev && needsSpace(nodes[j - 2], prev, node, objectItem && j === 1)) {
+        out += ' ';
+        column++;
+      }
+      const text = this.node(nodes, j, indent, column, j === nodes.length - 1 ? rest : 0, objectItem, owner);
+      out += text;
+      column = lastColumn(text, column);
+    });
+    return out;
+  }
+
+  private node(nodes: Node[], j: number, indent: number, column: number, rest: number, objectItem: boolean, owner: string | undefined): string {
+    const node = nodes[j]!;
+    if (node.kind !== 'group') return tokenText(node);
+    // The object an item's value is takes the item's owner
+    const value = nodes.length === 1 || (objectItem && j === 2 && nodes.length === 3) || (j === nodes.length - 1 && isPunct(nodes[j - 1], '=>'));
+    return this.group(node, indent, column, rest, value ? owner : undefined, callee(nodes, j));
+  }
+
+  private flat(nodes: Node[], objectItem: boolean, owner: string | undefined): string | null {
+    let out = '';
+    for (let j = 0; j < nodes.length; j++) {
+      const node = nodes[j]!;
+      if (node.kind === 'comment') return null;
+      if (j > 0 && needsSpace(nodes[j - 2], nodes[j - 1]!, node, objectItem && j === 1)) out += ' ';
+      const text = this.node(nodes, j, 0, 0, 0, objectItem, owner);
+      if (text.includes('\n')) return null;
+      out += text;
+    }
+    return out;
+  }
+
+  private itemOwners(group: Group, items: Item[], owner: string | undefined, call: string | undefined): Array<string | undefined> {
+    if (group.open === '(') return items.map((_, i) => (call !== undefined ? `${call}#${i}` : owner));
+    if (group.open === '{' && owner !== undefined) {
+      return items.map(item => {
+        const key = itemKey(item);
+        return key !== undefined ? `${owner}.${key}` : undefined;
+      });
+    }
+    return items.map(() => undefined);
+  }
+
+  private group(group: Group, indent: number, column: number, rest: number, owner: string | undefined, call: string | undefined): string {
+    if (group.block) {
+      if (group.children.length === 0) return '{}';
+      return `{\n${this.body(group.children, indent + this.unit)}\n${this.pad(indent)}}`;
+    }
+    const split = splitItems(group);
+    const object = group.open === '{';
+    const items = object ? reorder(split.items, owner) : split.items;
+    const owners = this.itemOwners(group, items, owner, call);
+    if (items.length === 0 && split.dangling.length === 0) return group.open + group.close;
+
+    // Lists of several objects (Fields, files) always take a line per item
+    const listing = group.open === '[' && items.length > 1 && hasObjects(group.children);
+    if (!hasComments(group.children) && !listing) {
+      const parts = items.map((item, i) => this.flat(item.nodes, object, owners[i]));
+      if (parts.every(p => p !== null)) {
+        const inner = parts.join(', ');
+        const text = object ? `{ ${inner} }` : `${group.open}${inner}${group.close}`;
+        if (column + text.length + rest <= this.width) return text;
+      }
+    }
+    const commented = split.dangling.length > 0 || items.some(item => item.leading.length > 0 || item.trailing);
+    if (group.open === '(' && !commented) {
+      const hugged = this.hug(items, owners, indent, column, rest);
+      if (hugged !== null) return hugged;
+    }
+
+    // Trailing commas, except in parentheses that are not a call's
+    const trailing = group.open !== '(' || call !== undefined;
+    const inner = indent + this.unit;
+    const lines: string[] = [];
+    items.forEach((item, i) => {
+      if (i > 0 && item.newlines > 1) lines.push('');
+      for (const comment of item.leading) lines.push(this.pad(inner) + comment.text);
+      const comma = i < items.length - 1 || trailing ? ',' : '';
+      const text = this.nodes(item.nodes, inner, inner, comma.length, object, owners[i]);
+      lines.push(`${this.pad(inner)}${text}${comma}${item.trailing ? ` ${item.trailing.text}` : ''}`);
+    });
+    for (const comment of split.dangling) lines.push(this.pad(inner) + comment.text);
+    return `${group.open}\n${lines.join('\n')}\n${this.pad(indent)}${group.close}`;
+  }
+
+  // Keep a call on the opening line when only its last argument, an object, array or arrow function, needs to break:
+  // Field({ ... }), Def('Name', { ... }), Def('Tree', self => ({ ... }))
+  private hug(items: Item[], owners: Array<string | undefined>, indent: number, column: number, rest: number): string | null {
+    const last = items[items.length - 1];
+    const target = last?.nodes[last.nodes.length - 1];
+    if (!last || target?.kind !== 'group') return null;
+    const arrow = last.nodes.length > 1;
+    if (arrow ? !isPunct(last.nodes[last.nodes.length - 2], '=>') : target.open === '(') return null;
+    const before = items.slice(0, -1).map((item, i) => this.flat(item.nodes, false, owners[i]));
+    const head = arrow ? this.flat(last.nodes.slice(0, -1), false, undefined) : '';
+    if (head === null || before.some(b => b === null)) return null;
+    const prefix = `(${before.map(b => `${b}, `).join('')}${arrow ? `${head} ` : ''}`;
+    if (column + prefix.length + 1 > this.width) return null;
+    return `${prefix}${this.group(target, indent, column + prefix.length, rest + 1, owners[items.length - 1], undefined)})`;
+  }
+}
+
+// The tokens a program is made of, apart from comments, commas and quoting; order aside, formatting keeps them
+function signature(source: string): string[] {
+  return tokenize(source).filter(t => t.kind !== 'comment' && !isPunct(t, ',')).map(tokenText).sort();
+}
+
+/**
+ * Format a spec file's source: two-space indentation, single quotes, lines
+ * wrapped at `width` with one item per line and trailing commas, calls kept on
+ * their opening line when only the last argument breaks (`Field({`), and the
+ * options of known types (Str, Field, Directory, ...) in the order their
+ * documentation lists them. Comments and the source's statement lines and
+ * blank lines are kept. Formatting formatted source returns it unchanged.
+ * Throws an Error for unbalanced brackets or unterminated strings.
+ *
+ * @example
+ * fs.writeFileSync(file, formatSpec(fs.readFileSync(file, 'utf-8')));
+ */
+export function formatSpec(source: string, options: FormatOptions = {}): string {
+  const tree = parse(tokenize(source));
+  if (tree.length === 0) return '';
+  const printer = new Printer(options.width ?? 100, options.indent ?? 2);
+  const formatted = `${printer.body(tree, 0).replace(/[ \t]+$/gm, '')}\n`;
+  if (signature(formatted).join('\n') !== signature(source).join('\n')) {
+    throw new Error('Formatting would change the spec; the source was left as it is');
+  }
+  return formatted;
+}
@@ -0,0 +1,86 @@
+// src/fscache.ts
+// FsCache - share file system reads between specs validating the same target
+
+import fs from 'node:fs';
+
+/**
+ * Memoized stat/read/readdir results for one validation pass.
+ * Failures are cached too, so every spec sees the same view of the target.
+ */
+export class FsCache {
+  private readonly stats = new Map<string, fs.Stats | Error>();
+  private readonly files = new Map<string, Buffer | Error>();
+  private readonly dirs = new Map<string, string[] | Error>();
+
+  stat(filePath: string): fs.Stats {
+    return memo(this.stats, filePath, () => fs.statSync(filePath));
+  }
+
+  readFile(filePath: string): Buffer {
+    return memo(this.files, filePath, () => fs.readFileSync(filePath));
+  }
+
+  readdir(dirPath: string): string[] {
+    return memo(this.dirs, dirPath, () => fs.readdirSync(dirPath));
+  }
+}
+
+function memo<T>(cache: Map<string, T | Error>, key: string, read: () => T): T {
+  let entry = cache.get(key);
+  if (entry === undefined) {
+    try {
+      entry = read();
+    } catch (err) {
+      entry = err as Error;
+    }
+    cache.set(key, entry);
+  }
+  if (entry instanceof Error) {
+    throw entry;
+  }
+  return entry;
+}
+
+// Validation is synchronous, so the cache in effect can be module state
+let active: FsCache | null = null;
+
+/**
+ * Run fn with every FS read made through this module served from cache
+ */
+export function withFsCache<T>(cache: FsCache, fn: () => T): T {
+  const previous = active;
+  active = cache;
+  try {
+    return fn();
+  } finally {
+    active = previous;
+  }
+}
+
+/**
+ * fs.statSync, served from the active cache if there is one
+ */
+export function statSync(filePath: string): fs.Stats {
+  return active ? active.stat(filePath) : fs.statSync(filePath);
+}
+
+/**
+ * fs.readFileSync, served from the active cache if there is one
+ */
+export function readFileSync(filePath: string): Buffer {
+  return active ? active.readFile(filePath) : fs.readFileSync(filePath);
+}
+
+/**
+ * fs.readFileSync(path, 'utf-8'), served from the active cache if there is one
+ */
+export function readTextFileSync(filePath: string): string {
+  return readFileSync(filePath).toString('utf-8');
+}
+
+/**
+ * fs.readdirSync, served from the active cache if there is one
+ */
+export function readdirSync(dirPath: string): string[] {
+  return active ? [...active.readdir(dirPath)] : fs.readdirSync(dirPath);
+}
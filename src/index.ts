@@ -2,30 +2,119 @@
 // Main entry point for @specspec/core
 
 // Base classes
-export { Type, Modifier, validateAny, tryMatch } from './base.js';
-export type { Validatable, LiteralValue, ObjectSpec, TypeDescription } from './base.js';
+export { Type, Modifier, validateAny, tryMatch, deprecationNotice } from './base.js';
+export type { Validatable, LiteralValue, ObjectSpec, TypeDescription, DeprecationSpec } from './base.js';
 
 // Context
 export { ValidationContext } from './context.js';
-export type { Context, Issue } from './context.js';
+export type { Context, Issue, JsonPatchOperation } from './context.js';
 
 // Primitive types
 export { Str, StrType, type StrSpec } from './types/primitives.js';
 export { Bool, BoolType, type BoolSpec } from './types/primitives.js';
 export { Num, NumType, type NumSpec } from './types/primitives.js';
+export { Decimal, DecimalType, type DecimalSpec } from './types/primitives.js';
 
 // Structural types
 export { Field, FieldType, type FieldSpec } from './types/structural.js';
-export { File, FileType, type FileSpec } from './types/structural.js';
-export { Directory, DirectoryType, type DirectorySpec } from './types/structural.js';
+export { File, FileType, type FileSpec, type ChecksumSpec } from './types/structural.js';
+export { Directory, DirectoryType, type DirectorySpec, type DirectoryFilesSpec } from './types/structural.js';
 export { JsonFile, JsonFileType, type JsonFileSpec } from './types/structural.js';
+export { When, WhenType, type WhenSpec } from './types/structural.js';
 
 // Modifiers
 export { OneOf, OneOfModifier } from './modifiers/oneof.js';
-export { ListOf, ListOfModifier, type ListOfSpec } from './modifiers/listof.js';
+export { AllOf, AllOfModifier } from './modifiers/allof.js';
+export { Not, NotModifier } from './modifiers/not.js';
+export { TaggedUnion, TaggedUnionModifier } from './modifiers/taggedunion.js';
+export { ListOf, ListOfModifier, type ListOfSpec, type ListSampleSpec } from './modifiers/listof.js';
+export { Nullable, NullableModifier } from './modifiers/nullable.js';
+export { Def, DefModifier, type DefBody, type DefOptions } from './modifiers/def.js';
+export { Meta, MetaModifier } from './modifiers/meta.js';
+export { Extend, ExtendModifier } from './modifiers/extend.js';
+export { Deprecated, DeprecatedModifier } from './modifiers/deprecated.js';
+export { Message, MessageModifier, type MessageSpec } from './modifiers/message.js';
+export { Generic, type GenericType, type GenericBody } from './modifiers/generic.js';
+export { Phase, PhaseModifier } from './modifiers/phase.js';
+
+// Digests
+export { registerDigest, getDigest, setFipsOnly, availableDigests, type Digest } from './digest.js';
 
 // Engine
-export { SpecEngine, createEngine, type ValidationResult, type EngineOptions } from './engine.js';
+export { SpecEngine, createEngine, type ValidationResult, type EngineOptions, type CompareResult, type NormalizeResult, type SampleResult } from './engine.js';
+export type { SpecResult, MultiValidationResult, MultiValidationOptions } from './engine.js';
+
+// Shared file system reads
+export { FsCache, withFsCache } from './fscache.js';
+
+// Incremental upload validation
+export { UploadSession, type UploadOptions, type UploadStatus } from './upload.js';
+
+// Compiled specs
+export { Schema, SchemaError } from './schema.js';
+
+// Hot-reloadable specs
+export { SchemaWatcher, fileSource, watchTree, diffIssues } from './watcher.js';
+export type { SpecSource, FileSourceOptions, SchemaWatcherOptions, WatchTreeOptions, IssueDiff } from './watcher.js';
+
+// Repair report
+export { repairReport, formatRepairReport, hasStructuralIssues, partitionStructural, type RepairReport, type RepairNode } from './repair.js';
+
+// Fix suggestions
+export { applyFixes, applyPatch, toPointer, type FixResult } from './fix.js';
+
+// Differential execution
+export { differential, normalizeResult, engineRunner, moduleRunner, commandRunner, listCorpus } from './differential.js';
+export type { Runner, RunOutcome, DifferentialChange, DifferentialReport } from './differential.js';
+
+// Redline comparison
+export { redline, snapshot, diffSnapshots, projectValue, type RedlineDifference } from './redline.js';
+
+// Defaults and normalization
+export { normalizeValue, type NormalizeOptions } from './normalize.js';
+
+// Sample data
+export { sampleValue, writeSample, type SampleOptions } from './sample.js';
+
+// Batch validation
+export { validateBatch, validateInput, validateJsonText, expandInputs, summarizeBatch } from './batch.js';
+export type { BatchOptions, BatchResult, BatchReport, IssueCodeCount } from './batch.js';
+
+// Spec tests against fixtures
+export { runFixtures, findFixtures, checkFixture, readExpectedIssues, expectedIssuesFile } from './fixtures.js';
+export type { ExpectedIssue, FixtureResult, FixtureReport, FixtureOptions } from './fixtures.js';
+export { CoverageRecorder } from './coverage.js';
+export type { CoverageRule, CoverageReport, CoverageOptions } from './coverage.js';
+
+// Corpus scoring
+export { scoreCorpus, corpusDocuments } from './score.js';
+export type { ScoreRule, ScoreReport, ScoreOptions } from './score.js';
+
+// Spec linter
+export { lintSpecs, type LintResult, type LintReport } from './lint.js';
+
+// Spec formatter
+export { formatSpec, type FormatOptions } from './fmt.js';
+
+// Project configuration
+export { parseToml, findConfig, loadConfig, applyIssuePolicy, CONFIG_FILE } from './config.js';
+export type { ProjectConfig, CodegenTarget, IssuePolicy, FailOn } from './config.js';
+
+// Spec version compatibility
+export { compatReport, type CompatReport, type CompatChange } from './compat.js';
+
+// OpenAPI import
+export { importOpenApi, type OpenApiImportOptions, type OpenApiImport } from './openapi.js';
+
+// Protobuf export
+export { exportProto, type ProtoExportOptions, type ProtoExport, type ProtoNote } from './proto.js';
+
+// Portable descriptions
+export { portableDescription } from './portable.js';
+
+// Language server
+export { SpecLanguageService, SpecLanguageServer, serveLanguageServer, messageReader, frameMessage } from './lsp.js';
+export type { Position, Range, Diagnostic, Location, CompletionItem, JsonRpcMessage } from './lsp.js';
 
 // Documentation generator
 export { generateDoc, generateMarkdown } from './doc.js';
@@ -0,0 +1,374 @@
+// src/lint.ts
+// Spec linter - problems in a spec that validation itself would never report
+
+import fs from 'node:fs';
+import { isType, isModifier, isObjectSpec, tryMatch, type Validatable, type ObjectSpec } from './base.js';
+import { ValidationContext, type Issue } from './context.js';
+import type { SpecEngine, ValidationResult } from './engine.js';
+import { SchemaError } from './schema.js';
+import { isLiteral, typeLabel } from './coverage.js';
+import { StrType, NumType, BoolType, DecimalType } from './types/primitives.js';
+import { FieldType, FileType, DirectoryType, JsonFileType } from './types/structural.js';
+import { ListOfModifier, OneOfModifier, TaggedUnionModifier, DefModifier } from './modifiers/index.js';
+
+/** One spec's findings; the spec's compile errors, when it has any, are its issues */
+export interface LintResult extends ValidationResult {
+  spec: string;
+}
+
+export interface LintReport {
+  /** True when no spec has an error-level finding */
+  ok: boolean;
+  /** One entry per spec, in the order they were given */
+  results: LintResult[];
+}
+
+function isPattern(v: unknown): v is RegExp {
+  return Object.prototype.toString.call(v) === '[object RegExp]';
+}
+
+// ═══════════════════════════════════════════════════════════════
+// Patterns
+// ═══════════════════════════════════════════════════════════════
+
+interface PatternInfo {
+  /** Characters any match consumes at least */
+  minLength: number;
+  /** No string matches */
+  never: boolean;
+}
+
+// Index of the `]` closing the class opened at `start`; `[]` is the empty class, `[^]` any character
+function classEnd(source: string, start: number): number {
+  let i = source[start + 1] === '^' ? start + 2 : start + 1;
+  while (i < source.length && source[i] !== ']') {
+    i += source[i] === '\\' ? 2 : 1;
+  }
+  return i;
+}
+
+// Index of the `)` closing the group opened at `start`
+function groupEnd(source: string, start: number): number {
+  let depth = 0;
+  for (let i = start; i < source.length; i++) {
+    const c = source[i];
+    if (c === '\\') i++;
+    else if (c === '[') i = classEnd(source, i);
+    else if (c === '(') depth++;
+    else if (c === ')' && --depth === 0) return i;
+  }
+  return source.length;
+}
+
+// The top-level alternatives of a pattern
+function alternatives(source: string): string[] {
+  const parts: string[] = [];
+  let from = 0;
+  for (let i = 0; i < source.length; i++) {
+    const c = source[i];
+    if (c === '\\') i++;
+    else if (c === '[') i = classEnd(source, i);
+    else if (c === '(') i = groupEnd(source, i);
+    else if (c === '|') {
+      parts.push(source.slice(from, i));
+      from = i + 1;
+    }
+  }
+  return [...parts, source.slice(from)];
+}
+
+function analyzeSequence(source: string, multiline: boolean): PatternInfo {
+  let minLength = 0;
+  let never = false;
+  let consumed = false; // A character must have been matched before this point
+  let ended = false; // `$` was passed
+  let i = 0;
+  while (i < source.length) {
+    const c = source[i]!;
+    let atom: PatternInfo;
+    if (c === '^' || c === '$') {
+      // Without the m flag, ^ after a character and a character after $ cannot match
+      if (!multiline && c === '^' && consumed) never = true;
+      if (c === '$') ended = true;
+      i++;
+      continue;
+    } else if (c === '\\') {
+      const e = source[i + 1] ?? '';
+      if (e === 'b' || e === 'B') {
+        i += 2;
+        continue;
+      }
+      const escape = /^\\(?:[1-9]\d*|k<[^>]*>|x[0-9a-fA-F]{2}|u\{[0-9a-fA-F]+\}|u[0-9a-fA-F]{4}|c[A-Za-z]|[pP]\{[^}]*\}|.)/.exec(source.slice(i))!;
+      // Backreferences may match the empty string
+      atom = { minLength: /^\\(?:[1-9]|k<)/.test(escape[0]) ? 0 : 1, never: false };
+      i += escape[0].length;
+    } else if (c === '[') {
+      const end = classEnd(source, i);
+      atom = { minLength: 1, never: end === i + 1 };
+      i = end + 1;
+    } else if (c === '(') {
+      const end = groupEnd(source, i);
+      const inner = source.slice(i + 1, end);
+      i = end + 1;
+      if (/^\?<?[=!]/.test(inner)) {
+        // Lookarounds consume nothing; an empty negative lookahead never matches
+        if (inner === '?!') never = true;
+        continue;
+      }
+      atom = analyzePattern(inner.replace(/^\?(?::|<[^>]*>)/, ''), multiline);
+    } else {
+      atom = { minLength: 1, never: false };
+      i++;
+    }
+
+    const quantifier = /^(?:[*+?]|\{(\d+)(?:,\d*)?\})\??/.exec(source.slice(i));
+    let times = 1;
+    if (quantifier) {
+      i += quantifier[0].length;
+      const q = quantifier[0][0];
+      times = q === '*' || q === '?' ? 0 : q === '+' ? 1 : Number(quantifier[1]);
+    }
+    if (times === 0) continue;
+    if (atom.never) never = true;
+    if (atom.minLength > 0) {
+      if (!multiline && ended) never = true;
+      consumed = true;
+      minLength += atom.minLength * times;
+    }
+  }
+  return { minLength, never };
+}
+
+/**
+ * What a pattern requires, from its source: the fewest characters a match
+ * consumes, and whether no string can match it (an empty class `[]`, `(?!)`,
+ * `^` after a character, a character after `$`). Conservative: `never` is only
+ * set when certain.
+ */
+function analyzePattern(source: string, multiline: boolean): PatternInfo {
+  const possible = alternatives(source).map(alt => analyzeSequence(alt, multiline)).filter(info => !info.never);
+  if (possible.length === 0) {
+    return { minLength: 0, never: true };
+  }
+  return { minLength: Math.min(...possible.map(info => info.minLength)), never: false };
+}
+
+// ═══════════════════════════════════════════════════════════════
+// Shadowed options
+// ═══════════════════════════════════════════════════════════════
+
+function unwrap(v: Validatable): Validatable {
+  return v instanceof DefModifier && !isObjectSpec(v.body) ? unwrap(v.body) : v;
+}
+
+const atMost = (outer: number | undefined, inner: number | undefined) => outer === undefined || (inner !== undefined && inner <= outer);
+const atLeast = (outer: number | undefined, inner: number | undefined) => outer === undefined || (inner !== undefined && inner >= outer);
+const samePattern = (a: RegExp | undefined, b: RegExp | undefined) => a === undefined || (b !== undefined && String(a) === String(b));
+
+/**
+ * Whether every value `later` accepts is accepted by `earlier` too, so a OneOf
+ * never gets past `earlier` to `later`. Literals are checked exactly; Str, Num
+ * and Bool by comparing their constraints; anything else only when identical.
+ */
+function shadows(earlier: Validatable, later: Validatable): boolean {
+  const a = unwrap(earlier);
+  const b = unwrap(later);
+  if (a === b) return true;
+  if (isPattern(b)) {
+    if (isPattern(a)) return String(a) === String(b);
+    return a instanceof StrType && !a.spec?.minLength && a.spec?.maxLength === undefined && a.spec?.match === undefined;
+  }
+  if (isLiteral(b)) {
+    return tryMatch(a, b, new ValidationContext([], b));
+  }
+  if (a instanceof StrType && b instanceof StrType) {
+    return atLeast(a.spec?.minLength ?? 0, b.spec?.minLength ?? 0)
+      && atMost(a.spec?.maxLength, b.spec?.maxLength)
+      && samePattern(a.spec?.match, b.spec?.match);
+  }
+  if (a instanceof NumType && b instanceof NumType) {
+    return atLeast(a.spec?.min, b.spec?.min) && atMost(a.spec?.max, b.spec?.max) && (!a.spec?.integer || b.spec?.integer === true);
+  }
+  return a instanceof BoolType && b instanceof BoolType;
+}
+
+// ═══════════════════════════════════════════════════════════════
+// Linter
+// ═══════════════════════════════════════════════════════════════
+
+function entryKey(item: Validatable): string | undefined {
+  const node = unwrap(item);
+  if (node instanceof FieldType) return `field "${node.spec.key}"`;
+  if (node instanceof FileType || node instanceof DirectoryType || node instanceof JsonFileType) {
+    return node.spec?.path !== undefined ? `entry "${node.spec.path}"` : undefined;
+  }
+  return undefined;
+}
+
+class SpecLinter {
+  readonly issues: Issue[] = [];
+  /** Names of the Defs the root reaches */
+  readonly reached = new Set<string>();
+  private readonly visited = new Set<object>();
+
+  private report(level: Issue['level'], code: string, message: string, path: string[]): void {
+    this.issues.push({ level, code, message, path });
+  }
+
+  walk(node: unknown, path: string[]): void {
+    if (isPattern(node)) {
+      this.checkPattern(node, path);
+      return;
+    }
+    if (node === null || typeof node !== 'object' || this.visited.has(node)) return;
+    this.visited.add(node);
+
+    if (node instanceof DefModifier) {
+      this.reached.add(node.defName);
+      this.walk(node.body, path);
+    } else if (node instanceof FieldType) {
+      this.walk(node.spec.value, [...path, node.spec.key]);
+    } else if (isObjectSpec(node)) {
+      this.checkObject(node, path);
+    } else if (node instanceof OneOfModifier) {
+      this.checkOptions(node, path);
+      node.options.forEach((option, i) => this.walk(option, [...path, `(option ${i + 1})`]));
+    } else if (node instanceof TaggedUnionModifier) {
+      for (const [tag, variant] of Object.entries(node.variants)) {
+        this.walk(variant, [...path, `(${node.discriminator}=${tag})`]);
+      }
+    } else if (node instanceof ListOfModifier) {
+      this.checkRange('items', node.spec?.min, node.spec?.max, path);
+      this.walk(node.itemType, [...path, '[]']);
+    } else if (node instanceof StrType) {
+      this.checkRange('characters', node.spec?.minLength, node.spec?.maxLength, path);
+      if (node.spec?.match) this.checkPattern(node.spec.match, path, node.spec.maxLength);
+    } else if (node instanceof NumType) {
+      const { min, max, integer } = node.spec ?? {};
+      if (!this.checkRange('', min, max, path) && integer && min !== undefined && max !== undefined && Math.ceil(min) > Math.floor(max)) {
+        this.report('error', 'lint.contradictory', `No integer lies between minimum ${min} and maximum ${max}`, path);
+      }
+    } else if (node instanceof DecimalType) {
+      const { min, max } = node.spec ?? {};
+      this.checkRange('', min === undefined ? undefined : Number(min), max === undefined ? undefined : Number(max), path);
+    } else if (node instanceof FileType || node instanceof DirectoryType || node instanceof JsonFileType) {
+      if (node instanceof DirectoryType) {
+        this.checkRange('files', node.spec?.files?.min, node.spec?.files?.max, path);
+      }
+      this.walkValues(node.spec, node.spec?.path !== undefined ? [...path, node.spec.path] : path);
+    } else {
+      // Other types and modifiers (AllOf, Not, Nullable, Meta, Extend, custom types): whatever they hold
+      this.walkValues(isType(node) ? node.spec : Object.values(node), path);
+    }
+  }
+
+  // Types, object specs and patterns anywhere inside a value
+  private walkValues(value: unknown, path: string[], seen = new Set<unknown>()): void {
+    if (isPattern(value) || isType(value) || isModifier(value) || isObjectSpec(value)) {
+      this.walk(value, path);
+    } else if (value !== null && typeof value === 'object' && !seen.has(value)) {
+      seen.add(value);
+      for (const v of Array.isArray(value) ? value : Object.values(value)) this.walkValues(v, path, seen);
+    }
+  }
+
+  private checkObject(spec: ObjectSpec, path: string[]): void {
+    const seen = new Set<string>();
+    for (const item of [...(spec.required ?? []), ...(spec.optional ?? [])]) {
+      const key = entryKey(item);
+      if (key !== undefined && seen.has(key)) {
+        this.report('warning', 'lint.duplicate_field', `The ${key} is declared more than once; every declaration is checked`, path);
+      }
+      if (key !== undefined) seen.add(key);
+    }
+    for (const item of spec.required ?? []) this.walk(item, path);
+    for (const item of spec.optional ?? []) this.walk(item, path);
+  }
+
+  private checkOptions(oneOf: OneOfModifier, path: string[]): void {
+    oneOf.options.forEach((option, j) => {
+      const i = oneOf.options.slice(0, j).findIndex(earlier => shadows(earlier, option));
+      if (i !== -1) {
+        this.report(
+          'warning',
+          'lint.shadowed_option',
+          `Option ${j + 1} (${typeLabel(option)}) is never chosen: option ${i + 1} (${typeLabel(oneOf.options[i]!)}) accepts every value it does`,
+          [...path, `(option ${j + 1})`]
+        );
+      }
+    });
+  }
+
+  // Returns true when it reported min > max
+  private checkRange(unit: string, min: number | undefined, max: number | undefined, path: string[]): boolean {
+    if (min === undefined || max === undefined || min <= max) return false;
+    const what = unit ? ` ${unit}` : '';
+    this.report('error', 'lint.contradictory', `Minimum ${min}${what} is greater than maximum ${max}${what}: nothing is accepted`, path);
+    return true;
+  }
+
+  private checkPattern(pattern: RegExp, path: string[], maxLength?: number): void {
+    const info = analyzePattern(pattern.source, pattern.flags.includes('m'));
+    if (info.never) {
+      this.report('error', 'lint.pattern_never_matches', `Pattern ${String(pattern)} cannot match any string`, path);
+    } else if (maxLength !== undefined && info.minLength > maxLength) {
+      this.report(
+        'error',
+        'lint.contradictory',
+        `Pattern ${String(pattern)} needs at least ${info.minLength} characters, but the maximum is ${maxLength}`,
+        path
+      );
+    }
+  }
+}
+
+// Def() names declared in a spec file's source, with their line
+function declaredDefs(source: string): Array<{ name: string; line: number }> {
+  return [...source.matchAll(/\bDef\(\s*(['"`])([^'"`]+)\1/g)].map(m => ({
+    name: m[2]!,
+    line: source.slice(0, m.index).split('\n').length,
+  }));
+}
+
+/**
+ * Lint spec files: Defs the root never reaches, OneOf options an earlier option
+ * shadows, contradictory constraints (minimum above maximum), patterns that
+ * cannot match, and fields or entries declared twice in one object. Specs are
+ * linted together, so a Def counts as used when any of them reaches it (lint a
+ * spec imported by others along with them). Problems that make a spec accept
+ * nothing are errors, the others warnings.
+ *
+ * @example
+ * const report = lintSpecs(new SpecEngine(), ['types.spec.js', 'order.spec.js']);
+ * for (const r of report.results) r.issues.forEach(i => console.log(r.spec, i.code, i.message));
+ */
+export function lintSpecs(engine: SpecEngine, specPaths: string[]): LintReport {
+  const linted = specPaths.map(spec => {
+    const linter = new SpecLinter();
+    try {
+      linter.walk(engine.compile(spec).root, []);
+    } catch (err) {
+      if (!(err instanceof SchemaError)) throw err;
+      return { spec, issues: err.issues };
+    }
+    return { spec, linter };
+  });
+
+  const reached = new Set(linted.flatMap(l => 'linter' in l ? [...l.linter.reached] : []));
+  const results = linted.map((l): LintResult => {
+    if (!('linter' in l)) {
+      return { spec: l.spec, ok: false, issues: l.issues };
+    }
+    const unused = declaredDefs(fs.readFileSync(l.spec, 'utf-8'))
+      .filter(def => !reached.has(def.name))
+      .map((def): Issue => ({
+        level: 'warning',
+        code: 'lint.unused_def',
+        message: `Def '${def.name}' (line ${def.line}) is never used`,
+        path: [],
+      }));
+    const issues = [...unused, ...l.linter.issues];
+    return { spec: l.spec, ok: !issues.some(i => i.level === 'error'), issues };
+  });
+  return { ok: results.every(r => r.ok), results };
+}
@@ -0,0 +1,885 @@
+// src/lsp.ts
+// Language server for spec files - diagnostics, go-to-definition, hover and completion over LSP
+
+import fs from 'node:fs';
+import path from 'node:path';
+import { fileURLToPath, pathToFileURL } from 'node:url';
+import { SpecEngine } from './engine.js';
+import { SchemaError } from './schema.js';
+import type { TypeDescription } from './base.js';
+import type { Issue } from './context.js';
+import { portableDescription } from './portable.js';
+import { fieldValue, isRef } from './codegen/base.js';
+
+/** Zero-based line and character, as LSP counts them */
+export interface Position {
+  line: number;
+  character: number;
+}
+
+export interface Range {
+  start: Position;
+  end: Position;
+}
+
+export interface Diagnostic {
+  range: Range;
+  /** LSP severity: 1 error, 2 warning, 3 information */
+  severity: 1 | 2 | 3;
+  code: string;
+  source: 'specspec';
+  message: string;
+}
+
+/** A place in a spec file */
+export interface Location {
+  file: string;
+  range: Range;
+}
+
+export interface CompletionItem {
+  label: string;
+  /** LSP CompletionItemKind: 3 function, 6 variable, 10 property */
+  kind: 3 | 6 | 10;
+  detail?: string;
+  documentation?: string;
+}
+
+// ═══════════════════════════════════════════════════════════════
+// Built-in types and their options
+// ═══════════════════════════════════════════════════════════════
+
+/** What an argument of a built-in takes: an options object, an object spec, TaggedUnion variants, or anything else */
+type ArgKind = 'options' | 'object' | 'variants' | 'other';
+
+interface Builtin {
+  signature: string;
+  doc: string;
+  args: ArgKind[];
+  /** The last argument repeats (OneOf, AllOf) */
+  variadic?: boolean;
+  /** Keys of the options argument */
+  options?: Record<string, string>;
+  /** Options that take an object of their own: its keys, or 'object' for an object spec */
+  nested?: Record<string, Record<string, string> | 'object'>;
+}
+
+const DESCRIPTION = 'Human-readable description';
+
+const OBJECT_SPEC: Record<string, string> = {
+  required: 'Fields that must be present',
+  optional: 'Fields that may be missing',
+};
+
+const BUILTINS: Record<string, Builtin> = {
+  Str: {
+    signature: 'Str(options?)',
+    doc: 'A string.',
+    args: ['options'],
+    options: {
+      description: DESCRIPTION,
+      minLength: 'Minimum length in characters',
+      maxLength: 'Maximum length in characters',
+      match: 'Regular expression the string must match',
+    },
+  },
+  Bool: {
+    signature: 'Bool(options?)',
+    doc: 'A boolean.',
+    args: ['options'],
+    options: { description: DESCRIPTION, default: 'Value filled in by normalization when missing' },
+  },
+  Num: {
+    signature: 'Num(options?)',
+    doc: 'A number.',
+    args: ['options'],
+    options: {
+      description: DESCRIPTION,
+      min: 'Inclusive lower bound',
+      max: 'Inclusive upper bound',
+      integer: 'Only accept integers',
+    },
+  },
+  Decimal: {
+    signature: 'Decimal(options?)',
+    doc: 'A decimal number, checked exactly (given as a string or a number).',
+    args: ['options'],
+    options: {
+      description: DESCRIPTION,
+      min: 'Inclusive lower bound; use a string to keep the bound exact',
+      max: 'Inclusive upper bound; use a string to keep the bound exact',
+      precision: 'Maximum number of significant digits (integer + fractional)',
+      scale: 'Maximum number of digits after the decimal point',
+    },
+  },
+  Field: {
+    signature: 'Field({ key, value?, optional?, nullable?, deprecated?, default? })',
+    doc: 'A field of an object.',
+    args: ['options'],
+    options: {
+      description: DESCRIPTION,
+      key: 'Name of the field',
+      value: 'Type of the field\'s value',
+      optional: 'The field may be missing',
+      nullable: 'Accept null in place of the value',
+      deprecated: 'Warn (field.deprecated) when the field is present; a string is the reason',
+      default: 'Value filled in by normalization when the field is missing (optional fields only)',
+    },
+    nested: {
+      value: 'object',
+      deprecated: { message: 'Reason or migration note', replacement: 'Field or type to use instead' },
+    },
+  },
+  File: {
+    signature: 'File(options?)',
+    doc: 'A file.',
+    args: ['options'],
+    options: {
+      description: DESCRIPTION,
+      path: 'Path relative to the enclosing directory',
+      ext: 'Required file extension',
+      content: 'Type of the file\'s content',
+      checksum: 'Expected digest of the file',
+    },
+    nested: {
+      content: 'object',
+      checksum: { algorithm: 'Digest algorithm (default: sha256)', value: 'Expected digest as a hex string' },
+    },
+  },
+  Directory: {
+    signature: 'Directory(options?)',
+    doc: 'A directory.',
+    args: ['options'],
+    options: {
+      description: DESCRIPTION,
+      path: 'Path relative to the enclosing directory',
+      content: 'Entries of the directory',
+      strict: 'Report entries the content does not declare',
+      files: 'How many files it holds: { ext?, match?, min?, max? }',
+    },
+    nested: {
+      content: 'object',
+      files: {
+        ext: 'Only count files with this extension (without the dot)',
+        match: 'Only count files whose name matches',
+        min: 'Minimum number of files',
+        max: 'Maximum number of files',
+      },
+    },
+  },
+  JsonFile: {
+    signature: 'JsonFile({ path, required?, optional? })',
+    doc: 'A JSON file and its fields.',
+    args: ['options'],
+    options: {
+      description: DESCRIPTION,
+      path: 'Path relative to the enclosing directory',
+      required: OBJECT_SPEC['required']!,
+      optional: OBJECT_SPEC['optional']!,
+    },
+  },
+  When: {
+    signature: 'When({ file, field?, is?, then?, else? })',
+    doc: 'Directory content that depends on a value in a JSON file of the directory.',
+    args: ['options'],
+    options: {
+      description: DESCRIPTION,
+      file: 'JSON file holding the value, relative to the directory',
+      field: 'Dotted path of the value in the file (default: the whole document)',
+      is: 'What the value must match for `then` to apply (default: the value is present)',
+      then: 'Content checked when the value matches',
+      else: 'Content checked when it does not, or is missing',
+    },
+    nested: { then: 'object', else: 'object' },
+  },
+  OneOf: { signature: 'OneOf(...options)', doc: 'Exactly one of the options matches.', args: ['object'], variadic: true },
+  AllOf: { signature: 'AllOf(...parts)', doc: 'Every part matches.', args: ['object'], variadic: true },
+  Not: { signature: 'Not(inner)', doc: 'The inner type does not match.', args: ['object'] },
+  TaggedUnion: {
+    signature: 'TaggedUnion(discriminator, variants)',
+    doc: 'An object whose discriminator field selects the variant it is checked against.',
+    args: ['other', 'variants'],
+  },
+  ListOf: {
+    signature: 'ListOf(itemType, options?)',
+    doc: 'A list of items of one type.',
+    args: ['object', 'options'],
+    options: {
+      min: 'Minimum number of items',
+      max: 'Maximum number of items',
+      unique: 'Items must be distinct',
+      uniqueBy: 'Items must be distinct in this field',
+      sorted: 'Items must be sorted: \'asc\' or \'desc\'',
+      sortedBy: 'Field the items are sorted by',
+      sample: 'Only check some of the items',
+    },
+    nested: {
+      sample: {
+        head: 'Check this many items at the start',
+        tail: 'Check this many items at the end',
+        random: 'Check this many items chosen at random',
+        seed: 'Seed for the random choice',
+      },
+    },
+  },
+  Nullable: { signature: 'Nullable(inner)', doc: 'The inner type, or null.', args: ['object'] },
+  Def: {
+    signature: 'Def(name, body, options?)',
+    doc: 'A named type, reusable and recursive (the body may be `self => ...`).',
+    args: ['other', 'object', 'options'],
+    options: {
+      maxDepth: 'Maximum nesting of this type within itself; deeper values report recursion.too_deep',
+      description: 'What the type means; documents generated validators and data structures for it',
+    },
+  },
+  Meta: { signature: 'Meta(inner, meta)', doc: 'Attach metadata to the issues the inner type reports.', args: ['object', 'other'] },
+  Extend: { signature: 'Extend(base, derived)', doc: 'An object spec with the base fields plus (or overridden by) the derived ones.', args: ['object', 'object'] },
+  Generic: { signature: 'Generic(name, body)', doc: 'A parameterized type; call it with type arguments to get a named instance.', args: ['other', 'other'] },
+  Message: {
+    signature: 'Message(inner, { code?, message? })',
+    doc: 'Report the inner type\'s failures with this code and message.',
+    args: ['object', 'options'],
+    options: { code: 'Issue code to report', message: 'Issue message to report' },
+  },
+  Deprecated: {
+    signature: 'Deprecated(inner, deprecation?)',
+    doc: 'Warn when the value is present.',
+    args: ['object', 'options'],
+    options: { message: 'Reason or migration note', replacement: 'Field or type to use instead' },
+  },
+  Phase: {
+    signature: 'Phase(order, inner)',
+    doc: 'Check the inner type in a later phase, skipped when earlier phases reported errors.',
+    args: ['other', 'object'],
+  },
+  Import: {
+    signature: 'Import(path)',
+    doc: 'The Def()s and `exports` of another spec file, relative to this one.',
+    args: ['other'],
+  },
+};
+
+// ═══════════════════════════════════════════════════════════════
+// Source scanning
+// ═══════════════════════════════════════════════════════════════
+
+const IDENT = /[A-Za-z_$][\w$]*/;
+
+/** An open bracket at some point of the source */
+interface Frame {
+  open: '(' | '[' | '{';
+  /** For `(`: the function called */
+  call?: string | undefined;
+  /** Index of the current argument (or entry) */
+  arg: number;
+  /** For `{`: `Name#arg` when it is an argument of a call, plus `.key` per nested option */
+  owner?: string | undefined;
+  /** For `{`: past the `:` of the current entry */
+  value: boolean;
+}
+
+function skipQuoted(source: string, start: number, end: number): number {
+  const quote = source[start];
+  for (let i = start + 1; i < end; i++) {
+    if (source[i] === '\\') i++;
+    else if (source[i] === quote) return i + 1;
+    else if (source[i] === '\n' && quote !== '`') return i;
+  }
+  return end;
+}
+
+function skipRegex(source: string, start: number, end: number): number {
+  let inClass = false;
+  for (let i = start + 1; i < end; i++) {
+    const c = source[i];
+    if (c === '\\') i++;
+    else if (c === '[') inClass = true;
+    else if (c === ']') inClass = false;
+    else if (c === '/' && !inClass) return i + 1;
+    else if (c === '\n') return i;
+  }
+  return end;
+}
+
+function ownerOf(source: string, at: number, top: Frame | undefined): string | undefined {
+  const before = source.slice(Math.max(0, at - 80), at);
+  const key = /([A-Za-z_$][\w$]*)\s*:\s*$/.exec(before);
+  if (key && top?.open === '{') {
+    return `${top.owner ?? ''}.${key[1]}`;
+  }
+  if (top?.open === '(' && top.call !== undefined) {
+    return `${top.call}#${top.arg}`;
+  }
+  return undefined;
+}
+
+/**
+ * The brackets open at `end`, outermost first. Strings, comments and regular
+ * expression literals are skipped, so their brackets do not count.
+ */
+function openFrames(source: string, end: number): Frame[] {
+  const stack: Frame[] = [];
+  let prev = '';
+  for (let i = 0; i < end;) {
+    const c = source[i]!;
+    const next = source[i + 1];
+    if (c === '/' && next === '/') {
+      const newline = source.indexOf('\n', i);
+      i = newline === -1 || newline > end ? end : newline;
+      continue;
+    }
+    if (c === '/' && next === '*') {
+      const close = source.indexOf('*/', i + 2);
+      i = close === -1 || close + 2 > end ? end : close + 2;
+      continue;
+    }
+    if (c === '"' || c === '\'' || c === '`') {
+      i = skipQuoted(source, i, end);
+      prev = c;
+      continue;
+    }
+    if (c === '/' && (prev === '' || '(,=:[!&|?{};'.includes(prev))) {
+      i = skipRegex(source, i, end);
+      prev = c;
+      continue;
+    }
+
+    const top = stack[stack.length - 1];
+    if (c === '(') {
+      const call = /([A-Za-z_$][\w$]*)\s*$/.exec(source.slice(Math.max(0, i - 80), i));
+      stack.push({ open: '(', call: call?.[1], arg: 0, value: false });
+    } else if (c === '[') {
+      stack.push({ open: '[', arg: 0, value: false });
+    } else if (c === '{') {
+      stack.push({ open: '{', owner: ownerOf(source, i, top), arg: 0, value: false });
+    } else if (c === ')' || c === ']' || c === '}') {
+      stack.pop();
+    } else if (c === ',' && top) {
+      top.arg++;
+      top.value = false;
+    } else if (c === ':' && top?.open === '{') {
+      top.value = true;
+    }
+    if (!/\s/.test(c)) prev = c;
+    i++;
+  }
+  return stack;
+}
+
+/** Keys an object at this owner takes (see Frame.owner); undefined when unknown */
+export function keysFor(owner: string | undefined): Record<string, string> | undefined {
+  if (owner === undefined) {
+    return OBJECT_SPEC;
+  }
+  const [head, ...keys] = owner.split('.');
+  const [call, arg] = head!.split('#');
+  const builtin = BUILTINS[call!];
+  if (!builtin || keys.length > 1) {
+    return undefined;
+  }
+  const n = Number(arg);
+  const kind = builtin.args[n] ?? (builtin.variadic ? builtin.args[builtin.args.length - 1] : undefined);
+  if (kind === 'object') {
+    return keys.length === 0 ? OBJECT_SPEC : undefined;
+  }
+  if (kind === 'variants') {
+    // Variant names are free; each variant is an object spec
+    return keys.length === 1 ? OBJECT_SPEC : undefined;
+  }
+  if (kind !== 'options') {
+    return undefined;
+  }
+  if (keys.length === 0) {
+    return builtin.options;
+  }
+  const nested = builtin.nested?.[keys[0]!];
+  return nested === 'object' ? OBJECT_SPEC : nested;
+}
+
+export function offsetAt(source: string, position: Position): number {
+  let offset = 0;
+  for (let line = 0; line < position.line; line++) {
+    const newline = source.indexOf('\n', offset);
+    if (newline === -1) return source.length;
+    offset = newline + 1;
+  }
+  const lineEnd = source.indexOf('\n', offset);
+  return Math.min(offset + position.character, lineEnd === -1 ? source.length : lineEnd);
+}
+
+export function positionAt(source: string, offset: number): Position {
+  const before = source.slice(0, offset);
+  const line = before.split('\n').length - 1;
+  return { line, character: offset - (before.lastIndexOf('\n') + 1) };
+}
+
+function rangeAt(source: string, start: number, length: number): Range {
+  return { start: positionAt(source, start), end: positionAt(source, start + length) };
+}
+
+/** The identifier at an offset, and where it starts */
+function wordAt(source: string, offset: number): { word: string; start: number } | undefined {
+  let start = offset;
+  while (start > 0 && /[\w$]/.test(source[start - 1]!)) start--;
+  let end = offset;
+  while (end < source.length && /[\w$]/.test(source[end]!)) end++;
+  const word = source.slice(start, end);
+  return IDENT.test(word) && !/^\d/.test(word) ? { word, start } : undefined;
+}
+
+function escapeRegex(text: string): string {
+  return text.replace(/[.*+?^${}()|[\]\\]/g, '\\$&');
+}
+
+/** Where a name is declared in a spec file: `const Name =`, Def('Name') / Generic('Name'), or `exports.Name =` */
+function findDeclaration(source: string, name: string, bindings = true): number | undefined {
+  const n = escapeRegex(name);
+  const patterns = [
+    ...(bindings ? [new RegExp(`\\b(?:const|let|var)\\s+(${n})\\s*=`)] : []),
+    new RegExp(`\\b(?:Def|Generic)\\(\\s*(['"\`])(${n})\\1`),
+    new RegExp(`\\bexports\\.(${n})\\s*=`),
+  ];
+  for (const pattern of patterns) {
+    const match = pattern.exec(source);
+    if (match) {
+      const group = match[match.length - 1]!;
+      return match.index + match[0].lastIndexOf(group);
+    }
+  }
+  return undefined;
+}
+
+// Local bindings declared with `const`/`let`/`var`
+function bindingNames(source: string): string[] {
+  return [...source.matchAll(/\b(?:const|let|var)\s+([A-Za-z_$][\w$]*)\s*=/g)].map(m => m[1]!);
+}
+
+interface ImportedName {
+  /** The imported spec file, as written */
+  request: string;
+  /** Name in the imported file; undefined for the namespace binding itself */
+  name?: string | undefined;
+}
+
+// Names bound from Import(): `const { A, B: C } = Import('./x.spec.js')` and `const ns = Import('./x.spec.js')`
+function importedNames(source: string): Map<string, ImportedName & { namespace: boolean }> {
+  const names = new Map<string, ImportedName & { namespace: boolean }>();
+  for (const m of source.matchAll(/\b(?:const|let|var)\s*\{([^}]*)\}\s*=\s*Import\(\s*(['"`])([^'"`]+)\2\s*\)/g)) {
+    for (const entry of m[1]!.split(',')) {
+      const [imported, local] = entry.split(':').map(s => s.trim());
+      if (imported) names.set(local || imported, { request: m[3]!, name: imported, namespace: false });
+    }
+  }
+  for (const m of source.matchAll(/\b(?:const|let|var)\s+([A-Za-z_$][\w$]*)\s*=\s*Import\(\s*(['"`])([^'"`]+)\2\s*\)/g)) {
+    names.set(m[1]!, { request: m[3]!, namespace: true });
+  }
+  return names;
+}
+
+// ═══════════════════════════════════════════════════════════════
+// Hover text for named types
+// ═══════════════════════════════════════════════════════════════
+
+function collectNamed(d: TypeDescription, named: Map<string, TypeDescription>): void {
+  if (d.ref !== undefined && !isRef(d) && !named.has(d.ref)) {
+    named.set(d.ref, d.name === 'Field' ? fieldValue(d) ?? d : d);
+  }
+  for (const child of [...(d.children?.required ?? []), ...(d.children?.optional ?? [])]) collectNamed(child, named);
+  for (const option of d.oneOf ?? []) collectNamed(option, named);
+  for (const part of d.allOf ?? []) collectNamed(part, named);
+  for (const variant of Object.values(d.variants ?? {})) collectNamed(variant, named);
+  if (d.not) collectNamed(d.not, named);
+  if (d.itemType) collectNamed(d.itemType, named);
+}
+
+function typeLabel(d: TypeDescription): string {
+  const name = d.ref ?? (d.name === 'ListOf' && d.itemType ? `ListOf<${typeLabel(d.itemType)}>` : d.name);
+  const label = d.constraints?.length ? `${name} (${d.constraints.join(', ')})` : name;
+  return d.nullable ? `${label} | null` : label;
+}
+
+function namedTypeDoc(name: string, d: TypeDescription): string {
+  const body = { ...d, ref: undefined };
+  const lines = ['```', `Def('${name}'): ${typeLabel(body)}`, '```'];
+  if (d.description) lines.push('', d.description);
+  const fields = [
+    ...(d.children?.required ?? []).map(f => ({ f, optional: f.optional ?? false })),
+    ...(d.children?.optional ?? []).map(f => ({ f, optional: true })),
+  ].filter(({ f }) => f.name === 'Field' && f.key !== undefined);
+  if (fields.length > 0) {
+    lines.push('');
+    for (const { f, optional } of fields) {
+      const value = fieldValue(f);
+      const doc = f.description ? ` — ${f.description}` : '';
+      lines.push(`- \`${f.key}\`${optional ? ' (optional)' : ''}: ${value ? typeLabel(value) : 'any'}${doc}`);
+    }
+  }
+  return lines.join('\n');
+}
+
+// ═══════════════════════════════════════════════════════════════
+// Language service
+// ═══════════════════════════════════════════════════════════════
+
+/** Where an error thrown while evaluating `file` happened, from its stack */
+function errorOffset(cause: unknown, source: string, file: string): { offset: number; length: number } | undefined {
+  // Errors thrown inside the sandbox come from its own realm, so no instanceof Error
+  const stack = (cause as { stack?: unknown } | undefined)?.stack;
+  if (typeof stack !== 'string') return undefined;
+  const lines = stack.split('\n');
+  // Syntax errors: "<file>:<line>", the source line, then a caret line under the error
+  const header = new RegExp(`^${escapeRegex(file)}:(\\d+)$`).exec(lines[0] ?? '');
+  if (header) {
+    const start = offsetAt(source, { line: Number(header[1]) - 1, character: 0 });
+    const caret = /^(\s*)(\^+)/.exec(lines[2] ?? '');
+    if (!caret) return { offset: start, length: 0 };
+    const offset = start + caret[1]!.length;
+    return { offset, length: Math.max(caret[2]!.length, wordAt(source, offset)?.word.length ?? 0) };
+  }
+  // Errors thrown while running: the first frame in the spec file
+  const frame = new RegExp(`${escapeRegex(file)}:(\\d+):(\\d+)`).exec(stack);
+  if (frame) {
+    const offset = offsetAt(source, { line: Number(frame[1]) - 1, character: Number(frame[2]) - 1 });
+    return { offset, length: wordAt(source, offset)?.word.length ?? 0 };
+  }
+  return undefined;
+}
+
+/**
+ * SpecLanguageService - editor support for spec files, independent of the transport
+ *
+ * Files are identified by path; Import() requests resolve relative to them and
+ * imported files are read from disk.
+ *
+ * @example
+ * const service = new SpecLanguageService();
+ * service.diagnose(source, '/specs/order.spec.js');
+ * service.definition(source, '/specs/order.spec.js', { line: 4, character: 30 });
+ */
+export class SpecLanguageService {
+  constructor(private readonly engine: SpecEngine = new SpecEngine()) {}
+
+  /**
+   * Errors that keep the spec from compiling, located in the source where possible
+   */
+  diagnose(source: string, file: string): Diagnostic[] {
+    try {
+      this.engine.compileSource(source, file);
+      return [];
+    } catch (err) {
+      if (!(err instanceof SchemaError)) throw err;
+      const at = errorOffset(err.cause, source, file);
+      return err.issues.map(issue => this.diagnostic(issue, source, at));
+    }
+  }
+
+  private diagnostic(issue: Issue, source: string, at: { offset: number; length: number } | undefined): Diagnostic {
+    let range = at ? rangeAt(source, at.offset, at.length) : undefined;
+    const key = /^Field "(.+)" has a default/.exec(issue.message);
+    if (!range && key) {
+      const match = new RegExp(`\\bkey\\s*:\\s*(['"\`])${escapeRegex(key[1]!)}\\1`).exec(source);
+      if (match) range = rangeAt(source, match.index, match[0].length);
+    }
+    return {
+      range: range ?? rangeAt(source, 0, source.indexOf('\n') === -1 ? source.length : source.indexOf('\n')),
+      severity: issue.level === 'error' ? 1 : issue.level === 'warning' ? 2 : 3,
+      code: issue.code,
+      source: 'specspec',
+      message: issue.message.replace(/^Spec file error: /, ''),
+    };
+  }
+
+  /**
+   * Where the type (or Import() file) at a position is defined: a Def or binding in
+   * this file, or a Def exported by an imported one
+   */
+  definition(source: string, file: string, position: Position): Location | null {
+    const offset = offsetAt(source, position);
+    const imports = importedNames(source);
+
+    // The path of an Import()
+    for (const m of source.matchAll(/\bImport\(\s*(['"`])([^'"`]+)\1/g)) {
+      const start = m.index + m[0].length - m[2]!.length - 1;
+      if (offset >= start && offset <= start + m[2]!.length) {
+        const target = path.resolve(path.dirname(file), m[2]!);
+        return fs.existsSync(target) ? { file: target, range: { start: { line: 0, character: 0 }, end: { line: 0, character: 0 } } } : null;
+      }
+    }
+
+    const at = wordAt(source, offset);
+    if (!at) return null;
+
+    // ns.Name, where ns = Import(...)
+    const member = /([A-Za-z_$][\w$]*)\s*\.\s*$/.exec(source.slice(Math.max(0, at.start - 80), at.start));
+    const namespace = member ? imports.get(member[1]!) : undefined;
+    if (namespace?.namespace) {
+      return this.importedDeclaration(file, namespace.request, at.word);
+    }
+
+    const imported = imports.get(at.word);
+    if (imported && !imported.namespace && imported.name !== undefined) {
+      return this.importedDeclaration(file, imported.request, imported.name);
+    }
+
+    const declared = findDeclaration(source, at.word);
+    return declared !== undefined ? { file, range: rangeAt(source, declared, at.word.length) } : null;
+  }
+
+  private importedDeclaration(file: string, request: string, name: string): Location | null {
+    const target = path.resolve(path.dirname(file), request);
+    let source: string;
+    try {
+      source = fs.readFileSync(target, 'utf-8');
+    } catch {
+      return null;
+    }
+    // Imports only see Def()s and exports, not other bindings
+    const declared = findDeclaration(source, name, false);
+    return declared !== undefined ? { file: target, range: rangeAt(source, declared, name.length) } : null;
+  }
+
+  /**
+   * Markdown for the identifier at a position: an option key, a built-in type, or a
+   * named type of this spec (with its description and fields)
+   */
+  hover(source: string, file: string, position: Position): string | null {
+    const at = wordAt(source, offsetAt(source, position));
+    if (!at) return null;
+
+    const top = openFrames(source, at.start).pop();
+    if (top?.open === '{' && !top.value) {
+      const doc = keysFor(top.owner)?.[at.word];
+      if (doc !== undefined) return `\`${at.word}\` — ${doc}`;
+    }
+
+    const builtin = BUILTINS[at.word];
+    if (builtin && findDeclaration(source, at.word) === undefined) {
+      return ['```', builtin.signature, '```', '', builtin.doc].join('\n');
+    }
+
+    const name = this.typeName(source, at.word);
+    const named = new Map<string, TypeDescription>();
+    try {
+      collectNamed(portableDescription(this.engine.compileSource(source, file).describe()), named);
+    } catch {
+      return null;
+    }
+    const desc = named.get(name);
+    return desc ? namedTypeDoc(name, desc) : null;
+  }
+
+  // The Def name an identifier stands for: `const X = Def('Name', ...)`, an imported Def, or the identifier itself
+  private typeName(source: string, word: string): string {
+    const def = new RegExp(`\\b(?:const|let|var)\\s+${escapeRegex(word)}\\s*=\\s*(?:Def|Generic)\\(\\s*(['"\`])([^'"\`]+)\\1`).exec(source);
+    if (def) return def[2]!;
+    return importedNames(source).get(word)?.name ?? word;
+  }
+
+  /**
+   * Completions at a position: the keys of the options (or object spec) being
+   * written, otherwise the types available in the spec
+   */
+  complete(source: string, position: Position): CompletionItem[] {
+    const offset = offsetAt(source, position);
+    const start = wordAt(source, offset)?.start ?? offset;
+    const top = openFrames(source, start).pop();
+    if (top?.open === '{' && !top.value) {
+      const keys = keysFor(top.owner);
+      if (keys) {
+        return Object.entries(keys).map(([label, documentation]): CompletionItem => ({ label, kind: 10, documentation }));
+      }
+    }
+
+    const items: CompletionItem[] = [...this.engine.typeNames(), 'Import'].map((label): CompletionItem => {
+      const builtin = BUILTINS[label];
+      return builtin ? { label, kind: 3, detail: builtin.signature, documentation: builtin.doc } : { label, kind: 3 };
+    });
+    const seen = new Set(items.map(i => i.label));
+    for (const label of [...bindingNames(source), ...importedNames(source).keys()]) {
+      if (!seen.has(label)) {
+        seen.add(label);
+        items.push({ label, kind: 6 });
+      }
+    }
+    return items;
+  }
+}
+
+// ═══════════════════════════════════════════════════════════════
+// LSP over JSON-RPC
+// ═══════════════════════════════════════════════════════════════
+
+export interface JsonRpcMessage {
+  jsonrpc: '2.0';
+  id?: number | string | null;
+  method?: string;
+  params?: unknown;
+  result?: unknown;
+  error?: { code: number; message: string };
+}
+
+interface DocumentParams {
+  textDocument: { uri: string; text?: string };
+  contentChanges?: Array<{ text: string }>;
+  position?: Position;
+}
+
+const METHOD_NOT_FOUND = -32601;
+const INTERNAL_ERROR = -32603;
+
+function uriToFile(uri: string): string {
+  return uri.startsWith('file:') ? fileURLToPath(uri) : uri;
+}
+
+/**
+ * SpecLanguageServer - the LSP methods, over any transport
+ *
+ * Documents are synced in full. Diagnostics are published when a document is
+ * opened or changed, and cleared when it is closed.
+ */
+export class SpecLanguageServer {
+  private readonly documents = new Map<string, string>();
+  private shutdown = false;
+
+  constructor(
+    private readonly service: SpecLanguageService,
+    private readonly send: (message: JsonRpcMessage) => void,
+    /** Called on `exit`, with the exit code LSP asks for */
+    private readonly onExit: (code: number) => void = () => {}
+  ) {}
+
+  handle(message: JsonRpcMessage): void {
+    const { id, method } = message;
+    if (method === undefined) return; // A response; the server sends no requests
+    let result: unknown;
+    try {
+      result = this.dispatch(method, (message.params ?? {}) as DocumentParams);
+    } catch (err) {
+      if (id !== undefined) {
+        const code = err instanceof MethodNotFound ? METHOD_NOT_FOUND : INTERNAL_ERROR;
+        this.send({ jsonrpc: '2.0', id, error: { code, message: (err as Error).message } });
+      }
+      return;
+    }
+    if (id !== undefined) {
+      this.send({ jsonrpc: '2.0', id, result: result ?? null });
+    }
+  }
+
+  private dispatch(method: string, params: DocumentParams): unknown {
+    switch (method) {
+      case 'initialize':
+        return {
+          capabilities: {
+            textDocumentSync: 1,
+            definitionProvider: true,
+            hoverProvider: true,
+            completionProvider: { triggerCharacters: ['{', ','] },
+          },
+          serverInfo: { name: 'specspec' },
+        };
+      case 'shutdown':
+        this.shutdown = true;
+        return null;
+      case 'exit':
+        this.onExit(this.shutdown ? 0 : 1);
+        return undefined;
+      case 'textDocument/didOpen':
+        this.update(params.textDocument.uri, params.textDocument.text ?? '');
+        return undefined;
+      case 'textDocument/didChange': {
+        const change = params.contentChanges?.[params.contentChanges.length - 1];
+        if (change) this.update(params.textDocument.uri, change.text);
+        return undefined;
+      }
+      case 'textDocument/didClose':
+        this.documents.delete(params.textDocument.uri);
+        this.send({ jsonrpc: '2.0', method: 'textDocument/publishDiagnostics', params: { uri: params.textDocument.uri, diagnostics: [] } });
+        return undefined;
+      case 'textDocument/definition':
+        return this.withDocument(params, (source, file, position) => {
+          const location = this.service.definition(source, file, position);
+          if (!location) return null;
+          const uri = location.file === file ? params.textDocument.uri : pathToFileURL(location.file).href;
+          return { uri, range: location.range };
+        });
+      case 'textDocument/hover':
+        return this.withDocument(params, (source, file, position) => {
+          const value = this.service.hover(source, file, position);
+          return value === null ? null : { contents: { kind: 'markdown', value } };
+        });
+      case 'textDocument/completion':
+        return this.withDocument(params, (source, _file, position) => this.service.complete(source, position));
+    }
+    if (method.startsWith('$/') || method === 'initialized') {
+      return undefined; // Notifications the server may ignore
+    }
+    throw new MethodNotFound(`Unhandled method ${method}`);
+  }
+
+  private update(uri: string, text: string): void {
+    this.documents.set(uri, text);
+    this.send({
+      jsonrpc: '2.0',
+      method: 'textDocument/publishDiagnostics',
+      params: { uri, diagnostics: this.service.diagnose(text, uriToFile(uri)) },
+    });
+  }
+
+  private withDocument<T>(params: DocumentParams, fn: (source: string, file: string, position: Position) => T): T | null {
+    const source = this.documents.get(params.textDocument.uri);
+    if (source === undefined || !params.position) return null;
+    return fn(source, uriToFile(params.textDocument.uri), params.position);
+  }
+}
+
+class MethodNotFound extends Error {}
+
+/**
+ * Split a byte stream into LSP messages (`Content-Length` framed JSON)
+ */
+export function messageReader(onMessage: (message: JsonRpcMessage) => void): (chunk: Buffer) => void {
+  let buffer = Buffer.alloc(0);
+  return (chunk) => {
+    buffer = Buffer.concat([buffer, chunk]);
+    for (;;) {
+      const headerEnd = buffer.indexOf('\r\n\r\n');
+      if (headerEnd === -1) return;
+      const length = /Content-Length:\s*(\d+)/i.exec(buffer.subarray(0, headerEnd).toString('ascii'));
+      if (!length) {
+        buffer = buffer.subarray(headerEnd + 4); // Not a message header; skip it
+        continue;
+      }
+      const start = headerEnd + 4;
+      const end = start + Number(length[1]);
+      if (buffer.length < end) return;
+      const body = buffer.subarray(start, end).toString('utf-8');
+      buffer = buffer.subarray(end);
+      try {
+        onMessage(JSON.parse(body) as JsonRpcMessage);
+      } catch {
+        // Malformed JSON: drop the message
+      }
+    }
+  };
+}
+
+/** Frame a message for an LSP byte stream */
+export function frameMessage(message: JsonRpcMessage): string {
+  const body = JSON.stringify(message);
+  return `Content-Length: ${Buffer.byteLength(body, 'utf-8')}\r\n\r\n${body}`;
+}
+
+/**
+ * Serve LSP over a pair of streams (stdin / stdout for `specspec lsp`)
+ */
+export function serveLanguageServer(
+  service: SpecLanguageService,
+  input: NodeJS.ReadableStream = process.stdin,
+  output: NodeJS.WritableStream = process.stdout,
+  onExit: (code: number) => void = (code) => process.exit(code)
+): SpecLanguageServer {
+  const server = new SpecLanguageServer(service, message => output.write(frameMessage(message)), onExit);
+  input.on('data', messageReader(message => server.handle(message)));
+  return server;
+}
@@ -0,0 +1,69 @@
+// src/modifiers/allof.ts
+// AllOf modifier - value must satisfy every given part
+
+import { Modifier, validateAny, tryMatch, type Validatable, type TypeDescription, describeValidatable } from '../base.js';
+import { ValidationContext, type Context } from '../context.js';
+
+export class AllOfModifier extends Modifier<unknown> {
+  constructor(private readonly parts: Validatable[]) {
+    super();
+  }
+
+  validate(value: unknown, ctx: Context): void {
+    // Run every part, then report the union of their issues.
+    // Parts often share checks (e.g. both expect an object), so identical issues are kept once.
+    const seen = new Set<string>();
+    for (const part of this.parts) {
+      const partCtx = new ValidationContext([...ctx.path], value, undefined, undefined, ctx.options);
+      validateAny(part, value, partCtx);
+      if (partCtx.partial) ctx.markPartial();
+
+      for (const issue of partCtx.issues) {
+        const key = JSON.stringify([issue.level, issue.code, issue.path, issue.message]);
+        if (seen.has(key)) continue;
+        seen.add(key);
+
+        // Re-report at the same nested path under the caller's context
+        let target = issue.meta ? ctx.withMeta(issue.meta) : ctx;
+        for (const segment of issue.path.slice(ctx.path.length)) {
+          target = target.child(segment, undefined);
+        }
+        // Keep the original level
+        if (issue.level === 'error') {
+          target.addIssue(issue.code, issue.message, issue.fix);
+        } else if (issue.level === 'warning') {
+          target.addWarning(issue.code, issue.message);
+        } else {
+          target.addInfo(issue.code, issue.message);
+        }
+      }
+    }
+  }
+
+  matches(value: unknown, ctx: Context): boolean {
+    return this.parts.every(part => tryMatch(part, value, ctx));
+  }
+
+  describe(): TypeDescription {
+    return {
+      name: 'AllOf',
+      allOf: this.parts.map(describeValidatable),
+    };
+  }
+}
+
+/**
+ * AllOf - value must satisfy every given part; issues from all parts are reported
+ *
+ * @example
+ * // Base shape plus extension constraints
+ * const Base = { required: [Field({ key: 'id', value: Str })] };
+ * const Timestamped = { required: [Field({ key: 'createdAt', value: Str })] };
+ * AllOf(Base, Timestamped)
+ *
+ * // Several constraints on one value
+ * AllOf(Str({ minLength: 3 }), /^[a-z-]+$/)
+ */
+export function AllOf(...parts: Validatable[]): AllOfModifier {
+  return new AllOfModifier(parts);
+}
@@ -0,0 +1,113 @@
+// src/modifiers/def.ts
+// Def modifier - named, reusable type definition
+
+import { Modifier, validateAny, tryMatch, type Validatable, type ObjectSpec, type TypeDescription, describeValidatable } from '../base.js';
+import type { Context } from '../context.js';
+
+/** Body of a Def, or a function returning it for types that refer to themselves */
+export type DefBody = Validatable | ObjectSpec | ((self: DefModifier) => Validatable | ObjectSpec);
+
+export interface DefOptions {
+  /** Maximum nesting of this type within itself; deeper values report recursion.too_deep */
+  maxDepth?: number;
+  /** What the type means; documents generated validators and data structures for it */
+  description?: string;
+}
+
+// Defs currently being described, so recursive types describe their inner uses as references
+const describing = new Set<DefModifier>();
+
+export class DefModifier extends Modifier<unknown> {
+  private resolved: Validatable | ObjectSpec | undefined;
+  private depth = 0;
+
+  constructor(
+    readonly defName: string,
+    private readonly source: DefBody,
+    readonly options: DefOptions = {}
+  ) {
+    super();
+  }
+
+  /** The body, resolved on first use so it may refer to this Def or to Defs declared later */
+  get body(): Validatable | ObjectSpec {
+    if (this.resolved === undefined) {
+      this.resolved = typeof this.source === 'function' ? this.source(this) : this.source;
+    }
+    return this.resolved;
+  }
+
+  validate(value: unknown, ctx: Context): void {
+    const { maxDepth } = this.options;
+    if (maxDepth !== undefined && this.depth >= maxDepth) {
+      ctx.addIssue('recursion.too_deep', `Nesting of ${this.defName} exceeds maximum depth ${maxDepth}`);
+      return;
+    }
+    this.depth++;
+    try {
+      // Object specs fall through to validateObjectSpec
+      validateAny(this.body as Validatable, value, ctx);
+    } finally {
+      this.depth--;
+    }
+  }
+
+  matches(value: unknown, ctx: Context): boolean {
+    const { maxDepth } = this.options;
+    if (maxDepth !== undefined && this.depth >= maxDepth) {
+      return false;
+    }
+    this.depth++;
+    try {
+      return tryMatch(this.body as Validatable, value, ctx);
+    } finally {
+      this.depth--;
+    }
+  }
+
+  describe(): TypeDescription {
+    // A use inside its own body is described by name only
+    if (describing.has(this)) {
+      return { name: 'Ref', ref: this.defName };
+    }
+    describing.add(this);
+    try {
+      // Same shape as the body, tagged with the name so generators can emit it once
+      const desc: TypeDescription = { ...describeValidatable(this.body), ref: this.defName };
+      if (this.options.maxDepth !== undefined) {
+        desc.maxDepth = this.options.maxDepth;
+      }
+      if (this.options.description !== undefined) {
+        desc.description = this.options.description;
+      }
+      return desc;
+    } finally {
+      describing.delete(this);
+    }
+  }
+}
+
+/**
+ * Def - name a type so it can be reused throughout the spec.
+ * Validation is the same as using the body directly; code generators emit
+ * one validator function per name instead of inlining every use.
+ *
+ * Pass a function as the body for recursive types; it is called on first use,
+ * so it can refer to the Def itself or to Defs declared after it.
+ *
+ * @example
+ * const Metadata = Def('Metadata', {
+ *   required: [Field({ key: 'name', value: Str() }), Field({ key: 'version', value: Str() })],
+ * }, { description: 'Package name and version' });
+ *
+ * Field({ key: 'package', value: Metadata })
+ * Field({ key: 'dependencies', value: ListOf(Metadata) })
+ *
+ * // Recursive: a tree node holding a list of nodes, at most 32 levels deep
+ * const TreeNode = Def('TreeNode', self => ({
+ *   required: [Field({ key: 'name', value: Str() }), Field({ key: 'children', value: ListOf(self) })],
+ * }), { maxDepth: 32 });
+ */
+export function Def(name: string, body: DefBody, options?: DefOptions): DefModifier {
+  return new DefModifier(name, body, options);
+}
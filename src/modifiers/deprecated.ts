@@ -0,0 +1,51 @@
+// src/modifiers/deprecated.ts
+// Deprecated modifier - warn when a deprecated type is used
+
+import { Modifier, validateAny, tryMatch, deprecationNotice, type Validatable, type ObjectSpec, type TypeDescription, type DeprecationSpec, isLiteralValue, describeValidatable } from '../base.js';
+import type { Context } from '../context.js';
+
+export class DeprecatedModifier extends Modifier<unknown> {
+  constructor(
+    private readonly inner: Validatable | ObjectSpec,
+    private readonly deprecation: string | DeprecationSpec = {}
+  ) {
+    super();
+  }
+
+  validate(value: unknown, ctx: Context): void {
+    ctx.addWarning('type.deprecated', this.notice());
+    validateAny(this.inner as Validatable, value, ctx);
+  }
+
+  matches(value: unknown, ctx: Context): boolean {
+    return tryMatch(this.inner as Validatable, value, ctx);
+  }
+
+  describe(): TypeDescription {
+    return { ...describeValidatable(this.inner), deprecated: this.notice() };
+  }
+
+  private notice(): string {
+    const inner = this.inner;
+    if (isLiteralValue(inner) && !(inner instanceof RegExp)) {
+      return deprecationNotice(JSON.stringify(inner), this.deprecation);
+    }
+    const desc = describeValidatable(inner);
+    return deprecationNotice(desc.ref ?? desc.name, this.deprecation);
+  }
+}
+
+/**
+ * Deprecated - accept the inner type but report a `type.deprecated` warning when it
+ * is used. Inside OneOf only the matched option warns, so single variants can be
+ * phased out. Warnings do not fail validation; generated validators report them too.
+ *
+ * @example
+ * Field({
+ *   key: 'license',
+ *   value: OneOf('MIT', 'Apache-2.0', Deprecated('GPL-2.0', { replacement: 'GPL-3.0' }), 'GPL-3.0'),
+ * })
+ */
+export function Deprecated(inner: Validatable | ObjectSpec, deprecation?: string | DeprecationSpec): DeprecatedModifier {
+  return new DeprecatedModifier(inner, deprecation);
+}
@@ -0,0 +1,124 @@
+// src/modifiers/extend.ts
+// Extend modifier - object type inheriting the fields of a base object type
+
+import { Modifier, validateAny, tryMatch, type Validatable, type ObjectSpec, type TypeDescription, isObjectSpec, describeValidatable } from '../base.js';
+import type { Context } from '../context.js';
+import { FieldType } from '../types/structural.js';
+import { DefModifier } from './def.js';
+
+/** Object spec of an extendable base: an object spec, a Def of one, or another Extend */
+function baseSpec(base: unknown): ObjectSpec | undefined {
+  if (base instanceof ExtendModifier) return base.spec;
+  if (base instanceof DefModifier) return baseSpec(base.body);
+  if (isObjectSpec(base)) return base;
+  return undefined;
+}
+
+function fieldKey(item: Validatable): string | undefined {
+  return item instanceof FieldType ? item.spec.key : undefined;
+}
+
+/**
+ * Merge a derived spec into its base: a derived field replaces the base field
+ * with the same key (in the list the derived spec puts it in); other fields are appended.
+ */
+function mergeSpecs(base: ObjectSpec, derived: ObjectSpec): ObjectSpec {
+  const derivedItems = [...(derived.required ?? []), ...(derived.optional ?? [])];
+  const overridden = new Map<string, { item: Validatable; required: boolean }>();
+  for (const item of derived.required ?? []) {
+    const key = fieldKey(item);
+    if (key !== undefined) overridden.set(key, { item, required: true });
+  }
+  for (const item of derived.optional ?? []) {
+    const key = fieldKey(item);
+    if (key !== undefined) overridden.set(key, { item, required: false });
+  }
+
+  const required: Validatable[] = [];
+  const optional: Validatable[] = [];
+  const placed = new Set<Validatable>();
+  const place = (item: Validatable, isRequired: boolean): void => {
+    (isRequired ? required : optional).push(item);
+    placed.add(item);
+  };
+
+  // Base fields keep their position, replaced in place when overridden
+  for (const [items, isRequired] of [[base.required ?? [], true], [base.optional ?? [], false]] as const) {
+    for (const item of items) {
+      const key = fieldKey(item);
+      const override = key !== undefined ? overridden.get(key) : undefined;
+      if (!override) {
+        place(item, isRequired);
+      } else if (!placed.has(override.item)) {
+        place(override.item, override.required);
+      }
+    }
+  }
+  for (const item of derivedItems) {
+    if (!placed.has(item)) {
+      place(item, (derived.required ?? []).includes(item));
+    }
+  }
+
+  return { required, optional };
+}
+
+export class ExtendModifier extends Modifier<unknown> {
+  /** The merged object spec */
+  readonly spec: ObjectSpec;
+
+  constructor(
+    private readonly base: ObjectSpec | DefModifier | ExtendModifier,
+    private readonly derived: ObjectSpec
+  ) {
+    super();
+    this.spec = mergeSpecs(baseSpec(base) ?? {}, derived);
+  }
+
+  /** True when no derived field replaces a base field */
+  private onlyAdds(): boolean {
+    const baseKeys = new Set(
+      [...(baseSpec(this.base)?.required ?? []), ...(baseSpec(this.base)?.optional ?? [])].map(fieldKey)
+    );
+    return [...(this.derived.required ?? []), ...(this.derived.optional ?? [])]
+      .every(item => fieldKey(item) === undefined || !baseKeys.has(fieldKey(item)));
+  }
+
+  validate(value: unknown, ctx: Context): void {
+    validateAny(this.spec as Validatable, value, ctx);
+  }
+
+  matches(value: unknown, ctx: Context): boolean {
+    return tryMatch(this.spec as Validatable, value, ctx);
+  }
+
+  describe(): TypeDescription {
+    // A named base that is only added to is composed rather than copied,
+    // so generated validators call the base's validator function
+    if (this.base instanceof DefModifier && this.onlyAdds()) {
+      return { name: 'AllOf', allOf: [this.base.describe(), describeValidatable(this.derived)] };
+    }
+    return describeValidatable(this.spec);
+  }
+}
+
+/**
+ * Extend - object type inheriting every field of a base object type.
+ * A derived field with the same key as a base field overrides it; other derived fields are added.
+ * The base may be an object spec, a Def() of one, or another Extend().
+ *
+ * @example
+ * const Base = Def('Base', { required: [Field({ key: 'id', value: Str() }), Field({ key: 'version', value: Str() })] });
+ *
+ * // Adds `plugins`, tightens `version`
+ * Extend(Base, {
+ *   required: [Field({ key: 'version', value: Str({ match: /^2\./ }) })],
+ *   optional: [Field({ key: 'plugins', value: ListOf(Str()), optional: true })],
+ * })
+ */
+export function Extend(base: ObjectSpec | Validatable, derived: ObjectSpec): ExtendModifier {
+  if (baseSpec(base) === undefined) {
+    throw new Error('Extend() base must be an object spec, a Def() of one, or another Extend()');
+  }
+  return new ExtendModifier(base as ObjectSpec | DefModifier | ExtendModifier, derived);
+}
@@ -0,0 +1,89 @@
+// src/modifiers/generic.ts
+// Generic - parameterized named type, instantiated with concrete type arguments
+
+import { type Validatable, type ObjectSpec, type TypeDescription, isLiteralValue, isType, isModifier, isObjectSpec, describeValidatable } from '../base.js';
+import { Def, DefModifier } from './def.js';
+
+// Name a type argument; Defs by their own name, without resolving their body
+function argName(v: Validatable | ObjectSpec): string {
+  if (v instanceof DefModifier) {
+    return v.defName;
+  } else if (isType(v) || isModifier(v)) {
+    return descName(v.describe());
+  } else if (isLiteralValue(v)) {
+    return v instanceof RegExp ? 'Pattern' : JSON.stringify(v);
+  } else if (isObjectSpec(v)) {
+    return 'Object';
+  }
+  return 'Unknown';
+}
+
+// Tell type arguments apart: Defs by name, anything else by its full description
+function argKey(v: Validatable | ObjectSpec): string {
+  return v instanceof DefModifier ? `Def ${v.defName}` : JSON.stringify(describeValidatable(v));
+}
+
+function descName(desc: TypeDescription): string {
+  if (desc.ref !== undefined) {
+    return desc.ref;
+  }
+  return desc.itemType ? `${desc.name}<${descName(desc.itemType)}>` : desc.name;
+}
+
+export type GenericBody = (...params: Array<Validatable | ObjectSpec>) => Validatable | ObjectSpec;
+
+/** A generic type: call it with type arguments to get a named instance */
+export interface GenericType {
+  (...args: Array<Validatable | ObjectSpec>): DefModifier;
+  readonly genericName: string;
+}
+
+/**
+ * Generic - declare a parameterized type once and instantiate it per payload type.
+ * Each instance is a Def named after its arguments (e.g. `Paginated<User>`), so
+ * code generators emit one concrete validator per instantiation. The same
+ * arguments always give the same instance.
+ *
+ * Instances are named from their arguments: pass Defs as arguments when two
+ * instantiations would otherwise share a name (e.g. two differently constrained Str());
+ * instantiating a name already taken by different arguments throws.
+ *
+ * @example
+ * const Paginated = Generic('Paginated', T => ({
+ *   required: [Field({ key: 'items', value: ListOf(T) }), Field({ key: 'total', value: Num({ min: 0 }) })],
+ * }));
+ *
+ * Field({ key: 'users', value: Paginated(User) })   // Paginated<User>
+ * Field({ key: 'orders', value: Paginated(Order) }) // Paginated<Order>
+ */
+export function Generic(name: string, body: GenericBody): GenericType {
+  const instances: Array<{ args: Array<Validatable | ObjectSpec>; keys: string[]; def: DefModifier }> = [];
+
+  const instantiate = (...args: Array<Validatable | ObjectSpec>): DefModifier => {
+    if (body.length > 0 && args.length !== body.length) {
+      throw new Error(`${name} expects ${body.length} type argument(s), got ${args.length}`);
+    }
+    const existing = instances.find(i => i.args.length === args.length && i.args.every((a, n) => a === args[n]));
+    if (existing) {
+      return existing.def;
+    }
+    const instanceName = `${name}<${args.map(argName).join(', ')}>`;
+    const keys = args.map(argKey);
+    const named = instances.find(i => i.def.defName === instanceName);
+    if (named) {
+      // Equal arguments written twice (e.g. Str() at each use) are the same instance
+      if (named.keys.length === keys.length && named.keys.every((k, n) => k === keys[n])) {
+        return named.def;
+      }
+      throw new Error(
+        `${instanceName} is already an instance of ${name} with different type arguments; pass a Def as the argument to name it (e.g. Def('ShortString', Str({ maxLength: 5 })))`
+      );
+    }
+    // Resolved on first use, so arguments may be Defs still being declared
+    const def = Def(instanceName, () => body(...args));
+    instances.push({ args, keys, def });
+    return def;
+  };
+
+  return Object.assign(instantiate, { genericName: name });
+}
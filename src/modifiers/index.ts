@@ -1,4 +1,15 @@
 // src/modifiers/index.ts
 
 export { OneOf, OneOfModifier } from './oneof.js';
-export { ListOf, ListOfModifier, type ListOfSpec } from './listof.js';
+export { AllOf, AllOfModifier } from './allof.js';
+export { Not, NotModifier } from './not.js';
+export { TaggedUnion, TaggedUnionModifier } from './taggedunion.js';
+export { ListOf, ListOfModifier, type ListOfSpec, type ListSampleSpec } from './listof.js';
+export { Nullable, NullableModifier } from './nullable.js';
+export { Def, DefModifier, type DefBody, type DefOptions } from './def.js';
+export { Meta, MetaModifier } from './meta.js';
+export { Extend, ExtendModifier } from './extend.js';
+export { Deprecated, DeprecatedModifier } from './deprecated.js';
+export { Message, MessageModifier, type MessageSpec } from './message.js';
+export { Generic, type GenericType, type GenericBody } from './generic.js';
+export { Phase, PhaseModifier, validateInPhases } from './phase.js';
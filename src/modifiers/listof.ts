@@ -1,35 +1,108 @@
 // src/modifiers/listof.ts
 // ListOf modifier - validates arrays
 
-import { Modifier, validateAny, type Validatable, type TypeDescription, isType, isModifier, isLiteralValue, isObjectSpec } from '../base.js';
+import { Modifier, validateAny, type Validatable, type TypeDescription, describeValidatable } from '../base.js';
 import type { Context } from '../context.js';
 
+export interface ListSampleSpec {
+  /** Always validate the first N items */
+  head?: number;
+  /** Always validate the last N items */
+  tail?: number;
+  /** Validate N further items chosen at random from the middle */
+  random?: number;
+  /** Seed for the random choice, so runs are reproducible (default: 0) */
+  seed?: number;
+}
+
 export interface ListOfSpec {
   min?: number;
   max?: number;
+  /** Items must be pairwise distinct (deep equality) */
+  unique?: boolean;
+  /** Object items must not share a value at this key path (e.g. 'id' or 'meta.id') */
+  uniqueBy?: string;
+  /** Items must be in this order (equal neighbours allowed) */
+  sorted?: 'asc' | 'desc';
+  /** Order object items by the value at this key path (e.g. 'timestamp'); implies ascending */
+  sortedBy?: string;
+  /** Validate only a sample of the items when the array is large */
+  sample?: ListSampleSpec;
+}
+
+// mulberry32 - small seeded PRNG returning floats in [0, 1)
+function seededRandom(seed: number): () => number {
+  let state = seed >>> 0;
+  return () => {
+    state = (state + 0x6D2B79F5) >>> 0;
+    let t = state;
+    t = Math.imul(t ^ (t >>> 15), t | 1);
+    t ^= t + Math.imul(t ^ (t >>> 7), t | 61);
+    return ((t ^ (t >>> 14)) >>> 0) / 4294967296;
+  };
 }
 
-// Helper to describe item type
-function describeItem(v: Validatable): TypeDescription {
-  if (isType(v)) {
-    return v.describe();
-  } else if (isModifier(v)) {
-    return v.describe();
-  } else if (isLiteralValue(v)) {
-    if (v instanceof RegExp) {
-      return { name: 'Pattern', constraints: [`matches \`${v}\``] };
+// Ascending item indices selected by a sample spec
+function sampleIndices(length: number, sample: ListSampleSpec): number[] {
+  const head = Math.min(sample.head ?? 0, length);
+  const tail = Math.min(sample.tail ?? 0, length - head);
+  const middle = length - head - tail;
+  const picked = new Set<number>();
+  for (let i = 0; i < head; i++) picked.add(i);
+  for (let i = length - tail; i < length; i++) picked.add(i);
+
+  const random = Math.min(sample.random ?? 0, middle);
+  if (random === middle) {
+    for (let i = head; i < head + middle; i++) picked.add(i);
+  } else {
+    const next = seededRandom(sample.seed ?? 0);
+    const target = head + tail + random;
+    while (picked.size < target) {
+      picked.add(head + Math.floor(next() * middle));
     }
-    return { name: 'Literal', constraints: [`equals ${JSON.stringify(v)}`] };
-  } else if (isObjectSpec(v)) {
-    return { name: 'Object', spec: v };
   }
-  return { name: 'Unknown' };
+  return Array.from(picked).sort((a, b) => a - b);
+}
+
+// Stable JSON text for deep equality (object keys sorted)
+function canonicalJson(v: unknown): string {
+  if (Array.isArray(v)) {
+    return `[${v.map(canonicalJson).join(',')}]`;
+  }
+  if (v !== null && typeof v === 'object') {
+    const entries = Object.keys(v).sort().map(k => `${JSON.stringify(k)}:${canonicalJson((v as Record<string, unknown>)[k])}`);
+    return `{${entries.join(',')}}`;
+  }
+  return JSON.stringify(v) ?? 'undefined';
+}
+
+// Resolve a dotted key path inside an item
+function getKeyPath(item: unknown, keyPath: string): unknown {
+  let current = item;
+  for (const key of keyPath.split('.')) {
+    if (current === null || typeof current !== 'object' || Array.isArray(current)) {
+      return undefined;
+    }
+    current = (current as Record<string, unknown>)[key];
+  }
+  return current;
+}
+
+// Order two sort keys; null when they are not both numbers or both strings
+function compareKeys(a: unknown, b: unknown): number | null {
+  if (typeof a === 'number' && typeof b === 'number') {
+    return a - b;
+  }
+  if (typeof a === 'string' && typeof b === 'string') {
+    return a < b ? -1 : a > b ? 1 : 0;
+  }
+  return null;
 }
 
 export class ListOfModifier extends Modifier<unknown[]> {
   constructor(
-    private readonly itemType: Validatable,
-    private readonly spec?: ListOfSpec
+    readonly itemType: Validatable,
+    readonly spec?: ListOfSpec
   ) {
     super();
   }
@@ -51,11 +124,59 @@ export class ListOfModifier extends Modifier<unknown[]> {
       ctx.addIssue('list.too_long', `Array length ${value.length} exceeds maximum ${spec.max}`);
     }
 
-    // Validate each item
-    value.forEach((item, index) => {
-      const childCtx = ctx.child(`[${index}]`, item);
-      validateAny(this.itemType, item, childCtx);
-    });
+    // Check uniqueness
+    if (spec?.unique || spec?.uniqueBy !== undefined) {
+      const seen = new Map<string, number>();
+      value.forEach((item, index) => {
+        const keyValue = spec.uniqueBy !== undefined ? getKeyPath(item, spec.uniqueBy) : item;
+        if (keyValue === undefined) return;
+        const key = canonicalJson(keyValue);
+        const first = seen.get(key);
+        if (first === undefined) {
+          seen.set(key, index);
+        } else {
+          const message = spec.uniqueBy !== undefined
+            ? `Items [${first}] and [${index}] share ${spec.uniqueBy} ${key}`
+            : `Items [${first}] and [${index}] are duplicates`;
+          ctx.child(`[${index}]`, item).addIssue('list.duplicate', message);
+        }
+      });
+    }
+
+    // Check ordering - only the first out-of-order pair is reported
+    if (spec?.sorted !== undefined || spec?.sortedBy !== undefined) {
+      const order = spec.sorted ?? 'asc';
+      let prev: { index: number; key: unknown } | undefined;
+      for (let index = 0; index < value.length; index++) {
+        const key = spec.sortedBy !== undefined ? getKeyPath(value[index], spec.sortedBy) : value[index];
+        if (typeof key !== 'number' && typeof key !== 'string') continue;
+        const cmp = prev ? compareKeys(prev.key, key) : null;
+        if (prev && cmp !== null && (order === 'asc' ? cmp > 0 : cmp < 0)) {
+          const by = spec.sortedBy !== undefined ? ` by ${spec.sortedBy}` : '';
+          ctx.child(`[${index}]`, value[index]).addIssue(
+            'list.not_sorted',
+            `Items [${prev.index}] and [${index}] are not in ${order === 'asc' ? 'ascending' : 'descending'} order${by}`
+          );
+          break;
+        }
+        prev = { index, key };
+      }
+    }
+
+    // Validate each item, or only a sample of them (offline runs turn sampling off)
+    const indices = spec?.sample && ctx.options.sampling !== false ? sampleIndices(value.length, spec.sample) : undefined;
+    if (indices && indices.length < value.length) {
+      ctx.addWarning('list.sampled', `Validated ${indices.length} of ${value.length} items (sampled); results are partial`);
+      ctx.markPartial();
+      for (const index of indices) {
+        validateAny(this.itemType, value[index], ctx.child(`[${index}]`, value[index]));
+      }
+    } else {
+      value.forEach((item, index) => {
+        const childCtx = ctx.child(`[${index}]`, item);
+        validateAny(this.itemType, item, childCtx);
+      });
+    }
   }
 
   matches(value: unknown, _ctx: Context): boolean {
@@ -76,10 +197,25 @@ export class ListOfModifier extends Modifier<unknown[]> {
     if (this.spec?.max !== undefined) {
       constraints.push(`maximum ${this.spec.max} items`);
     }
+    if (this.spec?.unique) {
+      constraints.push('unique items');
+    }
+    if (this.spec?.uniqueBy !== undefined) {
+      constraints.push(`unique by \`${this.spec.uniqueBy}\``);
+    }
+    if (this.spec?.sorted !== undefined || this.spec?.sortedBy !== undefined) {
+      const order = this.spec.sorted === 'desc' ? 'descending' : 'ascending';
+      const by = this.spec.sortedBy !== undefined ? ` by \`${this.spec.sortedBy}\`` : '';
+      constraints.push(`sorted ${order}${by}`);
+    }
+    if (this.spec?.sample) {
+      const { head = 0, tail = 0, random = 0, seed = 0 } = this.spec.sample;
+      constraints.push(`sampled: first ${head}, last ${tail}, ${random} random (seed ${seed})`);
+    }
     return {
       name: 'ListOf',
       constraints: constraints.length > 0 ? constraints : undefined,
-      itemType: describeItem(this.itemType),
+      itemType: describeValidatable(this.itemType),
     };
   }
 }
@@ -94,6 +230,17 @@ export class ListOfModifier extends Modifier<unknown[]> {
  * // With constraints
  * ListOf(Str, { min: 1, max: 10 })
  *
+ * // Uniqueness
+ * ListOf(Str, { unique: true })
+ * ListOf({ required: [Field({ key: 'id', value: Str })] }, { uniqueBy: 'id' })
+ *
+ * // Ordering
+ * ListOf(Num, { sorted: 'desc' })
+ * ListOf({ required: [Field({ key: 'timestamp', value: Str })] }, { sortedBy: 'timestamp' })
+ *
+ * // Sampling - validate first/last 100 and 1000 random items of huge arrays
+ * ListOf(Num, { sample: { head: 100, tail: 100, random: 1000, seed: 42 } })
+ *
  * // Complex items
  * ListOf(OneOf('a', 'b', 'c'))
  *
@@ -0,0 +1,73 @@
+// src/modifiers/message.ts
+// Message modifier - report the inner type's failure with a spec-defined code and message
+
+import { Modifier, validateAny, tryMatch, type Validatable, type ObjectSpec, type TypeDescription, describeValidatable } from '../base.js';
+import { ValidationContext, type Context } from '../context.js';
+
+export interface MessageSpec {
+  /** Issue code to report (default: the inner type's first issue code) */
+  code?: string;
+  /** Issue message to report (default: the inner type's first issue message) */
+  message?: string;
+}
+
+export class MessageModifier extends Modifier<unknown> {
+  constructor(
+    private readonly inner: Validatable | ObjectSpec,
+    private readonly report: MessageSpec
+  ) {
+    super();
+  }
+
+  validate(value: unknown, ctx: Context): void {
+    const innerCtx = new ValidationContext([...ctx.path], value, undefined, undefined, ctx.options);
+    validateAny(this.inner as Validatable, value, innerCtx);
+    if (innerCtx.partial) ctx.markPartial();
+
+    // Warnings are not failures of the rule, so they pass through unchanged
+    for (const issue of innerCtx.issues.filter(i => i.level === 'warning')) {
+      let target = issue.meta ? ctx.withMeta(issue.meta) : ctx;
+      for (const segment of issue.path.slice(ctx.path.length)) {
+        target = target.child(segment, undefined);
+      }
+      target.addWarning(issue.code, issue.message);
+    }
+
+    const errors = innerCtx.issues.filter(i => i.level === 'error');
+    const first = errors[0];
+    if (!first) {
+      return;
+    }
+    // One issue for the whole rule, at this path; a fix still applies if it targets the same value
+    const target = first.meta ? ctx.withMeta(first.meta) : ctx;
+    const fix = errors.length === 1 && first.path.length === ctx.path.length ? first.fix : undefined;
+    target.addIssue(this.report.code ?? first.code, this.report.message ?? first.message, fix);
+  }
+
+  matches(value: unknown, ctx: Context): boolean {
+    return tryMatch(this.inner as Validatable, value, ctx);
+  }
+
+  describe(): TypeDescription {
+    const inner = describeValidatable(this.inner);
+    return { ...inner, customIssue: { ...inner.customIssue, ...this.report } };
+  }
+}
+
+/**
+ * Message - report a failure of the inner type as a single issue with a
+ * domain-specific code and/or message, instead of the generic ones
+ * (e.g. `str.pattern_mismatch`). Generated validators report the same issue.
+ *
+ * @example
+ * Field({
+ *   key: 'region',
+ *   value: Message(Str({ match: /^eu-(west|central)-\d$/ }), {
+ *     code: 'policy.bad_region',
+ *     message: 'Region must be one of the EU datacenters',
+ *   }),
+ * })
+ */
+export function Message(inner: Validatable | ObjectSpec, report: MessageSpec): MessageModifier {
+  return new MessageModifier(inner, report);
+}
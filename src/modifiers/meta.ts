@@ -0,0 +1,41 @@
+// src/modifiers/meta.ts
+// Meta modifier - attach metadata to the issues of the inner type
+
+import { Modifier, validateAny, tryMatch, type Validatable, type ObjectSpec, type TypeDescription, describeValidatable } from '../base.js';
+import type { Context } from '../context.js';
+
+export class MetaModifier extends Modifier<unknown> {
+  constructor(
+    private readonly inner: Validatable | ObjectSpec,
+    private readonly meta: Record<string, unknown>
+  ) {
+    super();
+  }
+
+  validate(value: unknown, ctx: Context): void {
+    validateAny(this.inner as Validatable, value, ctx.withMeta(this.meta));
+  }
+
+  matches(value: unknown, ctx: Context): boolean {
+    return tryMatch(this.inner as Validatable, value, ctx);
+  }
+
+  describe(): TypeDescription {
+    const inner = describeValidatable(this.inner);
+    return { ...inner, meta: { ...inner.meta, ...this.meta } };
+  }
+}
+
+/**
+ * Meta - attach key-value metadata (owning team, doc URL, policy ID, ...) to every
+ * issue reported by the inner type. Nested Meta values are merged, inner keys winning.
+ *
+ * @example
+ * Field({
+ *   key: 'license',
+ *   value: Meta(OneOf('MIT', 'Apache-2.0'), { team: 'legal', doc: 'https://example.com/licensing', policy: 'LIC-1' }),
+ * })
+ */
+export function Meta(inner: Validatable | ObjectSpec, meta: Record<string, unknown>): MetaModifier {
+  return new MetaModifier(inner, meta);
+}
@@ -0,0 +1,52 @@
+// src/modifiers/not.ts
+// Not modifier - value must NOT match the inner type
+
+import { Modifier, validateAny, type Validatable, type TypeDescription, describeValidatable } from '../base.js';
+import { ValidationContext, type Context } from '../context.js';
+
+export class NotModifier extends Modifier<unknown> {
+  constructor(readonly inner: Validatable) {
+    super();
+  }
+
+  // Full validation (not just matches()) so nested rules count too
+  private innerAccepts(value: unknown, ctx: Context): boolean {
+    const testCtx = new ValidationContext([], value, undefined, undefined, ctx.options);
+    validateAny(this.inner, value, testCtx);
+    if (testCtx.partial) ctx.markPartial();
+    return testCtx.issues.every(i => i.level !== 'error');
+  }
+
+  validate(value: unknown, ctx: Context): void {
+    if (this.innerAccepts(value, ctx)) {
+      const inner = describeValidatable(this.inner);
+      const what = inner.constraints?.[0] ?? inner.name;
+      ctx.addIssue('not.matched', `Value must not match ${what}`);
+    }
+  }
+
+  matches(value: unknown, ctx: Context): boolean {
+    return !this.innerAccepts(value, ctx);
+  }
+
+  describe(): TypeDescription {
+    return {
+      name: 'Not',
+      not: describeValidatable(this.inner),
+    };
+  }
+}
+
+/**
+ * Not - value must NOT match the inner type
+ *
+ * @example
+ * // Reserved names
+ * Field({ key: 'id', value: AllOf(Str(), Not(/^(admin|root|system)$/)) })
+ *
+ * // Anything but a literal
+ * Not('latest')
+ */
+export function Not(inner: Validatable): NotModifier {
+  return new NotModifier(inner);
+}
@@ -0,0 +1,39 @@
+// src/modifiers/nullable.ts
+// Nullable modifier - accepts null in addition to the inner type
+
+import { Modifier, validateAny, tryMatch, type Validatable, type TypeDescription, describeValidatable } from '../base.js';
+import type { Context } from '../context.js';
+
+export class NullableModifier extends Modifier<unknown> {
+  constructor(readonly inner: Validatable) {
+    super();
+  }
+
+  validate(value: unknown, ctx: Context): void {
+    if (value === null) {
+      return;
+    }
+    validateAny(this.inner, value, ctx);
+  }
+
+  matches(value: unknown, ctx: Context): boolean {
+    return value === null || tryMatch(this.inner, value, ctx);
+  }
+
+  describe(): TypeDescription {
+    return { ...describeValidatable(this.inner), nullable: true };
+  }
+}
+
+/**
+ * Nullable - value may be null, otherwise must match the inner type
+ *
+ * @example
+ * Field({ key: 'end_date', value: Nullable(Str()) })
+ *
+ * // Same as
+ * Field({ key: 'end_date', value: Str(), nullable: true })
+ */
+export function Nullable(inner: Validatable): NullableModifier {
+  return new NullableModifier(inner);
+}
@@ -1,28 +1,11 @@
 // src/modifiers/oneof.ts
 // OneOf modifier - matches one of the given options
 
-import { Modifier, validateAny, tryMatch, type Validatable, type TypeDescription, isLiteralValue, isType, isModifier, isObjectSpec } from '../base.js';
+import { Modifier, validateAny, tryMatch, type Validatable, type TypeDescription, isLiteralValue, describeValidatable } from '../base.js';
 import type { Context } from '../context.js';
 
-// Helper to describe any Validatable
-function describeOption(v: Validatable): TypeDescription {
-  if (isType(v)) {
-    return v.describe();
-  } else if (isModifier(v)) {
-    return v.describe();
-  } else if (isLiteralValue(v)) {
-    if (v instanceof RegExp) {
-      return { name: 'Pattern', constraints: [`matches \`${v}\``] };
-    }
-    return { name: 'Literal', constraints: [`equals ${JSON.stringify(v)}`] };
-  } else if (isObjectSpec(v)) {
-    return { name: 'Object', spec: v };
-  }
-  return { name: 'Unknown' };
-}
-
 export class OneOfModifier extends Modifier<unknown> {
-  constructor(private readonly options: Validatable[]) {
+  constructor(readonly options: Validatable[]) {
     super();
   }
 
@@ -61,7 +44,7 @@ export class OneOfModifier extends Modifier<unknown> {
   describe(): TypeDescription {
     return {
       name: 'OneOf',
-      oneOf: this.options.map(describeOption),
+      oneOf: this.options.map(describeValidatable),
     };
   }
 }
@@ -0,0 +1,112 @@
+// src/modifiers/phase.ts
+// Phase modifier - defer the inner type's checks to a later validation phase
+
+import { Modifier, validateAny, tryMatch, type Validatable, type ObjectSpec, type TypeDescription, describeValidatable } from '../base.js';
+import { ValidationContext, type Context } from '../context.js';
+
+/** A check put off until its phase runs */
+interface DeferredCheck {
+  phase: number;
+  inner: Validatable | ObjectSpec;
+  value: unknown;
+  ctx: Context;
+}
+
+/** State of the phased validation in progress */
+interface PhasedRun {
+  root: ValidationContext;
+  /** Phase being run; the main pass is phase 0 */
+  current: number;
+  pending: DeferredCheck[];
+}
+
+let activeRun: PhasedRun | undefined;
+
+export class PhaseModifier extends Modifier<unknown> {
+  constructor(
+    readonly phase: number,
+    readonly inner: Validatable | ObjectSpec
+  ) {
+    super();
+  }
+
+  validate(value: unknown, ctx: Context): void {
+    // Checks made on behalf of another context (OneOf trials, Message, Not) run at once,
+    // since their issues are needed before the phased run gets to them
+    const run = activeRun;
+    if (run && this.phase > run.current && ctx instanceof ValidationContext && ctx.reportsTo(run.root)) {
+      run.pending.push({ phase: this.phase, inner: this.inner, value, ctx });
+      return;
+    }
+    validateAny(this.inner as Validatable, value, ctx);
+  }
+
+  matches(value: unknown, ctx: Context): boolean {
+    return tryMatch(this.inner as Validatable, value, ctx);
+  }
+
+  describe(): TypeDescription {
+    return { ...describeValidatable(this.inner), phase: this.phase };
+  }
+}
+
+/**
+ * Phase - check the inner type in a later validation phase. The main pass is phase 0;
+ * afterwards the deferred checks run phase by phase, lowest first, and the remaining
+ * phases are skipped (with a `phase.skipped` info issue) as soon as a phase has
+ * reported errors. Use it to keep follow-on issues and costly checks such as checksums
+ * away from bundles whose structure is already broken.
+ *
+ * @example
+ * Directory({
+ *   content: {
+ *     required: [
+ *       JsonFile({ path: 'manifest.json', required: [Field({ key: 'name', value: Str() })] }),
+ *       Phase(1, File({ path: 'payload.bin', checksum: { value: 'e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855' } })),
+ *     ],
+ *   },
+ * })
+ */
+export function Phase(order: number, inner: Validatable | ObjectSpec): PhaseModifier {
+  if (!Number.isInteger(order) || order < 1) {
+    throw new Error(`Phase() order must be a positive integer, got ${String(order)}`);
+  }
+  return new PhaseModifier(order, inner);
+}
+
+/**
+ * Run `pass` as the main phase of a validation reporting to `root`, then the checks
+ * deferred by Phase() in order, stopping at the first phase that leaves errors behind
+ */
+export function validateInPhases(root: ValidationContext, pass: () => void): void {
+  const outer = activeRun;
+  const run: PhasedRun = { root, current: 0, pending: [] };
+  activeRun = run;
+  try {
+    pass();
+    while (run.pending.length > 0) {
+      if (root.issues.some(i => i.level === 'error')) {
+        const phases = [...new Set(run.pending.map(c => c.phase))].sort((a, b) => a - b);
+        for (const phase of phases) {
+          const count = run.pending.filter(c => c.phase === phase).length;
+          root.issues.push({
+            level: 'info',
+            code: 'phase.skipped',
+            message: `Skipped ${count} check${count === 1 ? '' : 's'} of phase ${phase} because an earlier phase reported errors`,
+            path: [],
+          });
+        }
+        return;
+      }
+      const next = Math.min(...run.pending.map(c => c.phase));
+      const checks = run.pending.filter(c => c.phase === next);
+      run.pending = run.pending.filter(c => c.phase !== next);
+      run.current = next;
+      for (const check of checks) {
+        validateAny(check.inner as Validatable, check.value, check.ctx);
+      }
+    }
+  } finally {
+    activeRun = outer;
+  }
+}
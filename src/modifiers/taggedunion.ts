@@ -0,0 +1,79 @@
+// src/modifiers/taggedunion.ts
+// TaggedUnion modifier - dispatches to a variant by a discriminator field
+
+import { Modifier, validateAny, tryMatch, type Validatable, type ObjectSpec, type TypeDescription, describeValidatable } from '../base.js';
+import type { Context } from '../context.js';
+
+export class TaggedUnionModifier extends Modifier<Record<string, unknown>> {
+  constructor(
+    readonly discriminator: string,
+    readonly variants: Record<string, Validatable | ObjectSpec>
+  ) {
+    super();
+  }
+
+  private variantFor(value: unknown): Validatable | ObjectSpec | undefined {
+    if (value === null || typeof value !== 'object' || Array.isArray(value)) return undefined;
+    const tag = (value as Record<string, unknown>)[this.discriminator];
+    if (typeof tag !== 'string' || !Object.hasOwn(this.variants, tag)) return undefined;
+    return this.variants[tag];
+  }
+
+  validate(value: unknown, ctx: Context): void {
+    if (value === null || typeof value !== 'object' || Array.isArray(value)) {
+      ctx.addIssue('type.mismatch', `Expected object, got ${Array.isArray(value) ? 'array' : typeof value}`);
+      return;
+    }
+
+    const tag = (value as Record<string, unknown>)[this.discriminator];
+    if (tag === undefined) {
+      ctx.addIssue('field.missing', `Missing required field: ${this.discriminator}`);
+      return;
+    }
+
+    const variant = this.variantFor(value);
+    if (variant === undefined) {
+      ctx.child(this.discriminator, tag).addIssue(
+        'union.unknown_tag',
+        `Unknown ${this.discriminator} ${JSON.stringify(tag)}; expected one of: ${Object.keys(this.variants).join(', ')}`
+      );
+      return;
+    }
+
+    // Only the selected variant runs
+    validateAny(variant as Validatable, value, ctx);
+  }
+
+  matches(value: unknown, ctx: Context): boolean {
+    const variant = this.variantFor(value);
+    return variant !== undefined && tryMatch(variant as Validatable, value, ctx);
+  }
+
+  describe(): TypeDescription {
+    const variants: Record<string, TypeDescription> = {};
+    for (const [tag, variant] of Object.entries(this.variants)) {
+      variants[tag] = describeValidatable(variant);
+    }
+    return {
+      name: 'TaggedUnion',
+      discriminator: this.discriminator,
+      variants,
+    };
+  }
+}
+
+/**
+ * TaggedUnion - object whose discriminator field selects the variant to validate against
+ *
+ * @example
+ * TaggedUnion('kind', {
+ *   circle: { required: [Field({ key: 'radius', value: Num({ min: 0 }) })] },
+ *   rect: { required: [Field({ key: 'width', value: Num }), Field({ key: 'height', value: Num })] },
+ * })
+ */
+export function TaggedUnion(
+  discriminator: string,
+  variants: Record<string, Validatable | ObjectSpec>
+): TaggedUnionModifier {
+  return new TaggedUnionModifier(discriminator, variants);
+}
@@ -0,0 +1,167 @@
+// src/normalize.ts
+// Normalization - fill in field defaults, coerce string inputs and optionally drop undeclared fields
+
+import type { TypeDescription } from './base.js';
+import type { Issue } from './context.js';
+import { isExactAsNumber } from './types/primitives.js';
+import { isRef } from './codegen/base.js';
+
+export interface NormalizeOptions {
+  /** Drop fields the spec does not declare (objects whose fields are all declared) */
+  stripUnknown?: boolean;
+  /**
+   * Accept strings for numbers ("42") and booleans ("true" / "false"), and single
+   * values for lists, converting them and reporting a `coerce.*` info issue each.
+   * Decimal strings a number would round are kept as strings.
+   */
+  coerce?: boolean;
+}
+
+interface NormalizeState {
+  options: NormalizeOptions;
+  defs: Map<string, TypeDescription>;
+  issues: Issue[];
+}
+
+const NUMERIC = /^[+-]?(\d+\.?\d*|\.\d+)([eE][+-]?\d+)?$/;
+
+type Children = { required?: TypeDescription[] | undefined; optional?: TypeDescription[] | undefined };
+
+function allChildren(children: Children | undefined): TypeDescription[] {
+  return [...(children?.required ?? []), ...(children?.optional ?? [])];
+}
+
+function isPlainObject(v: unknown): v is Record<string, unknown> {
+  return v !== null && typeof v === 'object' && !Array.isArray(v);
+}
+
+// Named type bodies by name, so recursive uses (described as Ref) can be resolved
+function collectDefinitions(desc: TypeDescription, defs: Map<string, TypeDescription>): void {
+  if (desc.ref !== undefined && !isRef(desc) && !defs.has(desc.ref)) {
+    defs.set(desc.ref, desc);
+  }
+  for (const child of allChildren(desc.children)) collectDefinitions(child, defs);
+  for (const option of desc.oneOf ?? []) collectDefinitions(option, defs);
+  for (const part of desc.allOf ?? []) collectDefinitions(part, defs);
+  for (const variant of Object.values(desc.variants ?? {})) collectDefinitions(variant, defs);
+  if (desc.itemType) collectDefinitions(desc.itemType, defs);
+}
+
+/**
+ * Return a normalized copy of a JSON value: missing fields that declare a default
+ * get a copy of it, and with stripUnknown, undeclared fields are dropped.
+ * Values the spec does not describe field by field (e.g. OneOf options) are copied as they are.
+ * With coerce, the info issues for each conversion are appended to `issues`.
+ */
+export function normalizeValue(desc: TypeDescription, value: unknown, options: NormalizeOptions = {}, issues: Issue[] = []): unknown {
+  const defs = new Map<string, TypeDescription>();
+  collectDefinitions(desc, defs);
+  return normalize(desc, structuredClone(value), { options, defs, issues }, []);
+}
+
+function normalize(desc: TypeDescription, value: unknown, state: NormalizeState, path: string[]): unknown {
+  const { options, defs } = state;
+  if (isRef(desc) && desc.ref !== undefined) {
+    const def = defs.get(desc.ref);
+    return def ? normalize(def, value, state, path) : value;
+  }
+
+  if (options.coerce) {
+    value = coerce(desc, value, state, path);
+  }
+
+  if (desc.itemType && Array.isArray(value)) {
+    const itemType = desc.itemType;
+    return value.map((item, index) => normalize(itemType, item, state, [...path, `[${index}]`]));
+  }
+
+  if (!isPlainObject(value)) {
+    return value;
+  }
+
+  // TaggedUnion: the selected variant's fields, plus the tag
+  if (desc.variants && desc.discriminator !== undefined) {
+    const tag = value[desc.discriminator];
+    const variant = typeof tag === 'string' && Object.hasOwn(desc.variants, tag) ? desc.variants[tag] : undefined;
+    if (!variant) {
+      return value;
+    }
+    const result = normalize(variant, value, state, path);
+    return isPlainObject(result) ? { ...result, [desc.discriminator]: tag } : result;
+  }
+
+  // AllOf: every part fills in its defaults; only fields no part declares are dropped
+  if (desc.allOf) {
+    let result: unknown = value;
+    for (const part of desc.allOf) {
+      result = normalize(part, result, { ...state, options: { ...options, stripUnknown: false } }, path);
+    }
+    const declared = desc.allOf.map(part => declaredKeys(part, defs));
+    if (options.stripUnknown && isPlainObject(result) && declared.every(keys => keys !== null)) {
+      const keys = new Set(declared.flatMap(k => [...k!]));
+      return Object.fromEntries(Object.entries(result).filter(([key]) => keys.has(key)));
+    }
+    return result;
+  }
+
+  if (desc.children) {
+    const result: Record<string, unknown> = options.stripUnknown ? {} : { ...value };
+    for (const child of allChildren(desc.children)) {
+      if (child.name !== 'Field' || child.key === undefined) continue;
+      if (Object.hasOwn(value, child.key)) {
+        result[child.key] = normalize(child, value[child.key], state, [...path, child.key]);
+      } else if (child.default !== undefined) {
+        result[child.key] = normalize(child, structuredClone(child.default), state, [...path, child.key]);
+      }
+    }
+    return result;
+  }
+
+  return value;
+}
+
+// Convert a string (or single value) the type would reject into the value it means
+function coerce(desc: TypeDescription, value: unknown, state: NormalizeState, path: string[]): unknown {
+  const type = desc.name === 'Field' ? desc.summary : desc.name;
+  const report = (code: string, message: string) => {
+    state.issues.push({ level: 'info', code, message, path });
+  };
+
+  if (typeof value === 'string') {
+    const text = value.trim();
+    if (type === 'Decimal' && NUMERIC.test(text) && !isExactAsNumber(text)) {
+      // A number would round it; the string keeps every digit (and fails as a string)
+      report('coerce.inexact', `Kept string "${value}": a number cannot hold it exactly`);
+      return value;
+    }
+    if ((type === 'Number' || type === 'Decimal') && NUMERIC.test(text)) {
+      report('coerce.number', `Coerced string "${value}" to number`);
+      return Number(text);
+    }
+    if (type === 'Boolean' && (text === 'true' || text === 'false')) {
+      report('coerce.boolean', `Coerced string "${value}" to boolean`);
+      return text === 'true';
+    }
+  }
+  if (desc.itemType && !Array.isArray(value) && value !== null && value !== undefined) {
+    report('coerce.list', 'Coerced single value to a one-item list');
+    return [value];
+  }
+  return value;
+}
+
+// Field keys an object type declares; null when it is not described field by field
+function declaredKeys(desc: TypeDescription, defs: Map<string, TypeDescription>): Set<string> | null {
+  if (isRef(desc) && desc.ref !== undefined) {
+    const def = defs.get(desc.ref);
+    return def ? declaredKeys(def, defs) : null;
+  }
+  if (desc.children) {
+    return new Set(allChildren(desc.children).flatMap(child => child.name === 'Field' && child.key !== undefined ? [child.key] : []));
+  }
+  if (desc.allOf) {
+    const parts = desc.allOf.map(part => declaredKeys(part, defs));
+    return parts.every(keys => keys !== null) ? new Set(parts.flatMap(k => [...k!])) : null;
+  }
+  return null;
+}
@@ -0,0 +1,540 @@
+// src/openapi.ts
+// OpenAPI import - write a spec file for the schemas of an OpenAPI 3.x document
+
+export interface OpenApiImportOptions {
+  /**
+   * Also define the JSON request and response bodies of each operation, named after
+   * its operationId (e.g. `createPetRequest`, `createPetResponse201`)
+   */
+  operations?: boolean;
+  /** Definition that becomes the root of the spec (default: the last one) */
+  root?: string;
+}
+
+export interface OpenApiImport {
+  /** Spec file source: one Def() per schema, the root last */
+  source: string;
+  /** Names of the definitions, in document order */
+  definitions: string[];
+  /** Keywords the spec cannot express, prefixed with their location in the document */
+  warnings: string[];
+}
+
+type Schema = Record<string, unknown>;
+
+interface Definition {
+  name: string;
+  ident: string;
+  schema: unknown;
+  pointer: string;
+  description?: string | undefined;
+}
+
+interface ImportState {
+  document: Schema;
+  /** Identifiers of the component schemas, by name */
+  components: Map<string, string>;
+  warnings: string[];
+  /** Local references being inlined, to stop on cycles */
+  inlining: string[];
+  /** The definition being rendered refers to another definition */
+  refersToDefinition: boolean;
+  usesAny: boolean;
+}
+
+const METHODS = ['get', 'put', 'post', 'delete', 'options', 'head', 'patch', 'trace'];
+
+// Spec globals, JavaScript keywords and common built-ins, which definitions must not shadow
+const RESERVED = new Set([
+  'Str', 'Num', 'Bool', 'Decimal', 'Field', 'File', 'Directory', 'JsonFile', 'When', 'OneOf', 'AllOf', 'Not',
+  'TaggedUnion', 'ListOf', 'Nullable', 'Def', 'Meta', 'Extend', 'Deprecated', 'Message', 'Generic', 'Phase',
+  'Import', 'exports', 'console', 'JsonValue',
+  'Object', 'Array', 'String', 'Number', 'Boolean', 'Symbol', 'Date', 'RegExp', 'Error', 'Map', 'Set',
+  'Promise', 'JSON', 'Math', 'globalThis',
+  'break', 'case', 'catch', 'class', 'const', 'continue', 'debugger', 'default', 'delete', 'do', 'else',
+  'enum', 'export', 'extends', 'false', 'finally', 'for', 'function', 'if', 'import', 'in', 'instanceof',
+  'let', 'new', 'null', 'return', 'static', 'super', 'switch', 'this', 'throw', 'true', 'try', 'typeof',
+  'undefined', 'NaN', 'Infinity', 'var', 'void', 'while', 'with', 'yield', 'await', 'arguments', 'eval',
+]);
+
+// Accepts any JSON value, for schemas without constraints
+const ANY = `const JsonValue = Def('JsonValue', self => OneOf(Str(), Num(), Bool(), null, ListOf(self), { optional: [] }));`;
+
+function isSchemaObject(v: unknown): v is Schema {
+  return v !== null && typeof v === 'object' && !Array.isArray(v);
+}
+
+/**
+ * Write a spec file for an OpenAPI 3.0 / 3.1 document (parsed JSON).
+ * Each component schema becomes a Def() named after it; `$ref`s to them become
+ * references, so recursive schemas stay recursive. With `operations`, JSON request
+ * and response bodies get Defs too. The last Def is the spec's root, so the file can
+ * be validated against or passed to --codegen directly.
+ *
+ * Keywords the spec has no rule for (e.g. multipleOf, additionalProperties) are left
+ * out and listed in `warnings`; `format` is an annotation and is ignored.
+ */
+export function importOpenApi(document: unknown, options: OpenApiImportOptions = {}): OpenApiImport {
+  const version = isSchemaObject(document) ? document['openapi'] : undefined;
+  if (!isSchemaObject(document) || typeof version !== 'string' || !version.startsWith('3.')) {
+    throw new Error(`Not an OpenAPI 3 document${typeof version === 'string' ? ` (openapi: ${version})` : ''}`);
+  }
+
+  const state: ImportState = {
+    document,
+    components: new Map(),
+    warnings: [],
+    inlining: [],
+    refersToDefinition: false,
+    usesAny: false,
+  };
+  const definitions: Definition[] = [];
+  // JsonValue is the Def behind schemas without constraints
+  const names = new Set<string>(['JsonValue']);
+  const idents = new Set<string>();
+  const define = (name: string, schema: unknown, pointer: string, description?: unknown): Definition => {
+    const unique = uniqueName(name, names, '_');
+    const ident = identifier(unique);
+    const definition = {
+      name: unique,
+      ident: uniqueName(RESERVED.has(ident) ? `${ident}Schema` : ident, idents, ''),
+      schema,
+      pointer,
+      description: typeof description === 'string' ? description : undefined,
+    };
+    definitions.push(definition);
+    return definition;
+  };
+
+  const components = isSchemaObject(document['components']) ? document['components'] : {};
+  const schemas = isSchemaObject(components['schemas']) ? components['schemas'] : {};
+  for (const [name, schema] of Object.entries(schemas)) {
+    const pointer = `#/components/schemas/${escapePointer(name)}`;
+    const description = isSchemaObject(schema) ? schema['description'] : undefined;
+    state.components.set(name, define(name, schema, pointer, description).ident);
+  }
+
+  if (options.operations) {
+    const paths = isSchemaObject(document['paths']) ? document['paths'] : {};
+    for (const [route, item] of Object.entries(paths)) {
+      if (!isSchemaObject(item)) continue;
+      for (const method of METHODS) {
+        const operation = item[method];
+        if (!isSchemaObject(operation)) continue;
+        const base = typeof operation['operationId'] === 'string' ? operation['operationId'] : operationName(method, route);
+        const pointer = `#/paths/${escapePointer(route)}/${method}`;
+
+        const request = resolve(operation['requestBody'], state);
+        const requestSchema = jsonSchema(request);
+        if (requestSchema) {
+          define(`${base}Request`, requestSchema.schema, `${pointer}/requestBody/content/${escapePointer(requestSchema.mediaType)}/schema`, request?.['description']);
+        }
+
+        const responses = isSchemaObject(operation['responses']) ? operation['responses'] : {};
+        for (const [status, value] of Object.entries(responses)) {
+          const response = resolve(value, state);
+          const responseSchema = jsonSchema(response);
+          if (responseSchema) {
+            const suffix = status === 'default' ? 'Default' : status;
+            define(`${base}Response${suffix}`, responseSchema.schema, `${pointer}/responses/${status}/content/${escapePointer(responseSchema.mediaType)}/schema`, response?.['description']);
+          }
+        }
+      }
+    }
+  }
+
+  if (definitions.length === 0) {
+    throw new Error('The document defines no schemas');
+  }
+
+  // The root goes last: the spec's last definition is its root
+  const ordered = [...definitions];
+  if (options.root !== undefined) {
+    const index = ordered.findIndex(d => d.name === options.root);
+    if (index < 0) {
+      throw new Error(`Unknown definition: ${options.root} (available: ${definitions.map(d => d.name).join(', ')})`);
+    }
+    ordered.push(...ordered.splice(index, 1));
+  }
+
+  const body = ordered.map(definition => renderDefinition(definition, state));
+  const info = isSchemaObject(document['info']) ? document['info'] : {};
+  const title = typeof info['title'] === 'string' ? ` "${info['title']}"` : '';
+  const infoVersion = typeof info['version'] === 'string' ? ` ${info['version']}` : '';
+  const lines = [
+    `// Spec file generated by SpecSpec from OpenAPI document${title}${infoVersion}`,
+    `// Usage: specspec <this-file> --codegen rust -o src/validator.rs`,
+    '',
+    ...(state.usesAny ? [ANY, ''] : []),
+    body.join('\n\n'),
+    '',
+  ];
+  return { source: lines.join('\n'), definitions: definitions.map(d => d.name), warnings: state.warnings };
+}
+
+function renderDefinition(definition: Definition, state: ImportState): string {
+  state.refersToDefinition = false;
+  const body = render(definition.schema, definition.pointer, state, '');
+  // Bodies that refer to other definitions are resolved on first use, so declaration order does not matter
+  const lazy = state.refersToDefinition ? `() => ${body.startsWith('{') ? `(${body})` : body}` : body;
+  const options = definition.description !== undefined ? `, { description: ${quote(definition.description)} }` : '';
+  return `const ${definition.ident} = Def(${quote(definition.name)}, ${lazy}${options});`;
+}
+
+// ─── Schemas ───
+
+/** Render a schema as a spec expression */
+function render(schema: unknown, pointer: string, state: ImportState, indent: string): string {
+  const { code, nullable } = renderNonNull(schema, pointer, state, indent);
+  return nullable && code !== 'null' ? `Nullable(${code})` : code;
+}
+
+/** Render a schema without its null option, which fields express as `nullable: true` */
+function renderNonNull(schema: unknown, pointer: string, state: ImportState, indent: string): { code: string; nullable: boolean } {
+  if (schema === true) {
+    return { code: anyValue(state), nullable: false };
+  }
+  if (schema === false) {
+    return { code: `Not(${anyValue(state)})`, nullable: false };
+  }
+  if (!isSchemaObject(schema)) {
+    warn(state, pointer, 'schema is not an object');
+    return { code: anyValue(state), nullable: false };
+  }
+
+  const ref = schema['$ref'];
+  if (typeof ref === 'string') {
+    return { code: renderRef(ref, pointer, state, indent), nullable: false };
+  }
+
+  const declared = schema['type'];
+  const types = (Array.isArray(declared) ? declared : declared !== undefined ? [declared] : []).filter((t): t is string => typeof t === 'string');
+  const nullable = schema['nullable'] === true || types.includes('null');
+  const nonNull = types.filter(t => t !== 'null');
+
+  const parts: string[] = [];
+  if ('const' in schema || Array.isArray(schema['enum'])) {
+    parts.push(renderEnum(schema, pointer, state));
+  } else if (nonNull.length > 1) {
+    parts.push(`OneOf(${nonNull.map(t => renderType(t, schema, pointer, state, indent)).join(', ')})`);
+  } else {
+    const type = nonNull[0] ?? impliedType(schema);
+    if (type !== undefined) {
+      parts.push(renderType(type, schema, pointer, state, indent));
+    } else if (types.includes('null')) {
+      return { code: 'null', nullable: false };
+    }
+  }
+
+  if (Array.isArray(schema['allOf'])) {
+    schema['allOf'].forEach((part, i) => parts.push(render(part, `${pointer}/allOf/${i}`, state, indent)));
+  }
+  for (const keyword of ['oneOf', 'anyOf']) {
+    const options = schema[keyword];
+    if (Array.isArray(options)) {
+      parts.push(renderUnion(keyword, options, schema['discriminator'], pointer, state, indent));
+    }
+  }
+  if ('not' in schema) {
+    parts.push(`Not(${render(schema['not'], `${pointer}/not`, state, indent)})`);
+  }
+
+  const code = parts.length === 0 ? anyValue(state) : parts.length === 1 ? parts[0]! : `AllOf(${parts.join(', ')})`;
+  return { code, nullable };
+}
+
+function renderRef(ref: string, pointer: string, state: ImportState, indent: string): string {
+  const component = /^#\/components\/schemas\/([^/]+)$/.exec(ref);
+  if (component) {
+    const ident = state.components.get(unescapePointer(component[1]!));
+    if (ident !== undefined) {
+      state.refersToDefinition = true;
+      return ident;
+    }
+    warn(state, pointer, `$ref ${ref} does not exist`);
+    return anyValue(state);
+  }
+  if (!ref.startsWith('#')) {
+    warn(state, pointer, `external $ref ${ref} is not supported`);
+    return anyValue(state);
+  }
+  // Other local references are inlined
+  if (state.inlining.includes(ref)) {
+    warn(state, pointer, `recursive $ref ${ref} outside components/schemas is not supported`);
+    return anyValue(state);
+  }
+  const target = lookup(state.document, ref);
+  if (target === undefined) {
+    warn(state, pointer, `$ref ${ref} does not exist`);
+    return anyValue(state);
+  }
+  state.inlining.push(ref);
+  try {
+    return render(target, ref, state, indent);
+  } finally {
+    state.inlining.pop();
+  }
+}
+
+// The type a schema without `type` constrains, from its keywords
+function impliedType(schema: Schema): string | undefined {
+  if (['properties', 'required', 'additionalProperties', 'patternProperties'].some(k => k in schema)) return 'object';
+  if (['items', 'prefixItems', 'minItems', 'maxItems', 'uniqueItems'].some(k => k in schema)) return 'array';
+  if (['minLength', 'maxLength', 'pattern'].some(k => k in schema)) return 'string';
+  if (['minimum', 'maximum', 'exclusiveMinimum', 'exclusiveMaximum', 'multipleOf'].some(k => k in schema)) return 'number';
+  return undefined;
+}
+
+function renderType(type: string, schema: Schema, pointer: string, state: ImportState, indent: string): string {
+  switch (type) {
+    case 'string':
+      return renderString(schema, pointer, state);
+    case 'integer':
+    case 'number':
+      return renderNumber(type === 'integer', schema, pointer, state);
+    case 'boolean':
+      return 'Bool()';
+    case 'array':
+      return renderArray(schema, pointer, state, indent);
+    case 'object':
+      return renderObject(schema, pointer, state, indent);
+    default:
+      warn(state, pointer, `unknown type ${type}`);
+      return anyValue(state);
+  }
+}
+
+function renderString(schema: Schema, pointer: string, state: ImportState): string {
+  const spec: string[] = [];
+  for (const keyword of ['minLength', 'maxLength']) {
+    const value = schema[keyword];
+    if (typeof value === 'number') spec.push(`${keyword}: ${value}`);
+  }
+  const pattern = schema['pattern'];
+  if (typeof pattern === 'string') {
+    const regex = regexLiteral(pattern);
+    if (regex !== undefined) {
+      spec.push(`match: ${regex}`);
+    } else {
+      warn(state, pointer, `pattern ${JSON.stringify(pattern)} is not a valid regular expression`);
+    }
+  }
+  return spec.length > 0 ? `Str({ ${spec.join(', ')} })` : 'Str()';
+}
+
+function renderNumber(integer: boolean, schema: Schema, pointer: string, state: ImportState): string {
+  const spec: string[] = integer ? ['integer: true'] : [];
+  const bound = (inclusive: string, exclusive: string, direction: 1 | -1, key: string): void => {
+    const value = schema[inclusive];
+    const excl = schema[exclusive];
+    // 3.1: exclusiveMinimum is the bound; 3.0: a flag on minimum
+    const limit = typeof excl === 'number' ? excl : typeof value === 'number' ? value : undefined;
+    if (limit === undefined) return;
+    if (typeof excl === 'number' || excl === true) {
+      if (integer) {
+        spec.push(`${key}: ${direction > 0 ? Math.floor(limit) + 1 : Math.ceil(limit) - 1}`);
+        return;
+      }
+      warn(state, pointer, `${exclusive} is checked as inclusive`);
+    }
+    spec.push(`${key}: ${limit}`);
+  };
+  bound('minimum', 'exclusiveMinimum', 1, 'min');
+  bound('maximum', 'exclusiveMaximum', -1, 'max');
+  if ('multipleOf' in schema) {
+    warn(state, pointer, 'multipleOf is not supported');
+  }
+  return spec.length > 0 ? `Num({ ${spec.join(', ')} })` : 'Num()';
+}
+
+function renderArray(schema: Schema, pointer: string, state: ImportState, indent: string): string {
+  const items = 'items' in schema ? render(schema['items'], `${pointer}/items`, state, indent) : anyValue(state);
+  const spec: string[] = [];
+  if (typeof schema['minItems'] === 'number') spec.push(`min: ${schema['minItems']}`);
+  if (typeof schema['maxItems'] === 'number') spec.push(`max: ${schema['maxItems']}`);
+  if (schema['uniqueItems'] === true) spec.push('unique: true');
+  for (const keyword of ['prefixItems', 'contains']) {
+    if (keyword in schema) warn(state, pointer, `${keyword} is not supported`);
+  }
+  return spec.length > 0 ? `ListOf(${items}, { ${spec.join(', ')} })` : `ListOf(${items})`;
+}
+
+function renderObject(schema: Schema, pointer: string, state: ImportState, indent: string): string {
+  const properties = isSchemaObject(schema['properties']) ? schema['properties'] : {};
+  const required = Array.isArray(schema['required']) ? schema['required'].filter((k): k is string => typeof k === 'string') : [];
+  for (const keyword of ['additionalProperties', 'patternProperties', 'propertyNames', 'minProperties', 'maxProperties', 'dependentRequired']) {
+    if (keyword in schema && !(keyword === 'additionalProperties' && schema[keyword] === true)) {
+      warn(state, pointer, `${keyword} is not supported`);
+    }
+  }
+
+  const inner = `${indent}    `;
+  const fields = (keys: string[], optional: boolean): string =>
+    keys.map(key => `${inner}${renderField(key, properties[key], optional, `${pointer}/properties/${escapePointer(key)}`, state, inner)},\n`).join('');
+  const optionalKeys = Object.keys(properties).filter(k => !required.includes(k));
+
+  const lists: string[] = [];
+  if (required.length > 0) lists.push(`${indent}  required: [\n${fields(required, false)}${indent}  ],\n`);
+  if (optionalKeys.length > 0) lists.push(`${indent}  optional: [\n${fields(optionalKeys, true)}${indent}  ],\n`);
+  return lists.length > 0 ? `{\n${lists.join('')}${indent}}` : '{ optional: [] }';
+}
+
+function renderField(key: string, schema: unknown, optional: boolean, pointer: string, state: ImportState, indent: string): string {
+  const spec = [`key: ${quote(key)}`];
+  if (schema === undefined) {
+    // Listed in `required` without a schema: only its presence is checked
+    return `Field({ ${spec.join(', ')} })`;
+  }
+  const property = isSchemaObject(schema) ? schema : {};
+  if (typeof property['description'] === 'string') spec.push(`description: ${quote(property['description'])}`);
+  const { code, nullable } = renderNonNull(schema, pointer, state, indent);
+  spec.push(`value: ${code}`);
+  if (optional) spec.push('optional: true');
+  if (nullable) spec.push('nullable: true');
+  if (property['deprecated'] === true) spec.push('deprecated: true');
+  // Defaults only apply to optional fields
+  if (optional && 'default' in property) spec.push(`default: ${literal(property['default'])}`);
+  return `Field({ ${spec.join(', ')} })`;
+}
+
+function renderEnum(schema: Schema, pointer: string, state: ImportState): string {
+  const values = 'const' in schema ? [schema['const']] : schema['enum'] as unknown[];
+  const literals: string[] = [];
+  for (const value of values) {
+    if (value === null || ['string', 'number', 'boolean'].includes(typeof value)) {
+      literals.push(literal(value));
+    } else {
+      warn(state, pointer, `${'const' in schema ? 'const' : 'enum'} value ${JSON.stringify(value)} is not supported`);
+    }
+  }
+  if (literals.length === 0) return anyValue(state);
+  return literals.length === 1 ? literals[0]! : `OneOf(${literals.join(', ')})`;
+}
+
+function renderUnion(keyword: string, options: unknown[], discriminator: unknown, pointer: string, state: ImportState, indent: string): string {
+  const rendered = options.map((option, i) => render(option, `${pointer}/${keyword}/${i}`, state, indent));
+  const property = isSchemaObject(discriminator) ? discriminator['propertyName'] : undefined;
+  if (typeof property !== 'string') {
+    return `OneOf(${rendered.join(', ')})`;
+  }
+
+  // Tag values: the explicit mapping, else the names of the referenced schemas
+  const mapping = isSchemaObject(discriminator) && isSchemaObject(discriminator['mapping']) ? discriminator['mapping'] : {};
+  const tags = new Map<string, string>();
+  for (const [tag, ref] of Object.entries(mapping)) {
+    if (typeof ref === 'string') tags.set(ref.startsWith('#') ? ref : `#/components/schemas/${ref}`, tag);
+  }
+  const variants: string[] = [];
+  for (const [i, option] of options.entries()) {
+    const ref = isSchemaObject(option) ? option['$ref'] : undefined;
+    const name = typeof ref === 'string' ? /^#\/components\/schemas\/([^/]+)$/.exec(ref)?.[1] : undefined;
+    if (typeof ref !== 'string' || name === undefined) {
+      warn(state, `${pointer}/discriminator`, 'discriminator needs $refs to component schemas; checked as a plain union');
+      return `OneOf(${rendered.join(', ')})`;
+    }
+    variants.push(`${indent}  ${propertyKey(tags.get(ref) ?? unescapePointer(name))}: ${rendered[i]},\n`);
+  }
+  return `TaggedUnion(${quote(property)}, {\n${variants.join('')}${indent}})`;
+}
+
+function anyValue(state: ImportState): string {
+  state.usesAny = true;
+  return 'JsonValue';
+}
+
+function warn(state: ImportState, pointer: string, message: string): void {
+  state.warnings.push(`${pointer}: ${message}`);
+}
+
+// ─── Operations ───
+
+/** Follow a `$ref` to a request body or response */
+function resolve(value: unknown, state: ImportState): Schema | undefined {
+  let current = value;
+  for (let hops = 0; isSchemaObject(current) && typeof current['$ref'] === 'string' && hops < 8; hops++) {
+    current = lookup(state.document, current['$ref']);
+  }
+  return isSchemaObject(current) ? current : undefined;
+}
+
+/** The schema of the JSON content of a request body or response */
+function jsonSchema(body: Schema | undefined): { mediaType: string; schema: unknown } | undefined {
+  const content = body && isSchemaObject(body['content']) ? body['content'] : {};
+  const mediaType = 'application/json' in content
+    ? 'application/json'
+    : Object.keys(content).find(type => /^application\/([\w.-]+\+)?json\b/.test(type));
+  const media = mediaType !== undefined ? content[mediaType] : undefined;
+  if (mediaType === undefined || !isSchemaObject(media) || !('schema' in media)) {
+    return undefined;
+  }
+  return { mediaType, schema: media['schema'] };
+}
+
+// `get /pets/{petId}` -> getPetsPetId
+function operationName(method: string, route: string): string {
+  const words = route.split(/[^A-Za-z0-9]+/).filter(w => w.length > 0);
+  return method + words.map(w => w[0]!.toUpperCase() + w.slice(1)).join('');
+}
+
+// ─── Names and literals ───
+
+function identifier(name: string): string {
+  const ident = name.replace(/[^A-Za-z0-9_$]/g, '_');
+  return /^[0-9]/.test(ident) ? `_${ident}` : ident;
+}
+
+function uniqueName(name: string, taken: Set<string>, separator: string): string {
+  let unique = name;
+  for (let n = 2; taken.has(unique); n++) {
+    unique = `${name}${separator}${n}`;
+  }
+  taken.add(unique);
+  return unique;
+}
+
+function lookup(document: Schema, ref: string): unknown {
+  let current: unknown = document;
+  for (const segment of ref.replace(/^#\/?/, '').split('/').filter(s => s.length > 0)) {
+    if (!isSchemaObject(current) && !Array.isArray(current)) return undefined;
+    current = (current as Record<string, unknown>)[unescapePointer(segment)];
+  }
+  return current;
+}
+
+function escapePointer(segment: string): string {
+  return segment.replace(/~/g, '~0').replace(/\//g, '~1');
+}
+
+function unescapePointer(segment: string): string {
+  let decoded = segment;
+  try {
+    decoded = decodeURIComponent(segment);
+  } catch {
+    // not percent-encoded
+  }
+  return decoded.replace(/~1/g, '/').replace(/~0/g, '~');
+}
+
+function quote(text: string): string {
+  return `'${JSON.stringify(text).slice(1, -1).replace(/\\"/g, '"').replace(/'/g, "\\'")}'`;
+}
+
+function propertyKey(key: string): string {
+  return /^[A-Za-z_$][A-Za-z0-9_$]*$/.test(key) ? key : quote(key);
+}
+
+function literal(value: unknown): string {
+  return typeof value === 'string' ? quote(value) : JSON.stringify(value);
+}
+
+// JSON Schema patterns are ECMAScript regular expressions; some need the u flag
+function regexLiteral(pattern: string): string | undefined {
+  for (const flags of ['', 'u']) {
+    try {
+      return `/${new RegExp(pattern, flags).source}/${flags}`;
+    } catch {
+      // try the next flags
+    }
+  }
+  return undefined;
+}
@@ -0,0 +1,40 @@
+// src/portable.ts
+// Portable descriptions - a spec's TypeDescription as plain JSON, for runtimes that cannot run spec files
+
+import type { TypeDescription } from './base.js';
+
+type Children = { required?: TypeDescription[] | undefined; optional?: TypeDescription[] | undefined };
+
+function portableChildren(children: Children): Children {
+  return {
+    ...(children.required ? { required: children.required.map(portableDescription) } : {}),
+    ...(children.optional ? { optional: children.optional.map(portableDescription) } : {}),
+  };
+}
+
+/**
+ * Copy a description so that it survives JSON.stringify() intact: the raw `spec`
+ * values of custom types are left out.
+ *
+ * This is the form the specspec-prelude crate's `Engine` loads at runtime.
+ */
+export function portableDescription(desc: TypeDescription): TypeDescription {
+  const out: TypeDescription = { ...desc };
+  delete out.spec;
+
+  if (out.children) out.children = portableChildren(out.children);
+  if (out.oneOf) out.oneOf = out.oneOf.map(portableDescription);
+  if (out.allOf) out.allOf = out.allOf.map(portableDescription);
+  if (out.not) out.not = portableDescription(out.not);
+  if (out.itemType) out.itemType = portableDescription(out.itemType);
+  if (out.accept) out.accept = out.accept.map(portableDescription);
+  if (out.condition?.is) out.condition = { ...out.condition, is: portableDescription(out.condition.is) };
+  if (out.then) out.then = portableDescription(out.then);
+  if (out.else) out.else = portableDescription(out.else);
+  if (out.variants) {
+    out.variants = Object.fromEntries(
+      Object.entries(out.variants).map(([tag, variant]) => [tag, portableDescription(variant)])
+    );
+  }
+  return out;
+}
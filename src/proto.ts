@@ -0,0 +1,377 @@
+// src/proto.ts
+// Protobuf export - proto3 message definitions for the object types of a spec
+
+import type { TypeDescription } from './base.js';
+import { nameWords, fieldValue, isRef, parseLiteral } from './codegen/base.js';
+
+export interface ProtoExportOptions {
+  /** Package of the .proto file (e.g. `acme.orders.v1`) */
+  package?: string;
+}
+
+/** A rule of the spec that the .proto file does not carry */
+export interface ProtoNote {
+  /** Message, and field when the rule is on one (e.g. ['Order', 'sku']) */
+  path: string[];
+  message: string;
+}
+
+export interface ProtoExport {
+  /** The .proto file */
+  source: string;
+  /** Rules protobuf cannot represent, in the order their messages were declared */
+  notes: ProtoNote[];
+}
+
+type Children = { required?: TypeDescription[] | undefined; optional?: TypeDescription[] | undefined };
+
+/** A proto field type; repeated for lists */
+interface FieldType {
+  type: string;
+  repeated: boolean;
+  /** Scalars and enums, which need `optional` for presence */
+  scalar: boolean;
+}
+
+const VALUE: FieldType = { type: 'google.protobuf.Value', repeated: false, scalar: false };
+
+function pascal(name: string): string {
+  const ident = nameWords(name).map(w => w[0]!.toUpperCase() + w.slice(1)).join('');
+  return /^\d/.test(ident) ? `T${ident}` : ident;
+}
+
+function snake(name: string): string {
+  const ident = nameWords(name).join('_');
+  return /^\d/.test(ident) ? `f_${ident}` : ident;
+}
+
+// The JSON name protoc derives from a field name
+function jsonName(field: string): string {
+  return field.replace(/_([a-z0-9])/g, (_, c: string) => c.toUpperCase());
+}
+
+function comment(text: string | undefined, indent = ''): string[] {
+  return text ? text.split('\n').map(line => `${indent}// ${line}`.trimEnd()) : [];
+}
+
+function quote(text: string): string {
+  return JSON.stringify(text);
+}
+
+/**
+ * Map the object types of a data spec to proto3 messages, as a starting point for
+ * moving part of a pipeline to protobuf. Named types keep their name, inline objects
+ * are named after the field holding them, string OneOfs become enums and TaggedUnions
+ * become a message with a `oneof`. Values without a fixed shape use
+ * `google.protobuf.Value`.
+ *
+ * Rules protobuf has no way to express (required fields, lengths, ranges, patterns,
+ * list rules, literals) are left out and returned in `notes`. For file system specs,
+ * each JSON file with fields becomes a message named after its path.
+ */
+export function exportProto(desc: TypeDescription, options: ProtoExportOptions = {}): ProtoExport {
+  if (options.package !== undefined && !/^[A-Za-z_][A-Za-z0-9_]*(\.[A-Za-z_][A-Za-z0-9_]*)*$/.test(options.package)) {
+    throw new Error(`Invalid protobuf package name: ${options.package}`);
+  }
+  return new ProtoEmitter().emit(desc, options.package);
+}
+
+class ProtoEmitter {
+  /** Message and enum declarations by name, in the order they were first used */
+  private readonly decls = new Map<string, string>();
+  /** Bodies of named types, for recursive uses described by name only */
+  private readonly named = new Map<string, TypeDescription>();
+  private readonly enums = new Set<string>();
+  /** Nesting limits of named types, which field descriptions carry outside the body */
+  private readonly maxDepths = new Map<string, number>();
+  private readonly notes: ProtoNote[] = [];
+
+  emit(desc: TypeDescription, pkg: string | undefined): ProtoExport {
+    this.collectNamed(desc);
+    if (desc.fsType !== undefined) {
+      this.note([], 'file system rules are not exported; JSON files become messages');
+      this.jsonFiles(desc);
+    } else {
+      const root = desc.ref !== undefined ? pascal(desc.ref) : 'Root';
+      const type = this.typeOf(desc, root, [root]);
+      if (type.type !== root) {
+        // Only messages can be top level; wrap anything else
+        this.note([root], 'the root value is wrapped in the field `value`');
+        this.decls.set(root, [...comment(desc.description), `message ${root} {`, `  ${this.fieldLine(type, false, 'value', 1)}`, '}'].join('\n'));
+      }
+    }
+    if (this.decls.size === 0) {
+      throw new Error('The spec has no object types to export');
+    }
+
+    const body = [...this.decls.values()].join('\n\n');
+    const lines = ['syntax = "proto3";', ''];
+    if (pkg !== undefined) lines.push(`package ${pkg};`, '');
+    const imports = [
+      ...(/google\.protobuf\.(Value|ListValue)\b/.test(body) ? ['import "google/protobuf/struct.proto";'] : []),
+      ...(/google\.protobuf\.Empty\b/.test(body) ? ['import "google/protobuf/empty.proto";'] : []),
+    ];
+    if (imports.length > 0) lines.push(...imports, '');
+    lines.push(body, '');
+    return { source: lines.join('\n'), notes: this.notes };
+  }
+
+  private collectNamed(d: TypeDescription): void {
+    if (d.ref !== undefined && !isRef(d) && !this.named.has(d.ref)) {
+      this.named.set(d.ref, d.name === 'Field' ? fieldValue(d) ?? d : d);
+    }
+    if (d.ref !== undefined && d.maxDepth !== undefined) {
+      this.maxDepths.set(d.ref, d.maxDepth);
+    }
+    for (const child of [...(d.children?.required ?? []), ...(d.children?.optional ?? [])]) this.collectNamed(child);
+    for (const option of d.oneOf ?? []) this.collectNamed(option);
+    for (const part of d.allOf ?? []) this.collectNamed(part);
+    for (const variant of Object.values(d.variants ?? {})) this.collectNamed(variant);
+    if (d.itemType) this.collectNamed(d.itemType);
+  }
+
+  private jsonFiles(d: TypeDescription): void {
+    for (const child of [...(d.children?.required ?? []), ...(d.children?.optional ?? [])]) {
+      if (child.fsType === 'jsonFile') {
+        const children = child.children?.required?.length || child.children?.optional?.length ? child.children : undefined;
+        if (children) {
+          this.message(this.unique(pascal(child.filePath ?? child.key ?? 'File')), children, undefined, child.description);
+        }
+      } else if (child.fsType !== undefined) {
+        this.jsonFiles(child);
+      }
+    }
+  }
+
+  private note(path: string[], message: string): void {
+    this.notes.push({ path, message });
+  }
+
+  private unique(name: string): string {
+    let candidate = name;
+    for (let n = 2; this.decls.has(candidate); n++) {
+      candidate = `${name}${n}`;
+    }
+    return candidate;
+  }
+
+  /** Proto type for a value; `hint` names the messages and enums it needs, `path` locates notes */
+  private typeOf(d: TypeDescription, hint: string, path: string[]): FieldType {
+    return this.baseType(d, hint, path);
+  }
+
+  private baseType(d: TypeDescription, hint: string, path: string[]): FieldType {
+    if (d.ref !== undefined) {
+      const name = pascal(d.ref);
+      if (this.decls.has(name)) {
+        return { type: name, repeated: false, scalar: this.enums.has(name) };
+      }
+      const body = isRef(d) ? this.named.get(d.ref) : d;
+      if (body) {
+        const maxDepth = this.maxDepths.get(d.ref);
+        if (maxDepth !== undefined) this.note([name], `maximum nesting depth ${maxDepth}`);
+        // Named objects and enums keep the name; named scalars and lists have no proto counterpart
+        return this.baseType({ ...body, ref: undefined, nullable: false }, name, path);
+      }
+      return VALUE;
+    }
+
+    const constraints = (d.constraints ?? []).filter(c => c !== 'integer' && !c.startsWith('sampled'));
+    switch (d.name) {
+      case 'String':
+      case 'Pattern':
+        constraints.forEach(c => this.note(path, c));
+        return { type: 'string', repeated: false, scalar: true };
+      case 'Number':
+        constraints.forEach(c => this.note(path, c));
+        return { type: d.constraints?.includes('integer') ? 'int64' : 'double', repeated: false, scalar: true };
+      case 'Decimal':
+        this.note(path, 'decimal carried as a string');
+        constraints.forEach(c => this.note(path, c));
+        return { type: 'string', repeated: false, scalar: true };
+      case 'Boolean':
+        return { type: 'bool', repeated: false, scalar: true };
+      case 'Literal':
+        this.note(path, constraints[0] ?? 'literal value');
+        return literalType(d);
+      case 'ListOf':
+        return this.listType(d, hint, path, constraints);
+      case 'OneOf':
+        return this.oneOfType(d.oneOf ?? [], hint, path, d.description);
+      case 'AllOf':
+        return this.allOfType(d.allOf ?? [], hint, path, d.description);
+      case 'TaggedUnion':
+        return { type: this.taggedUnion(d, hint), repeated: false, scalar: false };
+      case 'Not':
+        this.note(path, `not ${d.not ? optionLabel(d.not) : 'a value'}`);
+        return VALUE;
+    }
+
+    if (d.children) {
+      return { type: this.message(this.unique(hint), d.children, undefined, d.description), repeated: false, scalar: false };
+    }
+    return VALUE;
+  }
+
+  private listType(d: TypeDescription, hint: string, path: string[], constraints: string[]): FieldType {
+    constraints.forEach(c => this.note(path, c));
+    if (!d.itemType) {
+      return { type: 'google.protobuf.Value', repeated: true, scalar: false };
+    }
+    if (d.itemType.nullable) {
+      this.note(path, 'null items');
+    }
+    const item = this.typeOf(d.itemType, `${hint}Item`, path);
+    if (item.repeated) {
+      // Repeated fields cannot nest
+      this.note(path, 'nested lists become google.protobuf.ListValue');
+      return { type: 'google.protobuf.ListValue', repeated: true, scalar: false };
+    }
+    return { ...item, repeated: true };
+  }
+
+  private message(name: string, children: Children, omit?: string, description?: string): string {
+    // Declared before the fields so recursive uses find it
+    this.decls.set(name, 'message');
+    const entries = [
+      ...(children.required ?? []).map(d => ({ d, optional: d.optional ?? false })),
+      ...(children.optional ?? []).map(d => ({ d, optional: true })),
+    ].filter(({ d }) => d.name === 'Field' && d.key !== undefined && d.key !== omit);
+
+    const lines = [...comment(description), `message ${name} {`];
+    const required: string[] = [];
+    const taken = new Set<string>();
+    entries.forEach(({ d, optional }, n) => {
+      const key = d.key!;
+      const path = [name, key];
+      let field = snake(key);
+      for (let k = 2; taken.has(field); k++) field = `${snake(key)}_${k}`;
+      taken.add(field);
+
+      const value = fieldValue(d);
+      let type: FieldType;
+      if (value) {
+        type = this.typeOf(value, `${name}${pascal(key)}`, path);
+      } else {
+        type = VALUE;
+      }
+      if (!optional) required.push(key);
+      if (d.nullable) this.note(path, 'null is not distinguished from a missing value');
+      if (d.default !== undefined) this.note(path, `default ${JSON.stringify(d.default)}`);
+
+      const fieldOptions: string[] = [];
+      if (jsonName(field) !== key) fieldOptions.push(`json_name = ${quote(key)}`);
+      if (d.deprecated !== undefined) fieldOptions.push('deprecated = true');
+      lines.push(...comment(d.description, '  '));
+      lines.push(`  ${this.fieldLine(type, optional || d.nullable === true, field, n + 1, fieldOptions)}`);
+    });
+    lines.push('}');
+    if (required.length > 0) {
+      this.note([name], `required fields ${required.join(', ')} (proto3 fields are all optional)`);
+    }
+    this.decls.set(name, lines.join('\n'));
+    return name;
+  }
+
+  private fieldLine(type: FieldType, optional: boolean, field: string, number: number, options: string[] = []): string {
+    const label = type.repeated ? 'repeated ' : optional && type.scalar ? 'optional ' : '';
+    const suffix = options.length > 0 ? ` [${options.join(', ')}]` : '';
+    return `${label}${type.type} ${field} = ${number}${suffix};`;
+  }
+
+  private oneOfType(options: TypeDescription[], hint: string, path: string[], description?: string): FieldType {
+    const literals = options.map(o => o.name === 'Literal' ? parseLiteral(o) : undefined);
+    if (literals.length > 0 && literals.every(v => typeof v === 'string')) {
+      return { type: this.stringEnum(this.unique(hint), literals as string[], path, description), repeated: false, scalar: true };
+    }
+
+    // Options of a single proto type need no union; either way, the options' own rules only apply to some values
+    const mark = this.notes.length;
+    const types = options.map((option, n) => this.typeOf(option, `${hint}${n + 1}`, path));
+    this.notes.splice(mark);
+    this.note(path, `one of ${options.map(optionLabel).join(', ')}`);
+    if (types.length > 0 && types.every(t => t.type === types[0]!.type && t.repeated === types[0]!.repeated)) {
+      return types[0]!;
+    }
+    return VALUE;
+  }
+
+  private stringEnum(name: string, values: string[], path: string[], description?: string): string {
+    // proto3 enums need a zero value, and value names are scoped to the package
+    const prefix = snake(name).toUpperCase();
+    const lines = [...comment(description), `enum ${name} {`, `  ${prefix}_UNSPECIFIED = 0;`];
+    const used = new Set<string>([`${prefix}_UNSPECIFIED`]);
+    values.forEach((value, n) => {
+      const base = /[A-Za-z0-9]/.test(value) ? `${prefix}_${nameWords(value).join('_').toUpperCase()}` : `${prefix}_VALUE_${n + 1}`;
+      let constant = base;
+      for (let k = 2; used.has(constant); k++) constant = `${base}_${k}`;
+      used.add(constant);
+      lines.push(`  ${constant} = ${n + 1};`);
+    });
+    lines.push('}');
+    this.note(path, `values become ${name} constants (e.g. ${quote(values[0]!)} is ${[...used][1]})`);
+    this.decls.set(name, lines.join('\n'));
+    this.enums.add(name);
+    return name;
+  }
+
+  private allOfType(parts: TypeDescription[], hint: string, path: string[], description?: string): FieldType {
+    // Object parts (e.g. an extended base and its added fields) merge into one message
+    const objects = parts
+      .map(part => isRef(part) && part.ref !== undefined ? this.named.get(part.ref) : part)
+      .filter((p): p is TypeDescription => p?.children !== undefined);
+    if (objects.length > 0) {
+      if (objects.length < parts.length) this.note(path, 'AllOf() parts other than objects');
+      return {
+        type: this.message(this.unique(hint), {
+          required: objects.flatMap(o => o.children?.required ?? []),
+          optional: objects.flatMap(o => o.children?.optional ?? []),
+        }, undefined, description),
+        repeated: false,
+        scalar: false,
+      };
+    }
+    // Not() parts only add a rule
+    const types = parts.map((part, n) => this.typeOf(part, `${hint}${n + 1}`, path)).filter((_, n) => parts[n]!.name !== 'Not');
+    if (types.some(t => t.type !== types[0]!.type || t.repeated !== types[0]!.repeated)) {
+      this.note(path, 'AllOf() parts of different types');
+    }
+    return types[0] ?? VALUE;
+  }
+
+  private taggedUnion(d: TypeDescription, hint: string): string {
+    const discriminator = d.discriminator ?? 'type';
+    const name = this.unique(hint);
+    this.decls.set(name, 'message');
+    // The discriminator becomes the oneof case, so variant messages leave it out
+    this.note([name], `the discriminator "${discriminator}" is the case of oneof ${snake(discriminator)}`);
+    const lines = [...comment(d.description), `message ${name} {`, `  oneof ${snake(discriminator)} {`];
+    Object.entries(d.variants ?? {}).forEach(([tag, variant], n) => {
+      const body = isRef(variant) && variant.ref !== undefined ? this.named.get(variant.ref) ?? variant : variant;
+      const variantName = body.children
+        ? this.message(this.unique(`${name}${pascal(tag)}`), body.children, discriminator, body.description)
+        : 'google.protobuf.Empty';
+      lines.push(`    ${variantName} ${snake(tag)} = ${n + 1};`);
+    });
+    lines.push('  }', '}');
+    this.decls.set(name, lines.join('\n'));
+    return name;
+  }
+}
+
+function optionLabel(d: TypeDescription): string {
+  return d.name === 'Literal' ? JSON.stringify(parseLiteral(d)) : d.ref ?? d.name;
+}
+
+function literalType(d: TypeDescription): FieldType {
+  const value = parseLiteral(d);
+  switch (typeof value) {
+    case 'string':
+      return { type: 'string', repeated: false, scalar: true };
+    case 'boolean':
+      return { type: 'bool', repeated: false, scalar: true };
+    case 'number':
+      return { type: Number.isInteger(value) ? 'int64' : 'double', repeated: false, scalar: true };
+  }
+  return VALUE;
+}
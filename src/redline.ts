@@ -0,0 +1,235 @@
+// src/redline.ts
+// Redline - spec-scoped structural comparison of two targets
+
+import fs from 'node:fs';
+import path from 'node:path';
+import type { TypeDescription } from './base.js';
+import { whenBranch } from './codegen/base.js';
+
+/**
+ * A single spec-relevant difference between two targets
+ */
+export interface RedlineDifference {
+  /** Path to the differing item (file path segments, then field keys / [index]) */
+  path: string[];
+  kind: 'added' | 'removed' | 'changed';
+  /** Value in the left target (absent for 'added') */
+  left?: unknown;
+  /** Value in the right target (absent for 'removed') */
+  right?: unknown;
+}
+
+type Children = { required?: TypeDescription[] | undefined; optional?: TypeDescription[] | undefined };
+
+function allChildren(children: Children | undefined): TypeDescription[] {
+  return [...(children?.required ?? []), ...(children?.optional ?? [])];
+}
+
+function isPlainObject(v: unknown): v is Record<string, unknown> {
+  return v !== null && typeof v === 'object' && !Array.isArray(v);
+}
+
+/**
+ * Project a JSON value onto the parts a data description cares about.
+ * Fields the spec does not declare are dropped; opaque values are kept whole.
+ */
+export function projectValue(desc: TypeDescription, value: unknown): unknown {
+  if (desc.name === 'ListOf' && desc.itemType && Array.isArray(value)) {
+    const itemType = desc.itemType;
+    return value.map(item => projectValue(itemType, item));
+  }
+
+  if (desc.name === 'Field') {
+    if (desc.itemType) {
+      return projectValue({ name: 'ListOf', itemType: desc.itemType }, value);
+    }
+    if (desc.children) {
+      return projectValue({ name: 'Object', children: desc.children }, value);
+    }
+    if (desc.allOf) {
+      return projectValue({ name: 'AllOf', allOf: desc.allOf }, value);
+    }
+    if (desc.variants) {
+      return projectValue({ name: 'TaggedUnion', discriminator: desc.discriminator, variants: desc.variants }, value);
+    }
+    return value;
+  }
+
+  // TaggedUnion: fields of the selected variant, plus the tag
+  if (desc.variants && desc.discriminator !== undefined && isPlainObject(value)) {
+    const tag = value[desc.discriminator];
+    const variant = typeof tag === 'string' && Object.hasOwn(desc.variants, tag) ? desc.variants[tag] : undefined;
+    const projected = variant ? projectValue(variant, value) : {};
+    return { ...(isPlainObject(projected) ? projected : {}), [desc.discriminator]: tag };
+  }
+
+  // AllOf: union of the fields the object parts declare
+  if (desc.allOf && isPlainObject(value)) {
+    const objectParts = desc.allOf.filter(part => part.children);
+    if (objectParts.length > 0) {
+      return Object.assign({}, ...objectParts.map(part => projectValue(part, value)));
+    }
+  }
+
+  if (desc.children && isPlainObject(value)) {
+    const result: Record<string, unknown> = {};
+    for (const child of allChildren(desc.children)) {
+      if (child.name === 'Field' && child.key !== undefined && child.key in value) {
+        result[child.key] = projectValue(child, value[child.key]);
+      }
+    }
+    return result;
+  }
+
+  return value;
+}
+
+function readJson(filePath: string): unknown {
+  try {
+    return JSON.parse(fs.readFileSync(filePath, 'utf-8'));
+  } catch {
+    return undefined;
+  }
+}
+
+// The value a When condition reads, added at its field path to the file's snapshot
+function withCondition(existing: unknown, condition: NonNullable<TypeDescription['condition']>, dirPath: string): unknown {
+  let value = readJson(path.join(dirPath, condition.file));
+  const segments = condition.field === undefined ? [] : condition.field.split('.');
+  for (const segment of segments) {
+    value = isPlainObject(value) || Array.isArray(value) ? (value as Record<string, unknown>)[segment] : undefined;
+  }
+  if (value === undefined || segments.length === 0) {
+    return value ?? existing;
+  }
+  const root: Record<string, unknown> = isPlainObject(existing) ? { ...existing } : {};
+  let target = root;
+  for (const segment of segments.slice(0, -1)) {
+    const next: Record<string, unknown> = isPlainObject(target[segment]) ? { ...(target[segment] as Record<string, unknown>) } : {};
+    target[segment] = next;
+    target = next;
+  }
+  target[segments[segments.length - 1]!] = value;
+  return root;
+}
+
+function snapshotChildren(children: Children | undefined, dirPath: string): Record<string, unknown> {
+  const result: Record<string, unknown> = {};
+  const conditions: NonNullable<TypeDescription['condition']>[] = [];
+  const visit = (list: TypeDescription[]): void => {
+    for (const child of list) {
+      if (child.name === 'When' && child.condition) {
+        // Entries of both branches are compared, as is the value that picks one
+        conditions.push(child.condition);
+        visit([child.then, child.else].flatMap(branch => branch ? allChildren(whenBranch(branch)) : []));
+        continue;
+      }
+      if (!child.filePath) continue;
+      const value = snapshotItem(child, path.join(dirPath, child.filePath));
+      if (value !== undefined) {
+        result[child.filePath] = value;
+      }
+    }
+  };
+  visit(allChildren(children));
+  for (const condition of conditions) {
+    const value = withCondition(result[condition.file], condition, dirPath);
+    if (value !== undefined) {
+      result[condition.file] = value;
+    }
+  }
+  return result;
+}
+
+function snapshotItem(desc: TypeDescription, fullPath: string): unknown {
+  let stat: fs.Stats;
+  try {
+    stat = fs.statSync(fullPath);
+  } catch {
+    return undefined;
+  }
+
+  switch (desc.fsType) {
+    case 'directory':
+      return stat.isDirectory() ? snapshotChildren(desc.children, fullPath) : undefined;
+    case 'jsonFile': {
+      if (!stat.isFile()) return undefined;
+      const content = readJson(fullPath);
+      return content === undefined ? null : projectValue({ name: 'Object', children: desc.children }, content);
+    }
+    case 'file': {
+      if (!stat.isFile()) return undefined;
+      // Only content the spec inspects is relevant; otherwise presence is enough
+      if (desc.children) {
+        const content = readJson(fullPath);
+        return content === undefined ? null : projectValue({ name: 'Object', children: desc.children }, content);
+      }
+      return true;
+    }
+  }
+  return undefined;
+}
+
+/**
+ * Capture the spec-relevant content of a target directory as a plain JSON tree.
+ * Files map to `true` (present), or to their projected JSON content when the spec inspects it.
+ */
+export function snapshot(desc: TypeDescription, targetPath: string): unknown {
+  if (desc.fsType === 'directory' || desc.fsType === 'bundle') {
+    const dirPath = desc.filePath ? path.join(targetPath, desc.filePath) : targetPath;
+    return snapshotChildren(desc.children, dirPath);
+  }
+  if (desc.fsType && desc.filePath) {
+    return snapshotChildren({ required: [desc] }, targetPath);
+  }
+  return projectValue(desc, readJson(targetPath));
+}
+
+/**
+ * Diff two snapshots
+ */
+export function diffSnapshots(left: unknown, right: unknown, basePath: string[] = []): RedlineDifference[] {
+  if (isPlainObject(left) && isPlainObject(right)) {
+    const diffs: RedlineDifference[] = [];
+    const keys = new Set([...Object.keys(left), ...Object.keys(right)]);
+    for (const key of keys) {
+      const p = [...basePath, key];
+      if (!(key in right)) {
+        diffs.push({ path: p, kind: 'removed', left: left[key] });
+      } else if (!(key in left)) {
+        diffs.push({ path: p, kind: 'added', right: right[key] });
+      } else {
+        diffs.push(...diffSnapshots(left[key], right[key], p));
+      }
+    }
+    return diffs;
+  }
+
+  if (Array.isArray(left) && Array.isArray(right)) {
+    const diffs: RedlineDifference[] = [];
+    const len = Math.max(left.length, right.length);
+    for (let i = 0; i < len; i++) {
+      const p = [...basePath, `[${i}]`];
+      if (i >= right.length) {
+        diffs.push({ path: p, kind: 'removed', left: left[i] });
+      } else if (i >= left.length) {
+        diffs.push({ path: p, kind: 'added', right: right[i] });
+      } else {
+        diffs.push(...diffSnapshots(left[i], right[i], p));
+      }
+    }
+    return diffs;
+  }
+
+  if (JSON.stringify(left) !== JSON.stringify(right)) {
+    return [{ path: basePath, kind: 'changed', left, right }];
+  }
+  return [];
+}
+
+/**
+ * Compare two targets, considering only what the spec describes
+ */
+export function redline(desc: TypeDescription, leftPath: string, rightPath: string): RedlineDifference[] {
+  return diffSnapshots(snapshot(desc, leftPath), snapshot(desc, rightPath));
+}
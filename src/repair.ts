@@ -0,0 +1,237 @@
+// src/repair.ts
+// Repair report - what a bundle should look like vs what it is
+
+import fs from 'node:fs';
+import path from 'node:path';
+import type { TypeDescription } from './base.js';
+import type { Issue } from './context.js';
+
+export interface RepairNode {
+  /** Entry name as declared by the spec (may contain '/') */
+  name: string;
+  /** Path relative to the target root, '/'-separated */
+  path: string;
+  kind: 'file' | 'directory';
+  /**
+   * present: declared and found; missing: required but not found;
+   * misplaced: required, not found here but found at `foundAt`;
+   * forbidden: found in a strict directory that does not declare it
+   */
+  status: 'present' | 'missing' | 'misplaced' | 'forbidden';
+  /** Where a misplaced entry currently is, relative to the target root */
+  foundAt?: string;
+  children: RepairNode[];
+}
+
+export interface RepairReport {
+  root: RepairNode;
+  /** Required entries not found anywhere */
+  missing: string[];
+  /** Required entries found at another location */
+  misplaced: Array<{ path: string; foundAt: string }>;
+  /** Entries that must be removed */
+  forbidden: string[];
+}
+
+/** Issue codes the repair report explains */
+const STRUCTURAL_CODES = new Set(['file.not_found', 'dir.not_found', 'dir.unexpected']);
+
+/**
+ * True when the issues include structural failures a repair report can explain
+ */
+export function hasStructuralIssues(issues: Issue[]): boolean {
+  return issues.some(i => STRUCTURAL_CODES.has(i.code));
+}
+
+/**
+ * Split issues into the structural ones (covered by a repair report) and the rest
+ */
+export function partitionStructural(issues: Issue[]): { structural: Issue[]; other: Issue[] } {
+  return {
+    structural: issues.filter(i => STRUCTURAL_CODES.has(i.code)),
+    other: issues.filter(i => !STRUCTURAL_CODES.has(i.code)),
+  };
+}
+
+function toPosix(p: string): string {
+  return p.split(path.sep).join('/');
+}
+
+function statKind(fullPath: string): 'file' | 'directory' | null {
+  try {
+    const stat = fs.statSync(fullPath);
+    return stat.isDirectory() ? 'directory' : 'file';
+  } catch {
+    return null;
+  }
+}
+
+/** Every file below a directory, relative and '/'-separated, sorted */
+function listFiles(root: string): string[] {
+  const files: string[] = [];
+  const walk = (rel: string): void => {
+    let entries: fs.Dirent[];
+    try {
+      entries = fs.readdirSync(path.join(root, rel), { withFileTypes: true });
+    } catch {
+      return;
+    }
+    for (const entry of entries) {
+      const childRel = rel ? `${rel}/${entry.name}` : entry.name;
+      if (entry.isDirectory()) {
+        walk(childRel);
+      } else {
+        files.push(childRel);
+      }
+    }
+  };
+  walk('');
+  return files.sort();
+}
+
+/**
+ * Build the expected-vs-actual tree for a directory spec
+ */
+function buildDirectory(desc: TypeDescription, fullDir: string, relDir: string): RepairNode[] {
+  const nodes: RepairNode[] = [];
+  const declared = new Set<string>();
+  const items = [
+    ...(desc.children?.required ?? []).map(d => ({ d, required: true })),
+    ...(desc.children?.optional ?? []).map(d => ({ d, required: false })),
+  ];
+
+  for (const { d, required } of items) {
+    if (!d.filePath || (d.fsType !== 'file' && d.fsType !== 'jsonFile' && d.fsType !== 'directory')) {
+      continue;
+    }
+    declared.add(d.filePath.split(/[\\/]/)[0]!);
+    const kind = d.fsType === 'directory' ? 'directory' : 'file';
+    const rel = relDir ? `${relDir}/${toPosix(d.filePath)}` : toPosix(d.filePath);
+    const full = path.join(fullDir, d.filePath);
+    const found = statKind(full) === kind;
+
+    if (!found && !required) {
+      continue;
+    }
+    nodes.push({
+      name: toPosix(d.filePath),
+      path: rel,
+      kind,
+      status: found ? 'present' : 'missing',
+      children: found && kind === 'directory' ? buildDirectory(d, full, rel) : [],
+    });
+  }
+
+  if (desc.strict && statKind(fullDir) === 'directory') {
+    for (const entry of fs.readdirSync(fullDir).sort()) {
+      if (declared.has(entry)) continue;
+      nodes.push({
+        name: entry,
+        path: relDir ? `${relDir}/${entry}` : entry,
+        kind: statKind(path.join(fullDir, entry)) ?? 'file',
+        status: 'forbidden',
+        children: [],
+      });
+    }
+  }
+
+  return nodes;
+}
+
+function flatten(node: RepairNode): RepairNode[] {
+  return [node, ...node.children.flatMap(flatten)];
+}
+
+/**
+ * Compare a target directory with what its spec requires.
+ *
+ * A required file that is missing but whose name appears elsewhere in the
+ * target is reported as misplaced, with its current location; a forbidden
+ * entry accounted for that way is not reported again.
+ */
+export function repairReport(desc: TypeDescription, targetPath: string): RepairReport {
+  const rootDir = desc.filePath ? path.join(targetPath, desc.filePath) : targetPath;
+  const root: RepairNode = {
+    name: desc.filePath ?? path.basename(path.resolve(targetPath)),
+    path: '',
+    kind: 'directory',
+    status: statKind(rootDir) === 'directory' ? 'present' : 'missing',
+    children: [],
+  };
+  if (root.status === 'missing' || desc.fsType !== 'directory') {
+    return { root, missing: root.status === 'missing' ? [root.name] : [], misplaced: [], forbidden: [] };
+  }
+  root.children = buildDirectory(desc, rootDir, '');
+
+  // Look for missing files elsewhere in the tree, preferring entries that must move anyway
+  const nodes = flatten(root);
+  const expected = new Set(nodes.filter(n => n.status === 'present').map(n => n.path));
+  const forbidden = nodes.filter(n => n.status === 'forbidden');
+  const files = listFiles(rootDir).filter(f => !expected.has(f));
+  const claimed = new Set<string>();
+
+  for (const node of nodes) {
+    if (node.status !== 'missing' || node.kind !== 'file') continue;
+    const base = path.posix.basename(node.name);
+    const candidates = files.filter(f => path.posix.basename(f) === base && !claimed.has(f));
+    const preferred = candidates.find(f => forbidden.some(n => f === n.path || f.startsWith(`${n.path}/`)));
+    const foundAt = preferred ?? candidates[0];
+    if (foundAt !== undefined) {
+      node.status = 'misplaced';
+      node.foundAt = foundAt;
+      claimed.add(foundAt);
+    }
+  }
+
+  // A forbidden file that is just a misplaced required file is covered by the move
+  const removeClaimed = (node: RepairNode): void => {
+    node.children = node.children.filter(c => !(c.status === 'forbidden' && c.kind === 'file' && claimed.has(c.path)));
+    node.children.forEach(removeClaimed);
+  };
+  removeClaimed(root);
+
+  const all = flatten(root);
+  return {
+    root,
+    missing: all.filter(n => n.status === 'missing').map(n => n.path),
+    misplaced: all.filter(n => n.status === 'misplaced').map(n => ({ path: n.path, foundAt: n.foundAt! })),
+    forbidden: all.filter(n => n.status === 'forbidden').map(n => n.path),
+  };
+}
+
+/**
+ * Render a repair report as a tree, one line per entry
+ *
+ * @example
+ * bundle/
+ * ├── meta.json  ✗ missing — found at data/meta.json, move it here
+ * ├── data/
+ * │   └── blob.bin
+ * └── junk.tmp  ✗ not allowed, remove it
+ */
+export function formatRepairReport(report: RepairReport): string {
+  const label = (node: RepairNode): string => {
+    const name = node.kind === 'directory' ? `${node.name}/` : node.name;
+    switch (node.status) {
+      case 'present':
+        return name;
+      case 'missing':
+        return `${name}  ✗ missing`;
+      case 'misplaced':
+        return `${name}  ✗ missing — found at ${node.foundAt}, move it here`;
+      case 'forbidden':
+        return `${name}  ✗ not allowed, remove it`;
+    }
+  };
+
+  const lines = [label(report.root)];
+  const render = (nodes: RepairNode[], prefix: string): void => {
+    nodes.forEach((node, index) => {
+      const last = index === nodes.length - 1;
+      lines.push(`${prefix}${last ? '└── ' : '├── '}${label(node)}`);
+      render(node.children, `${prefix}${last ? '    ' : '│   '}`);
+    });
+  };
+  render(report.root.children, '');
+  return lines.join('\n');
+}
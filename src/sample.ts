@@ -0,0 +1,573 @@
+// src/sample.ts
+// Sample data - generate JSON values and file trees that satisfy a spec, reproducibly from a seed
+
+import fs from 'node:fs';
+import path from 'node:path';
+import type { TypeDescription } from './base.js';
+import { isRef } from './codegen/base.js';
+
+export interface SampleOptions {
+  /** Seed for the random choices; the same spec and seed give the same sample (default: 0) */
+  seed?: number;
+  /** Chance of including each optional field, file or directory, from 0 to 1 (default: 0.5) */
+  optional?: number;
+}
+
+interface SampleState {
+  next: () => number;
+  optional: number;
+  defs: Map<string, TypeDescription>;
+  /** Nesting of each named type currently being generated */
+  depth: Map<string, number>;
+}
+
+type Children = { required?: TypeDescription[] | undefined; optional?: TypeDescription[] | undefined };
+
+/** Nesting of a recursive named type past which only required content is generated */
+const SHALLOW_DEPTH = 2;
+
+/** Nesting at which generation gives up on a recursive named type */
+const MAX_DEPTH = 8;
+
+/** Chance of null for a nullable value */
+const NULL_RATE = 0.2;
+
+const WORDS = ['lorem', 'ipsum', 'dolor', 'sit', 'amet', 'consectetur', 'adipiscing', 'elit', 'sed', 'tempor', 'magna', 'aliqua'];
+
+// End of central directory record of an archive with no entries
+const EMPTY_ZIP = Buffer.from([0x50, 0x4b, 0x05, 0x06, ...new Array<number>(18).fill(0)]);
+
+// mulberry32 - small seeded PRNG returning floats in [0, 1)
+function seededRandom(seed: number): () => number {
+  let state = seed >>> 0;
+  return () => {
+    state = (state + 0x6D2B79F5) >>> 0;
+    let t = state;
+    t = Math.imul(t ^ (t >>> 15), t | 1);
+    t ^= t + Math.imul(t ^ (t >>> 7), t | 61);
+    return ((t ^ (t >>> 14)) >>> 0) / 4294967296;
+  };
+}
+
+function int(state: SampleState, lo: number, hi: number): number {
+  return lo + Math.floor(state.next() * (hi - lo + 1));
+}
+
+function pick<T>(state: SampleState, items: T[]): T {
+  return items[Math.floor(state.next() * items.length)]!;
+}
+
+function allChildren(children: Children | undefined): TypeDescription[] {
+  return [...(children?.required ?? []), ...(children?.optional ?? [])];
+}
+
+// Named type bodies by name, so recursive uses (described as Ref) can be resolved
+function collectDefinitions(desc: TypeDescription, defs: Map<string, TypeDescription>): void {
+  if (desc.ref !== undefined && !isRef(desc) && !defs.has(desc.ref)) {
+    defs.set(desc.ref, desc);
+  }
+  for (const child of allChildren(desc.children)) collectDefinitions(child, defs);
+  for (const option of desc.oneOf ?? []) collectDefinitions(option, defs);
+  for (const part of desc.allOf ?? []) collectDefinitions(part, defs);
+  for (const variant of Object.values(desc.variants ?? {})) collectDefinitions(variant, defs);
+  for (const carrier of desc.accept ?? []) collectDefinitions(carrier, defs);
+  if (desc.itemType) collectDefinitions(desc.itemType, defs);
+}
+
+function createState(desc: TypeDescription, options: SampleOptions): SampleState {
+  const defs = new Map<string, TypeDescription>();
+  collectDefinitions(desc, defs);
+  return { next: seededRandom(options.seed ?? 0), optional: options.optional ?? 0.5, defs, depth: new Map() };
+}
+
+// True once a recursive named type is nested deep enough to stop adding optional content
+function shallow(state: SampleState): boolean {
+  return [...state.depth.values()].some(depth => depth >= SHALLOW_DEPTH);
+}
+
+/**
+ * Generate a JSON value that satisfies a description.
+ *
+ * Generation is best effort: values are built from the described constraints, so
+ * exclusions (Not), custom types and checks the description does not carry may
+ * still reject them. Validate the sample when that matters (SpecEngine.sample() does).
+ */
+export function sampleValue(desc: TypeDescription, options: SampleOptions = {}): unknown {
+  return sample(desc, createState(desc, options));
+}
+
+function sample(desc: TypeDescription, state: SampleState): unknown {
+  if (isRef(desc) && desc.ref !== undefined) {
+    const def = state.defs.get(desc.ref);
+    return def ? sample({ ...def, nullable: desc.nullable || def.nullable }, state) : null;
+  }
+  if (desc.ref === undefined) {
+    return sampleBody(desc, state);
+  }
+
+  const depth = state.depth.get(desc.ref) ?? 0;
+  if (depth >= Math.min(MAX_DEPTH, desc.maxDepth ?? MAX_DEPTH)) {
+    return null;
+  }
+  state.depth.set(desc.ref, depth + 1);
+  try {
+    return sampleBody(desc, state);
+  } finally {
+    state.depth.set(desc.ref, depth);
+  }
+}
+
+function sampleBody(d: TypeDescription, state: SampleState): unknown {
+  if (d.nullable && (shallow(state) || state.next() < NULL_RATE)) {
+    return null;
+  }
+
+  if (d.variants && d.discriminator !== undefined) {
+    const tag = pick(state, Object.keys(d.variants));
+    const value = sample(d.variants[tag]!, state);
+    return isPlainObject(value) ? { [d.discriminator]: tag, ...value } : value;
+  }
+  if (d.allOf) {
+    return sampleAllOf(d.allOf, state);
+  }
+  if (d.oneOf && d.oneOf.length > 0) {
+    return sample(pick(state, d.oneOf), state);
+  }
+  if (d.itemType) {
+    return sampleList(d, state);
+  }
+  if (d.children) {
+    return sampleObject(d.children, state);
+  }
+
+  const type = d.name === 'Field' ? d.summary : d.name;
+  switch (type) {
+    case 'String':
+    case 'Pattern':
+      return sampleString(d.constraints ?? [], state);
+    case 'Number':
+      return sampleNumber(d.constraints ?? [], state);
+    case 'Decimal':
+      return sampleDecimal(d.constraints ?? [], state);
+    case 'Boolean':
+      return state.next() < 0.5;
+    case 'Literal':
+      return JSON.parse((d.constraints?.[0] ?? 'equals null').slice('equals '.length));
+    default:
+      // Any value, or a type the description does not explain
+      return words(state, 3, 12);
+  }
+}
+
+function isPlainObject(v: unknown): v is Record<string, unknown> {
+  return v !== null && typeof v === 'object' && !Array.isArray(v);
+}
+
+function sampleObject(children: Children, state: SampleState): Record<string, unknown> {
+  const result: Record<string, unknown> = {};
+  const add = (child: TypeDescription, required: boolean) => {
+    if (child.name !== 'Field' || child.key === undefined) {
+      // A type applied to the whole object (e.g. a OneOf of shapes) contributes its fields
+      const value = sample(child, state);
+      if (isPlainObject(value)) Object.assign(result, value);
+      return;
+    }
+    if ((!required || child.optional) && (shallow(state) || state.next() >= state.optional)) {
+      return;
+    }
+    result[child.key] = sample({ ...child, name: child.summary ?? 'Unknown' }, state);
+  };
+  for (const child of children.required ?? []) add(child, true);
+  for (const child of children.optional ?? []) add(child, false);
+  return result;
+}
+
+// Objects merge the fields of every part; other values combine the parts' constraints
+function sampleAllOf(parts: TypeDescription[], state: SampleState): unknown {
+  const resolved = parts.map(part => isRef(part) && part.ref !== undefined ? state.defs.get(part.ref) ?? part : part);
+  if (resolved.every(part => part.children || part.allOf || part.variants)) {
+    return Object.assign({}, ...resolved.map(part => sample(part, state)));
+  }
+  const typed = resolved.filter(part => part.name !== 'Not');
+  const scalar = typed.find(part => part.name !== 'Pattern') ?? typed[0];
+  if (!scalar) {
+    return words(state, 3, 12);
+  }
+  return sample({ ...scalar, constraints: typed.flatMap(part => part.constraints ?? []) }, state);
+}
+
+function sampleList(d: TypeDescription, state: SampleState): unknown[] {
+  const constraints = d.constraints ?? [];
+  const minItems = bound(constraints, 'minimum', ' items') ?? 0;
+  const maxItems = bound(constraints, 'maximum', ' items');
+  const uniqueBy = quoted(constraints, 'unique by ');
+  const unique = constraints.includes('unique items') || uniqueBy !== undefined;
+  const sorted = constraints.find(c => c.startsWith('sorted '));
+
+  const lo = shallow(state) ? minItems : Math.max(minItems, Math.min(1, maxItems ?? 1));
+  const hi = shallow(state) ? lo : Math.max(lo, Math.min(maxItems ?? lo + 2, lo + 3));
+  const count = int(state, lo, hi);
+  const itemType = d.itemType!;
+
+  const items: unknown[] = [];
+  const seen = new Set<string>();
+  for (let attempt = 0; items.length < count && attempt < count * 10; attempt++) {
+    const item = sample(itemType, state);
+    if (unique) {
+      const key = JSON.stringify(uniqueBy !== undefined ? keyPath(item, uniqueBy) : item) ?? '';
+      if (seen.has(key)) continue;
+      seen.add(key);
+    }
+    items.push(item);
+  }
+
+  if (sorted !== undefined) {
+    const by = quoted([sorted.replace(/^sorted \w+ /, '')], 'by ');
+    const key = (item: unknown) => by !== undefined ? keyPath(item, by) : item;
+    items.sort((a, b) => compareKeys(key(a), key(b)));
+    if (sorted.startsWith('sorted descending')) items.reverse();
+  }
+  return items;
+}
+
+// Resolve a dotted key path inside an item
+function keyPath(item: unknown, keys: string): unknown {
+  let current = item;
+  for (const key of keys.split('.')) {
+    if (!isPlainObject(current)) return undefined;
+    current = current[key];
+  }
+  return current;
+}
+
+function compareKeys(a: unknown, b: unknown): number {
+  if (typeof a === 'number' && typeof b === 'number') return a - b;
+  if (typeof a === 'string' && typeof b === 'string') return a < b ? -1 : a > b ? 1 : 0;
+  return 0;
+}
+
+// ─── Constraints ───
+
+// `minimum 3 characters`, `maximum 10 items`, `minimum -1.5`; the unit is the text after the number
+function bound(constraints: string[], kind: 'minimum' | 'maximum' | 'precision' | 'scale', unit = ''): number | undefined {
+  for (const c of constraints) {
+    const m = /^(minimum|maximum|precision|scale) (-?\d+(?:\.\d+)?)(.*)$/.exec(c);
+    if (m && m[1] === kind && m[3] === unit) {
+      return Number(m[2]);
+    }
+  }
+  return undefined;
+}
+
+// `unique by \`id\`` -> id
+function quoted(constraints: string[], prefix: string): string | undefined {
+  const c = constraints.find(text => text.startsWith(prefix));
+  return c?.slice(prefix.length).replace(/^`|`$/g, '');
+}
+
+// ─── Scalars ───
+
+function words(state: SampleState, min: number, max: number): string {
+  const length = int(state, min, Math.max(min, max));
+  let text = pick(state, WORDS);
+  while (text.length < length) {
+    text += `-${pick(state, WORDS)}`;
+  }
+  return text.slice(0, length).replace(/-$/, 'a');
+}
+
+function sampleString(constraints: string[], state: SampleState): string {
+  const minLength = bound(constraints, 'minimum', ' characters') ?? 0;
+  const maxLength = bound(constraints, 'maximum', ' characters');
+  const patterns = constraints.filter(c => c.startsWith('matches ')).map(c => toRegExp(c.slice('matches '.length).replace(/^`|`$/g, '')));
+  const fits = (s: string) => s.length >= minLength && (maxLength === undefined || s.length <= maxLength);
+
+  const [first, ...rest] = patterns;
+  if (!first) {
+    return words(state, Math.max(minLength, Math.min(3, maxLength ?? 3)), maxLength ?? Math.max(minLength, 3) + 9);
+  }
+  let candidate = '';
+  for (let attempt = 0; attempt < 20; attempt++) {
+    try {
+      candidate = new RegexSampler(first.source, state).generate();
+    } catch {
+      break;
+    }
+    if (fits(candidate) && first.test(candidate) && rest.every(re => re.test(candidate))) {
+      break;
+    }
+  }
+  return candidate;
+}
+
+// `/^\d+$/i` -> the RegExp; other text is taken as the pattern source
+function toRegExp(text: string): RegExp {
+  const m = /^\/([\s\S]*)\/([a-z]*)$/.exec(text);
+  try {
+    return m ? new RegExp(m[1]!, m[2]) : new RegExp(text);
+  } catch {
+    return /(?:)/;
+  }
+}
+
+function sampleNumber(constraints: string[], state: SampleState): number {
+  const min = bound(constraints, 'minimum');
+  const max = bound(constraints, 'maximum');
+  const lo = min ?? (max !== undefined ? Math.min(0, max) - 100 : 0);
+  const hi = max ?? lo + 100;
+  if (constraints.includes('integer')) {
+    return Math.ceil(lo) > Math.floor(hi) ? Math.ceil(lo) : int(state, Math.ceil(lo), Math.floor(hi));
+  }
+  const value = Math.round((lo + state.next() * (hi - lo)) * 100) / 100;
+  return value < lo || value > hi ? lo : value;
+}
+
+function sampleDecimal(constraints: string[], state: SampleState): number {
+  const scale = bound(constraints, 'scale') ?? 2;
+  const precision = bound(constraints, 'precision', ' digits');
+  const unit = 10 ** scale;
+  const limit = precision !== undefined ? 10 ** precision - 1 : Number.MAX_SAFE_INTEGER;
+  const min = bound(constraints, 'minimum');
+  const max = bound(constraints, 'maximum');
+  const lo = Math.max(min !== undefined ? Math.ceil(min * unit) : 0, -limit);
+  const hi = Math.min(max !== undefined ? Math.floor(max * unit) : lo + 100 * unit, limit);
+  const units = lo > hi ? lo : int(state, lo, hi);
+  return Number((units / unit).toFixed(scale));
+}
+
+// ─── Patterns ───
+
+type RegexNode =
+  | { kind: 'chars'; chars: string }
+  | { kind: 'seq'; items: RegexNode[] }
+  | { kind: 'alt'; options: RegexNode[] }
+  | { kind: 'repeat'; node: RegexNode; min: number; max: number };
+
+const DIGITS = '0123456789';
+const LOWER = 'abcdefghijklmnopqrstuvwxyz';
+const WORD = `${LOWER}${LOWER.toUpperCase()}${DIGITS}_`;
+const PRINTABLE = Array.from({ length: 0x7f - 0x20 }, (_, i) => String.fromCharCode(0x20 + i)).join('');
+
+/**
+ * Generates strings matching a regular expression: literals, classes, groups,
+ * alternation and quantifiers. Anchors and word boundaries are ignored;
+ * lookarounds and backreferences are not supported and throw.
+ */
+class RegexSampler {
+  private pos = 0;
+  private readonly root: RegexNode;
+
+  constructor(private readonly source: string, private readonly state: SampleState) {
+    this.root = this.alternation();
+    if (this.pos < source.length) {
+      throw new Error(`Unexpected ${source[this.pos]} in pattern`);
+    }
+  }
+
+  generate(node: RegexNode = this.root): string {
+    switch (node.kind) {
+      case 'chars':
+        return node.chars.length > 0 ? pick(this.state, [...node.chars]) : '';
+      case 'seq':
+        return node.items.map(item => this.generate(item)).join('');
+      case 'alt':
+        return this.generate(pick(this.state, node.options));
+      case 'repeat': {
+        const count = int(this.state, node.min, node.max);
+        return Array.from({ length: count }, () => this.generate(node.node)).join('');
+      }
+    }
+  }
+
+  private alternation(): RegexNode {
+    const options = [this.sequence()];
+    while (this.source[this.pos] === '|') {
+      this.pos++;
+      options.push(this.sequence());
+    }
+    return options.length === 1 ? options[0]! : { kind: 'alt', options };
+  }
+
+  private sequence(): RegexNode {
+    const items: RegexNode[] = [];
+    while (this.pos < this.source.length && this.source[this.pos] !== '|' && this.source[this.pos] !== ')') {
+      const atom = this.atom();
+      if (atom) items.push(this.quantifier(atom));
+    }
+    return { kind: 'seq', items };
+  }
+
+  private atom(): RegexNode | null {
+    const c = this.source[this.pos++]!;
+    switch (c) {
+      case '^':
+      case '$':
+        return null;
+      case '.':
+        return { kind: 'chars', chars: `${LOWER}${DIGITS}` };
+      case '[':
+        return this.characterClass();
+      case '\\':
+        return this.escape(false);
+      case '(': {
+        if (this.source.startsWith('?:', this.pos)) {
+          this.pos += 2;
+        } else if (this.source[this.pos] === '?' && /^\?<[A-Za-z_$]/.test(this.source.slice(this.pos))) {
+          this.pos = this.source.indexOf('>', this.pos) + 1;
+        } else if (this.source[this.pos] === '?') {
+          throw new Error('Lookarounds are not supported');
+        }
+        const group = this.alternation();
+        if (this.source[this.pos++] !== ')') {
+          throw new Error('Unterminated group');
+        }
+        return group;
+      }
+      default:
+        return { kind: 'chars', chars: c };
+    }
+  }
+
+  private quantifier(node: RegexNode): RegexNode {
+    const c = this.source[this.pos];
+    let min: number;
+    let max: number | undefined;
+    if (c === '*' || c === '+' || c === '?') {
+      this.pos++;
+      min = c === '+' ? 1 : 0;
+      max = c === '?' ? 1 : undefined;
+    } else if (c === '{' && /^\{\d+(,\d*)?\}/.test(this.source.slice(this.pos))) {
+      const m = /^\{(\d+)(,(\d*))?\}/.exec(this.source.slice(this.pos))!;
+      this.pos += m[0].length;
+      min = Number(m[1]);
+      max = m[2] === undefined ? min : m[3] ? Number(m[3]) : undefined;
+    } else {
+      return node;
+    }
+    if (this.source[this.pos] === '?') this.pos++;
+    return { kind: 'repeat', node, min, max: max ?? min + 3 };
+  }
+
+  private characterClass(): RegexNode {
+    const negated = this.source[this.pos] === '^';
+    if (negated) this.pos++;
+    let chars = '';
+    while (this.pos < this.source.length && this.source[this.pos] !== ']') {
+      let start = this.source[this.pos++]!;
+      if (start === '\\') {
+        const escaped = this.escape(true);
+        if (escaped?.kind !== 'chars' || escaped.chars.length !== 1) {
+          chars += escaped?.kind === 'chars' ? escaped.chars : '';
+          continue;
+        }
+        start = escaped.chars;
+      }
+      if (this.source[this.pos] === '-' && this.source[this.pos + 1] !== ']' && this.pos + 1 < this.source.length) {
+        this.pos++;
+        let end = this.source[this.pos++]!;
+        if (end === '\\') {
+          const escaped = this.escape(true);
+          end = escaped?.kind === 'chars' ? escaped.chars[0] ?? end : end;
+        }
+        for (let code = start.charCodeAt(0); code <= end.charCodeAt(0); code++) {
+          chars += String.fromCharCode(code);
+        }
+      } else {
+        chars += start;
+      }
+    }
+    this.pos++;
+    return { kind: 'chars', chars: negated ? [...PRINTABLE].filter(ch => !chars.includes(ch)).join('') : chars };
+  }
+
+  private escape(inClass: boolean): RegexNode | null {
+    const c = this.source[this.pos++]!;
+    const chars = (text: string): RegexNode => ({ kind: 'chars', chars: text });
+    switch (c) {
+      case 'd': return chars(DIGITS);
+      case 'D': return chars(LOWER);
+      case 'w': return chars(WORD);
+      case 'W': return chars('-.+ ');
+      case 's': return chars(' ');
+      case 'S': return chars(LOWER);
+      case 'n': return chars('\n');
+      case 't': return chars('\t');
+      case 'r': return chars('\r');
+      case 'f': return chars('\f');
+      case 'v': return chars('\v');
+      case '0': return chars('\0');
+      case 'b': return inClass ? chars('\b') : null;
+      case 'B': return null;
+      case 'x':
+      case 'u': {
+        const m = c === 'x' ? /^[0-9a-fA-F]{2}/.exec(this.source.slice(this.pos)) : /^(\{[0-9a-fA-F]+\}|[0-9a-fA-F]{4})/.exec(this.source.slice(this.pos));
+        if (!m) return chars(c);
+        this.pos += m[0].length;
+        return chars(String.fromCodePoint(parseInt(m[0].replace(/[{}]/g, ''), 16)));
+      }
+      default:
+        if (/[1-9]/.test(c)) {
+          throw new Error('Backreferences are not supported');
+        }
+        return chars(c);
+    }
+  }
+}
+
+// ─── File system ───
+
+/**
+ * Write a file tree that satisfies a file system description under targetPath:
+ * directories with their required entries (and some optional ones), JSON files
+ * with sample content, and empty ZIP archives. Files with a checksum get
+ * placeholder content, which the checksum will reject.
+ */
+export function writeSample(desc: TypeDescription, targetPath: string, options: SampleOptions = {}): void {
+  writeEntry(desc, targetPath, createState(desc, options));
+}
+
+function writeEntry(desc: TypeDescription, basePath: string, state: SampleState): void {
+  const fullPath = desc.filePath ? path.join(basePath, desc.filePath) : basePath;
+  const writeFile = (content: string | Buffer) => {
+    fs.mkdirSync(path.dirname(fullPath), { recursive: true });
+    fs.writeFileSync(fullPath, content);
+  };
+
+  switch (desc.fsType) {
+    case 'bundle': {
+      const carrier = desc.accept?.find(c => c.fsType === 'directory') ?? desc.accept?.[0];
+      if (carrier) writeEntry(carrier, basePath, state);
+      return;
+    }
+    case 'directory': {
+      fs.mkdirSync(fullPath, { recursive: true });
+      const write = (child: TypeDescription) => {
+        const entry = child.oneOf && !child.fsType ? pick(state, child.oneOf) : child;
+        if (entry.fsType) writeEntry(entry, fullPath, state);
+      };
+      for (const child of desc.children?.required ?? []) write(child);
+      for (const child of desc.children?.optional ?? []) {
+        if (state.next() < state.optional) write(child);
+      }
+      return;
+    }
+    case 'jsonFile':
+      writeFile(`${JSON.stringify(sampleObject(desc.children ?? {}, state), null, 2)}\n`);
+      return;
+    case 'file': {
+      const content = allChildren(desc.children);
+      if (content.length === 0) {
+        writeFile(`Sample ${path.basename(fullPath)}\n`);
+      } else if (content.every(child => child.name === 'Field')) {
+        writeFile(`${JSON.stringify(sampleObject(desc.children!, state), null, 2)}\n`);
+      } else {
+        const value = sample(content[0]!, state);
+        writeFile(typeof value === 'string' ? value : `${JSON.stringify(value, null, 2)}\n`);
+      }
+      return;
+    }
+    case 'zipFile':
+      writeFile(EMPTY_ZIP);
+      return;
+  }
+}
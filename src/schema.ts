@@ -0,0 +1,106 @@
+// src/schema.ts
+// Schema - a compiled spec, ready to validate targets
+
+import type { Issue } from './context.js';
+import type { Type, Modifier, TypeDescription } from './base.js';
+import type { SpecEngine, ValidationResult, NormalizeResult, SampleResult, CompareResult } from './engine.js';
+import type { NormalizeOptions } from './normalize.js';
+import type { SampleOptions } from './sample.js';
+import { redline } from './redline.js';
+import { UploadSession, type UploadOptions } from './upload.js';
+
+/**
+ * A spec failed to compile. `issues` holds the spec.* issues, as run() would report them;
+ * `cause` is the error thrown while evaluating the spec file, if any (its stack locates it).
+ * `imports` lists the files the spec imported, or tried to, before it failed.
+ */
+export class SchemaError extends Error {
+  readonly imports: string[];
+
+  constructor(readonly issues: Issue[], options?: { cause?: unknown; imports?: string[] }) {
+    super(issues.map(i => i.message).join('; '), options?.cause === undefined ? undefined : { cause: options.cause });
+    this.name = 'SchemaError';
+    this.imports = options?.imports ?? [];
+  }
+}
+
+/**
+ * Schema - a spec that compiled successfully.
+ *
+ * Only SpecEngine.compile() / compileSource() create schemas; every spec
+ * error (syntax, unknown types, imports, unsupported checksum algorithms)
+ * has already been reported there. validate() never throws: a rule that
+ * fails unexpectedly is reported as an `engine.error` issue.
+ *
+ * @example
+ * const schema = engine.compile('package.spec.js'); // throws SchemaError
+ * const result = schema.validate('/path/to/project');
+ */
+export class Schema {
+  /** @internal use SpecEngine.compile() */
+  constructor(
+    readonly root: Type | Modifier,
+    private readonly engine: SpecEngine,
+    /** Absolute paths of the files the spec imports, transitively */
+    readonly imports: string[] = []
+  ) {}
+
+  /**
+   * Validate a target against this schema
+   */
+  validate(targetPath: string): ValidationResult {
+    return this.engine.validate(this.root, targetPath);
+  }
+
+  /**
+   * Validate a JSON value (for data specs)
+   */
+  validateValue(value: unknown): ValidationResult {
+    return this.engine.validateValue(this.root, value);
+  }
+
+  /**
+   * Validate a JSON value and return a normalized copy with field defaults filled in
+   */
+  normalize(value: unknown, options?: NormalizeOptions): NormalizeResult {
+    return this.engine.normalize(this.root, value, options);
+  }
+
+  /**
+   * Generate a JSON value that satisfies this schema (see SpecEngine.sample())
+   */
+  sample(options?: SampleOptions): SampleResult {
+    return this.engine.sample(this.root, options);
+  }
+
+  /**
+   * Write a sample file tree for this (file system) schema under targetPath and validate it
+   */
+  writeSample(targetPath: string, options?: SampleOptions): ValidationResult {
+    return this.engine.writeSample(this.root, targetPath, options);
+  }
+
+  /**
+   * Validate two targets and compare their spec-relevant content
+   */
+  compare(leftPath: string, rightPath: string): CompareResult {
+    const left = this.validate(leftPath);
+    const right = this.validate(rightPath);
+    const differences = redline(this.describe(), leftPath, rightPath);
+    return { identical: differences.length === 0, left, right, differences };
+  }
+
+  /**
+   * Start an incremental upload validated against this schema
+   */
+  upload(options?: UploadOptions): UploadSession {
+    return new UploadSession(this.root, { ...this.engine.validationOptions, ...options });
+  }
+
+  /**
+   * Describe the schema (for documentation and code generation)
+   */
+  describe(): TypeDescription {
+    return this.root.describe();
+  }
+}
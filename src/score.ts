@@ -0,0 +1,103 @@
+// src/score.ts
+// Corpus scoring - how many real documents each rule of a spec rejects
+
+import fs from 'node:fs';
+import path from 'node:path';
+import type { Schema } from './schema.js';
+import { validateInput } from './batch.js';
+import { CoverageRecorder } from './coverage.js';
+
+export interface ScoreRule {
+  /** Where the rule applies, as in coverage reports (`(root)`, dotted keys, `[]` for list items) */
+  path: string;
+  kind: 'constraint' | 'required';
+  /** What it checks, e.g. "maximum 32 characters", "required" */
+  rule: string;
+  /** Documents that reached the rule */
+  checked: number;
+  /** Documents the rule rejected */
+  violations: number;
+  /** violations / total documents, from 0 to 1 */
+  share: number;
+  /** Some of the documents the rule rejected */
+  examples: string[];
+}
+
+export interface ScoreReport {
+  /** Documents scored */
+  total: number;
+  /** Documents without errors */
+  passed: number;
+  /** Documents that could not be read or parsed, so no rule was checked */
+  unreadable: string[];
+  /** Every constraint and required entry of the spec, most violated first (ties in spec order) */
+  rules: ScoreRule[];
+}
+
+export interface ScoreOptions {
+  /** Offending documents kept per rule (default: 3) */
+  examples?: number;
+}
+
+const UNREADABLE_CODES = new Set(['file.read_error', 'json.parse_error']);
+
+function jsonFiles(dir: string): string[] {
+  return fs.readdirSync(dir, { withFileTypes: true })
+    .filter(e => !e.name.startsWith('.'))
+    .sort((a, b) => a.name.localeCompare(b.name))
+    .flatMap(e => {
+      const full = path.join(dir, e.name);
+      if (e.isDirectory()) return jsonFiles(full);
+      return e.name.endsWith('.json') ? [full] : [];
+    });
+}
+
+/**
+ * The documents of a corpus, each once: for data specs a directory stands for every
+ * JSON file under it; anything else (and every input of a file system spec) is one document
+ */
+export function corpusDocuments(inputs: string[], fileSystem: boolean): string[] {
+  const documents = inputs.flatMap(input =>
+    !fileSystem && fs.existsSync(input) && fs.statSync(input).isDirectory() ? jsonFiles(input) : [input]
+  );
+  return [...new Set(documents)];
+}
+
+/**
+ * Run a compiled spec over a corpus of real documents and count, per rule, the
+ * documents it rejects. Use it to calibrate a constraint before publishing it:
+ * tighten the spec, score the corpus, and see what share of existing data breaks.
+ *
+ * @example
+ * const report = scoreCorpus(engine.compile('config.spec.js'), ['corpus']);
+ * for (const r of report.rules.filter(r => r.violations > 0)) console.log(r.path, r.rule, r.share, r.examples);
+ */
+export function scoreCorpus(schema: Schema, inputs: string[], options: ScoreOptions = {}): ScoreReport {
+  const documents = corpusDocuments(inputs, schema.describe().fsType !== undefined);
+  const recorder = new CoverageRecorder(schema.root, { examples: options.examples ?? 3 });
+  const unreadable: string[] = [];
+  let passed = 0;
+  for (const document of documents) {
+    const result = recorder.record(() => validateInput(schema, document), document);
+    if (result.ok) {
+      passed++;
+    } else if (result.issues.some(i => UNREADABLE_CODES.has(i.code) && i.path.length === 0)) {
+      unreadable.push(document);
+    }
+  }
+
+  const total = documents.length;
+  const rules = recorder.report().rules
+    .filter((r): r is typeof r & { kind: ScoreRule['kind'] } => r.kind !== 'branch')
+    .map((r): ScoreRule => ({
+      path: r.path,
+      kind: r.kind,
+      rule: r.rule,
+      checked: r.hits,
+      violations: r.failures,
+      share: total > 0 ? r.failures / total : 0,
+      examples: r.examples ?? [],
+    }))
+    .sort((a, b) => b.violations - a.violations);
+  return { total, passed, unreadable, rules };
+}
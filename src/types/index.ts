@@ -3,8 +3,10 @@
 export { Str, StrType, type StrSpec } from './primitives.js';
 export { Bool, BoolType, type BoolSpec } from './primitives.js';
 export { Num, NumType, type NumSpec } from './primitives.js';
+export { Decimal, DecimalType, type DecimalSpec } from './primitives.js';
 
 export { Field, FieldType, type FieldSpec } from './structural.js';
-export { File, FileType, type FileSpec } from './structural.js';
-export { Directory, DirectoryType, type DirectorySpec } from './structural.js';
+export { File, FileType, type FileSpec, type ChecksumSpec } from './structural.js';
+export { Directory, DirectoryType, type DirectorySpec, type DirectoryFilesSpec } from './structural.js';
 export { JsonFile, JsonFileType, type JsonFileSpec } from './structural.js';
+export { When, WhenType, type WhenSpec } from './structural.js';
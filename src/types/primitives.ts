@@ -1,5 +1,5 @@
 // src/types/primitives.ts
-// 基础类型：Str, Bool, Num
+// 基础类型：Str, Bool, Num, Decimal
 
 import { Type, type TypeDescription } from '../base.js';
 import type { Context } from '../context.js';
@@ -92,6 +92,7 @@ export class BoolType extends Type<BoolSpec | undefined, boolean> {
       name: 'Boolean',
       description: this.spec?.description,
       constraints: constraints.length > 0 ? constraints : undefined,
+      default: this.spec?.default,
     };
   }
 }
@@ -161,3 +162,155 @@ export const Num = Object.assign(
   (spec?: NumSpec) => spec ? new NumType(spec) : defaultNum,
   { _default: defaultNum }
 );
+
+// ═══════════════════════════════════════════════════════════════
+// Decimal - 精确十进制数值类型
+// ═══════════════════════════════════════════════════════════════
+
+export interface DecimalSpec {
+  /** Human-readable description */
+  description?: string;
+  /** Inclusive lower bound; use a string to keep the bound exact */
+  min?: string | number;
+  /** Inclusive upper bound; use a string to keep the bound exact */
+  max?: string | number;
+  /** Maximum number of significant digits (integer + fractional) */
+  precision?: number;
+  /** Maximum number of digits after the decimal point */
+  scale?: number;
+}
+
+interface DecimalParts {
+  negative: boolean;
+  /** Significant digits, without leading or trailing zeros */
+  digits: string;
+  /** Position of the decimal point, counted from the first digit */
+  point: number;
+}
+
+/**
+ * 将十进制文本（可带指数）拆分为符号、有效数字和小数点位置；
+ * 指数只参与计算，不展开成零，所以 1e999999999999 不会分配巨大的字符串
+ */
+function parseDecimal(text: string): DecimalParts | null {
+  const m = /^([+-])?(\d*)(?:\.(\d*))?(?:[eE]([+-]?\d+))?$/.exec(text.trim());
+  if (!m || m[2]! + (m[3] ?? '') === '') return null;
+
+  const all = m[2]! + (m[3] ?? '');
+  const significant = all.replace(/^0+/, '');
+  const digits = significant.replace(/0+$/, '');
+  if (digits === '') return { negative: false, digits, point: 0 };
+
+  const point = m[2]!.length - (all.length - significant.length) + Number(m[4] ?? '0');
+  if (!Number.isSafeInteger(point)) return null;
+  return { negative: m[1] === '-', digits, point };
+}
+
+/** Digits before the decimal point, including zeros up to it */
+function intLength(parts: DecimalParts): number {
+  return parts.digits === '' ? 0 : Math.max(0, parts.point);
+}
+
+/** Digits after the decimal point, including zeros after it */
+function fracLength(parts: DecimalParts): number {
+  return Math.max(0, parts.digits.length - parts.point);
+}
+
+function compareDecimal(a: DecimalParts, b: DecimalParts): number {
+  if (a.negative !== b.negative) return a.negative ? -1 : 1;
+  const sign = a.negative ? -1 : 1;
+
+  if (a.digits === '' || b.digits === '') {
+    return (a.digits === '' ? 0 : 1) - (b.digits === '' ? 0 : 1);
+  }
+  if (a.point !== b.point) {
+    return (a.point < b.point ? -1 : 1) * sign;
+  }
+  // Neither has trailing zeros, so digit strings compare like their values
+  if (a.digits === b.digits) return 0;
+  return (a.digits < b.digits ? -1 : 1) * sign;
+}
+
+/**
+ * Whether a decimal text reads back unchanged from the number it parses to, i.e. no
+ * digit is lost to double rounding ("0.1" does, "0.10000000000000000001" does not)
+ */
+export function isExactAsNumber(text: string): boolean {
+  const parts = parseDecimal(text);
+  const number = Number(text.trim());
+  if (!parts || !Number.isFinite(number)) return false;
+  const back = parseDecimal(String(number));
+  return back !== null && compareDecimal(parts, back) === 0;
+}
+
+export class DecimalType extends Type<DecimalSpec | undefined, number> {
+  validate(value: unknown, ctx: Context): void {
+    if (typeof value !== 'number' || !Number.isFinite(value)) {
+      ctx.addIssue('type.mismatch', `Expected number, got ${typeof value}`);
+      return;
+    }
+
+    const spec = this.spec;
+    if (!spec) return;
+
+    // String(value) yields the shortest round-trip form, which is the closest
+    // we can get to the source text once JSON.parse has produced a double
+    const text = String(value);
+    const parts = parseDecimal(text);
+    if (!parts) {
+      ctx.addIssue('type.mismatch', `Expected decimal number, got ${text}`);
+      return;
+    }
+
+    const places = fracLength(parts);
+    const digits = intLength(parts) + places;
+    if (spec.precision !== undefined && digits > spec.precision) {
+      ctx.addIssue('decimal.too_many_digits', `Number ${text} has ${digits} digits, maximum is ${spec.precision}`);
+    }
+
+    if (spec.scale !== undefined && places > spec.scale) {
+      ctx.addIssue('decimal.scale_exceeded', `Number ${text} has ${places} decimal places, maximum is ${spec.scale}`);
+    }
+
+    if (spec.min !== undefined) {
+      const min = parseDecimal(String(spec.min));
+      if (min && compareDecimal(parts, min) < 0) {
+        ctx.addIssue('num.too_small', `Number ${text} is less than minimum ${spec.min}`);
+      }
+    }
+
+    if (spec.max !== undefined) {
+      const max = parseDecimal(String(spec.max));
+      if (max && compareDecimal(parts, max) > 0) {
+        ctx.addIssue('num.too_large', `Number ${text} exceeds maximum ${spec.max}`);
+      }
+    }
+  }
+
+  describe(): TypeDescription {
+    const constraints: string[] = [];
+    if (this.spec?.precision !== undefined) {
+      constraints.push(`precision ${this.spec.precision} digits`);
+    }
+    if (this.spec?.scale !== undefined) {
+      constraints.push(`scale ${this.spec.scale}`);
+    }
+    if (this.spec?.min !== undefined) {
+      constraints.push(`minimum ${this.spec.min}`);
+    }
+    if (this.spec?.max !== undefined) {
+      constraints.push(`maximum ${this.spec.max}`);
+    }
+    return {
+      name: 'Decimal',
+      description: this.spec?.description,
+      constraints: constraints.length > 0 ? constraints : undefined,
+    };
+  }
+}
+
+const defaultDecimal = new DecimalType(undefined);
+export const Decimal = Object.assign(
+  (spec?: DecimalSpec) => spec ? new DecimalType(spec) : defaultDecimal,
+  { _default: defaultDecimal }
+);
@@ -1,44 +1,118 @@
 // src/types/structural.ts
-// Structural types: Field, File, Directory
+// Structural types: Field, File, Directory, When
 
-import fs from 'node:fs';
+import type fs from 'node:fs';
 import path from 'node:path';
-import { Type, Modifier, validateAny, type Validatable, type ObjectSpec, type TypeDescription, isObjectSpec, isType, isModifier, isLiteralValue } from '../base.js';
-import type { Context } from '../context.js';
-
-// Helper to describe any Validatable
-function describeValidatable(v: Validatable | ObjectSpec): TypeDescription {
-  if (isType(v)) {
-    return v.describe();
-  } else if (isModifier(v)) {
-    return (v as Modifier).describe();
-  } else if (isLiteralValue(v)) {
-    if (v instanceof RegExp) {
-      return { name: 'Pattern', constraints: [`matches \`${v}\``] };
-    }
-    return { name: 'Literal', constraints: [`equals ${JSON.stringify(v)}`] };
-  } else if (isObjectSpec(v)) {
-    return {
-      name: 'Object',
-      children: {
-        required: v.required?.map(describeValidatable),
-        optional: v.optional?.map(describeValidatable),
-      },
-    };
-  }
-  return { name: 'Unknown' };
+import { Type, validateAny, tryMatch, deprecationNotice, type Validatable, type ObjectSpec, type TypeDescription, type DeprecationSpec, isObjectSpec, isLiteralValue, describeValidatable } from '../base.js';
+import type { Context, JsonPatchOperation } from '../context.js';
+import { DEFAULT_DIGEST, getDigest } from '../digest.js';
+import { statSync, readFileSync, readTextFileSync, readdirSync } from '../fscache.js';
+import { PhaseModifier } from '../modifiers/phase.js';
+
+// ═══════════════════════════════════════════════════════════════
+// Paths - the same result on Windows as on Linux
+// ═══════════════════════════════════════════════════════════════
+// Spec and issue paths use forward slashes on every platform. Node reads paths beyond
+// MAX_PATH itself (it adds the `\\?\` prefix), so only device names need checking.
+
+/** Windows device names, reserved with any extension (`nul.txt`) in every directory */
+const RESERVED_NAMES = new Set([
+  'CON', 'PRN', 'AUX', 'NUL',
+  'COM1', 'COM2', 'COM3', 'COM4', 'COM5', 'COM6', 'COM7', 'COM8', 'COM9',
+  'LPT1', 'LPT2', 'LPT3', 'LPT4', 'LPT5', 'LPT6', 'LPT7', 'LPT8', 'LPT9',
+]);
+
+/** A spec path with forward slashes */
+export function normalizeRelPath(relPath: string): string {
+  return relPath.replace(/\\/g, '/');
+}
+
+/** The first segment of a relative path that is a reserved device name on Windows */
+export function reservedName(relPath: string): string | undefined {
+  return relPath.split(/[/\\]/).find(segment => {
+    const stem = segment.split('.')[0]!.trimEnd();
+    return RESERVED_NAMES.has(stem.toUpperCase());
+  });
+}
+
+/**
+ * Report `fs.reserved_name` for a path Windows cannot open. Such entries are not looked
+ * up on any platform, so a target gets the same result everywhere.
+ */
+function checkReservedName(relPath: string, ctx: Context): boolean {
+  const name = reservedName(relPath);
+  if (name === undefined) return true;
+  ctx.addIssue('fs.reserved_name', `'${name}' is a reserved name on Windows`);
+  return false;
 }
 
 // ═══════════════════════════════════════════════════════════════
 // Field - JSON field type
 // ═══════════════════════════════════════════════════════════════
 
+/** Escape a key for use as a JSON Pointer segment */
+function pointerSegment(key: string): string {
+  return '/' + key.replace(/~/g, '~0').replace(/\//g, '~1');
+}
+
+/** Edit distance counting adjacent transpositions as one edit */
+function editDistance(a: string, b: string): number {
+  const d: number[][] = [];
+  for (let i = 0; i <= a.length; i++) {
+    d.push([i]);
+  }
+  for (let j = 1; j <= b.length; j++) {
+    d[0]![j] = j;
+  }
+  for (let i = 1; i <= a.length; i++) {
+    for (let j = 1; j <= b.length; j++) {
+      const cost = a[i - 1] === b[j - 1] ? 0 : 1;
+      let best = Math.min(d[i - 1]![j]! + 1, d[i]![j - 1]! + 1, d[i - 1]![j - 1]! + cost);
+      if (i > 1 && j > 1 && a[i - 1] === b[j - 2] && a[i - 2] === b[j - 1]) {
+        best = Math.min(best, d[i - 2]![j - 2]! + 1);
+      }
+      d[i]![j] = best;
+    }
+  }
+  return d[a.length]![b.length]!;
+}
+
+/**
+ * Find a present key that looks like a misspelling of the missing one.
+ * Returns undefined when no key is close enough or the closest is ambiguous.
+ */
+function suggestKey(record: Record<string, unknown>, key: string): string | undefined {
+  const limit = Math.max(1, Math.floor(key.length / 3));
+  let best: string | undefined;
+  let bestDistance = Infinity;
+  let tied = false;
+  for (const candidate of Object.keys(record)) {
+    if (candidate === key || record[candidate] === undefined) continue;
+    const distance = editDistance(candidate.toLowerCase(), key.toLowerCase());
+    if (distance > limit) continue;
+    if (distance < bestDistance) {
+      best = candidate;
+      bestDistance = distance;
+      tied = false;
+    } else if (distance === bestDistance) {
+      tied = true;
+    }
+  }
+  return tied ? undefined : best;
+}
+
 export interface FieldSpec {
   /** Human-readable description */
   description?: string;
   key: string;
   value?: Validatable | ObjectSpec;
   optional?: boolean;
+  /** Accept null in place of the value */
+  nullable?: boolean;
+  /** Warn (field.deprecated) when the field is present; a string is the reason */
+  deprecated?: boolean | string | DeprecationSpec;
+  /** Value filled in by normalization when the field is missing (optional fields only) */
+  default?: unknown;
 }
 
 export class FieldType extends Type<FieldSpec, Record<string, unknown>> {
@@ -49,16 +123,33 @@ export class FieldType extends Type<FieldSpec, Record<string, unknown>> {
     }
 
     const record = obj as Record<string, unknown>;
-    const { key, value, optional } = this.spec;
+    const { key, value, optional, nullable } = this.spec;
     const fieldValue = record[key];
 
     if (fieldValue === undefined) {
       if (!optional) {
-        ctx.addIssue('field.missing', `Missing required field: ${key}`);
+        // Attach a fix when it is mechanical: rename a misspelled key, or add the only allowed value
+        const misspelled = suggestKey(record, key);
+        if (misspelled !== undefined) {
+          const fix: JsonPatchOperation[] = [{ op: 'move', from: pointerSegment(misspelled), path: pointerSegment(key) }];
+          ctx.addIssue('field.missing', `Missing required field: ${key} (did you mean to rename "${misspelled}"?)`, fix);
+        } else if (value !== undefined && isLiteralValue(value) && !(value instanceof RegExp)) {
+          ctx.addIssue('field.missing', `Missing required field: ${key}`, [{ op: 'add', path: pointerSegment(key), value }]);
+        } else {
+          ctx.addIssue('field.missing', `Missing required field: ${key}`);
+        }
       }
       return;
     }
 
+    if (this.spec.deprecated) {
+      ctx.child(key, fieldValue).addWarning('field.deprecated', this.notice());
+    }
+
+    if (fieldValue === null && nullable) {
+      return;
+    }
+
     if (value !== undefined) {
       const childCtx = ctx.child(key, fieldValue);
       if (isObjectSpec(value)) {
@@ -76,13 +167,20 @@ export class FieldType extends Type<FieldSpec, Record<string, unknown>> {
   }
 
   describe(): TypeDescription {
-    const { description, key, value, optional } = this.spec;
+    const { description, key, value, optional, nullable } = this.spec;
     const desc: TypeDescription = {
       name: 'Field',
       key,
       description,
       optional,
+      nullable,
     };
+    if (this.spec.deprecated) {
+      desc.deprecated = this.notice();
+    }
+    if (this.spec.default !== undefined) {
+      desc.default = this.spec.default;
+    }
 
     if (value !== undefined) {
       const valueDesc = describeValidatable(value);
@@ -91,11 +189,37 @@ export class FieldType extends Type<FieldSpec, Record<string, unknown>> {
       desc.constraints = valueDesc.constraints;
       desc.children = valueDesc.children;
       desc.oneOf = valueDesc.oneOf;
+      desc.allOf = valueDesc.allOf;
+      desc.not = valueDesc.not;
+      desc.discriminator = valueDesc.discriminator;
+      desc.variants = valueDesc.variants;
       desc.itemType = valueDesc.itemType;
+      desc.ref = valueDesc.ref;
+      desc.maxDepth = valueDesc.maxDepth;
+      desc.meta = valueDesc.meta;
+      desc.customIssue = valueDesc.customIssue;
+      desc.phase = valueDesc.phase;
+      desc.valueDescription = valueDesc.description;
+      // Raw spec of a custom type, for custom rendering
+      desc.spec = valueDesc.spec;
+      desc.valueDeprecated = valueDesc.deprecated;
+      desc.nullable = nullable || valueDesc.nullable;
+      if (optional) {
+        // A type's own default (e.g. Bool({ default: false })) applies to optional fields
+        desc.default ??= valueDesc.default;
+      }
     }
 
     return desc;
   }
+
+  private notice(): string {
+    const { key, deprecated } = this.spec;
+    const spec = typeof deprecated === 'object' && deprecated.replacement
+      ? { ...deprecated, replacement: `"${deprecated.replacement}"` }
+      : deprecated ?? true;
+    return deprecationNotice(`Field "${key}"`, spec);
+  }
 }
 
 export const Field = (spec: FieldSpec) => new FieldType(spec);
@@ -104,12 +228,20 @@ export const Field = (spec: FieldSpec) => new FieldType(spec);
 // File - File type
 // ═══════════════════════════════════════════════════════════════
 
+export interface ChecksumSpec {
+  /** Digest algorithm (default: sha256) */
+  algorithm?: string;
+  /** Expected digest as a hex string */
+  value: string;
+}
+
 export interface FileSpec {
   /** Human-readable description */
   description?: string;
   path?: string;
   ext?: string;
   content?: Validatable | ObjectSpec;
+  checksum?: ChecksumSpec;
 }
 
 export class FileType extends Type<FileSpec | undefined, string> {
@@ -120,12 +252,14 @@ export class FileType extends Type<FileSpec | undefined, string> {
     }
 
     const spec = this.spec;
-    const filePath = spec?.path ? path.join(basePath, spec.path) : basePath;
+    const relPath = spec?.path ? normalizeRelPath(spec.path) : undefined;
+    if (relPath && !checkReservedName(relPath, ctx)) return;
+    const filePath = relPath ? path.join(basePath, relPath) : basePath;
 
     // Check file exists
     let stat: fs.Stats;
     try {
-      stat = fs.statSync(filePath);
+      stat = statSync(filePath);
     } catch {
       ctx.addIssue('file.not_found', `File not found: ${filePath}`);
       return;
@@ -144,11 +278,25 @@ export class FileType extends Type<FileSpec | undefined, string> {
       }
     }
 
+    // Verify checksum
+    if (spec?.checksum) {
+      const algorithm = spec.checksum.algorithm ?? DEFAULT_DIGEST;
+      const digest = getDigest(algorithm);
+      if (!digest) {
+        ctx.addIssue('checksum.unsupported_algorithm', `Digest algorithm not available: ${algorithm}`);
+      } else {
+        const actual = digest.hash(readFileSync(filePath));
+        if (actual !== spec.checksum.value.toLowerCase()) {
+          ctx.addIssue('checksum.mismatch', `${algorithm} digest mismatch: expected ${spec.checksum.value}, got ${actual}`);
+        }
+      }
+    }
+
     // Validate content
     if (spec?.content) {
-      const childCtx = ctx.child(spec.path ?? path.basename(filePath), null);
+      const childCtx = ctx.child(relPath ?? path.basename(filePath), null);
       try {
-        const content = fs.readFileSync(filePath, 'utf-8');
+        const content = readTextFileSync(filePath);
         // Try to parse as JSON if content validation is specified
         let parsedContent: unknown = content;
         try {
@@ -175,9 +323,10 @@ export class FileType extends Type<FileSpec | undefined, string> {
 
   matches(basePath: unknown, _ctx: Context): boolean {
     if (typeof basePath !== 'string') return false;
-    const filePath = this.spec?.path ? path.join(basePath, this.spec.path) : basePath;
+    if (this.spec?.path && reservedName(this.spec.path) !== undefined) return false;
+    const filePath = this.spec?.path ? path.join(basePath, normalizeRelPath(this.spec.path)) : basePath;
     try {
-      const stat = fs.statSync(filePath);
+      const stat = statSync(filePath);
       if (!stat.isFile()) return false;
       if (this.spec?.ext) {
         const ext = path.extname(filePath).slice(1);
@@ -203,6 +352,12 @@ export class FileType extends Type<FileSpec | undefined, string> {
       fileExt: this.spec?.ext,
       constraints: constraints.length > 0 ? constraints : undefined,
     };
+    if (this.spec?.checksum) {
+      const algorithm = this.spec.checksum.algorithm ?? DEFAULT_DIGEST;
+      constraints.push(`${algorithm} checksum ${this.spec.checksum.value}`);
+      desc.constraints = constraints;
+      desc.checksum = { algorithm, value: this.spec.checksum.value.toLowerCase() };
+    }
     if (this.spec?.content) {
       const contentDesc = describeValidatable(this.spec.content);
       desc.children = contentDesc.children ?? { required: [contentDesc] };
@@ -221,11 +376,88 @@ export const File = Object.assign(
 // Directory - Directory type
 // ═══════════════════════════════════════════════════════════════
 
+export interface DirectoryFilesSpec {
+  /** Only count files with this extension (without the dot) */
+  ext?: string;
+  /** Only count files whose name matches */
+  match?: RegExp;
+  min?: number;
+  max?: number;
+}
+
 export interface DirectorySpec {
   /** Human-readable description */
   description?: string;
   path?: string;
   content?: Validatable | ObjectSpec;
+  /** Report entries the content does not declare */
+  strict?: boolean;
+  /** How many files (directly inside, of a kind) the directory holds */
+  files?: DirectoryFilesSpec;
+}
+
+/**
+ * Entry names (first path segment) declared by a directory's content
+ */
+export function declaredEntries(content: Validatable | ObjectSpec | undefined): string[] {
+  if (content === undefined) return [];
+  const items = isObjectSpec(content)
+    ? [...(content.required ?? []), ...(content.optional ?? [])]
+    : [content];
+  const names: string[] = [];
+  for (const phased of items) {
+    const item = phased instanceof PhaseModifier ? phased.inner : phased;
+    if ((item instanceof FileType || item instanceof JsonFileType || item instanceof DirectoryType) && item.spec?.path) {
+      names.push(item.spec.path.split(/[\\/]/)[0]!);
+    } else if (item instanceof WhenType) {
+      // Either branch may apply
+      names.push(...declaredEntries(item.spec.then), ...declaredEntries(item.spec.else));
+    }
+  }
+  return names;
+}
+
+/**
+ * Validate a directory's content: required items must exist and validate,
+ * optional files and directories are only validated when they exist
+ */
+export function validateDirectoryContent(content: Validatable | ObjectSpec, dirPath: string, ctx: Context): void {
+  if (!isObjectSpec(content)) {
+    validateAny(content, dirPath, ctx);
+    return;
+  }
+  for (const item of content.required ?? []) {
+    validateAny(item, dirPath, ctx);
+  }
+  for (const item of content.optional ?? []) {
+    const entry = item instanceof PhaseModifier ? item.inner : item;
+    if (entry instanceof FileType || entry instanceof DirectoryType) {
+      // Only validate if the file/directory exists
+      if (entry.matches(dirPath, ctx)) {
+        validateAny(item, dirPath, ctx);
+      }
+    } else {
+      validateAny(item, dirPath, ctx);
+    }
+  }
+}
+
+// ".png files", "files matching /^icon-/"
+function filesLabel(files: DirectoryFilesSpec, count: number): string {
+  const noun = `${files.ext ? `.${files.ext} ` : ''}file${count === 1 ? '' : 's'}`;
+  return files.match ? `${noun} matching ${files.match}` : noun;
+}
+
+function countFiles(dirPath: string, files: DirectoryFilesSpec): number {
+  return readdirSync(dirPath).filter(name => {
+    if (files.ext !== undefined && path.extname(name).slice(1) !== files.ext) return false;
+    if (files.match && !files.match.test(name)) return false;
+    try {
+      return statSync(path.join(dirPath, name)).isFile();
+    } catch {
+      return false;
+    }
+  }).length;
 }
 
 export class DirectoryType extends Type<DirectorySpec | undefined, string> {
@@ -236,12 +468,14 @@ export class DirectoryType extends Type<DirectorySpec | undefined, string> {
     }
 
     const spec = this.spec;
-    const dirPath = spec?.path ? path.join(basePath, spec.path) : basePath;
+    const relPath = spec?.path ? normalizeRelPath(spec.path) : undefined;
+    if (relPath && !checkReservedName(relPath, ctx)) return;
+    const dirPath = relPath ? path.join(basePath, relPath) : basePath;
 
     // Check directory exists
     let stat: fs.Stats;
     try {
-      stat = fs.statSync(dirPath);
+      stat = statSync(dirPath);
     } catch {
       ctx.addIssue('dir.not_found', `Directory not found: ${dirPath}`);
       return;
@@ -254,34 +488,38 @@ export class DirectoryType extends Type<DirectorySpec | undefined, string> {
 
     // Validate content (child items)
     if (spec?.content) {
-      const childCtx = ctx.child(spec.path ?? path.basename(dirPath), dirPath);
-      if (isObjectSpec(spec.content)) {
-        // Required items must exist and validate
-        for (const item of spec.content.required ?? []) {
-          validateAny(item, dirPath, childCtx);
-        }
-        // Optional items only validated if they exist (match)
-        for (const item of spec.content.optional ?? []) {
-          if (item instanceof FileType || item instanceof DirectoryType) {
-            // Only validate if the file/directory exists
-            if (item.matches(dirPath, childCtx)) {
-              validateAny(item, dirPath, childCtx);
-            }
-          } else {
-            validateAny(item, dirPath, childCtx);
-          }
+      validateDirectoryContent(spec.content, dirPath, ctx.child(relPath ?? path.basename(dirPath), dirPath));
+    }
+
+    if (spec?.files) {
+      const { min, max } = spec.files;
+      const count = countFiles(dirPath, spec.files);
+      const childCtx = ctx.child(relPath ?? path.basename(dirPath), dirPath);
+      if (min !== undefined && count < min) {
+        childCtx.addIssue('dir.too_few_files', `Expected at least ${min} ${filesLabel(spec.files, min)} in ${dirPath}, found ${count}`);
+      }
+      if (max !== undefined && count > max) {
+        childCtx.addIssue('dir.too_many_files', `Expected at most ${max} ${filesLabel(spec.files, max)} in ${dirPath}, found ${count}`);
+      }
+    }
+
+    if (spec?.strict) {
+      const declared = new Set(declaredEntries(spec.content));
+      const childCtx = ctx.child(relPath ?? path.basename(dirPath), dirPath);
+      for (const entry of readdirSync(dirPath).sort()) {
+        if (!declared.has(entry)) {
+          childCtx.child(entry, null).addIssue('dir.unexpected', `Unexpected entry: ${path.join(dirPath, entry)}`);
         }
-      } else {
-        validateAny(spec.content, dirPath, childCtx);
       }
     }
   }
 
   matches(basePath: unknown, _ctx: Context): boolean {
     if (typeof basePath !== 'string') return false;
-    const dirPath = this.spec?.path ? path.join(basePath, this.spec.path) : basePath;
+    if (this.spec?.path && reservedName(this.spec.path) !== undefined) return false;
+    const dirPath = this.spec?.path ? path.join(basePath, normalizeRelPath(this.spec.path)) : basePath;
     try {
-      const stat = fs.statSync(dirPath);
+      const stat = statSync(dirPath);
       return stat.isDirectory();
     } catch {
       return false;
@@ -296,6 +534,21 @@ export class DirectoryType extends Type<DirectorySpec | undefined, string> {
       description: this.spec?.description,
       filePath: this.spec?.path,
     };
+    const constraints: string[] = [];
+    if (this.spec?.strict) {
+      desc.strict = true;
+      constraints.push('no undeclared entries');
+    }
+    const files = this.spec?.files;
+    if (files?.min !== undefined) {
+      constraints.push(`minimum ${files.min} ${filesLabel(files, files.min)}`);
+    }
+    if (files?.max !== undefined) {
+      constraints.push(`maximum ${files.max} ${filesLabel(files, files.max)}`);
+    }
+    if (constraints.length > 0) {
+      desc.constraints = constraints;
+    }
     if (this.spec?.content) {
       if (isObjectSpec(this.spec.content)) {
         desc.children = {
@@ -317,6 +570,73 @@ export const Directory = Object.assign(
   { _default: defaultDirectory }
 );
 
+// ═══════════════════════════════════════════════════════════════
+// When - directory content depending on a JSON file's value
+// ═══════════════════════════════════════════════════════════════
+
+export interface WhenSpec {
+  /** Human-readable description */
+  description?: string;
+  /** JSON file holding the value, relative to the directory */
+  file: string;
+  /** Dotted path of the value in the file (default: the whole document) */
+  field?: string;
+  /** What the value must match for `then` to apply (default: the value is present) */
+  is?: Validatable;
+  /** Content checked when the value matches */
+  then?: Validatable | ObjectSpec;
+  /** Content checked when it does not, or is missing */
+  else?: Validatable | ObjectSpec;
+}
+
+/** A value at a dotted path (`icons.enabled`, `targets.0.name`); undefined when missing */
+function valueAt(document: unknown, field: string | undefined): unknown {
+  let value = document;
+  for (const segment of field === undefined ? [] : field.split('.')) {
+    if (value === null || typeof value !== 'object') return undefined;
+    value = (value as Record<string, unknown>)[segment];
+  }
+  return value;
+}
+
+export class WhenType extends Type<WhenSpec, string> {
+  validate(dirPath: unknown, ctx: Context): void {
+    if (typeof dirPath !== 'string') {
+      ctx.addIssue('type.mismatch', `Expected path string, got ${typeof dirPath}`);
+      return;
+    }
+
+    const { file, field, is } = this.spec;
+    let document: unknown;
+    try {
+      document = JSON.parse(readTextFileSync(path.join(dirPath, file)));
+    } catch {
+      // A missing or broken file decides nothing; its own rules report it
+      return;
+    }
+
+    const value = valueAt(document, field);
+    const holds = value !== undefined && (is === undefined || tryMatch(is, value, ctx));
+    const content = holds ? this.spec.then : this.spec.else;
+    if (content !== undefined) {
+      validateDirectoryContent(content, dirPath, ctx);
+    }
+  }
+
+  describe(): TypeDescription {
+    const { description, file, field, is } = this.spec;
+    return {
+      name: 'When',
+      description,
+      condition: { file, field, is: is === undefined ? undefined : describeValidatable(is) },
+      then: this.spec.then === undefined ? undefined : describeValidatable(this.spec.then),
+      else: this.spec.else === undefined ? undefined : describeValidatable(this.spec.else),
+    };
+  }
+}
+
+export const When = (spec: WhenSpec) => new WhenType(spec);
+
 // ═══════════════════════════════════════════════════════════════
 // JsonFile - JSON file type (convenience wrapper)
 // ═══════════════════════════════════════════════════════════════
@@ -336,13 +656,15 @@ export class JsonFileType extends Type<JsonFileSpec, string> {
       return;
     }
 
-    const { path: filePath, required, optional } = this.spec;
+    const { required, optional } = this.spec;
+    const filePath = normalizeRelPath(this.spec.path);
+    if (!checkReservedName(filePath, ctx)) return;
     const fullPath = path.join(basePath, filePath);
 
     // Check file exists
     let stat: fs.Stats;
     try {
-      stat = fs.statSync(fullPath);
+      stat = statSync(fullPath);
     } catch {
       ctx.addIssue('file.not_found', `File not found: ${fullPath}`);
       return;
@@ -356,7 +678,7 @@ export class JsonFileType extends Type<JsonFileSpec, string> {
     // Parse JSON
     let content: unknown;
     try {
-      const raw = fs.readFileSync(fullPath, 'utf-8');
+      const raw = readTextFileSync(fullPath);
       content = JSON.parse(raw);
     } catch (err) {
       ctx.addIssue('json.parse_error', `Failed to parse JSON: ${(err as Error).message}`);
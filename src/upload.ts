@@ -0,0 +1,224 @@
+// src/upload.ts
+// UploadSession - incremental validation of a bundle whose files arrive one at a time
+
+import fs from 'node:fs';
+import os from 'node:os';
+import path from 'node:path';
+import { ValidationContext, type Context, type Issue, type ValidationOptions } from './context.js';
+import { isObjectSpec, validateAny, type Type, type Modifier, type Validatable, type ObjectSpec } from './base.js';
+import { FileType, DirectoryType, JsonFileType } from './types/structural.js';
+import { validateInPhases } from './modifiers/phase.js';
+import type { ValidationResult } from './engine.js';
+
+export interface UploadOptions extends ValidationOptions {
+  /** Name of the staged root directory, used as the first issue path segment (default: "upload") */
+  name?: string;
+}
+
+export interface UploadStatus {
+  /** False once any error has been reported so far */
+  ok: boolean;
+  /** Relative paths received so far, in arrival order */
+  received: string[];
+  /** Required spec files not yet received */
+  pending: string[];
+  /** Issues from per-file rules for the files received so far */
+  issues: Issue[];
+}
+
+/** A file item of the spec, with where it sits in the tree */
+interface FileRule {
+  item: FileType | JsonFileType;
+  /** Directory holding the file, relative to the upload root ('' for the root) */
+  dir: string;
+  /** Context path segments of the enclosing directory */
+  ctxPath: string[];
+  required: boolean;
+}
+
+function toPosix(p: string): string {
+  return p.split(path.sep).join('/');
+}
+
+// Why a received file cannot be staged at rel (relative to the upload root), if it cannot
+function invalidPath(rel: string, fullPath: string): string | undefined {
+  if (path.isAbsolute(rel) || rel === '..' || rel.startsWith('../')) {
+    return 'Path escapes the upload root';
+  }
+  if (rel === '') {
+    return 'Path is the upload root';
+  }
+  try {
+    if (fs.statSync(fullPath).isDirectory()) {
+      return 'Path is a directory';
+    }
+  } catch {
+    // Not received yet
+  }
+  return undefined;
+}
+
+/**
+ * Collect the file rules reachable from a directory's content
+ */
+function collectRules(
+  content: Validatable | ObjectSpec | undefined,
+  dir: string,
+  ctxPath: string[],
+  required: boolean,
+  rules: Map<string, FileRule>
+): void {
+  if (content === undefined) return;
+  const entries: Array<[Validatable, boolean]> = isObjectSpec(content)
+    ? [
+      ...(content.required ?? []).map((v): [Validatable, boolean] => [v, required]),
+      ...(content.optional ?? []).map((v): [Validatable, boolean] => [v, false]),
+    ]
+    : [[content as Validatable, required]];
+
+  for (const [item, itemRequired] of entries) {
+    if ((item instanceof FileType || item instanceof JsonFileType) && item.spec?.path) {
+      const rel = toPosix(path.join(dir, item.spec.path));
+      rules.set(rel, { item, dir, ctxPath, required: itemRequired });
+    } else if (item instanceof DirectoryType && item.spec?.path) {
+      const sub = path.join(dir, item.spec.path);
+      collectRules(item.spec.content, sub, [...ctxPath, item.spec.path], itemRequired, rules);
+    }
+  }
+}
+
+/**
+ * UploadSession - validates a bundle while its files are still arriving
+ *
+ * Files are staged in a temporary directory. Rules scoped to a single file run
+ * as soon as that file arrives; the full spec (presence of required items,
+ * directory-level rules) runs at finalize().
+ *
+ * @example
+ * const session = new UploadSession(Directory({ content: { required: [JsonFile({ path: 'meta.json', ... })] } }));
+ * session.addFile('meta.json', body);
+ * session.status();   // partial result
+ * session.finalize(); // full ValidationResult, staging removed
+ */
+export class UploadSession {
+  private readonly stagingDir: string;
+  private readonly rootDir: string;
+  private readonly rules = new Map<string, FileRule>();
+  private readonly received: string[] = [];
+  private readonly fileIssues = new Map<string, Issue[]>();
+  private readonly sessionIssues: Issue[] = [];
+  private closed = false;
+  private readonly validationOptions: ValidationOptions;
+
+  constructor(private readonly root: Type | Modifier, options?: UploadOptions) {
+    const name = options?.name ?? 'upload';
+    this.validationOptions = { sampling: options?.sampling ?? true };
+    this.stagingDir = fs.mkdtempSync(path.join(os.tmpdir(), 'specspec-upload-'));
+    this.rootDir = path.join(this.stagingDir, name);
+    fs.mkdirSync(this.rootDir);
+
+    // Issue paths match a full run against the staged root directory
+    if (root instanceof DirectoryType) {
+      collectRules(root.spec?.content, root.spec?.path ?? '', [root.spec?.path ?? name], true, this.rules);
+    } else {
+      collectRules(root, '', [], true, this.rules);
+    }
+  }
+
+  /**
+   * Receive one file and run the rules scoped to it.
+   * Receiving the same path again replaces the earlier content and its issues.
+   */
+  addFile(relPath: string, data: Uint8Array | string): Issue[] {
+    if (this.closed) {
+      throw new Error('Upload session is already finalized');
+    }
+
+    const fullPath = path.resolve(this.rootDir, relPath);
+    const rel = toPosix(path.relative(this.rootDir, fullPath));
+    const invalid = invalidPath(rel, fullPath);
+    if (invalid) {
+      const issue: Issue = {
+        level: 'error',
+        code: 'upload.invalid_path',
+        message: `${invalid}: ${relPath}`,
+        path: [relPath],
+      };
+      this.sessionIssues.push(issue);
+      return [issue];
+    }
+
+    fs.mkdirSync(path.dirname(fullPath), { recursive: true });
+    fs.writeFileSync(fullPath, data);
+    if (!this.received.includes(rel)) {
+      this.received.push(rel);
+    }
+
+    const rule = this.rules.get(rel);
+    if (!rule) {
+      return [];
+    }
+
+    const ctx = new ValidationContext([], this.rootDir, undefined, undefined, this.validationOptions);
+    let fileCtx: Context = ctx;
+    for (const segment of rule.ctxPath) {
+      fileCtx = fileCtx.child(segment, null);
+    }
+    try {
+      validateAny(rule.item, path.join(this.rootDir, rule.dir), fileCtx);
+    } catch (err) {
+      fileCtx.addIssue('engine.error', `Validation error: ${(err as Error).message}`);
+    }
+    this.fileIssues.set(rel, ctx.issues);
+    return ctx.issues;
+  }
+
+  /**
+   * Partial result for the files received so far
+   */
+  status(): UploadStatus {
+    const issues = [...this.sessionIssues, ...Array.from(this.fileIssues.values()).flat()];
+    const pending = Array.from(this.rules.entries())
+      .filter(([rel, rule]) => rule.required && !this.received.includes(rel))
+      .map(([rel]) => rel);
+    return {
+      ok: issues.every(i => i.level !== 'error'),
+      received: [...this.received],
+      pending,
+      issues,
+    };
+  }
+
+  /**
+   * Run the full spec against the received files and remove the staging area
+   */
+  finalize(): ValidationResult {
+    if (this.closed) {
+      throw new Error('Upload session is already finalized');
+    }
+
+    const ctx = new ValidationContext([], this.rootDir, undefined, undefined, this.validationOptions);
+    try {
+      validateInPhases(ctx, () => validateAny(this.root, this.rootDir, ctx));
+    } catch (err) {
+      ctx.addIssue('engine.error', `Validation error: ${(err as Error).message}`);
+    } finally {
+      this.abort();
+    }
+
+    const issues = [...this.sessionIssues, ...ctx.issues];
+    return {
+      ok: issues.every(i => i.level !== 'error'),
+      issues,
+      ...(ctx.partial ? { partial: true } : {}),
+    };
+  }
+
+  /**
+   * Discard the upload without validating it
+   */
+  abort(): void {
+    this.closed = true;
+    fs.rmSync(this.stagingDir, { recursive: true, force: true });
+  }
+}
@@ -0,0 +1,273 @@
+// src/watcher.ts
+// SchemaWatcher - hot-reload a spec in a long-running service
+
+import fs from 'node:fs';
+import path from 'node:path';
+import type { SpecEngine, ValidationResult } from './engine.js';
+import { SchemaError, type Schema } from './schema.js';
+import type { Issue } from './context.js';
+
+/**
+ * Where a spec's source code comes from: a file, or an entry in a spec registry
+ */
+export interface SpecSource {
+  /** Name used as the sandbox filename and in error messages */
+  name: string;
+  /** Read the current spec code */
+  read(): string;
+  /** Start watching for changes; returns a function that stops watching */
+  watch?(onChange: () => void): () => void;
+}
+
+export interface FileSourceOptions {
+  /** Polling interval in milliseconds (default: 1000) */
+  interval?: number;
+}
+
+/**
+ * Spec source backed by a file, polled for modification.
+ * Polling (rather than fs.watch) survives editors that save by renaming over the file.
+ */
+export function fileSource(specPath: string, options?: FileSourceOptions): SpecSource {
+  return {
+    name: specPath,
+    read: () => fs.readFileSync(specPath, 'utf-8'),
+    watch(onChange) {
+      const listener = (curr: fs.Stats, prev: fs.Stats): void => {
+        if (curr.mtimeMs !== prev.mtimeMs || curr.size !== prev.size) {
+          onChange();
+        }
+      };
+      fs.watchFile(specPath, { interval: options?.interval ?? 1000, persistent: false }, listener);
+      return () => fs.unwatchFile(specPath, listener);
+    },
+  };
+}
+
+export interface SchemaWatcherOptions {
+  /** Extra check on a newly compiled spec; throw to reject it (e.g. validate a known-good sample) */
+  verify?: (schema: Schema) => void;
+  /** Called after a new spec has been swapped in */
+  onReload?: (schema: Schema) => void;
+  /** Called when a changed spec fails to load; the previous spec stays active */
+  onError?: (error: Error) => void;
+  /** Polling interval for the files the spec imports, in milliseconds (default: 1000) */
+  interval?: number;
+}
+
+/**
+ * SchemaWatcher - keeps a compiled spec current with its source
+ *
+ * When the source or a file it imports (transitively) changes, the new spec is
+ * compiled and checked before it replaces the active one. The swap is a single reference assignment, so a
+ * validation in progress finishes against the spec it started with. A spec
+ * that fails to load is reported through onError and never swapped in.
+ *
+ * @example
+ * const watcher = new SchemaWatcher(engine, 'package.spec.js', {
+ *   onError: (err) => log.warn(`spec reload failed: ${err.message}`),
+ * });
+ * app.post('/check', (req, res) => res.json(watcher.validate(req.body.path)));
+ */
+export class SchemaWatcher {
+  private readonly source: SpecSource;
+  private active: Schema;
+  private activeCode: string;
+  /** Contents of the imported files the active spec was compiled with (undefined: unreadable) */
+  private activeImports = new Map<string, string | undefined>();
+  private stopWatching: (() => void) | undefined;
+  /** Stops watching each imported file, by path */
+  private readonly importWatchers = new Map<string, () => void>();
+
+  /**
+   * Loads the spec immediately; throws if the initial spec cannot be loaded
+   */
+  constructor(
+    private readonly engine: SpecEngine,
+    source: string | SpecSource,
+    private readonly options: SchemaWatcherOptions = {}
+  ) {
+    this.source = typeof source === 'string' ? fileSource(source) : source;
+    this.activeCode = this.source.read();
+    this.active = this.compile(this.activeCode);
+    this.stopWatching = this.source.watch?.(() => this.reload());
+    this.track(this.active.imports);
+  }
+
+  /**
+   * The currently active schema
+   */
+  get current(): Schema {
+    return this.active;
+  }
+
+  /**
+   * Validate a target against the currently active spec
+   */
+  validate(targetPath: string): ValidationResult {
+    return this.active.validate(targetPath);
+  }
+
+  /**
+   * Re-read the source and swap in the new spec if it loads.
+   * Call directly when the source has no watch support (e.g. on a registry notification).
+   * Returns true when a new spec was swapped in.
+   */
+  reload(): boolean {
+    let code: string;
+    let schema: Schema;
+    try {
+      code = this.source.read();
+      if (code === this.activeCode && !this.importsChanged()) {
+        return false;
+      }
+      schema = this.compile(code);
+    } catch (err) {
+      // Also watch imports the broken spec added, so fixing them triggers a reload
+      if (err instanceof SchemaError) {
+        this.watchImports([...this.activeImports.keys(), ...err.imports]);
+      }
+      this.options.onError?.(err as Error);
+      return false;
+    }
+
+    this.active = schema;
+    this.activeCode = code;
+    this.track(schema.imports);
+    this.options.onReload?.(schema);
+    return true;
+  }
+
+  /**
+   * Stop watching the source. The active spec stays usable.
+   */
+  close(): void {
+    this.stopWatching?.();
+    this.stopWatching = undefined;
+    this.watchImports([]);
+  }
+
+  private compile(code: string): Schema {
+    const schema = this.engine.compileSource(code, this.source.name);
+    this.options.verify?.(schema);
+    return schema;
+  }
+
+  // Remember the imported files of the active spec and watch them along with the source
+  private track(imports: string[]): void {
+    this.activeImports = new Map(imports.map(file => [file, readIfExists(file)]));
+    this.watchImports(imports);
+  }
+
+  private importsChanged(): boolean {
+    return [...this.activeImports].some(([file, code]) => readIfExists(file) !== code);
+  }
+
+  // Watch exactly these imported files; only a watched source gets its imports watched
+  private watchImports(files: string[]): void {
+    for (const [file, stop] of this.importWatchers) {
+      if (!files.includes(file)) {
+        stop();
+        this.importWatchers.delete(file);
+      }
+    }
+    if (this.stopWatching === undefined) {
+      return;
+    }
+    for (const file of files) {
+      if (!this.importWatchers.has(file)) {
+        this.importWatchers.set(file, fileSource(file, { interval: this.options.interval ?? 1000 }).watch!(() => this.reload()));
+      }
+    }
+  }
+}
+
+function readIfExists(file: string): string | undefined {
+  try {
+    return fs.readFileSync(file, 'utf-8');
+  } catch {
+    return undefined;
+  }
+}
+}
+
+export interface WatchTreeOptions {
+  /** Polling interval in milliseconds (default: 500) */
+  interval?: number;
+}
+
+// Modification times and sizes of a file, or of every file under a directory
+function snapshot(root: string, into: string[]): string[] {
+  let stat: fs.Stats;
+  try {
+    stat = fs.statSync(root);
+  } catch {
+    into.push(`${root} missing`);
+    return into;
+  }
+  if (stat.isDirectory()) {
+    into.push(`${root}/`);
+    for (const entry of fs.readdirSync(root).sort()) {
+      snapshot(path.join(root, entry), into);
+    }
+  } else {
+    into.push(`${root} ${stat.mtimeMs} ${stat.size}`);
+  }
+  return into;
+}
+
+/**
+ * Call onChange when any of the files, or any file under the directories, is
+ * created, modified or removed. Polled like fileSource(), but the poller keeps
+ * the process alive; returns a function that stops watching.
+ */
+export function watchTree(paths: string[], onChange: () => void, options?: WatchTreeOptions): () => void {
+  const take = (): string => paths.flatMap(p => snapshot(p, [])).join('\n');
+  let last = take();
+  const timer = setInterval(() => {
+    const current = take();
+    if (current !== last) {
+      last = current;
+      onChange();
+    }
+  }, options?.interval ?? 500);
+  return () => clearInterval(timer);
+}
+
+export interface IssueDiff {
+  /** Issues of the new run that the previous one did not report */
+  added: Issue[];
+  /** Issues of the previous run that the new one no longer reports */
+  resolved: Issue[];
+}
+
+function issueKey(issue: Issue): string {
+  return JSON.stringify([issue.level, issue.code, issue.path, issue.message]);
+}
+
+/**
+ * Compare the issues of two runs. Issues are the same when level, code, path
+ * and message match; repeated issues are counted.
+ */
+export function diffIssues(before: Issue[], after: Issue[]): IssueDiff {
+  const remaining = new Map<string, number>();
+  for (const issue of before) {
+    remaining.set(issueKey(issue), (remaining.get(issueKey(issue)) ?? 0) + 1);
+  }
+  const added: Issue[] = [];
+  for (const issue of after) {
+    const count = remaining.get(issueKey(issue)) ?? 0;
+    if (count > 0) {
+      remaining.set(issueKey(issue), count - 1);
+    } else {
+      added.push(issue);
+    }
+  }
+  const resolved = before.filter(issue => {
+    const count = remaining.get(issueKey(issue)) ?? 0;
+    if (count === 0) return false;
+    remaining.set(issueKey(issue), count - 1);
+    return true;
+  });
+  return { added, resolved };
+}
@@ -0,0 +1,80 @@
+// test/batch.test.ts
+
+import { describe, it, expect, beforeEach, afterEach } from 'vitest';
+import fs from 'node:fs';
+import os from 'node:os';
+import path from 'node:path';
+import { expandInputs, validateBatch, summarizeBatch, validateJsonText } from '../dist/batch.js';
+import { SpecEngine } from '../dist/engine.js';
+import { SchemaError } from '../dist/schema.js';
+
+describe('batch validation', () => {
+  let tmpDir: string;
+
+  const write = (rel: string, content: string) => {
+    fs.mkdirSync(path.dirname(path.join(tmpDir, rel)), { recursive: true });
+    fs.writeFileSync(path.join(tmpDir, rel), content);
+  };
+
+  beforeEach(() => {
+    tmpDir = fs.mkdtempSync(path.join(os.tmpdir(), 'specspec-batch-'));
+    write('config.spec.js', `Def('Config', { required: [Field({ key: 'name', value: Str({ minLength: 3 }) })] })`);
+    write('data/a.json', '{"name":"alpha"}');
+    write('data/b.json', '{"name":"b"}');
+    write('data/nested/c.json', '{"name":"c"}');
+    write('data/nested/broken.json', '{"name":');
+    write('data/.hidden.json', '{}');
+    write('data/notes.txt', '');
+  });
+
+  afterEach(() => {
+    fs.rmSync(tmpDir, { recursive: true, force: true });
+  });
+
+  it('expands glob patterns', () => {
+    const rel = (patterns: string[]) => expandInputs(patterns, tmpDir).map(p => path.relative(tmpDir, p));
+    expect(rel(['data/*.json'])).toEqual(['data/a.json', 'data/b.json']);
+    expect(rel(['data/**/*.json'])).toEqual(['data/a.json', 'data/b.json', 'data/nested/broken.json', 'data/nested/c.json']);
+    expect(rel(['data/{a,b}.json', 'data/a.json'])).toEqual(['data/a.json', 'data/b.json']);
+    expect(rel(['data/*/'])).toEqual(['data/nested']);
+    expect(rel(['missing.json'])).toEqual(['missing.json']);
+  });
+
+  it('validates inputs in parallel and reports them in order', async () => {
+    const inputs = expandInputs(['data/**/*.json', 'missing.json'], tmpDir);
+    const serial = await validateBatch(path.join(tmpDir, 'config.spec.js'), inputs, { jobs: 1 });
+    const parallel = await validateBatch(path.join(tmpDir, 'config.spec.js'), inputs, { jobs: 3 });
+
+    expect(parallel).toEqual(serial);
+    expect(serial.results.map(r => r.input)).toEqual(inputs);
+    expect(serial).toMatchObject({ ok: false, total: 5, passed: 1, failed: 4 });
+    expect(serial.codes).toEqual([
+      { code: 'str.too_short', inputs: 2, issues: 2 },
+      { code: 'file.read_error', inputs: 1, issues: 1 },
+      { code: 'json.parse_error', inputs: 1, issues: 1 },
+    ]);
+  });
+
+  it('throws SchemaError when the spec does not compile', async () => {
+    write('bad.spec.js', 'Str({');
+    await expect(validateBatch(path.join(tmpDir, 'bad.spec.js'), [])).rejects.toThrow('Spec file error');
+    await validateBatch(path.join(tmpDir, 'bad.spec.js'), []).catch(err => expect(err).toBeInstanceOf(SchemaError));
+  });
+
+  it('validates JSON text, such as a document read from stdin', () => {
+    const schema = new SpecEngine().compile(path.join(tmpDir, 'config.spec.js'));
+    expect(validateJsonText(schema, '{"name":"alpha"}')).toEqual({ ok: true, issues: [] });
+    expect(validateJsonText(schema, '{"name":"a"}').issues.map(i => i.code)).toEqual(['str.too_short']);
+    expect(validateJsonText(schema, '{"name":').issues.map(i => i.code)).toEqual(['json.parse_error']);
+  });
+
+  it('counts each code once per input', () => {
+    const issue = (code: string, level: 'error' | 'warning' | 'info' = 'error') => ({ level, code, message: code, path: [] });
+    const report = summarizeBatch([
+      { input: 'a', ok: false, issues: [issue('x'), issue('x'), issue('y', 'warning')] },
+      { input: 'b', ok: true, issues: [issue('y', 'warning'), issue('coerce.number', 'info')] },
+    ]);
+    expect(report.codes).toEqual([{ code: 'y', inputs: 2, issues: 2 }, { code: 'x', inputs: 1, issues: 2 }]);
+    expect(report).toMatchObject({ ok: false, passed: 1, failed: 1 });
+  });
+});
@@ -1,11 +1,26 @@
 // test/codegen.test.ts
 
+import fs from 'node:fs';
+import os from 'node:os';
+import path from 'node:path';
 import { describe, it, expect } from 'vitest';
-import { generatePython } from '../dist/codegen/python/generator.js';
+import { generatePython, PythonGenerator } from '../dist/codegen/python/generator.js';
 import { generateTypeScript } from '../dist/codegen/typescript/generator.js';
 import { generateSwift } from '../dist/codegen/swift/generator.js';
-import { generateRust } from '../dist/codegen/rust/generator.js';
+import { generateRust, RustGenerator, PRELUDE_CRATE_VERSION } from '../dist/codegen/rust/generator.js';
+import { generateGo } from '../dist/codegen/go/generator.js';
+import { generateKotlin } from '../dist/codegen/kotlin/generator.js';
+import { estimateCost } from '../dist/codegen/base.js';
+import {
+  FSContext, validateDecimal, validateFsDirectory, validateLiteral, validateMeta, validateStr, validateWhen, type Issue,
+} from '../dist/codegen/typescript/prelude.js';
 import type { TypeDescription } from '../dist/base.js';
+import { Def } from '../dist/modifiers/def.js';
+import { Generic } from '../dist/modifiers/generic.js';
+import { ListOf } from '../dist/modifiers/listof.js';
+import { Phase } from '../dist/modifiers/phase.js';
+import { Str, Num } from '../dist/types/primitives.js';
+import { Field } from '../dist/types/structural.js';
 
 describe('Code generators', () => {
   const descWithDescription: TypeDescription = {
@@ -91,6 +106,95 @@ describe('Code generators', () => {
       expect(code).toContain('// Generated Schema');
     });
   });
+
+  describe('Go generator', () => {
+    it('outputs description as comment when present', () => {
+      const code = generateGo(descWithDescription);
+      expect(code).toContain('// User profile validation schema');
+      expect(code).toContain('// Generated Schema');
+    });
+
+    it('emits a command line entry point only in package main', () => {
+      const main = generateGo(descWithoutDescription);
+      expect(main).toMatch(/^package main$/m);
+      expect(main).toContain('func main() {');
+      expect(main).toContain('func ValidateRoot(value any) ValidationResult {');
+
+      const library = generateGo(descWithoutDescription, { package: 'specs' });
+      expect(library).toMatch(/^package specs$/m);
+      expect(library).not.toContain('func main()');
+      expect(library).toContain('func ValidateRoot(value any) ValidationResult {');
+      expect(() => generateGo(descWithoutDescription, { package: 'my-specs' })).toThrow('Invalid Go package name: my-specs');
+    });
+
+    it('passes constraints as option structs', () => {
+      const desc: TypeDescription = {
+        name: 'Object',
+        children: {
+          required: [
+            { name: 'Field', key: 'name', summary: 'String', constraints: ['minimum 2 characters', 'matches `/^[a-z]+$/i`'] },
+            { name: 'Field', key: 'age', summary: 'Number', constraints: ['integer', 'minimum 0'] },
+            { name: 'Field', key: 'tags', itemType: { name: 'String' }, constraints: ['unique items'] },
+          ],
+        },
+      };
+      const code = generateGo(desc);
+      expect(code).toContain('validateStr(v, p, i, strOpts{minLength: some(2), pattern: some("(?i)^[a-z]+$")})');
+      expect(code).toContain('validateNum(v, p, i, numOpts{min: some[float64](0), integer: true})');
+      expect(code).toContain('listOpts{item: func(v any, p []string, i *Issues) { validateStr(v, p, i, strOpts{}) }, unique: true}');
+    });
+
+    it('tracks recursion depth per validation run', () => {
+      const Tree = Def('Tree', self => ({ required: [Field({ key: 'children', value: ListOf(self) })] }), { maxDepth: 4 });
+      const code = generateGo(Tree.describe());
+      expect(code).toContain('if i.depth["validateDefTree"] >= 4 {');
+      expect(code).toContain('defer i.leave("validateDefTree")');
+      expect(code).toContain('listOpts{item: validateDefTree}');
+    });
+  });
+
+  describe('Kotlin generator', () => {
+    it('outputs description as comment when present', () => {
+      const code = generateKotlin(descWithDescription);
+      expect(code).toContain('// User profile validation schema');
+      expect(code).toContain('// Generated Schema');
+    });
+
+    it('emits a main function only without a package', () => {
+      const script = generateKotlin(descWithoutDescription);
+      expect(script).not.toMatch(/^package /m);
+      expect(script).toContain('fun main(args: Array<String>) {');
+
+      const library = generateKotlin(descWithoutDescription, { package: 'com.acme.specs' });
+      expect(library).toMatch(/^@file:Suppress[^\n]*\n[\s\S]*^package com\.acme\.specs\n\nimport /m);
+      expect(library).not.toContain('fun main(');
+      expect(library).toContain('fun validateRoot(value: Any?): ValidationResult');
+    });
+
+    it('escapes template markers and passes Double bounds', () => {
+      const desc: TypeDescription = {
+        name: 'Object',
+        children: {
+          required: [
+            { name: 'Field', key: 'price', summary: 'String', constraints: ['matches `^\\$\\d+$`'] },
+            { name: 'Field', key: 'qty', summary: 'Number', constraints: ['minimum 1', 'maximum 2.5'] },
+          ],
+        },
+      };
+      const code = generateKotlin(desc);
+      expect(code).toContain('validateStr(v, p, i, pattern = "^\\\\\\$\\\\d+\\$")');
+      expect(code).toContain('validateNum(v, p, i, min = 1.0, max = 2.5)');
+    });
+
+    it('refers to named types by function reference', () => {
+      const Tree = Def('Tree', self => ({ required: [Field({ key: 'children', value: ListOf(self) })] }), { maxDepth: 4 });
+      const code = generateKotlin(Tree.describe(), { failFast: true });
+      expect(code).toContain('private fun validateDefTree(v: Any?, p: List<String>, i: Issues) {');
+      expect(code).toContain('if (depth >= 4) {');
+      expect(code).toContain('itemValidator = ::validateDefTree');
+      expect(code).toContain('private val rootValidator: Validator = ::validateDefTree');
+    });
+  });
 });
 
 describe('Code generator validation logic', () => {
@@ -185,6 +289,107 @@ describe('Code generator validation logic', () => {
     });
   });
 
+  describe('Decimal validation', () => {
+    it('generates decimal validator with string bounds', () => {
+      const desc: TypeDescription = {
+        name: 'Decimal',
+        constraints: ['precision 10 digits', 'scale 2', 'maximum 99999999.99'],
+      };
+
+      const pyCode = generatePython(desc);
+      expect(pyCode).toContain('max_val="99999999.99"');
+      expect(pyCode).toContain('precision=10');
+
+      const tsCode = generateTypeScript(desc);
+      expect(tsCode).toContain('max: "99999999.99"');
+      expect(tsCode).toContain('scale: 2');
+
+      const rsCode = generateRust(desc);
+      expect(rsCode).toContain('validate_decimal(v, p, i, None, Some("99999999.99"), Some(10), Some(2))');
+    });
+
+    it('compares bounds with huge exponents without expanding them', () => {
+      const check = (value: number, opts: Parameters<typeof validateDecimal>[3]) => {
+        const issues: Issue[] = [];
+        validateDecimal(value, [], issues, opts);
+        return issues.map(issue => issue.code);
+      };
+      expect(check(5, { max: '1e999999999999', min: '1e-999999999999' })).toEqual([]);
+      expect(check(5, { min: '1e999999999999' })).toEqual(['num.too_small']);
+      expect(check(-5, { max: '-1e999999999999' })).toEqual(['num.too_large']);
+      expect(check(1e300, { precision: 301, scale: 0 })).toEqual([]);
+      expect(check(1e-300, { precision: 300, scale: 300 })).toEqual([]);
+      expect(check(1e-300, { scale: 299 })).toEqual(['decimal.scale_exceeded']);
+    });
+  });
+
+  describe('Checksum validation', () => {
+    it('generates checksum check after the file check', () => {
+      const desc: TypeDescription = {
+        name: 'Bundle',
+        fsType: 'bundle',
+        accept: [{ name: 'Directory', fsType: 'directory' }],
+        children: {
+          required: [
+            { name: 'File', fsType: 'file', filePath: 'data.bin', checksum: { algorithm: 'blake3', value: 'abc' } },
+          ],
+        },
+      };
+
+      expect(generateRust(desc)).toContain('{ validate_file_checksum(ctx, "data.bin", p, i, "blake3", "abc"); }');
+      expect(generatePython(desc)).toContain('and validate_file_checksum(ctx, "data.bin", p, i, "blake3", "abc")');
+      expect(generateTypeScript(desc)).toContain('&& validateFileChecksum(ctx, "data.bin", p, i, "blake3", "abc")');
+    });
+
+    it('imports Swift crypto only for specs with checksums', () => {
+      const file: TypeDescription = { name: 'File', fsType: 'file', filePath: 'data.bin' };
+      const bundle = (child: TypeDescription): TypeDescription => ({
+        name: 'Bundle',
+        fsType: 'bundle',
+        accept: [{ name: 'Directory', fsType: 'directory' }],
+        children: { required: [child] },
+      });
+
+      const withChecksum = generateSwift(bundle({ ...file, checksum: { algorithm: 'sha256', value: 'abc' } }));
+      expect(withChecksum).toContain('import CryptoKit');
+      expect(withChecksum).toContain('public func validateFileChecksum(');
+      const without = generateSwift(bundle(file));
+      expect(without).not.toContain('Crypto');
+      expect(without).not.toContain('validateFileChecksum');
+      expect(without).toContain('public func validateFsFile(');
+    });
+
+    it('shares the bundle content check with validate_shared', () => {
+      const desc: TypeDescription = {
+        name: 'Bundle',
+        fsType: 'bundle',
+        accept: [{ name: 'Directory', fsType: 'directory' }],
+        children: { required: [{ name: 'File', fsType: 'file', filePath: 'data.bin' }] },
+      };
+
+      const rust = generateRust(desc);
+      expect(rust).toContain('static CONTENT_VALIDATOR: fn(&FSContext, &[String], &mut Issues) = |ctx, p, i| { validate_fs_file(ctx, "data.bin", p, i, None); };');
+      expect(rust).toContain('validate_bundle(path, p, i, &BundleOptions { accept_dir: true, accept_zip: false, zip_ext: None, name_pattern: None }, Some(&CONTENT_VALIDATOR))');
+      expect(rust).toContain('pub fn validate_shared(bundle: &SharedBundle, sub_path: &str) -> ValidationResult {');
+    });
+  });
+
+  describe('Nullable validation', () => {
+    it('wraps field value validator when nullable', () => {
+      const desc: TypeDescription = {
+        name: 'Field',
+        key: 'end_date',
+        summary: 'String',
+        nullable: true,
+      };
+
+      expect(generatePython(desc)).toContain('validator=lambda v, p, i: validate_nullable(v, p, i, validate_str)');
+      expect(generateTypeScript(desc)).toContain('validator: (v, p, i) => validateNullable(v, p, i, validateStr)');
+      expect(generateRust(desc)).toContain('validate_nullable(v, p, i, &(');
+      expect(generateSwift(desc)).toContain('validateNullable(v, p, &i,');
+    });
+  });
+
   describe('OneOf validation', () => {
     it('generates oneof validator', () => {
       const desc: TypeDescription = {
@@ -205,6 +410,501 @@ describe('Code generator validation logic', () => {
     });
   });
 
+  describe('AllOf validation', () => {
+    it('generates allof validator over every part', () => {
+      const desc: TypeDescription = {
+        name: 'Field',
+        key: 'record',
+        allOf: [
+          { name: 'Object', children: { required: [{ name: 'Field', key: 'id', summary: 'String' }] } },
+          { name: 'Object', children: { required: [{ name: 'Field', key: 'createdAt', summary: 'Number' }] } },
+        ],
+      };
+
+      expect(generatePython(desc)).toContain('validate_allof(v, p, i, [');
+      expect(generateTypeScript(desc)).toContain('validateAllOf(v, p, i, [');
+      expect(generateSwift(desc)).toContain('validateAllOf(v, p, &i, [');
+      expect(generateRust(desc)).toContain('validate_allof(v, p, i, &[');
+      expect(generateRust(desc)).toContain('"createdAt"');
+    });
+  });
+
+  describe('Not validation', () => {
+    it('generates negated validator', () => {
+      const desc: TypeDescription = {
+        name: 'Field',
+        key: 'id',
+        not: { name: 'Pattern', constraints: ['matches `/^(admin|root)$/`'] },
+      };
+
+      expect(generatePython(desc)).toContain('validate_not(v, p, i, ');
+      expect(generateTypeScript(desc)).toContain('validateNot(v, p, i, ');
+      expect(generateSwift(desc)).toContain('validateNot(v, p, &i, ');
+      expect(generateRust(desc)).toContain('validate_not(v, p, i, &(');
+    });
+
+    it('passes bare regex patterns to non-JS preludes', () => {
+      const desc: TypeDescription = { name: 'Pattern', constraints: ['matches `/^(admin|root)$/i`'] };
+
+      expect(generatePython(desc)).toContain('validate_pattern(v, p, i, "(?i)^(admin|root)$")');
+      expect(generateRust(desc)).toContain('validate_pattern(v, p, i, "(?i)^(admin|root)$")');
+      expect(generateTypeScript(desc)).toContain('/^(admin|root)$/i');
+    });
+  });
+
+  describe('TaggedUnion validation', () => {
+    it('generates dispatch table keyed by tag', () => {
+      const desc: TypeDescription = {
+        name: 'TaggedUnion',
+        discriminator: 'kind',
+        variants: {
+          circle: { name: 'Object', children: { required: [{ name: 'Field', key: 'radius', summary: 'Number' }] } },
+          rect: { name: 'Object', children: { required: [{ name: 'Field', key: 'width', summary: 'Number' }] } },
+        },
+      };
+
+      expect(generatePython(desc)).toContain('validate_tagged_union(v, p, i, "kind", {"circle": ');
+      expect(generateTypeScript(desc)).toContain('validateTaggedUnion(v, p, i, "kind", { "circle": ');
+      expect(generateSwift(desc)).toContain('validateTaggedUnion(v, p, &i, "kind", ["circle": ');
+      expect(generateRust(desc)).toContain('validate_tagged_union(v, p, i, "kind", &[("circle", &(');
+    });
+  });
+
+  describe('Named type definitions', () => {
+    const meta: TypeDescription = {
+      name: 'Object',
+      ref: 'PackageMeta',
+      children: { required: [{ name: 'Field', key: 'name', summary: 'String' }] },
+    };
+    const desc: TypeDescription = {
+      name: 'Object',
+      children: {
+        required: [
+          { name: 'Field', key: 'pkg', summary: 'Object', ref: 'PackageMeta', children: meta.children },
+          { name: 'Field', key: 'deps', summary: 'ListOf', itemType: meta },
+        ],
+      },
+    };
+
+    it('emits one function per named type', () => {
+      const rust = generateRust(desc);
+      expect(rust.match(/fn validate_def_package_meta\(/g)).toHaveLength(1);
+      expect(rust.match(/validate_field\(v, p, i, "name"/g)).toHaveLength(1);
+      expect(rust).toContain('validate_field(v, p, i, "pkg", Some(&(validate_def_package_meta)), false)');
+      expect(rust).toContain('validate_list(v, p, i, Some(&(validate_def_package_meta))');
+
+      expect(generatePython(desc)).toContain('def validate_def_package_meta(v, p, i):');
+      expect(generateTypeScript(desc)).toContain('function validateDefPackageMeta(v: unknown, p: string[], i: Issues): void {');
+      expect(generateSwift(desc)).toContain('func validateDefPackageMeta(_ v: Any, _ p: [String], _ i: inout Issues) {');
+    });
+
+    it('rejects one name used for different shapes', () => {
+      const conflicting: TypeDescription = {
+        name: 'ListOf',
+        itemType: { name: 'OneOf', ref: 'PackageMeta', oneOf: [{ name: 'Boolean' }, meta] },
+      };
+      expect(() => generateRust({ name: 'AllOf', allOf: [desc, conflicting] })).toThrow('PackageMeta');
+    });
+
+    it('emits recursive types as recursive functions', () => {
+      const tree: TypeDescription = {
+        name: 'Object',
+        ref: 'TreeNode',
+        maxDepth: 16,
+        children: {
+          required: [{ name: 'Field', key: 'children', summary: 'ListOf', itemType: { name: 'Ref', ref: 'TreeNode' } }],
+        },
+      };
+
+      const rust = generateRust(tree);
+      expect(rust.match(/fn validate_def_tree_node\(/g)).toHaveLength(1);
+      expect(rust).toContain('validate_list(v, p, i, Some(&(validate_def_tree_node))');
+      expect(rust).toContain('if depth >= 16 {');
+      expect(rust).toContain('"recursion.too_deep", "Nesting of TreeNode exceeds maximum depth 16"');
+
+      expect(generatePython(tree)).toContain('if _validate_def_tree_node_depth[0] >= 16:');
+      expect(generateTypeScript(tree)).toContain('if (validateDefTreeNodeDepth >= 16) {');
+      expect(generateSwift(tree)).toContain('if validateDefTreeNodeDepth >= 16 {');
+    });
+
+    it('emits one validator per generic instantiation', () => {
+      const Paginated = Generic('Paginated', T => ({
+        required: [Field({ key: 'items', value: ListOf(T) }), Field({ key: 'total', value: Num() })],
+      }));
+      const User = Def('User', { required: [Field({ key: 'name', value: Str() })] });
+      const Order = Def('Order', { required: [Field({ key: 'id', value: Num() })] });
+      const root = Def('Root', {
+        required: [Field({ key: 'users', value: Paginated(User) }), Field({ key: 'orders', value: Paginated(Order) })],
+      });
+
+      const rust = generateRust(root.describe());
+      expect(rust).toContain('// Paginated<User>');
+      expect(rust).toContain('validate_list(v, p, i, Some(&(validate_def_user))');
+      expect(rust).toContain('validate_list(v, p, i, Some(&(validate_def_order))');
+      expect(rust.match(/fn validate_def_paginated_(user|order)\(/g)).toHaveLength(2);
+    });
+
+    it('documents named types with their description', () => {
+      const documented: TypeDescription = { ...meta, description: 'Package metadata\nfrom the manifest' };
+      const root: TypeDescription = {
+        name: 'Object',
+        children: { required: [{ name: 'Field', key: 'pkg', summary: 'Object', ref: 'PackageMeta', valueDescription: documented.description, children: meta.children }] },
+      };
+      expect(generateRust(documented)).toContain('/// Package metadata\n/// from the manifest\nfn validate_def_package_meta(');
+      expect(generateRust(root)).toContain('/// Package metadata\n/// from the manifest\nfn validate_def_package_meta(');
+      expect(generateSwift(documented)).toContain('/// Package metadata\n/// from the manifest\nfunc validateDefPackageMeta(');
+      expect(generateTypeScript(documented)).toContain('/**\n * Package metadata\n * from the manifest\n */\nfunction validateDefPackageMeta(');
+      expect(generatePython(documented)).toContain('# Package metadata\n# from the manifest\ndef validate_def_package_meta(');
+    });
+
+    it('rejects references to undefined named types', () => {
+      expect(() => generateRust({ name: 'ListOf', itemType: { name: 'Ref', ref: 'Missing' } })).toThrow('Missing');
+    });
+  });
+
+  describe('Custom issue codes and messages', () => {
+    const desc: TypeDescription = {
+      name: 'Object',
+      children: {
+        required: [
+          {
+            name: 'Field',
+            key: 'region',
+            summary: 'String',
+            constraints: ['matches `^eu-`'],
+            customIssue: { code: 'policy.bad_region', message: 'Region must be in the EU' },
+          },
+          { name: 'Field', key: 'size', summary: 'Number', customIssue: { code: 'policy.bad_size' } },
+        ],
+      },
+    };
+
+    it('passes the code and message to the generated issue', () => {
+      expect(generatePython(desc)).toContain('validate_message(v, p, i, lambda v, p, i: validate_str(v, p, i, pattern="^eu-"), code="policy.bad_region", message="Region must be in the EU")');
+      expect(generatePython(desc)).toContain('code="policy.bad_size")');
+      expect(generateRust(desc)).toContain('Some("policy.bad_region"), Some("Region must be in the EU"))');
+      expect(generateRust(desc)).toContain('Some("policy.bad_size"), None)');
+      expect(generateTypeScript(desc)).toContain('"policy.bad_size", undefined)');
+      expect(generateSwift(desc)).toContain('"policy.bad_size", nil) }');
+    });
+  });
+
+  describe('Deprecation warnings', () => {
+    const desc: TypeDescription = {
+      name: 'Object',
+      children: {
+        required: [
+          { name: 'Field', key: 'legacy_id', summary: 'String', optional: true, deprecated: 'Field "legacy_id" is deprecated' },
+          { name: 'Field', key: 'license', summary: 'String', valueDeprecated: '"GPL-2.0" is deprecated' },
+        ],
+      },
+    };
+
+    it('wraps deprecated fields and types in a warning', () => {
+      expect(generatePython(desc)).toContain('validate_deprecated(v, p, i, validate_str, "field.deprecated", "Field \\"legacy_id\\" is deprecated")');
+      expect(generatePython(desc)).toContain('"type.deprecated", "\\"GPL-2.0\\" is deprecated")');
+      expect(generateRust(desc)).toContain('"field.deprecated", "Field \\"legacy_id\\" is deprecated")');
+      expect(generateTypeScript(desc)).toContain('validateDeprecated(v, p, i, validateStr, "type.deprecated"');
+      expect(generateSwift(desc)).toContain('"field.deprecated", "Field \\"legacy_id\\" is deprecated") }');
+    });
+
+    it('does not count warnings as failures', () => {
+      expect(generatePython(desc)).toContain('"ok": error_count(issues) == 0');
+      expect(generateRust(desc)).toContain('ok: error_count(&issues) == 0');
+      expect(generateTypeScript(desc)).toContain('ok: errorCount(issues) === 0');
+    });
+  });
+
+  describe('Issue metadata', () => {
+    const desc: TypeDescription = {
+      name: 'Object',
+      children: {
+        required: [
+          { name: 'Field', key: 'license', summary: 'String', meta: { team: 'legal' } },
+          { name: 'Field', key: 'tags', summary: 'ListOf', itemType: { name: 'String', meta: { team: 'ops' } } },
+        ],
+      },
+    };
+
+    it('attaches Meta() to the issues of the value', () => {
+      expect(generatePython(desc)).toContain('validate_meta(v, p, i, validate_str, "{\\"team\\":\\"legal\\"}")');
+      expect(generateTypeScript(desc)).toContain('validateMeta(v, p, i, validateStr, {"team":"legal"})');
+      expect(generateTypeScript(desc)).toContain('itemValidator: (v, p, i) => validateMeta(v, p, i, validateStr, {"team":"ops"})');
+      expect(generateRust(desc)).toContain('"{\\"team\\":\\"legal\\"}")');
+      expect(generateSwift(desc)).toContain('validateMeta(v, p, &i, ');
+      expect(generateGo(desc)).toContain('validateMeta(v, p, i, ');
+      expect(generateKotlin(desc)).toContain('"{\\"team\\":\\"ops\\"}") }');
+    });
+
+    it('keeps the metadata of nested rules', () => {
+      const issues: Issue[] = [];
+      const inner = (v: unknown, p: string[], i: Issue[]): void => validateMeta(v, p, i, validateStr, { team: 'ops' });
+      validateMeta(1, ['owner'], issues, inner, { team: 'legal', policy: 'LIC-1' });
+      expect(issues).toEqual([
+        expect.objectContaining({ code: 'type.mismatch', path: 'owner', meta: { team: 'ops', policy: 'LIC-1' } }),
+      ]);
+      validateMeta('ok', [], issues, validateStr, { team: 'legal' });
+      expect(issues).toHaveLength(1);
+    });
+
+    it('rejects Meta() on files instead of dropping it', () => {
+      const bundle: TypeDescription = {
+        name: 'Bundle',
+        fsType: 'bundle',
+        children: { required: [{ name: 'File', fsType: 'file', filePath: 'LICENSE', meta: { team: 'legal' } }] },
+      };
+      expect(() => generateRust(bundle)).toThrow('Meta()');
+    });
+  });
+
+  describe('Phases', () => {
+    it('rejects Phase() instead of running phased checks unconditionally', () => {
+      const bundle: TypeDescription = {
+        name: 'Bundle',
+        fsType: 'bundle',
+        children: { required: [{ name: 'File', fsType: 'file', filePath: 'payload.bin', phase: 2 }] },
+      };
+      expect(() => generateRust(bundle)).toThrow('Phase()');
+      expect(() => generatePython(bundle)).toThrow('Phase()');
+
+      const data = Field({ key: 'name', value: Phase(1, Str()) }).describe();
+      expect(data.phase).toBe(1);
+      expect(() => generateTypeScript({ name: 'Object', children: { required: [data] } })).toThrow('Phase()');
+    });
+  });
+
+  describe('Typed Rust output', () => {
+    const tree: TypeDescription = {
+      name: 'Object',
+      ref: 'TreeNode',
+      children: {
+        required: [{ name: 'Field', key: 'name', summary: 'String' }],
+        optional: [{ name: 'Field', key: 'parent', summary: 'Ref', ref: 'TreeNode' }],
+      },
+    };
+    const desc: TypeDescription = {
+      name: 'Object',
+      children: {
+        required: [
+          { name: 'Field', key: 'zip-code', summary: 'String' },
+          { name: 'Field', key: 'kind', summary: 'OneOf', oneOf: [
+            { name: 'Literal', constraints: ['equals "lib"'] },
+            { name: 'Literal', constraints: ['equals "cli-tool"'] },
+          ] },
+          { name: 'Field', key: 'tree', summary: 'Object', ref: 'TreeNode', children: tree.children },
+        ],
+        optional: [{ name: 'Field', key: 'port', summary: 'Number', constraints: ['integer'], default: 8080 }],
+      },
+    };
+
+    it('emits serde structs and a parse entry point', () => {
+      const rust = generateRust(desc, { types: true });
+      expect(rust).toContain('pub struct Root {');
+      expect(rust).toContain('#[serde(rename = "zip-code")]\n    pub zip_code: String,');
+      expect(rust).toContain('#[serde(rename = "cli-tool")]\n    CliTool,');
+      expect(rust).toContain('pub parent: Option<Box<TreeNode>>,');
+      expect(rust).toContain('#[serde(default = "default_root_port")]\n    pub port: i64,');
+      expect(rust).toContain('pub fn parse_root(value: &Value) -> Result<Root, Issues> {');
+      expect(rust).toContain('pub fn parse<T: serde::de::DeserializeOwned>(value: &Value) -> Result<T, Issues> {');
+      expect(rust).toContain('from_reader_validated(reader, &ROOT_VALIDATOR)');
+    });
+
+    it('documents structs and fields with their descriptions', () => {
+      const documented: TypeDescription = {
+        name: 'Object',
+        ref: 'Address',
+        description: 'A postal address',
+        children: { required: [{ name: 'Field', key: 'street', summary: 'String', description: 'Street and number' }] },
+      };
+      const rust = generateRust(documented, { types: true });
+      expect(rust).toContain('/// A postal address\n#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]\npub struct Address {');
+      expect(rust).toContain('    /// Street and number\n    pub street: String,');
+    });
+
+    it('emits no types unless asked', () => {
+      expect(generateRust(desc)).not.toContain('pub struct Root');
+    });
+  });
+
+  describe('External prelude', () => {
+    const desc: TypeDescription = { name: 'Object', children: { required: [{ name: 'Field', key: 'name', summary: 'String' }] } };
+
+    it('imports the pinned prelude crate instead of embedding it', () => {
+      const rust = generateRust(desc, { externalPrelude: true });
+      expect(rust).toContain(`specspec-prelude = "=${PRELUDE_CRATE_VERSION}"`);
+      expect(rust).toContain('use specspec_prelude::*;');
+      expect(rust).not.toContain('pub fn validate_str(');
+      expect(rust).toContain('validate_field(v, p, i, "name"');
+      expect(generateRust(desc)).toContain('pub fn validate_str(');
+    });
+
+    it('is rejected for languages without a published prelude', () => {
+      expect(() => generatePython(desc, { externalPrelude: true })).toThrow('python');
+    });
+  });
+
+  describe('Slim prelude', () => {
+    const desc: TypeDescription = { name: 'Object', children: { required: [{ name: 'Field', key: 'name', summary: 'String' }] } };
+
+    it('leaves out filesystem and zip support', () => {
+      const rust = generateRust(desc, { slim: true });
+      expect(rust).toContain('pub fn validate_str(');
+      expect(rust).not.toContain('use zip::ZipArchive;');
+      expect(rust).not.toContain('pub struct FSContext');
+      expect(rust).not.toContain('pub fn reserved_name(');
+      expect(rust).not.toContain('// --- fs ---');
+      expect(generateRust(desc)).toContain('pub struct FSContext');
+    });
+
+    it('depends on the prelude crate without default features', () => {
+      expect(generateRust(desc, { slim: true, externalPrelude: true })).toContain('default-features = false');
+    });
+
+    it('rejects filesystem specs', () => {
+      expect(() => generateRust({ name: 'Directory', fsType: 'directory' }, { slim: true })).toThrow('filesystem');
+    });
+  });
+
+  describe('When rules', () => {
+    const when: TypeDescription = {
+      name: 'When',
+      condition: { file: 'app.json', field: 'targets.0.platform', is: { name: 'Literal', constraints: ['equals "web"'] } },
+      then: { name: 'Directory', fsType: 'directory', filePath: 'public' },
+      else: { name: 'Object', children: { required: [{ name: 'File', fsType: 'file', filePath: 'main.swift' }] } },
+    };
+    const desc: TypeDescription = {
+      name: 'Bundle',
+      fsType: 'bundle',
+      accept: [{ name: 'Directory', fsType: 'directory' }],
+      children: { required: [when] },
+    };
+
+    it('checks the branch the condition picks', () => {
+      expect(generateTypeScript(desc)).toContain(
+        'validateWhen(ctx, p, i, { file: "app.json", field: "targets.0.platform", is: (v, p, i) => validateLiteral(v, p, i, "web") }, '
+        + '(ctx: FSContext, p: string[], i: Issues) => { validateFsDirectory(ctx, "public", p, i); }, '
+        + '(ctx: FSContext, p: string[], i: Issues) => { validateFsFile(ctx, "main.swift", p, i); })'
+      );
+      expect(generateRust(desc)).toContain(
+        'validate_when(ctx, p, i, &Condition { file: "app.json", field: Some("targets.0.platform"), is: Some(&(|v, p, i| validate_literal_str(v, p, i, "web"))) }, '
+        + 'Some(&(|ctx, p, i| { validate_fs_directory(ctx, "public", p, i); })), '
+        + 'Some(&(|ctx, p, i| { validate_fs_file(ctx, "main.swift", p, i, None); })))'
+      );
+      expect(generatePython(desc)).toContain('validate_when(ctx, p, i, "app.json", field="targets.0.platform", is_=lambda v, p, i: validate_literal(v, p, i, "web"), then=');
+      expect(generateSwift(desc)).toContain('validateWhen(ctx, p, &i, file: "app.json", field: "targets.0.platform", is: ');
+      expect(generateGo(desc)).toContain('validateWhen(ctx, p, i, whenOpts{file: "app.json", field: some("targets.0.platform"), is: ');
+      expect(generateKotlin(desc)).toContain('validateWhen(ctx, p, i, "app.json", field = "targets.0.platform", matching = ');
+    });
+
+    it('leaves out the parts a rule does not have', () => {
+      const exists: TypeDescription = { ...desc, children: { required: [{ name: 'When', condition: { file: 'app.json' }, then: when.then }] } };
+      expect(generateTypeScript(exists)).toContain('validateWhen(ctx, p, i, { file: "app.json" }, (ctx: FSContext, p: string[], i: Issues) => { validateFsDirectory(ctx, "public", p, i); }, undefined)');
+      expect(generateRust(exists)).toContain('&Condition { file: "app.json", field: None, is: None }, Some(&(|ctx, p, i| { validate_fs_directory(ctx, "public", p, i); })), None)');
+      expect(generatePython(exists)).toContain('validate_when(ctx, p, i, "app.json", then=lambda ctx, p, i: [validate_fs_directory(ctx, "public", p, i)])');
+    });
+
+    it('runs the generated check', () => {
+      const dir = fs.mkdtempSync(path.join(os.tmpdir(), 'specspec-when-'));
+      const ctx = new FSContext(dir, false);
+      const run = () => {
+        const issues: Issue[] = [];
+        validateWhen(ctx, [], issues, { file: 'app.json', field: 'targets.0.platform', is: (v, p, i) => validateLiteral(v, p, i, 'web') },
+          (ctx, p, i) => { validateFsDirectory(ctx, 'public', p, i); });
+        return issues.map(issue => issue.code);
+      };
+
+      try {
+        // No file and a broken file decide nothing
+        expect(run()).toEqual([]);
+        fs.writeFileSync(path.join(dir, 'app.json'), '[');
+        expect(run()).toEqual([]);
+
+        fs.writeFileSync(path.join(dir, 'app.json'), '{ "targets": [{ "platform": "web" }] }');
+        expect(run()).toEqual(['dir.not_found']);
+        fs.writeFileSync(path.join(dir, 'app.json'), '{ "targets": [{ "platform": "ios" }] }');
+        expect(run()).toEqual([]);
+      } finally {
+        fs.rmSync(dir, { recursive: true, force: true });
+      }
+    });
+  });
+
+  describe('Command line tool', () => {
+    const desc: TypeDescription = { name: 'Object', children: { required: [{ name: 'Field', key: 'name', summary: 'String' }] } };
+
+    it('emits a clap main with a validate command and reporter selection', () => {
+      const rust = generateRust(desc, { cli: 'mytool' });
+      expect(rust).toContain('#[command(name = "mytool", version');
+      expect(rust).toContain('Validate {\n        path: String,');
+      expect(rust).toContain('#[arg(long, value_enum, default_value_t = Format::Text)]');
+      expect(rust).toContain('std::process::exit(2);');
+      expect(rust).toContain('std::process::exit(if result.ok { 0 } else { 1 });');
+      expect(rust.match(/fn main\(\)/g)).toHaveLength(1);
+    });
+
+    it('validates bundle paths directly', () => {
+      const bundle: TypeDescription = { name: 'Bundle', fsType: 'bundle', accept: [{ name: 'Directory', fsType: 'directory' }] };
+      const rust = generateRust(bundle, { cli: 'pkgcheck' });
+      expect(rust).toContain('let result = validate_root(&path);');
+      expect(rust).not.toContain('Invalid JSON in {}');
+    });
+  });
+
+  describe('Property tests', () => {
+    const desc: TypeDescription = {
+      name: 'Object',
+      children: {
+        required: [
+          { name: 'Field', key: 'name', summary: 'String', constraints: ['minimum 3 characters', 'maximum 20 characters'] },
+          { name: 'Field', key: 'port', summary: 'Number', constraints: ['integer', 'minimum 1', 'maximum 65535'] },
+        ],
+      },
+    };
+
+    it('emits boundary tests for each constrained field', () => {
+      const rust = generateRust(desc, { propertyTests: true });
+      expect(rust).toContain('#[cfg(test)]\nmod property_tests {');
+      expect(rust).toContain('fn accepts_valid_values(');
+      expect(rust).toContain('fn rejects_name_too_short(');
+      expect(rust).toContain('fn accepts_port_at_max(');
+      expect(rust).toContain('fn rejects_missing_port(');
+    });
+
+    it('is off by default', () => {
+      expect(generateRust(desc)).not.toContain('mod property_tests');
+    });
+  });
+
+  describe('Fuzz target', () => {
+    const desc: TypeDescription = { name: 'Object', children: { required: [{ name: 'Field', key: 'name', summary: 'String' }] } };
+
+    it('emits a cargo-fuzz crate that includes the generated validator', () => {
+      const files = new RustGenerator().generateFuzzTarget(desc, '../src/validator.rs');
+      expect([...files.keys()]).toEqual(['Cargo.toml', '.gitignore', 'fuzz_targets/validate.rs']);
+      const target = files.get('fuzz_targets/validate.rs')!;
+      expect(target).toContain('#![no_main]');
+      expect(target).toContain('include!("../../src/validator.rs");');
+      expect(target).toContain('serde_json::from_slice::<serde_json::Value>(data)');
+      expect(files.get('Cargo.toml')).toContain('cargo-fuzz = true');
+      expect(files.get('Cargo.toml')).toContain('libfuzzer-sys = "0.4"');
+    });
+
+    it('feeds bundles an archive and follows the prelude options', () => {
+      const bundle: TypeDescription = { name: 'Bundle', fsType: 'bundle', accept: [{ name: 'ZipFile', fsType: 'zipFile', fileExt: 'pkg' }] };
+      const target = new RustGenerator().generateFuzzTarget(bundle, '../src/main.rs').get('fuzz_targets/validate.rs')!;
+      expect(target).toContain('specspec-fuzz-{}.pkg');
+      expect(target).toContain('validator::validate_root(&path.to_string_lossy())');
+
+      const manifest = new RustGenerator({ externalPrelude: true, slim: true }).generateFuzzTarget(desc, '../src/lib.rs').get('Cargo.toml')!;
+      expect(manifest).toContain(`specspec-prelude = { version = "=${PRELUDE_CRATE_VERSION}", default-features = false }`);
+      expect(manifest).not.toContain('zip =');
+    });
+
+    it('is not available for other languages', () => {
+      expect(() => new PythonGenerator().generateFuzzTarget(desc, 'validator.py')).toThrow('No fuzz target for python');
+    });
+  });
+
   describe('ListOf validation', () => {
     it('generates list validator with constraints', () => {
       const desc: TypeDescription = {
@@ -222,6 +922,35 @@ describe('Code generator validation logic', () => {
       expect(tsCode).toContain('validateList');
       expect(tsCode).toContain('minItems: 1');
       expect(tsCode).toContain('maxItems: 10');
+
+      expect(generateRust(desc)).toContain('&ListOptions { min_items: Some(1), max_items: Some(10), ..ListOptions::default() }');
+      expect(generateRust({ name: 'ListOf', itemType: { name: 'String' } })).toContain('&ListOptions::default())');
+    });
+
+    it('generates uniqueness arguments', () => {
+      const desc: TypeDescription = {
+        name: 'ListOf',
+        itemType: { name: 'Object' },
+        constraints: ['unique items', 'unique by `meta.id`'],
+      };
+
+      expect(generatePython(desc)).toContain('unique=True, unique_by="meta.id"');
+      expect(generateTypeScript(desc)).toContain('unique: true, uniqueBy: "meta.id"');
+      expect(generateSwift(desc)).toContain('unique: true, uniqueBy: "meta.id"');
+      expect(generateRust(desc)).toContain('&ListOptions { unique: true, unique_by: Some("meta.id"), ..ListOptions::default() }');
+    });
+
+    it('generates ordering arguments', () => {
+      const desc: TypeDescription = {
+        name: 'ListOf',
+        itemType: { name: 'Object' },
+        constraints: ['sorted descending by `timestamp`'],
+      };
+
+      expect(generatePython(desc)).toContain('sort_order="desc", sort_by="timestamp"');
+      expect(generateTypeScript(desc)).toContain('sorted: "desc", sortedBy: "timestamp"');
+      expect(generateSwift(desc)).toContain('sorted: "desc", sortedBy: "timestamp"');
+      expect(generateRust(desc)).toContain('sorted: Some("desc"), sorted_by: Some("timestamp")');
     });
   });
 
@@ -278,3 +1007,46 @@ describe('Code generator validation logic', () => {
     });
   });
 });
+
+describe('Check scheduling', () => {
+  const desc: TypeDescription = {
+    name: 'Object',
+    children: {
+      required: [
+        { name: 'Field', key: 'tags', itemType: { name: 'String', constraints: ['matches `^(a+)+$`'] } },
+        { name: 'Field', key: 'name', summary: 'String' },
+        { name: 'Field', key: 'enabled', summary: 'Boolean' },
+      ],
+    },
+  };
+
+  it('estimates regex and list checks as more expensive than scalars', () => {
+    expect(estimateCost({ name: 'Boolean' })).toBeLessThan(estimateCost({ name: 'String', constraints: ['matches `^[a-z]+$`'] }));
+    expect(estimateCost({ name: 'Pattern', constraints: ['matches `^(a+)+$`'] }))
+      .toBeGreaterThan(estimateCost({ name: 'Pattern', constraints: ['matches `^a+$`'] }));
+    expect(estimateCost({ name: 'JsonFile', fsType: 'jsonFile', filePath: 'a.json' }))
+      .toBeGreaterThan(estimateCost({ name: 'File', fsType: 'file', filePath: 'a.txt' }));
+  });
+
+  it('orders checks cheapest-first by default', () => {
+    const tsCode = generateTypeScript(desc);
+    const enabled = tsCode.indexOf('"enabled"');
+    const name = tsCode.indexOf('"name"');
+    const tags = tsCode.indexOf('"tags"');
+    expect(enabled).toBeLessThan(name);
+    expect(name).toBeLessThan(tags);
+  });
+
+  it('keeps spec order when scheduling is disabled', () => {
+    const tsCode = generateTypeScript(desc, { schedule: false });
+    expect(tsCode.indexOf('"tags"')).toBeLessThan(tsCode.indexOf('"enabled"'));
+  });
+
+  it('emits early exits in fail-fast mode', () => {
+    expect(generateTypeScript(desc, { failFast: true })).toContain('if (errorCount(i) > n) return;');
+    expect(generatePython(desc, { failFast: true })).toContain('run_checks(i, [');
+    expect(generateRust(desc, { failFast: true })).toContain('if error_count(i) > n { return; }');
+    expect(generateSwift(desc, { failFast: true })).toContain('if errorCount(i) > n { return }');
+    expect(generateTypeScript(desc)).not.toContain('errorCount(i) > n');
+  });
+});
@@ -0,0 +1,147 @@
+// test/compat.test.ts
+
+import { describe, it, expect } from 'vitest';
+import { compatReport } from '../dist/compat.js';
+import { Str, Num } from '../dist/types/primitives.js';
+import { Field, File, Directory, JsonFile, When } from '../dist/types/structural.js';
+import { OneOf } from '../dist/modifiers/oneof.js';
+import { ListOf } from '../dist/modifiers/listof.js';
+import { Nullable } from '../dist/modifiers/nullable.js';
+import { TaggedUnion } from '../dist/modifiers/taggedunion.js';
+import { Def } from '../dist/modifiers/def.js';
+
+function pkg(...fields: ReturnType<typeof Field>[]) {
+  return JsonFile({ path: 'package.json', required: fields });
+}
+
+function codes(oldSpec: { describe(): unknown }, newSpec: { describe(): unknown }) {
+  const report = compatReport(oldSpec.describe() as never, newSpec.describe() as never);
+  return report.changes.map(c => `${c.kind} ${c.code} ${c.path.join('.')}`);
+}
+
+describe('compatReport', () => {
+  it('reports no changes for the same spec', () => {
+    const spec = pkg(Field({ key: 'name', value: Str({ minLength: 1 }) }));
+    const report = compatReport(spec.describe(), spec.describe());
+    expect(report).toEqual({ compatible: true, breaking: 0, changes: [] });
+  });
+
+  it('flags a field that became required as breaking', () => {
+    const before = pkg(Field({ key: 'license', value: Str(), optional: true }));
+    const after = pkg(Field({ key: 'license', value: Str() }));
+    expect(codes(before, after)).toEqual(['breaking item.became_required license']);
+    expect(codes(after, before)).toEqual(['compatible item.became_optional license']);
+  });
+
+  it('treats new required fields as breaking and new optional fields as compatible', () => {
+    const before = pkg(Field({ key: 'name', value: Str() }));
+    const after = pkg(
+      Field({ key: 'name', value: Str() }),
+      Field({ key: 'version', value: Str() }),
+      Field({ key: 'homepage', value: Str(), optional: true }),
+    );
+    expect(codes(before, after)).toEqual([
+      'breaking item.added_required version',
+      'compatible item.added_optional homepage',
+    ]);
+    expect(codes(after, before)).toEqual([
+      'compatible item.removed version',
+      'compatible item.removed homepage',
+    ]);
+  });
+
+  it('flags narrowed types and tightened bounds', () => {
+    const before = pkg(Field({ key: 'port', value: Num({ min: 1, max: 65535 }) }), Field({ key: 'name', value: Str() }));
+    const after = pkg(Field({ key: 'port', value: Num({ min: 1024, max: 65535, integer: true }) }), Field({ key: 'name', value: Num() }));
+    const report = compatReport(before.describe(), after.describe());
+    expect(report.compatible).toBe(false);
+    expect(report.changes.map(c => [c.code, c.message])).toEqual([
+      ['constraint.tightened', 'Constraint tightened to minimum 1024'],
+      ['constraint.added', 'Value must now be integer'],
+      ['type.changed', 'Type changed from String to Number'],
+    ]);
+    expect(codes(after, before)).toContain('compatible constraint.loosened port');
+  });
+
+  it('flags removed enum values as breaking and added ones as compatible', () => {
+    const before = pkg(Field({ key: 'license', value: OneOf('MIT', 'GPL-2.0') }));
+    const after = pkg(Field({ key: 'license', value: OneOf('MIT', 'Apache-2.0') }));
+    const report = compatReport(before.describe(), after.describe());
+    expect(report.changes.map(c => `${c.kind} ${c.code}: ${c.message}`)).toEqual([
+      'breaking enum.value_removed: Value "GPL-2.0" is no longer accepted',
+      'compatible enum.value_added: Value "Apache-2.0" is now accepted',
+    ]);
+  });
+
+  it('accepts a literal widened to its type', () => {
+    const before = pkg(Field({ key: 'kind', value: 'app' }));
+    const after = pkg(Field({ key: 'kind', value: OneOf('app', Str()) }));
+    expect(codes(before, after)).toEqual(['compatible option.added kind']);
+    expect(codes(before, pkg(Field({ key: 'kind', value: Str() })))).toEqual(['compatible type.widened kind']);
+  });
+
+  it('flags new and changed patterns', () => {
+    const before = pkg(Field({ key: 'version', value: Str() }));
+    const after = pkg(Field({ key: 'version', value: Str({ match: /^\d+\.\d+\.\d+$/ }) }));
+    expect(codes(before, after)).toEqual(['breaking pattern.tightened version']);
+    expect(codes(after, before)).toEqual(['compatible pattern.removed version']);
+  });
+
+  it('compares list items, nullability and variants', () => {
+    const Shape = (kinds: string[]) => TaggedUnion('kind', Object.fromEntries(kinds.map(k => [k, { required: [Field({ key: 'size', value: Num() })] }])));
+    const before = pkg(
+      Field({ key: 'tags', value: ListOf(Str()) }),
+      Field({ key: 'parent', value: Nullable(Str()) }),
+      Field({ key: 'shape', value: Shape(['circle', 'square']) }),
+    );
+    const after = pkg(
+      Field({ key: 'tags', value: ListOf(Str({ maxLength: 20 }), { unique: true }) }),
+      Field({ key: 'parent', value: Str() }),
+      Field({ key: 'shape', value: Shape(['circle', 'hexagon']) }),
+    );
+    expect(codes(before, after)).toEqual([
+      'breaking constraint.added tags',
+      'breaking constraint.tightened tags.[]',
+      'breaking null.removed parent',
+      'compatible variant.added shape.[kind=hexagon]',
+      'breaking variant.removed shape.[kind=square]',
+    ]);
+  });
+
+  it('compares recursive named types once', () => {
+    const Tree = (leaf: ReturnType<typeof Str>) => Def('Tree', self => ({
+      required: [Field({ key: 'name', value: leaf }), Field({ key: 'children', value: ListOf(self) })],
+    }));
+    expect(codes(Tree(Str()), Tree(Str({ minLength: 1 })))).toEqual(['breaking constraint.tightened name']);
+  });
+
+  it('flags entries rejected by a newly strict directory', () => {
+    const before = Directory({ content: { required: [File({ path: 'README.md' })], optional: [File({ path: 'NOTES.md' })] } });
+    const after = Directory({ strict: true, content: { required: [File({ path: 'README.md' })] } });
+    expect(codes(before, after)).toEqual([
+      'breaking directory.strict ',
+      'breaking item.removed NOTES.md',
+    ]);
+  });
+
+  it('notes deprecations as compatible', () => {
+    const before = pkg(Field({ key: 'legacy_id', value: Str(), optional: true }));
+    const after = pkg(Field({ key: 'legacy_id', value: Str(), optional: true, deprecated: true }));
+    expect(codes(before, after)).toEqual(['compatible item.deprecated legacy_id']);
+  });
+
+  it('compares the conditions and branches of When rules', () => {
+    const rule = (is: boolean, then: Parameters<typeof When>[0]['then']) => Directory({
+      content: { required: [When({ file: 'manifest.json', field: 'icons.enabled', is, then })] },
+    });
+    const before = rule(true, Directory({ path: 'icons' }));
+    const after = rule(true, { required: [Directory({ path: 'icons' }), File({ path: 'icons/index.json' })] });
+    expect(codes(before, after)).toEqual(['breaking item.added_required [then].icons/index.json']);
+    expect(codes(after, before)).toEqual(['compatible item.removed [then].icons/index.json']);
+
+    const report = compatReport(before.describe(), rule(false, Directory({ path: 'icons' })).describe());
+    expect(report.changes.map(c => [c.code, c.message])).toEqual([
+      ['when.condition_changed', 'Condition changed from "manifest.json" field "icons.enabled" is true to "manifest.json" field "icons.enabled" is false'],
+    ]);
+  });
+});
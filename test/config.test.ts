@@ -0,0 +1,102 @@
+// test/config.test.ts
+
+import { describe, it, expect, beforeEach, afterEach } from 'vitest';
+import fs from 'node:fs';
+import os from 'node:os';
+import path from 'node:path';
+import { parseToml, findConfig, loadConfig, applyIssuePolicy } from '../dist/config.js';
+import type { ValidationResult } from '../dist/engine.js';
+
+describe('project configuration', () => {
+  let tmpDir: string;
+
+  beforeEach(() => {
+    tmpDir = fs.mkdtempSync(path.join(os.tmpdir(), 'specspec-config-'));
+  });
+
+  afterEach(() => {
+    fs.rmSync(tmpDir, { recursive: true, force: true });
+  });
+
+  it('parses tables, arrays of tables and values', () => {
+    const toml = parseToml([
+      '# comment',
+      'name = "a \\"b\\"" # trailing',
+      "raw = 'C:\\dir'",
+      'count = 1_000',
+      'on = true',
+      'list = [',
+      '  "x", # first',
+      '  "y",',
+      ']',
+      '[report]',
+      'fail-on = "warning"',
+      '[[codegen]]',
+      'lang = "go"',
+      '[[codegen]]',
+      'lang = "rust"',
+    ].join('\n'));
+
+    expect(toml).toEqual({
+      name: 'a "b"',
+      raw: 'C:\\dir',
+      count: 1000,
+      on: true,
+      list: ['x', 'y'],
+      report: { 'fail-on': 'warning' },
+      codegen: [{ lang: 'go' }, { lang: 'rust' }],
+    });
+    expect(() => parseToml('a = 1\na = 2', 'p.toml')).toThrow('p.toml:2: "a" is already defined');
+    expect(() => parseToml('a = bare', 'p.toml')).toThrow('p.toml:1: Invalid value: bare');
+  });
+
+  it('loads a configuration found in a parent directory', () => {
+    fs.writeFileSync(path.join(tmpDir, '.specspec.toml'), [
+      'spec = "specs/a.spec.js"',
+      'target = "dist"',
+      '[report]',
+      'format = "json"',
+      'suppress = ["list.sampled"]',
+      '[[codegen]]',
+      'lang = "python"',
+      'output = "gen/v.py"',
+      'fail-fast = true',
+    ].join('\n'));
+    fs.mkdirSync(path.join(tmpDir, 'sub'));
+
+    const file = findConfig(path.join(tmpDir, 'sub'))!;
+    expect(file).toBe(path.join(tmpDir, '.specspec.toml'));
+    expect(loadConfig(file)).toEqual({
+      file,
+      spec: path.join(tmpDir, 'specs/a.spec.js'),
+      target: path.join(tmpDir, 'dist'),
+      format: 'json',
+      suppress: ['list.sampled'],
+      codegen: [{ lang: 'python', output: path.join(tmpDir, 'gen/v.py'), failFast: true }],
+    });
+
+    fs.writeFileSync(file, '[report]\nfail-on = "warn"\n');
+    expect(() => loadConfig(file)).toThrow('"report.fail-on" must be one of: "error", "warning"');
+    fs.writeFileSync(file, '[report]\nwarnings-as-errors = true\n');
+    expect(loadConfig(file)).toEqual({ file, failOn: 'warning' });
+    fs.writeFileSync(file, 'spek = "a.spec.js"\n');
+    expect(() => loadConfig(file)).toThrow('Unknown key "spek"');
+  });
+
+  it('suppresses issues by code and path, and fails on the configured level', () => {
+    const result: ValidationResult = {
+      ok: false,
+      issues: [
+        { level: 'error', code: 'str.too_short', message: '', path: ['legacy', 'name'] },
+        { level: 'warning', code: 'field.deprecated', message: '', path: ['legacy', 'package.json', 'id'] },
+        { level: 'warning', code: 'field.deprecated', message: '', path: ['current'] },
+      ],
+    };
+
+    const suppressed = applyIssuePolicy(result, { suppress: ['str.*', 'field.deprecated@legacy.package.json'] });
+    expect(suppressed.issues.map(i => i.path.join('.'))).toEqual(['current']);
+    expect(suppressed.ok).toBe(true);
+    expect(applyIssuePolicy(result, { failOn: 'warning', suppress: ['str.too_short'] }).ok).toBe(false);
+    expect(applyIssuePolicy(result, { suppress: ['field.*@leg'] }).issues).toHaveLength(3);
+  });
+});
@@ -0,0 +1,100 @@
+// test/coverage.test.ts
+
+import { describe, it, expect, beforeEach, afterEach } from 'vitest';
+import fs from 'node:fs';
+import os from 'node:os';
+import path from 'node:path';
+import { SpecEngine } from '../dist/engine.js';
+import { CoverageRecorder, type CoverageRule } from '../dist/coverage.js';
+import { runFixtures } from '../dist/fixtures.js';
+
+const SPEC = `Def('Order', {
+  required: [
+    Field({ key: 'id', value: Str({ minLength: 3, match: /^[A-Z]/ }) }),
+    Field({ key: 'priority', value: OneOf('low', 'high', Num({ min: 0 })) }),
+    Field({ key: 'items', value: ListOf(TaggedUnion('kind', {
+      book: { required: [Field({ key: 'isbn', value: Str() })] },
+      gift: { required: [Field({ key: 'wrap', value: Bool() })] },
+    }), { max: 2 }) }),
+  ],
+  optional: [Field({ key: 'note', value: Str(), optional: true, nullable: true })],
+})`;
+
+// Rules as "path | rule | status"
+const summary = (rules: CoverageRule[]) => rules.map(r => `${r.path} | ${r.rule} | ${r.status}`);
+
+describe('rule coverage', () => {
+  let tmpDir: string;
+
+  beforeEach(() => {
+    tmpDir = fs.mkdtempSync(path.join(os.tmpdir(), 'specspec-coverage-'));
+    fs.writeFileSync(path.join(tmpDir, 'order.spec.js'), SPEC);
+  });
+
+  afterEach(() => {
+    fs.rmSync(tmpDir, { recursive: true, force: true });
+  });
+
+  it('lists constraints and branches, and records which runs reach and break them', () => {
+    const schema = new SpecEngine().compile(path.join(tmpDir, 'order.spec.js'));
+    const coverage = new CoverageRecorder(schema.root);
+    expect(summary(coverage.report().rules)).toEqual([
+      'id | required | missed',
+      'id | minimum 3 characters | missed',
+      'id | matches `/^[A-Z]/` | missed',
+      'priority | required | missed',
+      'priority | matches an option | missed',
+      'priority | option 1: "low" | missed',
+      'priority | option 2: "high" | missed',
+      'priority | option 3: Number | missed',
+      'priority(option 3) | minimum 0 | missed',
+      'items | required | missed',
+      'items | maximum 2 items | missed',
+      'items[].kind | required | missed',
+      'items[].kind | known tag | missed',
+      'items[] | kind=book | missed',
+      'items[] | kind=gift | missed',
+      'items[](kind=book).isbn | required | missed',
+      'items[](kind=gift).wrap | required | missed',
+      'note | present | missed',
+      'note | null | missed',
+    ]);
+
+    coverage.record(() => schema.validateValue({ id: 'A-1', priority: 'low', items: [{ kind: 'book', isbn: 'x' }], note: null }));
+    coverage.record(() => schema.validateValue({ id: 'a', priority: 3, items: [{ kind: 'gift' }, {}, { kind: 'book', isbn: '1' }] }));
+    // Validation outside record() is not counted
+    schema.validateValue({ priority: 'urgent' });
+
+    const report = coverage.report();
+    const rule = (path: string, name: string) => report.rules.find(r => r.path === path && r.rule === name)!;
+    expect(rule('id', 'minimum 3 characters')).toMatchObject({ hits: 2, failures: 1, status: 'covered' });
+    expect(rule('id', 'required')).toMatchObject({ hits: 2, failures: 0, status: 'unfailed' });
+    expect(rule('priority', 'option 2: "high"').status).toBe('missed');
+    expect(rule('priority', 'option 3: Number')).toMatchObject({ hits: 1, status: 'covered' });
+    expect(rule('items', 'maximum 2 items').status).toBe('covered');
+    expect(rule('items[].kind', 'required').status).toBe('covered');
+    expect(rule('items[](kind=gift).wrap', 'required')).toMatchObject({ hits: 1, failures: 1, status: 'covered' });
+    expect(rule('note', 'present')).toMatchObject({ hits: 1, status: 'covered' });
+    expect(rule('note', 'null').status).toBe('covered');
+    expect(report.covered).toBe(report.rules.filter(r => r.status === 'covered').length);
+    expect(report.total).toBe(19);
+  });
+
+  it('reports coverage of fixture runs', () => {
+    fs.mkdirSync(path.join(tmpDir, 'fixtures/valid'), { recursive: true });
+    fs.mkdirSync(path.join(tmpDir, 'fixtures/invalid'), { recursive: true });
+    fs.writeFileSync(path.join(tmpDir, 'fixtures/valid/a.json'), '{"id":"ABC","priority":"high","items":[]}');
+    fs.writeFileSync(path.join(tmpDir, 'fixtures/invalid/b.json'), '{"id":"AB","priority":"high","items":[]}');
+
+    const schema = new SpecEngine().compile(path.join(tmpDir, 'order.spec.js'));
+    expect(runFixtures(schema, path.join(tmpDir, 'fixtures')).coverage).toBeUndefined();
+    const { coverage } = runFixtures(schema, path.join(tmpDir, 'fixtures'), { coverage: true });
+    expect(summary(coverage!.rules.filter(r => r.path === 'id' || r.path === 'items[]'))).toEqual([
+      'id | required | unfailed',
+      'id | minimum 3 characters | covered',
+      'id | matches `/^[A-Z]/` | unfailed',
+      'items[] | kind=book | missed',
+      'items[] | kind=gift | missed',
+    ]);
+  });
+});
@@ -0,0 +1,91 @@
+// test/differential.test.ts
+
+import { describe, it, expect, beforeAll, afterAll } from 'vitest';
+import { SpecEngine } from '../dist/engine.js';
+import { differential, engineRunner, commandRunner, listCorpus, type Runner } from '../dist/differential.js';
+import fs from 'fs';
+import path from 'path';
+import os from 'os';
+
+describe('differential', () => {
+  let tmpDir: string;
+  let corpusDir: string;
+
+  const writeSpec = (name: string, valueSpec: string) => {
+    const specPath = path.join(tmpDir, name);
+    fs.writeFileSync(specPath, `
+      Directory({
+        content: {
+          required: [
+            JsonFile({ path: 'config.json', required: [Field({ key: 'port', value: ${valueSpec} })] })
+          ]
+        }
+      })
+    `);
+    return specPath;
+  };
+
+  beforeAll(() => {
+    tmpDir = fs.mkdtempSync(path.join(os.tmpdir(), 'specspec-differential-'));
+    corpusDir = path.join(tmpDir, 'corpus');
+    for (const [name, port] of [['a', 80], ['b', 8080], ['c', 'x']] as const) {
+      fs.mkdirSync(path.join(corpusDir, name), { recursive: true });
+      fs.writeFileSync(path.join(corpusDir, name, 'config.json'), JSON.stringify({ port }));
+    }
+  });
+
+  afterAll(() => {
+    fs.rmSync(tmpDir, { recursive: true });
+  });
+
+  it('reports no changes for identical validators', () => {
+    const specPath = writeSpec('same.spec.js', 'Num()');
+    const runner = engineRunner(new SpecEngine(), specPath);
+    const report = differential(listCorpus(corpusDir), runner, runner);
+    expect(report.total).toBe(3);
+    expect(report.changes).toHaveLength(0);
+  });
+
+  it('reports entries that flip verdict', () => {
+    const left = engineRunner(new SpecEngine(), writeSpec('old.spec.js', 'Num()'));
+    const right = engineRunner(new SpecEngine(), writeSpec('new.spec.js', 'Num({ max: 1024 })'));
+    const report = differential(listCorpus(corpusDir), left, right);
+
+    expect(report.verdictChanges).toBe(1);
+    expect(report.changes).toHaveLength(1);
+    const change = report.changes[0];
+    expect(path.basename(change.target)).toBe('b');
+    expect(change.left.ok).toBe(true);
+    expect(change.right.ok).toBe(false);
+    expect(change.added.map(i => i.code)).toEqual(['num.too_large']);
+    expect(change.removed).toHaveLength(0);
+  });
+
+  it('reports changed issues without a verdict flip', () => {
+    const left: Runner = () => ({ ok: false, issues: [{ code: 'a', path: 'x', message: '' }] });
+    const right: Runner = () => ({ ok: false, issues: [{ code: 'b', path: 'x', message: '' }] });
+    const report = differential(['t'], left, right);
+    expect(report.verdictChanges).toBe(0);
+    expect(report.changes[0].added[0].code).toBe('b');
+    expect(report.changes[0].removed[0].code).toBe('a');
+  });
+
+  it('reads results printed by a validator command', () => {
+    const script = path.join(tmpDir, 'validator.mjs');
+    fs.writeFileSync(script, `
+      const target = process.argv[2];
+      const ok = !target.endsWith('c');
+      console.log(JSON.stringify({ ok, issues: ok ? [] : [{ path: 'port', code: 'type.mismatch', message: 'bad' }] }));
+    `);
+    const run = commandRunner(process.execPath, [script]);
+    expect(run(path.join(corpusDir, 'a')).ok).toBe(true);
+    expect(run(path.join(corpusDir, 'c')).issues[0].code).toBe('type.mismatch');
+  });
+
+  it('treats a failing validator command as invalid', () => {
+    const run = commandRunner(process.execPath, ['-e', 'process.exit(3)']);
+    const outcome = run('anything');
+    expect(outcome.ok).toBe(false);
+    expect(outcome.issues[0].code).toBe('runner.error');
+  });
+});
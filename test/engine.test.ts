@@ -2,6 +2,7 @@
 
 import { describe, it, expect, beforeAll, afterAll } from 'vitest';
 import { SpecEngine } from '../dist/engine.js';
+import { SchemaError } from '../dist/schema.js';
 import fs from 'fs';
 import path from 'path';
 import os from 'os';
@@ -203,4 +204,363 @@ describe('SpecEngine', () => {
     // Should pass - target5 is a directory
     expect(result.ok).toBe(true);
   });
+
+  describe('compile', () => {
+    function compileError(engine: SpecEngine, code: string): SchemaError {
+      try {
+        engine.compileSource(code, path.join(tmpDir, 'inline.spec.js'));
+      } catch (err) {
+        if (err instanceof SchemaError) return err;
+        throw err;
+      }
+      throw new Error('expected compile to fail');
+    }
+
+    it('returns a schema that validates targets', () => {
+      const schema = new SpecEngine().compileSource(`Directory({ content: { required: [File({ path: 'a.txt' })] } })`);
+      expect(schema.describe().name).toBe('Directory');
+      expect(schema.validate(tmpDir).issues.map(i => i.code)).toEqual(['file.not_found']);
+    });
+
+    it('reports unregistered types at compile time', () => {
+      const err = compileError(new SpecEngine(), `Directory({ content: { required: [Email()] } })`);
+      expect(err.issues.map(i => i.code)).toEqual(['spec.unknown_type']);
+      expect(err.issues[0].message).toContain('Email');
+    });
+
+    it('reports unsupported checksum algorithms at compile time', () => {
+      const err = compileError(new SpecEngine(), `
+        Directory({ content: { required: [File({ path: 'a.bin', checksum: { algorithm: 'md4', value: 'ab' } })] } })
+      `);
+      expect(err.issues.map(i => i.code)).toEqual(['spec.unsupported_algorithm']);
+      expect(err.issues[0].message).toContain('a.bin');
+    });
+
+    it('checks the condition and branches of When rules at compile time', () => {
+      const err = compileError(new SpecEngine(), `
+        Directory({ content: { required: [When({
+          file: 'manifest.json',
+          then: File({ path: 'a.bin', checksum: { algorithm: 'md4', value: 'ab' } }),
+        })] } })
+      `);
+      expect(err.issues.map(i => i.code)).toEqual(['spec.unsupported_algorithm']);
+    });
+
+    it('reports defaults on required fields at compile time', () => {
+      const err = compileError(new SpecEngine(), `
+        JsonFile({ path: 'package.json', required: [Field({ key: 'license', value: Str(), default: 'MIT' })] })
+      `);
+      expect(err.issues.map(i => i.code)).toEqual(['spec.invalid_default']);
+      expect(err.issues[0].message).toContain('license');
+    });
+
+    it('normalizes JSON values with defaults filled in', () => {
+      const schema = new SpecEngine().compileSource(`
+        Def('Config', {
+          required: [Field({ key: 'name', value: Str() })],
+          optional: [
+            Field({ key: 'port', value: Num({ min: 1 }), optional: true, default: 8080 }),
+            Field({ key: 'debug', value: Bool({ default: false }), optional: true }),
+          ],
+        })
+      `);
+      const input = { name: 'api', extra: 1 };
+      const result = schema.normalize(input, { stripUnknown: true });
+      expect(result.ok).toBe(true);
+      expect(result.value).toEqual({ name: 'api', port: 8080, debug: false });
+      expect(input).toEqual({ name: 'api', extra: 1 });
+
+      const invalid = schema.normalize({ port: 0 });
+      expect(invalid.ok).toBe(false);
+      expect(invalid.issues.map(i => i.code)).toEqual(['field.missing', 'num.too_small']);
+      expect(invalid.value).toEqual({ port: 0, debug: false });
+    });
+
+    it('accepts string inputs in coercion mode', () => {
+      const schema = new SpecEngine().compileSource(`
+        Def('Env', { required: [Field({ key: 'port', value: Num({ min: 1 }) }), Field({ key: 'tls', value: Bool() })] })
+      `);
+      expect(schema.normalize({ port: '443', tls: 'true' }).ok).toBe(false);
+
+      const result = schema.normalize({ port: '443', tls: 'true' }, { coerce: true });
+      expect(result.ok).toBe(true);
+      expect(result.value).toEqual({ port: 443, tls: true });
+      expect(result.issues.map(i => [i.level, i.code])).toEqual([['info', 'coerce.number'], ['info', 'coerce.boolean']]);
+    });
+
+    it('samples ListOf items unless sampling is turned off', () => {
+      const dir = path.join(tmpDir, 'sampled');
+      fs.mkdirSync(dir);
+      fs.writeFileSync(path.join(dir, 'data.json'), JSON.stringify({ items: Array.from({ length: 100 }, () => 'x') }));
+      const spec = `
+        Directory({ content: { required: [JsonFile({ path: 'data.json', required: [
+          Field({ key: 'items', value: ListOf(Num(), { sample: { head: 2 } }) })
+        ] })] } })
+      `;
+
+      const sampled = new SpecEngine().compileSource(spec).validate(dir);
+      expect(sampled.partial).toBe(true);
+      expect(sampled.issues.filter(i => i.code === 'type.mismatch')).toHaveLength(2);
+
+      const full = new SpecEngine({ sampling: false }).compileSource(spec).validate(dir);
+      expect(full.partial).toBeUndefined();
+      expect(full.issues.filter(i => i.code === 'type.mismatch')).toHaveLength(100);
+    });
+
+    it('reports a missing spec file without throwing from run()', () => {
+      const result = new SpecEngine().run(path.join(tmpDir, 'absent.spec.js'), tmpDir);
+      expect(result.issues.map(i => i.code)).toEqual(['spec.not_found']);
+    });
+
+    it('never throws from validate()', async () => {
+      const { Type } = await import('../dist/base.js');
+      class Broken extends Type<void> {
+        validate(): void {
+          throw new Error('boom');
+        }
+      }
+      const engine = new SpecEngine({ types: { Broken: () => new Broken(undefined) } });
+      const schema = engine.compileSource('Broken()');
+      expect(schema.validate(tmpDir).issues.map(i => i.code)).toEqual(['engine.error']);
+    });
+  });
+
+  describe('runAll', () => {
+    let bundle: string;
+    let specs: string[];
+
+    beforeAll(() => {
+      bundle = path.join(tmpDir, 'multi-bundle');
+      fs.mkdirSync(bundle, { recursive: true });
+      fs.writeFileSync(path.join(bundle, 'manifest.json'), JSON.stringify({ name: 'app', license: 'GPL' }));
+      const write = (name: string, code: string): string => {
+        const specPath = path.join(tmpDir, name);
+        fs.writeFileSync(specPath, code);
+        return specPath;
+      };
+      specs = [
+        write('format.spec.js', `Directory({ content: { required: [JsonFile({ path: 'manifest.json', required: [Field({ key: 'name', value: Str() })] })] } })`),
+        write('policy.spec.js', `Directory({ content: { required: [JsonFile({ path: 'manifest.json', required: [Field({ key: 'license', value: 'MIT' })] })] } })`),
+        write('tenant.spec.js', `Directory({ content: { required: [File({ path: 'tenant.json' })] } })`),
+      ];
+    });
+
+    it('reports results per spec, in order', () => {
+      const result = new SpecEngine().runAll(specs, bundle);
+      expect(result.ok).toBe(false);
+      expect(result.results.map(r => [path.basename(r.spec), r.ok])).toEqual([
+        ['format.spec.js', true],
+        ['policy.spec.js', false],
+        ['tenant.spec.js', false],
+      ]);
+      expect(result.results[1].issues.map(i => i.code)).toEqual(['literal.mismatch']);
+      expect(result.results[2].issues.map(i => i.code)).toEqual(['file.not_found']);
+    });
+
+    it('skips the remaining specs after a failure with stopOnFailure', () => {
+      const result = new SpecEngine().runAll(specs, bundle, { stopOnFailure: true });
+      expect(result.results.map(r => r.skipped ?? false)).toEqual([false, false, true]);
+      expect(result.results[2].issues).toHaveLength(0);
+    });
+
+    it('reports a spec that fails to compile without stopping the others', () => {
+      const result = new SpecEngine().runAll([path.join(tmpDir, 'absent.spec.js'), specs[0]], bundle);
+      expect(result.results[0].issues.map(i => i.code)).toEqual(['spec.not_found']);
+      expect(result.results[1].ok).toBe(true);
+    });
+
+    it('reads each file once across specs', () => {
+      const engine = new SpecEngine();
+      const schemas = specs.slice(0, 2).map(spec => ({ spec, schema: engine.compile(spec) }));
+      const manifest = path.join(bundle, 'manifest.json');
+      const original = fs.readFileSync;
+      let reads = 0;
+      fs.readFileSync = ((...args: Parameters<typeof fs.readFileSync>) => {
+        if (args[0] === manifest) reads++;
+        return original(...args);
+      }) as typeof fs.readFileSync;
+      try {
+        engine.validateAll(schemas, bundle);
+      } finally {
+        fs.readFileSync = original;
+      }
+      expect(reads).toBe(1);
+    });
+  });
+
+  describe('imports', () => {
+    it('imports definitions relative to the importing file', () => {
+      const sharedDir = path.join(tmpDir, 'imports', 'shared');
+      fs.mkdirSync(sharedDir, { recursive: true });
+      fs.writeFileSync(path.join(sharedDir, 'common.spec.js'), `
+        const { Version } = Import('./version.spec.js');
+        Def('Metadata', { required: [Field({ key: 'name', value: Str() }), Field({ key: 'version', value: Version })] });
+        exports.NameField = Field({ key: 'name', value: Str({ minLength: 1 }) });
+      `);
+      fs.writeFileSync(path.join(sharedDir, 'version.spec.js'), `
+        Def('Version', Str({ minLength: 5 }));
+      `);
+      const specPath = path.join(tmpDir, 'imports', 'main.spec.js');
+      fs.writeFileSync(specPath, `
+        const { Metadata, NameField } = Import('./shared/common.spec.js');
+        Directory({
+          content: {
+            required: [JsonFile({ path: 'meta.json', required: [NameField, Field({ key: 'meta', value: Metadata })] })]
+          }
+        })
+      `);
+
+      const targetDir = path.join(tmpDir, 'imports', 'target');
+      fs.mkdirSync(targetDir);
+      fs.writeFileSync(path.join(targetDir, 'meta.json'), JSON.stringify({ name: 'x', meta: { name: 'x', version: '1' } }));
+
+      const result = new SpecEngine().run(specPath, targetDir);
+      expect(result.issues.map(i => i.code)).toEqual(['str.too_short']);
+      expect(result.issues[0].path).toEqual(['target', 'meta.json', 'meta', 'version']);
+    });
+
+    it('lists the files a spec imports', () => {
+      const specPath = path.join(tmpDir, 'imports', 'main.spec.js');
+      expect(new SpecEngine().compile(specPath).imports).toEqual([
+        path.join(tmpDir, 'imports', 'shared', 'common.spec.js'),
+        path.join(tmpDir, 'imports', 'shared', 'version.spec.js'),
+      ]);
+    });
+
+    it('reports import cycles', () => {
+      const dir = path.join(tmpDir, 'cycle');
+      fs.mkdirSync(dir);
+      fs.writeFileSync(path.join(dir, 'a.spec.js'), `Import('./b.spec.js'); Directory()`);
+      fs.writeFileSync(path.join(dir, 'b.spec.js'), `Import('./a.spec.js');`);
+
+      const result = new SpecEngine().run(path.join(dir, 'a.spec.js'), dir);
+      expect(result.issues[0].code).toBe('spec.import_cycle');
+      expect(result.issues[0].message).toContain('a.spec.js -> b.spec.js -> a.spec.js');
+    });
+
+    it('reports missing imports', () => {
+      const specPath = path.join(tmpDir, 'missing-import.spec.js');
+      fs.writeFileSync(specPath, `Import('./nope.spec.js'); Directory()`);
+
+      const result = new SpecEngine().run(specPath, tmpDir);
+      expect(result.issues[0].code).toBe('spec.import_not_found');
+    });
+
+    it('lists the imports a failed spec asked for', () => {
+      const specPath = path.join(tmpDir, 'missing-import.spec.js');
+      fs.writeFileSync(specPath, `Import('./nope.spec.js'); Directory()`);
+
+      let error: unknown;
+      try {
+        new SpecEngine().compile(specPath);
+      } catch (err) {
+        error = err;
+      }
+      expect(error).toBeInstanceOf(SchemaError);
+      expect((error as SchemaError).imports).toEqual([path.join(tmpDir, 'nope.spec.js')]);
+    });
+  });
+
+  describe('compare', () => {
+    const spec = `
+      Directory({
+        content: {
+          required: [
+            JsonFile({
+              path: 'package.json',
+              required: [Field({ key: 'name', value: Str() })],
+              optional: [Field({ key: 'deps', value: ListOf(Str()), optional: true })]
+            })
+          ],
+          optional: [File({ path: 'README.md' })]
+        }
+      })
+    `;
+
+    function makeTarget(name: string, pkg: unknown, files: string[] = []): string {
+      const dir = path.join(tmpDir, name);
+      fs.mkdirSync(dir);
+      fs.writeFileSync(path.join(dir, 'package.json'), JSON.stringify(pkg));
+      for (const f of files) {
+        fs.writeFileSync(path.join(dir, f), 'content');
+      }
+      return dir;
+    }
+
+    it('ignores differences the spec does not care about', () => {
+      const specPath = path.join(tmpDir, 'compare1.spec.js');
+      fs.writeFileSync(specPath, spec);
+      const left = makeTarget('cmp-a', { name: 'app', private: true }, ['NOTES.txt']);
+      const right = makeTarget('cmp-b', { name: 'app', version: '2.0.0' });
+
+      const result = new SpecEngine().compare(specPath, left, right);
+      expect(result.identical).toBe(true);
+      expect(result.left.ok).toBe(true);
+      expect(result.right.ok).toBe(true);
+    });
+
+    it('reports spec-relevant differences', () => {
+      const specPath = path.join(tmpDir, 'compare2.spec.js');
+      fs.writeFileSync(specPath, spec);
+      const left = makeTarget('cmp-c', { name: 'app', deps: ['a', 'b'] }, ['README.md']);
+      const right = makeTarget('cmp-d', { name: 'app2', deps: ['a'] });
+
+      const result = new SpecEngine().compare(specPath, left, right);
+      expect(result.identical).toBe(false);
+      expect(result.differences).toEqual([
+        { path: ['package.json', 'name'], kind: 'changed', left: 'app', right: 'app2' },
+        { path: ['package.json', 'deps', '[1]'], kind: 'removed', left: 'b' },
+        { path: ['README.md'], kind: 'removed', left: true },
+      ]);
+    });
+
+    it('compares the files of When branches and the value that picks one', () => {
+      const specPath = path.join(tmpDir, 'compare-when.spec.js');
+      fs.writeFileSync(specPath, `
+        Directory({ content: { required: [When({
+          file: 'package.json',
+          field: 'config.docs',
+          is: true,
+          then: File({ path: 'DOCS.md' }),
+          else: File({ path: 'NO_DOCS' }),
+        })] } })
+      `);
+      const left = makeTarget('cmp-when-a', { name: 'app', config: { docs: true, port: 1 } }, ['DOCS.md']);
+      const right = makeTarget('cmp-when-b', { name: 'app', config: { docs: false } }, ['NO_DOCS']);
+
+      const result = new SpecEngine().compare(specPath, left, right);
+      expect(result.differences).toEqual([
+        { path: ['DOCS.md'], kind: 'removed', left: true },
+        { path: ['package.json', 'config', 'docs'], kind: 'changed', left: true, right: false },
+        { path: ['NO_DOCS'], kind: 'added', right: true },
+      ]);
+    });
+
+    it('throws SchemaError when the spec cannot be loaded', () => {
+      const specPath = path.join(tmpDir, 'compare-broken.spec.js');
+      fs.writeFileSync(specPath, 'Directory({');
+      const left = makeTarget('cmp-e', { name: 'app' });
+
+      expect(() => new SpecEngine().compare(specPath, left, left)).toThrow(SchemaError);
+    });
+  });
+
+  describe('compat', () => {
+    it('reports breaking changes between two spec files', () => {
+      const oldPath = path.join(tmpDir, 'compat-v1.spec.js');
+      const newPath = path.join(tmpDir, 'compat-v2.spec.js');
+      fs.writeFileSync(oldPath, `JsonFile({ path: 'package.json', required: [Field({ key: 'license', value: OneOf('MIT', 'GPL-2.0'), optional: true })] })`);
+      fs.writeFileSync(newPath, `JsonFile({ path: 'package.json', required: [Field({ key: 'license', value: OneOf('MIT', 'GPL-3.0') })] })`);
+
+      const report = new SpecEngine().compat(oldPath, newPath);
+      expect(report?.compatible).toBe(false);
+      expect(report?.breaking).toBe(2);
+      expect(report?.changes.map(c => c.code)).toEqual(['item.became_required', 'enum.value_removed', 'enum.value_added']);
+    });
+
+    it('throws SchemaError when a spec cannot be loaded', () => {
+      const oldPath = path.join(tmpDir, 'compat-v1.spec.js');
+      expect(() => new SpecEngine().compat(oldPath, path.join(tmpDir, 'compat-missing.spec.js'))).toThrow(SchemaError);
+    });
+  });
 });
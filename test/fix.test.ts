@@ -0,0 +1,79 @@
+// test/fix.test.ts
+
+import { describe, it, expect } from 'vitest';
+import fs from 'fs';
+import os from 'os';
+import path from 'path';
+import { applyFixes, applyPatch, toPointer } from '../dist/fix.js';
+import { ValidationContext } from '../dist/context.js';
+import { validateAny } from '../dist/base.js';
+import { Field, Directory, JsonFile } from '../dist/types/structural.js';
+import { Str } from '../dist/types/primitives.js';
+import { ListOf } from '../dist/modifiers/listof.js';
+
+describe('applyPatch', () => {
+  it('applies add, remove, replace and move', () => {
+    const doc = { a: 1, b: [1, 2], c: 'x' };
+    const result = applyPatch(doc, [
+      { op: 'add', path: '/b/-', value: 3 },
+      { op: 'remove', path: '/a' },
+      { op: 'replace', path: '/c', value: 'y' },
+      { op: 'move', from: '/c', path: '/d' },
+    ]);
+    expect(result).toEqual({ b: [1, 2, 3], d: 'y' });
+  });
+
+  it('throws when a path does not exist', () => {
+    expect(() => applyPatch({}, [{ op: 'remove', path: '/missing' }])).toThrow();
+  });
+});
+
+describe('applyFixes', () => {
+  it('converts issue paths to JSON Pointers', () => {
+    expect(toPointer(['deps', '[2]', 'a/b'])).toBe('/deps/2/a~1b');
+  });
+
+  it('applies fixes relative to each issue path', () => {
+    const spec = Field({
+      key: 'packages',
+      value: ListOf({ required: [Field({ key: 'name', value: Str() }), Field({ key: 'kind', value: 'lib' })] }),
+    });
+    const doc = { packages: [{ nmae: 'core', kind: 'lib' }, { name: 'cli', kind: 'app' }] };
+    const ctx = new ValidationContext([], doc);
+    validateAny(spec, doc, ctx);
+
+    const result = applyFixes(doc, ctx.issues);
+    expect(result.applied).toHaveLength(2);
+    expect(result.skipped).toHaveLength(0);
+    expect(result.value).toEqual({ packages: [{ name: 'core', kind: 'lib' }, { name: 'cli', kind: 'lib' }] });
+    expect(doc.packages[0]).toHaveProperty('nmae');
+  });
+
+  it('only applies fixes inside the given document', () => {
+    const dir = fs.mkdtempSync(path.join(os.tmpdir(), 'specspec-fix-'));
+    fs.writeFileSync(path.join(dir, 'meta.json'), JSON.stringify({ titel: 'demo' }));
+    const spec = Directory({
+      content: { required: [JsonFile({ path: 'meta.json', required: [Field({ key: 'title', value: Str() })] })] },
+    });
+    const ctx = new ValidationContext([], dir);
+    validateAny(spec, dir, ctx);
+    fs.rmSync(dir, { recursive: true, force: true });
+
+    const docPath = ctx.issues[0].path;
+    expect(applyFixes({ titel: 'demo' }, ctx.issues, docPath).value).toEqual({ title: 'demo' });
+    expect(applyFixes({ titel: 'demo' }, ctx.issues, ['elsewhere']).applied).toHaveLength(0);
+  });
+
+  it('skips a fix whose target has changed', () => {
+    const issue = {
+      level: 'error' as const,
+      code: 'field.missing',
+      message: 'Missing required field: name',
+      path: [],
+      fix: [{ op: 'move' as const, from: '/nmae', path: '/name' }],
+    };
+    const result = applyFixes({ name: 'x' }, [issue]);
+    expect(result.skipped).toEqual([issue]);
+    expect(result.value).toEqual({ name: 'x' });
+  });
+});
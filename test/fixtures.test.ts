@@ -0,0 +1,85 @@
+// test/fixtures.test.ts
+
+import { describe, it, expect, beforeEach, afterEach } from 'vitest';
+import fs from 'node:fs';
+import os from 'node:os';
+import path from 'node:path';
+import { SpecEngine } from '../dist/engine.js';
+import { runFixtures, findFixtures, checkFixture, expectedIssuesFile } from '../dist/fixtures.js';
+
+describe('spec fixtures', () => {
+  let tmpDir: string;
+
+  const write = (rel: string, content: string) => {
+    fs.mkdirSync(path.dirname(path.join(tmpDir, rel)), { recursive: true });
+    fs.writeFileSync(path.join(tmpDir, rel), content);
+  };
+
+  beforeEach(() => {
+    tmpDir = fs.mkdtempSync(path.join(os.tmpdir(), 'specspec-fixtures-'));
+    write('config.spec.js', `Def('Config', {
+      required: [Field({ key: 'name', value: Str({ minLength: 3 }) })],
+      optional: [Field({ key: 'port', value: Num({ integer: true }), optional: true })],
+    })`);
+  });
+
+  afterEach(() => {
+    fs.rmSync(tmpDir, { recursive: true, force: true });
+  });
+
+  it('finds fixtures and their expected-issue files', () => {
+    write('fixtures/valid/a.json', '{}');
+    write('fixtures/valid/nested/b.json', '{}');
+    write('fixtures/valid/notes.md', '');
+    write('fixtures/invalid/c.json', '{}');
+    write('fixtures/invalid/c.expected.json', '[]');
+    fs.mkdirSync(path.join(tmpDir, 'fixtures/invalid/bundle'));
+
+    const rel = (fileSystem: boolean) => findFixtures(path.join(tmpDir, 'fixtures'), fileSystem)
+      .map(f => `${f.expected}:${path.relative(path.join(tmpDir, 'fixtures'), f.fixture)}`);
+    expect(rel(false)).toEqual(['valid:valid/a.json', 'valid:valid/nested/b.json', 'invalid:invalid/c.json']);
+    expect(rel(true)).toEqual(['valid:valid/a.json', 'valid:valid/nested', 'valid:valid/notes.md', 'invalid:invalid/bundle', 'invalid:invalid/c.json']);
+
+    expect(expectedIssuesFile(path.join(tmpDir, 'fixtures/invalid/c.json'))).toBe(path.join(tmpDir, 'fixtures/invalid/c.expected.json'));
+    expect(expectedIssuesFile(path.join(tmpDir, 'fixtures/invalid/bundle'))).toBe(path.join(tmpDir, 'fixtures/invalid/bundle.expected.json'));
+  });
+
+  it('reports fixtures that do not match their directory or expected issues', () => {
+    write('fixtures/valid/good.json', '{"name":"alpha","port":80}');
+    write('fixtures/valid/bad.json', '{"name":"al"}');
+    write('fixtures/invalid/short.json', '{"name":"al"}');
+    write('fixtures/invalid/short.expected.json', '[{"code":"str.too_short","path":"name"}]');
+    write('fixtures/invalid/extra.json', '{"name":"al","port":1.5}');
+    write('fixtures/invalid/extra.expected.json', '["str.too_short"]');
+    write('fixtures/invalid/wrong.json', '{"port":8080}');
+    write('fixtures/invalid/wrong.expected.json', '["str.too_short"]');
+    write('fixtures/invalid/passes.json', '{"name":"alpha"}');
+
+    const report = runFixtures(new SpecEngine().compile(path.join(tmpDir, 'config.spec.js')), path.join(tmpDir, 'fixtures'));
+    const byName = Object.fromEntries(report.results.map(r => [path.basename(r.fixture), r]));
+
+    expect(report).toMatchObject({ ok: false, total: 6, passed: 2, failed: 4 });
+    expect(byName['good.json']!.ok).toBe(true);
+    expect(byName['short.json']).toMatchObject({ ok: true, expectedFile: path.join(tmpDir, 'fixtures/invalid/short.expected.json') });
+    expect(byName['bad.json']!.unexpected.map(i => i.code)).toEqual(['str.too_short']);
+    expect(byName['extra.json']!.unexpected.map(i => i.code)).toEqual(['num.not_integer']);
+    expect(byName['wrong.json']!.missing).toEqual([{ code: 'str.too_short' }]);
+    expect(byName['passes.json']).toMatchObject({ ok: false, missing: [], unexpected: [] });
+  });
+
+  it('matches expected issues by code, path and level', () => {
+    const result = {
+      ok: false,
+      issues: [
+        { level: 'error' as const, code: 'field.missing', message: 'Missing required field', path: [] },
+        { level: 'warning' as const, code: 'field.deprecated', message: 'Deprecated', path: ['a', 0] },
+      ],
+    };
+    expect(checkFixture('x.json', 'invalid', result, [{ code: 'field.missing', path: '(root)' }]).ok).toBe(true);
+    expect(checkFixture('x.json', 'invalid', result, [{ code: 'field.missing' }, { code: 'field.deprecated', path: 'a.0', level: 'warning' }]).ok).toBe(true);
+    expect(checkFixture('x.json', 'invalid', result, [{ code: 'field.missing', path: 'a' }]).missing).toHaveLength(1);
+    expect(checkFixture('x.json', 'invalid', result, [{ code: 'field.missing', level: 'warning' }]).ok).toBe(false);
+    expect(checkFixture('x.json', 'invalid', result).ok).toBe(true);
+    expect(checkFixture('x.json', 'valid', result).unexpected.map(i => i.code)).toEqual(['field.missing']);
+  });
+});
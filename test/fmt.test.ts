@@ -0,0 +1,86 @@
+// test/fmt.test.ts
+
+import { describe, it, expect } from 'vitest';
+import { formatSpec } from '../dist/fmt.js';
+
+describe('spec formatter', () => {
+  it('orders type options and wraps long lines', () => {
+    const source = [
+      '// Versions',
+      'const Version = Str({ description: "semver", match: /^\\d+\\.\\d+$/, minLength: 3 })',
+      'const Tree = Def("Tree", self => ({ optional: [Field({ value: Num({ max: 10, min: -1 }), key: \'weight\' })], required: [Field({ key: \'name\', value: Str() }), Field({ key: \'children\', value: ListOf(self) })] }));',
+      '',
+      '',
+      'const Mode = OneOf(\'alpha-option-number-one\', \'beta-option-number-two\', \'gamma-option-number-three\', \'delta\')',
+    ].join('\n');
+
+    expect(formatSpec(source)).toBe([
+      '// Versions',
+      'const Version = Str({ minLength: 3, match: /^\\d+\\.\\d+$/, description: \'semver\' })',
+      'const Tree = Def(\'Tree\', self => ({',
+      '  required: [',
+      '    Field({ key: \'name\', value: Str() }),',
+      '    Field({ key: \'children\', value: ListOf(self) }),',
+      '  ],',
+      '  optional: [Field({ key: \'weight\', value: Num({ min: -1, max: 10 }) })],',
+      '}));',
+      '',
+      'const Mode = OneOf(',
+      '  \'alpha-option-number-one\',',
+      '  \'beta-option-number-two\',',
+      '  \'gamma-option-number-three\',',
+      '  \'delta\',',
+      ')',
+      '',
+    ].join('\n'));
+  });
+
+  it('keeps comments, blocks and objects of unknown types as they are', () => {
+    const source = [
+      'function check(x) { if (x > 1) { return x * -2 } else { return x++ } }',
+      'Directory({',
+      '  content: {',
+      '    required: [',
+      '      // The manifest',
+      '      JsonFile({ path: \'package.json\' }), // first',
+      '      File({ path: \'README.md\' })',
+      '    ]',
+      '  }',
+      '})',
+      'const meta = Meta(Str(), { team: \'core\', area: \'build\' })',
+    ].join('\n');
+
+    expect(formatSpec(source)).toBe([
+      'function check(x) {',
+      '  if (x > 1) {',
+      '    return x * -2',
+      '  } else {',
+      '    return x++',
+      '  }',
+      '}',
+      'Directory({',
+      '  content: {',
+      '    required: [',
+      '      // The manifest',
+      '      JsonFile({ path: \'package.json\' }), // first',
+      '      File({ path: \'README.md\' }),',
+      '    ],',
+      '  },',
+      '})',
+      'const meta = Meta(Str(), { team: \'core\', area: \'build\' })',
+      '',
+    ].join('\n'));
+    expect(() => formatSpec('Str({ minLength: 1 )')).toThrow('Unexpected ")" on line 1');
+  });
+
+  it('leaves formatted specs unchanged', () => {
+    const source = [
+      'const Name = Field({key: "name", value: Str({minLength: 1, maxLength: 214, match: /^[a-z0-9-]+$/}), description: "The package name, lowercase"})',
+      'export default Directory({ content: { required: [JsonFile({ path: `package.json`, required: [Name] })], optional: [Directory({ path: "src", content: { optional: [File({ path: "index.js" })] } })] } });',
+    ].join('\n');
+    const formatted = formatSpec(source);
+    expect(formatted).not.toBe(source);
+    expect(formatSpec(formatted)).toBe(formatted);
+    expect(formatSpec(formatted, { width: 60 })).not.toBe(formatted);
+  });
+});
@@ -0,0 +1,82 @@
+// test/lint.test.ts
+
+import { describe, it, expect, beforeEach, afterEach } from 'vitest';
+import fs from 'node:fs';
+import os from 'node:os';
+import path from 'node:path';
+import { SpecEngine } from '../dist/engine.js';
+import { lintSpecs } from '../dist/lint.js';
+
+describe('spec linter', () => {
+  let tmpDir: string;
+
+  const write = (name: string, source: string) => {
+    const file = path.join(tmpDir, name);
+    fs.writeFileSync(file, source);
+    return file;
+  };
+
+  // Findings as "code at path"
+  const findings = (specs: string[]) => lintSpecs(new SpecEngine(), specs).results
+    .map(r => r.issues.map(i => `${i.code} at ${i.path.join('.') || '(root)'}`));
+
+  beforeEach(() => {
+    tmpDir = fs.mkdtempSync(path.join(os.tmpdir(), 'specspec-lint-'));
+  });
+
+  afterEach(() => {
+    fs.rmSync(tmpDir, { recursive: true, force: true });
+  });
+
+  it('reports contradictions, patterns that cannot match and duplicate fields', () => {
+    const spec = write('order.spec.js', `Def('Order', {
+      required: [
+        Field({ key: 'id', value: Str({ minLength: 5, maxLength: 3 }) }),
+        Field({ key: 'code', value: Str({ match: /^[A-Z]{4}-\\d+$/, maxLength: 5 }) }),
+        Field({ key: 'ref', value: Str({ match: /^ab$c/ }) }),
+        Field({ key: 'tag', value: OneOf(/x[]/, /^(?:a|b$)c?$/) }),
+        Field({ key: 'qty', value: Num({ integer: true, min: 0.2, max: 0.8 }) }),
+        Field({ key: 'lines', value: ListOf(Str(), { min: 3, max: 1 }) }),
+      ],
+      optional: [Field({ key: 'id', value: Str(), optional: true })],
+    })`);
+
+    expect(findings([spec])).toEqual([[
+      'lint.duplicate_field at (root)',
+      'lint.contradictory at id',
+      'lint.contradictory at code',
+      'lint.pattern_never_matches at ref',
+      'lint.pattern_never_matches at tag.(option 1)',
+      'lint.contradictory at qty',
+      'lint.contradictory at lines',
+    ]]);
+    expect(lintSpecs(new SpecEngine(), [spec]).ok).toBe(false);
+  });
+
+  it('reports OneOf options an earlier option shadows', () => {
+    const spec = write('mode.spec.js', `OneOf(
+      Num({ min: 0 }), Num({ min: 1, max: 5 }), Num(), 'auto', Str({ maxLength: 4 }), 'fast', /^v\\d$/, 'manual'
+    )`);
+
+    const [result] = lintSpecs(new SpecEngine(), [spec]).results;
+    expect(result!.issues.map(i => i.path.join('.'))).toEqual(['(option 2)', '(option 6)']);
+    expect(result!.issues[1]!.message).toBe('Option 6 ("fast") is never chosen: option 5 (String) accepts every value it does');
+    expect(result!.ok).toBe(true);
+  });
+
+  it('reports Defs no linted spec reaches', () => {
+    const types = write('types.spec.js', `
+      const Sku = Def('Sku', Str({ match: /^[A-Z]{3}$/ }));
+      const Legacy = Def('Legacy', Str());
+      exports.Sku = Sku;
+      Def('Types', Sku);
+    `);
+    const order = write('order.spec.js', `
+      const { Sku } = Import('./types.spec.js');
+      Def('Order', { required: [Field({ key: 'sku', value: Sku })] });
+    `);
+
+    expect(findings([types, order])).toEqual([['lint.unused_def at (root)'], []]);
+    expect(lintSpecs(new SpecEngine(), [types, order]).results[0]!.issues[0]!.message).toBe("Def 'Legacy' (line 3) is never used");
+  });
+});
@@ -0,0 +1,141 @@
+// test/lsp.test.ts
+
+import { describe, it, expect, beforeEach, afterEach } from 'vitest';
+import fs from 'node:fs';
+import os from 'node:os';
+import path from 'node:path';
+import { pathToFileURL } from 'node:url';
+import { SpecLanguageService, SpecLanguageServer, messageReader, frameMessage, type JsonRpcMessage } from '../dist/lsp.js';
+
+// Position of the first `marker` in source, plus `delta` characters
+function at(source: string, marker: string, delta = 0) {
+  const offset = source.indexOf(marker) + delta;
+  const before = source.slice(0, offset);
+  return { line: before.split('\n').length - 1, character: offset - (before.lastIndexOf('\n') + 1) };
+}
+
+describe('SpecLanguageService', () => {
+  let tmpDir: string;
+  const service = new SpecLanguageService();
+
+  beforeEach(() => {
+    tmpDir = fs.mkdtempSync(path.join(os.tmpdir(), 'specspec-lsp-'));
+  });
+
+  afterEach(() => {
+    fs.rmSync(tmpDir, { recursive: true, force: true });
+  });
+
+  it('reports syntax errors and unknown types where they are', () => {
+    const file = path.join(tmpDir, 'a.spec.js');
+    const syntax = 'const A = Str();\nStr({ minLength: 3 ))\n';
+    const [error] = service.diagnose(syntax, file);
+    expect(error!.code).toBe('spec.syntax_error');
+    expect(error!.range.start).toEqual({ line: 1, character: 19 });
+
+    const unknown = 'Def(\'A\', {\n  required: [Field({ key: \'x\', value: Strr() })],\n})\n';
+    const [issue] = service.diagnose(unknown, file);
+    expect(issue!.code).toBe('spec.unknown_type');
+    expect(issue!.message).toMatch(/^Strr is not defined/);
+    expect(issue!.range).toEqual({ start: at(unknown, 'Strr'), end: at(unknown, 'Strr', 4) });
+
+    expect(service.diagnose('Str({ minLength: 1 })', file)).toEqual([]);
+  });
+
+  it('locates fields with invalid defaults', () => {
+    const source = 'JsonFile({ path: \'a.json\', required: [\n  Field({ key: \'port\', value: Num(), default: 80 }),\n] })';
+    const [issue] = service.diagnose(source, path.join(tmpDir, 'a.spec.js'));
+    expect(issue!.code).toBe('spec.invalid_default');
+    expect(issue!.range.start).toEqual(at(source, 'key: \'port\''));
+  });
+
+  it('goes to named types in the spec and in imported files', () => {
+    fs.writeFileSync(path.join(tmpDir, 'common.spec.js'), '// shared\nDef(\'Address\', { required: [Field({ key: \'city\', value: Str() })] });\n');
+    const file = path.join(tmpDir, 'order.spec.js');
+    const source = [
+      'const { Address } = Import(\'./common.spec.js\');',
+      'const common = Import(\'./common.spec.js\');',
+      'const Sku = Def(\'Sku\', Str({ match: /^[A-Z]{3}$/ }));',
+      'Def(\'Order\', { required: [Field({ key: \'sku\', value: Sku }), Field({ key: \'to\', value: Address }), Field({ key: \'from\', value: common.Address })] })',
+    ].join('\n');
+
+    expect(service.definition(source, file, at(source, 'value: Sku', 8))).toEqual({
+      file,
+      range: { start: at(source, 'Sku ='), end: at(source, 'Sku =', 3) },
+    });
+    const imported = { file: path.join(tmpDir, 'common.spec.js'), range: { start: { line: 1, character: 5 }, end: { line: 1, character: 12 } } };
+    expect(service.definition(source, file, at(source, 'value: Address', 9))).toEqual(imported);
+    expect(service.definition(source, file, at(source, 'common.Address', 8))).toEqual(imported);
+    expect(service.definition(source, file, at(source, './common', 3))!.file).toBe(path.join(tmpDir, 'common.spec.js'));
+    expect(service.definition(source, file, at(source, 'Field'))).toBeNull();
+  });
+
+  it('shows named types and built-ins on hover', () => {
+    const file = path.join(tmpDir, 'a.spec.js');
+    const source = [
+      'const Sku = Def(\'Sku\', Str({ match: /^[A-Z]{3}$/ }));',
+      'Def(\'Line\', { required: [Field({ key: \'sku\', value: Sku, description: \'Stock unit\' }), Field({ key: \'qty\', value: Num({ min: 1 }) })] }, { description: \'One order line\' })',
+    ].join('\n');
+
+    const line = service.hover(source, file, at(source, 'Line'))!;
+    expect(line).toContain('Def(\'Line\'): Object');
+    expect(line).toContain('One order line');
+    expect(line).toContain('- `sku`: Sku (matches `/^[A-Z]{3}$/`) — Stock unit');
+    expect(line).toContain('- `qty`: Number (minimum 1)');
+    expect(service.hover(source, file, at(source, 'value: Sku', 8))).toContain('Def(\'Sku\'): String (matches `/^[A-Z]{3}$/`)');
+    expect(service.hover(source, file, at(source, 'Num'))).toContain('Num(options?)');
+    expect(service.hover(source, file, at(source, 'min:'))).toBe('`min` — Inclusive lower bound');
+  });
+
+  it('completes the options of the type being written', () => {
+    const labels = (source: string) => service.complete(source, at(source, '|')).map(i => i.label);
+
+    expect(labels('Str({ minLength: 2, | })')).toEqual(['description', 'minLength', 'maxLength', 'match']);
+    expect(labels('ListOf(Str({ match: /[({]/ }), { | })')).toContain('uniqueBy');
+    expect(labels('ListOf(Str(), { sample: { | } })')).toEqual(['head', 'tail', 'random', 'seed']);
+    expect(labels('Def(\'A\', { required: [Field({ key: \'a\', | })] })')).toContain('nullable');
+    expect(labels('Def(\'A\', { | })')).toEqual(['required', 'optional']);
+    expect(labels('TaggedUnion(\'kind\', { circle: { | } })')).toEqual(['required', 'optional']);
+
+    const types = labels('const Sku = Def(\'Sku\', Str());\nField({ key: \'a\', value: | })');
+    expect(types).toContain('Str');
+    expect(types).toContain('TaggedUnion');
+    expect(types).toContain('Sku');
+  });
+});
+
+describe('SpecLanguageServer', () => {
+  it('speaks LSP over framed messages', () => {
+    const sent: JsonRpcMessage[] = [];
+    let exitCode: number | undefined;
+    const server = new SpecLanguageServer(new SpecLanguageService(), m => sent.push(m), code => { exitCode = code; });
+    const read = messageReader(m => server.handle(m));
+
+    const uri = pathToFileURL(path.join(os.tmpdir(), 'lsp.spec.js')).href;
+    const messages: JsonRpcMessage[] = [
+      { jsonrpc: '2.0', id: 1, method: 'initialize', params: { capabilities: {} } },
+      { jsonrpc: '2.0', method: 'initialized', params: {} },
+      { jsonrpc: '2.0', method: 'textDocument/didOpen', params: { textDocument: { uri, languageId: 'javascript', version: 1, text: 'Str({ minLength: ))' } } },
+      { jsonrpc: '2.0', method: 'textDocument/didChange', params: { textDocument: { uri, version: 2 }, contentChanges: [{ text: 'Str({ minLength: 1 })' }] } },
+      { jsonrpc: '2.0', id: 2, method: 'textDocument/hover', params: { textDocument: { uri }, position: { line: 0, character: 1 } } },
+      { jsonrpc: '2.0', id: 3, method: 'textDocument/formatting', params: { textDocument: { uri } } },
+      { jsonrpc: '2.0', id: 4, method: 'shutdown' },
+      { jsonrpc: '2.0', method: 'exit' },
+    ];
+    // Split the stream mid-message to exercise reassembly
+    const stream = Buffer.from(messages.map(frameMessage).join(''));
+    read(stream.subarray(0, 50));
+    read(stream.subarray(50));
+
+    expect((sent[0]!.result as { capabilities: { hoverProvider: boolean } }).capabilities.hoverProvider).toBe(true);
+    const diagnostics = sent.filter(m => m.method === 'textDocument/publishDiagnostics')
+      .map(m => (m.params as { diagnostics: unknown[] }).diagnostics.length);
+    expect(diagnostics).toEqual([1, 0]);
+    const hover = sent.find(m => m.id === 2)!.result as { contents: { kind: string; value: string } };
+    expect(hover.contents.kind).toBe('markdown');
+    expect(hover.contents.value).toContain('Str(options?)');
+    expect(sent.find(m => m.id === 3)!.error!.code).toBe(-32601);
+    expect(sent.find(m => m.id === 4)!.result).toBeNull();
+    expect(exitCode).toBe(0);
+  });
+});
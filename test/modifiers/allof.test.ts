@@ -0,0 +1,80 @@
+// test/modifiers/allof.test.ts
+
+import { describe, it, expect } from 'vitest';
+import { AllOf } from '../../dist/modifiers/allof.js';
+import { Str, Num } from '../../dist/types/primitives.js';
+import { Field } from '../../dist/types/structural.js';
+import { Type } from '../../dist/base.js';
+import type { Context } from '../../dist/context.js';
+import { createTestContext } from '../helpers.js';
+
+const Base = { required: [Field({ key: 'id', value: Str() })] };
+const Timestamped = { required: [Field({ key: 'createdAt', value: Num() })] };
+
+// Reports one issue of each level
+class Notes extends Type<undefined> {
+  validate(_value: unknown, ctx: Context): void {
+    ctx.addInfo('note.info', 'info');
+    ctx.addWarning('note.warning', 'warning');
+    ctx.addIssue('note.error', 'error');
+  }
+}
+
+describe('AllOf', () => {
+  it('accepts value satisfying every part', () => {
+    const ctx = createTestContext();
+    AllOf(Base, Timestamped).validate({ id: 'a', createdAt: 1 }, ctx);
+    expect(ctx.issues).toHaveLength(0);
+  });
+
+  it('reports issues from every failing part', () => {
+    const ctx = createTestContext();
+    AllOf(Base, Timestamped).validate({ id: 1 }, ctx);
+    expect(ctx.issues.map(i => i.code)).toEqual(['type.mismatch', 'field.missing']);
+    expect(ctx.issues[0].path).toEqual(['id']);
+    expect(ctx.issues[1].path).toEqual([]);
+  });
+
+  it('keeps nested paths under the current context', () => {
+    const ctx = createTestContext();
+    AllOf(Base, Timestamped).validate({ id: 'a', createdAt: 'soon' }, ctx.child('item', null));
+    expect(ctx.issues).toHaveLength(1);
+    expect(ctx.issues[0].path).toEqual(['item', 'createdAt']);
+  });
+
+  it('reports an issue shared by several parts once', () => {
+    const ctx = createTestContext();
+    AllOf(Base, Timestamped).validate('not an object', ctx);
+    expect(ctx.issues).toHaveLength(1);
+    expect(ctx.issues[0].code).toBe('type.mismatch');
+  });
+
+  it('combines constraints on a single value', () => {
+    const ctx = createTestContext();
+    AllOf(Str({ minLength: 3 }), /^[a-z]+$/).validate('A', ctx);
+    expect(ctx.issues.map(i => i.code)).toEqual(['str.too_short', 'literal.pattern_mismatch']);
+  });
+
+  it('keeps the level of each issue', () => {
+    const ctx = createTestContext();
+    AllOf(Str(), new Notes(undefined)).validate('a', ctx);
+    expect(ctx.issues.map(i => [i.level, i.code])).toEqual([
+      ['info', 'note.info'],
+      ['warning', 'note.warning'],
+      ['error', 'note.error'],
+    ]);
+  });
+
+  it('matches only when every part matches', () => {
+    const ctx = createTestContext();
+    expect(AllOf(Str(), /^a/).matches('abc', ctx)).toBe(true);
+    expect(AllOf(Str(), /^a/).matches('xyz', ctx)).toBe(false);
+  });
+
+  it('describes its parts', () => {
+    const desc = AllOf(Base, Timestamped).describe();
+    expect(desc.name).toBe('AllOf');
+    expect(desc.allOf).toHaveLength(2);
+    expect(desc.allOf![0].children!.required![0].key).toBe('id');
+  });
+});
@@ -0,0 +1,86 @@
+// test/modifiers/def.test.ts
+
+import { describe, it, expect } from 'vitest';
+import { Def } from '../../dist/modifiers/def.js';
+import { ListOf } from '../../dist/modifiers/listof.js';
+import { Str } from '../../dist/types/primitives.js';
+import { Field } from '../../dist/types/structural.js';
+import { createTestContext } from '../helpers.js';
+
+const Meta = Def('PackageMeta', {
+  required: [Field({ key: 'name', value: Str() }), Field({ key: 'version', value: Str() })],
+});
+
+describe('Def', () => {
+  it('validates like its body', () => {
+    const ctx = createTestContext();
+    Meta.validate({ name: 'core', version: '1.0.0' }, ctx);
+    expect(ctx.issues).toHaveLength(0);
+
+    Meta.validate({ name: 'core' }, ctx);
+    expect(ctx.issues.map(i => i.code)).toEqual(['field.missing']);
+  });
+
+  it('reports issues at the path of each use', () => {
+    const ctx = createTestContext();
+    const spec = Field({ key: 'deps', value: ListOf(Meta) });
+    spec.validate({ deps: [{ name: 'a', version: '1' }, { name: 'b', version: 2 }] }, ctx);
+    expect(ctx.issues).toHaveLength(1);
+    expect(ctx.issues[0].path).toEqual(['deps', '[1]', 'version']);
+  });
+
+  it('describes the body tagged with its name', () => {
+    const desc = Meta.describe();
+    expect(desc.name).toBe('Object');
+    expect(desc.ref).toBe('PackageMeta');
+    expect(desc.children?.required).toHaveLength(2);
+
+    const field = Field({ key: 'pkg', value: Meta }).describe();
+    expect(field.ref).toBe('PackageMeta');
+    expect(field.children?.required).toHaveLength(2);
+  });
+
+  it('validates recursive types', () => {
+    const Node = Def('TreeNode', self => ({
+      required: [Field({ key: 'name', value: Str() }), Field({ key: 'children', value: ListOf(self) })],
+    }));
+    const ctx = createTestContext();
+    Node.validate({ name: 'a', children: [{ name: 'b', children: [{ name: 3, children: [] }] }] }, ctx);
+    expect(ctx.issues).toHaveLength(1);
+    expect(ctx.issues[0].path).toEqual(['children', '[0]', 'children', '[0]', 'name']);
+  });
+
+  it('reports nesting beyond maxDepth', () => {
+    const Node = Def('TreeNode', self => ({
+      optional: [Field({ key: 'child', value: self, optional: true })],
+    }), { maxDepth: 2 });
+    const ctx = createTestContext();
+    Node.validate({ child: {} }, ctx);
+    expect(ctx.issues).toHaveLength(0);
+
+    Node.validate({ child: { child: {} } }, ctx);
+    expect(ctx.issues).toHaveLength(1);
+    expect(ctx.issues[0].path).toEqual(['child', 'child']);
+    expect(ctx.issues[0].code).toBe('recursion.too_deep');
+    expect(ctx.issues[0].message).toBe('Nesting of TreeNode exceeds maximum depth 2');
+  });
+
+  it('describes recursive uses by name', () => {
+    const Node = Def('TreeNode', self => ({
+      required: [Field({ key: 'children', value: ListOf(self) })],
+    }), { maxDepth: 8 });
+    const desc = Node.describe();
+    expect(desc.ref).toBe('TreeNode');
+    expect(desc.maxDepth).toBe(8);
+    expect(desc.children?.required?.[0]?.itemType).toEqual({ name: 'Ref', ref: 'TreeNode' });
+  });
+
+  it('describes its description, also for fields using it', () => {
+    const Address = Def('Address', { required: [Field({ key: 'street', value: Str() })] }, { description: 'A postal address' });
+    expect(Address.describe().description).toBe('A postal address');
+
+    const field = Field({ key: 'home', value: Address, description: 'Where the owner lives' }).describe();
+    expect(field.description).toBe('Where the owner lives');
+    expect(field.valueDescription).toBe('A postal address');
+  });
+});
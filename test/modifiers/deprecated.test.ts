@@ -0,0 +1,46 @@
+// test/modifiers/deprecated.test.ts
+
+import { describe, it, expect } from 'vitest';
+import { Deprecated } from '../../dist/modifiers/deprecated.js';
+import { OneOf } from '../../dist/modifiers/oneof.js';
+import { Str } from '../../dist/types/primitives.js';
+import { Field } from '../../dist/types/structural.js';
+import { createTestContext } from '../helpers.js';
+
+const License = OneOf('MIT', Deprecated('GPL-2.0', { replacement: 'GPL-3.0' }), 'GPL-3.0');
+
+describe('Deprecated', () => {
+  it('warns when the deprecated type is used', () => {
+    const ctx = createTestContext();
+    Deprecated(Str(), 'use a structured address').validate('1 Main St', ctx);
+    expect(ctx.issues).toHaveLength(1);
+    expect(ctx.issues[0].level).toBe('warning');
+    expect(ctx.issues[0].code).toBe('type.deprecated');
+    expect(ctx.issues[0].message).toBe('String is deprecated: use a structured address');
+  });
+
+  it('still reports errors of the inner type', () => {
+    const ctx = createTestContext();
+    Deprecated(Str()).validate(42, ctx);
+    expect(ctx.issues.map(i => i.level)).toEqual(['warning', 'error']);
+  });
+
+  it('warns only for the matched OneOf option', () => {
+    const ctx = createTestContext();
+    Field({ key: 'license', value: License }).validate({ license: 'GPL-2.0' }, ctx);
+    expect(ctx.issues).toHaveLength(1);
+    expect(ctx.issues[0].level).toBe('warning');
+    expect(ctx.issues[0].path).toEqual(['license']);
+    expect(ctx.issues[0].message).toBe('"GPL-2.0" is deprecated; use GPL-3.0 instead');
+
+    const current = createTestContext();
+    Field({ key: 'license', value: License }).validate({ license: 'MIT' }, current);
+    expect(current.issues).toHaveLength(0);
+  });
+
+  it('describes the deprecation for code generators', () => {
+    const desc = Field({ key: 'address', value: Deprecated(Str()) }).describe();
+    expect(desc.summary).toBe('String');
+    expect(desc.valueDeprecated).toBe('String is deprecated');
+  });
+});
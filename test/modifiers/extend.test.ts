@@ -0,0 +1,56 @@
+// test/modifiers/extend.test.ts
+
+import { describe, it, expect } from 'vitest';
+import { Extend } from '../../dist/modifiers/extend.js';
+import { Def } from '../../dist/modifiers/def.js';
+import { Str, Num } from '../../dist/types/primitives.js';
+import { Field } from '../../dist/types/structural.js';
+import { createTestContext } from '../helpers.js';
+
+const base = {
+  required: [Field({ key: 'id', value: Str() }), Field({ key: 'version', value: Str() })],
+  optional: [Field({ key: 'note', value: Str(), optional: true })],
+};
+
+describe('Extend', () => {
+  it('inherits base fields and adds derived ones', () => {
+    const Derived = Extend(base, { required: [Field({ key: 'size', value: Num() })] });
+    const ctx = createTestContext();
+    Derived.validate({ version: '1', size: 'big' }, ctx);
+    expect(ctx.issues.map(i => i.code)).toEqual(['field.missing', 'type.mismatch']);
+    expect(ctx.issues[1].path).toEqual(['size']);
+  });
+
+  it('overrides base fields by key', () => {
+    const Derived = Extend(base, {
+      required: [Field({ key: 'version', value: Str({ minLength: 3 }) }), Field({ key: 'note', value: Str() })],
+    });
+    const ctx = createTestContext();
+    Derived.validate({ id: 'a', version: '1' }, ctx);
+    expect(ctx.issues.map(i => i.code)).toEqual(['str.too_short', 'field.missing']);
+
+    const keys = (Derived.describe().children?.required ?? []).map(c => c.key);
+    expect(keys).toEqual(['id', 'version', 'note']);
+    expect(Derived.describe().children?.optional).toEqual([]);
+  });
+
+  it('extends a Def or another Extend', () => {
+    const Base = Def('Base', base);
+    const Middle = Extend(Base, { required: [Field({ key: 'id', value: Num() })] });
+    const Leaf = Extend(Middle, { required: [Field({ key: 'leaf', value: Str() })] });
+    const ctx = createTestContext();
+    Leaf.validate({ id: 'x', version: '1', leaf: 'y' }, ctx);
+    expect(ctx.issues.map(i => i.path)).toEqual([['id']]);
+  });
+
+  it('composes with a named base it only adds to', () => {
+    const desc = Extend(Def('Base', base), { required: [Field({ key: 'size', value: Num() })] }).describe();
+    expect(desc.name).toBe('AllOf');
+    expect(desc.allOf?.[0].ref).toBe('Base');
+    expect(desc.allOf?.[1].children?.required?.map(c => c.key)).toEqual(['size']);
+  });
+
+  it('rejects a base that is not an object type', () => {
+    expect(() => Extend(Str(), { required: [] })).toThrow();
+  });
+});
@@ -0,0 +1,60 @@
+// test/modifiers/generic.test.ts
+
+import { describe, it, expect } from 'vitest';
+import { Generic } from '../../dist/modifiers/generic.js';
+import { Def } from '../../dist/modifiers/def.js';
+import { ListOf } from '../../dist/modifiers/listof.js';
+import { Str, Num } from '../../dist/types/primitives.js';
+import { Field } from '../../dist/types/structural.js';
+import { createTestContext } from '../helpers.js';
+
+const User = Def('User', { required: [Field({ key: 'name', value: Str() })] });
+const Paginated = Generic('Paginated', T => ({
+  required: [Field({ key: 'items', value: ListOf(T) }), Field({ key: 'total', value: Num() })],
+}));
+
+describe('Generic', () => {
+  it('validates instances with their type argument', () => {
+    const ctx = createTestContext();
+    Paginated(User).validate({ items: [{ name: 'a' }, { name: 1 }], total: 2 }, ctx);
+    expect(ctx.issues).toHaveLength(1);
+    expect(ctx.issues[0].path).toEqual(['items', '[1]', 'name']);
+
+    const numbers = createTestContext();
+    Paginated(Num()).validate({ items: [1, 'two'], total: 2 }, numbers);
+    expect(numbers.issues.map(i => i.code)).toEqual(['type.mismatch']);
+  });
+
+  it('names instances after their arguments', () => {
+    expect(Paginated(User).defName).toBe('Paginated<User>');
+    expect(Paginated(ListOf(User)).defName).toBe('Paginated<ListOf<User>>');
+    expect(Paginated(Str()).describe().ref).toBe('Paginated<String>');
+  });
+
+  it('returns the same instance for the same arguments', () => {
+    expect(Paginated(User)).toBe(Paginated(User));
+    expect(Paginated(User)).not.toBe(Paginated(Def('Order', { required: [] })));
+  });
+
+  it('rejects different arguments that would share an instance name', () => {
+    const Page = Generic('Page', T => ({ required: [Field({ key: 'items', value: ListOf(T) })] }));
+    expect(Page(Str({ minLength: 1 }))).toBe(Page(Str({ minLength: 1 })));
+    expect(() => Page(Str({ maxLength: 5 }))).toThrow('pass a Def as the argument');
+    Page({ required: [Field({ key: 'id', value: Str() })] });
+    expect(() => Page({ required: [Field({ key: 'id', value: Num() })] })).toThrow('Page<Object> is already an instance of Page');
+  });
+
+  it('rejects the wrong number of type arguments', () => {
+    expect(() => Paginated(User, User)).toThrow('Paginated expects 1 type argument(s), got 2');
+  });
+
+  it('accepts instances of recursive types', () => {
+    const Tree = Def('Tree', self => ({
+      required: [Field({ key: 'children', value: Paginated(self) })],
+    }));
+    const ctx = createTestContext();
+    Tree.validate({ children: { items: [{ children: { items: [], total: 0 } }], total: 1 } }, ctx);
+    expect(ctx.issues).toHaveLength(0);
+    expect(Tree.describe().children?.required?.[0]?.ref).toBe('Paginated<Tree>');
+  });
+});
@@ -2,6 +2,7 @@
 
 import { describe, it, expect } from 'vitest';
 import { ListOf } from '../../dist/modifiers/listof.js';
+import { ValidationContext } from '../../dist/context.js';
 import { OneOf } from '../../dist/modifiers/oneof.js';
 import { Str, Num } from '../../dist/types/primitives.js';
 import { Field } from '../../dist/types/structural.js';
@@ -54,6 +55,126 @@ describe('ListOf', () => {
     expect(ctx.issues).toHaveLength(3);
   });
 
+  describe('uniqueness', () => {
+    it('reports duplicate items', () => {
+      const ctx = createTestContext();
+      ListOf(Str(), { unique: true }).validate(['a', 'b', 'a'], ctx);
+      expect(ctx.issues).toHaveLength(1);
+      expect(ctx.issues[0].code).toBe('list.duplicate');
+      expect(ctx.issues[0].path).toEqual(['[2]']);
+      expect(ctx.issues[0].message).toContain('[0]');
+    });
+
+    it('compares objects structurally', () => {
+      const ctx = createTestContext();
+      ListOf({}, { unique: true }).validate([{ a: 1, b: 2 }, { b: 2, a: 1 }], ctx);
+      expect(ctx.issues).toHaveLength(1);
+      expect(ctx.issues[0].code).toBe('list.duplicate');
+    });
+
+    it('reports items sharing a key path', () => {
+      const ctx = createTestContext();
+      ListOf({}, { uniqueBy: 'meta.id' }).validate([
+        { meta: { id: 1 } },
+        { meta: { id: 2 } },
+        { other: true },
+        { meta: { id: 1 }, extra: 'x' },
+      ], ctx);
+      expect(ctx.issues).toHaveLength(1);
+      expect(ctx.issues[0].path).toEqual(['[3]']);
+      expect(ctx.issues[0].message).toContain('meta.id');
+    });
+
+    it('describes uniqueness constraints', () => {
+      expect(ListOf(Str(), { unique: true }).describe().constraints).toContain('unique items');
+      expect(ListOf({}, { uniqueBy: 'id' }).describe().constraints).toContain('unique by `id`');
+    });
+  });
+
+  describe('ordering', () => {
+    it('accepts sorted items with equal neighbours', () => {
+      const ctx = createTestContext();
+      ListOf(Num(), { sorted: 'asc' }).validate([1, 2, 2, 5], ctx);
+      expect(ctx.issues).toHaveLength(0);
+    });
+
+    it('reports only the first out-of-order pair', () => {
+      const ctx = createTestContext();
+      ListOf(Num(), { sorted: 'desc' }).validate([5, 3, 4, 1, 2], ctx);
+      expect(ctx.issues).toHaveLength(1);
+      expect(ctx.issues[0].code).toBe('list.not_sorted');
+      expect(ctx.issues[0].path).toEqual(['[2]']);
+      expect(ctx.issues[0].message).toContain('descending');
+    });
+
+    it('orders by key path and skips items without the key', () => {
+      const ctx = createTestContext();
+      const events = [
+        { timestamp: '2024-01-01T00:00:00Z' },
+        { note: 'no timestamp' },
+        { timestamp: '2024-03-01T00:00:00Z' },
+        { timestamp: '2024-02-01T00:00:00Z' },
+      ];
+      ListOf({}, { sortedBy: 'timestamp' }).validate(events, ctx);
+      expect(ctx.issues).toHaveLength(1);
+      expect(ctx.issues[0].path).toEqual(['[3]']);
+      expect(ctx.issues[0].message).toContain('Items [2] and [3]');
+    });
+
+    it('describes ordering constraints', () => {
+      expect(ListOf(Num(), { sorted: 'desc' }).describe().constraints).toContain('sorted descending');
+      expect(ListOf({}, { sortedBy: 'timestamp' }).describe().constraints).toContain('sorted ascending by `timestamp`');
+    });
+  });
+
+  describe('sampling', () => {
+    const big = Array.from({ length: 1000 }, (_, i) => i);
+
+    it('validates only the sampled items and warns', () => {
+      const ctx = createTestContext();
+      const items: unknown[] = [...big];
+      items[0] = 'head';
+      items[999] = 'tail';
+      ListOf(Num(), { sample: { head: 5, tail: 5, random: 10, seed: 1 } }).validate(items, ctx);
+      const errors = ctx.issues.filter(i => i.level === 'error');
+      expect(errors.map(i => i.path)).toEqual([['[0]'], ['[999]']]);
+      const warning = ctx.issues.find(i => i.code === 'list.sampled');
+      expect(warning?.level).toBe('warning');
+      expect(warning?.message).toContain('20 of 1000');
+    });
+
+    it('picks the same random items for the same seed', () => {
+      const pick = () => {
+        const ctx = createTestContext();
+        ListOf(Str(), { sample: { random: 10, seed: 7 } }).validate(big, ctx);
+        return ctx.issues.filter(i => i.level === 'error').map(i => i.path[0]);
+      };
+      expect(pick()).toHaveLength(10);
+      expect(pick()).toEqual(pick());
+    });
+
+    it('validates everything when the array is small', () => {
+      const ctx = createTestContext();
+      ListOf(Num(), { sample: { head: 2, tail: 2, random: 2 } }).validate([1, 2, 'x', 4, 5], ctx);
+      expect(ctx.issues).toHaveLength(1);
+      expect(ctx.issues[0].code).toBe('type.mismatch');
+    });
+
+    it('validates everything when sampling is disabled', () => {
+      const ctx = new ValidationContext([], big, undefined, undefined, { sampling: false });
+      ListOf(Str(), { sample: { head: 1 } }).validate(big, ctx);
+      expect(ctx.issues).toHaveLength(1000);
+      expect(ctx.issues.some(i => i.code === 'list.sampled')).toBe(false);
+      expect(ctx.partial).toBe(false);
+    });
+
+    it('marks the context partial when sampling', () => {
+      const ctx = new ValidationContext([], big);
+      ListOf(Num(), { sample: { head: 1 } }).validate(big, ctx.child('items', big));
+      expect(ctx.partial).toBe(true);
+    });
+  });
+
   describe('with complex item types', () => {
     it('validates array of OneOf', () => {
       const ctx = createTestContext();
@@ -0,0 +1,51 @@
+// test/modifiers/message.test.ts
+
+import { describe, it, expect } from 'vitest';
+import { Message } from '../../dist/modifiers/message.js';
+import { Str, Num } from '../../dist/types/primitives.js';
+import { Field } from '../../dist/types/structural.js';
+import { createTestContext } from '../helpers.js';
+
+const Region = Message(Str({ match: /^eu-(west|central)-\d$/ }), {
+  code: 'policy.bad_region',
+  message: 'Region must be one of the EU datacenters',
+});
+
+describe('Message', () => {
+  it('reports the spec-defined code and message', () => {
+    const ctx = createTestContext();
+    Field({ key: 'region', value: Region }).validate({ region: 'us-east-1' }, ctx);
+    expect(ctx.issues).toHaveLength(1);
+    expect(ctx.issues[0].code).toBe('policy.bad_region');
+    expect(ctx.issues[0].message).toBe('Region must be one of the EU datacenters');
+    expect(ctx.issues[0].path).toEqual(['region']);
+  });
+
+  it('passes valid values', () => {
+    const ctx = createTestContext();
+    Region.validate('eu-west-1', ctx);
+    expect(ctx.issues).toHaveLength(0);
+  });
+
+  it('keeps the inner message when only the code is given', () => {
+    const ctx = createTestContext();
+    Message(Num({ min: 1 }), { code: 'policy.bad_size' }).validate(0, ctx);
+    expect(ctx.issues[0].code).toBe('policy.bad_size');
+    expect(ctx.issues[0].message).toContain('minimum 1');
+  });
+
+  it('reports a failing object as one issue at its own path', () => {
+    const ctx = createTestContext();
+    const Owner = Message({ required: [Field({ key: 'name', value: Str() }), Field({ key: 'email', value: Str() })] }, {
+      code: 'policy.bad_owner',
+    });
+    Field({ key: 'owner', value: Owner }).validate({ owner: {} }, ctx);
+    expect(ctx.issues.map(i => [i.code, i.path])).toEqual([['policy.bad_owner', ['owner']]]);
+  });
+
+  it('describes the custom issue for code generators', () => {
+    const desc = Field({ key: 'region', value: Region }).describe();
+    expect(desc.summary).toBe('String');
+    expect(desc.customIssue).toEqual({ code: 'policy.bad_region', message: 'Region must be one of the EU datacenters' });
+  });
+});
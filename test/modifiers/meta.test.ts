@@ -0,0 +1,52 @@
+// test/modifiers/meta.test.ts
+
+import { describe, it, expect } from 'vitest';
+import { Meta } from '../../dist/modifiers/meta.js';
+import { AllOf } from '../../dist/modifiers/allof.js';
+import { OneOf } from '../../dist/modifiers/oneof.js';
+import { Str } from '../../dist/types/primitives.js';
+import { Field } from '../../dist/types/structural.js';
+import { createTestContext } from '../helpers.js';
+
+const legal = { team: 'legal', doc: 'https://example.com/licensing' };
+
+describe('Meta', () => {
+  it('attaches metadata to issues of the inner type', () => {
+    const ctx = createTestContext();
+    Field({ key: 'license', value: Meta(OneOf('MIT', 'Apache-2.0'), legal) }).validate({ license: 'GPL' }, ctx);
+    expect(ctx.issues).toHaveLength(1);
+    expect(ctx.issues[0].path).toEqual(['license']);
+    expect(ctx.issues[0].meta).toEqual(legal);
+  });
+
+  it('leaves issues outside the inner type untouched', () => {
+    const ctx = createTestContext();
+    const spec = { required: [Field({ key: 'license', value: Meta(Str(), legal) }), Field({ key: 'name', value: Str() })] };
+    Field({ key: 'pkg', value: spec }).validate({ pkg: { license: 1, name: 2 } }, ctx);
+    expect(ctx.issues.map(i => i.meta)).toEqual([legal, undefined]);
+  });
+
+  it('merges nested metadata, inner keys winning', () => {
+    const ctx = createTestContext();
+    Meta(Meta(Str(), { policy: 'LIC-1', team: 'compliance' }), legal).validate(42, ctx);
+    expect(ctx.issues[0].meta).toEqual({ team: 'compliance', doc: legal.doc, policy: 'LIC-1' });
+  });
+
+  it('keeps metadata through AllOf', () => {
+    const ctx = createTestContext();
+    AllOf(Meta(Str({ minLength: 3 }), legal), Str()).validate('ab', ctx);
+    expect(ctx.issues[0].meta).toEqual(legal);
+  });
+
+  it('can be populated by custom rules', () => {
+    const ctx = createTestContext();
+    ctx.withMeta({ policy: 'SEC-7' }).child('token', 'x').addIssue('secret.leaked', 'Secret committed');
+    expect(ctx.issues[0]).toMatchObject({ path: ['token'], meta: { policy: 'SEC-7' } });
+  });
+
+  it('describes the inner type with its metadata', () => {
+    const desc = Field({ key: 'license', value: Meta(Str(), legal) }).describe();
+    expect(desc.summary).toBe('String');
+    expect(desc.meta).toEqual(legal);
+  });
+});
@@ -0,0 +1,55 @@
+// test/modifiers/not.test.ts
+
+import { describe, it, expect } from 'vitest';
+import { Not } from '../../dist/modifiers/not.js';
+import { AllOf } from '../../dist/modifiers/allof.js';
+import { Str } from '../../dist/types/primitives.js';
+import { Field } from '../../dist/types/structural.js';
+import { createTestContext } from '../helpers.js';
+
+describe('Not', () => {
+  it('rejects value matching the inner pattern', () => {
+    const ctx = createTestContext();
+    Not(/^(admin|root)$/).validate('admin', ctx);
+    expect(ctx.issues).toHaveLength(1);
+    expect(ctx.issues[0].code).toBe('not.matched');
+    expect(ctx.issues[0].message).toContain('admin|root');
+  });
+
+  it('accepts value not matching the inner type', () => {
+    const ctx = createTestContext();
+    Not('latest').validate('1.2.0', ctx);
+    expect(ctx.issues).toHaveLength(0);
+  });
+
+  it('applies nested rules of the inner type', () => {
+    const ctx = createTestContext();
+    const Legacy = { required: [Field({ key: 'version', value: Str({ maxLength: 1 }) })] };
+    Not(Legacy).validate({ version: '2' }, ctx);
+    expect(ctx.issues).toHaveLength(1);
+
+    const ok = createTestContext();
+    Not(Legacy).validate({ version: '10' }, ok);
+    expect(ok.issues).toHaveLength(0);
+  });
+
+  it('composes with AllOf for reserved names', () => {
+    const ctx = createTestContext();
+    const Id = AllOf(Str({ minLength: 1 }), Not(/^(admin|root)$/));
+    Id.validate('root', ctx.child('id', 'root'));
+    expect(ctx.issues).toHaveLength(1);
+    expect(ctx.issues[0].path).toEqual(['id']);
+  });
+
+  it('matches only values the inner type rejects', () => {
+    const ctx = createTestContext();
+    expect(Not(Str()).matches(1, ctx)).toBe(true);
+    expect(Not(Str()).matches('a', ctx)).toBe(false);
+  });
+
+  it('describes the excluded type', () => {
+    const desc = Not(/^x/).describe();
+    expect(desc.name).toBe('Not');
+    expect(desc.not?.name).toBe('Pattern');
+  });
+});
@@ -0,0 +1,64 @@
+// test/modifiers/nullable.test.ts
+
+import { describe, it, expect } from 'vitest';
+import { Nullable } from '../../dist/modifiers/nullable.js';
+import { Str } from '../../dist/types/primitives.js';
+import { Field } from '../../dist/types/structural.js';
+import { createTestContext } from '../helpers.js';
+
+describe('Nullable', () => {
+  it('accepts null', () => {
+    const ctx = createTestContext();
+    Nullable(Str()).validate(null, ctx);
+    expect(ctx.issues).toHaveLength(0);
+  });
+
+  it('validates non-null values with the inner type', () => {
+    const ctx = createTestContext();
+    Nullable(Str({ minLength: 3 })).validate('ab', ctx);
+    expect(ctx.issues).toHaveLength(1);
+    expect(ctx.issues[0].code).toBe('str.too_short');
+  });
+
+  it('describes as the inner type marked nullable', () => {
+    const desc = Nullable(Str()).describe();
+    expect(desc.name).toBe('String');
+    expect(desc.nullable).toBe(true);
+  });
+
+  it('describes inline object specs by their fields', () => {
+    const desc = Nullable({ required: [Field({ key: 'id', value: Str() })], optional: ['draft'] }).describe();
+    expect(desc.name).toBe('Object');
+    expect(desc.children?.required?.map(c => c.key)).toEqual(['id']);
+    expect(desc.children?.optional?.map(c => c.name)).toEqual(['Literal']);
+    expect(desc.nullable).toBe(true);
+  });
+
+  describe('on fields', () => {
+    it('accepts null for a nullable field', () => {
+      const ctx = createTestContext();
+      Field({ key: 'end_date', value: Str(), nullable: true }).validate({ end_date: null }, ctx);
+      expect(ctx.issues).toHaveLength(0);
+    });
+
+    it('rejects null for a non-nullable field', () => {
+      const ctx = createTestContext();
+      Field({ key: 'end_date', value: Str() }).validate({ end_date: null }, ctx);
+      expect(ctx.issues).toHaveLength(1);
+      expect(ctx.issues[0].code).toBe('type.mismatch');
+    });
+
+    it('still requires the key to be present', () => {
+      const ctx = createTestContext();
+      Field({ key: 'end_date', value: Str(), nullable: true }).validate({}, ctx);
+      expect(ctx.issues).toHaveLength(1);
+      expect(ctx.issues[0].code).toBe('field.missing');
+    });
+
+    it('marks field description nullable when value is Nullable', () => {
+      const desc = Field({ key: 'end_date', value: Nullable(Str()) }).describe();
+      expect(desc.summary).toBe('String');
+      expect(desc.nullable).toBe(true);
+    });
+  });
+});
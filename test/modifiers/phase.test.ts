@@ -0,0 +1,49 @@
+// test/modifiers/phase.test.ts
+
+import { describe, it, expect } from 'vitest';
+import { SpecEngine } from '../../dist/engine.js';
+import { Phase } from '../../dist/modifiers/phase.js';
+import { OneOf } from '../../dist/modifiers/oneof.js';
+import { Str, Num } from '../../dist/types/primitives.js';
+import { Field } from '../../dist/types/structural.js';
+import { createTestContext } from '../helpers.js';
+
+const engine = new SpecEngine();
+
+const Config = {
+  required: [
+    Field({ key: 'name', value: Str() }),
+    Phase(2, Field({ key: 'size', value: Num({ max: 10 }) })),
+    Phase(1, Field({ key: 'tag', value: Str({ minLength: 2 }) })),
+  ],
+};
+const root = Field({ key: 'config', value: Config });
+
+describe('Phase', () => {
+  it('runs later phases in order once earlier ones pass', () => {
+    const result = engine.validateValue(root, { config: { name: 'a', tag: 'ok', size: 20 } });
+    expect(result.ok).toBe(false);
+    expect(result.issues.map(i => [i.code, i.path])).toEqual([['num.too_large', ['config', 'size']]]);
+  });
+
+  it('skips the remaining phases after errors', () => {
+    const broken = engine.validateValue(root, { config: { name: 1, tag: 'x', size: 20 } });
+    expect(broken.issues.map(i => i.code)).toEqual(['type.mismatch', 'phase.skipped', 'phase.skipped']);
+    expect(broken.issues[1]).toMatchObject({ level: 'info', path: [], message: 'Skipped 1 check of phase 1 because an earlier phase reported errors' });
+
+    const badTag = engine.validateValue(root, { config: { name: 'a', tag: 'x', size: 20 } });
+    expect(badTag.issues.map(i => i.code)).toEqual(['str.too_short', 'phase.skipped']);
+    expect(badTag.ok).toBe(false);
+  });
+
+  it('checks at once outside a phased run and in OneOf options', () => {
+    const ctx = createTestContext();
+    Phase(1, Str()).validate(1, ctx);
+    expect(ctx.issues.map(i => i.code)).toEqual(['type.mismatch']);
+
+    const result = engine.validateValue(OneOf(Phase(1, Num()), Phase(1, Str())), 'a');
+    expect(result.ok).toBe(true);
+    expect(Phase(3, Str()).describe()).toMatchObject({ name: 'String', phase: 3 });
+    expect(() => Phase(0, Str())).toThrow('Phase() order must be a positive integer, got 0');
+  });
+});
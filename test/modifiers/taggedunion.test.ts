@@ -0,0 +1,70 @@
+// test/modifiers/taggedunion.test.ts
+
+import { describe, it, expect } from 'vitest';
+import { TaggedUnion } from '../../dist/modifiers/taggedunion.js';
+import { Num } from '../../dist/types/primitives.js';
+import { Field } from '../../dist/types/structural.js';
+import { createTestContext } from '../helpers.js';
+
+const Shape = TaggedUnion('kind', {
+  circle: { required: [Field({ key: 'radius', value: Num({ min: 0 }) })] },
+  rect: { required: [Field({ key: 'width', value: Num() }), Field({ key: 'height', value: Num() })] },
+});
+
+describe('TaggedUnion', () => {
+  it('accepts a valid variant', () => {
+    const ctx = createTestContext();
+    Shape.validate({ kind: 'rect', width: 1, height: 2 }, ctx);
+    expect(ctx.issues).toHaveLength(0);
+  });
+
+  it('validates only the selected variant', () => {
+    const ctx = createTestContext();
+    Shape.validate({ kind: 'circle', radius: -1 }, ctx);
+    expect(ctx.issues).toHaveLength(1);
+    expect(ctx.issues[0].code).toBe('num.too_small');
+    expect(ctx.issues[0].path).toEqual(['radius']);
+  });
+
+  it('reports unknown tag with the observed value', () => {
+    const ctx = createTestContext();
+    Shape.validate({ kind: 'triangle' }, ctx);
+    expect(ctx.issues).toHaveLength(1);
+    expect(ctx.issues[0].code).toBe('union.unknown_tag');
+    expect(ctx.issues[0].path).toEqual(['kind']);
+    expect(ctx.issues[0].message).toContain('"triangle"');
+    expect(ctx.issues[0].message).toContain('circle, rect');
+  });
+
+  it('reports missing discriminator', () => {
+    const ctx = createTestContext();
+    Shape.validate({ radius: 1 }, ctx);
+    expect(ctx.issues).toHaveLength(1);
+    expect(ctx.issues[0].code).toBe('field.missing');
+  });
+
+  it('rejects non-objects', () => {
+    const ctx = createTestContext();
+    Shape.validate([], ctx);
+    expect(ctx.issues[0].code).toBe('type.mismatch');
+  });
+
+  it('does not treat inherited properties as tags', () => {
+    const ctx = createTestContext();
+    Shape.validate({ kind: 'toString' }, ctx);
+    expect(ctx.issues[0].code).toBe('union.unknown_tag');
+  });
+
+  it('matches only known, valid variants', () => {
+    const ctx = createTestContext();
+    expect(Shape.matches({ kind: 'rect', width: 1, height: 2 }, ctx)).toBe(true);
+    expect(Shape.matches({ kind: 'square' }, ctx)).toBe(false);
+  });
+
+  it('describes discriminator and variants', () => {
+    const desc = Shape.describe();
+    expect(desc.name).toBe('TaggedUnion');
+    expect(desc.discriminator).toBe('kind');
+    expect(Object.keys(desc.variants!)).toEqual(['circle', 'rect']);
+  });
+});
@@ -0,0 +1,117 @@
+// test/normalize.test.ts
+
+import { describe, it, expect } from 'vitest';
+import { normalizeValue } from '../dist/normalize.js';
+import type { Issue } from '../dist/context.js';
+import { Str, Num, Bool, Decimal } from '../dist/types/primitives.js';
+import { Field } from '../dist/types/structural.js';
+import { ListOf } from '../dist/modifiers/listof.js';
+import { TaggedUnion } from '../dist/modifiers/taggedunion.js';
+import { Def } from '../dist/modifiers/def.js';
+import { Extend } from '../dist/modifiers/extend.js';
+
+const Retry = Def('Retry', {
+  required: [Field({ key: 'attempts', value: Num(), optional: true, default: 3 })],
+  optional: [Field({ key: 'backoff', value: Str(), optional: true, default: 'exponential' })],
+});
+
+describe('normalizeValue', () => {
+  it('fills in missing defaults and keeps present values', () => {
+    expect(normalizeValue(Retry.describe(), { attempts: 5 })).toEqual({ attempts: 5, backoff: 'exponential' });
+  });
+
+  it('keeps undeclared fields unless stripUnknown is set', () => {
+    expect(normalizeValue(Retry.describe(), { note: 'x' })).toEqual({ note: 'x', attempts: 3, backoff: 'exponential' });
+    expect(normalizeValue(Retry.describe(), { note: 'x' }, { stripUnknown: true })).toEqual({ attempts: 3, backoff: 'exponential' });
+  });
+
+  it('normalizes nested objects, list items and default objects', () => {
+    const Job = Def('Job', {
+      required: [
+        Field({ key: 'steps', value: ListOf({ required: [Field({ key: 'name', value: Str() }), Field({ key: 'retry', value: Retry, optional: true, default: {} })] }) }),
+      ],
+    });
+    expect(normalizeValue(Job.describe(), { steps: [{ name: 'build' }, { name: 'test', retry: { attempts: 1 } }] })).toEqual({
+      steps: [
+        { name: 'build', retry: { attempts: 3, backoff: 'exponential' } },
+        { name: 'test', retry: { attempts: 1, backoff: 'exponential' } },
+      ],
+    });
+  });
+
+  it('normalizes the selected TaggedUnion variant', () => {
+    const Shape = TaggedUnion('kind', {
+      circle: { required: [Field({ key: 'radius', value: Num(), optional: true, default: 1 })] },
+      square: { required: [Field({ key: 'side', value: Num() })] },
+    });
+    expect(normalizeValue(Shape.describe(), { kind: 'circle', color: 'red' }, { stripUnknown: true })).toEqual({ kind: 'circle', radius: 1 });
+  });
+
+  it('keeps fields declared by any part of an extended type', () => {
+    const Base = Def('Base', { required: [Field({ key: 'id', value: Str() })] });
+    const Derived = Extend(Base, { optional: [Field({ key: 'tags', value: ListOf(Str()), optional: true, default: [] })] });
+    expect(normalizeValue(Derived.describe(), { id: 'a', other: true }, { stripUnknown: true })).toEqual({ id: 'a', tags: [] });
+  });
+
+  it('resolves recursive types', () => {
+    const Node = Def('Node', self => ({
+      required: [Field({ key: 'children', value: ListOf(self), optional: true, default: [] })],
+    }));
+    expect(normalizeValue(Node.describe(), { children: [{}] })).toEqual({ children: [{ children: [] }] });
+  });
+
+  it('gives each filled-in default its own copy', () => {
+    const Tags = Def('Tags', { required: [Field({ key: 'tags', value: ListOf(Str()), optional: true, default: [] })] });
+    const a = normalizeValue(Tags.describe(), {}) as { tags: string[] };
+    const b = normalizeValue(Tags.describe(), {}) as { tags: string[] };
+    a.tags.push('x');
+    expect(b.tags).toEqual([]);
+  });
+});
+
+describe('normalizeValue with coerce', () => {
+  const Env = Def('Env', {
+    required: [
+      Field({ key: 'port', value: Num({ integer: true }) }),
+      Field({ key: 'debug', value: Bool() }),
+      Field({ key: 'hosts', value: ListOf(Str()) }),
+      Field({ key: 'weights', value: ListOf(Num()) }),
+      Field({ key: 'name', value: Str() }),
+    ],
+  });
+
+  it('converts strings and single values, reporting each as info', () => {
+    const issues: Issue[] = [];
+    const value = normalizeValue(Env.describe(), { port: '8080', debug: 'false', hosts: 'a.example', weights: ['1.5', '2'], name: '42' }, { coerce: true }, issues);
+    expect(value).toEqual({ port: 8080, debug: false, hosts: ['a.example'], weights: [1.5, 2], name: '42' });
+    expect(issues.map(i => [i.level, i.code, i.path.join('.')])).toEqual([
+      ['info', 'coerce.number', 'port'],
+      ['info', 'coerce.boolean', 'debug'],
+      ['info', 'coerce.list', 'hosts'],
+      ['info', 'coerce.number', 'weights.[0]'],
+      ['info', 'coerce.number', 'weights.[1]'],
+    ]);
+  });
+
+  it('leaves values that do not convert cleanly', () => {
+    const value = normalizeValue(Env.describe(), { port: '80a', debug: 'yes', hosts: null, weights: [], name: 'x' }, { coerce: true });
+    expect(value).toEqual({ port: '80a', debug: 'yes', hosts: null, weights: [], name: 'x' });
+  });
+
+  it('converts decimal strings only when a number holds them exactly', () => {
+    const Price = Def('Price', {
+      required: [Field({ key: 'amount', value: Decimal({ scale: 2 }) }), Field({ key: 'total', value: Decimal() })],
+    });
+    const issues: Issue[] = [];
+    const value = normalizeValue(Price.describe(), { amount: '12.34', total: '12345678901234567.89' }, { coerce: true }, issues);
+    expect(value).toEqual({ amount: 12.34, total: '12345678901234567.89' });
+    expect(issues.map(i => [i.level, i.code, i.path.join('.')])).toEqual([
+      ['info', 'coerce.number', 'amount'],
+      ['info', 'coerce.inexact', 'total'],
+    ]);
+  });
+
+  it('does not coerce unless asked', () => {
+    expect(normalizeValue(Env.describe(), { port: '8080' })).toEqual({ port: '8080' });
+  });
+});
@@ -0,0 +1,111 @@
+// test/openapi.test.ts
+
+import { describe, it, expect } from 'vitest';
+import { importOpenApi } from '../dist/openapi.js';
+import { SpecEngine } from '../dist/engine.js';
+import { generateRust } from '../dist/codegen/rust/generator.js';
+
+const petstore = {
+  openapi: '3.1.0',
+  info: { title: 'Petstore', version: '1.0.0' },
+  paths: {
+    '/pets': {
+      post: {
+        operationId: 'createPet',
+        requestBody: { $ref: '#/components/requestBodies/NewPet' },
+        responses: {
+          '201': { description: 'Created', content: { 'application/json': { schema: { $ref: '#/components/schemas/Pet' } } } },
+          default: { description: 'Error', content: { 'application/problem+json': { schema: { $ref: '#/components/schemas/Error' } } } },
+        },
+      },
+    },
+  },
+  components: {
+    requestBodies: {
+      NewPet: { content: { 'application/json': { schema: { type: 'object', required: ['name'], properties: { name: { type: 'string', minLength: 1 } } } } } },
+    },
+    schemas: {
+      Pet: {
+        description: 'A pet in the store',
+        type: 'object',
+        required: ['id', 'name', 'kind'],
+        properties: {
+          id: { type: 'integer', exclusiveMinimum: 0 },
+          name: { type: 'string', pattern: '^[A-Za-z ]+$' },
+          tag: { type: ['string', 'null'] },
+          status: { enum: ['available', 'sold'], default: 'available' },
+          kind: {
+            oneOf: [{ $ref: '#/components/schemas/Cat' }, { $ref: '#/components/schemas/Dog' }],
+            discriminator: { propertyName: 'type', mapping: { cat: '#/components/schemas/Cat' } },
+          },
+          children: { type: 'array', items: { $ref: '#/components/schemas/Pet' }, maxItems: 3 },
+        },
+      },
+      Cat: { type: 'object', required: ['type'], properties: { type: { const: 'cat' }, lives: { type: 'integer', maximum: 9 } } },
+      Dog: { type: 'object', required: ['type'], properties: { type: { const: 'Dog' } } },
+      Error: { type: 'object', properties: { code: { type: 'integer' }, detail: {} }, additionalProperties: false },
+    },
+  },
+};
+
+function compile(source: string) {
+  return new SpecEngine().compileSource(source);
+}
+
+describe('importOpenApi', () => {
+  it('defines each component schema, the root last', () => {
+    const { source, definitions } = importOpenApi(petstore, { root: 'Pet' });
+    expect(definitions).toEqual(['Pet', 'Cat', 'Dog', 'Error']);
+    expect(source).toContain("const Cat = Def('Cat', {");
+    expect(source).toContain("const ErrorSchema = Def('Error', {");
+    // Pet refers to definitions, so its body is resolved on first use
+    expect(source).toContain("const Pet = Def('Pet', () => ({");
+    expect(source.trimEnd().endsWith("{ description: 'A pet in the store' });")).toBe(true);
+  });
+
+  it('compiles to a spec that checks the schema rules', () => {
+    const pet = compile(importOpenApi(petstore, { root: 'Pet' }).source);
+    const valid = { id: 1, name: 'Rex', tag: null, kind: { type: 'Dog' }, children: [{ id: 2, name: 'Tom', kind: { type: 'cat', lives: 3 } }] };
+    expect(pet.normalize(valid).issues).toEqual([]);
+    expect(pet.normalize(valid).value).toMatchObject({ status: 'available' });
+
+    const invalid = { id: 0, name: 'R2', kind: { type: 'cat', lives: 10 }, children: [{}, {}, {}, {}] };
+    const codes = pet.normalize(invalid).issues.map(i => `${i.code} ${i.path.join('.')}`);
+    expect(codes).toContain('num.too_small id');
+    expect(codes).toContain('str.pattern_mismatch name');
+    expect(codes).toContain('num.too_large kind.lives');
+    expect(codes).toContain('list.too_long children');
+  });
+
+  it('defines request and response bodies with operations', () => {
+    const { source, definitions } = importOpenApi(petstore, { operations: true, root: 'createPetRequest' });
+    expect(definitions).toEqual(['Pet', 'Cat', 'Dog', 'Error', 'createPetRequest', 'createPetResponse201', 'createPetResponseDefault']);
+    expect(source).toContain("const createPetResponse201 = Def('createPetResponse201', () => Pet, { description: 'Created' });");
+
+    const request = compile(source);
+    expect(request.normalize({ name: 'Rex' }).ok).toBe(true);
+    expect(request.normalize({ name: '' }).ok).toBe(false);
+    expect(generateRust(request.describe())).toContain('fn validate_def_create_pet_request(');
+  });
+
+  it('reports keywords it cannot express', () => {
+    const { source, warnings } = importOpenApi({
+      openapi: '3.0.3',
+      components: {
+        schemas: {
+          Price: { type: 'number', minimum: 0, exclusiveMinimum: true, multipleOf: 0.01, nullable: true },
+          Remote: { $ref: 'https://example.com/schemas/remote.json' },
+        },
+      },
+    });
+    expect(source).toContain("const Price = Def('Price', Nullable(Num({ min: 0 })));");
+    expect(warnings).toEqual([
+      '#/components/schemas/Price: exclusiveMinimum is checked as inclusive',
+      '#/components/schemas/Price: multipleOf is not supported',
+      '#/components/schemas/Remote: external $ref https://example.com/schemas/remote.json is not supported',
+    ]);
+    expect(compile(source).normalize('anything').ok).toBe(true);
+    expect(() => importOpenApi({ swagger: '2.0' })).toThrow('Not an OpenAPI 3 document');
+    expect(() => importOpenApi(petstore, { root: 'Owner' })).toThrow('Unknown definition: Owner');
+  });
+});
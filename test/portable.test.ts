@@ -0,0 +1,37 @@
+// test/portable.test.ts
+
+import { describe, it, expect } from 'vitest';
+import { portableDescription } from '../dist/portable.js';
+import { SpecEngine } from '../dist/engine.js';
+
+function describeSpec(source: string) {
+  return new SpecEngine().compileSource(source).describe();
+}
+
+describe('portableDescription', () => {
+  it('describes object specs nested in modifiers as children', () => {
+    const desc = portableDescription(describeSpec(`
+      ListOf(OneOf({ required: [Field({ key: 'id', value: Num({ integer: true }) })] }, Str()))
+    `));
+    const option = desc.itemType!.oneOf![0]!;
+    expect(option.name).toBe('Object');
+    expect(option.children!.required![0]!.key).toBe('id');
+    expect(option.children!.required![0]!.constraints).toEqual(['integer']);
+    expect('spec' in option).toBe(false);
+  });
+
+  it('survives a JSON round trip', () => {
+    const desc = portableDescription(describeSpec(`
+      const Tree = Def('Tree', self => ({ optional: [Field({ key: 'children', value: ListOf(self), optional: true })] }), { maxDepth: 4 });
+      Def('Doc', {
+        required: [
+          Field({ key: 'kind', value: TaggedUnion('type', { a: { required: [Field({ key: 'x', value: Str({ match: /^x+$/ }) })] } }) }),
+          Field({ key: 'tree', value: Tree }),
+        ],
+      })
+    `));
+    expect(JSON.parse(JSON.stringify(desc))).toEqual(desc);
+    expect(desc.ref).toBe('Doc');
+    expect(JSON.stringify(desc)).toContain('"maxDepth":4');
+  });
+});
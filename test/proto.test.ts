@@ -0,0 +1,98 @@
+// test/proto.test.ts
+
+import { describe, it, expect } from 'vitest';
+import { exportProto } from '../dist/proto.js';
+import { SpecEngine } from '../dist/engine.js';
+
+function describeSpec(source: string) {
+  return new SpecEngine().compileSource(source).describe();
+}
+
+const order = describeSpec(`
+  const Tree = Def('Tree', self => ({ required: [Field({ key: 'children', value: ListOf(self) })] }), { maxDepth: 3 });
+  Def('Order', {
+    required: [
+      Field({ key: 'sku', description: 'Stock keeping unit', value: Str({ minLength: 3, maxLength: 8 }) }),
+      Field({ key: 'price', value: Decimal({ scale: 2 }) }),
+      Field({ key: 'qty', value: Num({ integer: true, min: 1 }) }),
+      Field({ key: 'status', value: OneOf('open', 'paid') }),
+      Field({ key: 'shipping', value: TaggedUnion('kind', {
+        pickup: { required: [Field({ key: 'kind', value: 'pickup' }), Field({ key: 'store', value: Str() })] },
+        post: { required: [Field({ key: 'zip', value: Str({ match: /^\\d{5}$/ }) })] },
+      }) }),
+    ],
+    optional: [
+      Field({ key: 'note', value: Str(), optional: true, nullable: true }),
+      Field({ key: 'tags', value: ListOf(Str(), { unique: true }), optional: true }),
+      Field({ key: 'HTTPStatus', value: Num({ integer: true }), optional: true, deprecated: true }),
+      Field({ key: 'extra', value: OneOf(Str(), Num()), optional: true }),
+      Field({ key: 'tree', value: Tree, optional: true }),
+    ],
+  }, { description: 'A customer order' })
+`);
+
+describe('exportProto', () => {
+  it('maps object types to messages', () => {
+    const { source } = exportProto(order, { package: 'acme.orders.v1' });
+    expect(source).toMatch(/^syntax = "proto3";\n\npackage acme\.orders\.v1;\n\nimport "google\/protobuf\/struct\.proto";\n/);
+    expect(source).toContain([
+      '// A customer order',
+      'message Order {',
+      '  // Stock keeping unit',
+      '  string sku = 1;',
+      '  string price = 2;',
+      '  int64 qty = 3;',
+      '  OrderStatus status = 4;',
+      '  OrderShipping shipping = 5;',
+      '  optional string note = 6;',
+      '  repeated string tags = 7;',
+      '  optional int64 http_status = 8 [json_name = "HTTPStatus", deprecated = true];',
+      '  google.protobuf.Value extra = 9;',
+      '  Tree tree = 10;',
+      '}',
+    ].join('\n'));
+    expect(source).toContain('enum OrderStatus {\n  ORDER_STATUS_UNSPECIFIED = 0;\n  ORDER_STATUS_OPEN = 1;\n  ORDER_STATUS_PAID = 2;\n}');
+    expect(source).toContain('message Tree {\n  repeated Tree children = 1;\n}');
+  });
+
+  it('turns tagged unions into a oneof without the discriminator', () => {
+    const { source } = exportProto(order);
+    expect(source).not.toContain('package ');
+    expect(source).toContain('message OrderShipping {\n  oneof kind {\n    OrderShippingPickup pickup = 1;\n    OrderShippingPost post = 2;\n  }\n}');
+    expect(source).toContain('message OrderShippingPickup {\n  string store = 1;\n}');
+  });
+
+  it('lists the rules protobuf cannot express', () => {
+    const notes = exportProto(order).notes.map(n => `${n.path.join('.')}: ${n.message}`);
+    for (const note of [
+      'Order.sku: minimum 3 characters',
+      'Order.sku: maximum 8 characters',
+      'Order.price: decimal carried as a string',
+      'Order.qty: minimum 1',
+      'Order.status: values become OrderStatus constants (e.g. "open" is ORDER_STATUS_OPEN)',
+      'OrderShippingPost.zip: matches `/^\\d{5}$/`',
+      'Order.note: null is not distinguished from a missing value',
+      'Order.tags: unique items',
+      'Order.extra: one of String, Number',
+      'Tree: maximum nesting depth 3',
+      'Order: required fields sku, price, qty, status, shipping (proto3 fields are all optional)',
+    ]) {
+      expect(notes).toContain(note);
+    }
+  });
+
+  it('exports JSON files of file system specs and wraps other roots', () => {
+    const bundle = describeSpec(`
+      Directory({ content: { required: [
+        JsonFile({ path: 'meta.json', required: [Field({ key: 'version', value: Str() })] }),
+        File({ path: 'README.md' }),
+      ] } })
+    `);
+    const { source, notes } = exportProto(bundle);
+    expect(source).toContain('message MetaJson {\n  string version = 1;\n}');
+    expect(notes[0]).toEqual({ path: [], message: 'file system rules are not exported; JSON files become messages' });
+
+    expect(exportProto(describeSpec('ListOf(Str())')).source).toContain('message Root {\n  repeated string value = 1;\n}');
+    expect(() => exportProto(order, { package: 'acme-orders' })).toThrow('Invalid protobuf package name: acme-orders');
+  });
+});
@@ -0,0 +1,72 @@
+// test/repair.test.ts
+
+import { describe, it, expect, beforeEach, afterEach } from 'vitest';
+import fs from 'fs';
+import os from 'os';
+import path from 'path';
+import { repairReport, formatRepairReport, partitionStructural } from '../dist/repair.js';
+import { Directory, File, JsonFile } from '../dist/types/structural.js';
+
+const spec = Directory({
+  strict: true,
+  content: {
+    required: [
+      JsonFile({ path: 'meta.json' }),
+      Directory({ path: 'data', strict: true, content: { required: [File({ path: 'blob.bin' })] } }),
+      File({ path: 'LICENSE' }),
+    ],
+    optional: [File({ path: 'README.md' })],
+  },
+});
+
+describe('repairReport', () => {
+  let dir: string;
+
+  beforeEach(() => {
+    dir = fs.mkdtempSync(path.join(os.tmpdir(), 'specspec-repair-'));
+  });
+
+  afterEach(() => {
+    fs.rmSync(dir, { recursive: true, force: true });
+  });
+
+  function write(rel: string): void {
+    fs.mkdirSync(path.dirname(path.join(dir, rel)), { recursive: true });
+    fs.writeFileSync(path.join(dir, rel), '{}');
+  }
+
+  it('triages missing, misplaced and forbidden entries', () => {
+    write('data/blob.bin');
+    write('data/meta.json');
+    write('junk.tmp');
+
+    const report = repairReport(spec.describe(), dir);
+    expect(report.missing).toEqual(['LICENSE']);
+    expect(report.misplaced).toEqual([{ path: 'meta.json', foundAt: 'data/meta.json' }]);
+    expect(report.forbidden).toEqual(['junk.tmp']);
+  });
+
+  it('renders the expected layout as a tree', () => {
+    write('data/blob.bin');
+    write('stray/blob.bin');
+
+    const text = formatRepairReport(repairReport(spec.describe(), dir));
+    expect(text.split('\n')).toEqual([
+      `${path.basename(dir)}/`,
+      '├── meta.json  ✗ missing',
+      '├── data/',
+      '│   └── blob.bin',
+      '├── LICENSE  ✗ missing',
+      '└── stray/  ✗ not allowed, remove it',
+    ]);
+  });
+
+  it('separates structural issues from the rest', () => {
+    const { structural, other } = partitionStructural([
+      { level: 'error', code: 'file.not_found', message: '', path: [] },
+      { level: 'error', code: 'str.too_short', message: '', path: [] },
+    ]);
+    expect(structural.map(i => i.code)).toEqual(['file.not_found']);
+    expect(other.map(i => i.code)).toEqual(['str.too_short']);
+  });
+});
@@ -0,0 +1,96 @@
+// test/sample.test.ts
+
+import { describe, it, expect } from 'vitest';
+import fs from 'fs';
+import path from 'path';
+import os from 'os';
+import { sampleValue } from '../dist/sample.js';
+import { SpecEngine } from '../dist/engine.js';
+import { Str, Num } from '../dist/types/primitives.js';
+import { Field } from '../dist/types/structural.js';
+import { ListOf } from '../dist/modifiers/listof.js';
+import { Def } from '../dist/modifiers/def.js';
+import { Nullable } from '../dist/modifiers/nullable.js';
+
+describe('sampleValue', () => {
+  const User = Def('User', {
+    required: [
+      Field({ key: 'id', value: Str({ match: /^u-\d{4}$/ }) }),
+      Field({ key: 'age', value: Num({ integer: true, min: 18, max: 99 }) }),
+      Field({ key: 'tags', value: ListOf(Str({ minLength: 2, maxLength: 5 }), { min: 1, max: 3, unique: true }) }),
+    ],
+  });
+
+  it('is reproducible from the seed', () => {
+    expect(sampleValue(User.describe(), { seed: 42 })).toEqual(sampleValue(User.describe(), { seed: 42 }));
+    const seeds = new Set([1, 2, 3, 4, 5].map(seed => JSON.stringify(sampleValue(User.describe(), { seed }))));
+    expect(seeds.size).toBeGreaterThan(1);
+  });
+
+  it('respects patterns, ranges and list constraints', () => {
+    for (let seed = 0; seed < 20; seed++) {
+      const user = sampleValue(User.describe(), { seed }) as { id: string; age: number; tags: string[] };
+      expect(user.id).toMatch(/^u-\d{4}$/);
+      expect(Number.isInteger(user.age) && user.age >= 18 && user.age <= 99).toBe(true);
+      expect(user.tags.length).toBeGreaterThanOrEqual(1);
+      expect(user.tags.length).toBeLessThanOrEqual(3);
+      expect(new Set(user.tags).size).toBe(user.tags.length);
+    }
+  });
+
+  it('stops expanding recursive named types', () => {
+    const Tree = Def('Tree', self => ({
+      required: [Field({ key: 'name', value: Str() }), Field({ key: 'children', value: ListOf(self) })],
+      optional: [Field({ key: 'parent', value: Nullable(self), optional: true })],
+    }));
+    const depth = (node: { children: unknown[] }): number => 1 + Math.max(0, ...node.children.map(c => depth(c as { children: unknown[] })));
+    for (let seed = 0; seed < 10; seed++) {
+      expect(depth(sampleValue(Tree.describe(), { seed, optional: 1 }) as { children: unknown[] })).toBeLessThanOrEqual(3);
+    }
+  });
+});
+
+describe('SpecEngine samples', () => {
+  it('returns samples the spec accepts', () => {
+    const schema = new SpecEngine().compileSource(`
+      Def('Order', {
+        required: [
+          Field({ key: 'sku', value: AllOf(Str({ minLength: 3, maxLength: 8 }), Str({ match: /^[a-z-]+$/ }), Not('admin')) }),
+          Field({ key: 'price', value: Decimal({ precision: 6, scale: 2, min: '0.01', max: '999.99' }) }),
+          Field({ key: 'lines', value: ListOf({ required: [Field({ key: 'n', value: Num({ integer: true, min: 1, max: 50 }) })] }, { uniqueBy: 'n', sortedBy: 'n', min: 2 }) }),
+          Field({ key: 'shipping', value: TaggedUnion('kind', { pickup: { required: [Field({ key: 'store', value: Str() })] }, post: { required: [Field({ key: 'zip', value: Str({ match: /^\\d{5}$/ }) })] } }) }),
+          Field({ key: 'status', value: OneOf('open', 'paid', 'shipped') }),
+        ],
+      })
+    `);
+    for (let seed = 0; seed < 20; seed++) {
+      const result = schema.sample({ seed });
+      expect(result.issues).toEqual([]);
+      expect(result.ok).toBe(true);
+    }
+  });
+
+  it('writes file trees for file system specs', () => {
+    const dir = fs.mkdtempSync(path.join(os.tmpdir(), 'specspec-sample-'));
+    try {
+      const schema = new SpecEngine().compileSource(`
+        Directory({
+          strict: true,
+          content: {
+            required: [
+              JsonFile({ path: 'meta.json', required: [Field({ key: 'version', value: Str({ match: /^\\d+\\.\\d+\\.\\d+$/ }) })] }),
+              Directory({ path: 'assets', content: { required: [File({ path: 'icon.png', ext: 'png' })] } }),
+            ],
+            optional: [File({ path: 'README.md' })],
+          },
+        })
+      `);
+      const target = path.join(dir, 'bundle');
+      expect(schema.writeSample(target, { seed: 3, optional: 1 })).toEqual({ ok: true, issues: [] });
+      expect(fs.readdirSync(target).sort()).toEqual(['README.md', 'assets', 'meta.json']);
+      expect(JSON.parse(fs.readFileSync(path.join(target, 'meta.json'), 'utf-8')).version).toMatch(/^\d+\.\d+\.\d+$/);
+    } finally {
+      fs.rmSync(dir, { recursive: true });
+    }
+  });
+});
@@ -0,0 +1,55 @@
+// test/score.test.ts
+
+import { describe, it, expect, beforeAll, afterAll } from 'vitest';
+import fs from 'fs';
+import path from 'path';
+import os from 'os';
+import { SpecEngine } from '../dist/engine.js';
+import { scoreCorpus, corpusDocuments } from '../dist/score.js';
+
+describe('scoreCorpus', () => {
+  let dir: string;
+  const engine = new SpecEngine();
+  const spec = `Def('Config', {
+    required: [
+      Field({ key: 'name', value: Str({ maxLength: 5 }) }),
+      Field({ key: 'tags', value: ListOf(Str(), { max: 2 }) }),
+    ],
+    optional: [Field({ key: 'port', value: Num({ min: 1 }), optional: true })],
+  })`;
+
+  beforeAll(() => {
+    dir = fs.mkdtempSync(path.join(os.tmpdir(), 'specspec-score-'));
+    fs.mkdirSync(path.join(dir, 'nested'));
+    fs.writeFileSync(path.join(dir, 'a.json'), JSON.stringify({ name: 'ab', tags: [] }));
+    fs.writeFileSync(path.join(dir, 'b.json'), JSON.stringify({ name: 'abcdefg', tags: ['a', 'b', 'c'] }));
+    fs.writeFileSync(path.join(dir, 'nested', 'c.json'), JSON.stringify({ name: 'abcdefgh', tags: [], port: 0 }));
+    fs.writeFileSync(path.join(dir, 'd.json'), '{ broken');
+    fs.writeFileSync(path.join(dir, 'notes.txt'), 'not part of the corpus');
+  });
+
+  afterAll(() => {
+    fs.rmSync(dir, { recursive: true, force: true });
+  });
+
+  it('counts the documents each rule rejects, most violated first', () => {
+    const report = scoreCorpus(engine.compileSource(spec), [dir], { examples: 1 });
+    expect(report.total).toBe(4);
+    expect(report.passed).toBe(1);
+    expect(report.unreadable).toEqual([path.join(dir, 'd.json')]);
+    expect(report.rules.map(r => [r.path, r.rule, r.violations])).toEqual([
+      ['name', 'maximum 5 characters', 2],
+      ['tags', 'maximum 2 items', 1],
+      ['port', 'minimum 1', 1],
+      ['name', 'required', 0],
+      ['tags', 'required', 0],
+    ]);
+    expect(report.rules[0]).toMatchObject({ checked: 3, share: 0.5, examples: [path.join(dir, 'b.json')] });
+  });
+
+  it('expands directories into JSON files for data specs only', () => {
+    const inputs = [dir, path.join(dir, 'a.json')];
+    expect(corpusDocuments(inputs, false)).toEqual(['a.json', 'b.json', 'd.json', 'nested/c.json'].map(f => path.join(dir, f)));
+    expect(corpusDocuments(inputs, true)).toEqual(inputs);
+  });
+});
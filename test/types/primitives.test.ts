@@ -1,7 +1,7 @@
 // test/types/primitives.test.ts
 
 import { describe, it, expect } from 'vitest';
-import { Str, Bool, Num } from '../../dist/types/primitives.js';
+import { Str, Bool, Num, Decimal } from '../../dist/types/primitives.js';
 import { createTestContext } from '../helpers.js';
 
 describe('Str', () => {
@@ -148,3 +148,75 @@ describe('Num', () => {
     expect(ctx.issues).toHaveLength(0);
   });
 });
+
+describe('Decimal', () => {
+  it('accepts valid number', () => {
+    const ctx = createTestContext();
+    Decimal({ precision: 10, scale: 2 }).validate(12345.67, ctx);
+    expect(ctx.issues).toHaveLength(0);
+  });
+
+  it('rejects non-number', () => {
+    const ctx = createTestContext();
+    Decimal().validate('1.5', ctx);
+    expect(ctx.issues).toHaveLength(1);
+    expect(ctx.issues[0].code).toBe('type.mismatch');
+  });
+
+  it('validates scale', () => {
+    const ctx = createTestContext();
+    Decimal({ scale: 2 }).validate(1.005, ctx);
+    expect(ctx.issues).toHaveLength(1);
+    expect(ctx.issues[0].code).toBe('decimal.scale_exceeded');
+  });
+
+  it('validates precision', () => {
+    const ctx = createTestContext();
+    Decimal({ precision: 5 }).validate(123456, ctx);
+    expect(ctx.issues).toHaveLength(1);
+    expect(ctx.issues[0].code).toBe('decimal.too_many_digits');
+  });
+
+  it('ignores trailing zeros when counting digits', () => {
+    const ctx = createTestContext();
+    Decimal({ precision: 3, scale: 1 }).validate(1.50, ctx);
+    expect(ctx.issues).toHaveLength(0);
+  });
+
+  it('compares bounds exactly', () => {
+    const ctx = createTestContext();
+    Decimal({ min: '0.1', max: '99999999.99' }).validate(99999999.99, ctx);
+    expect(ctx.issues).toHaveLength(0);
+
+    Decimal({ min: '0.1', max: '99999999.99' }).validate(100000000, ctx);
+    expect(ctx.issues).toHaveLength(1);
+    expect(ctx.issues[0].code).toBe('num.too_large');
+  });
+
+  it('compares bounds with huge exponents without expanding them', () => {
+    const ctx = createTestContext();
+    Decimal({ min: '1e-999999999999', max: '1e999999999999' }).validate(5, ctx);
+    expect(ctx.issues).toHaveLength(0);
+
+    Decimal({ min: '1e999999999999' }).validate(5, ctx);
+    Decimal({ max: '-1e999999999999' }).validate(-5, ctx);
+    expect(ctx.issues.map(issue => issue.code)).toEqual(['num.too_small', 'num.too_large']);
+  });
+
+  it('counts digits of large and small exponents', () => {
+    const ctx = createTestContext();
+    Decimal({ precision: 301, scale: 0 }).validate(1e300, ctx);
+    Decimal({ precision: 300, scale: 300 }).validate(1e-300, ctx);
+    expect(ctx.issues).toHaveLength(0);
+
+    Decimal({ scale: 299 }).validate(1e-300, ctx);
+    expect(ctx.issues).toHaveLength(1);
+    expect(ctx.issues[0].code).toBe('decimal.scale_exceeded');
+  });
+
+  it('includes constraints in describe()', () => {
+    const desc = Decimal({ precision: 10, scale: 2, min: '0' }).describe();
+    expect(desc.name).toBe('Decimal');
+    expect(desc.constraints).toEqual(['precision 10 digits', 'scale 2', 'minimum 0']);
+  });
+});
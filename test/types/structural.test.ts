@@ -1,8 +1,9 @@
 // test/types/structural.test.ts
 
 import { describe, it, expect } from 'vitest';
-import { Field, JsonFile } from '../../dist/types/structural.js';
-import { Str, Num } from '../../dist/types/primitives.js';
+import { Field, File, JsonFile, Directory, When, reservedName } from '../../dist/types/structural.js';
+import { registerDigest, setFipsOnly } from '../../dist/digest.js';
+import { Str, Num, Bool } from '../../dist/types/primitives.js';
 import { createTestContext } from '../helpers.js';
 
 describe('Field', () => {
@@ -27,6 +28,25 @@ describe('Field', () => {
     expect(ctx.issues[0].code).toBe('field.missing');
   });
 
+  it('suggests renaming a misspelled key', () => {
+    const ctx = createTestContext();
+    Field({ key: 'version', value: Str() }).validate({ verison: '1.0.0' }, ctx);
+    expect(ctx.issues[0].code).toBe('field.missing');
+    expect(ctx.issues[0].fix).toEqual([{ op: 'move', from: '/verison', path: '/version' }]);
+  });
+
+  it('suggests adding a field whose only value is a literal', () => {
+    const ctx = createTestContext();
+    Field({ key: 'type', value: 'module' }).validate({}, ctx);
+    expect(ctx.issues[0].fix).toEqual([{ op: 'add', path: '/type', value: 'module' }]);
+  });
+
+  it('attaches no fix when none is mechanical', () => {
+    const ctx = createTestContext();
+    Field({ key: 'name', value: Str() }).validate({ other: 'value' }, ctx);
+    expect(ctx.issues[0].fix).toBeUndefined();
+  });
+
   it('skips missing optional field', () => {
     const ctx = createTestContext();
     const obj = { other: 'value' };
@@ -34,6 +54,21 @@ describe('Field', () => {
     expect(ctx.issues).toHaveLength(0);
   });
 
+  it('warns when a deprecated field is present', () => {
+    const ctx = createTestContext();
+    const field = Field({ key: 'legacy_id', value: Str(), optional: true, deprecated: { message: 'ids are UUIDs now', replacement: 'id' } });
+    field.validate({ legacy_id: 'a1' }, ctx);
+    expect(ctx.issues).toHaveLength(1);
+    expect(ctx.issues[0].level).toBe('warning');
+    expect(ctx.issues[0].code).toBe('field.deprecated');
+    expect(ctx.issues[0].path).toEqual(['legacy_id']);
+    expect(ctx.issues[0].message).toBe('Field "legacy_id" is deprecated: ids are UUIDs now; use "id" instead');
+
+    const absent = createTestContext();
+    field.validate({}, absent);
+    expect(absent.issues).toHaveLength(0);
+  });
+
   it('validates field value type', () => {
     const ctx = createTestContext();
     const obj = { age: 'not a number' };
@@ -155,3 +190,219 @@ describe('JsonFile', () => {
     }
   });
 });
+
+describe('File checksum', () => {
+  // sha256("hello")
+  const HELLO_SHA256 = '2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824';
+
+  function withFile(run: (dir: string) => void) {
+    const fs = require('fs');
+    const path = require('path');
+    const tmpDir = fs.mkdtempSync(path.join(require('os').tmpdir(), 'specspec-'));
+    const filePath = path.join(tmpDir, 'data.bin');
+    fs.writeFileSync(filePath, 'hello');
+    try {
+      run(tmpDir);
+    } finally {
+      fs.unlinkSync(filePath);
+      fs.rmdirSync(tmpDir);
+    }
+  }
+
+  it('accepts matching sha256 digest by default', () => {
+    withFile((dir) => {
+      const ctx = createTestContext();
+      File({ path: 'data.bin', checksum: { value: HELLO_SHA256.toUpperCase() } }).validate(dir, ctx);
+      expect(ctx.issues).toHaveLength(0);
+    });
+  });
+
+  it('reports digest mismatch', () => {
+    withFile((dir) => {
+      const ctx = createTestContext();
+      File({ path: 'data.bin', checksum: { algorithm: 'sha512', value: HELLO_SHA256 } }).validate(dir, ctx);
+      expect(ctx.issues).toHaveLength(1);
+      expect(ctx.issues[0].code).toBe('checksum.mismatch');
+    });
+  });
+
+  it('uses registered digests and honors FIPS-only mode', () => {
+    registerDigest({ name: 'reverse', hash: (data) => Buffer.from(data).reverse().toString('hex') });
+    withFile((dir) => {
+      const spec = File({ path: 'data.bin', checksum: { algorithm: 'reverse', value: '6f6c6c6568' } });
+
+      const ctx = createTestContext();
+      spec.validate(dir, ctx);
+      expect(ctx.issues).toHaveLength(0);
+
+      setFipsOnly(true);
+      try {
+        const fipsCtx = createTestContext();
+        spec.validate(dir, fipsCtx);
+        expect(fipsCtx.issues[0].code).toBe('checksum.unsupported_algorithm');
+      } finally {
+        setFipsOnly(false);
+      }
+    });
+  });
+
+  it('includes checksum in describe()', () => {
+    const desc = File({ path: 'data.bin', checksum: { value: HELLO_SHA256 } }).describe();
+    expect(desc.checksum).toEqual({ algorithm: 'sha256', value: HELLO_SHA256 });
+  });
+});
+
+describe('Directory', () => {
+  it('reports undeclared entries in strict mode', () => {
+    const fs = require('fs');
+    const path = require('path');
+    const tmpDir = fs.mkdtempSync(path.join(require('os').tmpdir(), 'specspec-'));
+    fs.writeFileSync(path.join(tmpDir, 'keep.txt'), '');
+    fs.writeFileSync(path.join(tmpDir, 'stray.txt'), '');
+
+    try {
+      const content = { required: [File({ path: 'keep.txt' })] };
+      const lax = createTestContext();
+      Directory({ content }).validate(tmpDir, lax);
+      expect(lax.issues).toHaveLength(0);
+
+      const strict = createTestContext();
+      Directory({ content, strict: true }).validate(tmpDir, strict);
+      expect(strict.issues.map(i => i.code)).toEqual(['dir.unexpected']);
+      expect(strict.issues[0].path).toEqual([path.basename(tmpDir), 'stray.txt']);
+    } finally {
+      fs.rmSync(tmpDir, { recursive: true });
+    }
+  });
+});
+
+describe('Windows paths', () => {
+  it('reports reserved device names without looking them up', () => {
+    const ctx = createTestContext();
+    File({ path: 'CON' }).validate('/nonexistent', ctx);
+    Directory({ path: 'logs/nul.txt' }).validate('/nonexistent', ctx);
+    JsonFile({ path: 'Aux.json' }).validate('/nonexistent', ctx);
+    expect(ctx.issues.map(i => i.code)).toEqual(['fs.reserved_name', 'fs.reserved_name', 'fs.reserved_name']);
+    expect(ctx.issues[1].message).toBe("'nul.txt' is a reserved name on Windows");
+    expect(File({ path: 'com1.log' }).matches('/nonexistent', createTestContext())).toBe(false);
+    expect(reservedName('docs/console.log')).toBeUndefined();
+    expect(reservedName('docs\\LPT9.txt')).toBe('LPT9.txt');
+  });
+
+  it('reads backslash paths and reports them with forward slashes', () => {
+    const fs = require('fs');
+    const path = require('path');
+    const tmpDir = fs.mkdtempSync(path.join(require('os').tmpdir(), 'specspec-'));
+    fs.mkdirSync(path.join(tmpDir, 'a', 'b'), { recursive: true });
+    fs.writeFileSync(path.join(tmpDir, 'a', 'b', 'keep.txt'), '');
+    fs.writeFileSync(path.join(tmpDir, 'a', 'b', 'stray.txt'), '');
+
+    try {
+      const ctx = createTestContext();
+      Directory({
+        content: { required: [Directory({ path: 'a\\b', strict: true, content: { required: [File({ path: 'keep.txt' })] } })] },
+      }).validate(tmpDir, ctx);
+      expect(ctx.issues.map(i => i.code)).toEqual(['dir.unexpected']);
+      expect(ctx.issues[0].path).toEqual([path.basename(tmpDir), 'a/b', 'stray.txt']);
+    } finally {
+      fs.rmSync(tmpDir, { recursive: true });
+    }
+  });
+});
+
+describe('Directory files', () => {
+  it('counts the files of a kind', () => {
+    const fs = require('fs');
+    const path = require('path');
+    const tmpDir = fs.mkdtempSync(path.join(require('os').tmpdir(), 'specspec-'));
+    fs.writeFileSync(path.join(tmpDir, 'a.png'), '');
+    fs.writeFileSync(path.join(tmpDir, 'b.svg'), '');
+    fs.mkdirSync(path.join(tmpDir, 'c.png'));
+
+    try {
+      const ok = createTestContext();
+      Directory({ files: { ext: 'png', min: 1, max: 1 } }).validate(tmpDir, ok);
+      expect(ok.issues).toHaveLength(0);
+
+      const ctx = createTestContext();
+      Directory({ files: { ext: 'png', min: 2 } }).validate(tmpDir, ctx);
+      Directory({ files: { match: /\.(png|svg)$/, max: 1 } }).validate(tmpDir, ctx);
+      expect(ctx.issues.map(i => i.code)).toEqual(['dir.too_few_files', 'dir.too_many_files']);
+      expect(ctx.issues[0].message).toContain('at least 2 .png files');
+      expect(Directory({ files: { ext: 'png', min: 1 } }).describe().constraints).toEqual(['minimum 1 .png file']);
+    } finally {
+      fs.rmSync(tmpDir, { recursive: true });
+    }
+  });
+});
+
+describe('When', () => {
+  const fs = require('fs');
+  const path = require('path');
+
+  function bundle(manifest: string | null, icons: string[] | null): string {
+    const dir = fs.mkdtempSync(path.join(require('os').tmpdir(), 'specspec-'));
+    if (manifest !== null) fs.writeFileSync(path.join(dir, 'manifest.json'), manifest);
+    if (icons !== null) {
+      fs.mkdirSync(path.join(dir, 'icons'));
+      for (const icon of icons) fs.writeFileSync(path.join(dir, 'icons', icon), '');
+    }
+    return dir;
+  }
+
+  const spec = Directory({
+    strict: true,
+    content: {
+      required: [
+        JsonFile({ path: 'manifest.json', optional: [Field({ key: 'has_icons', value: Bool(), optional: true })] }),
+        When({
+          file: 'manifest.json',
+          field: 'has_icons',
+          is: true,
+          then: { required: [Directory({ path: 'icons', files: { ext: 'png', min: 1 } })] },
+        }),
+      ],
+    },
+  });
+
+  function codes(dir: string): string[] {
+    const ctx = createTestContext();
+    try {
+      spec.validate(dir, ctx);
+    } finally {
+      fs.rmSync(dir, { recursive: true });
+    }
+    return ctx.issues.map(i => i.code);
+  }
+
+  it('requires content when the manifest value matches', () => {
+    expect(codes(bundle('{"has_icons": true}', ['app.png']))).toEqual([]);
+    expect(codes(bundle('{"has_icons": true}', ['app.svg']))).toEqual(['dir.too_few_files']);
+    expect(codes(bundle('{"has_icons": true}', null))).toEqual(['dir.not_found']);
+  });
+
+  it('skips the content otherwise, or when the manifest cannot be read', () => {
+    expect(codes(bundle('{"has_icons": false}', null))).toEqual([]);
+    expect(codes(bundle('{}', null))).toEqual([]);
+    expect(codes(bundle('{', null))).toEqual(['json.parse_error']);
+    // Entries of either branch are declared for strict directories
+    expect(codes(bundle('{"has_icons": false}', []))).toEqual([]);
+  });
+
+  it('checks else content and nested fields', () => {
+    const dir = bundle('{"build": {"kind": "native"}}', null);
+    const when = When({ file: 'manifest.json', field: 'build.kind', is: 'web', else: File({ path: 'binary' }) });
+    const ctx = createTestContext();
+    try {
+      when.validate(dir, ctx);
+    } finally {
+      fs.rmSync(dir, { recursive: true });
+    }
+    expect(ctx.issues.map(i => i.code)).toEqual(['file.not_found']);
+    expect(when.describe()).toMatchObject({
+      name: 'When',
+      condition: { file: 'manifest.json', field: 'build.kind', is: { name: 'Literal' } },
+      else: { name: 'File', filePath: 'binary' },
+    });
+  });
+});
@@ -0,0 +1,125 @@
+// test/upload.test.ts
+
+import { describe, it, expect } from 'vitest';
+import crypto from 'crypto';
+import { UploadSession } from '../dist/upload.js';
+import { SpecEngine } from '../dist/engine.js';
+import { SchemaError } from '../dist/schema.js';
+import { Directory, File, JsonFile, Field } from '../dist/types/structural.js';
+import { Str } from '../dist/types/primitives.js';
+
+function bundleSpec() {
+  const data = 'payload';
+  return Directory({
+    content: {
+      required: [
+        JsonFile({
+          path: 'meta.json',
+          required: [Field({ key: 'name', value: Str() })],
+        }),
+        Directory({
+          path: 'data',
+          content: {
+            required: [
+              File({
+                path: 'blob.bin',
+                checksum: { value: crypto.createHash('sha256').update(data).digest('hex') },
+              }),
+            ],
+          },
+        }),
+      ],
+      optional: [
+        File({ path: 'README.md' }),
+      ],
+    },
+  });
+}
+
+describe('UploadSession', () => {
+  it('validates each file as it arrives', () => {
+    const session = new UploadSession(bundleSpec());
+
+    expect(session.addFile('meta.json', JSON.stringify({ name: 'demo' }))).toHaveLength(0);
+    const issues = session.addFile('data/blob.bin', 'tampered');
+    expect(issues).toHaveLength(1);
+    expect(issues[0].code).toBe('checksum.mismatch');
+    expect(issues[0].path).toEqual(['upload', 'data']);
+
+    session.abort();
+  });
+
+  it('reports partial status mid-upload', () => {
+    const session = new UploadSession(bundleSpec(), { name: 'bundle' });
+    session.addFile('meta.json', JSON.stringify({ name: 42 }));
+
+    const status = session.status();
+    expect(status.ok).toBe(false);
+    expect(status.received).toEqual(['meta.json']);
+    expect(status.pending).toEqual(['data/blob.bin']);
+    expect(status.issues).toHaveLength(1);
+    expect(status.issues[0].path).toEqual(['bundle', 'meta.json', 'name']);
+
+    session.abort();
+  });
+
+  it('replaces issues when a file is received again', () => {
+    const session = new UploadSession(bundleSpec());
+    session.addFile('meta.json', '{');
+    expect(session.status().issues[0].code).toBe('json.parse_error');
+
+    session.addFile('meta.json', JSON.stringify({ name: 'fixed' }));
+    expect(session.status().ok).toBe(true);
+    expect(session.status().received).toEqual(['meta.json']);
+
+    session.abort();
+  });
+
+  it('runs cross-file rules at finalize', () => {
+    const session = new UploadSession(bundleSpec());
+    session.addFile('meta.json', JSON.stringify({ name: 'demo' }));
+
+    const result = session.finalize();
+    expect(result.ok).toBe(false);
+    expect(result.issues.map(i => i.code)).toEqual(['dir.not_found']);
+  });
+
+  it('accepts a complete upload', () => {
+    const session = new UploadSession(bundleSpec());
+    session.addFile('data/blob.bin', 'payload');
+    session.addFile('meta.json', JSON.stringify({ name: 'demo' }));
+    session.addFile('README.md', '# demo');
+
+    expect(session.status().pending).toHaveLength(0);
+    expect(session.finalize()).toEqual({ ok: true, issues: [] });
+    expect(() => session.addFile('late.txt', 'x')).toThrow();
+  });
+
+  it('rejects paths outside the upload root', () => {
+    const session = new UploadSession(bundleSpec());
+    const issues = session.addFile('../escape.txt', 'x');
+    expect(issues[0].code).toBe('upload.invalid_path');
+    expect(session.finalize().issues.map(i => i.code)).toContain('upload.invalid_path');
+  });
+
+  it('rejects paths that are the upload root or a directory', () => {
+    const session = new UploadSession(bundleSpec());
+    session.addFile('data/blob.bin', 'payload');
+    for (const relPath of ['.', '', 'data/..', 'data']) {
+      const issues = session.addFile(relPath, 'x');
+      expect(issues.map(i => i.code)).toEqual(['upload.invalid_path']);
+    }
+    expect(session.addFile('.', 'x')[0].message).toBe('Path is the upload root: .');
+    expect(session.addFile('data', 'x')[0].message).toBe('Path is a directory: data');
+    session.finalize();
+  });
+
+  it('starts from a compiled schema', () => {
+    const engine = new SpecEngine();
+    const session = engine.compileSource(`Directory({ content: { required: [File({ path: 'a.txt' })] } })`).upload();
+    session.addFile('a.txt', 'x');
+    expect(session.finalize()).toEqual({ ok: true, issues: [] });
+
+    expect(() => engine.upload('/nonexistent/bundle.spec.js')).toThrow(SchemaError);
+  });
+});
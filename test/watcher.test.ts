@@ -0,0 +1,188 @@
+// test/watcher.test.ts
+
+import { describe, it, expect } from 'vitest';
+import fs from 'fs';
+import os from 'os';
+import path from 'path';
+import { SchemaWatcher, fileSource, watchTree, diffIssues } from '../dist/watcher.js';
+import { SpecEngine } from '../dist/engine.js';
+
+const dirSpec = (file: string) => `Directory({ content: { required: [File({ path: '${file}' })] } })`;
+
+function memorySource(code: string) {
+  const source = { name: 'registry:demo', code, read: () => source.code };
+  return source;
+}
+
+describe('SchemaWatcher', () => {
+  it('swaps in a changed spec', () => {
+    const target = fs.mkdtempSync(path.join(os.tmpdir(), 'specspec-watch-'));
+    fs.writeFileSync(path.join(target, 'b.txt'), '');
+    const source = memorySource(dirSpec('a.txt'));
+    const reloaded: unknown[] = [];
+    const watcher = new SchemaWatcher(new SpecEngine(), source, { onReload: root => reloaded.push(root) });
+
+    expect(watcher.validate(target).ok).toBe(false);
+    source.code = dirSpec('b.txt');
+    expect(watcher.reload()).toBe(true);
+    expect(watcher.validate(target).ok).toBe(true);
+    expect(reloaded).toEqual([watcher.current]);
+
+    fs.rmSync(target, { recursive: true, force: true });
+  });
+
+  it('keeps the previous spec when the new one fails to load', () => {
+    const source = memorySource(dirSpec('a.txt'));
+    const errors: Error[] = [];
+    const watcher = new SchemaWatcher(new SpecEngine(), source, { onError: err => errors.push(err) });
+    const before = watcher.current;
+
+    source.code = 'Directory({';
+    expect(watcher.reload()).toBe(false);
+    source.code = 'const x = 1;';
+    expect(watcher.reload()).toBe(false);
+
+    expect(watcher.current).toBe(before);
+    expect(errors).toHaveLength(2);
+    expect(errors[0].message).toContain('Spec file error');
+    expect(errors[1].message).toContain('must define a root type');
+  });
+
+  it('rejects a spec that fails the verify hook', () => {
+    const source = memorySource(dirSpec('a.txt'));
+    const errors: Error[] = [];
+    const watcher = new SchemaWatcher(new SpecEngine(), source, {
+      verify: root => {
+        if (JSON.stringify(root.describe()).includes('forbidden')) throw new Error('sample rejected');
+      },
+      onError: err => errors.push(err),
+    });
+
+    source.code = dirSpec('forbidden.txt');
+    expect(watcher.reload()).toBe(false);
+    expect(errors[0].message).toBe('sample rejected');
+  });
+
+  it('reloads when a file the spec imports changes', () => {
+    const dir = fs.mkdtempSync(path.join(os.tmpdir(), 'specspec-watch-'));
+    const specPath = path.join(dir, 'main.spec.js');
+    const typesPath = path.join(dir, 'types.spec.js');
+    fs.writeFileSync(typesPath, `Def('Entry', File({ path: 'a.txt' }))`);
+    fs.writeFileSync(specPath, `const { Entry } = Import('./types.spec.js'); Directory({ content: { required: [Entry] } })`);
+    fs.writeFileSync(path.join(dir, 'b.txt'), '');
+    const errors: Error[] = [];
+    const watcher = new SchemaWatcher(new SpecEngine(), { name: specPath, read: () => fs.readFileSync(specPath, 'utf-8') }, {
+      onError: err => errors.push(err),
+    });
+
+    expect(watcher.current.imports).toEqual([typesPath]);
+    expect(watcher.validate(dir).ok).toBe(false);
+    expect(watcher.reload()).toBe(false);
+    fs.writeFileSync(typesPath, `Def('Entry', File({ path: 'b.txt' }))`);
+    expect(watcher.reload()).toBe(true);
+    expect(watcher.validate(dir).ok).toBe(true);
+
+    fs.writeFileSync(typesPath, `Def('Entry', File({`);
+    expect(watcher.reload()).toBe(false);
+    expect(errors[0].message).toContain('types.spec.js');
+    expect(watcher.validate(dir).ok).toBe(true);
+
+    fs.rmSync(dir, { recursive: true, force: true });
+  });
+
+  it('watches the files the spec imports', async () => {
+    const dir = fs.mkdtempSync(path.join(os.tmpdir(), 'specspec-watch-'));
+    const specPath = path.join(dir, 'main.spec.js');
+    const typesPath = path.join(dir, 'types.spec.js');
+    fs.writeFileSync(typesPath, `Def('Entry', File({ path: 'a.txt' }))`);
+    fs.writeFileSync(specPath, `const { Entry } = Import('./types.spec.js'); Directory({ content: { required: [Entry] } })`);
+
+    const reloaded = new Promise<void>(resolve => {
+      const watcher = new SchemaWatcher(new SpecEngine(), fileSource(specPath, { interval: 20 }), {
+        interval: 20,
+        onReload: () => {
+          watcher.close();
+          resolve();
+        },
+      });
+    });
+    fs.writeFileSync(typesPath, `Def('Entry', File({ path: 'bb.txt' }))`);
+    const later = new Date(Date.now() + 5000);
+    fs.utimesSync(typesPath, later, later);
+
+    let timer: ReturnType<typeof setTimeout> | undefined;
+    const timeout = new Promise<never>((_, reject) => {
+      timer = setTimeout(() => reject(new Error('spec was not reloaded')), 4000);
+    });
+    await Promise.race([reloaded, timeout]);
+    clearTimeout(timer);
+    fs.rmSync(dir, { recursive: true, force: true });
+  });
+
+  it('throws when the initial spec cannot be loaded', () => {
+    expect(() => new SchemaWatcher(new SpecEngine(), memorySource('nope('))).toThrow();
+  });
+
+  it('reloads when the spec file changes', async () => {
+    const dir = fs.mkdtempSync(path.join(os.tmpdir(), 'specspec-watch-'));
+    const specPath = path.join(dir, 'demo.spec.js');
+    fs.writeFileSync(specPath, dirSpec('a.txt'));
+
+    const reloaded = new Promise<void>(resolve => {
+      const watcher = new SchemaWatcher(new SpecEngine(), fileSource(specPath, { interval: 20 }), {
+        onReload: () => {
+          watcher.close();
+          resolve();
+        },
+      });
+    });
+    fs.writeFileSync(specPath, dirSpec('bb.txt'));
+    const later = new Date(Date.now() + 5000);
+    fs.utimesSync(specPath, later, later);
+
+    // The poller does not keep the process alive on its own
+    let timer: ReturnType<typeof setTimeout> | undefined;
+    const timeout = new Promise<never>((_, reject) => {
+      timer = setTimeout(() => reject(new Error('spec was not reloaded')), 4000);
+    });
+    await Promise.race([reloaded, timeout]);
+    clearTimeout(timer);
+    fs.rmSync(dir, { recursive: true, force: true });
+  });
+});
+
+describe('watchTree', () => {
+  it('reports files added under a watched directory', async () => {
+    const dir = fs.mkdtempSync(path.join(os.tmpdir(), 'specspec-watch-'));
+    fs.mkdirSync(path.join(dir, 'sub'));
+
+    let stop = () => {};
+    const changed = new Promise<void>(resolve => {
+      stop = watchTree([dir, path.join(dir, 'missing.json')], resolve, { interval: 20 });
+    });
+    fs.writeFileSync(path.join(dir, 'sub', 'a.json'), '{}');
+
+    let timer: ReturnType<typeof setTimeout> | undefined;
+    const timeout = new Promise<never>((_, reject) => {
+      timer = setTimeout(() => reject(new Error('change was not reported')), 4000);
+    });
+    await Promise.race([changed, timeout]);
+    clearTimeout(timer);
+    stop();
+    fs.rmSync(dir, { recursive: true, force: true });
+  });
+});
+
+describe('diffIssues', () => {
+  const issue = (code: string, message = code) => ({ level: 'error' as const, code, message, path: ['a'] });
+
+  it('reports new and resolved issues, counting repeats', () => {
+    const before = [issue('str.too_short'), issue('field.missing'), issue('field.missing')];
+    const after = [issue('field.missing'), issue('str.too_long'), issue('str.too_short', 'other message')];
+    expect(diffIssues(before, after)).toEqual({
+      added: [issue('str.too_long'), issue('str.too_short', 'other message')],
+      resolved: [issue('str.too_short'), issue('field.missing')],
+    });
+    expect(diffIssues(before, before)).toEqual({ added: [], resolved: [] });
+  });
+});